@@ -18,10 +18,13 @@
 
 use crate::error::AccountCompressionError;
 use crate::events::ChangeLogEvent;
+use crate::state::HashType;
 use anchor_lang::prelude::*;
 use bytemuck::{cast_slice, cast_slice_mut};
-use solana_program::keccak::hashv;
-use spl_concurrent_merkle_tree::node::{empty_node_cached, empty_node_cached_mut, Node, EMPTY};
+use spl_concurrent_merkle_tree::{
+    hash::multi_proof_positions,
+    node::{Node, EMPTY},
+};
 use std::mem::size_of;
 
 /// Maximum depth of the tree, supported by the SPL Compression
@@ -70,6 +73,12 @@ fn get_cached_path_length(canopy: &[Node], max_depth: u32) -> Result<u32> {
     Ok(closest_power_of_2.trailing_zeros() - 1)
 }
 
+/// Returns the number of levels of the tree cached by the canopy
+pub fn get_canopy_depth(canopy_bytes: &[u8], max_depth: u32) -> Result<u32> {
+    check_canopy_bytes(canopy_bytes)?;
+    get_cached_path_length(cast_slice::<u8, Node>(canopy_bytes), max_depth)
+}
+
 pub fn update_canopy(
     canopy_bytes: &mut [u8],
     max_depth: u32,
@@ -87,18 +96,69 @@ pub fn update_canopy(
                     canopy[(path_node.index - 2) as usize] = path_node.node;
                 }
             }
+            ChangeLogEvent::V2(cl) => {
+                // Update the canopy from every path, the newest one last
+                for cl_path in cl.paths.iter() {
+                    for path_node in cl_path.path.iter().rev().skip(1).take(path_len as usize) {
+                        // node_idx - 2 maps to the canopy index
+                        canopy[(path_node.index - 2) as usize] = path_node.node;
+                    }
+                }
+            }
         }
     }
     Ok(())
 }
 
+/// Copies the canopy of a tree of depth `subtree_depth` into the canopy of a
+/// tree of depth `max_depth` whose leftmost subtree is that tree.
+///
+/// The canopy can't cache nodes below the ones cached by the canopy of the
+/// subtree, as they can't be computed on-chain. The ancestors of the subtree
+/// are on the path of its rightmost leaf, so they are set by `update_canopy`
+/// with the change log initializing the tree.
+pub fn copy_subtree_canopy(
+    canopy_bytes: &mut [u8],
+    max_depth: u32,
+    subtree_canopy_bytes: &[u8],
+    subtree_depth: u32,
+) -> Result<()> {
+    check_canopy_bytes(canopy_bytes)?;
+    check_canopy_bytes(subtree_canopy_bytes)?;
+    let canopy = cast_slice_mut::<u8, Node>(canopy_bytes);
+    let subtree_canopy = cast_slice::<u8, Node>(subtree_canopy_bytes);
+    let path_len = get_cached_path_length(canopy, max_depth)?;
+    let subtree_path_len = get_cached_path_length(subtree_canopy, subtree_depth)?;
+    let lowest_level = max_depth - path_len;
+    if lowest_level < subtree_depth - subtree_path_len {
+        msg!(
+            "Canopy caches level {} below level {} cached by the canopy of the subtree",
+            lowest_level,
+            subtree_depth - subtree_path_len
+        );
+        return err!(AccountCompressionError::CanopyLengthMismatch);
+    }
+
+    // Each level of the subtree canopy is copied to the left of the same level
+    for level in lowest_level..subtree_depth {
+        let len = 1 << (subtree_depth - level);
+        // node_idx - 2 maps to the canopy index
+        let subtree_start = len - 2;
+        let start = (1 << (max_depth - level)) - 2;
+        canopy[start..start + len]
+            .copy_from_slice(&subtree_canopy[subtree_start..subtree_start + len]);
+    }
+    Ok(())
+}
+
 pub fn fill_in_proof_from_canopy(
     canopy_bytes: &[u8],
     max_depth: u32,
     index: u32,
     proof: &mut Vec<Node>,
+    hash_type: HashType,
 ) -> Result<()> {
-    let mut empty_node_cache = Box::new([EMPTY; MAX_SUPPORTED_DEPTH]);
+    let empty_node_cache = Box::new([EMPTY; MAX_SUPPORTED_DEPTH]);
     check_canopy_bytes(canopy_bytes)?;
     let canopy = cast_slice::<u8, Node>(canopy_bytes);
     let path_len = get_cached_path_length(canopy, max_depth)?;
@@ -117,7 +177,8 @@ pub fn fill_in_proof_from_canopy(
         };
         if canopy[cached_idx] == EMPTY {
             let level = max_depth - (31 - node_idx.leading_zeros());
-            let empty_node = empty_node_cached::<MAX_SUPPORTED_DEPTH>(level, &mut empty_node_cache);
+            let empty_node =
+                hash_type.empty_node_cached::<MAX_SUPPORTED_DEPTH>(level, &empty_node_cache);
             inferred_nodes.push(empty_node);
         } else {
             inferred_nodes.push(canopy[cached_idx]);
@@ -132,6 +193,44 @@ pub fn fill_in_proof_from_canopy(
     Ok(())
 }
 
+/// Fills in the proof shared by the leaves at the sorted `indices` with the
+/// nodes cached in the canopy. The proof is expected to hold the nodes below
+/// the canopy, level by level in the order of `multi_proof_positions`.
+pub fn fill_in_multi_proof_from_canopy(
+    canopy_bytes: &[u8],
+    max_depth: u32,
+    indices: &[u32],
+    proof: &mut Vec<Node>,
+    hash_type: HashType,
+) -> Result<()> {
+    let mut empty_node_cache = Box::new([EMPTY; MAX_SUPPORTED_DEPTH]);
+    check_canopy_bytes(canopy_bytes)?;
+    let canopy = cast_slice::<u8, Node>(canopy_bytes);
+    let path_len = get_cached_path_length(canopy, max_depth)?;
+
+    let uncached_proof_len: usize = (0..max_depth - path_len)
+        .map(|level| multi_proof_positions(indices, level).len())
+        .sum();
+    let mut inferred_nodes = vec![];
+    for level in max_depth - path_len..max_depth {
+        for position in multi_proof_positions(indices, level) {
+            let node_idx = (1 << (max_depth - level)) + position as usize;
+            inferred_nodes.push(get_value_for_node::<MAX_SUPPORTED_DEPTH>(
+                node_idx,
+                level,
+                canopy,
+                &mut empty_node_cache,
+                hash_type,
+            ));
+        }
+    }
+    // As for a single proof, only the canopy nodes which aren't already part of
+    // the proof are added
+    let overlap = proof.len().saturating_sub(uncached_proof_len);
+    proof.extend(inferred_nodes.iter().skip(overlap));
+    Ok(())
+}
+
 /// Sets the leaf nodes of the canopy. The leaf nodes are the lowest level of the canopy,
 /// representing the leaves of the canopy-tree. The method will update the parent nodes of all the
 /// modified subtrees up to the uppermost level of the canopy. The leaf nodes indexing for the
@@ -142,6 +241,7 @@ pub fn set_canopy_leaf_nodes(
     max_depth: u32,
     start_index: u32,
     nodes: &[Node],
+    hash_type: HashType,
 ) -> Result<()> {
     check_canopy_bytes(canopy_bytes)?;
     let canopy = cast_slice_mut::<u8, Node>(canopy_bytes);
@@ -169,21 +269,28 @@ pub fn set_canopy_leaf_nodes(
                 level - 1,
                 canopy,
                 &mut empty_node_cache,
+                hash_type,
             );
             let right_child = get_value_for_node::<MAX_SUPPORTED_DEPTH>(
                 (node << 1) + 1,
                 level - 1,
                 canopy,
                 &mut empty_node_cache,
+                hash_type,
             );
-            canopy[node - 2].copy_from_slice(hashv(&[&left_child, &right_child]).as_ref());
+            canopy[node - 2] = hash_type.hash_pair(&left_child, &right_child);
         }
     }
     Ok(())
 }
 
 /// Checks the root of the canopy against the expected root.
-pub fn check_canopy_root(canopy_bytes: &[u8], expected_root: &Node, max_depth: u32) -> Result<()> {
+pub fn check_canopy_root(
+    canopy_bytes: &[u8],
+    expected_root: &Node,
+    max_depth: u32,
+    hash_type: HashType,
+) -> Result<()> {
     check_canopy_bytes(canopy_bytes)?;
     let canopy = cast_slice::<u8, Node>(canopy_bytes);
     if canopy.is_empty() {
@@ -191,11 +298,21 @@ pub fn check_canopy_root(canopy_bytes: &[u8], expected_root: &Node, max_depth: u
     }
     let mut empty_node_cache = Box::new([EMPTY; MAX_SUPPORTED_DEPTH]);
     // first two nodes are the children of the root, they have index 2 and 3 respectively
-    let left_root_child =
-        get_value_for_node::<MAX_SUPPORTED_DEPTH>(2, max_depth - 1, canopy, &mut empty_node_cache);
-    let right_root_child =
-        get_value_for_node::<MAX_SUPPORTED_DEPTH>(3, max_depth - 1, canopy, &mut empty_node_cache);
-    let actual_root = hashv(&[&left_root_child, &right_root_child]).to_bytes();
+    let left_root_child = get_value_for_node::<MAX_SUPPORTED_DEPTH>(
+        2,
+        max_depth - 1,
+        canopy,
+        &mut empty_node_cache,
+        hash_type,
+    );
+    let right_root_child = get_value_for_node::<MAX_SUPPORTED_DEPTH>(
+        3,
+        max_depth - 1,
+        canopy,
+        &mut empty_node_cache,
+        hash_type,
+    );
+    let actual_root = hash_type.hash_pair(&left_root_child, &right_root_child);
     if actual_root != *expected_root {
         msg!(
             "Canopy root mismatch. Expected: {:?}, Actual: {:?}",
@@ -260,11 +377,12 @@ fn get_value_for_node<const N: usize>(
     level: u32,
     canopy: &[Node],
     empty_node_cache: &mut [Node; N],
+    hash_type: HashType,
 ) -> Node {
     if canopy[node_idx - 2] != EMPTY {
         return canopy[node_idx - 2];
     }
-    empty_node_cached_mut::<N>(level, empty_node_cache)
+    hash_type.empty_node_cached_mut::<N>(level, empty_node_cache)
 }
 
 #[inline(always)]
@@ -275,7 +393,11 @@ fn leaf_node_index_to_canopy_index(path_len: u32, index: u32) -> Result<usize> {
 
 #[cfg(test)]
 mod tests {
-    use {super::*, spl_concurrent_merkle_tree::node::empty_node};
+    use {
+        super::*,
+        solana_program::keccak::hashv,
+        spl_concurrent_merkle_tree::node::{empty_node, empty_node_cached_mut},
+    };
 
     fn success_leaf_node_index_to_canopy_index(path_len: u32, index: u32, expected: usize) {
         assert_eq!(
@@ -325,7 +447,7 @@ mod tests {
     fn test_simple_single_level_canopy_set_canopy_leaf_nodes_with_empty_nodes() {
         let mut canopy_bytes = vec![0_u8; 2 * size_of::<Node>()];
         let nodes = vec![EMPTY; 2];
-        set_canopy_leaf_nodes(&mut canopy_bytes, 1, 0, &nodes).unwrap();
+        set_canopy_leaf_nodes(&mut canopy_bytes, 1, 0, &nodes, HashType::Keccak).unwrap();
         let canopy = cast_slice::<u8, Node>(&canopy_bytes);
 
         assert_eq!(canopy[0], EMPTY);
@@ -336,7 +458,7 @@ mod tests {
     fn test_simple_single_level_canopy_set_canopy_leaf_nodes_non_empty_nodes() {
         let mut canopy_bytes = vec![0_u8; 2 * size_of::<Node>()];
         let nodes = vec![[1_u8; 32], [2_u8; 32]];
-        set_canopy_leaf_nodes(&mut canopy_bytes, 1, 0, &nodes).unwrap();
+        set_canopy_leaf_nodes(&mut canopy_bytes, 1, 0, &nodes, HashType::Keccak).unwrap();
         let canopy = cast_slice::<u8, Node>(&canopy_bytes);
 
         assert_eq!(canopy[0], [1_u8; 32]);
@@ -347,7 +469,7 @@ mod tests {
     fn test_2levels_canopy_set_canopy_leaf_nodes_first_2_elements_provided() {
         let mut canopy_bytes = vec![0_u8; 6 * size_of::<Node>()];
         let nodes = vec![[1_u8; 32], [2_u8; 32]];
-        set_canopy_leaf_nodes(&mut canopy_bytes, 2, 0, &nodes).unwrap();
+        set_canopy_leaf_nodes(&mut canopy_bytes, 2, 0, &nodes, HashType::Keccak).unwrap();
         let canopy = cast_slice::<u8, Node>(&canopy_bytes);
 
         assert_eq!(canopy[0], hashv(&[&[1_u8; 32], &[2_u8; 32]]).to_bytes());
@@ -362,7 +484,7 @@ mod tests {
     fn test_2levels_canopy_set_canopy_leaf_nodes_last_2_elements_provided() {
         let mut canopy_bytes = vec![0_u8; 6 * size_of::<Node>()];
         let nodes = vec![[1_u8; 32], [2_u8; 32]];
-        set_canopy_leaf_nodes(&mut canopy_bytes, 2, 2, &nodes).unwrap();
+        set_canopy_leaf_nodes(&mut canopy_bytes, 2, 2, &nodes, HashType::Keccak).unwrap();
         let canopy = cast_slice::<u8, Node>(&canopy_bytes);
 
         assert_eq!(canopy[0], EMPTY); // is not updated
@@ -377,7 +499,7 @@ mod tests {
     fn test_2levels_canopy_set_canopy_leaf_nodes_middle_2_elements_provided() {
         let mut canopy_bytes = vec![0_u8; 6 * size_of::<Node>()];
         let nodes = vec![[1_u8; 32], [2_u8; 32]];
-        set_canopy_leaf_nodes(&mut canopy_bytes, 2, 1, &nodes).unwrap();
+        set_canopy_leaf_nodes(&mut canopy_bytes, 2, 1, &nodes, HashType::Keccak).unwrap();
         let canopy = cast_slice::<u8, Node>(&canopy_bytes);
 
         assert_eq!(canopy[2], EMPTY);
@@ -392,7 +514,7 @@ mod tests {
     fn test_3level_canopy_in_10_level_tree_set_canopy_leaf_nodes_first_2_elements_provided() {
         let mut canopy_bytes = vec![0_u8; 14 * size_of::<Node>()];
         let nodes = vec![[1_u8; 32], [2_u8; 32]];
-        set_canopy_leaf_nodes(&mut canopy_bytes, 10, 0, &nodes).unwrap();
+        set_canopy_leaf_nodes(&mut canopy_bytes, 10, 0, &nodes, HashType::Keccak).unwrap();
         let canopy = cast_slice::<u8, Node>(&canopy_bytes);
 
         let expected_hash12 = hashv(&[&[1_u8; 32], &[2_u8; 32]]).to_bytes();
@@ -413,7 +535,7 @@ mod tests {
     fn test_3level_canopy_in_10_level_tree_set_canopy_leaf_nodes_middle_2_elements_provided() {
         let mut canopy_bytes = vec![0_u8; 14 * size_of::<Node>()];
         let nodes = vec![[1_u8; 32], [2_u8; 32]];
-        set_canopy_leaf_nodes(&mut canopy_bytes, 10, 3, &nodes).unwrap();
+        set_canopy_leaf_nodes(&mut canopy_bytes, 10, 3, &nodes, HashType::Keccak).unwrap();
         let canopy = cast_slice::<u8, Node>(&canopy_bytes);
 
         let expected_hash_empty_1 = hashv(&[&empty_node(7), &[1_u8; 32]]).to_bytes();
@@ -439,7 +561,7 @@ mod tests {
     fn test_3level_canopy_empty_set_canopy_leaf_nodes_no_impact() {
         let mut canopy_bytes = vec![0_u8; 14 * size_of::<Node>()];
         let nodes = vec![];
-        set_canopy_leaf_nodes(&mut canopy_bytes, 10, 0, &nodes).unwrap();
+        set_canopy_leaf_nodes(&mut canopy_bytes, 10, 0, &nodes, HashType::Keccak).unwrap();
         assert_eq!(canopy_bytes, vec![0_u8; 14 * size_of::<Node>()]);
     }
 
@@ -448,8 +570,21 @@ mod tests {
         let mut canopy_bytes = vec![0_u8; 2 * size_of::<Node>()];
         let expected_root = hashv(&[&[1_u8; 32], &[2_u8; 32]]).to_bytes();
         let nodes = vec![[1_u8; 32], [2_u8; 32]];
-        set_canopy_leaf_nodes(&mut canopy_bytes, 1, 0, &nodes).unwrap();
-        check_canopy_root(&canopy_bytes, &expected_root, 30).unwrap();
+        set_canopy_leaf_nodes(&mut canopy_bytes, 1, 0, &nodes, HashType::Keccak).unwrap();
+        check_canopy_root(&canopy_bytes, &expected_root, 30, HashType::Keccak).unwrap();
+    }
+
+    #[test]
+    fn test_success_check_canopy_root_with_sha256() {
+        let mut canopy_bytes = vec![0_u8; 2 * size_of::<Node>()];
+        let expected_root = solana_program::hash::hashv(&[&[1_u8; 32], &[2_u8; 32]]).to_bytes();
+        let nodes = vec![[1_u8; 32], [2_u8; 32]];
+        set_canopy_leaf_nodes(&mut canopy_bytes, 1, 0, &nodes, HashType::Sha256).unwrap();
+        check_canopy_root(&canopy_bytes, &expected_root, 30, HashType::Sha256).unwrap();
+        assert_eq!(
+            check_canopy_root(&canopy_bytes, &expected_root, 30, HashType::Keccak).unwrap_err(),
+            AccountCompressionError::CanopyRootMismatch.into()
+        );
     }
 
     #[test]
@@ -461,8 +596,41 @@ mod tests {
             empty_node_cached_mut::<MAX_SUPPORTED_DEPTH>(top_level, &mut empty_node_cache);
         let expected_root = hashv(&[&[1_u8; 32], &right_branch]).to_bytes();
         let nodes = vec![[1_u8; 32], EMPTY];
-        set_canopy_leaf_nodes(&mut canopy_bytes, MAX_SUPPORTED_DEPTH as u32, 0, &nodes).unwrap();
-        check_canopy_root(&canopy_bytes, &expected_root, 30).unwrap();
+        set_canopy_leaf_nodes(
+            &mut canopy_bytes,
+            MAX_SUPPORTED_DEPTH as u32,
+            0,
+            &nodes,
+            HashType::Keccak,
+        )
+        .unwrap();
+        check_canopy_root(&canopy_bytes, &expected_root, 30, HashType::Keccak).unwrap();
+    }
+
+    #[test]
+    fn test_success_fill_in_multi_proof_from_canopy() {
+        let mut canopy_bytes = vec![0_u8; 6 * size_of::<Node>()];
+        let canopy = cast_slice_mut::<u8, Node>(&mut canopy_bytes);
+        for (i, node) in canopy.iter_mut().enumerate() {
+            *node = [i as u8 + 1; 32];
+        }
+        let nodes_below_canopy = vec![[11_u8; 32], [12_u8; 32], [13_u8; 32]];
+
+        // Leaves 0 and 1 share their ancestors, the siblings of the ancestors of
+        // the leaves at the level 2 are the canopy nodes 5 and 7
+        let mut proof = nodes_below_canopy.clone();
+        fill_in_multi_proof_from_canopy(&canopy_bytes, 4, &[0, 1, 9], &mut proof, HashType::Keccak)
+            .unwrap();
+        let mut expected = nodes_below_canopy.clone();
+        expected.extend([[4_u8; 32], [6_u8; 32]]);
+        assert_eq!(proof, expected);
+
+        // Canopy nodes already provided in the proof are skipped
+        let mut proof = nodes_below_canopy.clone();
+        proof.push([4_u8; 32]);
+        fill_in_multi_proof_from_canopy(&canopy_bytes, 4, &[0, 1, 9], &mut proof, HashType::Keccak)
+            .unwrap();
+        assert_eq!(proof, expected);
     }
 
     #[test]
@@ -470,11 +638,11 @@ mod tests {
         let mut canopy_bytes = vec![0_u8; 2 * size_of::<Node>()];
         let expected_root = hashv(&[&[1_u8; 32], &[2_u8; 32]]).to_bytes();
         let nodes = vec![[1_u8; 32], [2_u8; 32]];
-        set_canopy_leaf_nodes(&mut canopy_bytes, 1, 0, &nodes).unwrap();
+        set_canopy_leaf_nodes(&mut canopy_bytes, 1, 0, &nodes, HashType::Keccak).unwrap();
         let mut expected_root = expected_root;
         expected_root[0] = 0;
         assert_eq!(
-            check_canopy_root(&canopy_bytes, &expected_root, 30).unwrap_err(),
+            check_canopy_root(&canopy_bytes, &expected_root, 30, HashType::Keccak).unwrap_err(),
             AccountCompressionError::CanopyRootMismatch.into()
         );
     }
@@ -1,31 +1,39 @@
-//! This module provides a wrapper around the `ConcurrentMerkleTree` struct from
-//! the `spl_concurrent_merkle_tree` crate. It provides a set of functions that
-//! can be called from the Anchor program to interact with the tree.
-//! The functions are used to initialize the tree, set a leaf, fill empty or
-//! append a leaf, and prove a leaf. As the tree is generic over the depth and
-//! buffer size, the functions are implemented using macros that infer the depth
-//! and buffer size from the header information stored on-chain. Usage of the
-//! macros directly is discouraged, as they have huge match statements with
-//! every case taking it's own stack frame. Instead, use the exported functions
-//! from this module and refenrece or Box the arguments to the functions to
-//! avoid the stack frame explosion.
+//! This module provides a wrapper around the `ConcurrentMerkleTreeView` struct
+//! from the `spl_concurrent_merkle_tree` crate. It provides a set of functions
+//! that can be called from the Anchor program to interact with the tree.
+//! The functions are used to initialize the tree, set leaves, fill empty or
+//! append leaves and subtrees, insert values in indexed trees, and prove a
+//! leaf or the absence of a value. The view reads the tree in
+//! place from the account data, with the depth and buffer size stored in the
+//! header information on-chain, so any max depth up to 30 and any power of 2
+//! buffer size is supported. Usage of the macros directly is discouraged, as they
+//! expand to a match over the hash functions with every case taking it's own
+//! stack frame. Instead, use the exported functions from this module and
+//! reference or Box the arguments to the functions to avoid the stack frame
+//! explosion.
 
 pub use crate::error::AccountCompressionError;
 /// Exported for Anchor / Solita
 pub use spl_concurrent_merkle_tree::{
     concurrent_merkle_tree::{
-        ConcurrentMerkleTree, FillEmptyOrAppendArgs, InitializeWithRootArgs, ProveLeafArgs,
-        SetLeafArgs,
+        AppendBatchArgs, AppendSubtreeArgs, ConcurrentMerkleTree, FillEmptyOrAppendArgs,
+        InitializeWithRootArgs, InsertIndexedLeafArgs, MigrateArgs, ProveLeafArgs,
+        ProveNonMembershipArgs, SetLeafArgs, SetLeavesArgs,
     },
+    concurrent_merkle_tree_view::ConcurrentMerkleTreeView,
     error::ConcurrentMerkleTreeError,
+    hash::{Hasher, Keccak, Poseidon, Sha256},
     node::Node,
     node::EMPTY,
 };
 use {
     crate::{
-        events::ChangeLogEvent, macros::*, state::ConcurrentMerkleTreeHeader, zero_copy::ZeroCopy,
+        events::ChangeLogEvent,
+        macros::*,
+        state::{ConcurrentMerkleTreeHeader, HashType},
     },
     anchor_lang::prelude::*,
+    spl_concurrent_merkle_tree::indexed_leaf::IndexedLeaf,
 };
 
 #[inline(never)]
@@ -57,6 +65,36 @@ pub fn merkle_tree_set_leaf(
     merkle_tree_apply_fn_mut!(header, tree_id, tree_bytes, set_leaf, args)
 }
 
+#[inline(never)]
+pub fn merkle_tree_set_leaves(
+    header: &ConcurrentMerkleTreeHeader,
+    tree_id: Pubkey,
+    tree_bytes: &mut [u8],
+    args: &SetLeavesArgs,
+) -> Result<Box<ChangeLogEvent>> {
+    merkle_tree_apply_fn_mut_multiple!(header, tree_id, tree_bytes, set_leaves, args)
+}
+
+#[inline(never)]
+pub fn merkle_tree_append_subtree(
+    header: &ConcurrentMerkleTreeHeader,
+    tree_id: Pubkey,
+    tree_bytes: &mut [u8],
+    args: &AppendSubtreeArgs,
+) -> Result<Box<ChangeLogEvent>> {
+    merkle_tree_apply_fn_mut!(header, tree_id, tree_bytes, append_subtree, args)
+}
+
+#[inline(never)]
+pub fn merkle_tree_append_batch(
+    header: &ConcurrentMerkleTreeHeader,
+    tree_id: Pubkey,
+    tree_bytes: &mut [u8],
+    args: &AppendBatchArgs,
+) -> Result<Box<ChangeLogEvent>> {
+    merkle_tree_apply_fn_mut_multiple!(header, tree_id, tree_bytes, append_batch, args)
+}
+
 #[inline(never)]
 pub fn merkle_tree_fill_empty_or_append(
     header: &ConcurrentMerkleTreeHeader,
@@ -87,6 +125,94 @@ pub fn merkle_tree_append_leaf(
     merkle_tree_apply_fn_mut!(header, tree_id, tree_bytes, append, *args)
 }
 
+#[inline(never)]
+pub fn merkle_tree_insert_indexed_leaf(
+    header: &ConcurrentMerkleTreeHeader,
+    tree_id: Pubkey,
+    tree_bytes: &mut [u8],
+    args: &InsertIndexedLeafArgs,
+) -> Result<Box<ChangeLogEvent>> {
+    merkle_tree_apply_fn_mut_multiple!(header, tree_id, tree_bytes, insert_indexed_leaf, args)
+}
+
+#[inline(never)]
+pub fn merkle_tree_prove_non_membership(
+    header: &ConcurrentMerkleTreeHeader,
+    tree_id: Pubkey,
+    tree_bytes: &[u8],
+    args: &ProveNonMembershipArgs,
+) -> Result<Box<ChangeLogEvent>> {
+    merkle_tree_apply_fn!(header, tree_id, tree_bytes, prove_non_membership, args)
+}
+
+/// Returns the node of the default leaf starting every indexed tree, using the
+/// hash function of the tree
+pub fn indexed_tree_default_leaf(header: &ConcurrentMerkleTreeHeader) -> Node {
+    let leaf = IndexedLeaf::default();
+    match header.get_hash_type() {
+        HashType::Keccak => leaf.hash_with_hasher::<Keccak>(),
+        HashType::Sha256 => leaf.hash_with_hasher::<Sha256>(),
+        HashType::Poseidon => leaf.hash_with_hasher::<Poseidon>(),
+    }
+}
+
+/// Returns the number of leaves appended to the tree, which doesn't depend on
+/// the hash function of the tree
+#[inline(never)]
+pub fn merkle_tree_get_rightmost_index(
+    header: &ConcurrentMerkleTreeHeader,
+    tree_bytes: &[u8],
+) -> Result<u32> {
+    match ConcurrentMerkleTreeView::<_>::new(
+        tree_bytes,
+        header.get_max_depth(),
+        header.get_max_buffer_size(),
+    ) {
+        Ok(merkle_tree) => Ok(merkle_tree.get_rightmost_proof().index),
+        Err(err) => {
+            msg!("Error zero copying concurrent merkle tree: {}", err);
+            err!(AccountCompressionError::ZeroCopyError)
+        }
+    }
+}
+
+fn get_migration_args<H: Hasher>(
+    header: &ConcurrentMerkleTreeHeader,
+    tree_bytes: &[u8],
+    args: &MigrateArgs,
+) -> Result<Box<InitializeWithRootArgs>> {
+    match ConcurrentMerkleTreeView::<_, H>::new(
+        tree_bytes,
+        header.get_max_depth(),
+        header.get_max_buffer_size(),
+    ) {
+        Ok(merkle_tree) => match merkle_tree.get_migration_args(args) {
+            Ok(migration_args) => Ok(Box::new(migration_args)),
+            Err(err) => {
+                msg!("Error using concurrent merkle tree: {}", err);
+                err!(AccountCompressionError::ConcurrentMerkleTreeError)
+            }
+        },
+        Err(err) => {
+            msg!("Error zero copying concurrent merkle tree: {}", err);
+            err!(AccountCompressionError::ZeroCopyError)
+        }
+    }
+}
+
+#[inline(never)]
+pub fn merkle_tree_get_migration_args(
+    header: &ConcurrentMerkleTreeHeader,
+    tree_bytes: &[u8],
+    args: &MigrateArgs,
+) -> Result<Box<InitializeWithRootArgs>> {
+    match header.get_hash_type() {
+        HashType::Keccak => get_migration_args::<Keccak>(header, tree_bytes, args),
+        HashType::Sha256 => get_migration_args::<Sha256>(header, tree_bytes, args),
+        HashType::Poseidon => get_migration_args::<Poseidon>(header, tree_bytes, args),
+    }
+}
+
 /// Checks whether the tree in not initialized yet without doing the deserialization. A rought
 /// equivalent to deserializing the tree and calling is_initialized() on it without the heavy
 /// lifting with macros. An empty account is a zero'd account. The tree is considered empty if the
@@ -110,6 +236,6 @@ pub fn assert_tree_is_empty(
         return Ok(());
     }
     // check the tree is empty
-    merkle_tree_apply_fn_mut!(header, tree_id, tree_bytes, prove_tree_is_empty,)?;
+    merkle_tree_apply_fn!(header, tree_id, tree_bytes, prove_tree_is_empty,)?;
     Ok(())
 }
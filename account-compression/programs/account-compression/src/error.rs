@@ -67,6 +67,23 @@ pub enum AccountCompressionError {
     /// The canopy contains nodes to the right of the rightmost leaf of the tree
     #[msg("Canopy contains nodes to the right of the rightmost leaf of the tree")]
     CanopyRightmostLeafMismatch,
+
+    /// Appended subtrees must fit below the canopy, which caches the upper
+    /// levels of the tree and can only be updated along a single path
+    #[msg("Subtree is too high to be appended below the canopy of the tree")]
+    SubtreeTooHigh,
+
+    /// The tree was migrated into a deeper tree, which holds its leaves
+    #[msg("Tree was migrated into a deeper tree and is read-only")]
+    TreeReadOnly,
+
+    /// The instruction only applies to indexed trees
+    #[msg("Tree is not an indexed tree")]
+    TreeNotIndexed,
+
+    /// Leaves of an indexed tree can only be added with `insert_indexed_leaf`
+    #[msg("Leaves of an indexed tree can only be added with insert_indexed_leaf")]
+    IndexedTreeLeafModification,
 }
 
 impl From<&ConcurrentMerkleTreeError> for AccountCompressionError {
@@ -1,12 +1,15 @@
 use crate::state::PathNode;
 
 use anchor_lang::prelude::*;
-use spl_concurrent_merkle_tree::changelog::ChangeLog;
+use spl_concurrent_merkle_tree::{
+    changelog::ChangeLog, concurrent_merkle_tree_view::DynamicChangeLog, node::Node,
+};
 
 #[derive(AnchorDeserialize, AnchorSerialize)]
 #[repr(C)]
 pub enum ChangeLogEvent {
     V1(ChangeLogEventV1),
+    V2(ChangeLogEventV2),
 }
 
 #[derive(AnchorDeserialize, AnchorSerialize)]
@@ -25,6 +28,29 @@ pub struct ChangeLogEventV1 {
     pub index: u32,
 }
 
+/// Change log event of an operation modifying several leaves of the tree
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct ChangeLogEventV2 {
+    /// Public key of the ConcurrentMerkleTree
+    pub id: Pubkey,
+
+    /// Paths of the modified leaves, in the order they were applied
+    pub paths: Vec<ChangeLogPath>,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct ChangeLogPath {
+    /// Nodes of off-chain merkle tree needed by indexer
+    pub path: Vec<PathNode>,
+
+    /// Index corresponding to the number of successful operations on this tree
+    /// once this path was applied.
+    pub seq: u64,
+
+    /// Bitmap of node parity (used when hashing)
+    pub index: u32,
+}
+
 impl ChangeLogEvent {
     pub fn new(id: Pubkey, path: Vec<PathNode>, seq: u64, index: u32) -> Self {
         Self::V1(ChangeLogEventV1 {
@@ -36,29 +62,93 @@ impl ChangeLogEvent {
     }
 }
 
+/// Returns the nodes of the changelog path, from the leaf up to the root
+fn get_path_nodes(root: Node, changelog_path: &[Node], index: u32) -> Vec<PathNode> {
+    let path_len = changelog_path.len() as u32;
+    let mut path: Vec<PathNode> = changelog_path
+        .iter()
+        .enumerate()
+        .map(|(lvl, n)| PathNode::new(*n, (1 << (path_len - lvl as u32)) + (index >> lvl)))
+        .collect();
+    path.push(PathNode::new(root, 1));
+    path
+}
+
+/// Builds the event of the given changelog paths, given as their root, their
+/// path and their index, oldest first, where `seq` is the sequence number of
+/// the tree after the last one
+fn changelog_paths_event<'a>(
+    changelogs: impl ExactSizeIterator<Item = (Node, &'a [Node], u32)>,
+    tree_id: Pubkey,
+    seq: u64,
+) -> Box<ChangeLogEvent> {
+    let first_seq = seq.saturating_sub((changelogs.len() as u64).saturating_sub(1));
+    let paths = changelogs
+        .enumerate()
+        .map(|(i, (root, path, index))| ChangeLogPath {
+            path: get_path_nodes(root, path, index),
+            seq: first_seq + i as u64,
+            index,
+        })
+        .collect();
+    Box::new(ChangeLogEvent::V2(ChangeLogEventV2 { id: tree_id, paths }))
+}
+
 impl<const MAX_DEPTH: usize> From<(Box<ChangeLog<MAX_DEPTH>>, Pubkey, u64)>
     for Box<ChangeLogEvent>
 {
     fn from(log_info: (Box<ChangeLog<MAX_DEPTH>>, Pubkey, u64)) -> Self {
         let (changelog, tree_id, seq) = log_info;
-        let path_len = changelog.path.len() as u32;
-        let mut path: Vec<PathNode> = changelog
-            .path
-            .iter()
-            .enumerate()
-            .map(|(lvl, n)| {
-                PathNode::new(
-                    *n,
-                    (1 << (path_len - lvl as u32)) + (changelog.index >> lvl),
-                )
-            })
-            .collect();
-        path.push(PathNode::new(changelog.root, 1));
         Box::new(ChangeLogEvent::V1(ChangeLogEventV1 {
             id: tree_id,
-            path,
+            path: get_path_nodes(changelog.root, &changelog.path, changelog.index),
             seq,
             index: changelog.index,
         }))
     }
 }
+
+/// Builds the event of the given changelogs, oldest first, where `seq` is the
+/// sequence number of the tree after the last one
+impl<const MAX_DEPTH: usize> From<(Vec<ChangeLog<MAX_DEPTH>>, Pubkey, u64)>
+    for Box<ChangeLogEvent>
+{
+    fn from(log_info: (Vec<ChangeLog<MAX_DEPTH>>, Pubkey, u64)) -> Self {
+        let (changelogs, tree_id, seq) = log_info;
+        changelog_paths_event(
+            changelogs
+                .iter()
+                .map(|changelog| (changelog.root, &changelog.path[..], changelog.index)),
+            tree_id,
+            seq,
+        )
+    }
+}
+
+impl From<(DynamicChangeLog, Pubkey, u64)> for Box<ChangeLogEvent> {
+    fn from(log_info: (DynamicChangeLog, Pubkey, u64)) -> Self {
+        let (changelog, tree_id, seq) = log_info;
+        Box::new(ChangeLogEvent::V1(ChangeLogEventV1 {
+            id: tree_id,
+            path: get_path_nodes(changelog.root, &changelog.path, changelog.index),
+            seq,
+            index: changelog.index,
+        }))
+    }
+}
+
+/// Builds the event of the given changelogs of a tree sized at runtime,
+/// oldest first, where `seq` is the sequence number of the tree after the last
+/// one
+impl From<(Vec<DynamicChangeLog>, Pubkey, u64)> for Box<ChangeLogEvent> {
+    fn from(log_info: (Vec<DynamicChangeLog>, Pubkey, u64)) -> Self {
+        let (changelogs, tree_id, seq) = log_info;
+        changelog_paths_event(
+            changelogs
+                .iter()
+                .map(|changelog| (changelog.root, &changelog.path[..], changelog.index)),
+            tree_id,
+            seq,
+        )
+    }
+}
@@ -42,14 +42,16 @@ pub use crate::noop::{wrap_application_data_v1, Noop};
 
 use crate::canopy::{
     check_canopy_bytes, check_canopy_no_nodes_to_right_of_index, check_canopy_root,
-    fill_in_proof_from_canopy, set_canopy_leaf_nodes, update_canopy,
+    copy_subtree_canopy, fill_in_multi_proof_from_canopy, fill_in_proof_from_canopy,
+    get_canopy_depth, set_canopy_leaf_nodes, update_canopy,
 };
 use crate::concurrent_tree_wrapper::*;
 pub use crate::error::AccountCompressionError;
 pub use crate::events::{AccountCompressionEvent, ChangeLogEvent};
 use crate::noop::wrap_event;
 use crate::state::{
-    merkle_tree_get_size, ConcurrentMerkleTreeHeader, CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1,
+    merkle_tree_get_size, ConcurrentMerkleTreeHeader, HashType, IndexedLeaf, LeafUpdate,
+    CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1,
 };
 
 /// Exported for Anchor / Solita
@@ -116,6 +118,24 @@ pub struct TransferAuthority<'info> {
     pub authority: Signer<'info>,
 }
 
+/// Context for migrating a tree into a new, deeper tree
+#[derive(Accounts)]
+pub struct MigrateTree<'info> {
+    #[account(mut)]
+    /// CHECK: This account is validated in the instruction
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    #[account(zero)]
+    /// CHECK: This account will be zeroed out, and the size will be validated
+    pub new_merkle_tree: UncheckedAccount<'info>,
+
+    /// Authority that controls write-access to both trees
+    pub authority: Signer<'info>,
+
+    /// Program used to emit changelogs as cpi instruction data.
+    pub noop: Program<'info, Noop>,
+}
+
 /// Context for closing a tree
 #[derive(Accounts)]
 pub struct CloseTree<'info> {
@@ -151,6 +171,23 @@ pub mod spl_account_compression {
         ctx: Context<Initialize>,
         max_depth: u32,
         max_buffer_size: u32,
+    ) -> Result<()> {
+        init_empty_merkle_tree_with_hash_type(ctx, max_depth, max_buffer_size, HashType::Keccak)
+    }
+
+    /// Creates a new merkle tree like `init_empty_merkle_tree` with the nodes of the tree
+    /// computed by the given hash function.
+    ///
+    /// The hash type is recorded in the tree header and all subsequent instructions use it
+    /// to compute and verify the nodes of the tree. Trees created by `init_empty_merkle_tree`
+    /// use `HashType::Keccak`.
+    ///
+    /// Leaves of `HashType::Poseidon` trees must be BN254 field elements.
+    pub fn init_empty_merkle_tree_with_hash_type(
+        ctx: Context<Initialize>,
+        max_depth: u32,
+        max_buffer_size: u32,
+        hash_type: HashType,
     ) -> Result<()> {
         require_eq!(
             *ctx.accounts.merkle_tree.owner,
@@ -168,6 +205,7 @@ pub mod spl_account_compression {
             max_buffer_size,
             &ctx.accounts.authority.key(),
             Clock::get()?.slot,
+            hash_type,
         );
         header.serialize(&mut header_bytes)?;
 
@@ -184,6 +222,62 @@ pub mod spl_account_compression {
         update_canopy(canopy_bytes, header.get_max_depth(), None)
     }
 
+    /// Creates a new indexed merkle tree, whose leaves form a linked list sorted by value so
+    /// that the absence of a value can be proven with `verify_non_membership`.
+    ///
+    /// The tree is created like `init_empty_merkle_tree_with_hash_type` and marked as indexed
+    /// in its header, then the default leaf, of value 0, is appended at index 0. Values can
+    /// then only be added with `insert_indexed_leaf`: the other instructions modifying leaves
+    /// are rejected on indexed trees, as they would break the linked list.
+    pub fn init_indexed_merkle_tree(
+        ctx: Context<Initialize>,
+        max_depth: u32,
+        max_buffer_size: u32,
+        hash_type: HashType,
+    ) -> Result<()> {
+        require_eq!(
+            *ctx.accounts.merkle_tree.owner,
+            crate::id(),
+            AccountCompressionError::IncorrectAccountOwner
+        );
+        let mut merkle_tree_bytes = ctx.accounts.merkle_tree.try_borrow_mut_data()?;
+
+        let (mut header_bytes, rest) =
+            merkle_tree_bytes.split_at_mut(CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1);
+
+        let mut header = ConcurrentMerkleTreeHeader::try_from_slice(header_bytes)?;
+        header.initialize_indexed(
+            max_depth,
+            max_buffer_size,
+            &ctx.accounts.authority.key(),
+            Clock::get()?.slot,
+            hash_type,
+        );
+        header.serialize(&mut header_bytes)?;
+
+        let merkle_tree_size = merkle_tree_get_size(&header)?;
+        let (tree_bytes, canopy_bytes) = rest.split_at_mut(merkle_tree_size);
+        let id = ctx.accounts.merkle_tree.key();
+
+        let change_log_event = merkle_tree_initialize_empty(&header, id, tree_bytes)?;
+        wrap_event(
+            &AccountCompressionEvent::ChangeLog(*change_log_event),
+            &ctx.accounts.noop,
+        )?;
+
+        let leaf = indexed_tree_default_leaf(&header);
+        let change_log_event = merkle_tree_append_leaf(&header, id, tree_bytes, &leaf)?;
+        update_canopy(
+            canopy_bytes,
+            header.get_max_depth(),
+            Some(&change_log_event),
+        )?;
+        wrap_event(
+            &AccountCompressionEvent::ChangeLog(*change_log_event),
+            &ctx.accounts.noop,
+        )
+    }
+
     /// (Devnet only) In order to initialize a tree with a root, we need to create the tree on-chain first with
     /// the proper authority. The tree might contain a canopy, which is a cache of the uppermost
     /// nodes. The canopy is used to decrease the size of the proof required to update the tree.
@@ -204,6 +298,18 @@ pub mod spl_account_compression {
         ctx: Context<Initialize>,
         max_depth: u32,
         max_buffer_size: u32,
+    ) -> Result<()> {
+        prepare_batch_merkle_tree_with_hash_type(ctx, max_depth, max_buffer_size, HashType::Keccak)
+    }
+
+    /// (Devnet only) Prepares a tree like `prepare_batch_merkle_tree` with the nodes of the
+    /// tree computed by the given hash function. The canopy nodes and the root passed to
+    /// `append_canopy_nodes` and `init_prepared_tree_with_root` must be computed with it.
+    pub fn prepare_batch_merkle_tree_with_hash_type(
+        ctx: Context<Initialize>,
+        max_depth: u32,
+        max_buffer_size: u32,
+        hash_type: HashType,
     ) -> Result<()> {
         require_eq!(
             *ctx.accounts.merkle_tree.owner,
@@ -221,6 +327,7 @@ pub mod spl_account_compression {
             max_buffer_size,
             &ctx.accounts.authority.key(),
             Clock::get()?.slot,
+            hash_type,
         );
         header.serialize(&mut header_bytes)?;
         let merkle_tree_size = merkle_tree_get_size(&header)?;
@@ -274,6 +381,7 @@ pub mod spl_account_compression {
             header.get_max_depth(),
             start_index,
             &canopy_nodes,
+            header.get_hash_type(),
         )
     }
 
@@ -308,7 +416,12 @@ pub mod spl_account_compression {
         let merkle_tree_size = merkle_tree_get_size(&header)?;
         let (tree_bytes, canopy_bytes) = rest.split_at_mut(merkle_tree_size);
         // check the canopy root matches the tree root
-        check_canopy_root(canopy_bytes, &root, header.get_max_depth())?;
+        check_canopy_root(
+            canopy_bytes,
+            &root,
+            header.get_max_depth(),
+            header.get_hash_type(),
+        )?;
         // verify the canopy does not conain any nodes to the right of the rightmost leaf
         check_canopy_no_nodes_to_right_of_index(
             canopy_bytes,
@@ -326,6 +439,7 @@ pub mod spl_account_compression {
             header.get_max_depth(),
             rightmost_index,
             &mut proof,
+            header.get_hash_type(),
         )?;
         assert_eq!(proof.len(), header.get_max_depth() as usize);
 
@@ -366,6 +480,8 @@ pub mod spl_account_compression {
 
         let header = ConcurrentMerkleTreeHeader::try_from_slice(header_bytes)?;
         header.assert_valid_authority(&ctx.accounts.authority.key())?;
+        header.assert_not_read_only()?;
+        header.assert_not_indexed()?;
         header.assert_valid_leaf_index(index)?;
 
         let merkle_tree_size = merkle_tree_get_size(&header)?;
@@ -375,7 +491,13 @@ pub mod spl_account_compression {
         for node in ctx.remaining_accounts.iter() {
             proof.push(node.key().to_bytes());
         }
-        fill_in_proof_from_canopy(canopy_bytes, header.get_max_depth(), index, &mut proof)?;
+        fill_in_proof_from_canopy(
+            canopy_bytes,
+            header.get_max_depth(),
+            index,
+            &mut proof,
+            header.get_hash_type(),
+        )?;
         let id = ctx.accounts.merkle_tree.key();
         // A call is made to ConcurrentMerkleTree::set_leaf(root, previous_leaf, new_leaf, proof, index)
         let args = &SetLeafArgs {
@@ -398,6 +520,73 @@ pub mod spl_account_compression {
         )
     }
 
+    /// Executes an instruction that overwrites several leaf nodes against the
+    /// same root. The `leaves` must be sorted by strictly increasing index.
+    /// The nodes of the proof shared by all the leaves are passed via remaining
+    /// accounts, level by level starting from the leaves, each level holding
+    /// the siblings of the modified subtrees which can't be computed from the
+    /// leaves themselves, sorted by position.
+    /// A single `ChangeLogEvent` is emitted with the paths of all the leaves.
+    /// Every leaf still takes its own changelog in the tree, so that concurrent
+    /// proofs can be fast-forwarded, and at most `max_buffer_size` leaves can
+    /// be replaced at once.
+    pub fn replace_leaves(
+        ctx: Context<Modify>,
+        root: [u8; 32],
+        leaves: Vec<LeafUpdate>,
+    ) -> Result<()> {
+        require_eq!(
+            *ctx.accounts.merkle_tree.owner,
+            crate::id(),
+            AccountCompressionError::IncorrectAccountOwner
+        );
+        let mut merkle_tree_bytes = ctx.accounts.merkle_tree.try_borrow_mut_data()?;
+        let (header_bytes, rest) =
+            merkle_tree_bytes.split_at_mut(CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1);
+
+        let header = ConcurrentMerkleTreeHeader::try_from_slice(header_bytes)?;
+        header.assert_valid_authority(&ctx.accounts.authority.key())?;
+        header.assert_not_read_only()?;
+        header.assert_not_indexed()?;
+        for leaf in leaves.iter() {
+            header.assert_valid_leaf_index(leaf.index)?;
+        }
+
+        let merkle_tree_size = merkle_tree_get_size(&header)?;
+        let (tree_bytes, canopy_bytes) = rest.split_at_mut(merkle_tree_size);
+
+        let mut proof = vec![];
+        for node in ctx.remaining_accounts.iter() {
+            proof.push(node.key().to_bytes());
+        }
+        let indices: Vec<u32> = leaves.iter().map(|leaf| leaf.index).collect();
+        fill_in_multi_proof_from_canopy(
+            canopy_bytes,
+            header.get_max_depth(),
+            &indices,
+            &mut proof,
+            header.get_hash_type(),
+        )?;
+        let id = ctx.accounts.merkle_tree.key();
+        // A call is made to ConcurrentMerkleTree::set_leaves(root, leaves, proof)
+        let args = &SetLeavesArgs {
+            current_root: root,
+            leaves: leaves.into_iter().map(Into::into).collect(),
+            proof_vec: proof,
+        };
+        let change_log_event = merkle_tree_set_leaves(&header, id, tree_bytes, args)?;
+
+        update_canopy(
+            canopy_bytes,
+            header.get_max_depth(),
+            Some(&change_log_event),
+        )?;
+        wrap_event(
+            &AccountCompressionEvent::ChangeLog(*change_log_event),
+            &ctx.accounts.noop,
+        )
+    }
+
     /// Transfers `authority`.
     /// Requires `authority` to sign
     pub fn transfer_authority(
@@ -450,7 +639,13 @@ pub mod spl_account_compression {
         for node in ctx.remaining_accounts.iter() {
             proof.push(node.key().to_bytes());
         }
-        fill_in_proof_from_canopy(canopy_bytes, header.get_max_depth(), index, &mut proof)?;
+        fill_in_proof_from_canopy(
+            canopy_bytes,
+            header.get_max_depth(),
+            index,
+            &mut proof,
+            header.get_hash_type(),
+        )?;
         let id = ctx.accounts.merkle_tree.key();
 
         let args = &ProveLeafArgs {
@@ -464,6 +659,60 @@ pub mod spl_account_compression {
         Ok(())
     }
 
+    /// Verifies that `value` isn't in an indexed tree created by `init_indexed_merkle_tree`.
+    /// `low_leaf` is the leaf at `low_leaf_index` holding the largest value of the tree
+    /// smaller than `value`, and its proof is passed via remaining accounts.
+    /// If the low leaf doesn't prove the absence of the value, or the tree isn't indexed,
+    /// throws an error.
+    pub fn verify_non_membership(
+        ctx: Context<VerifyLeaf>,
+        root: [u8; 32],
+        value: [u8; 32],
+        low_leaf: IndexedLeaf,
+        low_leaf_index: u32,
+    ) -> Result<()> {
+        require_eq!(
+            *ctx.accounts.merkle_tree.owner,
+            crate::id(),
+            AccountCompressionError::IncorrectAccountOwner
+        );
+        let merkle_tree_bytes = ctx.accounts.merkle_tree.try_borrow_data()?;
+        let (header_bytes, rest) =
+            merkle_tree_bytes.split_at(CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1);
+
+        let header = ConcurrentMerkleTreeHeader::try_from_slice(header_bytes)?;
+        header.assert_valid()?;
+        header.assert_is_indexed()?;
+        header.assert_valid_leaf_index(low_leaf_index)?;
+
+        let merkle_tree_size = merkle_tree_get_size(&header)?;
+        let (tree_bytes, canopy_bytes) = rest.split_at(merkle_tree_size);
+
+        let mut proof = vec![];
+        for node in ctx.remaining_accounts.iter() {
+            proof.push(node.key().to_bytes());
+        }
+        fill_in_proof_from_canopy(
+            canopy_bytes,
+            header.get_max_depth(),
+            low_leaf_index,
+            &mut proof,
+            header.get_hash_type(),
+        )?;
+        let id = ctx.accounts.merkle_tree.key();
+
+        let args = &ProveNonMembershipArgs {
+            current_root: root,
+            value,
+            low_leaf: low_leaf.into(),
+            low_leaf_index,
+            proof_vec: proof,
+        };
+        merkle_tree_prove_non_membership(&header, id, tree_bytes, args)?;
+
+        Ok(())
+    }
+
     /// This instruction allows the tree's `authority` to append a new leaf to the tree
     /// without having to supply a proof.
     ///
@@ -482,6 +731,8 @@ pub mod spl_account_compression {
 
         let header = ConcurrentMerkleTreeHeader::try_from_slice(header_bytes)?;
         header.assert_valid_authority(&ctx.accounts.authority.key())?;
+        header.assert_not_read_only()?;
+        header.assert_not_indexed()?;
 
         let id = ctx.accounts.merkle_tree.key();
         let merkle_tree_size = merkle_tree_get_size(&header)?;
@@ -519,6 +770,8 @@ pub mod spl_account_compression {
 
         let header = ConcurrentMerkleTreeHeader::try_from_slice(header_bytes)?;
         header.assert_valid_authority(&ctx.accounts.authority.key())?;
+        header.assert_not_read_only()?;
+        header.assert_not_indexed()?;
         header.assert_valid_leaf_index(index)?;
 
         let merkle_tree_size = merkle_tree_get_size(&header)?;
@@ -528,7 +781,13 @@ pub mod spl_account_compression {
         for node in ctx.remaining_accounts.iter() {
             proof.push(node.key().to_bytes());
         }
-        fill_in_proof_from_canopy(canopy_bytes, header.get_max_depth(), index, &mut proof)?;
+        fill_in_proof_from_canopy(
+            canopy_bytes,
+            header.get_max_depth(),
+            index,
+            &mut proof,
+            header.get_hash_type(),
+        )?;
         // A call is made to ConcurrentMerkleTree::fill_empty_or_append
         let id = ctx.accounts.merkle_tree.key();
         let args = &FillEmptyOrAppendArgs {
@@ -550,6 +809,273 @@ pub mod spl_account_compression {
         )
     }
 
+    /// This instruction allows the tree's `authority` to append a precomputed
+    /// subtree of `2^h` leaves to the tree without having to supply a proof.
+    /// The subtree must start at a multiple of its number of leaves, and must
+    /// fit below the canopy of the tree.
+    /// The proof of `rightmost_leaf`, the last leaf of the subtree, within the
+    /// subtree is passed via remaining accounts; its length is the height `h`.
+    /// A single changelog is emitted for the last leaf of the subtree.
+    pub fn append_subtree(
+        ctx: Context<Modify>,
+        subtree_root: [u8; 32],
+        rightmost_leaf: [u8; 32],
+    ) -> Result<()> {
+        require_eq!(
+            *ctx.accounts.merkle_tree.owner,
+            crate::id(),
+            AccountCompressionError::IncorrectAccountOwner
+        );
+        let mut merkle_tree_bytes = ctx.accounts.merkle_tree.try_borrow_mut_data()?;
+        let (header_bytes, rest) =
+            merkle_tree_bytes.split_at_mut(CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1);
+
+        let header = ConcurrentMerkleTreeHeader::try_from_slice(header_bytes)?;
+        header.assert_valid_authority(&ctx.accounts.authority.key())?;
+        header.assert_not_read_only()?;
+        header.assert_not_indexed()?;
+
+        let merkle_tree_size = merkle_tree_get_size(&header)?;
+        let (tree_bytes, canopy_bytes) = rest.split_at_mut(merkle_tree_size);
+
+        let mut proof = vec![];
+        for node in ctx.remaining_accounts.iter() {
+            proof.push(node.key().to_bytes());
+        }
+        let canopy_depth = get_canopy_depth(canopy_bytes, header.get_max_depth())?;
+        if proof.len() as u32 > header.get_max_depth() - canopy_depth {
+            msg!(
+                "Subtree height {} exceeds max height {}",
+                proof.len(),
+                header.get_max_depth() - canopy_depth
+            );
+            return err!(AccountCompressionError::SubtreeTooHigh);
+        }
+        let id = ctx.accounts.merkle_tree.key();
+        // A call is made to ConcurrentMerkleTree::append_subtree(subtree_root, rightmost_leaf, proof)
+        let args = &AppendSubtreeArgs {
+            subtree_root,
+            rightmost_leaf,
+            proof_vec: proof,
+        };
+        let change_log_event = merkle_tree_append_subtree(&header, id, tree_bytes, args)?;
+
+        update_canopy(
+            canopy_bytes,
+            header.get_max_depth(),
+            Some(&change_log_event),
+        )?;
+        wrap_event(
+            &AccountCompressionEvent::ChangeLog(*change_log_event),
+            &ctx.accounts.noop,
+        )
+    }
+
+    /// This instruction allows the tree's `authority` to append several leaves
+    /// to the tree without having to supply a proof.
+    /// The leaves are appended as the largest aligned subtrees fitting below
+    /// the canopy of the tree, and a single `ChangeLogEvent` is emitted with
+    /// the path of the last leaf of every subtree.
+    pub fn append_batch(ctx: Context<Modify>, leaves: Vec<[u8; 32]>) -> Result<()> {
+        require_eq!(
+            *ctx.accounts.merkle_tree.owner,
+            crate::id(),
+            AccountCompressionError::IncorrectAccountOwner
+        );
+        let mut merkle_tree_bytes = ctx.accounts.merkle_tree.try_borrow_mut_data()?;
+        let (header_bytes, rest) =
+            merkle_tree_bytes.split_at_mut(CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1);
+
+        let header = ConcurrentMerkleTreeHeader::try_from_slice(header_bytes)?;
+        header.assert_valid_authority(&ctx.accounts.authority.key())?;
+        header.assert_not_read_only()?;
+        header.assert_not_indexed()?;
+
+        let merkle_tree_size = merkle_tree_get_size(&header)?;
+        let (tree_bytes, canopy_bytes) = rest.split_at_mut(merkle_tree_size);
+
+        let canopy_depth = get_canopy_depth(canopy_bytes, header.get_max_depth())?;
+        let id = ctx.accounts.merkle_tree.key();
+        // A call is made to ConcurrentMerkleTree::append_batch(leaves, max_subtree_height)
+        let args = &AppendBatchArgs {
+            leaves,
+            max_subtree_height: header.get_max_depth() - canopy_depth,
+        };
+        let change_log_event = merkle_tree_append_batch(&header, id, tree_bytes, args)?;
+
+        update_canopy(
+            canopy_bytes,
+            header.get_max_depth(),
+            Some(&change_log_event),
+        )?;
+        wrap_event(
+            &AccountCompressionEvent::ChangeLog(*change_log_event),
+            &ctx.accounts.noop,
+        )
+    }
+
+    /// Inserts `value` in an indexed tree created by `init_indexed_merkle_tree`.
+    /// `low_leaf` is the leaf at `low_leaf_index` holding the largest value of the tree
+    /// smaller than `value`, and its proof is passed via remaining accounts.
+    /// The low leaf is updated to point to the value, then the leaf of the value is
+    /// appended, pointing to the former next leaf of the low leaf.
+    /// A single `ChangeLogEvent` is emitted with the paths of both leaves.
+    pub fn insert_indexed_leaf(
+        ctx: Context<Modify>,
+        root: [u8; 32],
+        value: [u8; 32],
+        low_leaf: IndexedLeaf,
+        low_leaf_index: u32,
+    ) -> Result<()> {
+        require_eq!(
+            *ctx.accounts.merkle_tree.owner,
+            crate::id(),
+            AccountCompressionError::IncorrectAccountOwner
+        );
+        let mut merkle_tree_bytes = ctx.accounts.merkle_tree.try_borrow_mut_data()?;
+        let (header_bytes, rest) =
+            merkle_tree_bytes.split_at_mut(CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1);
+
+        let header = ConcurrentMerkleTreeHeader::try_from_slice(header_bytes)?;
+        header.assert_valid_authority(&ctx.accounts.authority.key())?;
+        header.assert_not_read_only()?;
+        header.assert_is_indexed()?;
+        header.assert_valid_leaf_index(low_leaf_index)?;
+
+        let merkle_tree_size = merkle_tree_get_size(&header)?;
+        let (tree_bytes, canopy_bytes) = rest.split_at_mut(merkle_tree_size);
+
+        let mut proof = vec![];
+        for node in ctx.remaining_accounts.iter() {
+            proof.push(node.key().to_bytes());
+        }
+        fill_in_proof_from_canopy(
+            canopy_bytes,
+            header.get_max_depth(),
+            low_leaf_index,
+            &mut proof,
+            header.get_hash_type(),
+        )?;
+        let id = ctx.accounts.merkle_tree.key();
+        // A call is made to ConcurrentMerkleTree::insert_indexed_leaf(root, value, low_leaf, low_leaf_index, proof)
+        let args = &InsertIndexedLeafArgs {
+            current_root: root,
+            value,
+            low_leaf: low_leaf.into(),
+            low_leaf_index,
+            proof_vec: proof,
+        };
+        let change_log_event = merkle_tree_insert_indexed_leaf(&header, id, tree_bytes, args)?;
+
+        update_canopy(
+            canopy_bytes,
+            header.get_max_depth(),
+            Some(&change_log_event),
+        )?;
+        wrap_event(
+            &AccountCompressionEvent::ChangeLog(*change_log_event),
+            &ctx.accounts.noop,
+        )
+    }
+
+    /// Migrates a tree into a new, deeper tree of depth `max_depth` whose
+    /// leftmost subtree is the tree, once it is full or about to be.
+    ///
+    /// The new tree is initialized from the current root of the tree, checked
+    /// with the proof of `rightmost_leaf`, the last leaf appended to the tree,
+    /// passed via remaining accounts like for `replace_leaf`. Every leaf keeps
+    /// its index, and its proof in the new tree is its proof in the tree
+    /// followed by empty nodes. The canopy of the new tree is copied from the
+    /// canopy of the tree, so it can't cache nodes below it.
+    ///
+    /// The tree is marked read-only, so that its leaves can only be modified
+    /// in the new tree.
+    pub fn migrate_tree(
+        ctx: Context<MigrateTree>,
+        root: [u8; 32],
+        rightmost_leaf: [u8; 32],
+        max_depth: u32,
+        max_buffer_size: u32,
+    ) -> Result<()> {
+        require_eq!(
+            *ctx.accounts.merkle_tree.owner,
+            crate::id(),
+            AccountCompressionError::IncorrectAccountOwner
+        );
+        require_eq!(
+            *ctx.accounts.new_merkle_tree.owner,
+            crate::id(),
+            AccountCompressionError::IncorrectAccountOwner
+        );
+        let mut merkle_tree_bytes = ctx.accounts.merkle_tree.try_borrow_mut_data()?;
+        let (mut header_bytes, rest) =
+            merkle_tree_bytes.split_at_mut(CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1);
+
+        let mut header = ConcurrentMerkleTreeHeader::try_from_slice(header_bytes)?;
+        header.assert_valid_authority(&ctx.accounts.authority.key())?;
+        header.assert_not_read_only()?;
+        header.assert_not_indexed()?;
+
+        let merkle_tree_size = merkle_tree_get_size(&header)?;
+        let (tree_bytes, canopy_bytes) = rest.split_at_mut(merkle_tree_size);
+
+        let mut proof = vec![];
+        for node in ctx.remaining_accounts.iter() {
+            proof.push(node.key().to_bytes());
+        }
+        // Trees without leaves are rejected when getting the migration args
+        let rightmost_index = merkle_tree_get_rightmost_index(&header, tree_bytes)?;
+        fill_in_proof_from_canopy(
+            canopy_bytes,
+            header.get_max_depth(),
+            rightmost_index.saturating_sub(1),
+            &mut proof,
+            header.get_hash_type(),
+        )?;
+        // A call is made to ConcurrentMerkleTree::get_migration_args(root, rightmost_leaf, proof, max_depth)
+        let args = &MigrateArgs {
+            current_root: root,
+            rightmost_leaf,
+            proof_vec: proof,
+            max_depth,
+        };
+        let migration_args = merkle_tree_get_migration_args(&header, tree_bytes, args)?;
+        header.set_read_only();
+        header.serialize(&mut header_bytes)?;
+
+        let mut new_merkle_tree_bytes = ctx.accounts.new_merkle_tree.try_borrow_mut_data()?;
+        let (mut new_header_bytes, new_rest) =
+            new_merkle_tree_bytes.split_at_mut(CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1);
+
+        let mut new_header = ConcurrentMerkleTreeHeader::try_from_slice(new_header_bytes)?;
+        new_header.initialize(
+            max_depth,
+            max_buffer_size,
+            &ctx.accounts.authority.key(),
+            Clock::get()?.slot,
+            header.get_hash_type(),
+        );
+        new_header.serialize(&mut new_header_bytes)?;
+
+        let new_merkle_tree_size = merkle_tree_get_size(&new_header)?;
+        let (new_tree_bytes, new_canopy_bytes) = new_rest.split_at_mut(new_merkle_tree_size);
+        let new_id = ctx.accounts.new_merkle_tree.key();
+        let change_log_event =
+            merkle_tree_initialize_with_root(&new_header, new_id, new_tree_bytes, &migration_args)?;
+
+        copy_subtree_canopy(
+            new_canopy_bytes,
+            max_depth,
+            canopy_bytes,
+            header.get_max_depth(),
+        )?;
+        update_canopy(new_canopy_bytes, max_depth, Some(&change_log_event))?;
+        wrap_event(
+            &AccountCompressionEvent::ChangeLog(*change_log_event),
+            &ctx.accounts.noop,
+        )
+    }
+
     pub fn close_empty_tree(ctx: Context<CloseTree>) -> Result<()> {
         require_eq!(
             *ctx.accounts.merkle_tree.owner,
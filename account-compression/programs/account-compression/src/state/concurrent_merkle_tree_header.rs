@@ -1,8 +1,11 @@
 use anchor_lang::prelude::*;
 use borsh::{BorshDeserialize, BorshSerialize};
 
-use spl_concurrent_merkle_tree::concurrent_merkle_tree::ConcurrentMerkleTree;
-use std::mem::size_of;
+use spl_concurrent_merkle_tree::{
+    concurrent_merkle_tree_view::ConcurrentMerkleTreeView,
+    hash::{Hasher, Keccak, Poseidon, Sha256},
+    node::{empty_node_cached_mut_with_hasher, empty_node_cached_with_hasher, Node},
+};
 
 use crate::error::AccountCompressionError;
 
@@ -27,17 +30,63 @@ impl std::fmt::Display for CompressionAccountType {
 #[cfg(feature = "idl-build")]
 impl anchor_lang::IdlBuild for CompressionAccountType {}
 
+/// Hash function used to compute the nodes of an SPL ConcurrentMerkleTree
+#[derive(Debug, Copy, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+#[repr(u8)]
+pub enum HashType {
+    /// Keccak-256, used by all trees created before the hash type was recorded
+    Keccak,
+
+    /// SHA-256, for trees whose roots are verified by EVM contracts
+    Sha256,
+
+    /// Poseidon over BN254, for trees whose roots are verified in ZK circuits
+    /// Leaves must be BN254 field elements
+    Poseidon,
+}
+
+impl std::fmt::Display for HashType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", &self)
+    }
+}
+
+#[cfg(feature = "idl-build")]
+impl anchor_lang::IdlBuild for HashType {}
+
+impl HashType {
+    /// Computes the parent node of `left` and `right`
+    pub fn hash_pair(&self, left: &Node, right: &Node) -> Node {
+        match self {
+            HashType::Keccak => Keccak::hash_pair(left, right),
+            HashType::Sha256 => Sha256::hash_pair(left, right),
+            HashType::Poseidon => Poseidon::hash_pair(left, right),
+        }
+    }
+
+    /// Calculates the hash of empty nodes up to `level` using an existing cache
+    pub fn empty_node_cached<const N: usize>(&self, level: u32, cache: &[Node; N]) -> Node {
+        match self {
+            HashType::Keccak => empty_node_cached_with_hasher::<Keccak, N>(level, cache),
+            HashType::Sha256 => empty_node_cached_with_hasher::<Sha256, N>(level, cache),
+            HashType::Poseidon => empty_node_cached_with_hasher::<Poseidon, N>(level, cache),
+        }
+    }
+
+    /// Calculates and caches the hash of empty nodes up to `level`
+    pub fn empty_node_cached_mut<const N: usize>(&self, level: u32, cache: &mut [Node; N]) -> Node {
+        match self {
+            HashType::Keccak => empty_node_cached_mut_with_hasher::<Keccak, N>(level, cache),
+            HashType::Sha256 => empty_node_cached_mut_with_hasher::<Sha256, N>(level, cache),
+            HashType::Poseidon => empty_node_cached_mut_with_hasher::<Poseidon, N>(level, cache),
+        }
+    }
+}
+
 /// Initialization parameters for an SPL ConcurrentMerkleTree.
 ///
-/// Only the following permutations are valid:
-///
-/// | max_depth | max_buffer_size       |
-/// | --------- | --------------------- |
-/// | 14        | (64, 256, 1024, 2048) |           
-/// | 20        | (64, 256, 1024, 2048) |           
-/// | 24        | (64, 256, 512, 1024, 2048) |           
-/// | 26        | (64, 256, 512, 1024, 2048) |           
-/// | 30        | (512, 1024, 2048) |           
+/// Any `max_depth` from 1 to 30 is valid, with any `max_buffer_size` that is
+/// a power of 2.
 ///
 #[repr(C)]
 #[derive(AnchorDeserialize, AnchorSerialize)]
@@ -52,12 +101,12 @@ pub struct ConcurrentMerkleTreeHeader {
 #[derive(AnchorDeserialize, AnchorSerialize)]
 pub struct ConcurrentMerkleTreeHeaderDataV1 {
     /// Buffer of changelogs stored on-chain.
-    /// Must be a power of 2; see above table for valid combinations.
+    /// Must be a power of 2.
     max_buffer_size: u32,
 
     /// Depth of the SPL ConcurrentMerkleTree to store.
     /// Tree capacity can be calculated as power(2, max_depth).
-    /// See above table for valid options.
+    /// Must be at most 30.
     max_depth: u32,
 
     /// Authority that validates the content of the trees.
@@ -73,9 +122,24 @@ pub struct ConcurrentMerkleTreeHeaderDataV1 {
     /// It takes 1 byte of space taken from the previous padding for existing accounts.
     is_batch_initialized: bool,
 
+    /// Hash function used to compute the nodes of the tree.
+    /// It takes 1 byte of space taken from the previous padding for existing accounts,
+    /// which are zeroed and therefore read as `HashType::Keccak`.
+    hash_type: HashType,
+
+    /// A flag indicating whether the tree was migrated into a deeper tree,
+    /// after which it can no longer be modified.
+    /// It takes 1 byte of space taken from the previous padding for existing accounts.
+    is_read_only: bool,
+
+    /// A flag indicating whether the tree is an indexed tree, whose leaves form a sorted
+    /// linked list and can only be added with `insert_indexed_leaf`.
+    /// It takes 1 byte of space taken from the previous padding for existing accounts.
+    is_indexed: bool,
+
     /// Needs padding for the account to be 8-byte aligned
     /// 8-byte alignment is necessary to zero-copy the SPL ConcurrentMerkleTree
-    _padding: [u8; 5],
+    _padding: [u8; 2],
 }
 
 #[repr(C)]
@@ -91,6 +155,7 @@ impl ConcurrentMerkleTreeHeader {
         max_buffer_size: u32,
         authority: &Pubkey,
         creation_slot: u64,
+        hash_type: HashType,
     ) {
         self.account_type = CompressionAccountType::ConcurrentMerkleTree;
 
@@ -103,6 +168,7 @@ impl ConcurrentMerkleTreeHeader {
                 header.max_depth = max_depth;
                 header.authority = *authority;
                 header.creation_slot = creation_slot;
+                header.hash_type = hash_type;
                 // is_batch_initialized is left false by default
             }
         }
@@ -116,8 +182,15 @@ impl ConcurrentMerkleTreeHeader {
         max_buffer_size: u32,
         authority: &Pubkey,
         creation_slot: u64,
+        hash_type: HashType,
     ) {
-        self.initialize(max_depth, max_buffer_size, authority, creation_slot);
+        self.initialize(
+            max_depth,
+            max_buffer_size,
+            authority,
+            creation_slot,
+            hash_type,
+        );
         match self.header {
             ConcurrentMerkleTreeHeaderData::V1(ref mut header) => {
                 header.is_batch_initialized = true;
@@ -125,6 +198,29 @@ impl ConcurrentMerkleTreeHeader {
         }
     }
 
+    /// Initializes the header with the given parameters and sets the `is_indexed` flag to true.
+    pub fn initialize_indexed(
+        &mut self,
+        max_depth: u32,
+        max_buffer_size: u32,
+        authority: &Pubkey,
+        creation_slot: u64,
+        hash_type: HashType,
+    ) {
+        self.initialize(
+            max_depth,
+            max_buffer_size,
+            authority,
+            creation_slot,
+            hash_type,
+        );
+        match self.header {
+            ConcurrentMerkleTreeHeaderData::V1(ref mut header) => {
+                header.is_indexed = true;
+            }
+        }
+    }
+
     pub fn get_max_depth(&self) -> u32 {
         match &self.header {
             ConcurrentMerkleTreeHeaderData::V1(header) => header.max_depth,
@@ -149,6 +245,33 @@ impl ConcurrentMerkleTreeHeader {
         }
     }
 
+    pub fn get_hash_type(&self) -> HashType {
+        match &self.header {
+            ConcurrentMerkleTreeHeaderData::V1(header) => header.hash_type,
+        }
+    }
+
+    pub fn get_is_read_only(&self) -> bool {
+        match &self.header {
+            ConcurrentMerkleTreeHeaderData::V1(header) => header.is_read_only,
+        }
+    }
+
+    pub fn get_is_indexed(&self) -> bool {
+        match &self.header {
+            ConcurrentMerkleTreeHeaderData::V1(header) => header.is_indexed,
+        }
+    }
+
+    /// Marks the tree as read-only once it was migrated into a deeper tree
+    pub fn set_read_only(&mut self) {
+        match self.header {
+            ConcurrentMerkleTreeHeaderData::V1(ref mut header) => {
+                header.is_read_only = true;
+            }
+        }
+    }
+
     pub fn set_new_authority(&mut self, new_authority: &Pubkey) {
         match self.header {
             ConcurrentMerkleTreeHeaderData::V1(ref mut header) => {
@@ -188,6 +311,32 @@ impl ConcurrentMerkleTreeHeader {
         Ok(())
     }
 
+    pub fn assert_not_read_only(&self) -> Result<()> {
+        require!(
+            !self.get_is_read_only(),
+            AccountCompressionError::TreeReadOnly
+        );
+        Ok(())
+    }
+
+    pub fn assert_is_indexed(&self) -> Result<()> {
+        require!(
+            self.get_is_indexed(),
+            AccountCompressionError::TreeNotIndexed
+        );
+        Ok(())
+    }
+
+    /// Leaves of an indexed tree can only be added with `insert_indexed_leaf`, which keeps
+    /// the linked list sorted
+    pub fn assert_not_indexed(&self) -> Result<()> {
+        require!(
+            !self.get_is_indexed(),
+            AccountCompressionError::IndexedTreeLeafModification
+        );
+        Ok(())
+    }
+
     pub fn assert_is_batch_initialized(&self) -> Result<()> {
         match &self.header {
             ConcurrentMerkleTreeHeaderData::V1(header) => {
@@ -203,48 +352,13 @@ impl ConcurrentMerkleTreeHeader {
 
 pub fn merkle_tree_get_size(header: &ConcurrentMerkleTreeHeader) -> Result<usize> {
     // Note: max_buffer_size MUST be a power of 2
-    match (header.get_max_depth(), header.get_max_buffer_size()) {
-        (3, 8) => Ok(size_of::<ConcurrentMerkleTree<3, 8>>()),
-        (5, 8) => Ok(size_of::<ConcurrentMerkleTree<5, 8>>()),
-        (6, 16) => Ok(size_of::<ConcurrentMerkleTree<6, 16>>()),
-        (7, 16) => Ok(size_of::<ConcurrentMerkleTree<7, 16>>()),
-        (8, 16) => Ok(size_of::<ConcurrentMerkleTree<8, 16>>()),
-        (9, 16) => Ok(size_of::<ConcurrentMerkleTree<9, 16>>()),
-        (10, 32) => Ok(size_of::<ConcurrentMerkleTree<10, 32>>()),
-        (11, 32) => Ok(size_of::<ConcurrentMerkleTree<11, 32>>()),
-        (12, 32) => Ok(size_of::<ConcurrentMerkleTree<12, 32>>()),
-        (13, 32) => Ok(size_of::<ConcurrentMerkleTree<13, 32>>()),
-        (14, 64) => Ok(size_of::<ConcurrentMerkleTree<14, 64>>()),
-        (14, 256) => Ok(size_of::<ConcurrentMerkleTree<14, 256>>()),
-        (14, 1024) => Ok(size_of::<ConcurrentMerkleTree<14, 1024>>()),
-        (14, 2048) => Ok(size_of::<ConcurrentMerkleTree<14, 2048>>()),
-        (15, 64) => Ok(size_of::<ConcurrentMerkleTree<15, 64>>()),
-        (16, 64) => Ok(size_of::<ConcurrentMerkleTree<16, 64>>()),
-        (17, 64) => Ok(size_of::<ConcurrentMerkleTree<17, 64>>()),
-        (18, 64) => Ok(size_of::<ConcurrentMerkleTree<18, 64>>()),
-        (19, 64) => Ok(size_of::<ConcurrentMerkleTree<19, 64>>()),
-        (20, 64) => Ok(size_of::<ConcurrentMerkleTree<20, 64>>()),
-        (20, 256) => Ok(size_of::<ConcurrentMerkleTree<20, 256>>()),
-        (20, 1024) => Ok(size_of::<ConcurrentMerkleTree<20, 1024>>()),
-        (20, 2048) => Ok(size_of::<ConcurrentMerkleTree<20, 2048>>()),
-        (24, 64) => Ok(size_of::<ConcurrentMerkleTree<24, 64>>()),
-        (24, 256) => Ok(size_of::<ConcurrentMerkleTree<24, 256>>()),
-        (24, 512) => Ok(size_of::<ConcurrentMerkleTree<24, 512>>()),
-        (24, 1024) => Ok(size_of::<ConcurrentMerkleTree<24, 1024>>()),
-        (24, 2048) => Ok(size_of::<ConcurrentMerkleTree<24, 2048>>()),
-        (26, 512) => Ok(size_of::<ConcurrentMerkleTree<26, 512>>()),
-        (26, 1024) => Ok(size_of::<ConcurrentMerkleTree<26, 1024>>()),
-        (26, 2048) => Ok(size_of::<ConcurrentMerkleTree<26, 2048>>()),
-        (30, 512) => Ok(size_of::<ConcurrentMerkleTree<30, 512>>()),
-        (30, 1024) => Ok(size_of::<ConcurrentMerkleTree<30, 1024>>()),
-        (30, 2048) => Ok(size_of::<ConcurrentMerkleTree<30, 2048>>()),
-        _ => {
+    ConcurrentMerkleTreeView::<&[u8]>::size(header.get_max_depth(), header.get_max_buffer_size())
+        .map_err(|_| {
             msg!(
                 "Failed to get size of max depth {} and max buffer size {}",
                 header.get_max_depth(),
                 header.get_max_buffer_size()
             );
-            err!(AccountCompressionError::ConcurrentMerkleTreeConstantsError)
-        }
-    }
+            error!(AccountCompressionError::ConcurrentMerkleTreeConstantsError)
+        })
 }
@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+use spl_concurrent_merkle_tree::indexed_leaf;
+
+/// Leaf of an indexed tree, pointing to the leaf holding the next larger value
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug)]
+pub struct IndexedLeaf {
+    pub value: [u8; 32],
+    pub next_index: u32,
+    pub next_value: [u8; 32],
+}
+
+impl From<IndexedLeaf> for indexed_leaf::IndexedLeaf {
+    fn from(leaf: IndexedLeaf) -> Self {
+        Self {
+            value: leaf.value,
+            next_index: leaf.next_index,
+            next_value: leaf.next_value,
+        }
+    }
+}
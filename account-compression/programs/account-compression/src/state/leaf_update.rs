@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+use spl_concurrent_merkle_tree::concurrent_merkle_tree;
+
+/// Replacement of the leaf at `index`, used to modify several leaves at once
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug)]
+pub struct LeafUpdate {
+    pub index: u32,
+    pub previous_leaf: [u8; 32],
+    pub new_leaf: [u8; 32],
+}
+
+impl From<LeafUpdate> for concurrent_merkle_tree::LeafUpdate {
+    fn from(leaf: LeafUpdate) -> Self {
+        Self {
+            index: leaf.index,
+            previous_leaf: leaf.previous_leaf,
+            new_leaf: leaf.new_leaf,
+        }
+    }
+}
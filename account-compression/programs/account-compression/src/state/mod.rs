@@ -1,6 +1,10 @@
 //! State needed to manipulate SPL ConcurrentMerkleTrees
 mod concurrent_merkle_tree_header;
+mod indexed_leaf;
+mod leaf_update;
 mod path_node;
 
 pub use concurrent_merkle_tree_header::*;
+pub use indexed_leaf::IndexedLeaf;
+pub use leaf_update::LeafUpdate;
 pub use path_node::PathNode;
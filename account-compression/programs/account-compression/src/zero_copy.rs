@@ -2,7 +2,7 @@
 use crate::error::error_msg;
 use anchor_lang::prelude::*;
 use bytemuck::Pod;
-use spl_concurrent_merkle_tree::concurrent_merkle_tree::ConcurrentMerkleTree;
+use spl_concurrent_merkle_tree::{concurrent_merkle_tree::ConcurrentMerkleTree, hash::Hasher};
 use std::mem::size_of;
 
 pub trait ZeroCopy: Pod {
@@ -25,7 +25,7 @@ pub trait ZeroCopy: Pod {
     }
 }
 
-impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize> ZeroCopy
-    for ConcurrentMerkleTree<MAX_DEPTH, MAX_BUFFER_SIZE>
+impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize, H: Hasher> ZeroCopy
+    for ConcurrentMerkleTree<MAX_DEPTH, MAX_BUFFER_SIZE, H>
 {
 }
@@ -1,7 +1,7 @@
 import type { Commitment, Connection, GetAccountInfoConfig, PublicKey } from '@solana/web3.js';
 import { BN } from 'bn.js';
 
-import { ConcurrentMerkleTreeHeaderDataV1, concurrentMerkleTreeHeaderDataV1Beet } from '../generated';
+import { ConcurrentMerkleTreeHeaderDataV1, concurrentMerkleTreeHeaderDataV1Beet, HashType } from '../generated';
 import {
     ConcurrentMerkleTreeHeader,
     concurrentMerkleTreeHeaderBeet,
@@ -129,6 +129,32 @@ export class ConcurrentMerkleTreeAccount {
     getIsBatchInitialized(): boolean {
         return this.getHeaderV1().isBatchInitialized;
     }
+
+    /**
+     * Returns the flag that indicates if the tree was migrated into a deeper tree
+     * and can no longer be modified
+     * @returns the flag
+     */
+    getIsReadOnly(): boolean {
+        return this.getHeaderV1().isReadOnly;
+    }
+
+    /**
+     * Returns the flag that indicates if this is an indexed tree, whose leaves
+     * can only be added with `insert_indexed_leaf`
+     * @returns the flag
+     */
+    getIsIndexed(): boolean {
+        return this.getHeaderV1().isIndexed;
+    }
+
+    /**
+     * Returns the hash function used to compute the nodes of this tree
+     * @returns the hash type
+     */
+    getHashType(): HashType {
+        return this.getHeaderV1().hashType;
+    }
 }
 
 /**
@@ -4,9 +4,9 @@ export const SPL_NOOP_ADDRESS = 'noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV';
 export const SPL_NOOP_PROGRAM_ID = new PublicKey(SPL_NOOP_ADDRESS);
 
 /**
- * DepthSizePair is a valid (`maxDepth`, `maxBufferSize`) tuple for an SPL ConcurrentMerkleTree
- * Only the tuples listed in {@link ALL_DEPTH_SIZE_PAIRS} are valid for
- * creating a new {@link ConcurrentMerkleTreeAccount}.
+ * DepthSizePair is a (`maxDepth`, `maxBufferSize`) tuple for an SPL ConcurrentMerkleTree
+ * The program accepts any `maxDepth` up to 30 with any power of 2 `maxBufferSize`,
+ * the tuples listed in {@link ALL_DEPTH_SIZE_PAIRS} are the commonly used ones.
  */
 export type DepthSizePair = {
     maxBufferSize: number;
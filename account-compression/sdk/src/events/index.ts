@@ -25,6 +25,38 @@ export function deserializeChangeLogEventV1(data: Buffer): ChangeLogEventV1 {
     }
 }
 
+/**
+ * Helper method for indexing a {@link ConcurrentMerkleTree}, which returns the
+ * path of every leaf modified by either a V1 or a V2 ChangeLogEvent, in the
+ * order they were applied
+ * @param data
+ * @returns
+ */
+export function deserializeChangeLogEvents(data: Buffer): ChangeLogEventV1[] {
+    const event = accountCompressionEventBeet.toFixedFromData(data, 0).read(data, 0);
+
+    if (event.__kind == 'ChangeLog') {
+        const changeLog = event.fields[0];
+        switch (changeLog.__kind) {
+            case 'V1': {
+                return [deserializeChangeLogEventV1(data)];
+            }
+            case 'V2': {
+                const changeLogV2 = changeLog.fields[0];
+                return changeLogV2.paths.map(path => {
+                    return {
+                        index: path.index,
+                        path: path.path,
+                        seq: new BN.BN(path.seq),
+                        treeId: changeLogV2.id,
+                    };
+                });
+            }
+        }
+    }
+    throw Error('Unable to decode buffer as ChangeLogEvent');
+}
+
 /**
  * Helper function for indexing data logged via `wrap_application_data_v1`
  * @param data
@@ -294,6 +294,86 @@ export class CanopyRightmostLeafMismatchError extends Error {
 createErrorFromCodeLookup.set(0x177d, () => new CanopyRightmostLeafMismatchError());
 createErrorFromNameLookup.set('CanopyRightmostLeafMismatch', () => new CanopyRightmostLeafMismatchError());
 
+/**
+ * SubtreeTooHigh: 'Subtree is too high to be appended below the canopy of the tree'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class SubtreeTooHighError extends Error {
+    readonly code: number = 0x177e;
+    readonly name: string = 'SubtreeTooHigh';
+    constructor() {
+        super('Subtree is too high to be appended below the canopy of the tree');
+        if (typeof Error.captureStackTrace === 'function') {
+            Error.captureStackTrace(this, SubtreeTooHighError);
+        }
+    }
+}
+
+createErrorFromCodeLookup.set(0x177e, () => new SubtreeTooHighError());
+createErrorFromNameLookup.set('SubtreeTooHigh', () => new SubtreeTooHighError());
+
+/**
+ * TreeReadOnly: 'Tree was migrated into a deeper tree and is read-only'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class TreeReadOnlyError extends Error {
+    readonly code: number = 0x177f;
+    readonly name: string = 'TreeReadOnly';
+    constructor() {
+        super('Tree was migrated into a deeper tree and is read-only');
+        if (typeof Error.captureStackTrace === 'function') {
+            Error.captureStackTrace(this, TreeReadOnlyError);
+        }
+    }
+}
+
+createErrorFromCodeLookup.set(0x177f, () => new TreeReadOnlyError());
+createErrorFromNameLookup.set('TreeReadOnly', () => new TreeReadOnlyError());
+
+/**
+ * TreeNotIndexed: 'Tree is not an indexed tree'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class TreeNotIndexedError extends Error {
+    readonly code: number = 0x1780;
+    readonly name: string = 'TreeNotIndexed';
+    constructor() {
+        super('Tree is not an indexed tree');
+        if (typeof Error.captureStackTrace === 'function') {
+            Error.captureStackTrace(this, TreeNotIndexedError);
+        }
+    }
+}
+
+createErrorFromCodeLookup.set(0x1780, () => new TreeNotIndexedError());
+createErrorFromNameLookup.set('TreeNotIndexed', () => new TreeNotIndexedError());
+
+/**
+ * IndexedTreeLeafModification: 'Leaves of an indexed tree can only be added with insert_indexed_leaf'
+ *
+ * @category Errors
+ * @category generated
+ */
+export class IndexedTreeLeafModificationError extends Error {
+    readonly code: number = 0x1781;
+    readonly name: string = 'IndexedTreeLeafModification';
+    constructor() {
+        super('Leaves of an indexed tree can only be added with insert_indexed_leaf');
+        if (typeof Error.captureStackTrace === 'function') {
+            Error.captureStackTrace(this, IndexedTreeLeafModificationError);
+        }
+    }
+}
+
+createErrorFromCodeLookup.set(0x1781, () => new IndexedTreeLeafModificationError());
+createErrorFromNameLookup.set('IndexedTreeLeafModification', () => new IndexedTreeLeafModificationError());
+
 /**
  * Attempts to resolve a custom program error from the provided error code.
  * @category Errors
@@ -1,10 +1,19 @@
 export * from './append';
+export * from './appendBatch';
 export * from './appendCanopyNodes';
+export * from './appendSubtree';
 export * from './closeEmptyTree';
 export * from './initEmptyMerkleTree';
+export * from './initEmptyMerkleTreeWithHashType';
+export * from './initIndexedMerkleTree';
 export * from './initPreparedTreeWithRoot';
+export * from './insertIndexedLeaf';
 export * from './insertOrAppend';
+export * from './migrateTree';
 export * from './prepareBatchMerkleTree';
+export * from './prepareBatchMerkleTreeWithHashType';
 export * from './replaceLeaf';
+export * from './replaceLeaves';
 export * from './transferAuthority';
 export * from './verifyLeaf';
+export * from './verifyNonMembership';
@@ -8,13 +8,18 @@
 import * as beet from '@metaplex-foundation/beet';
 import * as beetSolana from '@metaplex-foundation/beet-solana';
 import * as web3 from '@solana/web3.js';
+
+import { HashType, hashTypeBeet } from './HashType';
 export type ConcurrentMerkleTreeHeaderDataV1 = {
     authority: web3.PublicKey;
     creationSlot: beet.bignum;
+    hashType: HashType;
     isBatchInitialized: boolean;
+    isIndexed: boolean;
+    isReadOnly: boolean;
     maxBufferSize: number;
     maxDepth: number;
-    padding: number[] /* size: 5 */;
+    padding: number[] /* size: 2 */;
 };
 
 /**
@@ -28,7 +33,10 @@ export const concurrentMerkleTreeHeaderDataV1Beet = new beet.BeetArgsStruct<Conc
         ['authority', beetSolana.publicKey],
         ['creationSlot', beet.u64],
         ['isBatchInitialized', beet.bool],
-        ['padding', beet.uniformFixedSizeArray(beet.u8, 5)],
+        ['hashType', hashTypeBeet],
+        ['isReadOnly', beet.bool],
+        ['isIndexed', beet.bool],
+        ['padding', beet.uniformFixedSizeArray(beet.u8, 2)],
     ],
     'ConcurrentMerkleTreeHeaderDataV1',
 );
@@ -3,8 +3,13 @@ export * from './ApplicationDataEvent';
 export * from './ApplicationDataEventV1';
 export * from './ChangeLogEvent';
 export * from './ChangeLogEventV1';
+export * from './ChangeLogEventV2';
+export * from './ChangeLogPath';
 export * from './CompressionAccountType';
 export * from './ConcurrentMerkleTreeHeader';
 export * from './ConcurrentMerkleTreeHeaderData';
 export * from './ConcurrentMerkleTreeHeaderDataV1';
+export * from './HashType';
+export * from './IndexedLeaf';
+export * from './LeafUpdate';
 export * from './PathNode';
@@ -3,18 +3,28 @@ import { Connection, PublicKey, SystemProgram, TransactionInstruction } from '@s
 import { getConcurrentMerkleTreeAccountSize } from '../accounts';
 import { SPL_NOOP_PROGRAM_ID, ValidDepthSizePair } from '../constants';
 import {
+    createAppendBatchInstruction,
     createAppendCanopyNodesInstruction,
     createAppendInstruction,
+    createAppendSubtreeInstruction,
     createCloseEmptyTreeInstruction,
     createInitEmptyMerkleTreeInstruction,
+    createInitIndexedMerkleTreeInstruction,
     createInitPreparedTreeWithRootInstruction,
+    createInsertIndexedLeafInstruction,
+    createMigrateTreeInstruction,
     createPrepareBatchMerkleTreeInstruction,
+    createPrepareBatchMerkleTreeWithHashTypeInstruction,
     createReplaceLeafInstruction,
+    createReplaceLeavesInstruction,
     createTransferAuthorityInstruction,
     createVerifyLeafInstruction,
+    createVerifyNonMembershipInstruction,
+    HashType,
+    IndexedLeaf,
     PROGRAM_ID,
 } from '../generated';
-import { MerkleTreeProof } from '../merkle-tree';
+import { MerkleTreeMultiProof, MerkleTreeProof } from '../merkle-tree';
 
 /**
  * Helper function that adds proof nodes to a TransactionInstruction
@@ -57,27 +67,58 @@ export function createInitEmptyMerkleTreeIx(
 }
 
 /**
- * (Devnet only) Helper function for {@link createPrepareBatchMerkleTreeInstruction}
+ * Helper function for {@link createInitIndexedMerkleTreeInstruction}
+ *
+ * The tree starts with the default leaf, of value 0, at index 0, whose node is
+ * `hashIndexedLeaf` of a leaf with zeroed fields.
  * @param merkleTree
  * @param authority
  * @param depthSizePair
+ * @param hashType
  * @returns
  */
-export function prepareTreeIx(
+export function createInitIndexedMerkleTreeIx(
     merkleTree: PublicKey,
     authority: PublicKey,
     depthSizePair: ValidDepthSizePair,
+    hashType: HashType = HashType.Keccak,
 ): TransactionInstruction {
-    return createPrepareBatchMerkleTreeInstruction(
+    return createInitIndexedMerkleTreeInstruction(
         {
             authority: authority,
             merkleTree,
             noop: SPL_NOOP_PROGRAM_ID,
         },
-        depthSizePair,
+        { ...depthSizePair, hashType },
     );
 }
 
+/**
+ * (Devnet only) Helper function for {@link createPrepareBatchMerkleTreeInstruction}, or
+ * {@link createPrepareBatchMerkleTreeWithHashTypeInstruction} for trees not hashed with Keccak
+ * @param merkleTree
+ * @param authority
+ * @param depthSizePair
+ * @param hashType
+ * @returns
+ */
+export function prepareTreeIx(
+    merkleTree: PublicKey,
+    authority: PublicKey,
+    depthSizePair: ValidDepthSizePair,
+    hashType: HashType = HashType.Keccak,
+): TransactionInstruction {
+    const accounts = {
+        authority: authority,
+        merkleTree,
+        noop: SPL_NOOP_PROGRAM_ID,
+    };
+    if (hashType === HashType.Keccak) {
+        return createPrepareBatchMerkleTreeInstruction(accounts, depthSizePair);
+    }
+    return createPrepareBatchMerkleTreeWithHashTypeInstruction(accounts, { ...depthSizePair, hashType });
+}
+
 /**
  * (Devnet only) Helper function for {@link createAppendCanopyNodesInstruction}
  * @param merkleTree
@@ -176,6 +217,42 @@ export function createReplaceIx(
     );
 }
 
+/**
+ * Helper function for {@link createReplaceLeavesInstruction}
+ * @param merkleTree
+ * @param authority
+ * @param newLeaves new leaves, in the order of `proof.leafIndices`
+ * @param proof
+ * @returns
+ */
+export function createReplaceLeavesIx(
+    merkleTree: PublicKey,
+    authority: PublicKey,
+    newLeaves: Buffer[],
+    proof: MerkleTreeMultiProof,
+): TransactionInstruction {
+    return addProof(
+        createReplaceLeavesInstruction(
+            {
+                authority: authority,
+                merkleTree,
+                noop: SPL_NOOP_PROGRAM_ID,
+            },
+            {
+                leaves: proof.leafIndices.map((index, i) => {
+                    return {
+                        index,
+                        newLeaf: Array.from(newLeaves[i]),
+                        previousLeaf: Array.from(proof.leaves[i]),
+                    };
+                }),
+                root: Array.from(proof.root),
+            },
+        ),
+        proof.proof,
+    );
+}
+
 /**
  * Helper function for {@link createAppendInstruction}
  * @param merkleTree
@@ -200,6 +277,134 @@ export function createAppendIx(
     );
 }
 
+/**
+ * Helper function for {@link createAppendSubtreeInstruction}
+ * @param merkleTree
+ * @param authority
+ * @param subtreeRoot root of the subtree, whose leaves start at a multiple of their number
+ * @param rightmostLeaf last leaf of the subtree
+ * @param proof proof of the last leaf within the subtree
+ * @returns
+ */
+export function createAppendSubtreeIx(
+    merkleTree: PublicKey,
+    authority: PublicKey,
+    subtreeRoot: ArrayLike<number> | Buffer,
+    rightmostLeaf: ArrayLike<number> | Buffer,
+    proof: Buffer[],
+): TransactionInstruction {
+    return addProof(
+        createAppendSubtreeInstruction(
+            {
+                authority: authority,
+                merkleTree,
+                noop: SPL_NOOP_PROGRAM_ID,
+            },
+            {
+                rightmostLeaf: Array.from(rightmostLeaf),
+                subtreeRoot: Array.from(subtreeRoot),
+            },
+        ),
+        proof,
+    );
+}
+
+/**
+ * Helper function for {@link createAppendBatchInstruction}
+ * @param merkleTree
+ * @param authority
+ * @param newLeaves
+ * @returns
+ */
+export function createAppendBatchIx(
+    merkleTree: PublicKey,
+    authority: PublicKey,
+    newLeaves: (ArrayLike<number> | Buffer)[],
+): TransactionInstruction {
+    return createAppendBatchInstruction(
+        {
+            authority: authority,
+            merkleTree,
+            noop: SPL_NOOP_PROGRAM_ID,
+        },
+        {
+            leaves: newLeaves.map(leaf => Array.from(leaf)),
+        },
+    );
+}
+
+/**
+ * Helper function for {@link createInsertIndexedLeafInstruction}
+ * @param merkleTree
+ * @param authority
+ * @param value
+ * @param lowLeaf leaf holding the largest value of the tree smaller than `value`
+ * @param proof proof of `lowLeaf`
+ * @returns
+ */
+export function createInsertIndexedLeafIx(
+    merkleTree: PublicKey,
+    authority: PublicKey,
+    value: Buffer,
+    lowLeaf: IndexedLeaf,
+    proof: MerkleTreeProof,
+): TransactionInstruction {
+    return addProof(
+        createInsertIndexedLeafInstruction(
+            {
+                authority: authority,
+                merkleTree,
+                noop: SPL_NOOP_PROGRAM_ID,
+            },
+            {
+                lowLeaf,
+                lowLeafIndex: proof.leafIndex,
+                root: Array.from(proof.root),
+                value: Array.from(value),
+            },
+        ),
+        proof.proof,
+    );
+}
+
+/**
+ * Helper function for {@link createMigrateTreeInstruction}
+ *
+ * The new tree account must be allocated with {@link createAllocTreeIx} beforehand.
+ * Proofs of the leaves in the new tree are given by {@link MerkleTree.extendProof}.
+ * @param merkleTree
+ * @param newMerkleTree
+ * @param authority
+ * @param proof proof of the rightmost leaf of `merkleTree`
+ * @param depthSizePair dimensions of the new tree, deeper than `merkleTree`
+ * @returns
+ */
+export function createMigrateTreeIx(
+    merkleTree: PublicKey,
+    newMerkleTree: PublicKey,
+    authority: PublicKey,
+    proof: MerkleTreeProof,
+    depthSizePair: ValidDepthSizePair,
+): TransactionInstruction {
+    return addProof(
+        createMigrateTreeInstruction(
+            {
+                authority: authority,
+                merkleTree,
+                newMerkleTree,
+                noop: SPL_NOOP_PROGRAM_ID,
+            },
+            {
+                maxBufferSize: depthSizePair.maxBufferSize,
+                maxDepth: depthSizePair.maxDepth,
+                rightmostLeaf: Array.from(proof.leaf),
+                root: Array.from(proof.root),
+            },
+        ),
+        proof.proof,
+    );
+}
+
 /**
  * Helper function for {@link createTransferAuthorityIx}
  * @param merkleTree
@@ -245,6 +450,36 @@ export function createVerifyLeafIx(merkleTree: PublicKey, proof: MerkleTreeProof
     );
 }
 
+/**
+ * Helper function for {@link createVerifyNonMembershipInstruction}
+ * @param merkleTree
+ * @param value
+ * @param lowLeaf leaf holding the largest value of the tree smaller than `value`
+ * @param proof proof of `lowLeaf`
+ * @returns
+ */
+export function createVerifyNonMembershipIx(
+    merkleTree: PublicKey,
+    value: Buffer,
+    lowLeaf: IndexedLeaf,
+    proof: MerkleTreeProof,
+): TransactionInstruction {
+    return addProof(
+        createVerifyNonMembershipInstruction(
+            {
+                merkleTree,
+            },
+            {
+                lowLeaf,
+                lowLeafIndex: proof.leafIndex,
+                root: Array.from(proof.root),
+                value: Array.from(value),
+            },
+        ),
+        proof.proof,
+    );
+}
+
 /**
  * Helper function for creating the {@link ConcurrentMerkleTreeAccount}.
  * It is best to use this method to initialize a {@link ConcurrentMerkleTreeAccount}
@@ -1,6 +1,8 @@
 import { PublicKey } from '@solana/web3.js';
 import pkg from 'js-sha3';
 import * as Collections from 'typescript-collections';
+
+import { IndexedLeaf } from '../generated/types/IndexedLeaf';
 const { keccak_256 } = pkg;
 
 const CACHE_EMPTY_NODE = new Map<number, Buffer>();
@@ -13,6 +15,13 @@ export type MerkleTreeProof = {
     root: Buffer;
 };
 
+export type MerkleTreeMultiProof = {
+    leafIndices: number[];
+    leaves: Buffer[];
+    proof: Buffer[];
+    root: Buffer;
+};
+
 export class MerkleTree {
     leaves: TreeNode[];
     root: Buffer;
@@ -129,6 +138,39 @@ export class MerkleTree {
         };
     }
 
+    /**
+     * Returns the proof shared by the leaves at `leafIndices`, as expected by
+     * the `replaceLeaves` instruction: level by level starting from the leaves,
+     * the siblings of the ancestors of the leaves which aren't ancestors of
+     * one of the leaves themselves, sorted by position.
+     *
+     * @param leafIndices indices of the leaves, sorted by increasing index
+     * @param minimizeProofHeight whether to leave out the levels cached in the canopy
+     * @param treeHeight number of levels to include when `minimizeProofHeight` is set
+     */
+    getMultiProof(leafIndices: number[], minimizeProofHeight = false, treeHeight = -1): MerkleTreeMultiProof {
+        const proof: Buffer[] = [];
+        const height = minimizeProofHeight ? treeHeight : this.depth - 1;
+        for (let level = 0; level < height; level++) {
+            const ancestors = [...new Set(leafIndices.map(index => index >> level))];
+            ancestors.forEach((ancestor, i) => {
+                const hasSibling =
+                    ancestor % 2 === 0 ? ancestors[i + 1] === ancestor + 1 : ancestors[i - 1] === ancestor - 1;
+                if (!hasSibling) {
+                    // The sibling's own sibling is an ancestor of one of the leaves
+                    proof.push(this.getProof(ancestor << level).proof[level]);
+                }
+            });
+        }
+
+        return {
+            leafIndices,
+            leaves: leafIndices.map(index => this.leaves[index].node),
+            proof,
+            root: this.getRoot(),
+        };
+    }
+
     updateLeaf(leafIndex: number, newLeaf: Buffer, verbose = false) {
         const leaf = this.leaves[leafIndex];
         leaf.node = newLeaf;
@@ -182,6 +224,23 @@ export class MerkleTree {
         if (verbose) console.log(`Hashed ${rehashed} got ${received}`);
         return rehashed === received;
     }
+
+    /**
+     * Extends a proof into the proof of the same leaf in a tree of `depth` levels
+     * whose leftmost subtree is the tree of the proof, as created by `migrate_tree`.
+     * @param merkleTreeProof Proof to a leaf in the MerkleTree
+     * @param depth Depth of the deeper tree
+     * @returns Proof to the leaf in the deeper tree
+     */
+    static extendProof(merkleTreeProof: MerkleTreeProof, depth: number): MerkleTreeProof {
+        const proof = [...merkleTreeProof.proof];
+        let root = merkleTreeProof.root;
+        for (let level = proof.length; level < depth; level++) {
+            proof.push(emptyNode(level));
+            root = hash(root, emptyNode(level));
+        }
+        return { ...merkleTreeProof, proof, root };
+    }
 }
 
 export type TreeNode = {
@@ -200,6 +259,16 @@ export function hash(left: Buffer, right: Buffer): Buffer {
     return Buffer.from(keccak_256.digest(Buffer.concat([left, right])));
 }
 
+/**
+ * Hashes a leaf of an indexed tree into the node stored in the tree, as
+ * `insert_indexed_leaf` does on-chain for Keccak trees
+ */
+export function hashIndexedLeaf(leaf: IndexedLeaf): Buffer {
+    const nextIndex = Buffer.alloc(32);
+    nextIndex.writeUInt32BE(leaf.nextIndex, 28);
+    return hash(hash(Buffer.from(leaf.value), Buffer.from(leaf.nextValue)), nextIndex);
+}
+
 /*
  Breadth-first iteration over a merkle tree
 */
@@ -2,25 +2,36 @@ import { strict as assert } from 'node:assert';
 
 import { AnchorProvider } from '@coral-xyz/anchor';
 import NodeWallet from '@coral-xyz/anchor/dist/cjs/nodewallet';
+import { bs58 } from '@coral-xyz/anchor/dist/cjs/utils/bytes';
 import { Connection, Keypair, PublicKey, TransactionInstruction } from '@solana/web3.js';
 import { BN } from 'bn.js';
 import * as crypto from 'crypto';
 
 import {
     ConcurrentMerkleTreeAccount,
+    createAllocTreeIx,
+    createAppendBatchIx,
     createAppendCanopyNodesIx,
     createAppendIx,
+    createAppendSubtreeIx,
     createCloseEmptyTreeInstruction,
     createCloseEmptyTreeIx,
     createInitEmptyMerkleTreeIx,
+    createInitIndexedMerkleTreeIx,
     createInitPreparedTreeWithRootIx,
+    createInsertIndexedLeafIx,
+    createMigrateTreeIx,
     createReplaceIx,
+    createReplaceLeavesIx,
+    deserializeChangeLogEvents,
     createTransferAuthorityIx,
     createVerifyLeafIx,
+    createVerifyNonMembershipIx,
+    IndexedLeaf,
     prepareTreeIx,
     ValidDepthSizePair,
 } from '../src';
-import { hash, MerkleTree } from '../src/merkle-tree';
+import { hash, hashIndexedLeaf, MerkleTree } from '../src/merkle-tree';
 import { assertCMTProperties } from './accounts/concurrentMerkleTreeAccount.test';
 import { createTreeOnChain, execute, prepareTree } from './utils';
 
@@ -621,6 +632,58 @@ describe('Account Compression', () => {
                 'Updated on chain root matches root of updated off chain tree',
             );
         });
+        it('Append a batch of leaves and a subtree', async () => {
+            // Leaves 1 to 3 are appended as subtrees of 1 and 2 leaves
+            const batchLeaves = [1, 2, 3].map(() => crypto.randomBytes(32));
+            await execute(provider, [createAppendBatchIx(cmt, payer, batchLeaves)], [payerKeypair]);
+            batchLeaves.map((leaf, i) => {
+                offChainTree.updateLeaf(1 + i, leaf);
+            });
+
+            let splCMT = await ConcurrentMerkleTreeAccount.fromAccountAddress(connection, cmt);
+            assert(
+                Buffer.from(splCMT.getCurrentRoot()).equals(offChainTree.root),
+                'Updated on chain root does not match root of updated off chain tree',
+            );
+            assert.equal(splCMT.getCurrentSeq().toNumber(), 3);
+
+            // Leaves 4 to 7 are appended as a single subtree
+            const subtreeLeaves = [4, 5, 6, 7].map(() => crypto.randomBytes(32));
+            const subtree = new MerkleTree(subtreeLeaves);
+            const appendSubtreeIx = createAppendSubtreeIx(
+                cmt,
+                payer,
+                subtree.root,
+                subtreeLeaves[3],
+                subtree.getProof(3).proof,
+            );
+            await execute(provider, [appendSubtreeIx], [payerKeypair]);
+            subtreeLeaves.map((leaf, i) => {
+                offChainTree.updateLeaf(4 + i, leaf);
+            });
+
+            splCMT = await ConcurrentMerkleTreeAccount.fromAccountAddress(connection, cmt);
+            assert(
+                Buffer.from(splCMT.getCurrentRoot()).equals(offChainTree.root),
+                'Updated on chain root does not match root of updated off chain tree',
+            );
+            assert.equal(splCMT.getCurrentSeq().toNumber(), 4);
+        });
+        it('Fail to append a subtree which is not aligned', async () => {
+            const subtreeLeaves = [1, 2].map(() => crypto.randomBytes(32));
+            const subtree = new MerkleTree(subtreeLeaves);
+            const appendSubtreeIx = createAppendSubtreeIx(
+                cmt,
+                payer,
+                subtree.root,
+                subtreeLeaves[1],
+                subtree.getProof(1).proof,
+            );
+            try {
+                await execute(provider, [appendSubtreeIx], [payerKeypair]);
+                assert(false, 'Appending a subtree starting at leaf 1 should have failed');
+            } catch {}
+        });
         it('Verify proof works for that leaf', async () => {
             const newLeaf = crypto.randomBytes(32);
             const index = 0;
@@ -819,6 +882,38 @@ describe('Account Compression', () => {
                 'Updated on chain root does not match root of updated off chain tree',
             );
         });
+        it('Replace several of them in a single instruction', async () => {
+            const indices = [0, 1, 5, 40];
+            const newLeaves = indices.map(() => crypto.randomBytes(32));
+            const replaceLeavesIx = createReplaceLeavesIx(cmt, payer, newLeaves, offChainTree.getMultiProof(indices));
+
+            const txId = await execute(provider, [replaceLeavesIx], [payerKeypair]);
+
+            indices.map((index, i) => {
+                offChainTree.updateLeaf(index, newLeaves[i]);
+            });
+
+            const splCMT = await ConcurrentMerkleTreeAccount.fromAccountAddress(connection, cmt);
+            const onChainRoot = splCMT.getCurrentRoot();
+
+            assert(
+                Buffer.from(onChainRoot).equals(offChainTree.root),
+                'Updated on chain root does not match root of updated off chain tree',
+            );
+
+            // A single event holds the paths of all the leaves
+            const transaction = await connection.getTransaction(txId, {
+                commitment: 'confirmed',
+                maxSupportedTransactionVersion: 2,
+            });
+            const noopData = transaction!.meta!.innerInstructions![0].instructions[0].data;
+            const changeLogs = deserializeChangeLogEvents(Buffer.from(bs58.decode(noopData)));
+            assert.deepStrictEqual(changeLogs.map(changeLog => changeLog.index), indices);
+            assert(
+                Buffer.from(changeLogs[indices.length - 1].path[MAX_DEPTH].node).equals(offChainTree.root),
+                'Last path of the event does not end with the new root',
+            );
+        });
         it('Empty all of the leaves and close the tree', async () => {
             const ixArray: TransactionInstruction[] = [];
             const txList: Promise<string>[] = [];
@@ -873,6 +968,146 @@ describe('Account Compression', () => {
         });
     });
 
+    describe(`Having created a full tree with depth 3`, () => {
+        const DEPTH = 3;
+        const NEW_DEPTH_SIZE_PAIR: ValidDepthSizePair = { maxBufferSize: 8, maxDepth: 5 };
+        let newCmtKeypair: Keypair;
+        beforeEach(async () => {
+            [cmtKeypair, offChainTree] = await createTreeOnChain(provider, payerKeypair, 2 ** DEPTH, {
+                maxBufferSize: 8,
+                maxDepth: DEPTH,
+            });
+            cmt = cmtKeypair.publicKey;
+            newCmtKeypair = Keypair.generate();
+        });
+        it('Migrate it into a deeper tree', async () => {
+            const allocAccountIx = await createAllocTreeIx(
+                connection,
+                newCmtKeypair.publicKey,
+                payer,
+                NEW_DEPTH_SIZE_PAIR,
+                0,
+            );
+            const migrateIx = createMigrateTreeIx(
+                cmt,
+                newCmtKeypair.publicKey,
+                payer,
+                offChainTree.getProof(2 ** DEPTH - 1),
+                NEW_DEPTH_SIZE_PAIR,
+            );
+            await execute(provider, [allocAccountIx, migrateIx], [payerKeypair, newCmtKeypair]);
+
+            const splCMT = await ConcurrentMerkleTreeAccount.fromAccountAddress(connection, cmt);
+            assert(splCMT.getIsReadOnly(), 'Migrated tree should be read-only');
+            const newSplCMT = await ConcurrentMerkleTreeAccount.fromAccountAddress(connection, newCmtKeypair.publicKey);
+            assert.equal(newSplCMT.getMaxDepth(), NEW_DEPTH_SIZE_PAIR.maxDepth);
+            assert.equal(newSplCMT.tree.rightMostPath.index, 2 ** DEPTH);
+
+            // Leaves keep their index, with their proof extended
+            const proof = MerkleTree.extendProof(offChainTree.getProof(3), NEW_DEPTH_SIZE_PAIR.maxDepth);
+            assert(
+                Buffer.from(newSplCMT.getCurrentRoot()).equals(proof.root),
+                'Root of the new tree does not extend the root of the migrated tree',
+            );
+            const newLeaf = crypto.randomBytes(32);
+            await execute(provider, [createReplaceIx(newCmtKeypair.publicKey, payer, newLeaf, proof)], [payerKeypair]);
+
+            // The migrated tree can no longer be modified
+            try {
+                await execute(provider, [createAppendIx(cmt, payer, crypto.randomBytes(32))], [payerKeypair]);
+                assert(false, 'Appending to a migrated tree should have failed');
+            } catch {}
+        });
+    });
+
+    describe(`Having created an indexed tree with depth 3`, () => {
+        const DEPTH_SIZE_PAIR: ValidDepthSizePair = { maxBufferSize: 8, maxDepth: 3 };
+        const defaultLeaf: IndexedLeaf = {
+            nextIndex: 0,
+            nextValue: Array(32).fill(0),
+            value: Array(32).fill(0),
+        };
+        beforeEach(async () => {
+            cmtKeypair = Keypair.generate();
+            const allocAccountIx = await createAllocTreeIx(
+                connection,
+                cmtKeypair.publicKey,
+                payer,
+                DEPTH_SIZE_PAIR,
+                0,
+            );
+            const initIx = createInitIndexedMerkleTreeIx(cmtKeypair.publicKey, payer, DEPTH_SIZE_PAIR);
+            await execute(provider, [allocAccountIx, initIx], [payerKeypair, cmtKeypair]);
+            cmt = cmtKeypair.publicKey;
+
+            const leaves = Array(2 ** DEPTH_SIZE_PAIR.maxDepth).fill(Buffer.alloc(32));
+            leaves[0] = hashIndexedLeaf(defaultLeaf);
+            offChainTree = new MerkleTree(leaves);
+        });
+        it('Insert values and prove the absence of others', async () => {
+            const five = Buffer.alloc(32, 5);
+            await execute(
+                provider,
+                [createInsertIndexedLeafIx(cmt, payer, five, defaultLeaf, offChainTree.getProof(0))],
+                [payerKeypair],
+            );
+            const lowLeaf: IndexedLeaf = { ...defaultLeaf, nextIndex: 1, nextValue: Array.from(five) };
+            const fiveLeaf: IndexedLeaf = { ...defaultLeaf, value: Array.from(five) };
+            offChainTree.updateLeaf(0, hashIndexedLeaf(lowLeaf));
+            offChainTree.updateLeaf(1, hashIndexedLeaf(fiveLeaf));
+
+            let splCMT = await ConcurrentMerkleTreeAccount.fromAccountAddress(connection, cmt);
+            assert(
+                Buffer.from(splCMT.getCurrentRoot()).equals(offChainTree.root),
+                'On chain root does not match the indexed tree',
+            );
+
+            // Values between the default leaf and five are absent
+            const three = Buffer.alloc(32, 3);
+            await execute(
+                provider,
+                [createVerifyNonMembershipIx(cmt, three, lowLeaf, offChainTree.getProof(0))],
+                [payerKeypair],
+            );
+            try {
+                await execute(
+                    provider,
+                    [createVerifyNonMembershipIx(cmt, five, lowLeaf, offChainTree.getProof(0))],
+                    [payerKeypair],
+                );
+                assert(false, 'Proving the absence of an inserted value should have failed');
+            } catch {}
+
+            await execute(
+                provider,
+                [createInsertIndexedLeafIx(cmt, payer, three, lowLeaf, offChainTree.getProof(0))],
+                [payerKeypair],
+            );
+            offChainTree.updateLeaf(0, hashIndexedLeaf({ ...lowLeaf, nextIndex: 2, nextValue: Array.from(three) }));
+            offChainTree.updateLeaf(2, hashIndexedLeaf({ ...lowLeaf, value: Array.from(three) }));
+
+            splCMT = await ConcurrentMerkleTreeAccount.fromAccountAddress(connection, cmt);
+            assert(
+                Buffer.from(splCMT.getCurrentRoot()).equals(offChainTree.root),
+                'On chain root does not match the indexed tree',
+            );
+        });
+        it('Plain leaf mutations are rejected', async () => {
+            const splCMT = await ConcurrentMerkleTreeAccount.fromAccountAddress(connection, cmt);
+            assert(splCMT.getIsIndexed(), 'Indexed tree should be marked as indexed');
+
+            try {
+                await execute(provider, [createAppendIx(cmt, payer, crypto.randomBytes(32))], [payerKeypair]);
+                assert(false, 'Appending a plain leaf to an indexed tree should have failed');
+            } catch {}
+            try {
+                const replaceIx = createReplaceIx(cmt, payer, crypto.randomBytes(32), offChainTree.getProof(0));
+                await execute(provider, [replaceIx], [payerKeypair]);
+                assert(false, 'Replacing a leaf of an indexed tree should have failed');
+            } catch {}
+        });
+    });
+
     describe(`Having created a tree with depth 3`, () => {
         const DEPTH = 3;
         beforeEach(async () => {
@@ -923,6 +1158,24 @@ describe('Account Compression', () => {
                 "CMT updated its active index after attacker's transaction, when it shouldn't have done anything",
             );
         });
+        it('Non-membership proofs are rejected on a tree that is not indexed', async () => {
+            const splCMT = await ConcurrentMerkleTreeAccount.fromAccountAddress(connection, cmt);
+            assert(!splCMT.getIsIndexed(), 'Plain tree should not be marked as indexed');
+
+            const lowLeaf: IndexedLeaf = {
+                nextIndex: 0,
+                nextValue: Array(32).fill(0),
+                value: Array(32).fill(0),
+            };
+            try {
+                await execute(
+                    provider,
+                    [createVerifyNonMembershipIx(cmt, Buffer.alloc(32, 1), lowLeaf, offChainTree.getProof(0))],
+                    [payerKeypair],
+                );
+                assert(false, 'Proving non-membership in a tree that is not indexed should have failed');
+            } catch {}
+        });
     });
     describe(`Canopy test`, () => {
         const DEPTH = 5;
@@ -41,10 +41,10 @@ def initialize_binary_option_instruction(
         AccountMeta(pubkey=pool_account, is_signer=True, is_writable=True),
         AccountMeta(pubkey=escrow_mint_account, is_signer=False, is_writable=False),
         AccountMeta(pubkey=escrow_account, is_signer=True, is_writable=True),
-        AccountMeta(pubkey=long_token_mint_account, is_signer=True, is_writable=False),
-        AccountMeta(pubkey=short_token_mint_account, is_signer=True, is_writable=False),
-        AccountMeta(pubkey=mint_authority_account, is_signer=True, is_writable=False),
-        AccountMeta(pubkey=update_authority_account, is_signer=True, is_writable=False),
+        AccountMeta(pubkey=long_token_mint_account, is_signer=True, is_writable=True),
+        AccountMeta(pubkey=short_token_mint_account, is_signer=True, is_writable=True),
+        AccountMeta(pubkey=mint_authority_account, is_signer=True, is_writable=True),
+        AccountMeta(pubkey=update_authority_account, is_signer=True, is_writable=True),
         AccountMeta(pubkey=token_account, is_signer=False, is_writable=False),
         AccountMeta(pubkey=system_account, is_signer=False, is_writable=False),
         AccountMeta(pubkey=rent_account, is_signer=False, is_writable=False),
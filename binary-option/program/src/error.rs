@@ -28,6 +28,32 @@ pub enum BinaryOptionError {
     TradePricesIncorrect,
     #[error("AmountOverflow")]
     AmountOverflow,
+    #[error("OptionExpired")]
+    OptionExpired,
+    #[error("OptionNotExpired")]
+    OptionNotExpired,
+    #[error("StaleOraclePrice")]
+    StaleOraclePrice,
+    #[error("NotOracleSettled")]
+    NotOracleSettled,
+    #[error("DisputeWindowOpen")]
+    DisputeWindowOpen,
+    #[error("DisputeWindowClosed")]
+    DisputeWindowClosed,
+    #[error("InvalidExpiry")]
+    InvalidExpiry,
+    #[error("InvalidOrder")]
+    InvalidOrder,
+    #[error("OrdersDoNotCross")]
+    OrdersDoNotCross,
+    #[error("AlreadyDisputed")]
+    AlreadyDisputed,
+    #[error("NoPositionHeld")]
+    NoPositionHeld,
+    #[error("NotUpgradeAuthority")]
+    NotUpgradeAuthority,
+    #[error("SettlementWindowOpen")]
+    SettlementWindowOpen,
 }
 
 impl From<BinaryOptionError> for ProgramError {
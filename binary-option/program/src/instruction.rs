@@ -1,6 +1,8 @@
 use {
+    crate::state::{ArbiterConfig, OrderSide},
     borsh::{BorshDeserialize, BorshSerialize},
     solana_program::{
+        bpf_loader_upgradeable,
         instruction::{AccountMeta, Instruction},
         pubkey::Pubkey,
         sysvar,
@@ -21,6 +23,31 @@ pub struct TradeArgs {
     pub sell_price: u64,
 }
 
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct InitializeOracleBinaryOptionArgs {
+    pub decimals: u8,
+    pub strike_price: i64,
+    pub expiry: i64,
+    pub dispute_window: i64,
+    pub max_staleness: i64,
+    pub oracle_program_id: Pubkey,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SetArbiterArgs {
+    pub arbiter: Pubkey,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct PlaceOrderArgs {
+    pub side: OrderSide,
+    pub price: u64,
+    pub size: u64,
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Clone)]
 pub enum BinaryOptionInstruction {
     // TODO: Add comments here
@@ -28,9 +55,48 @@ pub enum BinaryOptionInstruction {
 
     Trade(TradeArgs),
 
+    /// Settles an owner-settled option with the winning mint chosen by the
+    /// owner, or an oracle-settled option from the oracle price once it has
+    /// expired, in which case anyone may call it with the oracle account.
+    /// A disputed oracle settlement is settled again by the arbiter, with
+    /// the same accounts as the owner. So is an oracle-settled option still
+    /// unsettled once `max_staleness` seconds have passed since expiry, after
+    /// which no oracle price can settle it
     Settle,
 
     Collect,
+
+    /// Same as `InitializeBinaryOption`, with the oracle account and the
+    /// arbiter config appended. The oracle account must be owned by the given
+    /// oracle program, and the arbiter of the config can't be the owner
+    InitializeOracleBinaryOption(InitializeOracleBinaryOptionArgs),
+
+    /// Lets the owner, or anyone holding long or short tokens, revert an
+    /// oracle settlement during the dispute window, once, after which only
+    /// the arbiter can settle the option. Holders append the token account
+    /// holding their position
+    Dispute,
+
+    /// Creates a resting order, moving `size` times its collateral per
+    /// contract from the owner into a new order escrow account. The
+    /// collateral of a bid is its price, the one of an ask is `10^decimals`
+    /// minus its price. The escrow authority is approved on the position
+    /// account so that the matched contracts can be burned on collection.
+    PlaceOrder(PlaceOrderArgs),
+
+    /// Refunds the unfilled collateral of an order to its owner and closes
+    /// the order and its escrow account
+    CancelOrder,
+
+    /// Matches a bid with an ask priced at or below it, minting long/short
+    /// pairs to their position accounts and moving the collateral into the
+    /// option escrow. The trade happens at the bid price and the ask owner is
+    /// refunded the price difference. Anyone may call it.
+    MatchOrders,
+
+    /// Creates or updates the arbiter config, signed by the upgrade authority
+    /// of the program. Options keep the arbiter they were created with
+    SetArbiter(SetArbiterArgs),
 }
 
 /// Creates an InitializeBinaryOption instruction
@@ -52,10 +118,10 @@ pub fn initialize_binary_option(
             AccountMeta::new(pool_account, true),
             AccountMeta::new_readonly(escrow_mint, false),
             AccountMeta::new(escrow_account, true),
-            AccountMeta::new_readonly(long_token_mint, true),
-            AccountMeta::new_readonly(short_token_mint, true),
-            AccountMeta::new_readonly(mint_authority, true),
-            AccountMeta::new_readonly(update_authority, true),
+            AccountMeta::new(long_token_mint, true),
+            AccountMeta::new(short_token_mint, true),
+            AccountMeta::new(mint_authority, true),
+            AccountMeta::new(update_authority, true),
             AccountMeta::new_readonly(spl_token::id(), false),
             AccountMeta::new_readonly(solana_program::system_program::id(), false),
             AccountMeta::new_readonly(sysvar::rent::id(), false),
@@ -67,6 +133,57 @@ pub fn initialize_binary_option(
     }
 }
 
+/// Creates an InitializeOracleBinaryOption instruction
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_oracle_binary_option(
+    program_id: Pubkey,
+    pool_account: Pubkey,
+    escrow_mint: Pubkey,
+    escrow_account: Pubkey,
+    long_token_mint: Pubkey,
+    short_token_mint: Pubkey,
+    mint_authority: Pubkey,
+    update_authority: Pubkey,
+    oracle: Pubkey,
+    decimals: u8,
+    strike_price: i64,
+    expiry: i64,
+    dispute_window: i64,
+    max_staleness: i64,
+    oracle_program_id: Pubkey,
+) -> Instruction {
+    let mut instruction = initialize_binary_option(
+        program_id,
+        pool_account,
+        escrow_mint,
+        escrow_account,
+        long_token_mint,
+        short_token_mint,
+        mint_authority,
+        update_authority,
+        decimals,
+    );
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(oracle, false));
+    instruction.accounts.push(AccountMeta::new_readonly(
+        ArbiterConfig::find_address(&program_id).0,
+        false,
+    ));
+    instruction.data = borsh::to_vec(&BinaryOptionInstruction::InitializeOracleBinaryOption(
+        InitializeOracleBinaryOptionArgs {
+            decimals,
+            strike_price,
+            expiry,
+            dispute_window,
+            max_staleness,
+            oracle_program_id,
+        },
+    ))
+    .unwrap();
+    instruction
+}
+
 /// Creates a Trade instruction
 #[allow(clippy::too_many_arguments)]
 pub fn trade(
@@ -133,6 +250,40 @@ pub fn settle(
     }
 }
 
+/// Creates a Settle instruction for an oracle-settled option
+pub fn settle_with_oracle(program_id: Pubkey, pool_account: Pubkey, oracle: Pubkey) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(pool_account, false),
+            AccountMeta::new_readonly(oracle, false),
+        ],
+        data: borsh::to_vec(&BinaryOptionInstruction::Settle).unwrap(),
+    }
+}
+
+/// Creates a Dispute instruction, with the position account of a disputer
+/// other than the owner
+pub fn dispute(
+    program_id: Pubkey,
+    pool_account: Pubkey,
+    disputer: Pubkey,
+    position_account: Option<Pubkey>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(pool_account, false),
+        AccountMeta::new_readonly(disputer, true),
+    ];
+    if let Some(position_account) = position_account {
+        accounts.push(AccountMeta::new_readonly(position_account, false));
+    }
+    Instruction {
+        program_id,
+        accounts,
+        data: borsh::to_vec(&BinaryOptionInstruction::Dispute).unwrap(),
+    }
+}
+
 /// Create a Collect instruction
 #[allow(clippy::too_many_arguments)]
 pub fn collect(
@@ -160,11 +311,136 @@ pub fn collect(
             AccountMeta::new(short_token_mint_account, false),
             AccountMeta::new(escrow_account, false),
             AccountMeta::new(escrow_authority_account, false),
-            AccountMeta::new_readonly(fee_payer_account, true),
             AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(fee_payer_account, true),
             AccountMeta::new_readonly(solana_program::system_program::id(), false),
             AccountMeta::new_readonly(sysvar::rent::id(), false),
         ],
         data: borsh::to_vec(&BinaryOptionInstruction::Collect).unwrap(),
     }
 }
+
+/// Creates a PlaceOrder instruction
+#[allow(clippy::too_many_arguments)]
+pub fn place_order(
+    program_id: Pubkey,
+    pool_account: Pubkey,
+    order_account: Pubkey,
+    order_escrow_account: Pubkey,
+    escrow_mint: Pubkey,
+    owner: Pubkey,
+    collateral_account: Pubkey,
+    position_account: Pubkey,
+    position_mint: Pubkey,
+    escrow_authority: Pubkey,
+    side: OrderSide,
+    price: u64,
+    size: u64,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(pool_account, false),
+            AccountMeta::new(order_account, true),
+            AccountMeta::new(order_escrow_account, true),
+            AccountMeta::new_readonly(escrow_mint, false),
+            AccountMeta::new(owner, true),
+            AccountMeta::new(collateral_account, false),
+            AccountMeta::new(position_account, false),
+            AccountMeta::new_readonly(position_mint, false),
+            AccountMeta::new_readonly(escrow_authority, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: borsh::to_vec(&BinaryOptionInstruction::PlaceOrder(PlaceOrderArgs {
+            side,
+            price,
+            size,
+        }))
+        .unwrap(),
+    }
+}
+
+/// Creates a CancelOrder instruction
+pub fn cancel_order(
+    program_id: Pubkey,
+    pool_account: Pubkey,
+    order_account: Pubkey,
+    order_escrow_account: Pubkey,
+    owner: Pubkey,
+    collateral_account: Pubkey,
+    escrow_authority: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(pool_account, false),
+            AccountMeta::new(order_account, false),
+            AccountMeta::new(order_escrow_account, false),
+            AccountMeta::new(owner, true),
+            AccountMeta::new(collateral_account, false),
+            AccountMeta::new_readonly(escrow_authority, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: borsh::to_vec(&BinaryOptionInstruction::CancelOrder).unwrap(),
+    }
+}
+
+/// Creates a MatchOrders instruction
+#[allow(clippy::too_many_arguments)]
+pub fn match_orders(
+    program_id: Pubkey,
+    pool_account: Pubkey,
+    escrow_account: Pubkey,
+    long_token_mint: Pubkey,
+    short_token_mint: Pubkey,
+    bid_order_account: Pubkey,
+    bid_order_escrow_account: Pubkey,
+    bid_position_account: Pubkey,
+    ask_order_account: Pubkey,
+    ask_order_escrow_account: Pubkey,
+    ask_position_account: Pubkey,
+    ask_collateral_account: Pubkey,
+    escrow_authority: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(pool_account, false),
+            AccountMeta::new(escrow_account, false),
+            AccountMeta::new(long_token_mint, false),
+            AccountMeta::new(short_token_mint, false),
+            AccountMeta::new(bid_order_account, false),
+            AccountMeta::new(bid_order_escrow_account, false),
+            AccountMeta::new(bid_position_account, false),
+            AccountMeta::new(ask_order_account, false),
+            AccountMeta::new(ask_order_escrow_account, false),
+            AccountMeta::new(ask_position_account, false),
+            AccountMeta::new(ask_collateral_account, false),
+            AccountMeta::new_readonly(escrow_authority, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: borsh::to_vec(&BinaryOptionInstruction::MatchOrders).unwrap(),
+    }
+}
+
+/// Creates a SetArbiter instruction
+pub fn set_arbiter(program_id: Pubkey, upgrade_authority: Pubkey, arbiter: Pubkey) -> Instruction {
+    let (program_data, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(ArbiterConfig::find_address(&program_id).0, false),
+            AccountMeta::new_readonly(program_data, false),
+            AccountMeta::new(upgrade_authority, true),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: borsh::to_vec(&BinaryOptionInstruction::SetArbiter(SetArbiterArgs {
+            arbiter,
+        }))
+        .unwrap(),
+    }
+}
@@ -1,25 +1,35 @@
 use {
     crate::{
         error::BinaryOptionError,
-        instruction::BinaryOptionInstruction,
+        instruction::{
+            BinaryOptionInstruction, InitializeOracleBinaryOptionArgs, PlaceOrderArgs,
+            SetArbiterArgs,
+        },
         spl_utils::{
-            spl_approve, spl_burn, spl_burn_signed, spl_initialize, spl_mint_initialize,
-            spl_mint_to, spl_set_authority, spl_token_transfer, spl_token_transfer_signed,
+            spl_approve, spl_burn, spl_burn_signed, spl_close_account_signed, spl_initialize,
+            spl_mint_initialize, spl_mint_to, spl_set_authority, spl_token_transfer,
+            spl_token_transfer_signed,
+        },
+        state::{ArbiterConfig, BinaryOption, OraclePrice, Order, OrderSide},
+        system_utils::{
+            create_new_account, create_or_allocate_account_raw, create_or_allocate_account_signed,
         },
-        state::BinaryOption,
-        system_utils::{create_new_account, create_or_allocate_account_raw},
         validation_utils::{
             assert_initialized, assert_keys_equal, assert_keys_unequal, assert_owned_by,
+            assert_upgrade_authority,
         },
     },
     borsh::BorshDeserialize,
     solana_program::{
         account_info::{next_account_info, AccountInfo},
+        clock::Clock,
         entrypoint::ProgramResult,
         msg,
         program_error::ProgramError,
         program_pack::Pack,
         pubkey::Pubkey,
+        system_program,
+        sysvar::Sysvar,
     },
     spl_token::{
         instruction::AuthorityType,
@@ -58,6 +68,30 @@ impl Processor {
                 msg!("Instruction: Collect");
                 process_collect(program_id, accounts)
             }
+            BinaryOptionInstruction::InitializeOracleBinaryOption(args) => {
+                msg!("Instruction: InitializeOracleBinaryOption");
+                process_initialize_oracle_binary_option(program_id, accounts, args)
+            }
+            BinaryOptionInstruction::Dispute => {
+                msg!("Instruction: Dispute");
+                process_dispute(program_id, accounts)
+            }
+            BinaryOptionInstruction::PlaceOrder(args) => {
+                msg!("Instruction: PlaceOrder");
+                process_place_order(program_id, accounts, args)
+            }
+            BinaryOptionInstruction::CancelOrder => {
+                msg!("Instruction: CancelOrder");
+                process_cancel_order(program_id, accounts)
+            }
+            BinaryOptionInstruction::MatchOrders => {
+                msg!("Instruction: MatchOrders");
+                process_match_orders(program_id, accounts)
+            }
+            BinaryOptionInstruction::SetArbiter(args) => {
+                msg!("Instruction: SetArbiter");
+                process_set_arbiter(program_id, accounts, args)
+            }
         }
     }
 }
@@ -167,8 +201,7 @@ pub fn process_initialize_binary_option(
         BinaryOption::LEN,
     )?;
 
-    let mut binary_option =
-        BinaryOption::try_from_slice(&binary_option_account_info.data.borrow_mut())?;
+    let mut binary_option = BinaryOption::from_account_info(binary_option_account_info)?;
     binary_option.decimals = decimals;
     binary_option.circulation = 0;
     binary_option.settled = false;
@@ -177,10 +210,61 @@ pub fn process_initialize_binary_option(
     binary_option.escrow_mint_account_pubkey = *escrow_mint_info.key;
     binary_option.escrow_account_pubkey = *escrow_account_info.key;
     binary_option.owner = *update_authority_info.key;
-    borsh::to_writer(
-        &mut binary_option_account_info.data.borrow_mut()[..],
-        &binary_option,
+    binary_option.save(binary_option_account_info)?;
+    Ok(())
+}
+
+pub fn process_initialize_oracle_binary_option(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: InitializeOracleBinaryOptionArgs,
+) -> ProgramResult {
+    process_initialize_binary_option(program_id, accounts, args.decimals)?;
+    let binary_option_account_info = &accounts[0];
+    let oracle_info = accounts.get(10).ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let arbiter_config_info = accounts.get(11).ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    if args.expiry <= Clock::get()?.unix_timestamp
+        || args.dispute_window < 0
+        || args.max_staleness < 0
+    {
+        return Err(BinaryOptionError::InvalidExpiry.into());
+    }
+    assert_keys_unequal(*oracle_info.key, Pubkey::default())?;
+    // The price format is read from accounts of this program only, which
+    // can't be a wallet or an account the owner writes to at will
+    assert_keys_unequal(args.oracle_program_id, system_program::id())?;
+    assert_keys_unequal(args.oracle_program_id, *program_id)?;
+    assert_owned_by(oracle_info, &args.oracle_program_id)?;
+    assert_keys_equal(
+        *arbiter_config_info.key,
+        ArbiterConfig::find_address(program_id).0,
     )?;
+    assert_owned_by(arbiter_config_info, program_id)?;
+    let arbiter_config = ArbiterConfig::from_account_info(arbiter_config_info)?;
+
+    let mut binary_option = BinaryOption::from_account_info(binary_option_account_info)?;
+    binary_option.oracle = *oracle_info.key;
+    binary_option.oracle_program = args.oracle_program_id;
+    binary_option.strike_price = args.strike_price;
+    binary_option.expiry = args.expiry;
+    binary_option.dispute_window = args.dispute_window;
+    binary_option.max_staleness = args.max_staleness;
+    // The owner disputes settlements, so it can't be the one resolving them
+    assert_keys_unequal(arbiter_config.arbiter, binary_option.owner)?;
+    binary_option.arbiter = arbiter_config.arbiter;
+    binary_option.save(binary_option_account_info)?;
+    Ok(())
+}
+
+fn assert_trading_open(binary_option: &BinaryOption) -> ProgramResult {
+    if binary_option.settled {
+        return Err(BinaryOptionError::AlreadySettled.into());
+    }
+    // Trading stops at expiry, once the outcome may already be known
+    if binary_option.is_oracle_settled() && Clock::get()?.unix_timestamp >= binary_option.expiry {
+        return Err(BinaryOptionError::OptionExpired.into());
+    }
     Ok(())
 }
 
@@ -216,8 +300,7 @@ pub fn process_trade(
     let seller_short_token_account: Account = assert_initialized(seller_short_token_account_info)?;
     let buyer_account: Account = assert_initialized(buyer_account_info)?;
     let seller_account: Account = assert_initialized(seller_account_info)?;
-    let mut binary_option =
-        BinaryOption::try_from_slice(&binary_option_account_info.data.borrow_mut())?;
+    let mut binary_option = BinaryOption::from_account_info(binary_option_account_info)?;
 
     // Get program derived address for escrow
     let (authority_key, bump_seed) = Pubkey::find_program_address(
@@ -244,9 +327,7 @@ pub fn process_trade(
     if total_price != u64::pow(10, binary_option.decimals as u32) {
         return Err(BinaryOptionError::TradePricesIncorrect.into());
     }
-    if binary_option.settled {
-        return Err(BinaryOptionError::AlreadySettled.into());
-    }
+    assert_trading_open(&binary_option)?;
     assert_keys_equal(*token_program_info.key, spl_token::id())?;
     assert_keys_unequal(*buyer_info.key, *seller_info.key)?;
     assert_keys_equal(*long_token_mint_info.owner, spl_token::id())?;
@@ -581,45 +662,131 @@ pub fn process_trade(
         s_l,
         long_token_mint.decimals,
     )?;
-    borsh::to_writer(
-        &mut binary_option_account_info.data.borrow_mut()[..],
-        &binary_option,
-    )?;
+    binary_option.save(binary_option_account_info)?;
     Ok(())
 }
 
 pub fn process_settle(_program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
-    // This should NEVER be called directly (otherwise this is literally a rug)
-    // The `pool_owner_info` needs to approve this action, so the recommended use
-    // case is to have a higher level program own the pool and use an oracle to
-    // resolve settlements
     let account_info_iter = &mut accounts.iter();
     let binary_option_account_info = next_account_info(account_info_iter)?;
-    let winning_mint_account_info = next_account_info(account_info_iter)?;
-    let pool_owner_info = next_account_info(account_info_iter)?;
 
-    let mut binary_option =
-        BinaryOption::try_from_slice(&binary_option_account_info.data.borrow_mut())?;
-    if !pool_owner_info.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
-    }
+    let mut binary_option = BinaryOption::from_account_info(binary_option_account_info)?;
     if binary_option.settled {
         return Err(BinaryOptionError::AlreadySettled.into());
     }
+    let now = Clock::get()?.unix_timestamp;
 
-    assert_keys_equal(*pool_owner_info.key, binary_option.owner)?;
-    if *winning_mint_account_info.key == binary_option.long_mint_account_pubkey
-        || *winning_mint_account_info.key == binary_option.short_mint_account_pubkey
+    // The oracle account, or the winning mint if the owner or the arbiter
+    // settles
+    let settlement_info = next_account_info(account_info_iter)?;
+    if binary_option.is_oracle_settled()
+        && !binary_option.disputed
+        && *settlement_info.key == binary_option.oracle
     {
-        binary_option.winning_side_pubkey = *winning_mint_account_info.key;
+        // Anyone can settle once the option has expired, from an oracle price
+        // published at most `max_staleness` seconds after expiry
+        let oracle_info = settlement_info;
+        assert_owned_by(oracle_info, &binary_option.oracle_program)?;
+        if now < binary_option.expiry {
+            return Err(BinaryOptionError::OptionNotExpired.into());
+        }
+        let oracle_price = OraclePrice::from_account_info(oracle_info)?;
+        let latest_publish_time = binary_option.settlement_window_end()?;
+        if oracle_price.publish_time < binary_option.expiry
+            || oracle_price.publish_time > latest_publish_time
+        {
+            return Err(BinaryOptionError::StaleOraclePrice.into());
+        }
+        msg!(
+            "Oracle price {} against strike price {}",
+            oracle_price.price,
+            binary_option.strike_price
+        );
+        binary_option.winning_side_pubkey = if oracle_price.price > binary_option.strike_price {
+            binary_option.long_mint_account_pubkey
+        } else {
+            binary_option.short_mint_account_pubkey
+        };
+        binary_option.settle_price = oracle_price.price;
     } else {
-        return Err(BinaryOptionError::InvalidWinner.into());
+        // This should NEVER be called directly (otherwise this is literally a rug)
+        // The `pool_owner_info` needs to approve this action, so the recommended use
+        // case is to have a higher level program own the pool and use an oracle to
+        // resolve settlements. Oracle-settled options are resolved by the
+        // arbiter instead, once disputed or if no oracle price settled them
+        // before the latest publish time
+        let winning_mint_account_info = settlement_info;
+        let pool_owner_info = next_account_info(account_info_iter)?;
+        if !pool_owner_info.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if binary_option.is_oracle_settled() {
+            assert_keys_equal(*pool_owner_info.key, binary_option.arbiter)?;
+            if !binary_option.disputed && now <= binary_option.settlement_window_end()? {
+                return Err(BinaryOptionError::SettlementWindowOpen.into());
+            }
+            binary_option.arbitrated = true;
+        } else {
+            assert_keys_equal(*pool_owner_info.key, binary_option.owner)?;
+        }
+        if *winning_mint_account_info.key == binary_option.long_mint_account_pubkey
+            || *winning_mint_account_info.key == binary_option.short_mint_account_pubkey
+        {
+            binary_option.winning_side_pubkey = *winning_mint_account_info.key;
+        } else {
+            return Err(BinaryOptionError::InvalidWinner.into());
+        }
     }
     binary_option.settled = true;
-    borsh::to_writer(
-        &mut binary_option_account_info.data.borrow_mut()[..],
-        &binary_option,
-    )?;
+    if binary_option.is_oracle_settled() {
+        binary_option.settled_at = now;
+    }
+    binary_option.save(binary_option_account_info)?;
+    Ok(())
+}
+
+pub fn process_dispute(_program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let binary_option_account_info = next_account_info(account_info_iter)?;
+    let disputer_info = next_account_info(account_info_iter)?;
+
+    let mut binary_option = BinaryOption::from_account_info(binary_option_account_info)?;
+    if !disputer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Besides the owner, anyone with a stake in the outcome can dispute
+    if *disputer_info.key != binary_option.owner {
+        let position_account_info = next_account_info(account_info_iter)?;
+        assert_owned_by(position_account_info, &spl_token::id())?;
+        let position_account: Account = assert_initialized(position_account_info)?;
+        assert_keys_equal(position_account.owner, *disputer_info.key)?;
+        if position_account.amount == 0
+            || (position_account.mint != binary_option.long_mint_account_pubkey
+                && position_account.mint != binary_option.short_mint_account_pubkey)
+        {
+            return Err(BinaryOptionError::NoPositionHeld.into());
+        }
+    }
+    if !binary_option.is_oracle_settled() {
+        return Err(BinaryOptionError::NotOracleSettled.into());
+    }
+    if !binary_option.settled {
+        return Err(BinaryOptionError::BetNotSettled.into());
+    }
+    // A single dispute hands the settlement over to the arbiter
+    if binary_option.disputed {
+        return Err(BinaryOptionError::AlreadyDisputed.into());
+    }
+    if Clock::get()?.unix_timestamp >= binary_option.dispute_window_end()? {
+        return Err(BinaryOptionError::DisputeWindowClosed.into());
+    }
+
+    binary_option.disputed = true;
+    binary_option.settled = false;
+    binary_option.settled_at = 0;
+    binary_option.settle_price = 0;
+    binary_option.winning_side_pubkey = Pubkey::default();
+    binary_option.save(binary_option_account_info)?;
     Ok(())
 }
 
@@ -642,8 +809,7 @@ pub fn process_collect(program_id: &Pubkey, accounts: &[AccountInfo]) -> Program
         assert_initialized(collector_short_token_account_info)?;
     let collector_account: Account = assert_initialized(collector_account_info)?;
     let escrow_account: Account = assert_initialized(escrow_account_info)?;
-    let mut binary_option =
-        BinaryOption::try_from_slice(&binary_option_account_info.data.borrow_mut())?;
+    let mut binary_option = BinaryOption::from_account_info(binary_option_account_info)?;
 
     // Get program derived address for escrow
     let (escrow_owner_key, bump_seed) = Pubkey::find_program_address(
@@ -666,6 +832,9 @@ pub fn process_collect(program_id: &Pubkey, accounts: &[AccountInfo]) -> Program
     if !binary_option.settled {
         return Err(BinaryOptionError::BetNotSettled.into());
     }
+    if Clock::get()?.unix_timestamp < binary_option.dispute_window_end()? {
+        return Err(BinaryOptionError::DisputeWindowOpen.into());
+    }
     assert_owned_by(long_token_mint_info, &spl_token::id())?;
     assert_owned_by(short_token_mint_info, &spl_token::id())?;
     assert_keys_equal(collector_long_token_account.owner, *collector_info.key)?;
@@ -736,9 +905,390 @@ pub fn process_collect(program_id: &Pubkey, accounts: &[AccountInfo]) -> Program
         )?;
         binary_option.decrement_supply(reward)?;
     }
+    binary_option.save(binary_option_account_info)?;
+    Ok(())
+}
+
+pub fn process_place_order(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: PlaceOrderArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let binary_option_account_info = next_account_info(account_info_iter)?;
+    let order_account_info = next_account_info(account_info_iter)?;
+    let order_escrow_account_info = next_account_info(account_info_iter)?;
+    let escrow_mint_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let collateral_account_info = next_account_info(account_info_iter)?;
+    let position_account_info = next_account_info(account_info_iter)?;
+    let position_mint_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    assert_owned_by(binary_option_account_info, program_id)?;
+    let binary_option = BinaryOption::from_account_info(binary_option_account_info)?;
+    let collateral_account: Account = assert_initialized(collateral_account_info)?;
+    let position_account: Account = assert_initialized(position_account_info)?;
+    let position_mint: Mint = assert_initialized(position_mint_info)?;
+
+    let (authority_key, _) = Pubkey::find_program_address(
+        &[
+            binary_option.long_mint_account_pubkey.as_ref(),
+            binary_option.short_mint_account_pubkey.as_ref(),
+            token_program_info.key.as_ref(),
+            program_id.as_ref(),
+        ],
+        program_id,
+    );
+
+    // Validate data
+    let unit = u64::pow(10, binary_option.decimals as u32);
+    if args.price == 0 || args.price >= unit || args.size == 0 {
+        return Err(BinaryOptionError::InvalidOrder.into());
+    }
+    assert_trading_open(&binary_option)?;
+    if !owner_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let position_mint_key = match args.side {
+        OrderSide::Bid => binary_option.long_mint_account_pubkey,
+        OrderSide::Ask => binary_option.short_mint_account_pubkey,
+    };
+    assert_keys_equal(*token_program_info.key, spl_token::id())?;
+    assert_keys_equal(authority_key, *authority_info.key)?;
+    assert_keys_equal(*position_mint_info.key, position_mint_key)?;
+    assert_keys_equal(position_account.mint, position_mint_key)?;
+    assert_keys_equal(position_account.owner, *owner_info.key)?;
+    assert_keys_equal(
+        *escrow_mint_info.key,
+        binary_option.escrow_mint_account_pubkey,
+    )?;
+    assert_keys_equal(
+        collateral_account.mint,
+        binary_option.escrow_mint_account_pubkey,
+    )?;
+    assert_keys_equal(collateral_account.owner, *owner_info.key)?;
+
+    let order = Order {
+        binary_option: *binary_option_account_info.key,
+        owner: *owner_info.key,
+        side: args.side,
+        price: args.price,
+        size: args.size,
+        escrow_account_pubkey: *order_escrow_account_info.key,
+        collateral_account_pubkey: *collateral_account_info.key,
+        position_account_pubkey: *position_account_info.key,
+    };
+    let collateral = order
+        .unit_collateral(unit)
+        .checked_mul(args.size)
+        .ok_or(BinaryOptionError::AmountOverflow)?;
+
+    // The order collateral is kept apart from the option escrow, which only
+    // backs the contracts in circulation
+    create_new_account(
+        owner_info,
+        order_escrow_account_info,
+        Account::LEN,
+        token_program_info,
+        rent_info,
+    )?;
+    spl_initialize(
+        token_program_info,
+        order_escrow_account_info,
+        escrow_mint_info,
+        authority_info,
+        rent_info,
+    )?;
+    spl_token_transfer(
+        token_program_info,
+        collateral_account_info,
+        order_escrow_account_info,
+        owner_info,
+        collateral,
+    )?;
+    // Matching happens without the owner, so the burn authority needed on
+    // collection is delegated up front
+    spl_approve(
+        token_program_info,
+        position_account_info,
+        position_mint_info,
+        authority_info,
+        owner_info,
+        u64::MAX,
+        position_mint.decimals,
+    )?;
+
+    create_or_allocate_account_raw(
+        *program_id,
+        order_account_info,
+        rent_info,
+        system_account_info,
+        owner_info,
+        Order::LEN,
+    )?;
+    borsh::to_writer(&mut order_account_info.data.borrow_mut()[..], &order)?;
+    Ok(())
+}
+
+pub fn process_cancel_order(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let binary_option_account_info = next_account_info(account_info_iter)?;
+    let order_account_info = next_account_info(account_info_iter)?;
+    let order_escrow_account_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let collateral_account_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    assert_owned_by(binary_option_account_info, program_id)?;
+    assert_owned_by(order_account_info, program_id)?;
+    let binary_option = BinaryOption::from_account_info(binary_option_account_info)?;
+    let order = Order::from_account_info(order_account_info)?;
+    let order_escrow_account: Account = assert_initialized(order_escrow_account_info)?;
+
+    let (authority_key, bump_seed) = Pubkey::find_program_address(
+        &[
+            binary_option.long_mint_account_pubkey.as_ref(),
+            binary_option.short_mint_account_pubkey.as_ref(),
+            token_program_info.key.as_ref(),
+            program_id.as_ref(),
+        ],
+        program_id,
+    );
+    let seeds = &[
+        binary_option.long_mint_account_pubkey.as_ref(),
+        binary_option.short_mint_account_pubkey.as_ref(),
+        token_program_info.key.as_ref(),
+        program_id.as_ref(),
+        &[bump_seed],
+    ];
+
+    if !owner_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    assert_keys_equal(*token_program_info.key, spl_token::id())?;
+    assert_keys_equal(authority_key, *authority_info.key)?;
+    assert_keys_equal(order.binary_option, *binary_option_account_info.key)?;
+    assert_keys_equal(order.owner, *owner_info.key)?;
+    assert_keys_equal(order.escrow_account_pubkey, *order_escrow_account_info.key)?;
+    assert_keys_equal(
+        order.collateral_account_pubkey,
+        *collateral_account_info.key,
+    )?;
+
+    spl_token_transfer_signed(
+        token_program_info,
+        order_escrow_account_info,
+        collateral_account_info,
+        authority_info,
+        order_escrow_account.amount,
+        seeds,
+    )?;
+    spl_close_account_signed(
+        token_program_info,
+        order_escrow_account_info,
+        owner_info,
+        authority_info,
+        seeds,
+    )?;
+
+    let order_lamports = order_account_info.lamports();
+    **order_account_info.lamports.borrow_mut() = 0;
+    **owner_info.lamports.borrow_mut() = owner_info
+        .lamports()
+        .checked_add(order_lamports)
+        .ok_or(BinaryOptionError::AmountOverflow)?;
+    order_account_info.data.borrow_mut().fill(0);
+    Ok(())
+}
+
+pub fn process_match_orders(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let binary_option_account_info = next_account_info(account_info_iter)?;
+    let escrow_account_info = next_account_info(account_info_iter)?;
+    let long_token_mint_info = next_account_info(account_info_iter)?;
+    let short_token_mint_info = next_account_info(account_info_iter)?;
+    let bid_order_account_info = next_account_info(account_info_iter)?;
+    let bid_order_escrow_account_info = next_account_info(account_info_iter)?;
+    let bid_position_account_info = next_account_info(account_info_iter)?;
+    let ask_order_account_info = next_account_info(account_info_iter)?;
+    let ask_order_escrow_account_info = next_account_info(account_info_iter)?;
+    let ask_position_account_info = next_account_info(account_info_iter)?;
+    let ask_collateral_account_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    assert_owned_by(binary_option_account_info, program_id)?;
+    assert_owned_by(bid_order_account_info, program_id)?;
+    assert_owned_by(ask_order_account_info, program_id)?;
+    let mut binary_option = BinaryOption::from_account_info(binary_option_account_info)?;
+    let mut bid = Order::from_account_info(bid_order_account_info)?;
+    let mut ask = Order::from_account_info(ask_order_account_info)?;
+
+    // Get program derived address for escrow
+    let (authority_key, bump_seed) = Pubkey::find_program_address(
+        &[
+            long_token_mint_info.key.as_ref(),
+            short_token_mint_info.key.as_ref(),
+            token_program_info.key.as_ref(),
+            program_id.as_ref(),
+        ],
+        program_id,
+    );
+    let seeds = &[
+        long_token_mint_info.key.as_ref(),
+        short_token_mint_info.key.as_ref(),
+        token_program_info.key.as_ref(),
+        program_id.as_ref(),
+        &[bump_seed],
+    ];
+
+    // Validate data
+    assert_trading_open(&binary_option)?;
+    if bid.side != OrderSide::Bid || ask.side != OrderSide::Ask {
+        return Err(BinaryOptionError::InvalidOrder.into());
+    }
+    if bid.price < ask.price {
+        return Err(BinaryOptionError::OrdersDoNotCross.into());
+    }
+    assert_keys_equal(*token_program_info.key, spl_token::id())?;
+    assert_keys_equal(authority_key, *authority_info.key)?;
+    assert_keys_equal(
+        *long_token_mint_info.key,
+        binary_option.long_mint_account_pubkey,
+    )?;
+    assert_keys_equal(
+        *short_token_mint_info.key,
+        binary_option.short_mint_account_pubkey,
+    )?;
+    assert_keys_equal(
+        *escrow_account_info.key,
+        binary_option.escrow_account_pubkey,
+    )?;
+    assert_keys_equal(bid.binary_option, *binary_option_account_info.key)?;
+    assert_keys_equal(ask.binary_option, *binary_option_account_info.key)?;
+    assert_keys_equal(
+        bid.escrow_account_pubkey,
+        *bid_order_escrow_account_info.key,
+    )?;
+    assert_keys_equal(bid.position_account_pubkey, *bid_position_account_info.key)?;
+    assert_keys_equal(
+        ask.escrow_account_pubkey,
+        *ask_order_escrow_account_info.key,
+    )?;
+    assert_keys_equal(ask.position_account_pubkey, *ask_position_account_info.key)?;
+    assert_keys_equal(
+        ask.collateral_account_pubkey,
+        *ask_collateral_account_info.key,
+    )?;
+
+    let n = bid.size.min(ask.size);
+    if n == 0 {
+        return Err(BinaryOptionError::InvalidOrder.into());
+    }
+    // Both sides trade at the bid price, so the ask keeps the price
+    // improvement
+    let unit = u64::pow(10, binary_option.decimals as u32);
+    let bid_collateral = n
+        .checked_mul(bid.price)
+        .ok_or(BinaryOptionError::AmountOverflow)?;
+    let ask_collateral = n
+        .checked_mul(unit - bid.price)
+        .ok_or(BinaryOptionError::AmountOverflow)?;
+    let ask_refund = n
+        .checked_mul(bid.price - ask.price)
+        .ok_or(BinaryOptionError::AmountOverflow)?;
+
+    spl_token_transfer_signed(
+        token_program_info,
+        bid_order_escrow_account_info,
+        escrow_account_info,
+        authority_info,
+        bid_collateral,
+        seeds,
+    )?;
+    spl_token_transfer_signed(
+        token_program_info,
+        ask_order_escrow_account_info,
+        escrow_account_info,
+        authority_info,
+        ask_collateral,
+        seeds,
+    )?;
+    spl_token_transfer_signed(
+        token_program_info,
+        ask_order_escrow_account_info,
+        ask_collateral_account_info,
+        authority_info,
+        ask_refund,
+        seeds,
+    )?;
+    spl_mint_to(
+        token_program_info,
+        bid_position_account_info,
+        long_token_mint_info,
+        authority_info,
+        n,
+        seeds,
+    )?;
+    spl_mint_to(
+        token_program_info,
+        ask_position_account_info,
+        short_token_mint_info,
+        authority_info,
+        n,
+        seeds,
+    )?;
+    binary_option.increment_supply(n)?;
+    bid.size -= n;
+    ask.size -= n;
+
+    binary_option.save(binary_option_account_info)?;
+    borsh::to_writer(&mut bid_order_account_info.data.borrow_mut()[..], &bid)?;
+    borsh::to_writer(&mut ask_order_account_info.data.borrow_mut()[..], &ask)?;
+    Ok(())
+}
+
+pub fn process_set_arbiter(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: SetArbiterArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let arbiter_config_info = next_account_info(account_info_iter)?;
+    let program_data_info = next_account_info(account_info_iter)?;
+    let upgrade_authority_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    assert_upgrade_authority(program_id, program_data_info, upgrade_authority_info)?;
+    assert_keys_unequal(args.arbiter, Pubkey::default())?;
+    let (arbiter_config_key, bump_seed) = ArbiterConfig::find_address(program_id);
+    assert_keys_equal(*arbiter_config_info.key, arbiter_config_key)?;
+
+    if arbiter_config_info.data_is_empty() {
+        create_or_allocate_account_signed(
+            *program_id,
+            arbiter_config_info,
+            rent_info,
+            system_account_info,
+            upgrade_authority_info,
+            ArbiterConfig::LEN,
+            &[ArbiterConfig::SEED, &[bump_seed]],
+        )?;
+    } else {
+        assert_owned_by(arbiter_config_info, program_id)?;
+    }
+    let arbiter_config = ArbiterConfig {
+        arbiter: args.arbiter,
+    };
     borsh::to_writer(
-        &mut binary_option_account_info.data.borrow_mut()[..],
-        &binary_option,
+        &mut arbiter_config_info.data.borrow_mut()[..],
+        &arbiter_config,
     )?;
     Ok(())
 }
@@ -7,8 +7,8 @@ use {
         pubkey::Pubkey,
     },
     spl_token::instruction::{
-        approve_checked, burn, initialize_account, initialize_mint, mint_to, set_authority,
-        transfer, AuthorityType,
+        approve_checked, burn, close_account, initialize_account, initialize_mint, mint_to,
+        set_authority, transfer, AuthorityType,
     },
 };
 
@@ -262,3 +262,30 @@ pub fn spl_set_authority<'a>(
     )?;
     Ok(())
 }
+
+pub fn spl_close_account_signed<'a>(
+    token_program: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    pda_account: &AccountInfo<'a>,
+    signers: &[&[u8]],
+) -> ProgramResult {
+    let ix = close_account(
+        token_program.key,
+        account.key,
+        destination.key,
+        pda_account.key,
+        &[],
+    )?;
+    invoke_signed(
+        &ix,
+        &[
+            account.clone(),
+            destination.clone(),
+            pda_account.clone(),
+            token_program.clone(),
+        ],
+        &[signers],
+    )?;
+    Ok(())
+}
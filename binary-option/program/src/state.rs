@@ -19,16 +19,86 @@ pub struct BinaryOption {
     pub short_mint_account_pubkey: Pubkey,
     pub owner: Pubkey,
     pub winning_side_pubkey: Pubkey,
+    /// Price oracle settling the option, or the default pubkey if the owner
+    /// settles it
+    pub oracle: Pubkey,
+    /// The long side wins if the oracle price is above the strike price
+    pub strike_price: i64,
+    /// Unix timestamp after which trading stops and the option can be settled
+    pub expiry: i64,
+    /// Seconds after settlement during which the owner may dispute it and
+    /// collection stays closed
+    pub dispute_window: i64,
+    pub settled_at: i64,
+    pub settle_price: i64,
+    /// Seconds after expiry during which an oracle price can settle the option
+    pub max_staleness: i64,
+    /// Settles the option for good once its oracle settlement was disputed,
+    /// or once no oracle price settled it in time, copied from the arbiter
+    /// config when the option is created
+    pub arbiter: Pubkey,
+    pub disputed: bool,
+    /// Program owning the oracle account, checked on every oracle settlement
+    pub oracle_program: Pubkey,
+    /// Set once the arbiter settled the option
+    pub arbitrated: bool,
 }
 
 impl BinaryOption {
-    pub const LEN: usize = 202;
+    pub const LEN: usize = 348;
+    /// Size of the options created before oracle settlement, which end with
+    /// `winning_side_pubkey`
+    pub const LEGACY_LEN: usize = 202;
 
+    /// Deserializes the option, reading the fields missing from legacy
+    /// accounts as zero
     pub fn from_account_info(a: &AccountInfo) -> Result<BinaryOption, ProgramError> {
-        let binary_option = BinaryOption::try_from_slice(&a.data.borrow_mut())?;
+        let data = a.data.borrow();
+        let binary_option = if data.len() < BinaryOption::LEN {
+            let mut padded = data.to_vec();
+            padded.resize(BinaryOption::LEN, 0);
+            BinaryOption::try_from_slice(&padded)?
+        } else {
+            BinaryOption::try_from_slice(&data)?
+        };
         Ok(binary_option)
     }
 
+    /// Serializes the option into the account, which must be large enough to
+    /// hold every field that isn't zero
+    pub fn save(&self, a: &AccountInfo) -> ProgramResult {
+        let bytes = borsh::to_vec(self)?;
+        let mut data = a.data.borrow_mut();
+        let len = data.len().min(bytes.len());
+        if bytes[len..].iter().any(|b| *b != 0) {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        data[..len].copy_from_slice(&bytes[..len]);
+        Ok(())
+    }
+
+    pub fn is_oracle_settled(&self) -> bool {
+        self.oracle != Pubkey::default()
+    }
+
+    /// Latest publish time of an oracle price settling the option, after
+    /// which the arbiter settles it instead
+    pub fn settlement_window_end(&self) -> Result<i64, ProgramError> {
+        self.expiry
+            .checked_add(self.max_staleness)
+            .ok_or_else(|| BinaryOptionError::AmountOverflow.into())
+    }
+
+    /// The arbiter's settlement can't be disputed
+    pub fn dispute_window_end(&self) -> Result<i64, ProgramError> {
+        if self.arbitrated {
+            return Ok(self.settled_at);
+        }
+        self.settled_at
+            .checked_add(self.dispute_window)
+            .ok_or_else(|| BinaryOptionError::AmountOverflow.into())
+    }
+
     pub fn increment_supply(&mut self, n: u64) -> ProgramResult {
         self.circulation = self
             .circulation
@@ -45,3 +115,94 @@ impl BinaryOption {
         Ok(())
     }
 }
+
+/// Arbiter of the oracle-settled options, chosen by the upgrade authority of
+/// the program so that option owners can't pick their own
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ArbiterConfig {
+    pub arbiter: Pubkey,
+}
+
+impl ArbiterConfig {
+    pub const LEN: usize = 32;
+    pub const SEED: &'static [u8] = b"arbiter";
+
+    pub fn find_address(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[ArbiterConfig::SEED], program_id)
+    }
+
+    pub fn from_account_info(a: &AccountInfo) -> Result<ArbiterConfig, ProgramError> {
+        let arbiter_config = ArbiterConfig::try_from_slice(&a.data.borrow())?;
+        Ok(arbiter_config)
+    }
+}
+
+/// Latest price published by an oracle account, read from the start of its
+/// data. Only accounts of the oracle program recorded in the option are read
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct OraclePrice {
+    pub price: i64,
+    pub publish_time: i64,
+}
+
+impl OraclePrice {
+    pub const LEN: usize = 16;
+
+    pub fn from_account_info(a: &AccountInfo) -> Result<OraclePrice, ProgramError> {
+        let data = a.data.borrow();
+        let oracle_price = OraclePrice::deserialize(&mut &data[..])?;
+        Ok(oracle_price)
+    }
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderSide {
+    /// Buys long tokens at the order price
+    Bid,
+    /// Sells long tokens at the order price, i.e. buys short tokens at its
+    /// complement
+    Ask,
+}
+
+/// Resting order for long or short tokens of a binary option, whose
+/// collateral is held in `escrow_account_pubkey` until it is matched or
+/// cancelled
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Order {
+    pub binary_option: Pubkey,
+    pub owner: Pubkey,
+    pub side: OrderSide,
+    /// Price of one long token, in escrow mint base units
+    pub price: u64,
+    /// Number of contracts left to fill
+    pub size: u64,
+    pub escrow_account_pubkey: Pubkey,
+    /// Escrow mint account of the owner, which funded the order and receives
+    /// the refunds
+    pub collateral_account_pubkey: Pubkey,
+    /// Long token account of a bid, or short token account of an ask, that
+    /// receives the filled contracts
+    pub position_account_pubkey: Pubkey,
+}
+
+impl Order {
+    pub const LEN: usize = 177;
+
+    pub fn from_account_info(a: &AccountInfo) -> Result<Order, ProgramError> {
+        let order = Order::try_from_slice(&a.data.borrow_mut())?;
+        Ok(order)
+    }
+
+    /// Collateral locked for each contract of the order, given the option's
+    /// unit price `10^decimals`
+    pub fn unit_collateral(&self, unit: u64) -> u64 {
+        match self.side {
+            OrderSide::Bid => self.price,
+            OrderSide::Ask => unit - self.price,
+        }
+    }
+}
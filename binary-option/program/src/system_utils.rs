@@ -3,7 +3,7 @@ use {
         account_info::AccountInfo,
         entrypoint::ProgramResult,
         msg,
-        program::invoke,
+        program::{invoke, invoke_signed},
         pubkey::Pubkey,
         system_instruction,
         sysvar::{rent::Rent, Sysvar},
@@ -97,3 +97,39 @@ pub fn create_or_allocate_account_raw<'a>(
 
     Ok(())
 }
+
+/// Same as `create_or_allocate_account_raw`, for an account at a program
+/// derived address of `program_id`
+#[inline(always)]
+pub fn create_or_allocate_account_signed<'a>(
+    program_id: Pubkey,
+    new_account_info: &AccountInfo<'a>,
+    rent_sysvar_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    size: usize,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    topup(
+        new_account_info,
+        rent_sysvar_info,
+        system_program_info,
+        payer_info,
+        size,
+    )?;
+    msg!("Allocate space for the account");
+    invoke_signed(
+        &system_instruction::allocate(new_account_info.key, size.try_into().unwrap()),
+        &[new_account_info.clone(), system_program_info.clone()],
+        &[signer_seeds],
+    )?;
+
+    msg!("Assign the account to the owning program");
+    invoke_signed(
+        &system_instruction::assign(new_account_info.key, &program_id),
+        &[new_account_info.clone(), system_program_info.clone()],
+        &[signer_seeds],
+    )?;
+
+    Ok(())
+}
@@ -2,6 +2,7 @@ use {
     crate::error::BinaryOptionError,
     solana_program::{
         account_info::AccountInfo,
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
         entrypoint::ProgramResult,
         program_error::ProgramError,
         program_pack::{IsInitialized, Pack},
@@ -45,3 +46,35 @@ pub fn assert_owned_by(account: &AccountInfo, owner: &Pubkey) -> ProgramResult {
         Ok(())
     }
 }
+
+/// assert signed by the upgrade authority of the program, given its program
+/// data account
+pub fn assert_upgrade_authority(
+    program_id: &Pubkey,
+    program_data_info: &AccountInfo,
+    authority_info: &AccountInfo,
+) -> ProgramResult {
+    let (program_data_key, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    assert_keys_equal(*program_data_info.key, program_data_key)?;
+    assert_owned_by(program_data_info, &bpf_loader_upgradeable::id())?;
+    if !authority_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // Bincode layout of `UpgradeableLoaderState::ProgramData`: the variant
+    // index, the deployment slot and the optional upgrade authority
+    let data = program_data_info.data.borrow();
+    let metadata = data
+        .get(..UpgradeableLoaderState::size_of_programdata_metadata())
+        .ok_or(ProgramError::InvalidAccountData)?;
+    let (variant, rest) = metadata.split_at(4);
+    let (has_authority, authority) = rest[8..].split_at(1);
+    if variant != 3u32.to_le_bytes()
+        || has_authority[0] != 1
+        || authority != authority_info.key.as_ref()
+    {
+        return Err(BinaryOptionError::NotUpgradeAuthority.into());
+    }
+    Ok(())
+}
@@ -0,0 +1,1141 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    binary_option::{
+        error::BinaryOptionError,
+        instruction::{
+            cancel_order, collect, dispute, initialize_oracle_binary_option, match_orders,
+            place_order, set_arbiter, settle, settle_with_oracle, trade,
+        },
+        processor::Processor,
+        state::{ArbiterConfig, BinaryOption, OraclePrice, Order, OrderSide},
+    },
+    borsh::BorshDeserialize,
+    solana_program_test::{
+        processor, tokio, ProgramTest, ProgramTestBanksClientExt, ProgramTestContext,
+    },
+    solana_sdk::{
+        account::Account as SolanaAccount,
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+        clock::Clock,
+        instruction::{Instruction, InstructionError},
+        program_pack::Pack,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        system_instruction,
+        transaction::{Transaction, TransactionError},
+    },
+    spl_token::state::{Account, Mint},
+};
+
+const DECIMALS: u8 = 2;
+const STRIKE_PRICE: i64 = 100;
+const DISPUTE_WINDOW: i64 = 60;
+const MAX_STALENESS: i64 = 30;
+const ORACLE_PROGRAM_ID: Pubkey = Pubkey::new_from_array([3; 32]);
+
+struct Trader {
+    wallet: Keypair,
+    escrow_account: Pubkey,
+    long_account: Pubkey,
+    short_account: Pubkey,
+}
+
+struct TestOption {
+    pool: Pubkey,
+    escrow_mint: Pubkey,
+    escrow: Pubkey,
+    long_mint: Pubkey,
+    short_mint: Pubkey,
+    escrow_authority: Pubkey,
+    oracle: Pubkey,
+    arbiter: Keypair,
+    expiry: i64,
+}
+
+fn program_test(oracle: &Pubkey) -> ProgramTest {
+    let mut program_test = ProgramTest::new(
+        "binary_option",
+        binary_option::id(),
+        processor!(Processor::process),
+    );
+    program_test.add_account(*oracle, oracle_account(0, 0));
+    program_test
+}
+
+fn oracle_account(price: i64, publish_time: i64) -> SolanaAccount {
+    SolanaAccount {
+        lamports: 1_000_000_000,
+        data: borsh::to_vec(&OraclePrice {
+            price,
+            publish_time,
+        })
+        .unwrap(),
+        owner: ORACLE_PROGRAM_ID,
+        ..SolanaAccount::default()
+    }
+}
+
+async fn process_instructions(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), TransactionError> {
+    let blockhash = context
+        .banks_client
+        .get_new_latest_blockhash(&context.last_blockhash)
+        .await
+        .unwrap();
+    context.last_blockhash = blockhash;
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|e| e.unwrap())
+}
+
+fn custom_error(error: BinaryOptionError) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+}
+
+async fn set_clock(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    context.set_sysvar(&clock);
+}
+
+/// Makes the payer the upgrade authority of the program, which the program
+/// test doesn't deploy through the upgradeable loader
+fn set_upgrade_authority(context: &mut ProgramTestContext) {
+    let (program_data, _) = Pubkey::find_program_address(
+        &[binary_option::id().as_ref()],
+        &bpf_loader_upgradeable::id(),
+    );
+    let program_data_account = SolanaAccount::new_data(
+        1_000_000_000,
+        &UpgradeableLoaderState::ProgramData {
+            slot: 0,
+            upgrade_authority_address: Some(context.payer.pubkey()),
+        },
+        &bpf_loader_upgradeable::id(),
+    )
+    .unwrap();
+    context.set_account(&program_data, &program_data_account.into());
+}
+
+async fn create_token_account(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    owner: &Pubkey,
+) -> Pubkey {
+    let account = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    process_instructions(
+        context,
+        &[
+            system_instruction::create_account(
+                &context.payer.pubkey(),
+                &account.pubkey(),
+                rent.minimum_balance(Account::LEN),
+                Account::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_account(
+                &spl_token::id(),
+                &account.pubkey(),
+                mint,
+                owner,
+            )
+            .unwrap(),
+        ],
+        &[&account],
+    )
+    .await
+    .unwrap();
+    account.pubkey()
+}
+
+async fn create_option(context: &mut ProgramTestContext, oracle: &Pubkey) -> TestOption {
+    let escrow_mint = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    process_instructions(
+        context,
+        &[
+            system_instruction::create_account(
+                &context.payer.pubkey(),
+                &escrow_mint.pubkey(),
+                rent.minimum_balance(Mint::LEN),
+                Mint::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_mint(
+                &spl_token::id(),
+                &escrow_mint.pubkey(),
+                &context.payer.pubkey(),
+                None,
+                DECIMALS,
+            )
+            .unwrap(),
+        ],
+        &[&escrow_mint],
+    )
+    .await
+    .unwrap();
+
+    let pool = Keypair::new();
+    let escrow = Keypair::new();
+    let long_mint = Keypair::new();
+    let short_mint = Keypair::new();
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    let expiry = clock.unix_timestamp + 100;
+    let payer = context.payer.pubkey();
+    let arbiter = Keypair::new();
+    set_upgrade_authority(context);
+    process_instructions(
+        context,
+        &[set_arbiter(binary_option::id(), payer, arbiter.pubkey())],
+        &[],
+    )
+    .await
+    .unwrap();
+    process_instructions(
+        context,
+        &[initialize_oracle_binary_option(
+            binary_option::id(),
+            pool.pubkey(),
+            escrow_mint.pubkey(),
+            escrow.pubkey(),
+            long_mint.pubkey(),
+            short_mint.pubkey(),
+            payer,
+            payer,
+            *oracle,
+            DECIMALS,
+            STRIKE_PRICE,
+            expiry,
+            DISPUTE_WINDOW,
+            MAX_STALENESS,
+            ORACLE_PROGRAM_ID,
+        )],
+        &[&pool, &escrow, &long_mint, &short_mint],
+    )
+    .await
+    .unwrap();
+
+    let (escrow_authority, _) = Pubkey::find_program_address(
+        &[
+            long_mint.pubkey().as_ref(),
+            short_mint.pubkey().as_ref(),
+            spl_token::id().as_ref(),
+            binary_option::id().as_ref(),
+        ],
+        &binary_option::id(),
+    );
+    TestOption {
+        pool: pool.pubkey(),
+        escrow_mint: escrow_mint.pubkey(),
+        escrow: escrow.pubkey(),
+        long_mint: long_mint.pubkey(),
+        short_mint: short_mint.pubkey(),
+        escrow_authority,
+        oracle: *oracle,
+        arbiter,
+        expiry,
+    }
+}
+
+async fn create_trader(context: &mut ProgramTestContext, option: &TestOption) -> Trader {
+    let wallet = Keypair::new();
+    let escrow_account = create_token_account(context, &option.escrow_mint, &wallet.pubkey()).await;
+    let long_account = create_token_account(context, &option.long_mint, &wallet.pubkey()).await;
+    let short_account = create_token_account(context, &option.short_mint, &wallet.pubkey()).await;
+    let payer = context.payer.pubkey();
+    process_instructions(
+        context,
+        &[spl_token::instruction::mint_to(
+            &spl_token::id(),
+            &option.escrow_mint,
+            &escrow_account,
+            &payer,
+            &[],
+            10_000,
+        )
+        .unwrap()],
+        &[],
+    )
+    .await
+    .unwrap();
+    Trader {
+        wallet,
+        escrow_account,
+        long_account,
+        short_account,
+    }
+}
+
+fn trade_instruction(
+    option: &TestOption,
+    buyer: &Trader,
+    seller: &Trader,
+    size: u64,
+) -> Instruction {
+    trade(
+        binary_option::id(),
+        option.pool,
+        option.escrow,
+        option.long_mint,
+        option.short_mint,
+        buyer.wallet.pubkey(),
+        seller.wallet.pubkey(),
+        buyer.escrow_account,
+        seller.escrow_account,
+        buyer.long_account,
+        buyer.short_account,
+        seller.long_account,
+        seller.short_account,
+        option.escrow_authority,
+        size,
+        30,
+        70,
+    )
+}
+
+fn settle_by(option: &TestOption, winning_mint: Pubkey, authority: Pubkey) -> Instruction {
+    settle(binary_option::id(), option.pool, winning_mint, authority)
+}
+
+fn collect_instruction(option: &TestOption, collector: &Trader, fee_payer: &Pubkey) -> Instruction {
+    collect(
+        binary_option::id(),
+        option.pool,
+        collector.wallet.pubkey(),
+        collector.long_account,
+        collector.short_account,
+        collector.escrow_account,
+        option.long_mint,
+        option.short_mint,
+        option.escrow,
+        option.escrow_authority,
+        *fee_payer,
+    )
+}
+
+struct TestOrder {
+    order: Pubkey,
+    escrow: Pubkey,
+}
+
+async fn place_test_order(
+    context: &mut ProgramTestContext,
+    option: &TestOption,
+    trader: &Trader,
+    side: OrderSide,
+    price: u64,
+    size: u64,
+) -> Result<TestOrder, TransactionError> {
+    let order = Keypair::new();
+    let escrow = Keypair::new();
+    let (position_account, position_mint) = match side {
+        OrderSide::Bid => (trader.long_account, option.long_mint),
+        OrderSide::Ask => (trader.short_account, option.short_mint),
+    };
+    let payer = context.payer.pubkey();
+    process_instructions(
+        context,
+        &[
+            system_instruction::transfer(&payer, &trader.wallet.pubkey(), 100_000_000),
+            place_order(
+                binary_option::id(),
+                option.pool,
+                order.pubkey(),
+                escrow.pubkey(),
+                option.escrow_mint,
+                trader.wallet.pubkey(),
+                trader.escrow_account,
+                position_account,
+                position_mint,
+                option.escrow_authority,
+                side,
+                price,
+                size,
+            ),
+        ],
+        &[&order, &escrow, &trader.wallet],
+    )
+    .await?;
+    Ok(TestOrder {
+        order: order.pubkey(),
+        escrow: escrow.pubkey(),
+    })
+}
+
+fn match_orders_instruction(
+    option: &TestOption,
+    bidder: &Trader,
+    bid: &TestOrder,
+    asker: &Trader,
+    ask: &TestOrder,
+) -> Instruction {
+    match_orders(
+        binary_option::id(),
+        option.pool,
+        option.escrow,
+        option.long_mint,
+        option.short_mint,
+        bid.order,
+        bid.escrow,
+        bidder.long_account,
+        ask.order,
+        ask.escrow,
+        asker.short_account,
+        asker.escrow_account,
+        option.escrow_authority,
+    )
+}
+
+fn cancel_order_instruction(option: &TestOption, owner: &Trader, order: &TestOrder) -> Instruction {
+    cancel_order(
+        binary_option::id(),
+        option.pool,
+        order.order,
+        order.escrow,
+        owner.wallet.pubkey(),
+        owner.escrow_account,
+        option.escrow_authority,
+    )
+}
+
+async fn get_order(context: &mut ProgramTestContext, order: &Pubkey) -> Order {
+    let account = context
+        .banks_client
+        .get_account(*order)
+        .await
+        .unwrap()
+        .unwrap();
+    Order::try_from_slice(&account.data).unwrap()
+}
+
+async fn get_binary_option(context: &mut ProgramTestContext, pool: &Pubkey) -> BinaryOption {
+    let account = context
+        .banks_client
+        .get_account(*pool)
+        .await
+        .unwrap()
+        .unwrap();
+    BinaryOption::try_from_slice(&account.data).unwrap()
+}
+
+async fn get_token_balance(context: &mut ProgramTestContext, account: &Pubkey) -> u64 {
+    let account = context
+        .banks_client
+        .get_account(*account)
+        .await
+        .unwrap()
+        .unwrap();
+    Account::unpack(&account.data).unwrap().amount
+}
+
+#[tokio::test]
+async fn success_oracle_settlement() {
+    let oracle = Pubkey::new_unique();
+    let mut context = program_test(&oracle).start_with_context().await;
+    let option = create_option(&mut context, &oracle).await;
+    let buyer = create_trader(&mut context, &option).await;
+    let seller = create_trader(&mut context, &option).await;
+
+    process_instructions(
+        &mut context,
+        &[trade_instruction(&option, &buyer, &seller, 10)],
+        &[&buyer.wallet, &seller.wallet],
+    )
+    .await
+    .unwrap();
+    assert_eq!(get_token_balance(&mut context, &option.escrow).await, 1000);
+
+    // nobody can settle before expiry
+    let settle = settle_with_oracle(binary_option::id(), option.pool, option.oracle);
+    assert_eq!(
+        process_instructions(&mut context, &[settle.clone()], &[])
+            .await
+            .unwrap_err(),
+        custom_error(BinaryOptionError::OptionNotExpired)
+    );
+
+    // trading closes at expiry
+    set_clock(&mut context, option.expiry).await;
+    assert_eq!(
+        process_instructions(
+            &mut context,
+            &[trade_instruction(&option, &buyer, &seller, 5)],
+            &[&buyer.wallet, &seller.wallet],
+        )
+        .await
+        .unwrap_err(),
+        custom_error(BinaryOptionError::OptionExpired)
+    );
+
+    // a price published before expiry cannot settle the option
+    context.set_account(&oracle, &oracle_account(150, option.expiry - 1).into());
+    assert_eq!(
+        process_instructions(&mut context, &[settle.clone()], &[])
+            .await
+            .unwrap_err(),
+        custom_error(BinaryOptionError::StaleOraclePrice)
+    );
+
+    // nor can a price published too long after expiry
+    context.set_account(
+        &oracle,
+        &oracle_account(150, option.expiry + MAX_STALENESS + 1).into(),
+    );
+    assert_eq!(
+        process_instructions(&mut context, &[settle.clone()], &[])
+            .await
+            .unwrap_err(),
+        custom_error(BinaryOptionError::StaleOraclePrice)
+    );
+
+    // nor can an account of another program holding the same data
+    let mut forged_oracle = oracle_account(150, option.expiry);
+    forged_oracle.owner = Pubkey::new_unique();
+    context.set_account(&oracle, &forged_oracle.into());
+    assert_eq!(
+        process_instructions(&mut context, &[settle.clone()], &[])
+            .await
+            .unwrap_err(),
+        custom_error(BinaryOptionError::IncorrectOwner)
+    );
+
+    // anyone settles from the oracle price, no owner signature required
+    context.set_account(&oracle, &oracle_account(150, option.expiry).into());
+    process_instructions(&mut context, &[settle.clone()], &[])
+        .await
+        .unwrap();
+    let binary_option = get_binary_option(&mut context, &option.pool).await;
+    assert!(binary_option.settled);
+    assert_eq!(binary_option.winning_side_pubkey, option.long_mint);
+    assert_eq!(binary_option.settle_price, 150);
+    assert_eq!(binary_option.settled_at, option.expiry);
+
+    // collection stays closed during the dispute window
+    let fee_payer = context.payer.pubkey();
+    assert_eq!(
+        process_instructions(
+            &mut context,
+            &[collect_instruction(&option, &buyer, &fee_payer)],
+            &[],
+        )
+        .await
+        .unwrap_err(),
+        custom_error(BinaryOptionError::DisputeWindowOpen)
+    );
+
+    set_clock(&mut context, option.expiry + DISPUTE_WINDOW).await;
+    for trader in [&buyer, &seller] {
+        process_instructions(
+            &mut context,
+            &[collect_instruction(&option, trader, &fee_payer)],
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+    assert_eq!(
+        get_token_balance(&mut context, &buyer.escrow_account).await,
+        10_000 - 300 + 1000
+    );
+    assert_eq!(
+        get_token_balance(&mut context, &seller.escrow_account).await,
+        10_000 - 700
+    );
+    assert_eq!(get_token_balance(&mut context, &option.escrow).await, 0);
+}
+
+#[tokio::test]
+async fn success_dispute() {
+    let oracle = Pubkey::new_unique();
+    let mut context = program_test(&oracle).start_with_context().await;
+    let option = create_option(&mut context, &oracle).await;
+    let buyer = create_trader(&mut context, &option).await;
+    let seller = create_trader(&mut context, &option).await;
+    process_instructions(
+        &mut context,
+        &[trade_instruction(&option, &buyer, &seller, 10)],
+        &[&buyer.wallet, &seller.wallet],
+    )
+    .await
+    .unwrap();
+
+    set_clock(&mut context, option.expiry).await;
+    context.set_account(&oracle, &oracle_account(150, option.expiry).into());
+    let settle = settle_with_oracle(binary_option::id(), option.pool, option.oracle);
+    process_instructions(&mut context, &[settle.clone()], &[])
+        .await
+        .unwrap();
+
+    // only the owner and position holders can dispute
+    let mallory = create_trader(&mut context, &option).await;
+    assert_eq!(
+        process_instructions(
+            &mut context,
+            &[dispute(
+                binary_option::id(),
+                option.pool,
+                mallory.wallet.pubkey(),
+                Some(mallory.short_account),
+            )],
+            &[&mallory.wallet],
+        )
+        .await
+        .unwrap_err(),
+        custom_error(BinaryOptionError::NoPositionHeld)
+    );
+    assert_eq!(
+        process_instructions(
+            &mut context,
+            &[dispute(
+                binary_option::id(),
+                option.pool,
+                mallory.wallet.pubkey(),
+                Some(seller.short_account),
+            )],
+            &[&mallory.wallet],
+        )
+        .await
+        .unwrap_err(),
+        custom_error(BinaryOptionError::PublicKeyMismatch)
+    );
+
+    process_instructions(
+        &mut context,
+        &[dispute(
+            binary_option::id(),
+            option.pool,
+            seller.wallet.pubkey(),
+            Some(seller.short_account),
+        )],
+        &[&seller.wallet],
+    )
+    .await
+    .unwrap();
+    let binary_option = get_binary_option(&mut context, &option.pool).await;
+    assert!(!binary_option.settled);
+    assert!(binary_option.disputed);
+    assert_eq!(binary_option.winning_side_pubkey, Pubkey::default());
+
+    // the oracle can no longer settle a disputed option, nor can the owner
+    let owner = context.payer.pubkey();
+    set_clock(&mut context, option.expiry + 10).await;
+    assert_eq!(
+        process_instructions(&mut context, &[settle], &[])
+            .await
+            .unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::NotEnoughAccountKeys)
+    );
+    assert_eq!(
+        process_instructions(
+            &mut context,
+            &[settle_by(&option, option.short_mint, owner)],
+            &[],
+        )
+        .await
+        .unwrap_err(),
+        custom_error(BinaryOptionError::PublicKeyMismatch)
+    );
+
+    // the arbiter settles for good, and collection opens right away
+    process_instructions(
+        &mut context,
+        &[settle_by(
+            &option,
+            option.short_mint,
+            option.arbiter.pubkey(),
+        )],
+        &[&option.arbiter],
+    )
+    .await
+    .unwrap();
+    let binary_option = get_binary_option(&mut context, &option.pool).await;
+    assert!(binary_option.settled);
+    assert_eq!(binary_option.winning_side_pubkey, option.short_mint);
+    assert_eq!(
+        process_instructions(
+            &mut context,
+            &[dispute(binary_option::id(), option.pool, owner, None)],
+            &[],
+        )
+        .await
+        .unwrap_err(),
+        custom_error(BinaryOptionError::AlreadyDisputed)
+    );
+
+    let fee_payer = context.payer.pubkey();
+    process_instructions(
+        &mut context,
+        &[collect_instruction(&option, &seller, &fee_payer)],
+        &[],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &seller.escrow_account).await,
+        10_000 - 700 + 1000
+    );
+}
+
+#[tokio::test]
+async fn success_dispute_window_closes() {
+    let oracle = Pubkey::new_unique();
+    let mut context = program_test(&oracle).start_with_context().await;
+    let option = create_option(&mut context, &oracle).await;
+
+    set_clock(&mut context, option.expiry).await;
+    context.set_account(&oracle, &oracle_account(150, option.expiry).into());
+    process_instructions(
+        &mut context,
+        &[settle_with_oracle(
+            binary_option::id(),
+            option.pool,
+            option.oracle,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    set_clock(&mut context, option.expiry + DISPUTE_WINDOW).await;
+    let owner = context.payer.pubkey();
+    assert_eq!(
+        process_instructions(
+            &mut context,
+            &[dispute(binary_option::id(), option.pool, owner, None)],
+            &[],
+        )
+        .await
+        .unwrap_err(),
+        custom_error(BinaryOptionError::DisputeWindowClosed)
+    );
+}
+
+#[tokio::test]
+async fn success_missed_settlement_window() {
+    let oracle = Pubkey::new_unique();
+    let mut context = program_test(&oracle).start_with_context().await;
+    let option = create_option(&mut context, &oracle).await;
+    let buyer = create_trader(&mut context, &option).await;
+    let seller = create_trader(&mut context, &option).await;
+    process_instructions(
+        &mut context,
+        &[trade_instruction(&option, &buyer, &seller, 10)],
+        &[&buyer.wallet, &seller.wallet],
+    )
+    .await
+    .unwrap();
+
+    // the oracle was updated again before anyone settled the option, so its
+    // latest price can't settle it anymore
+    let latest_publish_time = option.expiry + MAX_STALENESS;
+    set_clock(&mut context, latest_publish_time).await;
+    context.set_account(
+        &oracle,
+        &oracle_account(150, latest_publish_time + 1).into(),
+    );
+    assert_eq!(
+        process_instructions(
+            &mut context,
+            &[settle_with_oracle(
+                binary_option::id(),
+                option.pool,
+                option.oracle,
+            )],
+            &[],
+        )
+        .await
+        .unwrap_err(),
+        custom_error(BinaryOptionError::StaleOraclePrice)
+    );
+
+    // the arbiter only takes over once an oracle price can no longer settle it
+    let arbiter_settle = settle_by(&option, option.short_mint, option.arbiter.pubkey());
+    assert_eq!(
+        process_instructions(&mut context, &[arbiter_settle.clone()], &[&option.arbiter])
+            .await
+            .unwrap_err(),
+        custom_error(BinaryOptionError::SettlementWindowOpen)
+    );
+
+    set_clock(&mut context, latest_publish_time + 1).await;
+    let owner = context.payer.pubkey();
+    assert_eq!(
+        process_instructions(
+            &mut context,
+            &[settle_by(&option, option.long_mint, owner)],
+            &[],
+        )
+        .await
+        .unwrap_err(),
+        custom_error(BinaryOptionError::PublicKeyMismatch)
+    );
+    process_instructions(&mut context, &[arbiter_settle], &[&option.arbiter])
+        .await
+        .unwrap();
+    let binary_option = get_binary_option(&mut context, &option.pool).await;
+    assert!(binary_option.settled);
+    assert!(binary_option.arbitrated);
+    assert_eq!(binary_option.winning_side_pubkey, option.short_mint);
+
+    // the arbiter's settlement can't be disputed, and collection opens right
+    // away
+    assert_eq!(
+        process_instructions(
+            &mut context,
+            &[dispute(
+                binary_option::id(),
+                option.pool,
+                buyer.wallet.pubkey(),
+                Some(buyer.long_account),
+            )],
+            &[&buyer.wallet],
+        )
+        .await
+        .unwrap_err(),
+        custom_error(BinaryOptionError::DisputeWindowClosed)
+    );
+    let fee_payer = context.payer.pubkey();
+    process_instructions(
+        &mut context,
+        &[collect_instruction(&option, &seller, &fee_payer)],
+        &[],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &seller.escrow_account).await,
+        10_000 - 700 + 1000
+    );
+}
+
+#[tokio::test]
+async fn success_set_arbiter() {
+    let oracle = Pubkey::new_unique();
+    let mut context = program_test(&oracle).start_with_context().await;
+    let option = create_option(&mut context, &oracle).await;
+    let (arbiter_config, _) = ArbiterConfig::find_address(&binary_option::id());
+
+    // only the upgrade authority of the program picks the arbiter
+    let mallory = Keypair::new();
+    let payer = context.payer.pubkey();
+    assert_eq!(
+        process_instructions(
+            &mut context,
+            &[
+                system_instruction::transfer(&payer, &mallory.pubkey(), 100_000_000),
+                set_arbiter(binary_option::id(), mallory.pubkey(), mallory.pubkey()),
+            ],
+            &[&mallory],
+        )
+        .await
+        .unwrap_err(),
+        TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(BinaryOptionError::NotUpgradeAuthority as u32)
+        )
+    );
+
+    let arbiter = Pubkey::new_unique();
+    process_instructions(
+        &mut context,
+        &[set_arbiter(binary_option::id(), payer, arbiter)],
+        &[],
+    )
+    .await
+    .unwrap();
+    let account = context
+        .banks_client
+        .get_account(arbiter_config)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        ArbiterConfig::try_from_slice(&account.data).unwrap(),
+        ArbiterConfig { arbiter }
+    );
+
+    // options keep the arbiter they were created with
+    let binary_option = get_binary_option(&mut context, &option.pool).await;
+    assert_eq!(binary_option.arbiter, option.arbiter.pubkey());
+
+    // and the owner can't be the arbiter of its own options
+    process_instructions(
+        &mut context,
+        &[set_arbiter(binary_option::id(), payer, payer)],
+        &[],
+    )
+    .await
+    .unwrap();
+    let pool = Keypair::new();
+    let escrow = Keypair::new();
+    let long_mint = Keypair::new();
+    let short_mint = Keypair::new();
+    assert_eq!(
+        process_instructions(
+            &mut context,
+            &[initialize_oracle_binary_option(
+                binary_option::id(),
+                pool.pubkey(),
+                option.escrow_mint,
+                escrow.pubkey(),
+                long_mint.pubkey(),
+                short_mint.pubkey(),
+                payer,
+                payer,
+                oracle,
+                DECIMALS,
+                STRIKE_PRICE,
+                option.expiry,
+                DISPUTE_WINDOW,
+                MAX_STALENESS,
+                ORACLE_PROGRAM_ID,
+            )],
+            &[&pool, &escrow, &long_mint, &short_mint],
+        )
+        .await
+        .unwrap_err(),
+        custom_error(BinaryOptionError::PublicKeysShouldBeUnique)
+    );
+}
+
+#[tokio::test]
+async fn success_legacy_option() {
+    // options created before oracle settlement are shorter than the current
+    // layout and still settle and deserialize
+    let owner = Keypair::new();
+    let long_mint = Pubkey::new_unique();
+    let legacy_option = BinaryOption {
+        decimals: DECIMALS,
+        circulation: 0,
+        settled: false,
+        escrow_mint_account_pubkey: Pubkey::new_unique(),
+        escrow_account_pubkey: Pubkey::new_unique(),
+        long_mint_account_pubkey: long_mint,
+        short_mint_account_pubkey: Pubkey::new_unique(),
+        owner: owner.pubkey(),
+        winning_side_pubkey: Pubkey::default(),
+        oracle: Pubkey::default(),
+        strike_price: 0,
+        expiry: 0,
+        dispute_window: 0,
+        settled_at: 0,
+        settle_price: 0,
+        max_staleness: 0,
+        arbiter: Pubkey::default(),
+        disputed: false,
+        oracle_program: Pubkey::default(),
+        arbitrated: false,
+    };
+    let mut data = borsh::to_vec(&legacy_option).unwrap();
+    data.truncate(BinaryOption::LEGACY_LEN);
+    let pool = Pubkey::new_unique();
+    let mut program_test = program_test(&Pubkey::new_unique());
+    program_test.add_account(
+        pool,
+        SolanaAccount {
+            lamports: 1_000_000_000,
+            data,
+            owner: binary_option::id(),
+            ..SolanaAccount::default()
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+
+    process_instructions(
+        &mut context,
+        &[settle(binary_option::id(), pool, long_mint, owner.pubkey())],
+        &[&owner],
+    )
+    .await
+    .unwrap();
+    let account = context
+        .banks_client
+        .get_account(pool)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.data.len(), BinaryOption::LEGACY_LEN);
+    let mut data = account.data;
+    data.resize(BinaryOption::LEN, 0);
+    let binary_option = BinaryOption::try_from_slice(&data).unwrap();
+    assert!(binary_option.settled);
+    assert_eq!(binary_option.winning_side_pubkey, long_mint);
+}
+
+#[tokio::test]
+async fn success_order_matching() {
+    let oracle = Pubkey::new_unique();
+    let mut context = program_test(&oracle).start_with_context().await;
+    let option = create_option(&mut context, &oracle).await;
+    let bidder = create_trader(&mut context, &option).await;
+    let asker = create_trader(&mut context, &option).await;
+
+    // prices must be strictly between 0 and 10^decimals
+    assert_eq!(
+        place_test_order(&mut context, &option, &bidder, OrderSide::Bid, 100, 10)
+            .await
+            .err()
+            .unwrap(),
+        TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(BinaryOptionError::InvalidOrder as u32)
+        )
+    );
+
+    // the collateral moves into the order escrow
+    let bid = place_test_order(&mut context, &option, &bidder, OrderSide::Bid, 40, 10)
+        .await
+        .unwrap();
+    let ask = place_test_order(&mut context, &option, &asker, OrderSide::Ask, 30, 6)
+        .await
+        .unwrap();
+    assert_eq!(get_token_balance(&mut context, &bid.escrow).await, 400);
+    assert_eq!(get_token_balance(&mut context, &ask.escrow).await, 420);
+
+    // anyone matches crossing orders at the bid price
+    process_instructions(
+        &mut context,
+        &[match_orders_instruction(
+            &option, &bidder, &bid, &asker, &ask,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    assert_eq!(get_token_balance(&mut context, &option.escrow).await, 600);
+    assert_eq!(
+        get_token_balance(&mut context, &bidder.long_account).await,
+        6
+    );
+    assert_eq!(
+        get_token_balance(&mut context, &asker.short_account).await,
+        6
+    );
+    assert_eq!(get_token_balance(&mut context, &bid.escrow).await, 160);
+    assert_eq!(get_token_balance(&mut context, &ask.escrow).await, 0);
+    assert_eq!(
+        get_token_balance(&mut context, &asker.escrow_account).await,
+        10_000 - 420 + 60
+    );
+    assert_eq!(get_order(&mut context, &bid.order).await.size, 4);
+    assert_eq!(get_order(&mut context, &ask.order).await.size, 0);
+    assert_eq!(
+        get_binary_option(&mut context, &option.pool)
+            .await
+            .circulation,
+        6
+    );
+
+    // a filled order cannot be matched again, and orders must cross
+    assert_eq!(
+        process_instructions(
+            &mut context,
+            &[match_orders_instruction(
+                &option, &bidder, &bid, &asker, &ask,
+            )],
+            &[],
+        )
+        .await
+        .unwrap_err(),
+        custom_error(BinaryOptionError::InvalidOrder)
+    );
+    let high_ask = place_test_order(&mut context, &option, &asker, OrderSide::Ask, 50, 4)
+        .await
+        .unwrap();
+    assert_eq!(
+        process_instructions(
+            &mut context,
+            &[match_orders_instruction(
+                &option, &bidder, &bid, &asker, &high_ask,
+            )],
+            &[],
+        )
+        .await
+        .unwrap_err(),
+        custom_error(BinaryOptionError::OrdersDoNotCross)
+    );
+
+    // only the owner cancels, getting the unfilled collateral back
+    assert_eq!(
+        process_instructions(
+            &mut context,
+            &[cancel_order_instruction(&option, &asker, &bid)],
+            &[&asker.wallet],
+        )
+        .await
+        .unwrap_err(),
+        custom_error(BinaryOptionError::PublicKeyMismatch)
+    );
+    for (owner, order) in [(&bidder, &bid), (&asker, &ask), (&asker, &high_ask)] {
+        process_instructions(
+            &mut context,
+            &[cancel_order_instruction(&option, owner, order)],
+            &[&owner.wallet],
+        )
+        .await
+        .unwrap();
+        for account in [order.order, order.escrow] {
+            assert!(context
+                .banks_client
+                .get_account(account)
+                .await
+                .unwrap()
+                .is_none());
+        }
+    }
+    assert_eq!(
+        get_token_balance(&mut context, &bidder.escrow_account).await,
+        10_000 - 240
+    );
+    assert_eq!(
+        get_token_balance(&mut context, &asker.escrow_account).await,
+        10_000 - 360
+    );
+
+    // matched positions are collected without the owner's signature
+    set_clock(&mut context, option.expiry).await;
+    context.set_account(&oracle, &oracle_account(150, option.expiry).into());
+    process_instructions(
+        &mut context,
+        &[settle_with_oracle(
+            binary_option::id(),
+            option.pool,
+            option.oracle,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    set_clock(&mut context, option.expiry + DISPUTE_WINDOW).await;
+    let fee_payer = context.payer.pubkey();
+    process_instructions(
+        &mut context,
+        &[collect_instruction(&option, &bidder, &fee_payer)],
+        &[],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &bidder.escrow_account).await,
+        10_000 - 240 + 600
+    );
+    assert_eq!(
+        get_token_balance(&mut context, &bidder.long_account).await,
+        0
+    );
+}
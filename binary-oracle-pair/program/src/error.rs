@@ -56,6 +56,15 @@ pub enum PoolError {
     /// No decision has been made yet
     #[error("No decision has been made yet")]
     NoDecisionMadeYet,
+    /// Number of outcome mints out of range
+    #[error("Number of outcome mints out of range")]
+    InvalidOutcomeCount,
+    /// Outcome index out of range, or not the decided outcome
+    #[error("Invalid outcome")]
+    InvalidOutcome,
+    /// Deposit account is not the one of the pool
+    #[error("Deposit account does not match the pool")]
+    InvalidDepositAccount,
 }
 
 impl From<PoolError> for ProgramError {
@@ -96,6 +105,13 @@ impl PrintProgramError for PoolError {
             }
             PoolError::InvalidSlotForDeposit => msg!("Deposit can't be made in current slot"),
             PoolError::NoDecisionMadeYet => msg!("Error: No decision has been made yet"),
+            PoolError::InvalidOutcomeCount => {
+                msg!("Error: Number of outcome mints out of range")
+            }
+            PoolError::InvalidOutcome => msg!("Error: Invalid outcome"),
+            PoolError::InvalidDepositAccount => {
+                msg!("Error: Deposit account does not match the pool")
+            }
         }
     }
 }
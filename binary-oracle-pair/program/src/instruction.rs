@@ -26,20 +26,21 @@ pub struct InitArgs {
 /// Instruction definition
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
 pub enum PoolInstruction {
-    /// Initializes a new binary oracle pair pool.
+    /// Initializes a new oracle pool with one outcome mint per possible
+    /// outcome.
     ///
     ///   0. `[w]` Pool account.
     ///   1. `[]` Authority
     ///   2. `[]` Decider authority
     ///   3. `[]` Deposit currency SPL Token mint. Must be initialized.
     ///   4. `[w]` Deposit token account. Should not be initialized
-    ///   5. `[w]` Token Pass mint. Should not be initialized
-    ///   6. `[w]` Token Fail mint. Should not be initialized
-    ///   7. `[]` Rent sysvar
-    ///   8. `[]` Token program id
+    ///   5. `[]` Rent sysvar
+    ///   6. `[]` Token program id
+    ///   7. ..7+N `[w]` N outcome mints, at least 2. Should not be initialized
     InitPool(InitArgs),
 
-    ///   Deposit into the pool.
+    ///   Deposit into the pool, minting one token of each outcome per
+    ///   deposit token.
     ///
     ///   0. `[]` Pool
     ///   1. `[]` Authority
@@ -47,44 +48,39 @@ pub enum PoolInstruction {
     ///   3. `[w]` Token SOURCE Account, amount is transferable by pool
     ///      authority with allowances.
     ///   4. `[w]` Deposit token account
-    ///   5. `[w]` token_P PASS mint
-    ///   6. `[w]` token_F FAIL mint
-    ///   7. `[w]` token_P DESTINATION Account
-    ///   8. `[w]` token_F DESTINATION Account
-    ///   9. `[]` Sysvar Clock
-    ///   10. `[]` Token program id
+    ///   5. `[]` Sysvar Clock
+    ///   6. `[]` Token program id
+    ///   7. ..7+2N For each outcome, in order:
+    ///      `[w]` outcome mint
+    ///      `[w]` outcome DESTINATION Account
     Deposit(u64),
 
     ///   Withdraw from the pool.
-    ///   If current slot is < mint_end slot, 1 Pass AND 1 Fail token convert
-    ///     to 1 deposit   
-    ///   If current slot is > decide_end_slot slot && decide ==
-    ///     Some(true), 1 Pass convert to 1 deposit   otherwise 1 Fail converts
-    ///     to 1 deposit
-    ///
-    ///   Pass tokens convert 1:1 to the deposit token iff decision is set to
-    ///     Some(true) AND current slot is > decide_end_slot.
+    ///   If current slot is < mint_end slot, or > decide_end_slot without a
+    ///     decision, a full set of 1 token of each outcome converts to 1
+    ///     deposit
+    ///   Once decided, 1 token of the decided outcome converts to 1 deposit
     ///
     ///   0. `[]` Pool
     ///   1. `[]` Authority
     ///   2. `[s]` User transfer authority
     ///   3. `[w]` Pool deposit token account
-    ///   4. `[w]` token_P PASS SOURCE Account
-    ///   5. `[w]` token_F FAIL SOURCE Account
-    ///   6. `[w]` token_P PASS mint
-    ///   7. `[w]` token_F FAIL mint
-    ///   8. `[w]` Deposit DESTINATION Account
-    ///   9. `[]` Sysvar Clock
-    ///   10. `[]` Token program id
+    ///   4. `[w]` Deposit DESTINATION Account
+    ///   5. `[]` Sysvar Clock
+    ///   6. `[]` Token program id
+    ///   7. ..7+2N For each outcome before the decision, or for the decided
+    ///      outcome only afterwards:
+    ///      `[w]` outcome mint
+    ///      `[w]` outcome SOURCE Account
     Withdraw(u64),
 
-    ///  Trigger the decision.
+    ///  Trigger the decision, naming the index of the winning outcome.
     ///  Call only succeeds once and if current slot > mint_end slot AND <
     /// decide_end slot
-    ///   0. `[]` Pool
+    ///   0. `[w]` Pool
     ///   1. `[s]` Decider pubkey
     ///   2. `[]` Sysvar Clock
-    Decide(bool),
+    Decide(u8),
 }
 
 /// Create `InitPool` instruction
@@ -96,24 +92,26 @@ pub fn init_pool(
     decider: &Pubkey,
     deposit_token_mint: &Pubkey,
     deposit_account: &Pubkey,
-    token_pass_mint: &Pubkey,
-    token_fail_mint: &Pubkey,
+    outcome_mints: &[Pubkey],
     token_program_id: &Pubkey,
     init_args: InitArgs,
 ) -> Result<Instruction, ProgramError> {
     let init_data = PoolInstruction::InitPool(init_args);
     let data = borsh::to_vec(&init_data)?;
-    let accounts = vec![
+    let mut accounts = vec![
         AccountMeta::new(*pool, false),
         AccountMeta::new_readonly(*authority, false),
         AccountMeta::new_readonly(*decider, false),
         AccountMeta::new_readonly(*deposit_token_mint, false),
         AccountMeta::new(*deposit_account, false),
-        AccountMeta::new(*token_pass_mint, false),
-        AccountMeta::new(*token_fail_mint, false),
         AccountMeta::new_readonly(sysvar::rent::id(), false),
         AccountMeta::new_readonly(*token_program_id, false),
     ];
+    accounts.extend(
+        outcome_mints
+            .iter()
+            .map(|mint| AccountMeta::new(*mint, false)),
+    );
     Ok(Instruction {
         program_id: *program_id,
         accounts,
@@ -130,17 +128,15 @@ pub fn deposit(
     user_transfer_authority: &Pubkey,
     user_token_account: &Pubkey,
     pool_deposit_token_account: &Pubkey,
-    token_pass_mint: &Pubkey,
-    token_fail_mint: &Pubkey,
-    token_pass_destination_account: &Pubkey,
-    token_fail_destination_account: &Pubkey,
+    outcome_mints: &[Pubkey],
+    outcome_destination_accounts: &[Pubkey],
     token_program_id: &Pubkey,
     amount: u64,
 ) -> Result<Instruction, ProgramError> {
     let init_data = PoolInstruction::Deposit(amount);
     let data = borsh::to_vec(&init_data)?;
 
-    let accounts = vec![
+    let mut accounts = vec![
         AccountMeta::new_readonly(*pool, false),
         AccountMeta::new_readonly(*authority, false),
         AccountMeta::new_readonly(
@@ -149,13 +145,10 @@ pub fn deposit(
         ),
         AccountMeta::new(*user_token_account, false),
         AccountMeta::new(*pool_deposit_token_account, false),
-        AccountMeta::new(*token_pass_mint, false),
-        AccountMeta::new(*token_fail_mint, false),
-        AccountMeta::new(*token_pass_destination_account, false),
-        AccountMeta::new(*token_fail_destination_account, false),
         AccountMeta::new_readonly(sysvar::clock::id(), false),
         AccountMeta::new_readonly(*token_program_id, false),
     ];
+    append_outcome_accounts(&mut accounts, outcome_mints, outcome_destination_accounts)?;
     Ok(Instruction {
         program_id: *program_id,
         accounts,
@@ -163,7 +156,9 @@ pub fn deposit(
     })
 }
 
-/// Create `Withdraw` instruction
+/// Create `Withdraw` instruction. Before the decision, `outcome_mints` and
+/// `outcome_user_accounts` cover every outcome, afterwards only the decided
+/// one.
 #[allow(clippy::too_many_arguments)]
 pub fn withdraw(
     program_id: &Pubkey,
@@ -171,17 +166,15 @@ pub fn withdraw(
     authority: &Pubkey,
     user_transfer_authority: &Pubkey,
     pool_deposit_token_account: &Pubkey,
-    token_pass_user_account: &Pubkey,
-    token_fail_user_account: &Pubkey,
-    token_pass_mint: &Pubkey,
-    token_fail_mint: &Pubkey,
     user_token_destination_account: &Pubkey,
+    outcome_mints: &[Pubkey],
+    outcome_user_accounts: &[Pubkey],
     token_program_id: &Pubkey,
     amount: u64,
 ) -> Result<Instruction, ProgramError> {
     let init_data = PoolInstruction::Withdraw(amount);
     let data = borsh::to_vec(&init_data)?;
-    let accounts = vec![
+    let mut accounts = vec![
         AccountMeta::new_readonly(*pool, false),
         AccountMeta::new_readonly(*authority, false),
         AccountMeta::new_readonly(
@@ -189,14 +182,11 @@ pub fn withdraw(
             authority != user_transfer_authority,
         ),
         AccountMeta::new(*pool_deposit_token_account, false),
-        AccountMeta::new(*token_pass_user_account, false),
-        AccountMeta::new(*token_fail_user_account, false),
-        AccountMeta::new(*token_pass_mint, false),
-        AccountMeta::new(*token_fail_mint, false),
         AccountMeta::new(*user_token_destination_account, false),
         AccountMeta::new_readonly(sysvar::clock::id(), false),
         AccountMeta::new_readonly(*token_program_id, false),
     ];
+    append_outcome_accounts(&mut accounts, outcome_mints, outcome_user_accounts)?;
     Ok(Instruction {
         program_id: *program_id,
         accounts,
@@ -209,9 +199,9 @@ pub fn decide(
     program_id: &Pubkey,
     pool: &Pubkey,
     decider: &Pubkey,
-    decision: bool,
+    outcome: u8,
 ) -> Result<Instruction, ProgramError> {
-    let init_data = PoolInstruction::Decide(decision);
+    let init_data = PoolInstruction::Decide(outcome);
     let data = borsh::to_vec(&init_data)?;
     let accounts = vec![
         AccountMeta::new(*pool, false),
@@ -224,3 +214,18 @@ pub fn decide(
         data,
     })
 }
+
+fn append_outcome_accounts(
+    accounts: &mut Vec<AccountMeta>,
+    outcome_mints: &[Pubkey],
+    outcome_token_accounts: &[Pubkey],
+) -> Result<(), ProgramError> {
+    if outcome_mints.len() != outcome_token_accounts.len() {
+        return Err(ProgramError::InvalidArgument);
+    }
+    for (mint, token_account) in outcome_mints.iter().zip(outcome_token_accounts) {
+        accounts.push(AccountMeta::new(*mint, false));
+        accounts.push(AccountMeta::new(*token_account, false));
+    }
+    Ok(())
+}
@@ -4,7 +4,7 @@ use {
     crate::{
         error::PoolError,
         instruction::PoolInstruction,
-        state::{Decision, Pool, POOL_VERSION},
+        state::{Decision, Pool, MAX_OUTCOMES, MIN_OUTCOMES, POOL_VERSION},
     },
     borsh::BorshDeserialize,
     solana_program::{
@@ -181,6 +181,28 @@ impl Processor {
         }
     }
 
+    /// Splits the trailing accounts of an instruction into (outcome mint,
+    /// outcome token account) pairs, checking the mints against the given
+    /// outcomes of the pool
+    fn outcome_account_pairs<'a, 'b>(
+        accounts: &'b [AccountInfo<'a>],
+        outcome_mints: &[Pubkey],
+    ) -> Result<Vec<(&'b AccountInfo<'a>, &'b AccountInfo<'a>)>, ProgramError> {
+        if accounts.len() != outcome_mints.len() * 2 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        accounts
+            .chunks_exact(2)
+            .zip(outcome_mints)
+            .map(|(pair, outcome_mint)| {
+                if pair[0].key != outcome_mint {
+                    return Err(PoolError::InvalidTokenMint.into());
+                }
+                Ok((&pair[0], &pair[1]))
+            })
+            .collect()
+    }
+
     /// Initialize the pool
     pub fn process_init_pool(
         program_id: &Pubkey,
@@ -195,18 +217,24 @@ impl Processor {
         let decider_info = next_account_info(account_info_iter)?;
         let deposit_token_mint_info = next_account_info(account_info_iter)?;
         let deposit_account_info = next_account_info(account_info_iter)?;
-        let token_pass_mint_info = next_account_info(account_info_iter)?;
-        let token_fail_mint_info = next_account_info(account_info_iter)?;
         let rent_info = next_account_info(account_info_iter)?;
         let rent = &Rent::from_account_info(rent_info)?;
         let token_program_info = next_account_info(account_info_iter)?;
+        let outcome_mint_infos = account_info_iter.as_slice();
 
-        let mut pool = Pool::try_from_slice(&pool_account_info.data.borrow())?;
+        let mut pool = Pool::unpack(&pool_account_info.data.borrow())?;
         // Pool account should not be already initialized
         if pool.is_initialized() {
             return Err(PoolError::AlreadyInUse.into());
         }
 
+        if outcome_mint_infos.len() < MIN_OUTCOMES || outcome_mint_infos.len() > MAX_OUTCOMES {
+            return Err(PoolError::InvalidOutcomeCount.into());
+        }
+        if pool_account_info.data_len() < Pool::get_packed_len(outcome_mint_infos.len()) {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+
         // Check if pool account is rent-exempt
         if !rent.is_exempt(pool_account_info.lamports(), pool_account_info.data_len()) {
             return Err(PoolError::NotRentExempt.into());
@@ -231,16 +259,6 @@ impl Processor {
             return Err(PoolError::DepositAccountInUse.into());
         }
 
-        let token_pass = Mint::unpack_unchecked(&token_pass_mint_info.data.borrow())?;
-        if token_pass.is_initialized() {
-            return Err(PoolError::TokenMintInUse.into());
-        }
-
-        let token_fail = Mint::unpack_unchecked(&token_fail_mint_info.data.borrow())?;
-        if token_fail.is_initialized() {
-            return Err(PoolError::TokenMintInUse.into());
-        }
-
         invoke(
             &spl_token::instruction::initialize_account(
                 token_program_info.key,
@@ -258,50 +276,42 @@ impl Processor {
             ],
         )?;
 
-        invoke(
-            &spl_token::instruction::initialize_mint(
-                &spl_token::id(),
-                token_pass_mint_info.key,
-                authority_info.key,
-                None,
-                deposit_token_mint.decimals,
-            )
-            .unwrap(),
-            &[
-                token_program_info.clone(),
-                token_pass_mint_info.clone(),
-                rent_info.clone(),
-            ],
-        )?;
+        for outcome_mint_info in outcome_mint_infos {
+            // Also rejects a mint listed twice, since it was initialized the
+            // first time
+            let outcome_mint = Mint::unpack_unchecked(&outcome_mint_info.data.borrow())?;
+            if outcome_mint.is_initialized() {
+                return Err(PoolError::TokenMintInUse.into());
+            }
 
-        invoke(
-            &spl_token::instruction::initialize_mint(
-                &spl_token::id(),
-                token_fail_mint_info.key,
-                authority_info.key,
-                None,
-                deposit_token_mint.decimals,
-            )
-            .unwrap(),
-            &[
-                token_program_info.clone(),
-                token_fail_mint_info.clone(),
-                rent_info.clone(),
-            ],
-        )?;
+            invoke(
+                &spl_token::instruction::initialize_mint(
+                    &spl_token::id(),
+                    outcome_mint_info.key,
+                    authority_info.key,
+                    None,
+                    deposit_token_mint.decimals,
+                )
+                .unwrap(),
+                &[
+                    token_program_info.clone(),
+                    outcome_mint_info.clone(),
+                    rent_info.clone(),
+                ],
+            )?;
+        }
 
         pool.version = POOL_VERSION;
         pool.bump_seed = bump_seed;
         pool.token_program_id = *token_program_info.key;
         pool.deposit_account = *deposit_account_info.key;
-        pool.token_pass_mint = *token_pass_mint_info.key;
-        pool.token_fail_mint = *token_fail_mint_info.key;
         pool.decider = *decider_info.key;
         pool.mint_end_slot = mint_end_slot;
         pool.decide_end_slot = decide_end_slot;
         pool.decision = Decision::Undecided;
+        pool.outcome_mints = outcome_mint_infos.iter().map(|info| *info.key).collect();
 
-        borsh::to_writer(&mut pool_account_info.data.borrow_mut()[..], &pool).map_err(|e| e.into())
+        pool.pack(&mut pool_account_info.data.borrow_mut())
     }
 
     /// Process Deposit instruction
@@ -316,10 +326,6 @@ impl Processor {
         let user_transfer_authority_info = next_account_info(account_info_iter)?;
         let user_token_account_info = next_account_info(account_info_iter)?;
         let pool_deposit_token_account_info = next_account_info(account_info_iter)?;
-        let token_pass_mint_info = next_account_info(account_info_iter)?;
-        let token_fail_mint_info = next_account_info(account_info_iter)?;
-        let token_pass_destination_account_info = next_account_info(account_info_iter)?;
-        let token_fail_destination_account_info = next_account_info(account_info_iter)?;
         let clock_info = next_account_info(account_info_iter)?;
         let clock = &Clock::from_account_info(clock_info)?;
         let token_program_id_info = next_account_info(account_info_iter)?;
@@ -328,7 +334,7 @@ impl Processor {
             return Err(PoolError::InvalidAmount.into());
         }
 
-        let pool = Pool::try_from_slice(&pool_account_info.data.borrow())?;
+        let pool = Pool::unpack(&pool_account_info.data.borrow())?;
 
         if clock.slot > pool.mint_end_slot {
             return Err(PoolError::InvalidSlotForDeposit.into());
@@ -339,6 +345,11 @@ impl Processor {
         if *authority_account_info.key != authority_pub_key {
             return Err(PoolError::InvalidAuthorityAccount.into());
         }
+        if *pool_deposit_token_account_info.key != pool.deposit_account {
+            return Err(PoolError::InvalidDepositAccount.into());
+        }
+        let outcome_accounts =
+            Self::outcome_account_pairs(account_info_iter.as_slice(), &pool.outcome_mints)?;
 
         // Transfer deposit tokens from user's account to our deposit account
         Self::transfer(
@@ -352,26 +363,18 @@ impl Processor {
             pool.bump_seed,
         )?;
 
-        // Mint PASS tokens to user account
-        Self::mint(
-            token_program_id_info.clone(),
-            token_pass_mint_info.clone(),
-            token_pass_destination_account_info.clone(),
-            authority_account_info.clone(),
-            amount,
-            pool_account_info.key,
-            pool.bump_seed,
-        )?;
-        // Mint FAIL tokens to user account
-        Self::mint(
-            token_program_id_info.clone(),
-            token_fail_mint_info.clone(),
-            token_fail_destination_account_info.clone(),
-            authority_account_info.clone(),
-            amount,
-            pool_account_info.key,
-            pool.bump_seed,
-        )?;
+        // Mint a full set of outcome tokens to the user accounts
+        for (outcome_mint_info, outcome_destination_account_info) in outcome_accounts {
+            Self::mint(
+                token_program_id_info.clone(),
+                outcome_mint_info.clone(),
+                outcome_destination_account_info.clone(),
+                authority_account_info.clone(),
+                amount,
+                pool_account_info.key,
+                pool.bump_seed,
+            )?;
+        }
 
         Ok(())
     }
@@ -387,10 +390,6 @@ impl Processor {
         let authority_account_info = next_account_info(account_info_iter)?;
         let user_transfer_authority_info = next_account_info(account_info_iter)?;
         let pool_deposit_token_account_info = next_account_info(account_info_iter)?;
-        let token_pass_user_account_info = next_account_info(account_info_iter)?;
-        let token_fail_user_account_info = next_account_info(account_info_iter)?;
-        let token_pass_mint_info = next_account_info(account_info_iter)?;
-        let token_fail_mint_info = next_account_info(account_info_iter)?;
         let user_token_destination_account_info = next_account_info(account_info_iter)?;
         let clock_info = next_account_info(account_info_iter)?;
         let clock = &Clock::from_account_info(clock_info)?;
@@ -400,122 +399,63 @@ impl Processor {
             return Err(PoolError::InvalidAmount.into());
         }
 
-        let user_pass_token_account = Account::unpack(&token_pass_user_account_info.data.borrow())?;
-        let user_fail_token_account = Account::unpack(&token_fail_user_account_info.data.borrow())?;
-
-        let pool = Pool::try_from_slice(&pool_account_info.data.borrow())?;
+        let pool = Pool::unpack(&pool_account_info.data.borrow())?;
 
-        if pool.token_pass_mint != *token_pass_mint_info.key {
-            return Err(PoolError::InvalidTokenMint.into());
-        }
-        if pool.token_fail_mint != *token_fail_mint_info.key {
-            return Err(PoolError::InvalidTokenMint.into());
-        }
         let authority_pub_key =
             Self::authority_id(program_id, pool_account_info.key, pool.bump_seed)?;
         if *authority_account_info.key != authority_pub_key {
             return Err(PoolError::InvalidAuthorityAccount.into());
         }
+        if *pool_deposit_token_account_info.key != pool.deposit_account {
+            return Err(PoolError::InvalidDepositAccount.into());
+        }
 
-        match pool.decision {
-            Decision::Pass => {
-                // Burn PASS tokens
-                Self::burn(
-                    token_program_id_info.clone(),
-                    token_pass_user_account_info.clone(),
-                    token_pass_mint_info.clone(),
-                    authority_account_info.clone(),
-                    user_transfer_authority_info.clone(),
-                    amount,
-                    pool_account_info.key,
-                    pool.bump_seed,
-                )?;
-
-                // Transfer deposit tokens from pool deposit account to user destination account
-                Self::transfer(
-                    token_program_id_info.clone(),
-                    pool_deposit_token_account_info.clone(),
-                    user_token_destination_account_info.clone(),
-                    authority_account_info.clone(),
-                    authority_account_info.clone(),
-                    amount,
-                    pool_account_info.key,
-                    pool.bump_seed,
-                )?;
-            }
-            Decision::Fail => {
-                // Burn FAIL tokens
-                Self::burn(
-                    token_program_id_info.clone(),
-                    token_fail_user_account_info.clone(),
-                    token_fail_mint_info.clone(),
-                    authority_account_info.clone(),
-                    user_transfer_authority_info.clone(),
-                    amount,
-                    pool_account_info.key,
-                    pool.bump_seed,
-                )?;
-
-                // Transfer deposit tokens from pool deposit account to user destination account
-                Self::transfer(
-                    token_program_id_info.clone(),
-                    pool_deposit_token_account_info.clone(),
-                    user_token_destination_account_info.clone(),
-                    authority_account_info.clone(),
-                    authority_account_info.clone(),
-                    amount,
-                    pool_account_info.key,
-                    pool.bump_seed,
-                )?;
+        let burned_outcome_mints = match pool.decision {
+            // Only the decided outcome converts to deposit tokens
+            Decision::Decided(outcome) => {
+                let outcome = pool
+                    .outcome_mints
+                    .get(outcome as usize)
+                    .ok_or(PoolError::InvalidOutcome)?;
+                std::slice::from_ref(outcome)
             }
+            // Before the decision, a full set of outcome tokens converts back
             Decision::Undecided => {
                 let current_slot = clock.slot;
-                if current_slot < pool.mint_end_slot || current_slot > pool.decide_end_slot {
-                    let possible_withdraw_amount = amount
-                        .min(user_pass_token_account.amount)
-                        .min(user_fail_token_account.amount);
-
-                    // Burn PASS tokens
-                    Self::burn(
-                        token_program_id_info.clone(),
-                        token_pass_user_account_info.clone(),
-                        token_pass_mint_info.clone(),
-                        authority_account_info.clone(),
-                        user_transfer_authority_info.clone(),
-                        possible_withdraw_amount,
-                        pool_account_info.key,
-                        pool.bump_seed,
-                    )?;
-
-                    // Burn FAIL tokens
-                    Self::burn(
-                        token_program_id_info.clone(),
-                        token_fail_user_account_info.clone(),
-                        token_fail_mint_info.clone(),
-                        authority_account_info.clone(),
-                        user_transfer_authority_info.clone(),
-                        amount,
-                        pool_account_info.key,
-                        pool.bump_seed,
-                    )?;
-
-                    // Transfer deposit tokens from pool deposit account to user destination account
-                    Self::transfer(
-                        token_program_id_info.clone(),
-                        pool_deposit_token_account_info.clone(),
-                        user_token_destination_account_info.clone(),
-                        authority_account_info.clone(),
-                        authority_account_info.clone(),
-                        amount,
-                        pool_account_info.key,
-                        pool.bump_seed,
-                    )?;
-                } else {
+                if current_slot >= pool.mint_end_slot && current_slot <= pool.decide_end_slot {
                     return Err(PoolError::NoDecisionMadeYet.into());
                 }
+                &pool.outcome_mints[..]
             }
+        };
+        let outcome_accounts =
+            Self::outcome_account_pairs(account_info_iter.as_slice(), burned_outcome_mints)?;
+
+        for (outcome_mint_info, outcome_user_account_info) in outcome_accounts {
+            Self::burn(
+                token_program_id_info.clone(),
+                outcome_user_account_info.clone(),
+                outcome_mint_info.clone(),
+                authority_account_info.clone(),
+                user_transfer_authority_info.clone(),
+                amount,
+                pool_account_info.key,
+                pool.bump_seed,
+            )?;
         }
 
+        // Transfer deposit tokens from pool deposit account to user destination account
+        Self::transfer(
+            token_program_id_info.clone(),
+            pool_deposit_token_account_info.clone(),
+            user_token_destination_account_info.clone(),
+            authority_account_info.clone(),
+            authority_account_info.clone(),
+            amount,
+            pool_account_info.key,
+            pool.bump_seed,
+        )?;
+
         Ok(())
     }
 
@@ -523,7 +463,7 @@ impl Processor {
     pub fn process_decide(
         _program_id: &Pubkey,
         accounts: &[AccountInfo],
-        decision: bool,
+        outcome: u8,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
         let pool_account_info = next_account_info(account_info_iter)?;
@@ -531,7 +471,7 @@ impl Processor {
         let clock_info = next_account_info(account_info_iter)?;
         let clock = &Clock::from_account_info(clock_info)?;
 
-        let mut pool = Pool::try_from_slice(&pool_account_info.data.borrow())?;
+        let mut pool = Pool::unpack(&pool_account_info.data.borrow())?;
 
         if *decider_account_info.key != pool.decider {
             return Err(PoolError::WrongDeciderAccount.into());
@@ -550,13 +490,12 @@ impl Processor {
             return Err(PoolError::InvalidSlotForDecision.into());
         }
 
-        pool.decision = if decision {
-            Decision::Pass
-        } else {
-            Decision::Fail
-        };
+        if outcome as usize >= pool.outcome_mints.len() {
+            return Err(PoolError::InvalidOutcome.into());
+        }
+        pool.decision = Decision::Decided(outcome);
 
-        borsh::to_writer(&mut pool_account_info.data.borrow_mut()[..], &pool).map_err(|e| e.into())
+        pool.pack(&mut pool_account_info.data.borrow_mut())
     }
 
     /// Processes an instruction
@@ -585,9 +524,9 @@ impl Processor {
                 msg!("Instruction: Withdraw");
                 Self::process_withdraw(program_id, accounts, amount)
             }
-            PoolInstruction::Decide(decision) => {
+            PoolInstruction::Decide(outcome) => {
                 msg!("Instruction: Decide");
-                Self::process_decide(program_id, accounts, decision)
+                Self::process_decide(program_id, accounts, outcome)
             }
         }
     }
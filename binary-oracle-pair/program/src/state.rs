@@ -1,14 +1,26 @@
 //! State transition types
 
 use {
+    crate::error::PoolError,
     borsh::{BorshDeserialize, BorshSerialize},
-    solana_program::pubkey::Pubkey,
+    solana_program::{
+        borsh1::try_from_slice_unchecked, program_error::ProgramError, pubkey::Pubkey,
+    },
 };
 
 /// Uninitialized version value, all instances are at least version 1
 pub const UNINITIALIZED_VERSION: u8 = 0;
+/// Version of the pass/fail pools, which only had two outcomes
+pub const PASS_FAIL_POOL_VERSION: u8 = 1;
 /// Initialized pool version
-pub const POOL_VERSION: u8 = 1;
+pub const POOL_VERSION: u8 = 2;
+
+/// Smallest number of outcomes of a pool
+pub const MIN_OUTCOMES: usize = 2;
+/// Largest number of outcomes of a pool, so that Deposit and Withdraw, which
+/// take a mint and a token account for each outcome on top of 7 accounts,
+/// still fit in a transaction
+pub const MAX_OUTCOMES: usize = 8;
 
 /// Program states.
 #[repr(C)]
@@ -26,12 +38,6 @@ pub struct Pool {
     /// Account to deposit into
     pub deposit_account: Pubkey,
 
-    /// Mint information for token Pass
-    pub token_pass_mint: Pubkey,
-
-    /// Mint information for token Fail
-    pub token_fail_mint: Pubkey,
-
     /// decider key
     pub decider: Pubkey,
 
@@ -43,6 +49,9 @@ pub struct Pool {
 
     /// decision status
     pub decision: Decision,
+
+    /// Mint information for each outcome token, indexed by outcome
+    pub outcome_mints: Vec<Pubkey>,
 }
 
 /// Decision status
@@ -50,20 +59,114 @@ pub struct Pool {
 pub enum Decision {
     /// Decision was not made
     Undecided,
-    /// Decision set at Pass
-    Pass,
-    /// Decision set at Fail
-    Fail,
+    /// Decision set at the outcome with the given index
+    Decided(u8),
 }
 
 impl Pool {
-    /// Length serialized data
-    pub const LEN: usize = 179;
+    /// Length of the serialized data for the given number of outcomes
+    pub fn get_packed_len(num_outcomes: usize) -> usize {
+        // fixed fields, largest decision variant, outcome mints vector
+        1 + 1 + 32 + 32 + 32 + 8 + 8 + 2 + 4 + 32 * num_outcomes
+    }
 
     /// Check if Pool already initialized
     pub fn is_initialized(&self) -> bool {
         self.version != UNINITIALIZED_VERSION
     }
+
+    /// Deserializes a pool, reading pass/fail pools as pools with the pass
+    /// outcome at index 0 and the fail outcome at index 1
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        match data.first() {
+            Some(&PASS_FAIL_POOL_VERSION) => {
+                Ok(try_from_slice_unchecked::<PassFailPool>(data)?.into())
+            }
+            _ => Ok(try_from_slice_unchecked::<Pool>(data)?),
+        }
+    }
+
+    /// Serializes the pool, keeping the layout of pass/fail pools so that
+    /// they still fit their account
+    pub fn pack(&self, data: &mut [u8]) -> Result<(), ProgramError> {
+        if self.version == PASS_FAIL_POOL_VERSION {
+            borsh::to_writer(data, &PassFailPool::try_from(self)?)?;
+        } else {
+            borsh::to_writer(data, self)?;
+        }
+        Ok(())
+    }
+}
+
+/// Layout of the pass/fail pools
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+struct PassFailPool {
+    version: u8,
+    bump_seed: u8,
+    token_program_id: Pubkey,
+    deposit_account: Pubkey,
+    token_pass_mint: Pubkey,
+    token_fail_mint: Pubkey,
+    decider: Pubkey,
+    mint_end_slot: u64,
+    decide_end_slot: u64,
+    decision: PassFailDecision,
+}
+
+/// Decision status of the pass/fail pools
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+enum PassFailDecision {
+    Undecided,
+    Pass,
+    Fail,
+}
+
+impl From<PassFailPool> for Pool {
+    fn from(pool: PassFailPool) -> Self {
+        Self {
+            version: pool.version,
+            bump_seed: pool.bump_seed,
+            token_program_id: pool.token_program_id,
+            deposit_account: pool.deposit_account,
+            decider: pool.decider,
+            mint_end_slot: pool.mint_end_slot,
+            decide_end_slot: pool.decide_end_slot,
+            decision: match pool.decision {
+                PassFailDecision::Undecided => Decision::Undecided,
+                PassFailDecision::Pass => Decision::Decided(0),
+                PassFailDecision::Fail => Decision::Decided(1),
+            },
+            outcome_mints: vec![pool.token_pass_mint, pool.token_fail_mint],
+        }
+    }
+}
+
+impl TryFrom<&Pool> for PassFailPool {
+    type Error = ProgramError;
+
+    fn try_from(pool: &Pool) -> Result<Self, Self::Error> {
+        let [token_pass_mint, token_fail_mint] = pool.outcome_mints[..] else {
+            return Err(PoolError::InvalidOutcomeCount.into());
+        };
+        Ok(Self {
+            version: pool.version,
+            bump_seed: pool.bump_seed,
+            token_program_id: pool.token_program_id,
+            deposit_account: pool.deposit_account,
+            token_pass_mint,
+            token_fail_mint,
+            decider: pool.decider,
+            mint_end_slot: pool.mint_end_slot,
+            decide_end_slot: pool.decide_end_slot,
+            decision: match pool.decision {
+                Decision::Undecided => PassFailDecision::Undecided,
+                Decision::Decided(0) => PassFailDecision::Pass,
+                Decision::Decided(1) => PassFailDecision::Fail,
+                Decision::Decided(_) => return Err(PoolError::InvalidOutcome.into()),
+            },
+        })
+    }
 }
 
 mod test {
@@ -77,18 +180,60 @@ mod test {
             bump_seed: 2,
             token_program_id: Pubkey::new_unique(),
             deposit_account: Pubkey::new_unique(),
-            token_pass_mint: Pubkey::new_unique(),
-            token_fail_mint: Pubkey::new_unique(),
             decider: Pubkey::new_unique(),
             mint_end_slot: 433,
             decide_end_slot: 5546,
-            decision: Decision::Fail,
+            decision: Decision::Decided(2),
+            outcome_mints: vec![
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+            ],
         };
 
         let packed = borsh::to_vec(&p).unwrap();
+        assert_eq!(packed.len(), Pool::get_packed_len(3));
 
         let unpacked = Pool::try_from_slice(packed.as_slice()).unwrap();
 
         assert_eq!(p, unpacked);
     }
+
+    #[test]
+    pub fn test_pass_fail_pool_pack_unpack() {
+        let pass_fail_pool = PassFailPool {
+            version: PASS_FAIL_POOL_VERSION,
+            bump_seed: 2,
+            token_program_id: Pubkey::new_unique(),
+            deposit_account: Pubkey::new_unique(),
+            token_pass_mint: Pubkey::new_unique(),
+            token_fail_mint: Pubkey::new_unique(),
+            decider: Pubkey::new_unique(),
+            mint_end_slot: 433,
+            decide_end_slot: 5546,
+            decision: PassFailDecision::Undecided,
+        };
+        let mut packed = borsh::to_vec(&pass_fail_pool).unwrap();
+        assert_eq!(packed.len(), 179);
+
+        let mut pool = Pool::unpack(&packed).unwrap();
+        assert_eq!(
+            pool.outcome_mints,
+            vec![
+                pass_fail_pool.token_pass_mint,
+                pass_fail_pool.token_fail_mint
+            ]
+        );
+
+        pool.decision = Decision::Decided(1);
+        pool.pack(&mut packed).unwrap();
+        assert_eq!(
+            PassFailPool::try_from_slice(&packed).unwrap(),
+            PassFailPool {
+                decision: PassFailDecision::Fail,
+                ..pass_fail_pool
+            }
+        );
+        assert_eq!(Pool::unpack(&packed).unwrap(), pool);
+    }
 }
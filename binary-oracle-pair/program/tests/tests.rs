@@ -2,15 +2,18 @@
 
 use {
     borsh::de::BorshDeserialize,
-    solana_program::{hash::Hash, program_pack::Pack, pubkey::Pubkey, system_instruction},
+    solana_program::{
+        hash::Hash, instruction::InstructionError, program_pack::Pack, pubkey::Pubkey,
+        system_instruction,
+    },
     solana_program_test::*,
     solana_sdk::{
         account::Account,
         signature::{Keypair, Signer},
-        transaction::Transaction,
+        transaction::{Transaction, TransactionError},
         transport::TransportError,
     },
-    spl_binary_oracle_pair::*,
+    spl_binary_oracle_pair::{error::PoolError, *},
 };
 
 pub fn program_test() -> ProgramTest {
@@ -112,10 +115,10 @@ pub async fn make_decision(
     program_context: &mut ProgramTestContext,
     pool_account: &Pubkey,
     decider: &Keypair,
-    decision: bool,
+    outcome: u8,
 ) -> Result<(), TransportError> {
     let mut transaction = Transaction::new_with_payer(
-        &[instruction::decide(&id(), pool_account, &decider.pubkey(), decision).unwrap()],
+        &[instruction::decide(&id(), pool_account, &decider.pubkey(), outcome).unwrap()],
         Some(&program_context.payer.pubkey()),
     );
 
@@ -137,11 +140,9 @@ pub async fn make_withdraw(
     authority: &Pubkey,
     user_transfer_authority: &Pubkey,
     pool_deposit_account: &Pubkey,
-    user_pass_account: &Pubkey,
-    user_fail_account: &Pubkey,
-    token_pass_mint: &Pubkey,
-    token_fail_mint: &Pubkey,
     user_account: &Pubkey,
+    outcome_mints: &[Pubkey],
+    user_outcome_accounts: &[Pubkey],
     withdraw_amount: u64,
 ) -> Result<(), TransportError> {
     let mut transaction = Transaction::new_with_payer(
@@ -151,11 +152,9 @@ pub async fn make_withdraw(
             authority,
             user_transfer_authority,
             pool_deposit_account,
-            user_pass_account,
-            user_fail_account,
-            token_pass_mint,
-            token_fail_mint,
             user_account,
+            outcome_mints,
+            user_outcome_accounts,
             &spl_token::id(),
             withdraw_amount,
         )
@@ -166,11 +165,24 @@ pub async fn make_withdraw(
     program_context
         .banks_client
         .process_transaction(transaction)
-        .await
-        .unwrap();
+        .await?;
     Ok(())
 }
 
+fn assert_pool_error(result: Result<(), TransportError>, error: PoolError) {
+    match result.unwrap_err() {
+        TransportError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(code),
+        )) => assert_eq!(code, error as u32),
+        err => panic!("unexpected error {:?}", err),
+    }
+}
+
+fn pubkeys(keypairs: &[Keypair]) -> Vec<Pubkey> {
+    keypairs.iter().map(|keypair| keypair.pubkey()).collect()
+}
+
 pub async fn get_token_balance(banks_client: &mut BanksClient, token: &Pubkey) -> u64 {
     let token_account = banks_client.get_account(*token).await.unwrap().unwrap();
     let account_info: spl_token::state::Account =
@@ -185,8 +197,7 @@ pub struct TestPool {
     pub deposit_token_mint: Keypair,
     pub deposit_token_mint_owner: Keypair,
     pub pool_deposit_account: Keypair,
-    pub token_pass_mint: Keypair,
-    pub token_fail_mint: Keypair,
+    pub outcome_mints: Vec<Keypair>,
     pub decider: Keypair,
     pub mint_end_slot: u64,
     pub decide_end_slot: u64,
@@ -194,6 +205,10 @@ pub struct TestPool {
 
 impl TestPool {
     pub fn new() -> Self {
+        Self::with_outcomes(2)
+    }
+
+    pub fn with_outcomes(num_outcomes: usize) -> Self {
         let pool_account = Keypair::new();
         let (authority, bump_seed) =
             Pubkey::find_program_address(&[&pool_account.pubkey().to_bytes()[..32]], &id());
@@ -204,22 +219,37 @@ impl TestPool {
             deposit_token_mint: Keypair::new(),
             deposit_token_mint_owner: Keypair::new(),
             pool_deposit_account: Keypair::new(),
-            token_pass_mint: Keypair::new(),
-            token_fail_mint: Keypair::new(),
+            outcome_mints: (0..num_outcomes).map(|_| Keypair::new()).collect(),
             decider: Keypair::new(),
             mint_end_slot: 2,
             decide_end_slot: 2000,
         }
     }
 
+    pub fn outcome_mint_pubkeys(&self) -> Vec<Pubkey> {
+        pubkeys(&self.outcome_mints)
+    }
+
     pub async fn init_pool(
         &self,
         banks_client: &mut BanksClient,
         payer: &Keypair,
         recent_blockhash: &Hash,
     ) {
+        self.try_init_pool(banks_client, payer, recent_blockhash)
+            .await
+            .unwrap();
+    }
+
+    pub async fn try_init_pool(
+        &self,
+        banks_client: &mut BanksClient,
+        payer: &Keypair,
+        recent_blockhash: &Hash,
+    ) -> Result<(), TransportError> {
         let rent = banks_client.get_rent().await.unwrap();
-        let pool_rent = rent.minimum_balance(state::Pool::LEN);
+        let pool_len = state::Pool::get_packed_len(self.outcome_mints.len());
+        let pool_rent = rent.minimum_balance(pool_len);
         let mint_rent = rent.minimum_balance(spl_token::state::Mint::LEN);
         let account_rent = rent.minimum_balance(spl_token::state::Account::LEN);
 
@@ -230,7 +260,7 @@ impl TestPool {
             recent_blockhash,
             &self.pool_account,
             pool_rent,
-            state::Pool::LEN as u64,
+            pool_len as u64,
             &id(),
         )
         .await
@@ -254,58 +284,47 @@ impl TestPool {
             bump_seed: self.bump_seed,
         };
 
-        let mut transaction = Transaction::new_with_payer(
-            &[
-                system_instruction::create_account(
-                    &payer.pubkey(),
-                    &self.pool_deposit_account.pubkey(),
-                    account_rent,
-                    spl_token::state::Account::LEN as u64,
-                    &spl_token::id(),
-                ),
-                system_instruction::create_account(
-                    &payer.pubkey(),
-                    &self.token_pass_mint.pubkey(),
-                    mint_rent,
-                    spl_token::state::Mint::LEN as u64,
-                    &spl_token::id(),
-                ),
-                system_instruction::create_account(
-                    &payer.pubkey(),
-                    &self.token_fail_mint.pubkey(),
-                    mint_rent,
-                    spl_token::state::Mint::LEN as u64,
-                    &spl_token::id(),
-                ),
-                instruction::init_pool(
-                    &id(),
-                    &self.pool_account.pubkey(),
-                    &self.authority,
-                    &self.decider.pubkey(),
-                    &self.deposit_token_mint.pubkey(),
-                    &self.pool_deposit_account.pubkey(),
-                    &self.token_pass_mint.pubkey(),
-                    &self.token_fail_mint.pubkey(),
-                    &spl_token::id(),
-                    init_args,
-                )
-                .unwrap(),
-            ],
-            Some(&payer.pubkey()),
+        let mut instructions = vec![system_instruction::create_account(
+            &payer.pubkey(),
+            &self.pool_deposit_account.pubkey(),
+            account_rent,
+            spl_token::state::Account::LEN as u64,
+            &spl_token::id(),
+        )];
+        for outcome_mint in &self.outcome_mints {
+            instructions.push(system_instruction::create_account(
+                &payer.pubkey(),
+                &outcome_mint.pubkey(),
+                mint_rent,
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::id(),
+            ));
+        }
+        instructions.push(
+            instruction::init_pool(
+                &id(),
+                &self.pool_account.pubkey(),
+                &self.authority,
+                &self.decider.pubkey(),
+                &self.deposit_token_mint.pubkey(),
+                &self.pool_deposit_account.pubkey(),
+                &self.outcome_mint_pubkeys(),
+                &spl_token::id(),
+                init_args,
+            )
+            .unwrap(),
         );
+        let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
 
-        transaction.sign(
-            &[
-                payer,
-                &self.pool_deposit_account,
-                &self.token_pass_mint,
-                &self.token_fail_mint,
-            ],
-            *recent_blockhash,
-        );
-        banks_client.process_transaction(transaction).await.unwrap();
+        let mut signers = vec![payer, &self.pool_deposit_account];
+        signers.extend(self.outcome_mints.iter());
+        transaction.sign(&signers, *recent_blockhash);
+        banks_client.process_transaction(transaction).await?;
+        Ok(())
     }
 
+    /// Creates a funded deposit token account for the user, and one token
+    /// account per outcome
     #[allow(clippy::too_many_arguments)]
     pub async fn prepare_accounts_for_deposit(
         &self,
@@ -317,9 +336,7 @@ impl TestPool {
         user_account: &Keypair,
         authority: &Pubkey,
         user_account_owner: &Keypair,
-        user_pass_account: &Keypair,
-        user_fail_account: &Keypair,
-    ) {
+    ) -> Vec<Keypair> {
         // Create user account
         create_token_account(
             banks_client,
@@ -358,39 +375,32 @@ impl TestPool {
         .await
         .unwrap();
 
-        // Create token accounts for PASS and FAIL tokens
-        create_token_account(
-            banks_client,
-            payer,
-            recent_blockhash,
-            user_pass_account,
-            &self.token_pass_mint.pubkey(),
-            &user_account_owner.pubkey(),
-        )
-        .await
-        .unwrap();
-
-        create_token_account(
-            banks_client,
-            payer,
-            recent_blockhash,
-            user_fail_account,
-            &self.token_fail_mint.pubkey(),
-            &user_account_owner.pubkey(),
-        )
-        .await
-        .unwrap();
+        // Create token accounts for the outcome tokens
+        let mut user_outcome_accounts = vec![];
+        for outcome_mint in &self.outcome_mints {
+            let user_outcome_account = Keypair::new();
+            create_token_account(
+                banks_client,
+                payer,
+                recent_blockhash,
+                &user_outcome_account,
+                &outcome_mint.pubkey(),
+                &user_account_owner.pubkey(),
+            )
+            .await
+            .unwrap();
+            user_outcome_accounts.push(user_outcome_account);
+        }
+        user_outcome_accounts
     }
 
-    #[allow(clippy::too_many_arguments)]
     pub async fn make_deposit(
         &self,
         banks_client: &mut BanksClient,
         payer: &Keypair,
         recent_blockhash: &Hash,
         user_account: &Keypair,
-        user_pass_account: &Keypair,
-        user_fail_account: &Keypair,
+        user_outcome_accounts: &[Keypair],
         deposit_amount: u64,
     ) {
         let mut transaction = Transaction::new_with_payer(
@@ -401,10 +411,8 @@ impl TestPool {
                 &self.authority,
                 &user_account.pubkey(),
                 &self.pool_deposit_account.pubkey(),
-                &self.token_pass_mint.pubkey(),
-                &self.token_fail_mint.pubkey(),
-                &user_pass_account.pubkey(),
-                &user_fail_account.pubkey(),
+                &self.outcome_mint_pubkeys(),
+                &pubkeys(user_outcome_accounts),
                 &spl_token::id(),
                 deposit_amount,
             )
@@ -423,8 +431,7 @@ impl TestPool {
         recent_blockhash: &Hash,
         user_account: &Keypair,
         user_authority: &Keypair,
-        user_pass_account: &Keypair,
-        user_fail_account: &Keypair,
+        user_outcome_accounts: &[Keypair],
         deposit_amount: u64,
     ) {
         let mut transaction = Transaction::new_with_payer(
@@ -435,10 +442,8 @@ impl TestPool {
                 &user_authority.pubkey(),
                 &user_account.pubkey(),
                 &self.pool_deposit_account.pubkey(),
-                &self.token_pass_mint.pubkey(),
-                &self.token_fail_mint.pubkey(),
-                &user_pass_account.pubkey(),
-                &user_fail_account.pubkey(),
+                &self.outcome_mint_pubkeys(),
+                &pubkeys(user_outcome_accounts),
                 &spl_token::id(),
                 deposit_amount,
             )
@@ -521,23 +526,107 @@ async fn get_account(banks_client: &mut BanksClient, pubkey: &Pubkey) -> Account
         .expect("account empty")
 }
 
+/// Deposits `deposit_amount` for a new user and lets the pool authority burn
+/// their outcome tokens
+async fn deposit_and_approve_burns(
+    program_context: &mut ProgramTestContext,
+    pool: &TestPool,
+    deposit_amount: u64,
+) -> (Keypair, Vec<Keypair>) {
+    let user_account = Keypair::new();
+    let user_account_owner = Keypair::new();
+
+    let user_outcome_accounts = pool
+        .prepare_accounts_for_deposit(
+            &mut program_context.banks_client,
+            &program_context.payer,
+            &program_context.last_blockhash,
+            deposit_amount,
+            deposit_amount,
+            &user_account,
+            &pool.authority,
+            &user_account_owner,
+        )
+        .await;
+
+    pool.make_deposit(
+        &mut program_context.banks_client,
+        &program_context.payer,
+        &program_context.last_blockhash,
+        &user_account,
+        &user_outcome_accounts,
+        deposit_amount,
+    )
+    .await;
+
+    // Set allowances to burn the outcome tokens
+    for user_outcome_account in &user_outcome_accounts {
+        approve_delegate(
+            &mut program_context.banks_client,
+            &program_context.payer,
+            &program_context.last_blockhash,
+            &user_outcome_account.pubkey(),
+            &pool.authority,
+            &user_account_owner,
+            deposit_amount,
+        )
+        .await
+        .unwrap();
+    }
+    (user_account, user_outcome_accounts)
+}
+
 #[tokio::test]
 async fn test_init_pool() {
     let (mut banks_client, payer, recent_blockhash) = program_test().start().await;
 
-    let pool = TestPool::new();
+    let pool = TestPool::with_outcomes(3);
 
     pool.init_pool(&mut banks_client, &payer, &recent_blockhash)
         .await;
 
     let pool_account_data = get_account(&mut banks_client, &pool.pool_account.pubkey()).await;
 
-    assert_eq!(pool_account_data.data.len(), state::Pool::LEN);
+    assert_eq!(pool_account_data.data.len(), state::Pool::get_packed_len(3));
     assert_eq!(pool_account_data.owner, id());
 
     // check if Pool is initialized
-    let pool = state::Pool::try_from_slice(pool_account_data.data.as_slice()).unwrap();
-    assert!(pool.is_initialized());
+    let pool_data =
+        solana_program::borsh1::try_from_slice_unchecked::<state::Pool>(&pool_account_data.data)
+            .unwrap();
+    assert!(pool_data.is_initialized());
+    assert_eq!(pool_data.outcome_mints, pool.outcome_mint_pubkeys());
+    for outcome_mint in &pool.outcome_mints {
+        let mint_account = get_account(&mut banks_client, &outcome_mint.pubkey()).await;
+        let mint = spl_token::state::Mint::unpack(&mint_account.data).unwrap();
+        assert_eq!(mint.mint_authority, Some(pool.authority).into());
+    }
+}
+
+#[tokio::test]
+async fn test_init_pool_with_single_outcome() {
+    let (mut banks_client, payer, recent_blockhash) = program_test().start().await;
+
+    let pool = TestPool::with_outcomes(1);
+
+    assert_pool_error(
+        pool.try_init_pool(&mut banks_client, &payer, &recent_blockhash)
+            .await,
+        PoolError::InvalidOutcomeCount,
+    );
+}
+
+#[tokio::test]
+async fn test_init_pool_with_too_many_outcomes() {
+    let (mut banks_client, payer, recent_blockhash) = program_test().start().await;
+
+    let pool = TestPool::with_outcomes(state::MAX_OUTCOMES + 1);
+
+    assert_pool_error(
+        pool.try_init_pool(&mut banks_client, &payer, &recent_blockhash)
+            .await,
+        PoolError::InvalidOutcomeCount,
+    );
 }
 
 #[tokio::test]
@@ -546,29 +635,26 @@ async fn test_deposit_with_program_authority() {
 
     let deposit_amount = 100;
 
-    let pool = TestPool::new();
+    let pool = TestPool::with_outcomes(3);
 
     pool.init_pool(&mut banks_client, &payer, &recent_blockhash)
         .await;
 
     let user_account = Keypair::new();
     let user_account_owner = Keypair::new();
-    let user_pass_account = Keypair::new();
-    let user_fail_account = Keypair::new();
 
-    pool.prepare_accounts_for_deposit(
-        &mut banks_client,
-        &payer,
-        &recent_blockhash,
-        deposit_amount,
-        deposit_amount,
-        &user_account,
-        &pool.authority,
-        &user_account_owner,
-        &user_pass_account,
-        &user_fail_account,
-    )
-    .await;
+    let user_outcome_accounts = pool
+        .prepare_accounts_for_deposit(
+            &mut banks_client,
+            &payer,
+            &recent_blockhash,
+            deposit_amount,
+            deposit_amount,
+            &user_account,
+            &pool.authority,
+            &user_account_owner,
+        )
+        .await;
 
     let user_balance_before = get_token_balance(&mut banks_client, &user_account.pubkey()).await;
     assert_eq!(user_balance_before, deposit_amount);
@@ -579,8 +665,7 @@ async fn test_deposit_with_program_authority() {
         &payer,
         &recent_blockhash,
         &user_account,
-        &user_pass_account,
-        &user_fail_account,
+        &user_outcome_accounts,
         deposit_amount,
     )
     .await;
@@ -594,12 +679,12 @@ async fn test_deposit_with_program_authority() {
         get_token_balance(&mut banks_client, &pool.pool_deposit_account.pubkey()).await;
     assert_eq!(pool_deposit_account_balance, deposit_amount);
 
-    // Check if user has PASS and FAIL tokens
-    let user_pass_tokens = get_token_balance(&mut banks_client, &user_pass_account.pubkey()).await;
-    assert_eq!(user_pass_tokens, deposit_amount);
-
-    let user_fail_tokens = get_token_balance(&mut banks_client, &user_fail_account.pubkey()).await;
-    assert_eq!(user_fail_tokens, deposit_amount);
+    // Check if user has one token of each outcome per deposit
+    for user_outcome_account in &user_outcome_accounts {
+        let user_outcome_tokens =
+            get_token_balance(&mut banks_client, &user_outcome_account.pubkey()).await;
+        assert_eq!(user_outcome_tokens, deposit_amount);
+    }
 }
 
 #[tokio::test]
@@ -616,22 +701,19 @@ async fn test_deposit_with_user_authority() {
     let user_account = Keypair::new();
     let user_account_owner = Keypair::new();
     let user_transfer_authority = Keypair::new();
-    let user_pass_account = Keypair::new();
-    let user_fail_account = Keypair::new();
 
-    pool.prepare_accounts_for_deposit(
-        &mut banks_client,
-        &payer,
-        &recent_blockhash,
-        deposit_amount,
-        deposit_amount,
-        &user_account,
-        &user_transfer_authority.pubkey(),
-        &user_account_owner,
-        &user_pass_account,
-        &user_fail_account,
-    )
-    .await;
+    let user_outcome_accounts = pool
+        .prepare_accounts_for_deposit(
+            &mut banks_client,
+            &payer,
+            &recent_blockhash,
+            deposit_amount,
+            deposit_amount,
+            &user_account,
+            &user_transfer_authority.pubkey(),
+            &user_account_owner,
+        )
+        .await;
 
     let user_balance_before = get_token_balance(&mut banks_client, &user_account.pubkey()).await;
     assert_eq!(user_balance_before, deposit_amount);
@@ -643,8 +725,7 @@ async fn test_deposit_with_user_authority() {
         &recent_blockhash,
         &user_account,
         &user_transfer_authority,
-        &user_pass_account,
-        &user_fail_account,
+        &user_outcome_accounts,
         deposit_amount,
     )
     .await;
@@ -658,12 +739,12 @@ async fn test_deposit_with_user_authority() {
         get_token_balance(&mut banks_client, &pool.pool_deposit_account.pubkey()).await;
     assert_eq!(pool_deposit_account_balance, deposit_amount);
 
-    // Check if user has PASS and FAIL tokens
-    let user_pass_tokens = get_token_balance(&mut banks_client, &user_pass_account.pubkey()).await;
-    assert_eq!(user_pass_tokens, deposit_amount);
-
-    let user_fail_tokens = get_token_balance(&mut banks_client, &user_fail_account.pubkey()).await;
-    assert_eq!(user_fail_tokens, deposit_amount);
+    // Check if user has one token of each outcome per deposit
+    for user_outcome_account in &user_outcome_accounts {
+        let user_outcome_tokens =
+            get_token_balance(&mut banks_client, &user_outcome_account.pubkey()).await;
+        assert_eq!(user_outcome_tokens, deposit_amount);
+    }
 }
 
 #[tokio::test]
@@ -673,7 +754,7 @@ async fn test_withdraw_no_decision() {
     let deposit_amount = 100;
     let withdraw_amount = 50;
 
-    let pool = TestPool::new();
+    let pool = TestPool::with_outcomes(3);
 
     pool.init_pool(
         &mut program_context.banks_client,
@@ -682,232 +763,62 @@ async fn test_withdraw_no_decision() {
     )
     .await;
 
-    let user_account = Keypair::new();
-    let user_account_owner = Keypair::new();
-    let user_pass_account = Keypair::new();
-    let user_fail_account = Keypair::new();
-
-    pool.prepare_accounts_for_deposit(
-        &mut program_context.banks_client,
-        &program_context.payer,
-        &program_context.last_blockhash,
-        deposit_amount,
-        deposit_amount,
-        &user_account,
-        &pool.authority,
-        &user_account_owner,
-        &user_pass_account,
-        &user_fail_account,
-    )
-    .await;
-
-    // Make deposit
-    pool.make_deposit(
-        &mut program_context.banks_client,
-        &program_context.payer,
-        &program_context.last_blockhash,
-        &user_account,
-        &user_pass_account,
-        &user_fail_account,
-        deposit_amount,
-    )
-    .await;
-
-    // Set allowances to burn PASS and FAIL tokens
-    approve_delegate(
-        &mut program_context.banks_client,
-        &program_context.payer,
-        &program_context.last_blockhash,
-        &user_pass_account.pubkey(),
-        &pool.authority,
-        &user_account_owner,
-        deposit_amount,
-    )
-    .await
-    .unwrap();
-    approve_delegate(
-        &mut program_context.banks_client,
-        &program_context.payer,
-        &program_context.last_blockhash,
-        &user_fail_account.pubkey(),
-        &pool.authority,
-        &user_account_owner,
-        deposit_amount,
-    )
-    .await
-    .unwrap();
+    let (user_account, user_outcome_accounts) =
+        deposit_and_approve_burns(&mut program_context, &pool, deposit_amount).await;
 
     let user_balance_before =
         get_token_balance(&mut program_context.banks_client, &user_account.pubkey()).await;
     assert_eq!(user_balance_before, 0);
 
-    // Check balance of pool deposit account
-    let pool_deposit_account_balance = get_token_balance(
-        &mut program_context.banks_client,
-        &pool.pool_deposit_account.pubkey(),
-    )
-    .await;
-    assert_eq!(pool_deposit_account_balance, deposit_amount);
-
-    // Check if user has PASS and FAIL tokens
-    let user_pass_tokens = get_token_balance(
-        &mut program_context.banks_client,
-        &user_pass_account.pubkey(),
-    )
-    .await;
-    assert_eq!(user_pass_tokens, deposit_amount);
-
-    let user_fail_tokens = get_token_balance(
-        &mut program_context.banks_client,
-        &user_fail_account.pubkey(),
-    )
-    .await;
-    assert_eq!(user_fail_tokens, deposit_amount);
-
-    make_withdraw(
+    // Burning an incomplete set is not allowed
+    let err = make_withdraw(
         &mut program_context,
         &pool.pool_account.pubkey(),
         &pool.authority,
         &pool.authority,
         &pool.pool_deposit_account.pubkey(),
-        &user_pass_account.pubkey(),
-        &user_fail_account.pubkey(),
-        &pool.token_pass_mint.pubkey(),
-        &pool.token_fail_mint.pubkey(),
         &user_account.pubkey(),
+        &pool.outcome_mint_pubkeys()[..2],
+        &pubkeys(&user_outcome_accounts)[..2],
         withdraw_amount,
     )
     .await
-    .unwrap();
-
-    let user_balance_after =
-        get_token_balance(&mut program_context.banks_client, &user_account.pubkey()).await;
-    assert_eq!(user_balance_after, withdraw_amount);
-
-    // Check balance of pool deposit account after withdraw
-    let pool_deposit_account_balance_after = get_token_balance(
-        &mut program_context.banks_client,
-        &pool.pool_deposit_account.pubkey(),
-    )
-    .await;
-    assert_eq!(
-        pool_deposit_account_balance_after,
-        deposit_amount - withdraw_amount
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        TransportError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::NotEnoughAccountKeys
+        ))
+    ));
+
+    // Withdrawing from another account than the pool deposit account is not
+    // allowed
+    assert_pool_error(
+        make_withdraw(
+            &mut program_context,
+            &pool.pool_account.pubkey(),
+            &pool.authority,
+            &pool.authority,
+            &user_account.pubkey(),
+            &user_account.pubkey(),
+            &pool.outcome_mint_pubkeys(),
+            &pubkeys(&user_outcome_accounts),
+            withdraw_amount,
+        )
+        .await,
+        PoolError::InvalidDepositAccount,
     );
 
-    // Check if program burned PASS and FAIL tokens
-    let user_pass_tokens_after = get_token_balance(
-        &mut program_context.banks_client,
-        &user_pass_account.pubkey(),
-    )
-    .await;
-    assert_eq!(user_pass_tokens_after, deposit_amount - withdraw_amount);
-
-    let user_fail_tokens_after = get_token_balance(
-        &mut program_context.banks_client,
-        &user_fail_account.pubkey(),
-    )
-    .await;
-    assert_eq!(user_fail_tokens_after, deposit_amount - withdraw_amount);
-}
-
-#[tokio::test]
-async fn test_withdraw_pass_decision() {
-    let mut program_context = program_test().start_with_context().await;
-
-    let deposit_amount = 100;
-    let withdraw_amount = 50;
-
-    let pool = TestPool::new();
-
-    pool.init_pool(
-        &mut program_context.banks_client,
-        &program_context.payer,
-        &program_context.last_blockhash,
-    )
-    .await;
-
-    let user_account = Keypair::new();
-    let user_account_owner = Keypair::new();
-    let user_pass_account = Keypair::new();
-    let user_fail_account = Keypair::new();
-
-    pool.prepare_accounts_for_deposit(
-        &mut program_context.banks_client,
-        &program_context.payer,
-        &program_context.last_blockhash,
-        deposit_amount,
-        deposit_amount,
-        &user_account,
-        &pool.authority,
-        &user_account_owner,
-        &user_pass_account,
-        &user_fail_account,
-    )
-    .await;
-
-    // Make deposit
-    pool.make_deposit(
-        &mut program_context.banks_client,
-        &program_context.payer,
-        &program_context.last_blockhash,
-        &user_account,
-        &user_pass_account,
-        &user_fail_account,
-        deposit_amount,
-    )
-    .await;
-
-    // Set allowances to burn PASS and FAIL tokens
-    approve_delegate(
-        &mut program_context.banks_client,
-        &program_context.payer,
-        &program_context.last_blockhash,
-        &user_pass_account.pubkey(),
-        &pool.authority,
-        &user_account_owner,
-        deposit_amount,
-    )
-    .await
-    .unwrap();
-    approve_delegate(
-        &mut program_context.banks_client,
-        &program_context.payer,
-        &program_context.last_blockhash,
-        &user_fail_account.pubkey(),
-        &pool.authority,
-        &user_account_owner,
-        deposit_amount,
-    )
-    .await
-    .unwrap();
-
-    let decision = true;
-
-    program_context
-        .warp_to_slot(pool.mint_end_slot + 1)
-        .unwrap();
-
-    make_decision(
-        &mut program_context,
-        &pool.pool_account.pubkey(),
-        &pool.decider,
-        decision,
-    )
-    .await
-    .unwrap();
-
     make_withdraw(
         &mut program_context,
         &pool.pool_account.pubkey(),
         &pool.authority,
         &pool.authority,
         &pool.pool_deposit_account.pubkey(),
-        &user_pass_account.pubkey(),
-        &user_fail_account.pubkey(),
-        &pool.token_pass_mint.pubkey(),
-        &pool.token_fail_mint.pubkey(),
         &user_account.pubkey(),
+        &pool.outcome_mint_pubkeys(),
+        &pubkeys(&user_outcome_accounts),
         withdraw_amount,
     )
     .await
@@ -928,30 +839,26 @@ async fn test_withdraw_pass_decision() {
         deposit_amount - withdraw_amount
     );
 
-    // Check if program burned PASS and FAIL tokens
-    let user_pass_tokens_after = get_token_balance(
-        &mut program_context.banks_client,
-        &user_pass_account.pubkey(),
-    )
-    .await;
-    assert_eq!(user_pass_tokens_after, deposit_amount - withdraw_amount);
-
-    let user_fail_tokens_after = get_token_balance(
-        &mut program_context.banks_client,
-        &user_fail_account.pubkey(),
-    )
-    .await;
-    assert_eq!(user_fail_tokens_after, deposit_amount);
+    // Check if program burned a full set of outcome tokens
+    for user_outcome_account in &user_outcome_accounts {
+        let user_outcome_tokens_after = get_token_balance(
+            &mut program_context.banks_client,
+            &user_outcome_account.pubkey(),
+        )
+        .await;
+        assert_eq!(user_outcome_tokens_after, deposit_amount - withdraw_amount);
+    }
 }
 
 #[tokio::test]
-async fn test_withdraw_fail_decision() {
+async fn test_withdraw_decided_outcome() {
     let mut program_context = program_test().start_with_context().await;
 
     let deposit_amount = 100;
     let withdraw_amount = 50;
+    let outcome = 1;
 
-    let pool = TestPool::new();
+    let pool = TestPool::with_outcomes(3);
 
     pool.init_pool(
         &mut program_context.banks_client,
@@ -960,62 +867,8 @@ async fn test_withdraw_fail_decision() {
     )
     .await;
 
-    let user_account = Keypair::new();
-    let user_account_owner = Keypair::new();
-    let user_pass_account = Keypair::new();
-    let user_fail_account = Keypair::new();
-
-    pool.prepare_accounts_for_deposit(
-        &mut program_context.banks_client,
-        &program_context.payer,
-        &program_context.last_blockhash,
-        deposit_amount,
-        deposit_amount,
-        &user_account,
-        &pool.authority,
-        &user_account_owner,
-        &user_pass_account,
-        &user_fail_account,
-    )
-    .await;
-
-    // Make deposit
-    pool.make_deposit(
-        &mut program_context.banks_client,
-        &program_context.payer,
-        &program_context.last_blockhash,
-        &user_account,
-        &user_pass_account,
-        &user_fail_account,
-        deposit_amount,
-    )
-    .await;
-
-    // Set allowances to burn PASS and FAIL tokens
-    approve_delegate(
-        &mut program_context.banks_client,
-        &program_context.payer,
-        &program_context.last_blockhash,
-        &user_pass_account.pubkey(),
-        &pool.authority,
-        &user_account_owner,
-        deposit_amount,
-    )
-    .await
-    .unwrap();
-    approve_delegate(
-        &mut program_context.banks_client,
-        &program_context.payer,
-        &program_context.last_blockhash,
-        &user_fail_account.pubkey(),
-        &pool.authority,
-        &user_account_owner,
-        deposit_amount,
-    )
-    .await
-    .unwrap();
-
-    let decision = false;
+    let (user_account, user_outcome_accounts) =
+        deposit_and_approve_burns(&mut program_context, &pool, deposit_amount).await;
 
     program_context
         .warp_to_slot(pool.mint_end_slot + 1)
@@ -1025,22 +878,37 @@ async fn test_withdraw_fail_decision() {
         &mut program_context,
         &pool.pool_account.pubkey(),
         &pool.decider,
-        decision,
+        outcome as u8,
     )
     .await
     .unwrap();
 
+    // Losing outcomes cannot be redeemed
+    assert_pool_error(
+        make_withdraw(
+            &mut program_context,
+            &pool.pool_account.pubkey(),
+            &pool.authority,
+            &pool.authority,
+            &pool.pool_deposit_account.pubkey(),
+            &user_account.pubkey(),
+            &[pool.outcome_mints[0].pubkey()],
+            &[user_outcome_accounts[0].pubkey()],
+            withdraw_amount,
+        )
+        .await,
+        PoolError::InvalidTokenMint,
+    );
+
     make_withdraw(
         &mut program_context,
         &pool.pool_account.pubkey(),
         &pool.authority,
         &pool.authority,
         &pool.pool_deposit_account.pubkey(),
-        &user_pass_account.pubkey(),
-        &user_fail_account.pubkey(),
-        &pool.token_pass_mint.pubkey(),
-        &pool.token_fail_mint.pubkey(),
         &user_account.pubkey(),
+        &[pool.outcome_mints[outcome].pubkey()],
+        &[user_outcome_accounts[outcome].pubkey()],
         withdraw_amount,
     )
     .await
@@ -1061,27 +929,26 @@ async fn test_withdraw_fail_decision() {
         deposit_amount - withdraw_amount
     );
 
-    // Check if program burned PASS and FAIL tokens
-    let user_pass_tokens_after = get_token_balance(
-        &mut program_context.banks_client,
-        &user_pass_account.pubkey(),
-    )
-    .await;
-    assert_eq!(user_pass_tokens_after, deposit_amount);
-
-    let user_fail_tokens_after = get_token_balance(
-        &mut program_context.banks_client,
-        &user_fail_account.pubkey(),
-    )
-    .await;
-    assert_eq!(user_fail_tokens_after, deposit_amount - withdraw_amount);
+    // Check if program only burned tokens of the decided outcome
+    for (i, user_outcome_account) in user_outcome_accounts.iter().enumerate() {
+        let user_outcome_tokens_after = get_token_balance(
+            &mut program_context.banks_client,
+            &user_outcome_account.pubkey(),
+        )
+        .await;
+        if i == outcome {
+            assert_eq!(user_outcome_tokens_after, deposit_amount - withdraw_amount);
+        } else {
+            assert_eq!(user_outcome_tokens_after, deposit_amount);
+        }
+    }
 }
 
 #[tokio::test]
 async fn test_decide() {
     let mut program_context = program_test().start_with_context().await;
 
-    let pool = TestPool::new();
+    let pool = TestPool::with_outcomes(3);
 
     pool.init_pool(
         &mut program_context.banks_client,
@@ -1097,22 +964,34 @@ async fn test_decide() {
         .unwrap()
         .unwrap();
 
-    let pool_data_before =
-        state::Pool::try_from_slice(pool_account_data_before.data.as_slice()).unwrap();
+    let pool_data_before = solana_program::borsh1::try_from_slice_unchecked::<state::Pool>(
+        pool_account_data_before.data.as_slice(),
+    )
+    .unwrap();
 
     assert_eq!(pool_data_before.decision, state::Decision::Undecided);
 
-    let decision = true;
-
     program_context
         .warp_to_slot(pool.mint_end_slot + 1)
         .unwrap();
 
+    // The outcome index must name one of the pool outcomes
+    assert_pool_error(
+        make_decision(
+            &mut program_context,
+            &pool.pool_account.pubkey(),
+            &pool.decider,
+            3,
+        )
+        .await,
+        PoolError::InvalidOutcome,
+    );
+
     make_decision(
         &mut program_context,
         &pool.pool_account.pubkey(),
         &pool.decider,
-        decision,
+        2,
     )
     .await
     .unwrap();
@@ -1127,5 +1006,5 @@ async fn test_decide() {
     let pool_data_after =
         state::Pool::try_from_slice(pool_account_data_after.data.as_slice()).unwrap();
 
-    assert_eq!(pool_data_after.decision, state::Decision::Pass);
+    assert_eq!(pool_data_after.decision, state::Decision::Decided(2));
 }
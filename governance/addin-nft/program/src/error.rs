@@ -0,0 +1,117 @@
+//! Error types
+
+use {
+    num_derive::FromPrimitive,
+    solana_program::{
+        decode_error::DecodeError,
+        msg,
+        program_error::{PrintProgramError, ProgramError},
+    },
+    thiserror::Error,
+};
+
+/// Errors that may be returned by the NftVoter addin program
+#[derive(Clone, Debug, Eq, Error, FromPrimitive, PartialEq)]
+pub enum NftVoterError {
+    /// Invalid Realm authority
+    #[error("Invalid Realm authority")]
+    InvalidRealmAuthority = 1200,
+
+    /// Invalid Realm for Registrar
+    #[error("Invalid Realm for Registrar")]
+    InvalidRealmForRegistrar,
+
+    /// Invalid Governing Token Mint for Registrar
+    #[error("Invalid Governing Token Mint for Registrar")]
+    InvalidGoverningTokenMintForRegistrar,
+
+    /// Max number of collections reached for Registrar
+    #[error("Max number of collections reached for Registrar")]
+    MaxCollectionsReached,
+
+    /// Invalid collection weight or size
+    #[error("Invalid collection weight or size")]
+    InvalidCollectionConfig,
+
+    /// Invalid collection account
+    #[error("Invalid collection account")]
+    InvalidCollectionAccount,
+
+    /// Collection is not configured for Registrar
+    #[error("Collection is not configured for Registrar")]
+    CollectionNotConfigured,
+
+    /// Collection is not verified for NFT
+    #[error("Collection is not verified for NFT")]
+    CollectionNotVerified,
+
+    /// Invalid NFT collection membership account
+    #[error("Invalid NFT collection membership account")]
+    InvalidCollectionMembershipAccount,
+
+    /// Invalid NFT token account owner
+    #[error("Invalid NFT token account owner")]
+    InvalidNftOwner,
+
+    /// Invalid NFT token account or mint
+    #[error("Invalid NFT token account or mint")]
+    InvalidNftAccount,
+
+    /// Duplicated NFT
+    #[error("Duplicated NFT")]
+    DuplicatedNftDetected,
+
+    /// Invalid VoterWeightRecord for Registrar
+    #[error("Invalid VoterWeightRecord for Registrar")]
+    InvalidVoterWeightRecordForRegistrar,
+
+    /// Invalid MaxVoterWeightRecord for Registrar
+    #[error("Invalid MaxVoterWeightRecord for Registrar")]
+    InvalidMaxVoterWeightRecordForRegistrar,
+
+    /// Governing token owner must sign
+    #[error("Governing token owner must sign")]
+    GoverningTokenOwnerMustSign,
+
+    /// CastVote action must be used via CastNftVote instruction
+    #[error("CastVote action must be used via CastNftVote instruction")]
+    CastVoteIsNotAllowed,
+
+    /// NFT already voted on the Proposal
+    #[error("NFT already voted on the Proposal")]
+    NftAlreadyVoted,
+
+    /// Invalid Proposal for NftVoteRecord
+    #[error("Invalid Proposal for NftVoteRecord")]
+    InvalidProposalForNftVoteRecord,
+
+    /// Invalid governing token owner for NftVoteRecord
+    #[error("Invalid governing token owner for NftVoteRecord")]
+    InvalidTokenOwnerForNftVoteRecord,
+
+    /// Invalid governance VoteRecord address
+    #[error("Invalid governance VoteRecord address")]
+    InvalidVoteRecordAddress,
+
+    /// Governance vote must be relinquished before NFT votes can be relinquished
+    #[error("Governance vote must be relinquished before NFT votes can be relinquished")]
+    VoteRecordMustBeRelinquished,
+}
+
+impl PrintProgramError for NftVoterError {
+    fn print<E>(&self) {
+        msg!("GOVERNANCE-ADDIN-NFT-ERROR: {}", &self.to_string());
+    }
+}
+
+impl From<NftVoterError> for ProgramError {
+    fn from(e: NftVoterError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+impl<T> DecodeError<T> for NftVoterError {
+    fn type_of() -> &'static str {
+        "Governance NFT Voter Addin Error"
+    }
+}
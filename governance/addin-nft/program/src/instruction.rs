@@ -0,0 +1,450 @@
+//! Program instructions
+
+use {
+    crate::state::{
+        enums::CollectionKind, max_voter_weight_record::get_max_voter_weight_record_address,
+        nft_vote_record::get_nft_vote_record_address, registrar::get_registrar_address,
+        voter_weight_record::get_voter_weight_record_address,
+    },
+    borsh::{BorshDeserialize, BorshSchema, BorshSerialize},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+    spl_governance::state::{
+        token_owner_record::get_token_owner_record_address, vote_record::get_vote_record_address,
+    },
+    spl_governance_addin_api::voter_weight::VoterWeightAction,
+};
+
+/// NFT accounts used to establish voter weight
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NftVoteAccounts {
+    /// Token account holding the NFT
+    pub nft_token_account: Pubkey,
+
+    /// NFT mint
+    pub nft_mint: Pubkey,
+
+    /// Collection membership account
+    /// Either the Metaplex Metadata account of the NFT or the TokenGroupMember
+    /// account the NFT mint points to
+    pub collection_membership: Pubkey,
+}
+
+/// Instructions supported by the NftVoter addin program
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
+#[allow(clippy::large_enum_variant)]
+pub enum NftVoterInstruction {
+    /// Creates Registrar for the given Realm and governing token mint
+    /// The Registrar stores the NFT collections which can be used for voting
+    ///
+    /// 0. `[writable]` Registrar account.
+    ///     * PDA seeds: ['registrar', realm, governing_token_mint]
+    /// 1. `[]` Governance program id
+    /// 2. `[]` Realm account
+    /// 3. `[]` Governing token mint of the Realm (community or council)
+    /// 4. `[signer]` Realm authority
+    /// 5. `[signer]` Payer
+    /// 6. `[]` System
+    CreateRegistrar {
+        /// Max number of collections which can be configured for the Registrar
+        #[allow(dead_code)]
+        max_collections: u8,
+    },
+
+    /// Creates MaxVoterWeightRecord for the Registrar
+    /// The max voter weight is computed from the configured collections
+    ///
+    /// 0. `[]` Registrar account
+    /// 1. `[writable]` MaxVoterWeightRecord account.
+    ///     * PDA seeds: ['max-voter-weight-record', realm,
+    ///       governing_token_mint]
+    /// 2. `[signer]` Payer
+    /// 3. `[]` System
+    CreateMaxVoterWeightRecord {},
+
+    /// Configures NFT collection which can be used for voting
+    /// If the collection is already configured then its config is replaced
+    /// The MaxVoterWeightRecord is updated with the new max voter weight
+    ///
+    /// 0. `[writable]` Registrar account
+    /// 1. `[]` Realm account
+    /// 2. `[signer]` Realm authority
+    /// 3. `[]` Collection account. Collection NFT mint for Metaplex
+    ///    collections or TokenGroup account for token-group collections
+    /// 4. `[writable]` MaxVoterWeightRecord account
+    ConfigureCollection {
+        /// The kind of the collection
+        #[allow(dead_code)]
+        kind: CollectionKind,
+
+        /// The number of NFTs in the collection
+        #[allow(dead_code)]
+        size: u32,
+
+        /// The voter weight of each NFT from the collection
+        #[allow(dead_code)]
+        weight: u64,
+    },
+
+    /// Creates VoterWeightRecord for the given governing token owner
+    ///
+    /// 0. `[]` Registrar account
+    /// 1. `[writable]` VoterWeightRecord account.
+    ///     * PDA seeds: ['voter-weight-record', realm, governing_token_mint,
+    ///       governing_token_owner]
+    /// 2. `[signer]` Payer
+    /// 3. `[]` System
+    CreateVoterWeightRecord {
+        /// The governing token owner (voter) the record is for
+        #[allow(dead_code)]
+        governing_token_owner: Pubkey,
+    },
+
+    /// Updates VoterWeightRecord with the weight of the given NFTs for any
+    /// action other than CastVote
+    /// The weight is valid only within the current slot and the instruction
+    /// should be executed in the same transaction as the governance
+    /// instruction
+    ///
+    /// 0. `[]` Registrar account
+    /// 1. `[writable]` VoterWeightRecord account
+    ///
+    /// For each NFT:
+    /// n. `[]` NFT token account owned by the governing token owner
+    /// n+1. `[]` NFT mint
+    /// n+2. `[]` Collection membership account. Metaplex Metadata account or
+    ///      the TokenGroupMember account the NFT mint points to
+    UpdateVoterWeightRecord {
+        /// The governance action the voter weight is evaluated for
+        #[allow(dead_code)]
+        voter_weight_action: VoterWeightAction,
+    },
+
+    /// Casts NFT votes on the given Proposal and accumulates the weight of the
+    /// NFTs into VoterWeightRecord
+    /// For each NFT an NftVoteRecord is created to prevent the NFT from being
+    /// used again on the same Proposal
+    /// If the NFTs don't fit into a single transaction then the instruction
+    /// can be executed multiple times within the same slot and the weight
+    /// is accumulated
+    ///
+    /// 0. `[]` Registrar account
+    /// 1. `[writable]` VoterWeightRecord account
+    /// 2. `[signer]` Governing token owner (voter)
+    /// 3. `[signer]` Payer
+    /// 4. `[]` System
+    ///
+    /// For each NFT:
+    /// n. `[]` NFT token account owned by the governing token owner
+    /// n+1. `[]` NFT mint
+    /// n+2. `[]` Collection membership account. Metaplex Metadata account or
+    ///      the TokenGroupMember account the NFT mint points to
+    /// n+3. `[writable]` NftVoteRecord account.
+    ///     * PDA seeds: ['nft-vote-record', proposal, nft_mint]
+    CastNftVote {
+        /// The Proposal the NFTs vote on
+        #[allow(dead_code)]
+        proposal: Pubkey,
+    },
+
+    /// Relinquishes NFT votes on the given Proposal and disposes the
+    /// NftVoteRecord accounts
+    /// If the Proposal is still being voted on then the governance vote must
+    /// be relinquished first
+    ///
+    /// 0. `[]` Registrar account
+    /// 1. `[writable]` VoterWeightRecord account
+    /// 2. `[]` Governance account
+    /// 3. `[]` Proposal account
+    /// 4. `[]` Governance VoteRecord account of the governing token owner's
+    ///    TokenOwnerRecord for the Proposal. It can be already disposed
+    ///     * PDA seeds: ['governance', proposal, token_owner_record]
+    /// 5. `[signer]` Governing token owner (voter)
+    /// 6. `[writable]` Beneficiary account which receives the lamports from
+    ///    the disposed NftVoteRecord accounts
+    ///
+    /// For each NFT:
+    /// n. `[writable]` NftVoteRecord account
+    RelinquishNftVote {},
+}
+
+/// Creates CreateRegistrar instruction
+#[allow(clippy::too_many_arguments)]
+pub fn create_registrar(
+    program_id: &Pubkey,
+    // Accounts
+    governance_program_id: &Pubkey,
+    realm: &Pubkey,
+    governing_token_mint: &Pubkey,
+    realm_authority: &Pubkey,
+    payer: &Pubkey,
+    // Args
+    max_collections: u8,
+) -> Instruction {
+    let registrar_address = get_registrar_address(program_id, realm, governing_token_mint);
+
+    let accounts = vec![
+        AccountMeta::new(registrar_address, false),
+        AccountMeta::new_readonly(*governance_program_id, false),
+        AccountMeta::new_readonly(*realm, false),
+        AccountMeta::new_readonly(*governing_token_mint, false),
+        AccountMeta::new_readonly(*realm_authority, true),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    let instruction = NftVoterInstruction::CreateRegistrar { max_collections };
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: borsh::to_vec(&instruction).unwrap(),
+    }
+}
+
+/// Creates CreateMaxVoterWeightRecord instruction
+pub fn create_max_voter_weight_record(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governing_token_mint: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    let registrar_address = get_registrar_address(program_id, realm, governing_token_mint);
+    let max_voter_weight_record_address =
+        get_max_voter_weight_record_address(program_id, realm, governing_token_mint);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(registrar_address, false),
+        AccountMeta::new(max_voter_weight_record_address, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    let instruction = NftVoterInstruction::CreateMaxVoterWeightRecord {};
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: borsh::to_vec(&instruction).unwrap(),
+    }
+}
+
+/// Creates ConfigureCollection instruction
+#[allow(clippy::too_many_arguments)]
+pub fn configure_collection(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governing_token_mint: &Pubkey,
+    realm_authority: &Pubkey,
+    collection: &Pubkey,
+    // Args
+    kind: CollectionKind,
+    size: u32,
+    weight: u64,
+) -> Instruction {
+    let registrar_address = get_registrar_address(program_id, realm, governing_token_mint);
+    let max_voter_weight_record_address =
+        get_max_voter_weight_record_address(program_id, realm, governing_token_mint);
+
+    let accounts = vec![
+        AccountMeta::new(registrar_address, false),
+        AccountMeta::new_readonly(*realm, false),
+        AccountMeta::new_readonly(*realm_authority, true),
+        AccountMeta::new_readonly(*collection, false),
+        AccountMeta::new(max_voter_weight_record_address, false),
+    ];
+
+    let instruction = NftVoterInstruction::ConfigureCollection { kind, size, weight };
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: borsh::to_vec(&instruction).unwrap(),
+    }
+}
+
+/// Creates CreateVoterWeightRecord instruction
+pub fn create_voter_weight_record(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governing_token_mint: &Pubkey,
+    payer: &Pubkey,
+    // Args
+    governing_token_owner: &Pubkey,
+) -> Instruction {
+    let registrar_address = get_registrar_address(program_id, realm, governing_token_mint);
+    let voter_weight_record_address = get_voter_weight_record_address(
+        program_id,
+        realm,
+        governing_token_mint,
+        governing_token_owner,
+    );
+
+    let accounts = vec![
+        AccountMeta::new_readonly(registrar_address, false),
+        AccountMeta::new(voter_weight_record_address, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    let instruction = NftVoterInstruction::CreateVoterWeightRecord {
+        governing_token_owner: *governing_token_owner,
+    };
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: borsh::to_vec(&instruction).unwrap(),
+    }
+}
+
+/// Creates UpdateVoterWeightRecord instruction
+pub fn update_voter_weight_record(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governing_token_mint: &Pubkey,
+    governing_token_owner: &Pubkey,
+    nfts: &[NftVoteAccounts],
+    // Args
+    voter_weight_action: VoterWeightAction,
+) -> Instruction {
+    let registrar_address = get_registrar_address(program_id, realm, governing_token_mint);
+    let voter_weight_record_address = get_voter_weight_record_address(
+        program_id,
+        realm,
+        governing_token_mint,
+        governing_token_owner,
+    );
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(registrar_address, false),
+        AccountMeta::new(voter_weight_record_address, false),
+    ];
+
+    for nft in nfts {
+        accounts.push(AccountMeta::new_readonly(nft.nft_token_account, false));
+        accounts.push(AccountMeta::new_readonly(nft.nft_mint, false));
+        accounts.push(AccountMeta::new_readonly(nft.collection_membership, false));
+    }
+
+    let instruction = NftVoterInstruction::UpdateVoterWeightRecord {
+        voter_weight_action,
+    };
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: borsh::to_vec(&instruction).unwrap(),
+    }
+}
+
+/// Creates CastNftVote instruction
+#[allow(clippy::too_many_arguments)]
+pub fn cast_nft_vote(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governing_token_mint: &Pubkey,
+    governing_token_owner: &Pubkey,
+    payer: &Pubkey,
+    nfts: &[NftVoteAccounts],
+    // Args
+    proposal: &Pubkey,
+) -> Instruction {
+    let registrar_address = get_registrar_address(program_id, realm, governing_token_mint);
+    let voter_weight_record_address = get_voter_weight_record_address(
+        program_id,
+        realm,
+        governing_token_mint,
+        governing_token_owner,
+    );
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(registrar_address, false),
+        AccountMeta::new(voter_weight_record_address, false),
+        AccountMeta::new_readonly(*governing_token_owner, true),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    for nft in nfts {
+        let nft_vote_record_address =
+            get_nft_vote_record_address(program_id, proposal, &nft.nft_mint);
+
+        accounts.push(AccountMeta::new_readonly(nft.nft_token_account, false));
+        accounts.push(AccountMeta::new_readonly(nft.nft_mint, false));
+        accounts.push(AccountMeta::new_readonly(nft.collection_membership, false));
+        accounts.push(AccountMeta::new(nft_vote_record_address, false));
+    }
+
+    let instruction = NftVoterInstruction::CastNftVote {
+        proposal: *proposal,
+    };
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: borsh::to_vec(&instruction).unwrap(),
+    }
+}
+
+/// Creates RelinquishNftVote instruction
+#[allow(clippy::too_many_arguments)]
+pub fn relinquish_nft_vote(
+    program_id: &Pubkey,
+    // Accounts
+    governance_program_id: &Pubkey,
+    realm: &Pubkey,
+    governing_token_mint: &Pubkey,
+    governance: &Pubkey,
+    proposal: &Pubkey,
+    governing_token_owner: &Pubkey,
+    beneficiary: &Pubkey,
+    nft_mints: &[Pubkey],
+) -> Instruction {
+    let registrar_address = get_registrar_address(program_id, realm, governing_token_mint);
+    let voter_weight_record_address = get_voter_weight_record_address(
+        program_id,
+        realm,
+        governing_token_mint,
+        governing_token_owner,
+    );
+    let token_owner_record_address = get_token_owner_record_address(
+        governance_program_id,
+        realm,
+        governing_token_mint,
+        governing_token_owner,
+    );
+    let vote_record_address =
+        get_vote_record_address(governance_program_id, proposal, &token_owner_record_address);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(registrar_address, false),
+        AccountMeta::new(voter_weight_record_address, false),
+        AccountMeta::new_readonly(*governance, false),
+        AccountMeta::new_readonly(*proposal, false),
+        AccountMeta::new_readonly(vote_record_address, false),
+        AccountMeta::new_readonly(*governing_token_owner, true),
+        AccountMeta::new(*beneficiary, false),
+    ];
+
+    for nft_mint in nft_mints {
+        let nft_vote_record_address = get_nft_vote_record_address(program_id, proposal, nft_mint);
+        accounts.push(AccountMeta::new(nft_vote_record_address, false));
+    }
+
+    let instruction = NftVoterInstruction::RelinquishNftVote {};
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: borsh::to_vec(&instruction).unwrap(),
+    }
+}
@@ -0,0 +1,62 @@
+//! Program processor
+
+mod process_cast_nft_vote;
+mod process_configure_collection;
+mod process_create_max_voter_weight_record;
+mod process_create_registrar;
+mod process_create_voter_weight_record;
+mod process_relinquish_nft_vote;
+mod process_update_voter_weight_record;
+
+use {
+    crate::instruction::NftVoterInstruction,
+    process_cast_nft_vote::*,
+    process_configure_collection::*,
+    process_create_max_voter_weight_record::*,
+    process_create_registrar::*,
+    process_create_voter_weight_record::*,
+    process_relinquish_nft_vote::*,
+    process_update_voter_weight_record::*,
+    solana_program::{
+        account_info::AccountInfo, borsh1::try_from_slice_unchecked, entrypoint::ProgramResult,
+        msg, program_error::ProgramError, pubkey::Pubkey,
+    },
+};
+
+/// Processes an instruction
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    msg!("VERSION:{:?}", env!("CARGO_PKG_VERSION"));
+
+    let instruction: NftVoterInstruction =
+        try_from_slice_unchecked(input).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    msg!("GOVERNANCE-ADDIN-NFT-INSTRUCTION: {:?}", instruction);
+
+    match instruction {
+        NftVoterInstruction::CreateRegistrar { max_collections } => {
+            process_create_registrar(program_id, accounts, max_collections)
+        }
+        NftVoterInstruction::CreateMaxVoterWeightRecord {} => {
+            process_create_max_voter_weight_record(program_id, accounts)
+        }
+        NftVoterInstruction::ConfigureCollection { kind, size, weight } => {
+            process_configure_collection(program_id, accounts, kind, size, weight)
+        }
+        NftVoterInstruction::CreateVoterWeightRecord {
+            governing_token_owner,
+        } => process_create_voter_weight_record(program_id, accounts, governing_token_owner),
+        NftVoterInstruction::UpdateVoterWeightRecord {
+            voter_weight_action,
+        } => process_update_voter_weight_record(program_id, accounts, voter_weight_action),
+        NftVoterInstruction::CastNftVote { proposal } => {
+            process_cast_nft_vote(program_id, accounts, proposal)
+        }
+        NftVoterInstruction::RelinquishNftVote {} => {
+            process_relinquish_nft_vote(program_id, accounts)
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Program state processor
+
+use {
+    crate::{
+        error::NftVoterError,
+        state::{
+            enums::NftVoterAccountType,
+            nft_vote_record::{get_nft_vote_record_address_seeds, NftVoteRecord},
+            registrar::get_registrar_data,
+            voter_weight_record::get_voter_weight_record_data_for_registrar,
+        },
+        tools::nft::resolve_nft_vote_weight,
+    },
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        clock::Clock,
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+        rent::Rent,
+        sysvar::Sysvar,
+    },
+    spl_governance_addin_api::voter_weight::VoterWeightAction,
+    spl_governance_tools::account::create_and_serialize_account_signed,
+};
+
+/// Processes CastNftVote instruction
+pub fn process_cast_nft_vote(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    proposal: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let registrar_info = next_account_info(account_info_iter)?; // 0
+    let voter_weight_record_info = next_account_info(account_info_iter)?; // 1
+    let governing_token_owner_info = next_account_info(account_info_iter)?; // 2
+    let payer_info = next_account_info(account_info_iter)?; // 3
+    let system_info = next_account_info(account_info_iter)?; // 4
+
+    let rent = Rent::get()?;
+    let clock = Clock::get()?;
+
+    let registrar_data = get_registrar_data(program_id, registrar_info)?;
+
+    let mut voter_weight_record_data = get_voter_weight_record_data_for_registrar(
+        program_id,
+        voter_weight_record_info,
+        &registrar_data,
+    )?;
+
+    if voter_weight_record_data.governing_token_owner != *governing_token_owner_info.key
+        || !governing_token_owner_info.is_signer
+    {
+        return Err(NftVoterError::GoverningTokenOwnerMustSign.into());
+    }
+
+    let mut voter_weight = 0u64;
+    let mut unique_nft_mints = vec![];
+
+    while account_info_iter.len() > 0 {
+        let nft_token_account_info = next_account_info(account_info_iter)?; // n
+        let nft_mint_info = next_account_info(account_info_iter)?; // n+1
+        let collection_membership_info = next_account_info(account_info_iter)?; // n+2
+        let nft_vote_record_info = next_account_info(account_info_iter)?; // n+3
+
+        let nft_vote_weight = resolve_nft_vote_weight(
+            &registrar_data,
+            governing_token_owner_info.key,
+            nft_token_account_info,
+            nft_mint_info,
+            collection_membership_info,
+            &mut unique_nft_mints,
+        )?;
+
+        voter_weight = voter_weight.checked_add(nft_vote_weight).unwrap();
+
+        // The NftVoteRecord PDA can only be created once per Proposal and NFT
+        // and it prevents the NFT from being used again after it's transferred
+        if !nft_vote_record_info.data_is_empty() {
+            return Err(NftVoterError::NftAlreadyVoted.into());
+        }
+
+        let nft_vote_record_data = NftVoteRecord {
+            account_type: NftVoterAccountType::NftVoteRecord,
+            proposal,
+            nft_mint: *nft_mint_info.key,
+            governing_token_owner: *governing_token_owner_info.key,
+            reserved: [0; 8],
+        };
+
+        create_and_serialize_account_signed(
+            payer_info,
+            nft_vote_record_info,
+            &nft_vote_record_data,
+            &get_nft_vote_record_address_seeds(&proposal, nft_mint_info.key),
+            program_id,
+            system_info,
+            &rent,
+            0,
+        )?;
+    }
+
+    // If the voter has more NFTs than fit into a single transaction then the
+    // instruction can be executed multiple times within the same slot for the
+    // same Proposal and the weight is accumulated
+    if voter_weight_record_data.weight_action == Some(VoterWeightAction::CastVote)
+        && voter_weight_record_data.weight_action_target == Some(proposal)
+        && voter_weight_record_data.voter_weight_expiry == Some(clock.slot)
+    {
+        voter_weight = voter_weight_record_data
+            .voter_weight
+            .checked_add(voter_weight)
+            .unwrap();
+    }
+
+    voter_weight_record_data.voter_weight = voter_weight;
+
+    // The weight is only valid for the current slot and must be used by
+    // the governance CastVote instruction within the same transaction
+    voter_weight_record_data.voter_weight_expiry = Some(clock.slot);
+    voter_weight_record_data.weight_action = Some(VoterWeightAction::CastVote);
+    voter_weight_record_data.weight_action_target = Some(proposal);
+
+    borsh::to_writer(
+        &mut voter_weight_record_info.data.borrow_mut()[..],
+        &voter_weight_record_data,
+    )?;
+
+    Ok(())
+}
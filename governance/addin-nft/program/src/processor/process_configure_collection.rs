@@ -0,0 +1,71 @@
+//! Program state processor
+
+use {
+    crate::{
+        error::NftVoterError,
+        state::{
+            enums::CollectionKind,
+            max_voter_weight_record::get_max_voter_weight_record_data_for_registrar,
+            registrar::{get_registrar_data, CollectionConfig},
+        },
+        tools::nft::get_collection_membership_program_id,
+    },
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+    },
+};
+
+/// Processes ConfigureCollection instruction
+pub fn process_configure_collection(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    kind: CollectionKind,
+    size: u32,
+    weight: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let registrar_info = next_account_info(account_info_iter)?; // 0
+    let realm_info = next_account_info(account_info_iter)?; // 1
+    let realm_authority_info = next_account_info(account_info_iter)?; // 2
+    let collection_info = next_account_info(account_info_iter)?; // 3
+    let max_voter_weight_record_info = next_account_info(account_info_iter)?; // 4
+
+    let mut registrar_data = get_registrar_data(program_id, registrar_info)?;
+
+    registrar_data.assert_realm_authority_is_signer(realm_info, realm_authority_info)?;
+
+    if size == 0 {
+        return Err(NftVoterError::InvalidCollectionConfig.into());
+    }
+
+    let membership_program_id = get_collection_membership_program_id(collection_info, &kind)?;
+
+    registrar_data.upsert_collection_config(CollectionConfig {
+        collection: *collection_info.key,
+        kind,
+        membership_program_id,
+        size,
+        weight,
+        reserved: [0; 8],
+    })?;
+
+    let mut max_voter_weight_record_data = get_max_voter_weight_record_data_for_registrar(
+        program_id,
+        max_voter_weight_record_info,
+        &registrar_data,
+    )?;
+
+    max_voter_weight_record_data.max_voter_weight = registrar_data.get_max_voter_weight()?;
+
+    borsh::to_writer(
+        &mut max_voter_weight_record_info.data.borrow_mut()[..],
+        &max_voter_weight_record_data,
+    )?;
+
+    borsh::to_writer(&mut registrar_info.data.borrow_mut()[..], &registrar_data)?;
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+//! Program state processor
+
+use {
+    crate::state::{
+        max_voter_weight_record::get_max_voter_weight_record_address_seeds,
+        registrar::get_registrar_data,
+    },
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+        rent::Rent,
+        sysvar::Sysvar,
+    },
+    spl_governance_addin_api::max_voter_weight::MaxVoterWeightRecord,
+    spl_governance_tools::account::create_and_serialize_account_signed,
+};
+
+/// Processes CreateMaxVoterWeightRecord instruction
+pub fn process_create_max_voter_weight_record(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let registrar_info = next_account_info(account_info_iter)?; // 0
+    let max_voter_weight_record_info = next_account_info(account_info_iter)?; // 1
+    let payer_info = next_account_info(account_info_iter)?; // 2
+    let system_info = next_account_info(account_info_iter)?; // 3
+
+    let rent = Rent::get()?;
+
+    let registrar_data = get_registrar_data(program_id, registrar_info)?;
+
+    // The max voter weight is derived from the collections configuration and
+    // doesn't change until the collections are reconfigured hence it never
+    // expires
+    let max_voter_weight_record_data = MaxVoterWeightRecord {
+        account_discriminator: MaxVoterWeightRecord::ACCOUNT_DISCRIMINATOR,
+        realm: registrar_data.realm,
+        governing_token_mint: registrar_data.governing_token_mint,
+        max_voter_weight: registrar_data.get_max_voter_weight()?,
+        max_voter_weight_expiry: None,
+        reserved: [0; 8],
+    };
+
+    create_and_serialize_account_signed(
+        payer_info,
+        max_voter_weight_record_info,
+        &max_voter_weight_record_data,
+        &get_max_voter_weight_record_address_seeds(
+            &registrar_data.realm,
+            &registrar_data.governing_token_mint,
+        ),
+        program_id,
+        system_info,
+        &rent,
+        0,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+//! Program state processor
+
+use {
+    crate::state::{
+        enums::NftVoterAccountType,
+        registrar::{assert_realm_authority_is_signer, get_registrar_address_seeds, Registrar},
+    },
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+        rent::Rent,
+        sysvar::Sysvar,
+    },
+    spl_governance::state::realm::get_realm_data_for_governing_token_mint,
+    spl_governance_tools::account::create_and_serialize_account_signed,
+};
+
+/// Processes CreateRegistrar instruction
+pub fn process_create_registrar(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    max_collections: u8,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let registrar_info = next_account_info(account_info_iter)?; // 0
+    let governance_program_info = next_account_info(account_info_iter)?; // 1
+    let realm_info = next_account_info(account_info_iter)?; // 2
+    let governing_token_mint_info = next_account_info(account_info_iter)?; // 3
+    let realm_authority_info = next_account_info(account_info_iter)?; // 4
+    let payer_info = next_account_info(account_info_iter)?; // 5
+    let system_info = next_account_info(account_info_iter)?; // 6
+
+    let rent = Rent::get()?;
+
+    let realm_data = get_realm_data_for_governing_token_mint(
+        governance_program_info.key,
+        realm_info,
+        governing_token_mint_info.key,
+    )?;
+
+    assert_realm_authority_is_signer(&realm_data.authority, realm_authority_info)?;
+
+    let registrar_data = Registrar {
+        account_type: NftVoterAccountType::Registrar,
+        governance_program_id: *governance_program_info.key,
+        realm: *realm_info.key,
+        governing_token_mint: *governing_token_mint_info.key,
+        max_collections,
+        collection_configs: vec![],
+        reserved: [0; 64],
+    };
+
+    create_and_serialize_account_signed(
+        payer_info,
+        registrar_info,
+        &registrar_data,
+        &get_registrar_address_seeds(realm_info.key, governing_token_mint_info.key),
+        program_id,
+        system_info,
+        &rent,
+        0,
+    )?;
+
+    Ok(())
+}
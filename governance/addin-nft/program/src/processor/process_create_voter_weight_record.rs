@@ -0,0 +1,68 @@
+//! Program state processor
+
+use {
+    crate::state::{
+        registrar::get_registrar_data, voter_weight_record::get_voter_weight_record_address_seeds,
+    },
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+        rent::Rent,
+        sysvar::Sysvar,
+    },
+    spl_governance_addin_api::voter_weight::{VoterWeightAction, VoterWeightRecord},
+    spl_governance_tools::account::create_and_serialize_account_signed,
+};
+
+/// Processes CreateVoterWeightRecord instruction
+pub fn process_create_voter_weight_record(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    governing_token_owner: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let registrar_info = next_account_info(account_info_iter)?; // 0
+    let voter_weight_record_info = next_account_info(account_info_iter)?; // 1
+    let payer_info = next_account_info(account_info_iter)?; // 2
+    let system_info = next_account_info(account_info_iter)?; // 3
+
+    let rent = Rent::get()?;
+
+    let registrar_data = get_registrar_data(program_id, registrar_info)?;
+
+    // The record is created with no weight and it's expired from the start
+    // The weight must be established using UpdateVoterWeightRecord or
+    // CastNftVote before it can be used
+    // Note: weight_action and weight_action_target are set upfront to allocate
+    // the max size of the account because they are only known later
+    let voter_weight_record_data = VoterWeightRecord {
+        account_discriminator: VoterWeightRecord::ACCOUNT_DISCRIMINATOR,
+        realm: registrar_data.realm,
+        governing_token_mint: registrar_data.governing_token_mint,
+        governing_token_owner,
+        voter_weight: 0,
+        voter_weight_expiry: Some(0),
+        weight_action: Some(VoterWeightAction::CastVote),
+        weight_action_target: Some(Pubkey::default()),
+        reserved: [0; 8],
+    };
+
+    create_and_serialize_account_signed(
+        payer_info,
+        voter_weight_record_info,
+        &voter_weight_record_data,
+        &get_voter_weight_record_address_seeds(
+            &registrar_data.realm,
+            &registrar_data.governing_token_mint,
+            &governing_token_owner,
+        ),
+        program_id,
+        system_info,
+        &rent,
+        0,
+    )?;
+
+    Ok(())
+}
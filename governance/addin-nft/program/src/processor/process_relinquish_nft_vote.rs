@@ -0,0 +1,121 @@
+//! Program state processor
+
+use {
+    crate::{
+        error::NftVoterError,
+        state::{
+            nft_vote_record::get_nft_vote_record_data_for_proposal_and_token_owner,
+            registrar::get_registrar_data,
+            voter_weight_record::get_voter_weight_record_data_for_registrar,
+        },
+    },
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        clock::Clock,
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+        sysvar::Sysvar,
+    },
+    spl_governance::state::{
+        enums::ProposalState, governance::get_governance_data_for_realm,
+        proposal::get_proposal_data_for_governance,
+        token_owner_record::get_token_owner_record_address, vote_record::get_vote_record_address,
+    },
+    spl_governance_tools::account::dispose_account,
+};
+
+/// Processes RelinquishNftVote instruction
+pub fn process_relinquish_nft_vote(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let registrar_info = next_account_info(account_info_iter)?; // 0
+    let voter_weight_record_info = next_account_info(account_info_iter)?; // 1
+    let governance_info = next_account_info(account_info_iter)?; // 2
+    let proposal_info = next_account_info(account_info_iter)?; // 3
+    let vote_record_info = next_account_info(account_info_iter)?; // 4
+    let governing_token_owner_info = next_account_info(account_info_iter)?; // 5
+    let beneficiary_info = next_account_info(account_info_iter)?; // 6
+
+    let registrar_data = get_registrar_data(program_id, registrar_info)?;
+    let governance_program_id = &registrar_data.governance_program_id;
+
+    let mut voter_weight_record_data = get_voter_weight_record_data_for_registrar(
+        program_id,
+        voter_weight_record_info,
+        &registrar_data,
+    )?;
+
+    if voter_weight_record_data.governing_token_owner != *governing_token_owner_info.key
+        || !governing_token_owner_info.is_signer
+    {
+        return Err(NftVoterError::GoverningTokenOwnerMustSign.into());
+    }
+
+    let governance_data = get_governance_data_for_realm(
+        governance_program_id,
+        governance_info,
+        &registrar_data.realm,
+    )?;
+
+    let proposal_data = get_proposal_data_for_governance(
+        governance_program_id,
+        proposal_info,
+        governance_info.key,
+    )?;
+
+    let token_owner_record_address = get_token_owner_record_address(
+        governance_program_id,
+        &registrar_data.realm,
+        &registrar_data.governing_token_mint,
+        governing_token_owner_info.key,
+    );
+
+    let vote_record_address = get_vote_record_address(
+        governance_program_id,
+        proposal_info.key,
+        &token_owner_record_address,
+    );
+
+    if vote_record_address != *vote_record_info.key {
+        return Err(NftVoterError::InvalidVoteRecordAddress.into());
+    }
+
+    let clock = Clock::get()?;
+
+    // If the Proposal is still being voted on then the NFT votes can only be
+    // relinquished once the governance vote was relinquished and the VoteRecord
+    // disposed. Otherwise the NFTs could be used again to increase the weight
+    // of the already cast vote
+    if proposal_data.state == ProposalState::Voting
+        && !proposal_data.has_voting_max_time_ended(&governance_data.config, clock.unix_timestamp)
+    {
+        if !vote_record_info.data_is_empty() {
+            return Err(NftVoterError::VoteRecordMustBeRelinquished.into());
+        }
+
+        // Invalidate the accumulated weight to prevent it from being used after
+        // the NftVoteRecords are disposed
+        voter_weight_record_data.voter_weight = 0;
+        voter_weight_record_data.voter_weight_expiry = Some(clock.slot);
+
+        borsh::to_writer(
+            &mut voter_weight_record_info.data.borrow_mut()[..],
+            &voter_weight_record_data,
+        )?;
+    }
+
+    while account_info_iter.len() > 0 {
+        let nft_vote_record_info = next_account_info(account_info_iter)?; // n
+
+        get_nft_vote_record_data_for_proposal_and_token_owner(
+            program_id,
+            nft_vote_record_info,
+            proposal_info.key,
+            governing_token_owner_info.key,
+        )?;
+
+        dispose_account(nft_vote_record_info, beneficiary_info)?;
+    }
+
+    Ok(())
+}
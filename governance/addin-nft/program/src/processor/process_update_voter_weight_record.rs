@@ -0,0 +1,86 @@
+//! Program state processor
+
+use {
+    crate::{
+        error::NftVoterError,
+        state::{
+            registrar::get_registrar_data,
+            voter_weight_record::get_voter_weight_record_data_for_registrar,
+        },
+        tools::nft::resolve_nft_vote_weight,
+    },
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        clock::Clock,
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+        sysvar::Sysvar,
+    },
+    spl_governance_addin_api::voter_weight::VoterWeightAction,
+};
+
+/// Processes UpdateVoterWeightRecord instruction
+pub fn process_update_voter_weight_record(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    voter_weight_action: VoterWeightAction,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let registrar_info = next_account_info(account_info_iter)?; // 0
+    let voter_weight_record_info = next_account_info(account_info_iter)?; // 1
+
+    // CastVote requires NftVoteRecords to prevent double voting with the same
+    // NFTs and hence must be established using CastNftVote
+    if voter_weight_action == VoterWeightAction::CastVote {
+        return Err(NftVoterError::CastVoteIsNotAllowed.into());
+    }
+
+    let registrar_data = get_registrar_data(program_id, registrar_info)?;
+
+    let mut voter_weight_record_data = get_voter_weight_record_data_for_registrar(
+        program_id,
+        voter_weight_record_info,
+        &registrar_data,
+    )?;
+
+    let mut voter_weight = 0u64;
+    let mut unique_nft_mints = vec![];
+
+    while account_info_iter.len() > 0 {
+        let nft_token_account_info = next_account_info(account_info_iter)?; // n
+        let nft_mint_info = next_account_info(account_info_iter)?; // n+1
+        let collection_membership_info = next_account_info(account_info_iter)?; // n+2
+
+        let nft_vote_weight = resolve_nft_vote_weight(
+            &registrar_data,
+            &voter_weight_record_data.governing_token_owner,
+            nft_token_account_info,
+            nft_mint_info,
+            collection_membership_info,
+            &mut unique_nft_mints,
+        )?;
+
+        voter_weight = voter_weight.checked_add(nft_vote_weight).unwrap();
+    }
+
+    let clock = Clock::get()?;
+
+    voter_weight_record_data.voter_weight = voter_weight;
+
+    // The weight is only valid for the current slot because NFTs can be
+    // transferred at any time
+    voter_weight_record_data.voter_weight_expiry = Some(clock.slot);
+    voter_weight_record_data.weight_action = Some(voter_weight_action);
+
+    // The target is not known here and it's not used for actions other than
+    // CastVote
+    voter_weight_record_data.weight_action_target = None;
+
+    borsh::to_writer(
+        &mut voter_weight_record_info.data.borrow_mut()[..],
+        &voter_weight_record_data,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+//! State enumerations
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+/// Defines all NftVoter accounts types
+/// Note: VoterWeightRecord and MaxVoterWeightRecord use the addin api
+/// discriminators and are not part of the enum
+#[derive(Clone, Debug, Default, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub enum NftVoterAccountType {
+    /// Default uninitialized account state
+    #[default]
+    Uninitialized,
+
+    /// Registrar with the NFT collections configured for a Realm and governing
+    /// token mint
+    Registrar,
+
+    /// NFT vote record which prevents the same NFT from being used twice to
+    /// vote on a Proposal
+    NftVoteRecord,
+}
+
+/// The kind of NFT collection and how membership of an NFT is established
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub enum CollectionKind {
+    /// Metaplex Token Metadata collection
+    /// The collection address is the collection NFT mint and the membership is
+    /// established by the verified `collection` of the NFT metadata
+    Metaplex,
+
+    /// token-group interface collection (for example spl-token-collection)
+    /// The collection address is the TokenGroup account and the membership is
+    /// established by the TokenGroupMember the NFT mint points to
+    TokenGroup,
+}
@@ -0,0 +1,52 @@
+//! MaxVoterWeightRecord account
+
+use {
+    crate::{error::NftVoterError, state::registrar::Registrar},
+    solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey},
+    spl_governance_addin_api::max_voter_weight::MaxVoterWeightRecord,
+    spl_governance_tools::account::get_account_data,
+};
+
+/// Returns MaxVoterWeightRecord PDA seeds
+pub fn get_max_voter_weight_record_address_seeds<'a>(
+    realm: &'a Pubkey,
+    governing_token_mint: &'a Pubkey,
+) -> [&'a [u8]; 3] {
+    [
+        b"max-voter-weight-record",
+        realm.as_ref(),
+        governing_token_mint.as_ref(),
+    ]
+}
+
+/// Returns MaxVoterWeightRecord PDA address
+pub fn get_max_voter_weight_record_address(
+    program_id: &Pubkey,
+    realm: &Pubkey,
+    governing_token_mint: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &get_max_voter_weight_record_address_seeds(realm, governing_token_mint),
+        program_id,
+    )
+    .0
+}
+
+/// Deserializes MaxVoterWeightRecord account and checks it belongs to the
+/// given Registrar
+pub fn get_max_voter_weight_record_data_for_registrar(
+    program_id: &Pubkey,
+    max_voter_weight_record_info: &AccountInfo,
+    registrar: &Registrar,
+) -> Result<MaxVoterWeightRecord, ProgramError> {
+    let max_voter_weight_record_data =
+        get_account_data::<MaxVoterWeightRecord>(program_id, max_voter_weight_record_info)?;
+
+    if max_voter_weight_record_data.realm != registrar.realm
+        || max_voter_weight_record_data.governing_token_mint != registrar.governing_token_mint
+    {
+        return Err(NftVoterError::InvalidMaxVoterWeightRecordForRegistrar.into());
+    }
+
+    Ok(max_voter_weight_record_data)
+}
@@ -0,0 +1,7 @@
+//! Program accounts
+
+pub mod enums;
+pub mod max_voter_weight_record;
+pub mod nft_vote_record;
+pub mod registrar;
+pub mod voter_weight_record;
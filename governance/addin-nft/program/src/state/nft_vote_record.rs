@@ -0,0 +1,84 @@
+//! NftVoteRecord account
+
+use {
+    crate::{error::NftVoterError, state::enums::NftVoterAccountType},
+    borsh::{BorshDeserialize, BorshSchema, BorshSerialize},
+    solana_program::{
+        account_info::AccountInfo, program_error::ProgramError, program_pack::IsInitialized,
+        pubkey::Pubkey,
+    },
+    spl_governance_tools::account::{get_account_data, AccountMaxSize},
+};
+
+/// NftVoteRecord account
+/// The account is created when an NFT is used to vote on a Proposal and
+/// guarantees the same NFT can't be used again to vote on the same Proposal
+/// regardless of its current owner
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub struct NftVoteRecord {
+    /// Account type
+    pub account_type: NftVoterAccountType,
+
+    /// The Proposal the NFT was used to vote on
+    pub proposal: Pubkey,
+
+    /// The mint of the NFT
+    pub nft_mint: Pubkey,
+
+    /// The owner of the NFT at the time of the vote
+    /// Only the owner can relinquish the vote and reclaim the rent
+    pub governing_token_owner: Pubkey,
+
+    /// Reserved space for future versions
+    pub reserved: [u8; 8],
+}
+
+impl AccountMaxSize for NftVoteRecord {}
+
+impl IsInitialized for NftVoteRecord {
+    fn is_initialized(&self) -> bool {
+        self.account_type == NftVoterAccountType::NftVoteRecord
+    }
+}
+
+/// Returns NftVoteRecord PDA seeds
+pub fn get_nft_vote_record_address_seeds<'a>(
+    proposal: &'a Pubkey,
+    nft_mint: &'a Pubkey,
+) -> [&'a [u8]; 3] {
+    [b"nft-vote-record", proposal.as_ref(), nft_mint.as_ref()]
+}
+
+/// Returns NftVoteRecord PDA address
+pub fn get_nft_vote_record_address(
+    program_id: &Pubkey,
+    proposal: &Pubkey,
+    nft_mint: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &get_nft_vote_record_address_seeds(proposal, nft_mint),
+        program_id,
+    )
+    .0
+}
+
+/// Deserializes NftVoteRecord account and checks it belongs to the given
+/// Proposal and governing token owner
+pub fn get_nft_vote_record_data_for_proposal_and_token_owner(
+    program_id: &Pubkey,
+    nft_vote_record_info: &AccountInfo,
+    proposal: &Pubkey,
+    governing_token_owner: &Pubkey,
+) -> Result<NftVoteRecord, ProgramError> {
+    let nft_vote_record_data = get_account_data::<NftVoteRecord>(program_id, nft_vote_record_info)?;
+
+    if nft_vote_record_data.proposal != *proposal {
+        return Err(NftVoterError::InvalidProposalForNftVoteRecord.into());
+    }
+
+    if nft_vote_record_data.governing_token_owner != *governing_token_owner {
+        return Err(NftVoterError::InvalidTokenOwnerForNftVoteRecord.into());
+    }
+
+    Ok(nft_vote_record_data)
+}
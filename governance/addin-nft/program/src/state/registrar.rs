@@ -0,0 +1,314 @@
+//! Registrar account
+
+use {
+    crate::{
+        error::NftVoterError,
+        state::enums::{CollectionKind, NftVoterAccountType},
+    },
+    borsh::{BorshDeserialize, BorshSchema, BorshSerialize},
+    solana_program::{
+        account_info::AccountInfo, program_error::ProgramError, program_pack::IsInitialized,
+        pubkey::Pubkey,
+    },
+    spl_governance::state::realm::get_realm_data_for_governing_token_mint,
+    spl_governance_tools::account::{get_account_data, AccountMaxSize},
+};
+
+/// Configuration of an NFT collection which can be used for voting
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub struct CollectionConfig {
+    /// The collection address
+    /// For Metaplex collections it's the collection NFT mint and for
+    /// token-group collections it's the TokenGroup account
+    pub collection: Pubkey,
+
+    /// The kind of the collection
+    pub kind: CollectionKind,
+
+    /// The program which owns the collection membership accounts
+    /// For Metaplex collections it's the Token Metadata program and for
+    /// token-group collections it's the owner of the TokenGroup account
+    pub membership_program_id: Pubkey,
+
+    /// The number of NFTs in the collection
+    /// It's used to compute the max voter weight of the Realm
+    pub size: u32,
+
+    /// The voter weight of each NFT from the collection
+    pub weight: u64,
+
+    /// Reserved space for future versions
+    pub reserved: [u8; 8],
+}
+
+impl CollectionConfig {
+    /// Serialized size of CollectionConfig
+    pub const SIZE: usize = 32 + 1 + 32 + 4 + 8 + 8;
+
+    /// Returns the max voter weight of the collection
+    pub fn get_max_weight(&self) -> Result<u64, ProgramError> {
+        (self.size as u64)
+            .checked_mul(self.weight)
+            .ok_or(ProgramError::ArithmeticOverflow)
+    }
+}
+
+/// Registrar account
+/// It stores the NFT collections which can be used for voting for the given
+/// Realm and governing token mint
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub struct Registrar {
+    /// Account type
+    pub account_type: NftVoterAccountType,
+
+    /// spl-governance program the Realm belongs to
+    pub governance_program_id: Pubkey,
+
+    /// Realm of the Registrar
+    pub realm: Pubkey,
+
+    /// Governing token mint the Registrar is for
+    /// It's either the community or the council mint of the Realm
+    pub governing_token_mint: Pubkey,
+
+    /// Max number of collections which can be configured for the Registrar
+    pub max_collections: u8,
+
+    /// Collections configured for the Registrar
+    pub collection_configs: Vec<CollectionConfig>,
+
+    /// Reserved space for future versions
+    pub reserved: [u8; 64],
+}
+
+impl AccountMaxSize for Registrar {
+    fn get_max_size(&self) -> Option<usize> {
+        Some(get_registrar_size(self.max_collections))
+    }
+}
+
+impl IsInitialized for Registrar {
+    fn is_initialized(&self) -> bool {
+        self.account_type == NftVoterAccountType::Registrar
+    }
+}
+
+impl Registrar {
+    /// Returns the config of the given collection if it's configured for the
+    /// Registrar
+    pub fn get_collection_config(
+        &self,
+        collection: &Pubkey,
+    ) -> Result<&CollectionConfig, ProgramError> {
+        self.collection_configs
+            .iter()
+            .find(|cc| cc.collection == *collection)
+            .ok_or_else(|| NftVoterError::CollectionNotConfigured.into())
+    }
+
+    /// Inserts a new collection config or replaces the existing one for the
+    /// same collection
+    pub fn upsert_collection_config(
+        &mut self,
+        collection_config: CollectionConfig,
+    ) -> Result<(), ProgramError> {
+        if let Some(existing) = self
+            .collection_configs
+            .iter_mut()
+            .find(|cc| cc.collection == collection_config.collection)
+        {
+            *existing = collection_config;
+        } else {
+            if self.collection_configs.len() >= self.max_collections as usize {
+                return Err(NftVoterError::MaxCollectionsReached.into());
+            }
+            self.collection_configs.push(collection_config);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the max voter weight of all the configured collections
+    pub fn get_max_voter_weight(&self) -> Result<u64, ProgramError> {
+        self.collection_configs.iter().try_fold(0u64, |total, cc| {
+            total
+                .checked_add(cc.get_max_weight()?)
+                .ok_or(ProgramError::ArithmeticOverflow)
+        })
+    }
+
+    /// Asserts the given Realm is the Registrar's Realm and its authority
+    /// signed the transaction
+    pub fn assert_realm_authority_is_signer(
+        &self,
+        realm_info: &AccountInfo,
+        realm_authority_info: &AccountInfo,
+    ) -> Result<(), ProgramError> {
+        if self.realm != *realm_info.key {
+            return Err(NftVoterError::InvalidRealmForRegistrar.into());
+        }
+
+        let realm_data = get_realm_data_for_governing_token_mint(
+            &self.governance_program_id,
+            realm_info,
+            &self.governing_token_mint,
+        )?;
+
+        assert_realm_authority_is_signer(&realm_data.authority, realm_authority_info)
+    }
+}
+
+/// Asserts the given Realm authority is the signer
+pub fn assert_realm_authority_is_signer(
+    realm_authority: &Option<Pubkey>,
+    realm_authority_info: &AccountInfo,
+) -> Result<(), ProgramError> {
+    if *realm_authority != Some(*realm_authority_info.key) {
+        return Err(NftVoterError::InvalidRealmAuthority.into());
+    }
+
+    if !realm_authority_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    Ok(())
+}
+
+/// Returns the size of Registrar account for the given max number of
+/// collections
+pub fn get_registrar_size(max_collections: u8) -> usize {
+    1 + 32 * 3 + 1 + 4 + CollectionConfig::SIZE * max_collections as usize + 64
+}
+
+/// Returns Registrar PDA seeds
+pub fn get_registrar_address_seeds<'a>(
+    realm: &'a Pubkey,
+    governing_token_mint: &'a Pubkey,
+) -> [&'a [u8]; 3] {
+    [b"registrar", realm.as_ref(), governing_token_mint.as_ref()]
+}
+
+/// Returns Registrar PDA address
+pub fn get_registrar_address(
+    program_id: &Pubkey,
+    realm: &Pubkey,
+    governing_token_mint: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &get_registrar_address_seeds(realm, governing_token_mint),
+        program_id,
+    )
+    .0
+}
+
+/// Deserializes Registrar account and checks owner program
+pub fn get_registrar_data(
+    program_id: &Pubkey,
+    registrar_info: &AccountInfo,
+) -> Result<Registrar, ProgramError> {
+    get_account_data::<Registrar>(program_id, registrar_info)
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    fn create_test_registrar(max_collections: u8) -> Registrar {
+        Registrar {
+            account_type: NftVoterAccountType::Registrar,
+            governance_program_id: Pubkey::new_unique(),
+            realm: Pubkey::new_unique(),
+            governing_token_mint: Pubkey::new_unique(),
+            max_collections,
+            collection_configs: vec![],
+            reserved: [0; 64],
+        }
+    }
+
+    fn create_test_collection_config(size: u32, weight: u64) -> CollectionConfig {
+        CollectionConfig {
+            collection: Pubkey::new_unique(),
+            kind: CollectionKind::TokenGroup,
+            membership_program_id: Pubkey::new_unique(),
+            size,
+            weight,
+            reserved: [0; 8],
+        }
+    }
+
+    #[test]
+    fn test_max_size() {
+        let mut registrar = create_test_registrar(3);
+
+        for _ in 0..3 {
+            registrar
+                .upsert_collection_config(create_test_collection_config(10, 1))
+                .unwrap();
+        }
+
+        let size = borsh::to_vec(&registrar).unwrap().len();
+
+        assert_eq!(registrar.get_max_size(), Some(size));
+    }
+
+    #[test]
+    fn test_upsert_collection_config_with_max_collections_reached_error() {
+        // Arrange
+        let mut registrar = create_test_registrar(1);
+
+        registrar
+            .upsert_collection_config(create_test_collection_config(10, 1))
+            .unwrap();
+
+        // Act
+        let err = registrar
+            .upsert_collection_config(create_test_collection_config(10, 1))
+            .err()
+            .unwrap();
+
+        // Assert
+        assert_eq!(err, NftVoterError::MaxCollectionsReached.into());
+    }
+
+    #[test]
+    fn test_upsert_collection_config_replaces_existing_config() {
+        // Arrange
+        let mut registrar = create_test_registrar(1);
+
+        let mut collection_config = create_test_collection_config(10, 1);
+        registrar
+            .upsert_collection_config(collection_config.clone())
+            .unwrap();
+
+        collection_config.weight = 5;
+
+        // Act
+        registrar
+            .upsert_collection_config(collection_config.clone())
+            .unwrap();
+
+        // Assert
+        assert_eq!(registrar.collection_configs, vec![collection_config]);
+        assert_eq!(registrar.get_max_voter_weight().unwrap(), 50);
+    }
+
+    #[test]
+    fn test_get_max_voter_weight_with_overflow_error() {
+        // Arrange
+        let mut registrar = create_test_registrar(2);
+
+        registrar
+            .upsert_collection_config(create_test_collection_config(1, u64::MAX))
+            .unwrap();
+        registrar
+            .upsert_collection_config(create_test_collection_config(1, 1))
+            .unwrap();
+
+        // Act
+        let err = registrar.get_max_voter_weight().err().unwrap();
+
+        // Assert
+        assert_eq!(err, ProgramError::ArithmeticOverflow);
+    }
+}
@@ -0,0 +1,55 @@
+//! VoterWeightRecord account
+
+use {
+    crate::{error::NftVoterError, state::registrar::Registrar},
+    solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey},
+    spl_governance_addin_api::voter_weight::VoterWeightRecord,
+    spl_governance_tools::account::get_account_data,
+};
+
+/// Returns VoterWeightRecord PDA seeds
+pub fn get_voter_weight_record_address_seeds<'a>(
+    realm: &'a Pubkey,
+    governing_token_mint: &'a Pubkey,
+    governing_token_owner: &'a Pubkey,
+) -> [&'a [u8]; 4] {
+    [
+        b"voter-weight-record",
+        realm.as_ref(),
+        governing_token_mint.as_ref(),
+        governing_token_owner.as_ref(),
+    ]
+}
+
+/// Returns VoterWeightRecord PDA address
+pub fn get_voter_weight_record_address(
+    program_id: &Pubkey,
+    realm: &Pubkey,
+    governing_token_mint: &Pubkey,
+    governing_token_owner: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &get_voter_weight_record_address_seeds(realm, governing_token_mint, governing_token_owner),
+        program_id,
+    )
+    .0
+}
+
+/// Deserializes VoterWeightRecord account and checks it belongs to the given
+/// Registrar
+pub fn get_voter_weight_record_data_for_registrar(
+    program_id: &Pubkey,
+    voter_weight_record_info: &AccountInfo,
+    registrar: &Registrar,
+) -> Result<VoterWeightRecord, ProgramError> {
+    let voter_weight_record_data =
+        get_account_data::<VoterWeightRecord>(program_id, voter_weight_record_info)?;
+
+    if voter_weight_record_data.realm != registrar.realm
+        || voter_weight_record_data.governing_token_mint != registrar.governing_token_mint
+    {
+        return Err(NftVoterError::InvalidVoterWeightRecordForRegistrar.into());
+    }
+
+    Ok(voter_weight_record_data)
+}
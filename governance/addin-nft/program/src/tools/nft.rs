@@ -0,0 +1,225 @@
+//! NFT ownership and collection membership verification
+
+use {
+    crate::{
+        error::NftVoterError,
+        state::{
+            enums::CollectionKind,
+            registrar::{CollectionConfig, Registrar},
+        },
+    },
+    solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey},
+    spl_discriminator::SplDiscriminate,
+    spl_token_2022::{
+        extension::{
+            group_member_pointer::GroupMemberPointer, BaseStateWithExtensions, StateWithExtensions,
+        },
+        state::{Account, Mint},
+    },
+    spl_token_group_interface::state::{TokenGroup, TokenGroupMember},
+    spl_type_length_value::state::{TlvState, TlvStateBorrowed},
+};
+
+/// Checks the given account is owned by either spl-token or spl-token-2022
+/// program
+fn assert_is_token_program_account(account_info: &AccountInfo) -> Result<(), ProgramError> {
+    if *account_info.owner != spl_token::id() && *account_info.owner != spl_token_2022::id() {
+        return Err(NftVoterError::InvalidNftAccount.into());
+    }
+
+    Ok(())
+}
+
+/// Asserts the given token account holds the NFT of the given mint and it's
+/// owned by the governing token owner
+/// Note: Both spl-token and spl-token-2022 NFTs are supported
+pub fn assert_is_valid_nft(
+    governing_token_owner: &Pubkey,
+    nft_token_account_info: &AccountInfo,
+    nft_mint_info: &AccountInfo,
+) -> Result<(), ProgramError> {
+    assert_is_token_program_account(nft_token_account_info)?;
+
+    if nft_mint_info.owner != nft_token_account_info.owner {
+        return Err(NftVoterError::InvalidNftAccount.into());
+    }
+
+    let token_account_data = nft_token_account_info.try_borrow_data()?;
+    let token_account = StateWithExtensions::<Account>::unpack(&token_account_data)?;
+
+    if token_account.base.owner != *governing_token_owner {
+        return Err(NftVoterError::InvalidNftOwner.into());
+    }
+
+    if token_account.base.mint != *nft_mint_info.key || token_account.base.amount != 1 {
+        return Err(NftVoterError::InvalidNftAccount.into());
+    }
+
+    let mint_data = nft_mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+
+    if mint.base.decimals != 0 || mint.base.supply != 1 {
+        return Err(NftVoterError::InvalidNftAccount.into());
+    }
+
+    Ok(())
+}
+
+/// Resolves the collection config of the given NFT using the Metaplex
+/// Metadata account of the NFT
+fn get_metaplex_collection_config<'a>(
+    registrar: &'a Registrar,
+    nft_mint: &Pubkey,
+    metadata_info: &AccountInfo,
+) -> Result<&'a CollectionConfig, ProgramError> {
+    let metadata =
+        spl_inline_mpl_token_metadata::get_metadata_data_for_mint(metadata_info, nft_mint)?;
+
+    let collection = metadata
+        .collection
+        .ok_or(NftVoterError::CollectionNotVerified)?;
+
+    if !collection.verified {
+        return Err(NftVoterError::CollectionNotVerified.into());
+    }
+
+    let collection_config = registrar.get_collection_config(&collection.key)?;
+
+    if collection_config.kind != CollectionKind::Metaplex {
+        return Err(NftVoterError::CollectionNotConfigured.into());
+    }
+
+    Ok(collection_config)
+}
+
+/// Resolves the collection config of the given NFT using the TokenGroupMember
+/// the NFT mint points to
+/// The member can be stored either in the NFT mint itself (spl-token-2022
+/// extension) or in a separate account owned by the group program
+fn get_token_group_collection_config<'a>(
+    registrar: &'a Registrar,
+    nft_mint_info: &AccountInfo,
+    member_info: &AccountInfo,
+) -> Result<&'a CollectionConfig, ProgramError> {
+    if *nft_mint_info.owner != spl_token_2022::id() {
+        return Err(NftVoterError::InvalidCollectionMembershipAccount.into());
+    }
+
+    let mint_data = nft_mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+
+    // The member account must be the one the mint points to
+    let member_pointer = mint.get_extension::<GroupMemberPointer>()?;
+    if Option::<Pubkey>::from(member_pointer.member_address) != Some(*member_info.key) {
+        return Err(NftVoterError::InvalidCollectionMembershipAccount.into());
+    }
+
+    let is_valid_member = |member: &TokenGroupMember| -> Option<&'a CollectionConfig> {
+        if member.mint != *nft_mint_info.key {
+            return None;
+        }
+
+        registrar.collection_configs.iter().find(|cc| {
+            cc.kind == CollectionKind::TokenGroup
+                && cc.collection == member.group
+                && cc.membership_program_id == *member_info.owner
+        })
+    };
+
+    let collection_config = if member_info.key == nft_mint_info.key {
+        is_valid_member(mint.get_extension::<TokenGroupMember>()?)
+    } else {
+        let member_data = member_info.try_borrow_data()?;
+        let state = TlvStateBorrowed::unpack(&member_data)?;
+
+        // A mint can be a member of multiple groups and hence the member
+        // account can store multiple TokenGroupMember entries
+        let member_count = state
+            .get_discriminators()?
+            .iter()
+            .filter(|d| **d == TokenGroupMember::SPL_DISCRIMINATOR)
+            .count();
+
+        (0..member_count).find_map(|repetition_number| {
+            state
+                .get_value_with_repetition::<TokenGroupMember>(repetition_number)
+                .ok()
+                .and_then(is_valid_member)
+        })
+    };
+
+    collection_config.ok_or_else(|| NftVoterError::CollectionNotConfigured.into())
+}
+
+/// Resolves the voter weight of the given NFT
+/// The NFT must be owned by the governing token owner and belong to one of
+/// the collections configured for the Registrar
+///
+/// The collection membership account is either the Metaplex Metadata account
+/// of the NFT or the TokenGroupMember account the NFT mint points to
+pub fn resolve_nft_vote_weight(
+    registrar: &Registrar,
+    governing_token_owner: &Pubkey,
+    nft_token_account_info: &AccountInfo,
+    nft_mint_info: &AccountInfo,
+    collection_membership_info: &AccountInfo,
+    unique_nft_mints: &mut Vec<Pubkey>,
+) -> Result<u64, ProgramError> {
+    // The same NFT can't be used twice within the same instruction
+    if unique_nft_mints.contains(nft_mint_info.key) {
+        return Err(NftVoterError::DuplicatedNftDetected.into());
+    }
+    unique_nft_mints.push(*nft_mint_info.key);
+
+    assert_is_valid_nft(governing_token_owner, nft_token_account_info, nft_mint_info)?;
+
+    let collection_config = if *collection_membership_info.owner
+        == spl_inline_mpl_token_metadata::id()
+    {
+        get_metaplex_collection_config(registrar, nft_mint_info.key, collection_membership_info)?
+    } else {
+        get_token_group_collection_config(registrar, nft_mint_info, collection_membership_info)?
+    };
+
+    Ok(collection_config.weight)
+}
+
+/// Asserts the given account is a valid collection of the given kind and
+/// returns the program which owns the collection membership accounts
+pub fn get_collection_membership_program_id(
+    collection_info: &AccountInfo,
+    kind: &CollectionKind,
+) -> Result<Pubkey, ProgramError> {
+    match kind {
+        CollectionKind::Metaplex => {
+            // Metaplex collection is identified by the collection NFT mint
+            assert_is_token_program_account(collection_info)
+                .map_err(|_| NftVoterError::InvalidCollectionAccount)?;
+
+            let collection_data = collection_info.try_borrow_data()?;
+            StateWithExtensions::<Mint>::unpack(&collection_data)
+                .map_err(|_| NftVoterError::InvalidCollectionAccount)?;
+
+            Ok(spl_inline_mpl_token_metadata::id())
+        }
+        CollectionKind::TokenGroup => {
+            let collection_data = collection_info.try_borrow_data()?;
+
+            let is_token_group = if *collection_info.owner == spl_token_2022::id() {
+                StateWithExtensions::<Mint>::unpack(&collection_data)
+                    .and_then(|mint| mint.get_extension::<TokenGroup>().map(|_| ()))
+                    .is_ok()
+            } else {
+                TlvStateBorrowed::unpack(&collection_data)
+                    .and_then(|state| state.get_first_value::<TokenGroup>().map(|_| ()))
+                    .is_ok()
+            };
+
+            if !is_token_group {
+                return Err(NftVoterError::InvalidCollectionAccount.into());
+            }
+
+            Ok(*collection_info.owner)
+        }
+    }
+}
@@ -0,0 +1,164 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+
+use {
+    program_test::NftVoterProgramTest,
+    solana_program_test::*,
+    solana_sdk::signer::Signer,
+    spl_governance::state::{enums::ProposalState, proposal::ProposalV2},
+    spl_governance_addin_api::voter_weight::VoterWeightAction,
+    spl_governance_addin_nft::error::NftVoterError,
+};
+
+#[tokio::test]
+async fn test_cast_nft_vote() {
+    // Arrange
+    let mut nft_voter_test = NftVoterProgramTest::start_new().await;
+
+    let realm_cookie = nft_voter_test.with_realm().await;
+    let mut registrar_cookie = nft_voter_test.with_registrar(&realm_cookie).await.unwrap();
+
+    let metaplex_collection_cookie = nft_voter_test.with_metaplex_collection().await;
+    nft_voter_test
+        .with_collection_config(
+            &realm_cookie,
+            &mut registrar_cookie,
+            &metaplex_collection_cookie,
+            3,
+            10,
+        )
+        .await
+        .unwrap();
+
+    let group_collection_cookie = nft_voter_test.with_token_group_collection().await;
+    nft_voter_test
+        .with_collection_config(
+            &realm_cookie,
+            &mut registrar_cookie,
+            &group_collection_cookie,
+            2,
+            5,
+        )
+        .await
+        .unwrap();
+
+    let voter_cookie = nft_voter_test.with_voter(&realm_cookie).await;
+
+    let nft_cookie1 = nft_voter_test
+        .with_metaplex_nft(&metaplex_collection_cookie, &voter_cookie, true)
+        .await;
+    let nft_cookie2 = nft_voter_test
+        .with_token_group_nft(&group_collection_cookie, &voter_cookie)
+        .await;
+
+    let proposal_cookie = nft_voter_test
+        .with_proposal(&realm_cookie, &voter_cookie, &[&nft_cookie1])
+        .await;
+
+    // Act
+    nft_voter_test
+        .with_cast_vote(
+            &realm_cookie,
+            &proposal_cookie,
+            &voter_cookie,
+            &[&nft_cookie1, &nft_cookie2],
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    let voter_weight_record = nft_voter_test
+        .get_voter_weight_record(&voter_cookie.voter_weight_record)
+        .await;
+
+    assert_eq!(15, voter_weight_record.voter_weight);
+    assert_eq!(
+        Some(VoterWeightAction::CastVote),
+        voter_weight_record.weight_action
+    );
+    assert_eq!(
+        Some(proposal_cookie.address),
+        voter_weight_record.weight_action_target
+    );
+
+    let nft_vote_record = nft_voter_test
+        .get_nft_vote_record(&proposal_cookie.address, &nft_cookie1.accounts.nft_mint)
+        .await
+        .unwrap();
+
+    assert_eq!(proposal_cookie.address, nft_vote_record.proposal);
+    assert_eq!(
+        voter_cookie.token_owner.pubkey(),
+        nft_vote_record.governing_token_owner
+    );
+
+    let proposal = nft_voter_test
+        .bench
+        .get_borsh_account::<ProposalV2>(&proposal_cookie.address)
+        .await;
+
+    // Max voter weight is 3*10 + 2*5 = 40 and the vote weight 15 doesn't tip the
+    // Proposal
+    assert_eq!(ProposalState::Voting, proposal.state);
+    assert_eq!(15, proposal.options[0].vote_weight);
+}
+
+#[tokio::test]
+async fn test_cast_nft_vote_with_nft_already_voted_error() {
+    // Arrange
+    let mut nft_voter_test = NftVoterProgramTest::start_new().await;
+
+    let realm_cookie = nft_voter_test.with_realm().await;
+    let mut registrar_cookie = nft_voter_test.with_registrar(&realm_cookie).await.unwrap();
+
+    let collection_cookie = nft_voter_test.with_metaplex_collection().await;
+    nft_voter_test
+        .with_collection_config(
+            &realm_cookie,
+            &mut registrar_cookie,
+            &collection_cookie,
+            10,
+            1,
+        )
+        .await
+        .unwrap();
+
+    let voter_cookie = nft_voter_test.with_voter(&realm_cookie).await;
+    let nft_cookie = nft_voter_test
+        .with_metaplex_nft(&collection_cookie, &voter_cookie, true)
+        .await;
+
+    let proposal_cookie = nft_voter_test
+        .with_proposal(&realm_cookie, &voter_cookie, &[&nft_cookie])
+        .await;
+
+    nft_voter_test
+        .with_cast_nft_vote(
+            &realm_cookie,
+            &proposal_cookie,
+            &voter_cookie,
+            &[&nft_cookie],
+        )
+        .await
+        .unwrap();
+
+    let nft_cookie2 = nft_voter_test
+        .with_metaplex_nft(&collection_cookie, &voter_cookie, true)
+        .await;
+
+    // Act
+    let err = nft_voter_test
+        .with_cast_nft_vote(
+            &realm_cookie,
+            &proposal_cookie,
+            &voter_cookie,
+            &[&nft_cookie2, &nft_cookie],
+        )
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(err, NftVoterError::NftAlreadyVoted.into());
+}
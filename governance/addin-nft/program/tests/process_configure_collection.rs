@@ -0,0 +1,168 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+
+use {
+    program_test::NftVoterProgramTest,
+    solana_program_test::*,
+    spl_governance_addin_nft::{error::NftVoterError, state::enums::CollectionKind},
+};
+
+#[tokio::test]
+async fn test_configure_collection() {
+    // Arrange
+    let mut nft_voter_test = NftVoterProgramTest::start_new().await;
+
+    let realm_cookie = nft_voter_test.with_realm().await;
+    let mut registrar_cookie = nft_voter_test.with_registrar(&realm_cookie).await.unwrap();
+
+    let collection_cookie = nft_voter_test.with_metaplex_collection().await;
+
+    // Act
+    nft_voter_test
+        .with_collection_config(
+            &realm_cookie,
+            &mut registrar_cookie,
+            &collection_cookie,
+            10,
+            3,
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    let registrar = nft_voter_test
+        .get_registrar_account(&registrar_cookie.address)
+        .await;
+
+    assert_eq!(1, registrar.collection_configs.len());
+
+    let collection_config = &registrar.collection_configs[0];
+    assert_eq!(collection_cookie.address, collection_config.collection);
+    assert_eq!(CollectionKind::Metaplex, collection_config.kind);
+    assert_eq!(10, collection_config.size);
+    assert_eq!(3, collection_config.weight);
+
+    let max_voter_weight_record = nft_voter_test
+        .get_max_voter_weight_record(&registrar_cookie.max_voter_weight_record)
+        .await;
+
+    assert_eq!(30, max_voter_weight_record.max_voter_weight);
+}
+
+#[tokio::test]
+async fn test_configure_token_group_collection() {
+    // Arrange
+    let mut nft_voter_test = NftVoterProgramTest::start_new().await;
+
+    let realm_cookie = nft_voter_test.with_realm().await;
+    let mut registrar_cookie = nft_voter_test.with_registrar(&realm_cookie).await.unwrap();
+
+    let collection_cookie = nft_voter_test.with_token_group_collection().await;
+
+    // Act
+    nft_voter_test
+        .with_collection_config(
+            &realm_cookie,
+            &mut registrar_cookie,
+            &collection_cookie,
+            5,
+            2,
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    let collection_config = &registrar_cookie.account.collection_configs[0];
+
+    assert_eq!(CollectionKind::TokenGroup, collection_config.kind);
+    assert_eq!(
+        collection_cookie.group_program_id.unwrap(),
+        collection_config.membership_program_id
+    );
+}
+
+#[tokio::test]
+async fn test_configure_collection_updates_existing_config() {
+    // Arrange
+    let mut nft_voter_test = NftVoterProgramTest::start_new().await;
+
+    let realm_cookie = nft_voter_test.with_realm().await;
+    let mut registrar_cookie = nft_voter_test.with_registrar(&realm_cookie).await.unwrap();
+
+    let collection_cookie = nft_voter_test.with_metaplex_collection().await;
+
+    nft_voter_test
+        .with_collection_config(
+            &realm_cookie,
+            &mut registrar_cookie,
+            &collection_cookie,
+            10,
+            3,
+        )
+        .await
+        .unwrap();
+
+    // Act
+    nft_voter_test
+        .with_collection_config(
+            &realm_cookie,
+            &mut registrar_cookie,
+            &collection_cookie,
+            20,
+            1,
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(1, registrar_cookie.account.collection_configs.len());
+
+    let max_voter_weight_record = nft_voter_test
+        .get_max_voter_weight_record(&registrar_cookie.max_voter_weight_record)
+        .await;
+
+    assert_eq!(20, max_voter_weight_record.max_voter_weight);
+}
+
+#[tokio::test]
+async fn test_configure_collection_with_max_collections_reached_error() {
+    // Arrange
+    let mut nft_voter_test = NftVoterProgramTest::start_new().await;
+
+    let realm_cookie = nft_voter_test.with_realm().await;
+    let mut registrar_cookie = nft_voter_test.with_registrar(&realm_cookie).await.unwrap();
+
+    for _ in 0..registrar_cookie.account.max_collections {
+        let collection_cookie = nft_voter_test.with_metaplex_collection().await;
+
+        nft_voter_test
+            .with_collection_config(
+                &realm_cookie,
+                &mut registrar_cookie,
+                &collection_cookie,
+                10,
+                1,
+            )
+            .await
+            .unwrap();
+    }
+
+    let collection_cookie = nft_voter_test.with_metaplex_collection().await;
+
+    // Act
+    let err = nft_voter_test
+        .with_collection_config(
+            &realm_cookie,
+            &mut registrar_cookie,
+            &collection_cookie,
+            10,
+            1,
+        )
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(err, NftVoterError::MaxCollectionsReached.into());
+}
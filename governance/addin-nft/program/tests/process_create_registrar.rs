@@ -0,0 +1,55 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+
+use {
+    program_test::NftVoterProgramTest,
+    solana_program_test::*,
+    solana_sdk::signature::Keypair,
+    spl_governance_addin_nft::{error::NftVoterError, state::enums::NftVoterAccountType},
+};
+
+#[tokio::test]
+async fn test_create_registrar() {
+    // Arrange
+    let mut nft_voter_test = NftVoterProgramTest::start_new().await;
+
+    let realm_cookie = nft_voter_test.with_realm().await;
+
+    // Act
+    let registrar_cookie = nft_voter_test.with_registrar(&realm_cookie).await.unwrap();
+
+    // Assert
+    let registrar = nft_voter_test
+        .get_registrar_account(&registrar_cookie.address)
+        .await;
+
+    assert_eq!(registrar_cookie.account, registrar);
+    assert_eq!(NftVoterAccountType::Registrar, registrar.account_type);
+
+    let max_voter_weight_record = nft_voter_test
+        .get_max_voter_weight_record(&registrar_cookie.max_voter_weight_record)
+        .await;
+
+    assert_eq!(0, max_voter_weight_record.max_voter_weight);
+    assert_eq!(None, max_voter_weight_record.max_voter_weight_expiry);
+}
+
+#[tokio::test]
+async fn test_create_registrar_with_invalid_realm_authority_error() {
+    // Arrange
+    let mut nft_voter_test = NftVoterProgramTest::start_new().await;
+
+    let mut realm_cookie = nft_voter_test.with_realm().await;
+    realm_cookie.realm_authority = Keypair::new();
+
+    // Act
+    let err = nft_voter_test
+        .with_registrar(&realm_cookie)
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(err, NftVoterError::InvalidRealmAuthority.into());
+}
@@ -0,0 +1,132 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+
+use {
+    program_test::NftVoterProgramTest, solana_program_test::*,
+    spl_governance_addin_nft::error::NftVoterError,
+};
+
+#[tokio::test]
+async fn test_relinquish_nft_vote() {
+    // Arrange
+    let mut nft_voter_test = NftVoterProgramTest::start_new().await;
+
+    let realm_cookie = nft_voter_test.with_realm().await;
+    let mut registrar_cookie = nft_voter_test.with_registrar(&realm_cookie).await.unwrap();
+
+    let collection_cookie = nft_voter_test.with_metaplex_collection().await;
+    nft_voter_test
+        .with_collection_config(
+            &realm_cookie,
+            &mut registrar_cookie,
+            &collection_cookie,
+            10,
+            1,
+        )
+        .await
+        .unwrap();
+
+    let voter_cookie = nft_voter_test.with_voter(&realm_cookie).await;
+    let nft_cookie = nft_voter_test
+        .with_metaplex_nft(&collection_cookie, &voter_cookie, true)
+        .await;
+
+    let proposal_cookie = nft_voter_test
+        .with_proposal(&realm_cookie, &voter_cookie, &[&nft_cookie])
+        .await;
+
+    nft_voter_test
+        .with_cast_vote(
+            &realm_cookie,
+            &proposal_cookie,
+            &voter_cookie,
+            &[&nft_cookie],
+        )
+        .await
+        .unwrap();
+
+    nft_voter_test
+        .relinquish_vote(&realm_cookie, &proposal_cookie, &voter_cookie)
+        .await
+        .unwrap();
+
+    // Act
+    nft_voter_test
+        .relinquish_nft_vote(
+            &realm_cookie,
+            &proposal_cookie,
+            &voter_cookie,
+            &[&nft_cookie],
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    let nft_vote_record = nft_voter_test
+        .get_nft_vote_record(&proposal_cookie.address, &nft_cookie.accounts.nft_mint)
+        .await;
+
+    assert_eq!(None, nft_vote_record);
+
+    let voter_weight_record = nft_voter_test
+        .get_voter_weight_record(&voter_cookie.voter_weight_record)
+        .await;
+
+    assert_eq!(0, voter_weight_record.voter_weight);
+}
+
+#[tokio::test]
+async fn test_relinquish_nft_vote_with_vote_record_must_be_relinquished_error() {
+    // Arrange
+    let mut nft_voter_test = NftVoterProgramTest::start_new().await;
+
+    let realm_cookie = nft_voter_test.with_realm().await;
+    let mut registrar_cookie = nft_voter_test.with_registrar(&realm_cookie).await.unwrap();
+
+    let collection_cookie = nft_voter_test.with_metaplex_collection().await;
+    nft_voter_test
+        .with_collection_config(
+            &realm_cookie,
+            &mut registrar_cookie,
+            &collection_cookie,
+            10,
+            1,
+        )
+        .await
+        .unwrap();
+
+    let voter_cookie = nft_voter_test.with_voter(&realm_cookie).await;
+    let nft_cookie = nft_voter_test
+        .with_metaplex_nft(&collection_cookie, &voter_cookie, true)
+        .await;
+
+    let proposal_cookie = nft_voter_test
+        .with_proposal(&realm_cookie, &voter_cookie, &[&nft_cookie])
+        .await;
+
+    nft_voter_test
+        .with_cast_vote(
+            &realm_cookie,
+            &proposal_cookie,
+            &voter_cookie,
+            &[&nft_cookie],
+        )
+        .await
+        .unwrap();
+
+    // Act
+    let err = nft_voter_test
+        .relinquish_nft_vote(
+            &realm_cookie,
+            &proposal_cookie,
+            &voter_cookie,
+            &[&nft_cookie],
+        )
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(err, NftVoterError::VoteRecordMustBeRelinquished.into());
+}
@@ -0,0 +1,305 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+
+use {
+    program_test::{
+        cookies::{NftCookie, RealmCookie, VoterCookie},
+        NftVoterProgramTest,
+    },
+    solana_program::program_error::ProgramError,
+    solana_program_test::*,
+    solana_sdk::signer::Signer,
+    spl_governance_addin_api::voter_weight::VoterWeightAction,
+    spl_governance_addin_nft::{error::NftVoterError, instruction::update_voter_weight_record},
+};
+
+async fn update_voter_weight(
+    nft_voter_test: &mut NftVoterProgramTest,
+    realm_cookie: &RealmCookie,
+    voter_cookie: &VoterCookie,
+    nft_cookies: &[&NftCookie],
+    action: VoterWeightAction,
+) -> Result<(), ProgramError> {
+    let update_voter_weight_record_ix = update_voter_weight_record(
+        &nft_voter_test.program_id,
+        &realm_cookie.address,
+        &realm_cookie.community_mint,
+        &voter_cookie.token_owner.pubkey(),
+        &nft_cookies
+            .iter()
+            .map(|nft_cookie| nft_cookie.accounts.clone())
+            .collect::<Vec<_>>(),
+        action,
+    );
+
+    nft_voter_test
+        .bench
+        .process_transaction(&[update_voter_weight_record_ix], None)
+        .await
+}
+
+#[tokio::test]
+async fn test_update_voter_weight_record() {
+    // Arrange
+    let mut nft_voter_test = NftVoterProgramTest::start_new().await;
+
+    let realm_cookie = nft_voter_test.with_realm().await;
+    let mut registrar_cookie = nft_voter_test.with_registrar(&realm_cookie).await.unwrap();
+
+    let metaplex_collection_cookie = nft_voter_test.with_metaplex_collection().await;
+    nft_voter_test
+        .with_collection_config(
+            &realm_cookie,
+            &mut registrar_cookie,
+            &metaplex_collection_cookie,
+            10,
+            3,
+        )
+        .await
+        .unwrap();
+
+    let group_collection_cookie = nft_voter_test.with_token_group_collection().await;
+    nft_voter_test
+        .with_collection_config(
+            &realm_cookie,
+            &mut registrar_cookie,
+            &group_collection_cookie,
+            10,
+            5,
+        )
+        .await
+        .unwrap();
+
+    let voter_cookie = nft_voter_test.with_voter(&realm_cookie).await;
+
+    let nft_cookie1 = nft_voter_test
+        .with_metaplex_nft(&metaplex_collection_cookie, &voter_cookie, true)
+        .await;
+    let nft_cookie2 = nft_voter_test
+        .with_token_group_nft(&group_collection_cookie, &voter_cookie)
+        .await;
+
+    // Act
+    update_voter_weight(
+        &mut nft_voter_test,
+        &realm_cookie,
+        &voter_cookie,
+        &[&nft_cookie1, &nft_cookie2],
+        VoterWeightAction::CreateProposal,
+    )
+    .await
+    .unwrap();
+
+    // Assert
+    let voter_weight_record = nft_voter_test
+        .get_voter_weight_record(&voter_cookie.voter_weight_record)
+        .await;
+
+    let clock = nft_voter_test.bench.get_clock().await;
+
+    assert_eq!(8, voter_weight_record.voter_weight);
+    assert_eq!(Some(clock.slot), voter_weight_record.voter_weight_expiry);
+    assert_eq!(
+        Some(VoterWeightAction::CreateProposal),
+        voter_weight_record.weight_action
+    );
+    assert_eq!(None, voter_weight_record.weight_action_target);
+}
+
+#[tokio::test]
+async fn test_update_voter_weight_record_with_cast_vote_not_allowed_error() {
+    // Arrange
+    let mut nft_voter_test = NftVoterProgramTest::start_new().await;
+
+    let realm_cookie = nft_voter_test.with_realm().await;
+    let mut registrar_cookie = nft_voter_test.with_registrar(&realm_cookie).await.unwrap();
+
+    let collection_cookie = nft_voter_test.with_metaplex_collection().await;
+    nft_voter_test
+        .with_collection_config(
+            &realm_cookie,
+            &mut registrar_cookie,
+            &collection_cookie,
+            10,
+            1,
+        )
+        .await
+        .unwrap();
+
+    let voter_cookie = nft_voter_test.with_voter(&realm_cookie).await;
+    let nft_cookie = nft_voter_test
+        .with_metaplex_nft(&collection_cookie, &voter_cookie, true)
+        .await;
+
+    // Act
+    let err = update_voter_weight(
+        &mut nft_voter_test,
+        &realm_cookie,
+        &voter_cookie,
+        &[&nft_cookie],
+        VoterWeightAction::CastVote,
+    )
+    .await
+    .err()
+    .unwrap();
+
+    // Assert
+    assert_eq!(err, NftVoterError::CastVoteIsNotAllowed.into());
+}
+
+#[tokio::test]
+async fn test_update_voter_weight_record_with_unverified_collection_error() {
+    // Arrange
+    let mut nft_voter_test = NftVoterProgramTest::start_new().await;
+
+    let realm_cookie = nft_voter_test.with_realm().await;
+    let mut registrar_cookie = nft_voter_test.with_registrar(&realm_cookie).await.unwrap();
+
+    let collection_cookie = nft_voter_test.with_metaplex_collection().await;
+    nft_voter_test
+        .with_collection_config(
+            &realm_cookie,
+            &mut registrar_cookie,
+            &collection_cookie,
+            10,
+            1,
+        )
+        .await
+        .unwrap();
+
+    let voter_cookie = nft_voter_test.with_voter(&realm_cookie).await;
+    let nft_cookie = nft_voter_test
+        .with_metaplex_nft(&collection_cookie, &voter_cookie, false)
+        .await;
+
+    // Act
+    let err = update_voter_weight(
+        &mut nft_voter_test,
+        &realm_cookie,
+        &voter_cookie,
+        &[&nft_cookie],
+        VoterWeightAction::CreateProposal,
+    )
+    .await
+    .err()
+    .unwrap();
+
+    // Assert
+    assert_eq!(err, NftVoterError::CollectionNotVerified.into());
+}
+
+#[tokio::test]
+async fn test_update_voter_weight_record_with_collection_not_configured_error() {
+    // Arrange
+    let mut nft_voter_test = NftVoterProgramTest::start_new().await;
+
+    let realm_cookie = nft_voter_test.with_realm().await;
+    nft_voter_test.with_registrar(&realm_cookie).await.unwrap();
+
+    let collection_cookie = nft_voter_test.with_metaplex_collection().await;
+
+    let voter_cookie = nft_voter_test.with_voter(&realm_cookie).await;
+    let nft_cookie = nft_voter_test
+        .with_metaplex_nft(&collection_cookie, &voter_cookie, true)
+        .await;
+
+    // Act
+    let err = update_voter_weight(
+        &mut nft_voter_test,
+        &realm_cookie,
+        &voter_cookie,
+        &[&nft_cookie],
+        VoterWeightAction::CreateProposal,
+    )
+    .await
+    .err()
+    .unwrap();
+
+    // Assert
+    assert_eq!(err, NftVoterError::CollectionNotConfigured.into());
+}
+
+#[tokio::test]
+async fn test_update_voter_weight_record_with_duplicated_nft_error() {
+    // Arrange
+    let mut nft_voter_test = NftVoterProgramTest::start_new().await;
+
+    let realm_cookie = nft_voter_test.with_realm().await;
+    let mut registrar_cookie = nft_voter_test.with_registrar(&realm_cookie).await.unwrap();
+
+    let collection_cookie = nft_voter_test.with_metaplex_collection().await;
+    nft_voter_test
+        .with_collection_config(
+            &realm_cookie,
+            &mut registrar_cookie,
+            &collection_cookie,
+            10,
+            1,
+        )
+        .await
+        .unwrap();
+
+    let voter_cookie = nft_voter_test.with_voter(&realm_cookie).await;
+    let nft_cookie = nft_voter_test
+        .with_metaplex_nft(&collection_cookie, &voter_cookie, true)
+        .await;
+
+    // Act
+    let err = update_voter_weight(
+        &mut nft_voter_test,
+        &realm_cookie,
+        &voter_cookie,
+        &[&nft_cookie, &nft_cookie],
+        VoterWeightAction::CreateProposal,
+    )
+    .await
+    .err()
+    .unwrap();
+
+    // Assert
+    assert_eq!(err, NftVoterError::DuplicatedNftDetected.into());
+}
+
+#[tokio::test]
+async fn test_update_voter_weight_record_with_invalid_nft_owner_error() {
+    // Arrange
+    let mut nft_voter_test = NftVoterProgramTest::start_new().await;
+
+    let realm_cookie = nft_voter_test.with_realm().await;
+    let mut registrar_cookie = nft_voter_test.with_registrar(&realm_cookie).await.unwrap();
+
+    let collection_cookie = nft_voter_test.with_metaplex_collection().await;
+    nft_voter_test
+        .with_collection_config(
+            &realm_cookie,
+            &mut registrar_cookie,
+            &collection_cookie,
+            10,
+            1,
+        )
+        .await
+        .unwrap();
+
+    let voter_cookie = nft_voter_test.with_voter(&realm_cookie).await;
+    let voter_cookie2 = nft_voter_test.with_voter(&realm_cookie).await;
+
+    let nft_cookie = nft_voter_test
+        .with_metaplex_nft(&collection_cookie, &voter_cookie2, true)
+        .await;
+
+    // Act
+    let err = update_voter_weight(
+        &mut nft_voter_test,
+        &realm_cookie,
+        &voter_cookie,
+        &[&nft_cookie],
+        VoterWeightAction::CreateProposal,
+    )
+    .await
+    .err()
+    .unwrap();
+
+    // Assert
+    assert_eq!(err, NftVoterError::InvalidNftOwner.into());
+}
@@ -0,0 +1,48 @@
+use {
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signature::Keypair,
+    spl_governance_addin_nft::{instruction::NftVoteAccounts, state::registrar::Registrar},
+};
+
+#[derive(Debug)]
+pub struct RealmCookie {
+    pub address: Pubkey,
+    pub realm_authority: Keypair,
+    pub community_mint: Pubkey,
+}
+
+#[derive(Debug)]
+pub struct RegistrarCookie {
+    pub address: Pubkey,
+    pub account: Registrar,
+    #[allow(dead_code)]
+    pub max_voter_weight_record: Pubkey,
+}
+
+#[derive(Debug)]
+pub struct CollectionCookie {
+    pub address: Pubkey,
+    pub authority: Keypair,
+    /// Owner of the TokenGroup and TokenGroupMember accounts for token-group
+    /// collections
+    pub group_program_id: Option<Pubkey>,
+}
+
+#[derive(Debug)]
+pub struct NftCookie {
+    pub accounts: NftVoteAccounts,
+}
+
+#[derive(Debug)]
+pub struct VoterCookie {
+    pub token_owner: Keypair,
+    pub token_owner_record: Pubkey,
+    pub voter_weight_record: Pubkey,
+}
+
+#[derive(Debug)]
+pub struct ProposalCookie {
+    pub address: Pubkey,
+    pub governance: Pubkey,
+    pub proposal_owner_record: Pubkey,
+}
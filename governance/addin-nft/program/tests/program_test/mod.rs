@@ -0,0 +1,779 @@
+use {
+    crate::program_test::cookies::{
+        CollectionCookie, NftCookie, ProposalCookie, RealmCookie, RegistrarCookie, VoterCookie,
+    },
+    solana_program::{program_error::ProgramError, pubkey::Pubkey, system_instruction},
+    solana_program_test::{processor, ProgramTest},
+    solana_sdk::{
+        account::{AccountSharedData, WritableAccount},
+        signature::Keypair,
+        signer::Signer,
+    },
+    spl_governance::{
+        instruction::{
+            cast_vote, create_governance, create_proposal, create_realm, create_token_owner_record,
+            relinquish_vote, sign_off_proposal,
+        },
+        state::{
+            enums::{MintMaxVoterWeightSource, VoteThreshold, VoteTipping},
+            governance::{
+                get_governance_address, GovernanceConfig, DEFAULT_DEPOSIT_EXEMPT_PROPOSAL_COUNT,
+            },
+            proposal::{get_proposal_address, VoteType},
+            realm::{get_realm_address, GoverningTokenConfigAccountArgs},
+            realm_config::GoverningTokenType,
+            token_owner_record::get_token_owner_record_address,
+            vote_record::{Vote, VoteChoice},
+        },
+    },
+    spl_governance_addin_api::{
+        max_voter_weight::MaxVoterWeightRecord,
+        voter_weight::{VoterWeightAction, VoterWeightRecord},
+    },
+    spl_governance_addin_nft::{
+        instruction::{
+            cast_nft_vote, configure_collection, create_max_voter_weight_record, create_registrar,
+            create_voter_weight_record, relinquish_nft_vote, update_voter_weight_record,
+            NftVoteAccounts,
+        },
+        processor::process_instruction,
+        state::{
+            enums::{CollectionKind, NftVoterAccountType},
+            max_voter_weight_record::get_max_voter_weight_record_address,
+            nft_vote_record::{get_nft_vote_record_address, NftVoteRecord},
+            registrar::{get_registrar_address, Registrar},
+            voter_weight_record::get_voter_weight_record_address,
+        },
+    },
+    spl_governance_test_sdk::ProgramTestBench,
+    spl_token_2022::{
+        extension::{group_member_pointer, ExtensionType},
+        state::{Account, Mint},
+    },
+    spl_token_group_interface::state::{TokenGroup, TokenGroupMember},
+    spl_type_length_value::state::{TlvState, TlvStateMut, TlvStateOwned},
+    std::str::FromStr,
+};
+
+pub mod cookies;
+
+pub struct NftVoterProgramTest {
+    pub bench: ProgramTestBench,
+    pub program_id: Pubkey,
+    pub governance_program_id: Pubkey,
+}
+
+impl NftVoterProgramTest {
+    #[allow(dead_code)]
+    pub async fn start_new() -> Self {
+        let mut program_test = ProgramTest::default();
+
+        let program_id = Pubkey::from_str("GovernanceNftVoter1111111111111111111111111").unwrap();
+        program_test.add_program(
+            "spl_governance_addin_nft",
+            program_id,
+            processor!(process_instruction),
+        );
+
+        let governance_program_id =
+            Pubkey::from_str("Governance111111111111111111111111111111111").unwrap();
+        program_test.add_program(
+            "spl_governance",
+            governance_program_id,
+            processor!(spl_governance::processor::process_instruction),
+        );
+
+        let bench = ProgramTestBench::start_new(program_test).await;
+
+        Self {
+            bench,
+            program_id,
+            governance_program_id,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub async fn with_realm(&mut self) -> RealmCookie {
+        let name = self.bench.get_unique_name("realm");
+        let realm_address = get_realm_address(&self.governance_program_id, &name);
+
+        let community_mint_keypair = Keypair::new();
+        let community_mint_authority = Keypair::new();
+
+        self.bench
+            .create_mint(
+                &community_mint_keypair,
+                &community_mint_authority.pubkey(),
+                None,
+            )
+            .await;
+
+        let realm_authority = Keypair::new();
+
+        let community_token_config_args = GoverningTokenConfigAccountArgs {
+            voter_weight_addin: Some(self.program_id),
+            max_voter_weight_addin: Some(self.program_id),
+            token_type: GoverningTokenType::default(),
+        };
+
+        let create_realm_ix = create_realm(
+            &self.governance_program_id,
+            &realm_authority.pubkey(),
+            &community_mint_keypair.pubkey(),
+            &self.bench.payer.pubkey(),
+            None,
+            Some(community_token_config_args),
+            None,
+            name,
+            1,
+            MintMaxVoterWeightSource::FULL_SUPPLY_FRACTION,
+        );
+
+        self.bench
+            .process_transaction(&[create_realm_ix], None)
+            .await
+            .unwrap();
+
+        RealmCookie {
+            address: realm_address,
+            realm_authority,
+            community_mint: community_mint_keypair.pubkey(),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub async fn with_registrar(
+        &mut self,
+        realm_cookie: &RealmCookie,
+    ) -> Result<RegistrarCookie, ProgramError> {
+        let max_collections = 3;
+
+        let create_registrar_ix = create_registrar(
+            &self.program_id,
+            &self.governance_program_id,
+            &realm_cookie.address,
+            &realm_cookie.community_mint,
+            &realm_cookie.realm_authority.pubkey(),
+            &self.bench.payer.pubkey(),
+            max_collections,
+        );
+
+        let create_max_voter_weight_record_ix = create_max_voter_weight_record(
+            &self.program_id,
+            &realm_cookie.address,
+            &realm_cookie.community_mint,
+            &self.bench.payer.pubkey(),
+        );
+
+        self.bench
+            .process_transaction(
+                &[create_registrar_ix, create_max_voter_weight_record_ix],
+                Some(&[&realm_cookie.realm_authority]),
+            )
+            .await?;
+
+        let account = Registrar {
+            account_type: NftVoterAccountType::Registrar,
+            governance_program_id: self.governance_program_id,
+            realm: realm_cookie.address,
+            governing_token_mint: realm_cookie.community_mint,
+            max_collections,
+            collection_configs: vec![],
+            reserved: [0; 64],
+        };
+
+        Ok(RegistrarCookie {
+            address: get_registrar_address(
+                &self.program_id,
+                &realm_cookie.address,
+                &realm_cookie.community_mint,
+            ),
+            account,
+            max_voter_weight_record: get_max_voter_weight_record_address(
+                &self.program_id,
+                &realm_cookie.address,
+                &realm_cookie.community_mint,
+            ),
+        })
+    }
+
+    #[allow(dead_code)]
+    pub async fn with_metaplex_collection(&mut self) -> CollectionCookie {
+        let collection_mint = Keypair::new();
+        let authority = Keypair::new();
+
+        self.bench
+            .create_mint(&collection_mint, &authority.pubkey(), None)
+            .await;
+
+        CollectionCookie {
+            address: collection_mint.pubkey(),
+            authority,
+            group_program_id: None,
+        }
+    }
+
+    /// Creates TokenGroup account owned by a mock group program
+    #[allow(dead_code)]
+    pub async fn with_token_group_collection(&mut self) -> CollectionCookie {
+        let group_address = Pubkey::new_unique();
+        let group_program_id = Pubkey::new_unique();
+        let authority = Keypair::new();
+
+        let group = TokenGroup::new(
+            &Pubkey::new_unique(),
+            Some(authority.pubkey()).try_into().unwrap(),
+            100,
+        );
+
+        let mut data = vec![0; TlvStateOwned::get_base_len() + std::mem::size_of::<TokenGroup>()];
+        let mut state = TlvStateMut::unpack(&mut data).unwrap();
+        *state.init_value::<TokenGroup>(false).unwrap().0 = group;
+
+        self.set_account(&group_address, data, &group_program_id);
+
+        CollectionCookie {
+            address: group_address,
+            authority,
+            group_program_id: Some(group_program_id),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub async fn with_collection_config(
+        &mut self,
+        realm_cookie: &RealmCookie,
+        registrar_cookie: &mut RegistrarCookie,
+        collection_cookie: &CollectionCookie,
+        size: u32,
+        weight: u64,
+    ) -> Result<(), ProgramError> {
+        let kind = if collection_cookie.group_program_id.is_some() {
+            CollectionKind::TokenGroup
+        } else {
+            CollectionKind::Metaplex
+        };
+
+        let configure_collection_ix = configure_collection(
+            &self.program_id,
+            &realm_cookie.address,
+            &realm_cookie.community_mint,
+            &realm_cookie.realm_authority.pubkey(),
+            &collection_cookie.address,
+            kind,
+            size,
+            weight,
+        );
+
+        self.bench
+            .process_transaction(
+                &[configure_collection_ix],
+                Some(&[&realm_cookie.realm_authority]),
+            )
+            .await?;
+
+        registrar_cookie.account = self.get_registrar_account(&registrar_cookie.address).await;
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub async fn with_voter(&mut self, realm_cookie: &RealmCookie) -> VoterCookie {
+        let token_owner = Keypair::new();
+
+        let create_token_owner_record_ix = create_token_owner_record(
+            &self.governance_program_id,
+            &realm_cookie.address,
+            &token_owner.pubkey(),
+            &realm_cookie.community_mint,
+            &self.bench.payer.pubkey(),
+        );
+
+        let create_voter_weight_record_ix = create_voter_weight_record(
+            &self.program_id,
+            &realm_cookie.address,
+            &realm_cookie.community_mint,
+            &self.bench.payer.pubkey(),
+            &token_owner.pubkey(),
+        );
+
+        self.bench
+            .process_transaction(
+                &[create_token_owner_record_ix, create_voter_weight_record_ix],
+                None,
+            )
+            .await
+            .unwrap();
+
+        VoterCookie {
+            token_owner_record: get_token_owner_record_address(
+                &self.governance_program_id,
+                &realm_cookie.address,
+                &realm_cookie.community_mint,
+                &token_owner.pubkey(),
+            ),
+            voter_weight_record: get_voter_weight_record_address(
+                &self.program_id,
+                &realm_cookie.address,
+                &realm_cookie.community_mint,
+                &token_owner.pubkey(),
+            ),
+            token_owner,
+        }
+    }
+
+    /// Creates spl-token NFT with Metaplex Metadata account
+    #[allow(dead_code)]
+    pub async fn with_metaplex_nft(
+        &mut self,
+        collection_cookie: &CollectionCookie,
+        voter_cookie: &VoterCookie,
+        is_verified: bool,
+    ) -> NftCookie {
+        let nft_mint = Keypair::new();
+        let nft_mint_authority = Keypair::new();
+
+        self.bench
+            .create_mint(&nft_mint, &nft_mint_authority.pubkey(), None)
+            .await;
+
+        let nft_token_account = self
+            .bench
+            .with_token_account(
+                &nft_mint.pubkey(),
+                &voter_cookie.token_owner.pubkey(),
+                &nft_mint_authority,
+                1,
+            )
+            .await;
+
+        // Metadata layout: key, update_authority, mint, data (name, symbol, uri,
+        // seller_fee_basis_points, creators), primary_sale_happened, is_mutable,
+        // edition_nonce, token_standard, collection
+        let metadata_data = borsh::to_vec(&(
+            4u8,
+            collection_cookie.authority.pubkey(),
+            nft_mint.pubkey(),
+            (
+                "NFT".to_string(),
+                "NFT".to_string(),
+                "https://nft".to_string(),
+                0u16,
+                None::<Vec<(Pubkey, bool, u8)>>,
+            ),
+            false,
+            true,
+            None::<u8>,
+            Some(0u8),
+            Some((is_verified, collection_cookie.address)),
+        ))
+        .unwrap();
+
+        let metadata_address =
+            spl_inline_mpl_token_metadata::get_metadata_address(&nft_mint.pubkey());
+        self.set_account(
+            &metadata_address,
+            metadata_data,
+            &spl_inline_mpl_token_metadata::id(),
+        );
+
+        NftCookie {
+            accounts: NftVoteAccounts {
+                nft_token_account: nft_token_account.address,
+                nft_mint: nft_mint.pubkey(),
+                collection_membership: metadata_address,
+            },
+        }
+    }
+
+    /// Creates spl-token-2022 NFT with GroupMemberPointer pointing to a
+    /// TokenGroupMember account owned by the mock group program
+    #[allow(dead_code)]
+    pub async fn with_token_group_nft(
+        &mut self,
+        collection_cookie: &CollectionCookie,
+        voter_cookie: &VoterCookie,
+    ) -> NftCookie {
+        let nft_mint = Keypair::new();
+        let nft_mint_authority = Keypair::new();
+        let nft_token_account = Keypair::new();
+        let member_address = Pubkey::new_unique();
+
+        let mint_len =
+            ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::GroupMemberPointer])
+                .unwrap();
+        let account_len = ExtensionType::try_calculate_account_len::<Account>(&[]).unwrap();
+
+        let instructions = [
+            system_instruction::create_account(
+                &self.bench.payer.pubkey(),
+                &nft_mint.pubkey(),
+                self.bench.rent.minimum_balance(mint_len),
+                mint_len as u64,
+                &spl_token_2022::id(),
+            ),
+            group_member_pointer::instruction::initialize(
+                &spl_token_2022::id(),
+                &nft_mint.pubkey(),
+                None,
+                Some(member_address),
+            )
+            .unwrap(),
+            spl_token_2022::instruction::initialize_mint2(
+                &spl_token_2022::id(),
+                &nft_mint.pubkey(),
+                &nft_mint_authority.pubkey(),
+                None,
+                0,
+            )
+            .unwrap(),
+            system_instruction::create_account(
+                &self.bench.payer.pubkey(),
+                &nft_token_account.pubkey(),
+                self.bench.rent.minimum_balance(account_len),
+                account_len as u64,
+                &spl_token_2022::id(),
+            ),
+            spl_token_2022::instruction::initialize_account3(
+                &spl_token_2022::id(),
+                &nft_token_account.pubkey(),
+                &nft_mint.pubkey(),
+                &voter_cookie.token_owner.pubkey(),
+            )
+            .unwrap(),
+            spl_token_2022::instruction::mint_to(
+                &spl_token_2022::id(),
+                &nft_mint.pubkey(),
+                &nft_token_account.pubkey(),
+                &nft_mint_authority.pubkey(),
+                &[],
+                1,
+            )
+            .unwrap(),
+        ];
+
+        self.bench
+            .process_transaction(
+                &instructions,
+                Some(&[&nft_mint, &nft_token_account, &nft_mint_authority]),
+            )
+            .await
+            .unwrap();
+
+        let member = TokenGroupMember::new(&nft_mint.pubkey(), &collection_cookie.address, 1);
+
+        let mut data =
+            vec![0; TlvStateOwned::get_base_len() + std::mem::size_of::<TokenGroupMember>()];
+        let mut state = TlvStateMut::unpack(&mut data).unwrap();
+        *state.init_value::<TokenGroupMember>(true).unwrap().0 = member;
+
+        self.set_account(
+            &member_address,
+            data,
+            &collection_cookie.group_program_id.unwrap(),
+        );
+
+        NftCookie {
+            accounts: NftVoteAccounts {
+                nft_token_account: nft_token_account.pubkey(),
+                nft_mint: nft_mint.pubkey(),
+                collection_membership: member_address,
+            },
+        }
+    }
+
+    /// Creates Governance and a signed off Proposal using the voter as the
+    /// proposal owner
+    #[allow(dead_code)]
+    pub async fn with_proposal(
+        &mut self,
+        realm_cookie: &RealmCookie,
+        voter_cookie: &VoterCookie,
+        nft_cookies: &[&NftCookie],
+    ) -> ProposalCookie {
+        let governance_seed = Pubkey::new_unique();
+
+        let governance_config = GovernanceConfig {
+            community_vote_threshold: VoteThreshold::YesVotePercentage(60),
+            min_community_weight_to_create_proposal: 1,
+            transactions_hold_up_time: 10,
+            voting_base_time: 3600,
+            community_vote_tipping: VoteTipping::Strict,
+            council_vote_threshold: VoteThreshold::YesVotePercentage(10),
+            council_veto_vote_threshold: VoteThreshold::YesVotePercentage(50),
+            min_council_weight_to_create_proposal: 2,
+            council_vote_tipping: VoteTipping::Strict,
+            community_veto_vote_threshold: VoteThreshold::YesVotePercentage(55),
+            voting_cool_off_time: 0,
+            deposit_exempt_proposal_count: DEFAULT_DEPOSIT_EXEMPT_PROPOSAL_COUNT,
+        };
+
+        let create_governance_ix = create_governance(
+            &self.governance_program_id,
+            &realm_cookie.address,
+            &governance_seed,
+            &voter_cookie.token_owner_record,
+            &self.bench.payer.pubkey(),
+            &realm_cookie.realm_authority.pubkey(),
+            None,
+            governance_config,
+        );
+
+        self.bench
+            .process_transaction(
+                &[create_governance_ix],
+                Some(&[&realm_cookie.realm_authority]),
+            )
+            .await
+            .unwrap();
+
+        let governance_address = get_governance_address(
+            &self.governance_program_id,
+            &realm_cookie.address,
+            &governance_seed,
+        );
+
+        let proposal_seed = Pubkey::new_unique();
+
+        let update_voter_weight_record_ix = update_voter_weight_record(
+            &self.program_id,
+            &realm_cookie.address,
+            &realm_cookie.community_mint,
+            &voter_cookie.token_owner.pubkey(),
+            &nft_cookies
+                .iter()
+                .map(|nft_cookie| nft_cookie.accounts.clone())
+                .collect::<Vec<_>>(),
+            VoterWeightAction::CreateProposal,
+        );
+
+        let create_proposal_ix = create_proposal(
+            &self.governance_program_id,
+            &governance_address,
+            &voter_cookie.token_owner_record,
+            &voter_cookie.token_owner.pubkey(),
+            &self.bench.payer.pubkey(),
+            Some(voter_cookie.voter_weight_record),
+            &realm_cookie.address,
+            "Proposal #1".to_string(),
+            "Proposal Description".to_string(),
+            &realm_cookie.community_mint,
+            VoteType::SingleChoice,
+            vec!["Yes".to_string()],
+            true,
+            &proposal_seed,
+        );
+
+        let proposal_address = get_proposal_address(
+            &self.governance_program_id,
+            &governance_address,
+            &realm_cookie.community_mint,
+            &proposal_seed,
+        );
+
+        let sign_off_proposal_ix = sign_off_proposal(
+            &self.governance_program_id,
+            &realm_cookie.address,
+            &governance_address,
+            &proposal_address,
+            &voter_cookie.token_owner.pubkey(),
+            Some(&voter_cookie.token_owner_record),
+        );
+
+        self.bench
+            .process_transaction(
+                &[
+                    update_voter_weight_record_ix,
+                    create_proposal_ix,
+                    sign_off_proposal_ix,
+                ],
+                Some(&[&voter_cookie.token_owner]),
+            )
+            .await
+            .unwrap();
+
+        ProposalCookie {
+            address: proposal_address,
+            governance: governance_address,
+            proposal_owner_record: voter_cookie.token_owner_record,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub async fn with_cast_nft_vote(
+        &mut self,
+        realm_cookie: &RealmCookie,
+        proposal_cookie: &ProposalCookie,
+        voter_cookie: &VoterCookie,
+        nft_cookies: &[&NftCookie],
+    ) -> Result<(), ProgramError> {
+        let cast_nft_vote_ix = cast_nft_vote(
+            &self.program_id,
+            &realm_cookie.address,
+            &realm_cookie.community_mint,
+            &voter_cookie.token_owner.pubkey(),
+            &self.bench.payer.pubkey(),
+            &nft_cookies
+                .iter()
+                .map(|nft_cookie| nft_cookie.accounts.clone())
+                .collect::<Vec<_>>(),
+            &proposal_cookie.address,
+        );
+
+        self.bench
+            .process_transaction(&[cast_nft_vote_ix], Some(&[&voter_cookie.token_owner]))
+            .await
+    }
+
+    /// Casts NFT votes and governance vote within the same transaction
+    #[allow(dead_code)]
+    pub async fn with_cast_vote(
+        &mut self,
+        realm_cookie: &RealmCookie,
+        proposal_cookie: &ProposalCookie,
+        voter_cookie: &VoterCookie,
+        nft_cookies: &[&NftCookie],
+    ) -> Result<(), ProgramError> {
+        let cast_nft_vote_ix = cast_nft_vote(
+            &self.program_id,
+            &realm_cookie.address,
+            &realm_cookie.community_mint,
+            &voter_cookie.token_owner.pubkey(),
+            &self.bench.payer.pubkey(),
+            &nft_cookies
+                .iter()
+                .map(|nft_cookie| nft_cookie.accounts.clone())
+                .collect::<Vec<_>>(),
+            &proposal_cookie.address,
+        );
+
+        let cast_vote_ix = cast_vote(
+            &self.governance_program_id,
+            &realm_cookie.address,
+            &proposal_cookie.governance,
+            &proposal_cookie.address,
+            &proposal_cookie.proposal_owner_record,
+            &voter_cookie.token_owner_record,
+            &voter_cookie.token_owner.pubkey(),
+            &realm_cookie.community_mint,
+            &self.bench.payer.pubkey(),
+            Some(voter_cookie.voter_weight_record),
+            Some(get_max_voter_weight_record_address(
+                &self.program_id,
+                &realm_cookie.address,
+                &realm_cookie.community_mint,
+            )),
+            Vote::Approve(vec![VoteChoice {
+                rank: 0,
+                weight_percentage: 100,
+            }]),
+        );
+
+        self.bench
+            .process_transaction(
+                &[cast_nft_vote_ix, cast_vote_ix],
+                Some(&[&voter_cookie.token_owner]),
+            )
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn relinquish_vote(
+        &mut self,
+        realm_cookie: &RealmCookie,
+        proposal_cookie: &ProposalCookie,
+        voter_cookie: &VoterCookie,
+    ) -> Result<(), ProgramError> {
+        let relinquish_vote_ix = relinquish_vote(
+            &self.governance_program_id,
+            &realm_cookie.address,
+            &proposal_cookie.governance,
+            &proposal_cookie.address,
+            &voter_cookie.token_owner_record,
+            &realm_cookie.community_mint,
+            Some(voter_cookie.token_owner.pubkey()),
+            Some(self.bench.payer.pubkey()),
+        );
+
+        self.bench
+            .process_transaction(&[relinquish_vote_ix], Some(&[&voter_cookie.token_owner]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn relinquish_nft_vote(
+        &mut self,
+        realm_cookie: &RealmCookie,
+        proposal_cookie: &ProposalCookie,
+        voter_cookie: &VoterCookie,
+        nft_cookies: &[&NftCookie],
+    ) -> Result<(), ProgramError> {
+        let relinquish_nft_vote_ix = relinquish_nft_vote(
+            &self.program_id,
+            &self.governance_program_id,
+            &realm_cookie.address,
+            &realm_cookie.community_mint,
+            &proposal_cookie.governance,
+            &proposal_cookie.address,
+            &voter_cookie.token_owner.pubkey(),
+            &self.bench.payer.pubkey(),
+            &nft_cookies
+                .iter()
+                .map(|nft_cookie| nft_cookie.accounts.nft_mint)
+                .collect::<Vec<_>>(),
+        );
+
+        self.bench
+            .process_transaction(
+                &[relinquish_nft_vote_ix],
+                Some(&[&voter_cookie.token_owner]),
+            )
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub fn set_account(&mut self, address: &Pubkey, data: Vec<u8>, owner: &Pubkey) {
+        let account = AccountSharedData::create(
+            self.bench.rent.minimum_balance(data.len()),
+            data,
+            *owner,
+            false,
+            0,
+        );
+
+        self.bench.context.set_account(address, &account);
+    }
+
+    #[allow(dead_code)]
+    pub async fn get_registrar_account(&mut self, address: &Pubkey) -> Registrar {
+        self.bench.get_borsh_account::<Registrar>(address).await
+    }
+
+    #[allow(dead_code)]
+    pub async fn get_voter_weight_record(&mut self, address: &Pubkey) -> VoterWeightRecord {
+        self.bench
+            .get_borsh_account::<VoterWeightRecord>(address)
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn get_max_voter_weight_record(&mut self, address: &Pubkey) -> MaxVoterWeightRecord {
+        self.bench
+            .get_borsh_account::<MaxVoterWeightRecord>(address)
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn get_nft_vote_record(
+        &mut self,
+        proposal: &Pubkey,
+        nft_mint: &Pubkey,
+    ) -> Option<NftVoteRecord> {
+        let address = get_nft_vote_record_address(&self.program_id, proposal, nft_mint);
+
+        self.bench
+            .get_account(&address)
+            .await
+            .map(|a| borsh::from_slice::<NftVoteRecord>(&a.data).unwrap())
+    }
+}
@@ -20,6 +20,38 @@ pub enum GovernanceChatError {
     /// Account already initialized
     #[error("Account already initialized")]
     AccountAlreadyInitialized,
+
+    /// Message author must sign
+    #[error("Message author must sign")]
+    MessageAuthorMustSign,
+
+    /// Invalid Proposal for message
+    #[error("Invalid Proposal for message")]
+    InvalidProposalForMessage,
+
+    /// Invalid Proposal option reference
+    #[error("Invalid Proposal option reference")]
+    InvalidProposalOptionReference,
+
+    /// Invalid ProposalTransaction reference
+    #[error("Invalid ProposalTransaction reference")]
+    InvalidProposalTransactionReference,
+
+    /// Message rate limit exceeded
+    #[error("Message rate limit exceeded")]
+    MessageRateLimitExceeded,
+
+    /// Invalid ChatAuthorRecord account address
+    #[error("Invalid ChatAuthorRecord account address")]
+    InvalidChatAuthorRecordAddress,
+
+    /// Invalid payer for message
+    #[error("Invalid payer for message")]
+    InvalidMessagePayer,
+
+    /// ChatMessageV1 can't be edited or deleted
+    #[error("ChatMessageV1 can't be edited or deleted")]
+    ChatMessageV1NotSupported,
 }
 
 impl PrintProgramError for GovernanceChatError {
@@ -1,7 +1,7 @@
 //! Program instructions
 
 use {
-    crate::state::MessageBody,
+    crate::state::{get_chat_author_record_address, MessageBody},
     borsh::{BorshDeserialize, BorshSchema, BorshSerialize},
     solana_program::{
         instruction::{AccountMeta, Instruction},
@@ -24,13 +24,18 @@ pub enum GovernanceChatInstruction {
     ///   4. `[]` TokenOwnerRecord account for the message author
     ///   5. `[signer]` Governance Authority (TokenOwner or Governance Delegate)
     ///   6. `[writable, signer]` ChatMessage account
-    ///   7. `[signer]` Payer
+    ///   7. `[writable, signer]` Payer
     ///   8. `[]` System program
     ///   9. `[]` ReplyTo Message account (optional)
-    ///    10. `[]` Optional Voter Weight Record
+    ///   10. `[]` RealmConfig account
+    ///   11. `[]` Optional Voter Weight Record
+    ///   12. `[writable]` ChatAuthorRecord account used to rate limit posting.
+    ///       PDA seeds: ['chat-author', proposal, token_owner_record]
+    ///   13. `[]` Referenced ProposalTransaction account (optional) Required
+    ///       if the message body references a ProposalTransaction
     PostMessage {
         #[allow(dead_code)]
-        /// Message body (text or reaction)
+        /// Message body (text, reaction or reference)
         body: MessageBody,
 
         #[allow(dead_code)]
@@ -38,6 +43,34 @@ pub enum GovernanceChatInstruction {
         /// If yes then ReplyTo Message account has to be provided
         is_reply: bool,
     },
+
+    /// Edits the body of a message
+    /// Only the author of the message can edit it
+    ///
+    ///   0. `[]` Governance program id
+    ///   1. `[]` Proposal account the message is for
+    ///   2. `[writable]` ChatMessage account
+    ///   3. `[signer]` Author of the message
+    ///   4. `[writable, signer]` Payer of the message which funds the extra
+    ///      rent if the message size increases or receives the refunded rent if
+    ///      it decreases
+    ///   5. `[]` System program
+    ///   6. `[]` Referenced ProposalTransaction account (optional) Required
+    ///      if the message body references a ProposalTransaction
+    EditMessage {
+        #[allow(dead_code)]
+        /// New message body
+        body: MessageBody,
+    },
+
+    /// Deletes a message and disposes its account
+    /// Only the author of the message can delete it
+    ///
+    ///   0. `[writable]` ChatMessage account
+    ///   1. `[signer]` Author of the message
+    ///   2. `[writable]` Payer of the message which receives lamports from the
+    ///      disposed ChatMessage account
+    DeleteMessage {},
 }
 
 /// Creates PostMessage instruction
@@ -66,7 +99,7 @@ pub fn post_message(
         AccountMeta::new_readonly(*token_owner_record, false),
         AccountMeta::new_readonly(*governance_authority, true),
         AccountMeta::new(*chat_message, true),
-        AccountMeta::new_readonly(*payer, true),
+        AccountMeta::new(*payer, true),
         AccountMeta::new_readonly(system_program::id(), false),
     ];
 
@@ -85,6 +118,15 @@ pub fn post_message(
         None,
     );
 
+    accounts.push(AccountMeta::new(
+        get_chat_author_record_address(program_id, proposal, token_owner_record),
+        false,
+    ));
+
+    if let Some(proposal_transaction) = body.get_referenced_proposal_transaction() {
+        accounts.push(AccountMeta::new_readonly(*proposal_transaction, false));
+    }
+
     let instruction = GovernanceChatInstruction::PostMessage { body, is_reply };
 
     Instruction {
@@ -93,3 +135,60 @@ pub fn post_message(
         data: borsh::to_vec(&instruction).unwrap(),
     }
 }
+
+/// Creates EditMessage instruction
+pub fn edit_message(
+    program_id: &Pubkey,
+    // Accounts
+    governance_program_id: &Pubkey,
+    proposal: &Pubkey,
+    chat_message: &Pubkey,
+    author: &Pubkey,
+    payer: &Pubkey,
+    // Args
+    body: MessageBody,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*governance_program_id, false),
+        AccountMeta::new_readonly(*proposal, false),
+        AccountMeta::new(*chat_message, false),
+        AccountMeta::new_readonly(*author, true),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    if let Some(proposal_transaction) = body.get_referenced_proposal_transaction() {
+        accounts.push(AccountMeta::new_readonly(*proposal_transaction, false));
+    }
+
+    let instruction = GovernanceChatInstruction::EditMessage { body };
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: borsh::to_vec(&instruction).unwrap(),
+    }
+}
+
+/// Creates DeleteMessage instruction
+pub fn delete_message(
+    program_id: &Pubkey,
+    // Accounts
+    chat_message: &Pubkey,
+    author: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*chat_message, false),
+        AccountMeta::new_readonly(*author, true),
+        AccountMeta::new(*payer, false),
+    ];
+
+    let instruction = GovernanceChatInstruction::DeleteMessage {};
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: borsh::to_vec(&instruction).unwrap(),
+    }
+}
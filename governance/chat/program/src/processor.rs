@@ -5,7 +5,10 @@ use {
         error::GovernanceChatError,
         instruction::GovernanceChatInstruction,
         state::{
-            assert_is_valid_chat_message, ChatMessage, GovernanceChatAccountType, MessageBody,
+            assert_is_valid_chat_message, get_chat_author_record_address,
+            get_chat_author_record_address_seeds, get_chat_author_record_data,
+            get_chat_message_data, ChatAuthorRecord, ChatMessage, GovernanceChatAccountType,
+            MessageBody, MessageReference,
         },
     },
     borsh::BorshDeserialize,
@@ -16,15 +19,23 @@ use {
         msg,
         program_error::ProgramError,
         pubkey::Pubkey,
+        rent::Rent,
         sysvar::Sysvar,
     },
     spl_governance::state::{
-        governance::get_governance_data_for_realm, proposal::get_proposal_data_for_governance,
-        realm::get_realm_data, realm_config::get_realm_config_data_for_realm,
+        governance::get_governance_data_for_realm,
+        proposal::{get_proposal_data, get_proposal_data_for_governance, ProposalV2},
+        proposal_transaction::get_proposal_transaction_data_for_proposal,
+        realm::get_realm_data,
+        realm_config::get_realm_config_data_for_realm,
         token_owner_record::get_token_owner_record_data_for_realm,
     },
     spl_governance_addin_api::voter_weight::VoterWeightAction,
-    spl_governance_tools::account::create_and_serialize_account,
+    spl_governance_tools::account::{
+        create_and_serialize_account, create_and_serialize_account_signed, dispose_account,
+        extend_account_size, AccountMaxSize,
+    },
+    std::cmp::Ordering,
 };
 
 /// Processes an instruction
@@ -43,7 +54,50 @@ pub fn process_instruction(
             msg!("GOVERNANCE-CHAT-INSTRUCTION: PostMessage");
             process_post_message(program_id, accounts, body, is_reply)
         }
+        GovernanceChatInstruction::EditMessage { body } => {
+            msg!("GOVERNANCE-CHAT-INSTRUCTION: EditMessage");
+            process_edit_message(program_id, accounts, body)
+        }
+        GovernanceChatInstruction::DeleteMessage {} => {
+            msg!("GOVERNANCE-CHAT-INSTRUCTION: DeleteMessage");
+            process_delete_message(program_id, accounts)
+        }
+    }
+}
+
+/// Asserts the items referenced by the message body belong to the given
+/// Proposal
+fn assert_is_valid_message_body(
+    governance_program_id: &Pubkey,
+    proposal_info: &AccountInfo,
+    proposal_data: &ProposalV2,
+    body: &MessageBody,
+    proposal_transaction_info: Option<&AccountInfo>,
+) -> ProgramResult {
+    if let MessageBody::Reference { reference, .. } = body {
+        match reference {
+            MessageReference::ProposalOption { option_index } => {
+                if *option_index as usize >= proposal_data.options.len() {
+                    return Err(GovernanceChatError::InvalidProposalOptionReference.into());
+                }
+            }
+            MessageReference::ProposalTransaction {
+                proposal_transaction,
+            } => {
+                let proposal_transaction_info = proposal_transaction_info
+                    .filter(|info| info.key == proposal_transaction)
+                    .ok_or(GovernanceChatError::InvalidProposalTransactionReference)?;
+
+                get_proposal_transaction_data_for_proposal(
+                    governance_program_id,
+                    proposal_transaction_info,
+                    proposal_info.key,
+                )?;
+            }
+        }
     }
+
+    Ok(())
 }
 
 /// Processes PostMessage instruction
@@ -91,7 +145,7 @@ pub fn process_post_message(
 
     // deserialize proposal to assert it belongs to the given governance and hence
     // belongs to the same realm as the token owner
-    let _proposal_data = get_proposal_data_for_governance(
+    let proposal_data = get_proposal_data_for_governance(
         governance_program_id,
         proposal_info,
         governance_info.key,
@@ -118,15 +172,79 @@ pub fn process_post_message(
         return Err(GovernanceChatError::NotEnoughTokensToCommentProposal.into());
     }
 
+    let chat_author_record_info = next_account_info(account_info_iter)?; // 12
+
+    let proposal_transaction_info = if body.get_referenced_proposal_transaction().is_some() {
+        Some(next_account_info(account_info_iter)?) // 13
+    } else {
+        None
+    };
+
+    assert_is_valid_message_body(
+        governance_program_id,
+        proposal_info,
+        &proposal_data,
+        &body,
+        proposal_transaction_info,
+    )?;
+
     let clock = Clock::get()?;
 
+    // Rate limit posting using the author's voter weight resolved for the
+    // CommentProposal action as the number of messages allowed within the rate
+    // limit window
+    if *chat_author_record_info.key
+        != get_chat_author_record_address(
+            program_id,
+            proposal_info.key,
+            token_owner_record_info.key,
+        )
+    {
+        return Err(GovernanceChatError::InvalidChatAuthorRecordAddress.into());
+    }
+
+    if chat_author_record_info.data_is_empty() {
+        let mut chat_author_record_data = ChatAuthorRecord {
+            account_type: GovernanceChatAccountType::ChatAuthorRecord,
+            proposal: *proposal_info.key,
+            author: token_owner_record_data.governing_token_owner,
+            window_start_at: clock.unix_timestamp,
+            window_messages_count: 0,
+            reserved: [0; 32],
+        };
+
+        chat_author_record_data.record_message(clock.unix_timestamp, voter_weight)?;
+
+        create_and_serialize_account_signed(
+            payer_info,
+            chat_author_record_info,
+            &chat_author_record_data,
+            &get_chat_author_record_address_seeds(proposal_info.key, token_owner_record_info.key),
+            program_id,
+            system_info,
+            &Rent::get()?,
+            0,
+        )?;
+    } else {
+        let mut chat_author_record_data =
+            get_chat_author_record_data(program_id, chat_author_record_info)?;
+
+        chat_author_record_data.record_message(clock.unix_timestamp, voter_weight)?;
+
+        borsh::to_writer(
+            &mut chat_author_record_info.data.borrow_mut()[..],
+            &chat_author_record_data,
+        )?;
+    }
+
     let chat_message_data = ChatMessage {
-        account_type: GovernanceChatAccountType::ChatMessage,
+        account_type: GovernanceChatAccountType::ChatMessageV2,
         proposal: *proposal_info.key,
         author: token_owner_record_data.governing_token_owner,
         posted_at: clock.unix_timestamp,
         reply_to: reply_to_address,
         body,
+        payer: *payer_info.key,
     };
 
     create_and_serialize_account(
@@ -139,3 +257,103 @@ pub fn process_post_message(
 
     Ok(())
 }
+
+/// Processes EditMessage instruction
+pub fn process_edit_message(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    body: MessageBody,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let governance_program_info = next_account_info(account_info_iter)?; // 0
+    let proposal_info = next_account_info(account_info_iter)?; // 1
+    let chat_message_info = next_account_info(account_info_iter)?; // 2
+    let author_info = next_account_info(account_info_iter)?; // 3
+
+    let payer_info = next_account_info(account_info_iter)?; // 4
+    let system_info = next_account_info(account_info_iter)?; // 5
+
+    let proposal_transaction_info = if body.get_referenced_proposal_transaction().is_some() {
+        Some(next_account_info(account_info_iter)?) // 6
+    } else {
+        None
+    };
+
+    let mut chat_message_data = get_chat_message_data(program_id, chat_message_info)?;
+    chat_message_data.assert_author_is_signer(author_info)?;
+    chat_message_data.assert_is_payer(payer_info)?;
+
+    if chat_message_data.proposal != *proposal_info.key {
+        return Err(GovernanceChatError::InvalidProposalForMessage.into());
+    }
+
+    let proposal_data = get_proposal_data(governance_program_info.key, proposal_info)?;
+
+    assert_is_valid_message_body(
+        governance_program_info.key,
+        proposal_info,
+        &proposal_data,
+        &body,
+        proposal_transaction_info,
+    )?;
+
+    chat_message_data.body = body;
+
+    // Resize the account to the new body size
+    let rent = Rent::get()?;
+    let chat_message_size = chat_message_data.get_max_size().unwrap();
+
+    match chat_message_size.cmp(&chat_message_info.data_len()) {
+        Ordering::Greater => {
+            extend_account_size(
+                chat_message_info,
+                payer_info,
+                chat_message_size,
+                &rent,
+                system_info,
+            )?;
+        }
+        Ordering::Less => {
+            chat_message_info.realloc(chat_message_size, false)?;
+
+            // Refund the rent which is no longer needed to the payer
+            let refund_lamports = chat_message_info
+                .lamports()
+                .saturating_sub(rent.minimum_balance(chat_message_size));
+
+            **chat_message_info.lamports.borrow_mut() = chat_message_info
+                .lamports()
+                .checked_sub(refund_lamports)
+                .unwrap();
+
+            **payer_info.lamports.borrow_mut() =
+                payer_info.lamports().checked_add(refund_lamports).unwrap();
+        }
+        Ordering::Equal => {}
+    }
+
+    borsh::to_writer(
+        &mut chat_message_info.data.borrow_mut()[..],
+        &chat_message_data,
+    )?;
+
+    Ok(())
+}
+
+/// Processes DeleteMessage instruction
+pub fn process_delete_message(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let chat_message_info = next_account_info(account_info_iter)?; // 0
+    let author_info = next_account_info(account_info_iter)?; // 1
+    let payer_info = next_account_info(account_info_iter)?; // 2
+
+    let chat_message_data = get_chat_message_data(program_id, chat_message_info)?;
+    chat_message_data.assert_author_is_signer(author_info)?;
+    chat_message_data.assert_is_payer(payer_info)?;
+
+    dispose_account(chat_message_info, payer_info)?;
+
+    Ok(())
+}
@@ -1,12 +1,15 @@
 //! Program state
 
 use {
+    crate::error::GovernanceChatError,
     borsh::{BorshDeserialize, BorshSchema, BorshSerialize},
     solana_program::{
         account_info::AccountInfo, clock::UnixTimestamp, program_error::ProgramError,
-        pubkey::Pubkey,
+        program_pack::IsInitialized, pubkey::Pubkey,
+    },
+    spl_governance_tools::account::{
+        assert_is_valid_account_of_types, get_account_data, get_account_type, AccountMaxSize,
     },
-    spl_governance_tools::account::{assert_is_valid_account_of_type, AccountMaxSize},
 };
 
 /// Defines all GovernanceChat accounts types
@@ -15,8 +18,33 @@ pub enum GovernanceChatAccountType {
     /// Default uninitialized account state
     Uninitialized,
 
+    /// Chat message without the payer of its rent
+    /// Note: V1 messages can't be edited or deleted because the account which
+    /// paid for them is unknown
+    ChatMessageV1,
+
+    /// Record of messages posted by an author for a Proposal used to rate
+    /// limit posting
+    ChatAuthorRecord,
+
     /// Chat message
-    ChatMessage,
+    ChatMessageV2,
+}
+
+/// Proposal item a message can reference
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub enum MessageReference {
+    /// Proposal option at the given index
+    ProposalOption {
+        /// The index of the option
+        option_index: u8,
+    },
+
+    /// ProposalTransaction account of the Proposal
+    ProposalTransaction {
+        /// ProposalTransaction account address
+        proposal_transaction: Pubkey,
+    },
 }
 
 /// Chat message body
@@ -29,6 +57,33 @@ pub enum MessageBody {
     /// In the UI reactions are displayed together under the parent message (as
     /// opposed to hierarchical replies)
     Reaction(String),
+
+    /// Text message encoded as utf-8 string which references a specific
+    /// Proposal option or ProposalTransaction
+    Reference {
+        /// Referenced Proposal item
+        reference: MessageReference,
+
+        /// Message text
+        text: String,
+    },
+}
+
+impl MessageBody {
+    /// Returns the ProposalTransaction account referenced by the message body
+    /// if any
+    pub fn get_referenced_proposal_transaction(&self) -> Option<&Pubkey> {
+        match self {
+            MessageBody::Reference {
+                reference:
+                    MessageReference::ProposalTransaction {
+                        proposal_transaction,
+                    },
+                ..
+            } => Some(proposal_transaction),
+            _ => None,
+        }
+    }
 }
 
 /// Chat message
@@ -51,6 +106,10 @@ pub struct ChatMessage {
 
     /// Body of the message
     pub body: MessageBody,
+
+    /// The account which paid for the message and receives its rent back when
+    /// the message is shrunk or deleted
+    pub payer: Pubkey,
 }
 
 impl AccountMaxSize for ChatMessage {
@@ -58,25 +117,175 @@ impl AccountMaxSize for ChatMessage {
         let body_size = match &self.body {
             MessageBody::Text(body) => body.len(),
             MessageBody::Reaction(body) => body.len(),
+            MessageBody::Reference {
+                reference: MessageReference::ProposalOption { .. },
+                text,
+            } => text.len() + 2,
+            MessageBody::Reference {
+                reference: MessageReference::ProposalTransaction { .. },
+                text,
+            } => text.len() + 33,
         };
 
-        Some(body_size + 111)
+        Some(body_size + 143)
     }
 }
 
+impl IsInitialized for ChatMessage {
+    fn is_initialized(&self) -> bool {
+        self.account_type == GovernanceChatAccountType::ChatMessageV2
+    }
+}
+
+impl ChatMessage {
+    /// Asserts the given account is the author of the message and signed the
+    /// transaction
+    pub fn assert_author_is_signer(&self, author_info: &AccountInfo) -> Result<(), ProgramError> {
+        if self.author != *author_info.key || !author_info.is_signer {
+            return Err(GovernanceChatError::MessageAuthorMustSign.into());
+        }
+
+        Ok(())
+    }
+
+    /// Asserts the given account is the payer of the message
+    pub fn assert_is_payer(&self, payer_info: &AccountInfo) -> Result<(), ProgramError> {
+        if self.payer != *payer_info.key {
+            return Err(GovernanceChatError::InvalidMessagePayer.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Deserializes ChatMessage account and checks owner program
+pub fn get_chat_message_data(
+    program_id: &Pubkey,
+    chat_message_info: &AccountInfo,
+) -> Result<ChatMessage, ProgramError> {
+    let account_type: GovernanceChatAccountType = get_account_type(program_id, chat_message_info)?;
+
+    if account_type == GovernanceChatAccountType::ChatMessageV1 {
+        return Err(GovernanceChatError::ChatMessageV1NotSupported.into());
+    }
+
+    get_account_data::<ChatMessage>(program_id, chat_message_info)
+}
+
 /// Checks whether Chat account exists, is initialized and  owned by
 /// governance-chat program
 pub fn assert_is_valid_chat_message(
     program_id: &Pubkey,
     chat_message_info: &AccountInfo,
 ) -> Result<(), ProgramError> {
-    assert_is_valid_account_of_type(
+    assert_is_valid_account_of_types(
         program_id,
         chat_message_info,
-        GovernanceChatAccountType::ChatMessage,
+        |account_type: &GovernanceChatAccountType| {
+            *account_type == GovernanceChatAccountType::ChatMessageV1
+                || *account_type == GovernanceChatAccountType::ChatMessageV2
+        },
     )
 }
 
+/// The time window in seconds the number of posted messages is limited for
+/// Note: The number of messages an author can post for a Proposal within the
+/// window is the author's voter weight resolved for the CommentProposal action,
+/// which lets a voter weight addin set the rate limit
+pub const MESSAGE_RATE_LIMIT_WINDOW: UnixTimestamp = 60;
+
+/// Record of messages posted by an author for a Proposal
+/// It's used to rate limit posting and it's created when the author posts
+/// the first message for the Proposal
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub struct ChatAuthorRecord {
+    /// Account type
+    pub account_type: GovernanceChatAccountType,
+
+    /// The proposal the messages are posted for
+    pub proposal: Pubkey,
+
+    /// Author of the messages
+    pub author: Pubkey,
+
+    /// The start of the current rate limit window
+    pub window_start_at: UnixTimestamp,
+
+    /// The number of messages posted within the current rate limit window
+    pub window_messages_count: u64,
+
+    /// Reserved space for future versions
+    pub reserved: [u8; 32],
+}
+
+impl AccountMaxSize for ChatAuthorRecord {}
+
+impl IsInitialized for ChatAuthorRecord {
+    fn is_initialized(&self) -> bool {
+        self.account_type == GovernanceChatAccountType::ChatAuthorRecord
+    }
+}
+
+impl ChatAuthorRecord {
+    /// Records a message posted at the given time and asserts the author
+    /// didn't exceed the posting rate limit of max_messages per
+    /// MESSAGE_RATE_LIMIT_WINDOW
+    pub fn record_message(
+        &mut self,
+        posted_at: UnixTimestamp,
+        max_messages: u64,
+    ) -> Result<(), ProgramError> {
+        if posted_at.saturating_sub(self.window_start_at) >= MESSAGE_RATE_LIMIT_WINDOW {
+            self.window_start_at = posted_at;
+            self.window_messages_count = 0;
+        }
+
+        if self.window_messages_count >= max_messages {
+            return Err(GovernanceChatError::MessageRateLimitExceeded.into());
+        }
+
+        self.window_messages_count = self.window_messages_count.checked_add(1).unwrap();
+
+        Ok(())
+    }
+}
+
+/// Returns ChatAuthorRecord PDA seeds
+/// Note: The record is derived from the author's TokenOwnerRecord which
+/// can be resolved without knowing the author when the message is posted by a
+/// delegate
+pub fn get_chat_author_record_address_seeds<'a>(
+    proposal: &'a Pubkey,
+    token_owner_record: &'a Pubkey,
+) -> [&'a [u8]; 3] {
+    [
+        b"chat-author".as_ref(),
+        proposal.as_ref(),
+        token_owner_record.as_ref(),
+    ]
+}
+
+/// Returns ChatAuthorRecord PDA address
+pub fn get_chat_author_record_address(
+    program_id: &Pubkey,
+    proposal: &Pubkey,
+    token_owner_record: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &get_chat_author_record_address_seeds(proposal, token_owner_record),
+        program_id,
+    )
+    .0
+}
+
+/// Deserializes ChatAuthorRecord account and checks owner program
+pub fn get_chat_author_record_data(
+    program_id: &Pubkey,
+    chat_author_record_info: &AccountInfo,
+) -> Result<ChatAuthorRecord, ProgramError> {
+    get_account_data::<ChatAuthorRecord>(program_id, chat_author_record_info)
+}
+
 #[cfg(test)]
 mod test {
 
@@ -85,15 +294,89 @@ mod test {
     #[test]
     fn test_max_size() {
         let message = ChatMessage {
-            account_type: GovernanceChatAccountType::ChatMessage,
+            account_type: GovernanceChatAccountType::ChatMessageV2,
             proposal: Pubkey::new_unique(),
             author: Pubkey::new_unique(),
             posted_at: 10,
             reply_to: Some(Pubkey::new_unique()),
             body: MessageBody::Text("message".to_string()),
+            payer: Pubkey::new_unique(),
         };
         let size = borsh::to_vec(&message).unwrap().len();
 
         assert_eq!(message.get_max_size(), Some(size));
     }
+
+    #[test]
+    fn test_max_size_with_reference_body() {
+        for reference in [
+            MessageReference::ProposalOption { option_index: 1 },
+            MessageReference::ProposalTransaction {
+                proposal_transaction: Pubkey::new_unique(),
+            },
+        ] {
+            let message = ChatMessage {
+                account_type: GovernanceChatAccountType::ChatMessageV2,
+                proposal: Pubkey::new_unique(),
+                author: Pubkey::new_unique(),
+                posted_at: 10,
+                reply_to: Some(Pubkey::new_unique()),
+                body: MessageBody::Reference {
+                    reference,
+                    text: "message".to_string(),
+                },
+                payer: Pubkey::new_unique(),
+            };
+            let size = borsh::to_vec(&message).unwrap().len();
+
+            assert_eq!(message.get_max_size(), Some(size));
+        }
+    }
+
+    fn create_test_chat_author_record() -> ChatAuthorRecord {
+        ChatAuthorRecord {
+            account_type: GovernanceChatAccountType::ChatAuthorRecord,
+            proposal: Pubkey::new_unique(),
+            author: Pubkey::new_unique(),
+            window_start_at: 100,
+            window_messages_count: 0,
+            reserved: [0; 32],
+        }
+    }
+
+    #[test]
+    fn test_record_message_within_window() {
+        let mut chat_author_record = create_test_chat_author_record();
+
+        chat_author_record.record_message(110, 2).unwrap();
+
+        assert_eq!(chat_author_record.window_start_at, 100);
+        assert_eq!(chat_author_record.window_messages_count, 1);
+    }
+
+    #[test]
+    fn test_record_message_with_rate_limit_exceeded_error() {
+        let mut chat_author_record = create_test_chat_author_record();
+        chat_author_record.window_messages_count = 2;
+
+        let err = chat_author_record.record_message(110, 2).err().unwrap();
+
+        assert_eq!(err, GovernanceChatError::MessageRateLimitExceeded.into());
+    }
+
+    #[test]
+    fn test_record_message_resets_window() {
+        let mut chat_author_record = create_test_chat_author_record();
+        chat_author_record.window_messages_count = 2;
+
+        chat_author_record
+            .record_message(100 + MESSAGE_RATE_LIMIT_WINDOW, 2)
+            .unwrap();
+
+        assert_eq!(
+            chat_author_record.window_start_at,
+            100 + MESSAGE_RATE_LIMIT_WINDOW
+        );
+        assert_eq!(chat_author_record.window_messages_count, 1);
+    }
 }
@@ -0,0 +1,131 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    program_test::GovernanceChatProgramTest,
+    solana_program_test::tokio,
+    solana_sdk::signature::{Keypair, Signer},
+    spl_governance_chat::{error::GovernanceChatError, state::MessageBody},
+};
+
+mod program_test;
+
+#[tokio::test]
+async fn test_delete_message() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+
+    let payer = Keypair::new();
+    governance_chat_test
+        .bench
+        .transfer_sol(&payer.pubkey(), 1_000_000_000)
+        .await;
+
+    let chat_message_cookie = governance_chat_test
+        .with_chat_message_using_payer(
+            &proposal_cookie,
+            None,
+            MessageBody::Text("My comment".to_string()),
+            &payer,
+        )
+        .await
+        .unwrap();
+
+    let chat_message_account = governance_chat_test
+        .bench
+        .get_account(&chat_message_cookie.address)
+        .await
+        .unwrap();
+
+    let payer_account = governance_chat_test
+        .bench
+        .get_account(&payer.pubkey())
+        .await
+        .unwrap();
+
+    // Act
+    governance_chat_test
+        .delete_chat_message(
+            &chat_message_cookie,
+            &proposal_cookie.token_owner,
+            &payer.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    let deleted_chat_message_account = governance_chat_test
+        .bench
+        .get_account(&chat_message_cookie.address)
+        .await;
+
+    assert_eq!(None, deleted_chat_message_account);
+
+    let refunded_payer_account = governance_chat_test
+        .bench
+        .get_account(&payer.pubkey())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        payer_account.lamports + chat_message_account.lamports,
+        refunded_payer_account.lamports
+    );
+}
+
+#[tokio::test]
+async fn test_delete_message_with_message_author_must_sign_error() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+
+    let chat_message_cookie = governance_chat_test
+        .with_chat_message(&proposal_cookie, None)
+        .await
+        .unwrap();
+
+    // Act
+    let err = governance_chat_test
+        .delete_chat_message(
+            &chat_message_cookie,
+            &Keypair::new(),
+            &chat_message_cookie.account.payer,
+        )
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(err, GovernanceChatError::MessageAuthorMustSign.into());
+}
+
+#[tokio::test]
+async fn test_delete_message_with_invalid_payer_error() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+
+    let chat_message_cookie = governance_chat_test
+        .with_chat_message(&proposal_cookie, None)
+        .await
+        .unwrap();
+
+    let beneficiary_cookie = governance_chat_test.bench.with_wallet().await;
+
+    // Act
+    let err = governance_chat_test
+        .delete_chat_message(
+            &chat_message_cookie,
+            &proposal_cookie.token_owner,
+            &beneficiary_cookie.address,
+        )
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(err, GovernanceChatError::InvalidMessagePayer.into());
+}
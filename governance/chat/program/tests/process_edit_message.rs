@@ -0,0 +1,226 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    program_test::GovernanceChatProgramTest,
+    solana_program_test::tokio,
+    solana_sdk::signature::Keypair,
+    spl_governance_chat::{
+        error::GovernanceChatError,
+        state::{MessageBody, MessageReference},
+    },
+};
+
+mod program_test;
+
+#[tokio::test]
+async fn test_edit_message_with_longer_body() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+
+    let mut chat_message_cookie = governance_chat_test
+        .with_chat_message(&proposal_cookie, None)
+        .await
+        .unwrap();
+
+    let message_body = MessageBody::Text("My edited and much longer comment".to_string());
+
+    // Act
+    governance_chat_test
+        .edit_chat_message(
+            &mut chat_message_cookie,
+            &proposal_cookie.token_owner,
+            message_body,
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    let chat_message_data = governance_chat_test
+        .get_message_account(&chat_message_cookie.address)
+        .await;
+
+    assert_eq!(chat_message_data, chat_message_cookie.account);
+}
+
+#[tokio::test]
+async fn test_edit_message_with_shorter_body() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+
+    let mut chat_message_cookie = governance_chat_test
+        .with_chat_message(&proposal_cookie, None)
+        .await
+        .unwrap();
+
+    let chat_message_account = governance_chat_test
+        .bench
+        .get_account(&chat_message_cookie.address)
+        .await
+        .unwrap();
+
+    let message_body = MessageBody::Text("Edited".to_string());
+
+    // Act
+    governance_chat_test
+        .edit_chat_message(
+            &mut chat_message_cookie,
+            &proposal_cookie.token_owner,
+            message_body,
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    let chat_message_data = governance_chat_test
+        .get_message_account(&chat_message_cookie.address)
+        .await;
+
+    assert_eq!(chat_message_data, chat_message_cookie.account);
+
+    // The rent for the released space is refunded
+    let edited_chat_message_account = governance_chat_test
+        .bench
+        .get_account(&chat_message_cookie.address)
+        .await
+        .unwrap();
+
+    assert!(edited_chat_message_account.data.len() < chat_message_account.data.len());
+    assert!(edited_chat_message_account.lamports < chat_message_account.lamports);
+}
+
+#[tokio::test]
+async fn test_edit_message_with_proposal_transaction_reference() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+
+    let proposal_transaction = governance_chat_test
+        .with_proposal_transaction(&proposal_cookie)
+        .await;
+
+    let mut chat_message_cookie = governance_chat_test
+        .with_chat_message(&proposal_cookie, None)
+        .await
+        .unwrap();
+
+    let message_body = MessageBody::Reference {
+        reference: MessageReference::ProposalTransaction {
+            proposal_transaction,
+        },
+        text: "My comment on transaction".to_string(),
+    };
+
+    // Act
+    governance_chat_test
+        .edit_chat_message(
+            &mut chat_message_cookie,
+            &proposal_cookie.token_owner,
+            message_body,
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    let chat_message_data = governance_chat_test
+        .get_message_account(&chat_message_cookie.address)
+        .await;
+
+    assert_eq!(chat_message_data, chat_message_cookie.account);
+}
+
+#[tokio::test]
+async fn test_edit_message_with_invalid_proposal_option_reference_error() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+
+    let mut chat_message_cookie = governance_chat_test
+        .with_chat_message(&proposal_cookie, None)
+        .await
+        .unwrap();
+
+    let message_body = MessageBody::Reference {
+        reference: MessageReference::ProposalOption { option_index: 5 },
+        text: "My comment on option".to_string(),
+    };
+
+    // Act
+    let err = governance_chat_test
+        .edit_chat_message(
+            &mut chat_message_cookie,
+            &proposal_cookie.token_owner,
+            message_body,
+        )
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(
+        err,
+        GovernanceChatError::InvalidProposalOptionReference.into()
+    );
+}
+
+#[tokio::test]
+async fn test_edit_message_with_message_author_must_sign_error() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+
+    let mut chat_message_cookie = governance_chat_test
+        .with_chat_message(&proposal_cookie, None)
+        .await
+        .unwrap();
+
+    let message_body = MessageBody::Text("Not my comment".to_string());
+
+    // Act
+    let err = governance_chat_test
+        .edit_chat_message(&mut chat_message_cookie, &Keypair::new(), message_body)
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(err, GovernanceChatError::MessageAuthorMustSign.into());
+}
+
+#[tokio::test]
+async fn test_edit_message_with_invalid_proposal_error() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+    let proposal_cookie2 = governance_chat_test.with_proposal().await;
+
+    let mut chat_message_cookie = governance_chat_test
+        .with_chat_message(&proposal_cookie, None)
+        .await
+        .unwrap();
+
+    chat_message_cookie.account.proposal = proposal_cookie2.address;
+
+    let message_body = MessageBody::Text("Edited".to_string());
+
+    // Act
+    let err = governance_chat_test
+        .edit_chat_message(
+            &mut chat_message_cookie,
+            &proposal_cookie.token_owner,
+            message_body,
+        )
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(err, GovernanceChatError::InvalidProposalForMessage.into());
+}
@@ -1,9 +1,18 @@
 #![cfg(feature = "test-sbf")]
 
 use {
-    program_test::GovernanceChatProgramTest, solana_program_test::tokio,
-    solana_sdk::signature::Keypair, spl_governance::error::GovernanceError,
-    spl_governance_chat::error::GovernanceChatError,
+    program_test::GovernanceChatProgramTest,
+    solana_program::pubkey::Pubkey,
+    solana_program_test::tokio,
+    solana_sdk::signature::{Keypair, Signer},
+    spl_governance::error::GovernanceError,
+    spl_governance_chat::{
+        error::GovernanceChatError,
+        state::{
+            get_chat_author_record_address, GovernanceChatAccountType, MessageBody,
+            MessageReference,
+        },
+    },
 };
 
 mod program_test;
@@ -149,3 +158,244 @@ async fn test_post_message_with_voter_weight_addin() {
 
     assert_eq!(chat_message_data, chat_message_cookie.account);
 }
+
+#[tokio::test]
+async fn test_post_message_with_proposal_option_reference() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+
+    let message_body = MessageBody::Reference {
+        reference: MessageReference::ProposalOption { option_index: 0 },
+        text: "My comment on option".to_string(),
+    };
+
+    // Act
+    let chat_message_cookie = governance_chat_test
+        .with_chat_message_body(&proposal_cookie, None, message_body)
+        .await
+        .unwrap();
+
+    // Assert
+    let chat_message_data = governance_chat_test
+        .get_message_account(&chat_message_cookie.address)
+        .await;
+
+    assert_eq!(chat_message_data, chat_message_cookie.account);
+}
+
+#[tokio::test]
+async fn test_post_message_with_invalid_proposal_option_reference_error() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+
+    // The proposal has only one option
+    let message_body = MessageBody::Reference {
+        reference: MessageReference::ProposalOption { option_index: 1 },
+        text: "My comment on option".to_string(),
+    };
+
+    // Act
+    let err = governance_chat_test
+        .with_chat_message_body(&proposal_cookie, None, message_body)
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(
+        err,
+        GovernanceChatError::InvalidProposalOptionReference.into()
+    );
+}
+
+#[tokio::test]
+async fn test_post_message_with_proposal_transaction_reference() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+
+    let proposal_transaction = governance_chat_test
+        .with_proposal_transaction(&proposal_cookie)
+        .await;
+
+    let message_body = MessageBody::Reference {
+        reference: MessageReference::ProposalTransaction {
+            proposal_transaction,
+        },
+        text: "My comment on transaction".to_string(),
+    };
+
+    // Act
+    let chat_message_cookie = governance_chat_test
+        .with_chat_message_body(&proposal_cookie, None, message_body)
+        .await
+        .unwrap();
+
+    // Assert
+    let chat_message_data = governance_chat_test
+        .get_message_account(&chat_message_cookie.address)
+        .await;
+
+    assert_eq!(chat_message_data, chat_message_cookie.account);
+}
+
+#[tokio::test]
+async fn test_post_message_with_proposal_transaction_of_other_proposal_error() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+
+    let proposal_cookie2 = governance_chat_test.with_proposal().await;
+
+    let proposal_transaction = governance_chat_test
+        .with_proposal_transaction(&proposal_cookie2)
+        .await;
+
+    let message_body = MessageBody::Reference {
+        reference: MessageReference::ProposalTransaction {
+            proposal_transaction,
+        },
+        text: "My comment on transaction".to_string(),
+    };
+
+    // Act
+    let err = governance_chat_test
+        .with_chat_message_body(&proposal_cookie, None, message_body)
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(
+        err,
+        GovernanceError::InvalidProposalForProposalTransaction.into()
+    );
+}
+
+#[tokio::test]
+async fn test_post_message_creates_chat_author_record() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+
+    // Act
+    governance_chat_test
+        .with_chat_message(&proposal_cookie, None)
+        .await
+        .unwrap();
+
+    governance_chat_test
+        .with_chat_message(&proposal_cookie, None)
+        .await
+        .unwrap();
+
+    // Assert
+    let chat_author_record_address = get_chat_author_record_address(
+        &governance_chat_test.program_id,
+        &proposal_cookie.address,
+        &proposal_cookie.token_owner_record_address,
+    );
+
+    let chat_author_record_data = governance_chat_test
+        .get_chat_author_record_account(&chat_author_record_address)
+        .await;
+
+    assert_eq!(
+        chat_author_record_data.account_type,
+        GovernanceChatAccountType::ChatAuthorRecord
+    );
+    assert_eq!(chat_author_record_data.proposal, proposal_cookie.address);
+    assert_eq!(
+        chat_author_record_data.author,
+        proposal_cookie.token_owner.pubkey()
+    );
+    assert_eq!(chat_author_record_data.window_messages_count, 2);
+}
+
+#[tokio::test]
+async fn test_post_message_with_rate_limit_exceeded_error() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+
+    let mut proposal_cookie = governance_chat_test.with_proposal().await;
+
+    // The voter weight of the author is the number of messages allowed within
+    // the rate limit window
+    let voter_weight = 2;
+
+    let token_owner_record_cookie = governance_chat_test
+        .with_token_owner_deposit(&proposal_cookie, voter_weight)
+        .await;
+
+    proposal_cookie.token_owner_record_address = token_owner_record_cookie.address;
+    proposal_cookie.token_owner = token_owner_record_cookie.token_owner;
+
+    for _ in 0..voter_weight {
+        governance_chat_test
+            .with_chat_message(&proposal_cookie, None)
+            .await
+            .unwrap();
+    }
+
+    // Act
+    let err = governance_chat_test
+        .with_chat_message(&proposal_cookie, None)
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(err, GovernanceChatError::MessageRateLimitExceeded.into());
+}
+
+#[tokio::test]
+async fn test_post_message_with_invalid_chat_author_record_address_error() {
+    // Arrange
+    let mut governance_chat_test = GovernanceChatProgramTest::start_new().await;
+
+    let proposal_cookie = governance_chat_test.with_proposal().await;
+
+    let message_account = Keypair::new();
+
+    let mut post_message_ix = spl_governance_chat::instruction::post_message(
+        &governance_chat_test.program_id,
+        &governance_chat_test.governance_program_id,
+        &proposal_cookie.realm_address,
+        &proposal_cookie.governance_address,
+        &proposal_cookie.address,
+        &proposal_cookie.token_owner_record_address,
+        &proposal_cookie.token_owner.pubkey(),
+        None,
+        &message_account.pubkey(),
+        &governance_chat_test.bench.payer.pubkey(),
+        proposal_cookie.voter_weight_record,
+        MessageBody::Text("My comment".to_string()),
+    );
+
+    // ChatAuthorRecord account
+    post_message_ix.accounts[10].pubkey = Pubkey::new_unique();
+
+    // Act
+    let err = governance_chat_test
+        .bench
+        .process_transaction(
+            &[post_message_ix],
+            Some(&[&proposal_cookie.token_owner, &message_account]),
+        )
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(
+        err,
+        GovernanceChatError::InvalidChatAuthorRecordAddress.into()
+    );
+}
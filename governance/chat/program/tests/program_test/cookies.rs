@@ -24,6 +24,7 @@ pub struct ProposalCookie {
 }
 
 #[derive(Debug)]
+#[allow(dead_code)]
 pub struct TokenOwnerRecordCookie {
     pub address: Pubkey,
     pub token_owner: Keypair,
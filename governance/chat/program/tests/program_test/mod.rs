@@ -1,13 +1,13 @@
 use {
     self::cookies::TokenOwnerRecordCookie,
     crate::program_test::cookies::{ChatMessageCookie, ProposalCookie},
-    solana_program::{program_error::ProgramError, pubkey::Pubkey},
+    solana_program::{instruction::Instruction, program_error::ProgramError, pubkey::Pubkey},
     solana_program_test::{processor, ProgramTest},
     solana_sdk::{signature::Keypair, signer::Signer},
     spl_governance::{
         instruction::{
             create_governance, create_proposal, create_realm, create_token_owner_record,
-            deposit_governing_tokens,
+            deposit_governing_tokens, insert_transaction,
         },
         state::{
             enums::{MintMaxVoterWeightSource, VoteThreshold},
@@ -15,6 +15,7 @@ use {
                 get_governance_address, GovernanceConfig, DEFAULT_DEPOSIT_EXEMPT_PROPOSAL_COUNT,
             },
             proposal::{get_proposal_address, VoteType},
+            proposal_transaction::get_proposal_transaction_address,
             realm::{get_realm_address, GoverningTokenConfigAccountArgs},
             realm_config::GoverningTokenType,
             token_owner_record::get_token_owner_record_address,
@@ -22,9 +23,9 @@ use {
     },
     spl_governance_addin_mock::instruction::setup_voter_weight_record,
     spl_governance_chat::{
-        instruction::post_message,
+        instruction::{delete_message, edit_message, post_message},
         processor::process_instruction,
-        state::{ChatMessage, GovernanceChatAccountType, MessageBody},
+        state::{ChatAuthorRecord, ChatMessage, GovernanceChatAccountType, MessageBody},
     },
     spl_governance_test_sdk::{addins::ensure_addin_mock_is_built, ProgramTestBench},
     std::str::FromStr,
@@ -359,9 +360,37 @@ impl GovernanceChatProgramTest {
         &mut self,
         proposal_cookie: &ProposalCookie,
         reply_to: Option<Pubkey>,
+    ) -> Result<ChatMessageCookie, ProgramError> {
+        self.with_chat_message_body(
+            proposal_cookie,
+            reply_to,
+            MessageBody::Text("My comment".to_string()),
+        )
+        .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn with_chat_message_body(
+        &mut self,
+        proposal_cookie: &ProposalCookie,
+        reply_to: Option<Pubkey>,
+        message_body: MessageBody,
+    ) -> Result<ChatMessageCookie, ProgramError> {
+        let payer = self.bench.payer.insecure_clone();
+
+        self.with_chat_message_using_payer(proposal_cookie, reply_to, message_body, &payer)
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn with_chat_message_using_payer(
+        &mut self,
+        proposal_cookie: &ProposalCookie,
+        reply_to: Option<Pubkey>,
+        message_body: MessageBody,
+        payer: &Keypair,
     ) -> Result<ChatMessageCookie, ProgramError> {
         let message_account = Keypair::new();
-        let message_body = MessageBody::Text("My comment".to_string());
 
         let post_message_ix = post_message(
             &self.program_id,
@@ -373,7 +402,7 @@ impl GovernanceChatProgramTest {
             &proposal_cookie.token_owner.pubkey(),
             reply_to,
             &message_account.pubkey(),
-            &self.bench.payer.pubkey(),
+            &payer.pubkey(),
             proposal_cookie.voter_weight_record,
             message_body.clone(),
         );
@@ -381,18 +410,19 @@ impl GovernanceChatProgramTest {
         let clock = self.bench.get_clock().await;
 
         let message = ChatMessage {
-            account_type: GovernanceChatAccountType::ChatMessage,
+            account_type: GovernanceChatAccountType::ChatMessageV2,
             proposal: proposal_cookie.address,
             author: proposal_cookie.token_owner.pubkey(),
             posted_at: clock.unix_timestamp,
             reply_to,
             body: message_body,
+            payer: payer.pubkey(),
         };
 
         self.bench
             .process_transaction(
                 &[post_message_ix],
-                Some(&[&proposal_cookie.token_owner, &message_account]),
+                Some(&[&proposal_cookie.token_owner, &message_account, payer]),
             )
             .await?;
 
@@ -402,6 +432,99 @@ impl GovernanceChatProgramTest {
         })
     }
 
+    #[allow(dead_code)]
+    pub async fn edit_chat_message(
+        &mut self,
+        chat_message_cookie: &mut ChatMessageCookie,
+        author: &Keypair,
+        message_body: MessageBody,
+    ) -> Result<(), ProgramError> {
+        let edit_message_ix = edit_message(
+            &self.program_id,
+            &self.governance_program_id,
+            &chat_message_cookie.account.proposal,
+            &chat_message_cookie.address,
+            &author.pubkey(),
+            &self.bench.payer.pubkey(),
+            message_body.clone(),
+        );
+
+        self.bench
+            .process_transaction(&[edit_message_ix], Some(&[author]))
+            .await?;
+
+        chat_message_cookie.account.body = message_body;
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub async fn delete_chat_message(
+        &mut self,
+        chat_message_cookie: &ChatMessageCookie,
+        author: &Keypair,
+        payer: &Pubkey,
+    ) -> Result<(), ProgramError> {
+        let delete_message_ix = delete_message(
+            &self.program_id,
+            &chat_message_cookie.address,
+            &author.pubkey(),
+            payer,
+        );
+
+        self.bench
+            .process_transaction(&[delete_message_ix], Some(&[author]))
+            .await
+    }
+
+    /// Inserts a placeholder transaction into the Proposal and returns the
+    /// ProposalTransaction address
+    #[allow(dead_code)]
+    pub async fn with_proposal_transaction(&mut self, proposal_cookie: &ProposalCookie) -> Pubkey {
+        let instruction = Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![],
+            data: vec![],
+        };
+
+        let insert_transaction_ix = insert_transaction(
+            &self.governance_program_id,
+            &proposal_cookie.governance_address,
+            &proposal_cookie.address,
+            &proposal_cookie.token_owner_record_address,
+            &proposal_cookie.token_owner.pubkey(),
+            &self.bench.payer.pubkey(),
+            0,
+            0,
+            vec![instruction.into()],
+        );
+
+        self.bench
+            .process_transaction(
+                &[insert_transaction_ix],
+                Some(&[&proposal_cookie.token_owner]),
+            )
+            .await
+            .unwrap();
+
+        get_proposal_transaction_address(
+            &self.governance_program_id,
+            &proposal_cookie.address,
+            &0_u8.to_le_bytes(),
+            &0_u16.to_le_bytes(),
+        )
+    }
+
+    #[allow(dead_code)]
+    pub async fn get_chat_author_record_account(
+        &mut self,
+        chat_author_record_address: &Pubkey,
+    ) -> ChatAuthorRecord {
+        self.bench
+            .get_borsh_account::<ChatAuthorRecord>(chat_author_record_address)
+            .await
+    }
+
     #[allow(dead_code)]
     pub async fn get_message_account(&mut self, message_address: &Pubkey) -> ChatMessage {
         self.bench
@@ -548,6 +548,42 @@ pub enum GovernanceError {
     /// TokenOwnerRecordLockAuthority not found
     #[error("TokenOwnerRecordLockAuthority not found")]
     TokenOwnerRecordLockAuthorityNotFound, // 631
+
+    /// Invalid TreasuryStream config
+    #[error("Invalid TreasuryStream config")]
+    InvalidTreasuryStreamConfig, // 632
+
+    /// Invalid Governance for TreasuryStream
+    #[error("Invalid Governance for TreasuryStream")]
+    InvalidGovernanceForTreasuryStream, // 633
+
+    /// Invalid treasury for TreasuryStream
+    #[error("Invalid treasury for TreasuryStream")]
+    InvalidTreasuryForTreasuryStream, // 634
+
+    /// Invalid recipient for TreasuryStream
+    #[error("Invalid recipient for TreasuryStream")]
+    InvalidRecipientForTreasuryStream, // 635
+
+    /// TreasuryStream has nothing to release
+    #[error("TreasuryStream has nothing to release")]
+    TreasuryStreamHasNothingToRelease, // 636
+
+    /// Instruction is not allowed by Governance InstructionPolicy
+    #[error("Instruction is not allowed by Governance InstructionPolicy")]
+    InstructionNotAllowedByPolicy, // 637
+
+    /// Instruction amount exceeds Governance InstructionPolicy limit
+    #[error("Instruction amount exceeds Governance InstructionPolicy limit")]
+    InstructionAmountExceedsPolicyLimit, // 638
+
+    /// InstructionPolicy account is required by Governance
+    #[error("InstructionPolicy account is required by Governance")]
+    InstructionPolicyAccountRequired, // 639
+
+    /// Invalid Governance for InstructionPolicy
+    #[error("Invalid Governance for InstructionPolicy")]
+    InvalidGovernanceForInstructionPolicy, // 640
 }
 
 impl PrintProgramError for GovernanceError {
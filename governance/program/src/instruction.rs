@@ -4,6 +4,7 @@ use {
     crate::state::{
         enums::MintMaxVoterWeightSource,
         governance::{get_governance_address, GovernanceConfig},
+        instruction_policy::{get_instruction_policy_address, AllowedInstruction},
         native_treasury::get_native_treasury_address,
         program_metadata::get_program_metadata_address,
         proposal::{get_proposal_address, VoteType},
@@ -18,6 +19,7 @@ use {
         required_signatory::get_required_signatory_address,
         signatory_record::get_signatory_record_address,
         token_owner_record::get_token_owner_record_address,
+        treasury_stream::get_treasury_stream_address,
         vote_record::{get_vote_record_address, Vote},
     },
     borsh::{BorshDeserialize, BorshSchema, BorshSerialize},
@@ -252,6 +254,9 @@ pub enum GovernanceInstruction {
     ///   5. `[signer]` Payer
     ///   6. `[]` System program
     ///   7. `[]` Rent sysvar
+    ///   8. `[]` Optional InstructionPolicy account. PDA seeds:
+    ///      ['instruction-policy', governance] Required if the Governance has
+    ///      InstructionPolicy enabled
     InsertTransaction {
         #[allow(dead_code)]
         /// The index of the option the transaction is for
@@ -603,9 +608,9 @@ pub enum GovernanceInstruction {
     /// Sets Realm config item
     /// Note:
     /// This instruction is used to set a single RealmConfig item at a time
-    /// In the current version it only supports TokenOwnerRecordLockAuthority
-    /// however eventually all Realm configuration items should be set using
-    /// this instruction and SetRealmConfig instruction should be deprecated
+    /// without the need to provide the remaining config items
+    /// It supports all Realm configuration items and should be used instead
+    /// of SetRealmConfig instruction which requires the whole config
     ///
     ///   0. `[writable]` Realm account
     ///   1. `[writable]` RealmConfig account
@@ -617,6 +622,104 @@ pub enum GovernanceInstruction {
         /// Config args
         args: SetRealmConfigItemArgs,
     },
+
+    /// Creates TreasuryStream which releases amount_per_period from the
+    /// Governance treasury to the recipient for every full period elapsed
+    /// between start_at and end_at
+    /// The stream allows a single Proposal to authorize recurring payments
+    /// (ex. contributor payroll) which can be released by anyone
+    ///
+    ///   0. `[signer]` The Governance account the stream is for
+    ///   1. `[writable]` TreasuryStream account. PDA seeds: ['treasury-stream',
+    ///      governance, stream_seed]
+    ///   2. `[]` Treasury account the funds are released from
+    ///     * NativeTreasury account for SOL streams
+    ///     * SPL token account owned by the Governance PDA for token streams
+    ///   3. `[signer]` Payer
+    ///   4. `[]` System program
+    CreateTreasuryStream {
+        #[allow(dead_code)]
+        /// Seed used to derive the TreasuryStream address
+        stream_seed: Pubkey,
+
+        #[allow(dead_code)]
+        /// The recipient of the released funds
+        /// It's a wallet for SOL streams and SPL token account for token
+        /// streams
+        recipient: Pubkey,
+
+        #[allow(dead_code)]
+        /// Mint of the streamed tokens or None for SOL streams
+        mint: Option<Pubkey>,
+
+        #[allow(dead_code)]
+        /// Amount released for every full period
+        amount_per_period: u64,
+
+        #[allow(dead_code)]
+        /// Period length in seconds
+        period: u32,
+
+        #[allow(dead_code)]
+        /// The time the stream starts vesting at
+        start_at: UnixTimestamp,
+
+        #[allow(dead_code)]
+        /// The time the stream stops vesting at
+        end_at: UnixTimestamp,
+    },
+
+    /// Releases the vested and not yet released funds of TreasuryStream to
+    /// its recipient
+    /// The instruction is permissionless and can be executed by anyone
+    ///
+    ///   0. `[]` The Governance account the stream belongs to
+    ///   1. `[writable]` TreasuryStream account
+    ///   2. `[writable]` Treasury account the funds are released from
+    ///   3. `[writable]` Recipient account
+    ///   4. `[]` System program for SOL streams or SPL Token program for
+    ///      token streams
+    ReleaseTreasuryStream {},
+
+    /// Cancels TreasuryStream and disposes its account
+    /// Note: Vested but not released funds are not released on cancellation
+    /// If they should be paid out then ReleaseTreasuryStream should be
+    /// executed before within the same Proposal transaction
+    ///
+    ///   0. `[signer]` The Governance account the stream belongs to
+    ///   1. `[writable]` TreasuryStream account
+    ///   2. `[writable]` Beneficiary account which would receive lamports from
+    ///      the disposed TreasuryStream account
+    CancelTreasuryStream {},
+
+    /// Sets InstructionPolicy for the Governance
+    /// Once the policy is set Proposals can only insert instructions allowed
+    /// by the policy. If the policy already exists then it's replaced
+    /// The policy is controlled by the Realm authority so a restricted
+    /// Governance can't change it through its own Proposals
+    ///
+    ///   0. `[]` Realm account the Governance belongs to
+    ///   1. `[writable]` The Governance account the policy is for
+    ///   2. `[writable]` InstructionPolicy account. PDA seeds:
+    ///      ['instruction-policy', governance]
+    ///   3. `[signer]` Realm authority
+    ///   4. `[signer]` Payer
+    ///   5. `[]` System program
+    SetInstructionPolicy {
+        #[allow(dead_code)]
+        /// Instructions Proposals are allowed to insert
+        allowed_instructions: Vec<AllowedInstruction>,
+    },
+
+    /// Removes InstructionPolicy from the Governance and disposes its account
+    ///
+    ///   0. `[]` Realm account the Governance belongs to
+    ///   1. `[writable]` The Governance account the policy is for
+    ///   2. `[writable]` InstructionPolicy account
+    ///   3. `[signer]` Realm authority
+    ///   4. `[writable]` Beneficiary account which would receive lamports from
+    ///      the disposed InstructionPolicy account
+    RemoveInstructionPolicy {},
 }
 
 /// Creates CreateRealm instruction
@@ -1189,6 +1292,11 @@ pub fn insert_transaction(
         AccountMeta::new(*payer, true),
         AccountMeta::new_readonly(system_program::id(), false),
         AccountMeta::new_readonly(sysvar::rent::id(), false),
+        // The account is only used if the Governance has InstructionPolicy enabled
+        AccountMeta::new_readonly(
+            get_instruction_policy_address(program_id, governance),
+            false,
+        ),
     ];
 
     let instruction = GovernanceInstruction::InsertTransaction {
@@ -1764,3 +1872,165 @@ pub fn set_realm_config_item(
         data: borsh::to_vec(&instruction).unwrap(),
     }
 }
+
+/// Creates CreateTreasuryStream instruction
+#[allow(clippy::too_many_arguments)]
+pub fn create_treasury_stream(
+    program_id: &Pubkey,
+    // Accounts
+    governance: &Pubkey,
+    treasury: &Pubkey,
+    payer: &Pubkey,
+    // Args
+    stream_seed: &Pubkey,
+    recipient: &Pubkey,
+    mint: Option<Pubkey>,
+    amount_per_period: u64,
+    period: u32,
+    start_at: UnixTimestamp,
+    end_at: UnixTimestamp,
+) -> Instruction {
+    let treasury_stream_address = get_treasury_stream_address(program_id, governance, stream_seed);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*governance, true),
+        AccountMeta::new(treasury_stream_address, false),
+        AccountMeta::new_readonly(*treasury, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    let instruction = GovernanceInstruction::CreateTreasuryStream {
+        stream_seed: *stream_seed,
+        recipient: *recipient,
+        mint,
+        amount_per_period,
+        period,
+        start_at,
+        end_at,
+    };
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: borsh::to_vec(&instruction).unwrap(),
+    }
+}
+
+/// Creates ReleaseTreasuryStream instruction
+pub fn release_treasury_stream(
+    program_id: &Pubkey,
+    // Accounts
+    governance: &Pubkey,
+    treasury_stream: &Pubkey,
+    treasury: &Pubkey,
+    recipient: &Pubkey,
+    // Args
+    is_token_stream: bool,
+) -> Instruction {
+    let transfer_program_id = if is_token_stream {
+        spl_token::id()
+    } else {
+        system_program::id()
+    };
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*governance, false),
+        AccountMeta::new(*treasury_stream, false),
+        AccountMeta::new(*treasury, false),
+        AccountMeta::new(*recipient, false),
+        AccountMeta::new_readonly(transfer_program_id, false),
+    ];
+
+    let instruction = GovernanceInstruction::ReleaseTreasuryStream {};
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: borsh::to_vec(&instruction).unwrap(),
+    }
+}
+
+/// Creates CancelTreasuryStream instruction
+pub fn cancel_treasury_stream(
+    program_id: &Pubkey,
+    // Accounts
+    governance: &Pubkey,
+    treasury_stream: &Pubkey,
+    beneficiary: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*governance, true),
+        AccountMeta::new(*treasury_stream, false),
+        AccountMeta::new(*beneficiary, false),
+    ];
+
+    let instruction = GovernanceInstruction::CancelTreasuryStream {};
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: borsh::to_vec(&instruction).unwrap(),
+    }
+}
+
+/// Creates SetInstructionPolicy instruction
+pub fn set_instruction_policy(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governance: &Pubkey,
+    realm_authority: &Pubkey,
+    payer: &Pubkey,
+    // Args
+    allowed_instructions: Vec<AllowedInstruction>,
+) -> Instruction {
+    let instruction_policy_address = get_instruction_policy_address(program_id, governance);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*realm, false),
+        AccountMeta::new(*governance, false),
+        AccountMeta::new(instruction_policy_address, false),
+        AccountMeta::new_readonly(*realm_authority, true),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    let instruction = GovernanceInstruction::SetInstructionPolicy {
+        allowed_instructions,
+    };
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: borsh::to_vec(&instruction).unwrap(),
+    }
+}
+
+/// Creates RemoveInstructionPolicy instruction
+pub fn remove_instruction_policy(
+    program_id: &Pubkey,
+    // Accounts
+    realm: &Pubkey,
+    governance: &Pubkey,
+    realm_authority: &Pubkey,
+    beneficiary: &Pubkey,
+) -> Instruction {
+    let instruction_policy_address = get_instruction_policy_address(program_id, governance);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*realm, false),
+        AccountMeta::new(*governance, false),
+        AccountMeta::new(instruction_policy_address, false),
+        AccountMeta::new_readonly(*realm_authority, true),
+        AccountMeta::new(*beneficiary, false),
+    ];
+
+    let instruction = GovernanceInstruction::RemoveInstructionPolicy {};
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: borsh::to_vec(&instruction).unwrap(),
+    }
+}
@@ -3,6 +3,7 @@
 mod process_add_required_signatory;
 mod process_add_signatory;
 mod process_cancel_proposal;
+mod process_cancel_treasury_stream;
 mod process_cast_vote;
 mod process_complete_proposal;
 mod process_create_governance;
@@ -12,19 +13,23 @@ mod process_create_proposal;
 mod process_create_realm;
 
 mod process_create_token_owner_record;
+mod process_create_treasury_stream;
 mod process_deposit_governing_tokens;
 mod process_execute_transaction;
 mod process_finalize_vote;
 
 mod process_insert_transaction;
 mod process_refund_proposal_deposit;
+mod process_release_treasury_stream;
 mod process_relinquish_token_owner_record_locks;
 mod process_relinquish_vote;
+mod process_remove_instruction_policy;
 mod process_remove_required_signatory;
 mod process_remove_transaction;
 mod process_revoke_governing_tokens;
 mod process_set_governance_config;
 mod process_set_governance_delegate;
+mod process_set_instruction_policy;
 mod process_set_realm_authority;
 mod process_set_realm_config;
 mod process_set_realm_config_item;
@@ -38,6 +43,7 @@ use {
     process_add_required_signatory::*,
     process_add_signatory::*,
     process_cancel_proposal::*,
+    process_cancel_treasury_stream::*,
     process_cast_vote::*,
     process_complete_proposal::*,
     process_create_governance::*,
@@ -45,18 +51,22 @@ use {
     process_create_proposal::*,
     process_create_realm::*,
     process_create_token_owner_record::*,
+    process_create_treasury_stream::*,
     process_deposit_governing_tokens::*,
     process_execute_transaction::*,
     process_finalize_vote::*,
     process_insert_transaction::*,
     process_refund_proposal_deposit::*,
+    process_release_treasury_stream::*,
     process_relinquish_token_owner_record_locks::*,
     process_relinquish_vote::*,
+    process_remove_instruction_policy::*,
     process_remove_required_signatory::*,
     process_remove_transaction::*,
     process_revoke_governing_tokens::*,
     process_set_governance_config::*,
     process_set_governance_delegate::*,
+    process_set_instruction_policy::*,
     process_set_realm_authority::*,
     process_set_realm_config::*,
     process_set_realm_config_item::*,
@@ -222,5 +232,41 @@ pub fn process_instruction(
         GovernanceInstruction::SetRealmConfigItem { args } => {
             process_set_realm_config_item(program_id, accounts, args)
         }
+
+        GovernanceInstruction::CreateTreasuryStream {
+            stream_seed,
+            recipient,
+            mint,
+            amount_per_period,
+            period,
+            start_at,
+            end_at,
+        } => process_create_treasury_stream(
+            program_id,
+            accounts,
+            stream_seed,
+            recipient,
+            mint,
+            amount_per_period,
+            period,
+            start_at,
+            end_at,
+        ),
+
+        GovernanceInstruction::ReleaseTreasuryStream {} => {
+            process_release_treasury_stream(program_id, accounts)
+        }
+
+        GovernanceInstruction::CancelTreasuryStream {} => {
+            process_cancel_treasury_stream(program_id, accounts)
+        }
+
+        GovernanceInstruction::SetInstructionPolicy {
+            allowed_instructions,
+        } => process_set_instruction_policy(program_id, accounts, allowed_instructions),
+
+        GovernanceInstruction::RemoveInstructionPolicy {} => {
+            process_remove_instruction_policy(program_id, accounts)
+        }
     }
 }
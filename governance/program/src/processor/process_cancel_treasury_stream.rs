@@ -0,0 +1,36 @@
+//! Program state processor
+
+use {
+    crate::{
+        error::GovernanceError, state::treasury_stream::get_treasury_stream_data_for_governance,
+    },
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+    },
+    spl_governance_tools::account::dispose_account,
+};
+
+/// Processes CancelTreasuryStream instruction
+pub fn process_cancel_treasury_stream(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let governance_info = next_account_info(account_info_iter)?; // 0
+    let treasury_stream_info = next_account_info(account_info_iter)?; // 1
+    let beneficiary_info = next_account_info(account_info_iter)?; // 2
+
+    // Only governance PDA via a proposal can cancel the stream
+    if !governance_info.is_signer {
+        return Err(GovernanceError::GovernancePdaMustSign.into());
+    };
+
+    get_treasury_stream_data_for_governance(program_id, treasury_stream_info, governance_info.key)?;
+
+    dispose_account(treasury_stream_info, beneficiary_info)?;
+
+    Ok(())
+}
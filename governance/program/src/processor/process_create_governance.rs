@@ -10,7 +10,7 @@ use {
             },
             realm::get_realm_data,
         },
-        tools::structs::Reserved119,
+        tools::structs::Reserved118,
     },
     solana_program::{
         account_info::{next_account_info, AccountInfo},
@@ -61,7 +61,8 @@ pub fn process_create_governance(
         governance_seed: *governance_seed_info.key,
         config,
         reserved1: 0,
-        reserved_v2: Reserved119::default(),
+        reserved_v2: Reserved118::default(),
+        instruction_policy_enabled: false,
         required_signatories_count: 0,
         active_proposal_count: 0,
     };
@@ -0,0 +1,99 @@
+//! Program state processor
+
+use {
+    crate::{
+        error::GovernanceError,
+        state::{
+            enums::GovernanceAccountType,
+            governance::assert_is_valid_governance,
+            native_treasury::get_native_treasury_address,
+            treasury_stream::{get_treasury_stream_address_seeds, TreasuryStream},
+        },
+        tools::spl_token::{
+            assert_is_valid_spl_token_account, get_spl_token_mint, get_spl_token_owner,
+        },
+    },
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        clock::UnixTimestamp,
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+        rent::Rent,
+        sysvar::Sysvar,
+    },
+    spl_governance_tools::account::create_and_serialize_account_signed,
+};
+
+/// Processes CreateTreasuryStream instruction
+#[allow(clippy::too_many_arguments)]
+pub fn process_create_treasury_stream(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    stream_seed: Pubkey,
+    recipient: Pubkey,
+    mint: Option<Pubkey>,
+    amount_per_period: u64,
+    period: u32,
+    start_at: UnixTimestamp,
+    end_at: UnixTimestamp,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let governance_info = next_account_info(account_info_iter)?; // 0
+    let treasury_stream_info = next_account_info(account_info_iter)?; // 1
+    let treasury_info = next_account_info(account_info_iter)?; // 2
+    let payer_info = next_account_info(account_info_iter)?; // 3
+    let system_info = next_account_info(account_info_iter)?; // 4
+
+    let rent = Rent::get()?;
+
+    // Only governance PDA via a proposal can authorize spending from its treasury
+    if !governance_info.is_signer {
+        return Err(GovernanceError::GovernancePdaMustSign.into());
+    };
+
+    assert_is_valid_governance(program_id, governance_info)?;
+
+    if let Some(mint) = mint {
+        // Token streams are released from token accounts owned by the governance PDA
+        assert_is_valid_spl_token_account(treasury_info)?;
+
+        if get_spl_token_owner(treasury_info)? != *governance_info.key
+            || get_spl_token_mint(treasury_info)? != mint
+        {
+            return Err(GovernanceError::InvalidTreasuryForTreasuryStream.into());
+        }
+    } else if *treasury_info.key != get_native_treasury_address(program_id, governance_info.key) {
+        // SOL streams are released from the governance NativeTreasury
+        return Err(GovernanceError::InvalidTreasuryForTreasuryStream.into());
+    }
+
+    let treasury_stream_data = TreasuryStream {
+        account_type: GovernanceAccountType::TreasuryStream,
+        governance: *governance_info.key,
+        treasury: *treasury_info.key,
+        mint,
+        recipient,
+        amount_per_period,
+        period,
+        start_at,
+        end_at,
+        released_amount: 0,
+        reserved: [0; 32],
+    };
+
+    treasury_stream_data.assert_is_valid_config()?;
+
+    create_and_serialize_account_signed::<TreasuryStream>(
+        payer_info,
+        treasury_stream_info,
+        &treasury_stream_data,
+        &get_treasury_stream_address_seeds(governance_info.key, &stream_seed),
+        program_id,
+        system_info,
+        &rent,
+        0,
+    )?;
+
+    Ok(())
+}
@@ -6,6 +6,7 @@ use {
         state::{
             enums::{GovernanceAccountType, TransactionExecutionStatus},
             governance::get_governance_data,
+            instruction_policy::get_instruction_policy_data_for_governance,
             proposal::get_proposal_data_for_governance,
             proposal_transaction::{
                 get_proposal_transaction_address_seeds, InstructionData, ProposalTransactionV2,
@@ -51,9 +52,22 @@ pub fn process_insert_transaction(
         return Err(GovernanceError::TransactionAlreadyExists.into());
     }
 
-    // Governance account is no longer used and it's deserialized only to validate
-    // the provided account
-    let _governance_data = get_governance_data(program_id, governance_info)?;
+    let governance_data = get_governance_data(program_id, governance_info)?;
+
+    if governance_data.instruction_policy_enabled {
+        let instruction_policy_info = next_account_info(account_info_iter)
+            .map_err(|_| GovernanceError::InstructionPolicyAccountRequired)?; // 8
+
+        let instruction_policy_data = get_instruction_policy_data_for_governance(
+            program_id,
+            instruction_policy_info,
+            governance_info.key,
+        )?;
+
+        for instruction in &instructions {
+            instruction_policy_data.assert_instruction_is_allowed(instruction)?;
+        }
+    }
 
     let mut proposal_data =
         get_proposal_data_for_governance(program_id, proposal_info, governance_info.key)?;
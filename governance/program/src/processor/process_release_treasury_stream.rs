@@ -0,0 +1,98 @@
+//! Program state processor
+
+use {
+    crate::{
+        error::GovernanceError,
+        state::{
+            governance::get_governance_data, native_treasury::get_native_treasury_address_seeds,
+            treasury_stream::get_treasury_stream_data_for_governance,
+        },
+        tools::spl_token::transfer_spl_tokens_signed,
+    },
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        clock::Clock,
+        entrypoint::ProgramResult,
+        program::invoke_signed,
+        pubkey::Pubkey,
+        system_instruction,
+        sysvar::Sysvar,
+    },
+};
+
+/// Processes ReleaseTreasuryStream instruction
+pub fn process_release_treasury_stream(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let governance_info = next_account_info(account_info_iter)?; // 0
+    let treasury_stream_info = next_account_info(account_info_iter)?; // 1
+    let treasury_info = next_account_info(account_info_iter)?; // 2
+    let recipient_info = next_account_info(account_info_iter)?; // 3
+    let transfer_program_info = next_account_info(account_info_iter)?; // 4
+
+    let clock = Clock::get()?;
+
+    let governance_data = get_governance_data(program_id, governance_info)?;
+
+    let mut treasury_stream_data = get_treasury_stream_data_for_governance(
+        program_id,
+        treasury_stream_info,
+        governance_info.key,
+    )?;
+
+    if treasury_stream_data.treasury != *treasury_info.key {
+        return Err(GovernanceError::InvalidTreasuryForTreasuryStream.into());
+    }
+
+    if treasury_stream_data.recipient != *recipient_info.key {
+        return Err(GovernanceError::InvalidRecipientForTreasuryStream.into());
+    }
+
+    let amount = treasury_stream_data.get_releasable_amount(clock.unix_timestamp);
+
+    if amount == 0 {
+        return Err(GovernanceError::TreasuryStreamHasNothingToRelease.into());
+    }
+
+    if treasury_stream_data.mint.is_some() {
+        transfer_spl_tokens_signed(
+            treasury_info,
+            recipient_info,
+            governance_info,
+            &governance_data.get_governance_address_seeds()?,
+            program_id,
+            amount,
+            transfer_program_info,
+        )?;
+    } else {
+        let mut treasury_seeds = get_native_treasury_address_seeds(governance_info.key).to_vec();
+        let (_, treasury_bump_seed) = Pubkey::find_program_address(&treasury_seeds, program_id);
+        let treasury_bump = &[treasury_bump_seed];
+        treasury_seeds.push(treasury_bump);
+
+        invoke_signed(
+            &system_instruction::transfer(treasury_info.key, recipient_info.key, amount),
+            &[
+                treasury_info.clone(),
+                recipient_info.clone(),
+                transfer_program_info.clone(),
+            ],
+            &[&treasury_seeds[..]],
+        )?;
+    }
+
+    treasury_stream_data.released_amount = treasury_stream_data
+        .released_amount
+        .checked_add(amount)
+        .unwrap();
+
+    borsh::to_writer(
+        &mut treasury_stream_info.data.borrow_mut()[..],
+        &treasury_stream_data,
+    )?;
+
+    Ok(())
+}
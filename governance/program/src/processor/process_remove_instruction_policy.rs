@@ -0,0 +1,54 @@
+//! Program state processor
+
+use {
+    crate::{
+        error::GovernanceError,
+        state::{
+            governance::get_governance_data_for_realm,
+            instruction_policy::get_instruction_policy_data_for_governance,
+            realm::get_realm_data_for_authority,
+        },
+    },
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+    },
+    spl_governance_tools::account::dispose_account,
+};
+
+/// Processes RemoveInstructionPolicy instruction
+pub fn process_remove_instruction_policy(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let realm_info = next_account_info(account_info_iter)?; // 0
+    let governance_info = next_account_info(account_info_iter)?; // 1
+    let instruction_policy_info = next_account_info(account_info_iter)?; // 2
+    let realm_authority_info = next_account_info(account_info_iter)?; // 3
+    let beneficiary_info = next_account_info(account_info_iter)?; // 4
+
+    get_realm_data_for_authority(program_id, realm_info, realm_authority_info.key)?;
+
+    if !realm_authority_info.is_signer {
+        return Err(GovernanceError::RealmAuthorityMustSign.into());
+    }
+
+    let mut governance_data =
+        get_governance_data_for_realm(program_id, governance_info, realm_info.key)?;
+
+    get_instruction_policy_data_for_governance(
+        program_id,
+        instruction_policy_info,
+        governance_info.key,
+    )?;
+
+    governance_data.instruction_policy_enabled = false;
+    governance_data.serialize(&mut governance_info.data.borrow_mut()[..])?;
+
+    dispose_account(instruction_policy_info, beneficiary_info)?;
+
+    Ok(())
+}
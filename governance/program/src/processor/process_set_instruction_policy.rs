@@ -0,0 +1,105 @@
+//! Program state processor
+
+use {
+    crate::{
+        error::GovernanceError,
+        state::{
+            enums::GovernanceAccountType,
+            governance::get_governance_data_for_realm,
+            instruction_policy::{
+                get_instruction_policy_address_seeds, get_instruction_policy_data_for_governance,
+                AllowedInstruction, InstructionPolicy,
+            },
+            realm::get_realm_data_for_authority,
+        },
+    },
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+        rent::Rent,
+        sysvar::Sysvar,
+    },
+    spl_governance_tools::account::{create_and_serialize_account_signed, extend_account_size},
+};
+
+/// Processes SetInstructionPolicy instruction
+pub fn process_set_instruction_policy(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    allowed_instructions: Vec<AllowedInstruction>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let realm_info = next_account_info(account_info_iter)?; // 0
+    let governance_info = next_account_info(account_info_iter)?; // 1
+    let instruction_policy_info = next_account_info(account_info_iter)?; // 2
+    let realm_authority_info = next_account_info(account_info_iter)?; // 3
+
+    let payer_info = next_account_info(account_info_iter)?; // 4
+    let system_info = next_account_info(account_info_iter)?; // 5
+
+    let rent = Rent::get()?;
+
+    // The policy is controlled by the Realm and not by the Governance itself
+    // otherwise a Governance could lift its own restrictions
+    get_realm_data_for_authority(program_id, realm_info, realm_authority_info.key)?;
+
+    if !realm_authority_info.is_signer {
+        return Err(GovernanceError::RealmAuthorityMustSign.into());
+    }
+
+    let mut governance_data =
+        get_governance_data_for_realm(program_id, governance_info, realm_info.key)?;
+
+    if instruction_policy_info.data_is_empty() {
+        let instruction_policy_data = InstructionPolicy {
+            account_type: GovernanceAccountType::InstructionPolicy,
+            governance: *governance_info.key,
+            allowed_instructions,
+            reserved: [0; 32],
+        };
+
+        create_and_serialize_account_signed::<InstructionPolicy>(
+            payer_info,
+            instruction_policy_info,
+            &instruction_policy_data,
+            &get_instruction_policy_address_seeds(governance_info.key),
+            program_id,
+            system_info,
+            &rent,
+            0,
+        )?;
+    } else {
+        let mut instruction_policy_data = get_instruction_policy_data_for_governance(
+            program_id,
+            instruction_policy_info,
+            governance_info.key,
+        )?;
+
+        instruction_policy_data.allowed_instructions = allowed_instructions;
+
+        let instruction_policy_size = borsh::object_length(&instruction_policy_data)?;
+        if instruction_policy_info.data_len() < instruction_policy_size {
+            extend_account_size(
+                instruction_policy_info,
+                payer_info,
+                instruction_policy_size,
+                &rent,
+                system_info,
+            )?;
+        }
+
+        borsh::to_writer(
+            &mut instruction_policy_info.data.borrow_mut()[..],
+            &instruction_policy_data,
+        )?;
+    }
+
+    // The policy can only be enforced using GovernanceV2 account layout and
+    // legacy GovernanceV1 account is migrated here if needed
+    governance_data.instruction_policy_enabled = true;
+    governance_data.serialize_as_governance_v2(governance_info, payer_info, system_info, &rent)?;
+
+    Ok(())
+}
@@ -4,7 +4,10 @@ use {
     crate::{
         error::GovernanceError,
         state::{
-            realm::{get_realm_data_for_authority, SetRealmConfigItemArgs},
+            realm::{
+                assert_valid_mint_max_voter_weight_source, get_realm_data_for_authority,
+                SetRealmConfigItemArgs,
+            },
             realm_config::get_realm_config_data_for_realm,
         },
         tools::structs::SetConfigItemActionType,
@@ -34,7 +37,7 @@ pub fn process_set_realm_config_item(
 
     let rent = Rent::get()?;
 
-    let realm_data =
+    let mut realm_data =
         get_realm_data_for_authority(program_id, realm_info, realm_authority_info.key)?;
 
     if !realm_authority_info.is_signer {
@@ -76,6 +79,60 @@ pub fn process_set_realm_config_item(
                 }
             }
         }
+        SetRealmConfigItemArgs::CouncilMint { council_mint } => {
+            // Council mint can only be removed and changing it to other mint or restoring
+            // it is not supported. See SetRealmConfig for details
+            if council_mint.is_some() && council_mint != realm_data.config.council_mint {
+                return Err(GovernanceError::RealmCouncilMintChangeIsNotSupported.into());
+            }
+
+            realm_data.config.council_mint = council_mint;
+        }
+        SetRealmConfigItemArgs::VoterWeightAddin {
+            governing_token_mint,
+            voter_weight_addin,
+        } => {
+            let token_config =
+                realm_config_data.get_token_config_mut(&realm_data, &governing_token_mint)?;
+
+            token_config.voter_weight_addin = voter_weight_addin;
+        }
+        SetRealmConfigItemArgs::MaxVoterWeightAddin {
+            governing_token_mint,
+            max_voter_weight_addin,
+        } => {
+            let token_config =
+                realm_config_data.get_token_config_mut(&realm_data, &governing_token_mint)?;
+
+            token_config.max_voter_weight_addin = max_voter_weight_addin;
+        }
+        SetRealmConfigItemArgs::CommunityMintMaxVoterWeightSource {
+            community_mint_max_voter_weight_source,
+        } => {
+            assert_valid_mint_max_voter_weight_source(&community_mint_max_voter_weight_source)?;
+
+            realm_data.config.community_mint_max_voter_weight_source =
+                community_mint_max_voter_weight_source;
+        }
+        SetRealmConfigItemArgs::GoverningTokenType {
+            governing_token_mint,
+            token_type,
+        } => {
+            if governing_token_mint == realm_data.community_mint {
+                realm_config_data.assert_can_change_community_token_type(&token_type)?;
+            }
+
+            let token_config =
+                realm_config_data.get_token_config_mut(&realm_data, &governing_token_mint)?;
+
+            token_config.token_type = token_type;
+        }
+        SetRealmConfigItemArgs::MinCommunityWeightToCreateGovernance {
+            min_community_weight_to_create_governance,
+        } => {
+            realm_data.config.min_community_weight_to_create_governance =
+                min_community_weight_to_create_governance;
+        }
     }
 
     realm_config_data.serialize(
@@ -86,5 +143,7 @@ pub fn process_set_realm_config_item(
         &rent,
     )?;
 
+    realm_data.serialize(&mut realm_info.data.borrow_mut()[..])?;
+
     Ok(())
 }
@@ -102,6 +102,14 @@ pub enum GovernanceAccountType {
 
     /// Required signatory account
     RequiredSignatory,
+
+    /// Treasury stream account which releases funds from the treasury
+    /// to the recipient over time
+    TreasuryStream,
+
+    /// Instruction policy account which restricts the instructions Proposals
+    /// can insert for the Governance
+    InstructionPolicy,
 }
 
 /// What state a Proposal is in
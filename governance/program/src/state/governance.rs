@@ -8,7 +8,7 @@ use {
             realm::{assert_is_valid_realm, RealmV2},
             vote_record::VoteKind,
         },
-        tools::structs::Reserved119,
+        tools::structs::Reserved118,
     },
     borsh::{io::Write, BorshDeserialize, BorshSchema, BorshSerialize},
     solana_program::{
@@ -110,7 +110,11 @@ pub struct GovernanceV2 {
     /// Note 1: V1 accounts must be resized before using this space
     /// Note 2: The reserved space should be used from the end to also allow the
     /// config to grow if needed
-    pub reserved_v2: Reserved119,
+    pub reserved_v2: Reserved118,
+
+    /// Indicates whether the Governance has InstructionPolicy account which
+    /// restricts the instructions Proposals can insert
+    pub instruction_policy_enabled: bool,
 
     /// The number of required signatories for proposals in the Governance
     pub required_signatories_count: u8,
@@ -159,7 +163,9 @@ pub fn is_governance_v2_account_type(account_type: &GovernanceAccountType) -> bo
         | GovernanceAccountType::VoteRecordV2
         | GovernanceAccountType::ProgramMetadata
         | GovernanceAccountType::ProposalDeposit
-        | GovernanceAccountType::RequiredSignatory => false,
+        | GovernanceAccountType::RequiredSignatory
+        | GovernanceAccountType::TreasuryStream
+        | GovernanceAccountType::InstructionPolicy => false,
     }
 }
 
@@ -195,7 +201,9 @@ pub fn try_get_governance_v2_type_for_v1(
         | GovernanceAccountType::VoteRecordV2
         | GovernanceAccountType::ProgramMetadata
         | GovernanceAccountType::ProposalDeposit
-        | GovernanceAccountType::RequiredSignatory => None,
+        | GovernanceAccountType::RequiredSignatory
+        | GovernanceAccountType::TreasuryStream
+        | GovernanceAccountType::InstructionPolicy => None,
     }
 }
 
@@ -244,7 +252,9 @@ impl GovernanceV2 {
             | GovernanceAccountType::RealmV2
             | GovernanceAccountType::TokenOwnerRecordV2
             | GovernanceAccountType::SignatoryRecordV2
-            | GovernanceAccountType::RequiredSignatory => {
+            | GovernanceAccountType::RequiredSignatory
+            | GovernanceAccountType::TreasuryStream
+            | GovernanceAccountType::InstructionPolicy => {
                 return Err(GovernanceToolsError::InvalidAccountType.into())
             }
         };
@@ -262,7 +272,7 @@ impl GovernanceV2 {
 
             // If reserved_v2 is used it must be individually assessed for GovernanceV1
             // account backward compatibility impact
-            if self.reserved_v2 != Reserved119::default() {
+            if self.reserved_v2 != Reserved118::default() || self.instruction_policy_enabled {
                 panic!("Extended data not supported by GovernanceV1")
             }
 
@@ -417,7 +427,8 @@ pub fn get_governance_data(
             governance_seed: governance_data_v1.governance_seed,
             reserved1: 0,
             config: governance_data_v1.config,
-            reserved_v2: Reserved119::default(),
+            reserved_v2: Reserved118::default(),
+            instruction_policy_enabled: false,
             required_signatories_count: 0,
             // GovernanceV1 layout doesn't support active_proposal_count
             // For any legacy GovernanceV1 account it's not preserved until the account layout is
@@ -699,7 +710,8 @@ mod test {
             governance_seed: Pubkey::new_unique(),
             reserved1: 0,
             config: create_test_governance_config(),
-            reserved_v2: Reserved119::default(),
+            reserved_v2: Reserved118::default(),
+            instruction_policy_enabled: false,
             active_proposal_count: 10,
             required_signatories_count: 0,
         }
@@ -0,0 +1,299 @@
+//! InstructionPolicy account
+
+use {
+    crate::{
+        error::GovernanceError,
+        state::{enums::GovernanceAccountType, proposal_transaction::InstructionData},
+    },
+    borsh::{BorshDeserialize, BorshSchema, BorshSerialize},
+    solana_program::{
+        account_info::AccountInfo, program_error::ProgramError, program_pack::IsInitialized,
+        pubkey::Pubkey,
+    },
+    spl_governance_tools::account::{get_account_data, AccountMaxSize},
+};
+
+/// Limit on an amount encoded in the instruction data
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub struct InstructionAmountLimit {
+    /// Offset of the amount (little-endian u64) in the instruction data
+    /// For example the amount of spl-token Transfer and TransferChecked
+    /// instructions is encoded at offset 1
+    pub offset: u16,
+
+    /// The max amount the instruction can use
+    pub max_amount: u64,
+}
+
+/// Instruction allowed by InstructionPolicy
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub struct AllowedInstruction {
+    /// The program the instruction must be executed by
+    pub program_id: Pubkey,
+
+    /// The prefix the instruction data must start with (the instruction
+    /// discriminator) Empty discriminator allows all instructions of the
+    /// program
+    pub discriminator: Vec<u8>,
+
+    /// Optional limit on the amount the instruction can use
+    pub amount_limit: Option<InstructionAmountLimit>,
+}
+
+impl AllowedInstruction {
+    /// Checks if the given instruction matches the program and discriminator
+    fn matches(&self, instruction: &InstructionData) -> bool {
+        instruction.program_id == self.program_id
+            && instruction.data.starts_with(&self.discriminator)
+    }
+
+    /// Checks if the amount used by the given instruction is within the limit
+    /// Note: If the amount can't be read from the instruction data then the
+    /// instruction is considered to exceed the limit
+    fn is_within_amount_limit(&self, instruction: &InstructionData) -> bool {
+        match &self.amount_limit {
+            None => true,
+            Some(amount_limit) => {
+                let offset = amount_limit.offset as usize;
+
+                instruction
+                    .data
+                    .get(offset..offset.saturating_add(8))
+                    .and_then(|amount| amount.try_into().ok())
+                    .map(u64::from_le_bytes)
+                    .is_some_and(|amount| amount <= amount_limit.max_amount)
+            }
+        }
+    }
+}
+
+/// Instruction policy which restricts the instructions Proposals can insert
+/// for the Governance
+///
+/// Note: The policy is enforced when transactions are inserted into Proposals
+/// and it doesn't apply to transactions inserted before the policy was set
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub struct InstructionPolicy {
+    /// Account type
+    pub account_type: GovernanceAccountType,
+
+    /// Governance the policy belongs to
+    pub governance: Pubkey,
+
+    /// Instructions Proposals are allowed to insert
+    /// Note: Empty list prevents Proposals from inserting any instructions
+    pub allowed_instructions: Vec<AllowedInstruction>,
+
+    /// Reserved space for future versions
+    pub reserved: [u8; 32],
+}
+
+impl AccountMaxSize for InstructionPolicy {}
+
+impl IsInitialized for InstructionPolicy {
+    fn is_initialized(&self) -> bool {
+        self.account_type == GovernanceAccountType::InstructionPolicy
+    }
+}
+
+impl InstructionPolicy {
+    /// Asserts the given instruction is allowed by the policy
+    pub fn assert_instruction_is_allowed(
+        &self,
+        instruction: &InstructionData,
+    ) -> Result<(), ProgramError> {
+        let mut matching_instructions = self
+            .allowed_instructions
+            .iter()
+            .filter(|ai| ai.matches(instruction))
+            .peekable();
+
+        if matching_instructions.peek().is_none() {
+            return Err(GovernanceError::InstructionNotAllowedByPolicy.into());
+        }
+
+        if !matching_instructions.any(|ai| ai.is_within_amount_limit(instruction)) {
+            return Err(GovernanceError::InstructionAmountExceedsPolicyLimit.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Deserializes InstructionPolicy account, checks the owner program, and
+/// asserts the policy belongs to the given governance
+pub fn get_instruction_policy_data_for_governance(
+    program_id: &Pubkey,
+    instruction_policy_info: &AccountInfo,
+    governance: &Pubkey,
+) -> Result<InstructionPolicy, ProgramError> {
+    let instruction_policy_data =
+        get_account_data::<InstructionPolicy>(program_id, instruction_policy_info)?;
+
+    if instruction_policy_data.governance != *governance {
+        return Err(GovernanceError::InvalidGovernanceForInstructionPolicy.into());
+    }
+
+    Ok(instruction_policy_data)
+}
+
+/// Returns InstructionPolicy PDA seeds
+pub fn get_instruction_policy_address_seeds(governance: &Pubkey) -> [&[u8]; 2] {
+    [b"instruction-policy".as_ref(), governance.as_ref()]
+}
+
+/// Returns InstructionPolicy PDA address
+pub fn get_instruction_policy_address(program_id: &Pubkey, governance: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &get_instruction_policy_address_seeds(governance),
+        program_id,
+    )
+    .0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn create_test_instruction_policy() -> InstructionPolicy {
+        InstructionPolicy {
+            account_type: GovernanceAccountType::InstructionPolicy,
+            governance: Pubkey::new_unique(),
+            allowed_instructions: vec![
+                AllowedInstruction {
+                    program_id: spl_token::id(),
+                    discriminator: vec![3],
+                    amount_limit: Some(InstructionAmountLimit {
+                        offset: 1,
+                        max_amount: 100,
+                    }),
+                },
+                AllowedInstruction {
+                    program_id: spl_token::id(),
+                    discriminator: vec![12],
+                    amount_limit: Some(InstructionAmountLimit {
+                        offset: 1,
+                        max_amount: 100,
+                    }),
+                },
+            ],
+            reserved: [0; 32],
+        }
+    }
+
+    fn create_test_instruction(program_id: Pubkey, tag: u8, amount: u64) -> InstructionData {
+        let mut data = vec![tag];
+        data.extend_from_slice(&amount.to_le_bytes());
+
+        InstructionData {
+            program_id,
+            accounts: vec![],
+            data,
+        }
+    }
+
+    #[test]
+    fn test_assert_instruction_is_allowed() {
+        let instruction_policy = create_test_instruction_policy();
+
+        instruction_policy
+            .assert_instruction_is_allowed(&create_test_instruction(spl_token::id(), 3, 100))
+            .unwrap();
+
+        instruction_policy
+            .assert_instruction_is_allowed(&create_test_instruction(spl_token::id(), 12, 1))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_assert_instruction_is_allowed_with_any_instruction_of_program() {
+        let mut instruction_policy = create_test_instruction_policy();
+        let program_id = Pubkey::new_unique();
+
+        instruction_policy
+            .allowed_instructions
+            .push(AllowedInstruction {
+                program_id,
+                discriminator: vec![],
+                amount_limit: None,
+            });
+
+        instruction_policy
+            .assert_instruction_is_allowed(&create_test_instruction(program_id, 7, u64::MAX))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_assert_instruction_is_allowed_with_not_allowed_program_error() {
+        let instruction_policy = create_test_instruction_policy();
+
+        let err = instruction_policy
+            .assert_instruction_is_allowed(&create_test_instruction(Pubkey::new_unique(), 3, 1))
+            .err()
+            .unwrap();
+
+        assert_eq!(err, GovernanceError::InstructionNotAllowedByPolicy.into());
+    }
+
+    #[test]
+    fn test_assert_instruction_is_allowed_with_not_allowed_discriminator_error() {
+        let instruction_policy = create_test_instruction_policy();
+
+        // spl-token MintTo
+        let err = instruction_policy
+            .assert_instruction_is_allowed(&create_test_instruction(spl_token::id(), 7, 1))
+            .err()
+            .unwrap();
+
+        assert_eq!(err, GovernanceError::InstructionNotAllowedByPolicy.into());
+    }
+
+    #[test]
+    fn test_assert_instruction_is_allowed_with_amount_exceeding_limit_error() {
+        let instruction_policy = create_test_instruction_policy();
+
+        let err = instruction_policy
+            .assert_instruction_is_allowed(&create_test_instruction(spl_token::id(), 3, 101))
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            err,
+            GovernanceError::InstructionAmountExceedsPolicyLimit.into()
+        );
+    }
+
+    #[test]
+    fn test_assert_instruction_is_allowed_with_missing_amount_error() {
+        let instruction_policy = create_test_instruction_policy();
+
+        let instruction = InstructionData {
+            program_id: spl_token::id(),
+            accounts: vec![],
+            data: vec![3, 1],
+        };
+
+        let err = instruction_policy
+            .assert_instruction_is_allowed(&instruction)
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            err,
+            GovernanceError::InstructionAmountExceedsPolicyLimit.into()
+        );
+    }
+
+    #[test]
+    fn test_assert_instruction_is_allowed_with_empty_policy_error() {
+        let mut instruction_policy = create_test_instruction_policy();
+        instruction_policy.allowed_instructions = vec![];
+
+        let err = instruction_policy
+            .assert_instruction_is_allowed(&create_test_instruction(spl_token::id(), 3, 1))
+            .err()
+            .unwrap();
+
+        assert_eq!(err, GovernanceError::InstructionNotAllowedByPolicy.into());
+    }
+}
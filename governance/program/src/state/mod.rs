@@ -2,6 +2,7 @@
 
 pub mod enums;
 pub mod governance;
+pub mod instruction_policy;
 pub mod legacy;
 pub mod native_treasury;
 pub mod program_metadata;
@@ -13,4 +14,5 @@ pub mod realm_config;
 pub mod required_signatory;
 pub mod signatory_record;
 pub mod token_owner_record;
+pub mod treasury_stream;
 pub mod vote_record;
@@ -28,9 +28,9 @@ use {
 };
 
 /// SetRealmConfigItem instruction arguments to set a single Realm config item
-/// Note: In the current version only TokenOwnerRecordLockAuthority is supported
-/// Eventually all Realm config items should be supported for single config item
-/// change
+/// Each item is changed individually without the need to provide the other
+/// config items which prevents race conditions when multiple proposals change
+/// different config items of the same Realm
 #[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
 pub enum SetRealmConfigItemArgs {
     /// Set TokenOwnerRecord lock authority
@@ -45,6 +45,60 @@ pub enum SetRealmConfigItemArgs {
         #[allow(dead_code)]
         authority: Pubkey,
     },
+
+    /// Set council mint
+    /// Note: Council mint can only be removed at present and changing it to
+    /// other mint or restoring it is not supported
+    CouncilMint {
+        /// The new council mint or None to remove the council mint from the
+        /// Realm
+        #[allow(dead_code)]
+        council_mint: Option<Pubkey>,
+    },
+
+    /// Set voter weight addin for the governing token
+    VoterWeightAddin {
+        /// Mint of the governing token the addin is for
+        #[allow(dead_code)]
+        governing_token_mint: Pubkey,
+        /// The new voter weight addin or None to remove the addin
+        #[allow(dead_code)]
+        voter_weight_addin: Option<Pubkey>,
+    },
+
+    /// Set max voter weight addin for the governing token
+    MaxVoterWeightAddin {
+        /// Mint of the governing token the addin is for
+        #[allow(dead_code)]
+        governing_token_mint: Pubkey,
+        /// The new max voter weight addin or None to remove the addin
+        #[allow(dead_code)]
+        max_voter_weight_addin: Option<Pubkey>,
+    },
+
+    /// Set the source used for community mint max vote weight source
+    CommunityMintMaxVoterWeightSource {
+        /// The new community mint max voter weight source
+        #[allow(dead_code)]
+        community_mint_max_voter_weight_source: MintMaxVoterWeightSource,
+    },
+
+    /// Set governing token type
+    GoverningTokenType {
+        /// Mint of the governing token to set the type for
+        #[allow(dead_code)]
+        governing_token_mint: Pubkey,
+        /// The new governing token type
+        #[allow(dead_code)]
+        token_type: GoverningTokenType,
+    },
+
+    /// Set min number of community tokens required to create a governance
+    MinCommunityWeightToCreateGovernance {
+        /// The new min community weight to create governance
+        #[allow(dead_code)]
+        min_community_weight_to_create_governance: u64,
+    },
 }
 
 /// Realm Config instruction args
@@ -221,7 +275,9 @@ pub fn is_realm_account_type(account_type: &GovernanceAccountType) -> bool {
         | GovernanceAccountType::VoteRecordV2
         | GovernanceAccountType::ProgramMetadata
         | GovernanceAccountType::ProposalDeposit
-        | GovernanceAccountType::RequiredSignatory => false,
+        | GovernanceAccountType::RequiredSignatory
+        | GovernanceAccountType::TreasuryStream
+        | GovernanceAccountType::InstructionPolicy => false,
     }
 }
 
@@ -480,7 +536,16 @@ pub fn get_governing_token_holding_address(
 pub fn assert_valid_realm_config_args(
     realm_config_args: &RealmConfigArgs,
 ) -> Result<(), ProgramError> {
-    match realm_config_args.community_mint_max_voter_weight_source {
+    assert_valid_mint_max_voter_weight_source(
+        &realm_config_args.community_mint_max_voter_weight_source,
+    )
+}
+
+/// Asserts given mint max voter weight source is correct
+pub fn assert_valid_mint_max_voter_weight_source(
+    mint_max_voter_weight_source: &MintMaxVoterWeightSource,
+) -> Result<(), ProgramError> {
+    match *mint_max_voter_weight_source {
         MintMaxVoterWeightSource::SupplyFraction(fraction) => {
             if !(1..=MintMaxVoterWeightSource::SUPPLY_FRACTION_BASE).contains(&fraction) {
                 return Err(GovernanceError::InvalidMaxVoterWeightSupplyFraction.into());
@@ -225,6 +225,16 @@ impl RealmConfigAccount {
     pub fn assert_can_change_config(
         &self,
         realm_config_args: &RealmConfigArgs,
+    ) -> Result<(), ProgramError> {
+        self.assert_can_change_community_token_type(
+            &realm_config_args.community_token_config_args.token_type,
+        )
+    }
+
+    /// Asserts the community token type can be changed to the given type
+    pub fn assert_can_change_community_token_type(
+        &self,
+        token_type: &GoverningTokenType,
     ) -> Result<(), ProgramError> {
         // Existing community token type can't be changed to Membership because it would
         // give the Realm authority the right to burn members tokens which should not be
@@ -233,8 +243,7 @@ impl RealmConfigAccount {
         // council tokens denote membership which should be controlled by the
         // Realm
         if self.community_token_config.token_type != GoverningTokenType::Membership
-            && realm_config_args.community_token_config_args.token_type
-                == GoverningTokenType::Membership
+            && *token_type == GoverningTokenType::Membership
         {
             return Err(GovernanceError::CannotChangeCommunityTokenTypeToMembership.into());
         }
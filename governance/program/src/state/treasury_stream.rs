@@ -0,0 +1,240 @@
+//! TreasuryStream account
+
+use {
+    crate::{error::GovernanceError, state::enums::GovernanceAccountType},
+    borsh::{BorshDeserialize, BorshSchema, BorshSerialize},
+    solana_program::{
+        account_info::AccountInfo, clock::UnixTimestamp, program_error::ProgramError,
+        program_pack::IsInitialized, pubkey::Pubkey,
+    },
+    spl_governance_tools::account::{get_account_data, AccountMaxSize},
+};
+
+/// Treasury stream (budget) authorized by Governance
+///
+/// The stream releases amount_per_period from the Governance treasury to the
+/// recipient for every full period elapsed between start_at and end_at.
+/// Vested funds can be released by anyone and the stream can only be created
+/// and cancelled by the Governance PDA (through a Proposal)
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub struct TreasuryStream {
+    /// Account type
+    pub account_type: GovernanceAccountType,
+
+    /// Governance the stream belongs to
+    pub governance: Pubkey,
+
+    /// The treasury account funds are released from
+    /// It's either the Governance NativeTreasury account for SOL streams or an
+    /// SPL token account owned by the Governance PDA for token streams
+    pub treasury: Pubkey,
+
+    /// Mint of the streamed tokens or None for SOL streams
+    pub mint: Option<Pubkey>,
+
+    /// The recipient of the released funds
+    /// It's a wallet for SOL streams and SPL token account for token streams
+    pub recipient: Pubkey,
+
+    /// Amount released for every full period
+    pub amount_per_period: u64,
+
+    /// Period length in seconds
+    pub period: u32,
+
+    /// The time the stream starts vesting at
+    pub start_at: UnixTimestamp,
+
+    /// The time the stream stops vesting at
+    pub end_at: UnixTimestamp,
+
+    /// Total amount already released to the recipient
+    pub released_amount: u64,
+
+    /// Reserved space for future versions
+    pub reserved: [u8; 32],
+}
+
+impl AccountMaxSize for TreasuryStream {
+    fn get_max_size(&self) -> Option<usize> {
+        Some(1 + 32 + 32 + 33 + 32 + 8 + 4 + 8 + 8 + 8 + 32)
+    }
+}
+
+impl IsInitialized for TreasuryStream {
+    fn is_initialized(&self) -> bool {
+        self.account_type == GovernanceAccountType::TreasuryStream
+    }
+}
+
+impl TreasuryStream {
+    /// Returns the number of full periods elapsed at the given time
+    fn get_elapsed_periods(&self, unix_timestamp: UnixTimestamp) -> u64 {
+        if unix_timestamp <= self.start_at {
+            return 0;
+        }
+
+        let elapsed_time = unix_timestamp
+            .min(self.end_at)
+            .checked_sub(self.start_at)
+            .unwrap();
+
+        (elapsed_time as u64)
+            .checked_div(self.period as u64)
+            .unwrap()
+    }
+
+    /// Returns the total amount vested at the given time
+    pub fn get_vested_amount(&self, unix_timestamp: UnixTimestamp) -> u64 {
+        self.get_elapsed_periods(unix_timestamp)
+            .checked_mul(self.amount_per_period)
+            .unwrap()
+    }
+
+    /// Returns the amount vested but not released yet at the given time
+    pub fn get_releasable_amount(&self, unix_timestamp: UnixTimestamp) -> u64 {
+        self.get_vested_amount(unix_timestamp)
+            .checked_sub(self.released_amount)
+            .unwrap()
+    }
+
+    /// Asserts the stream config is valid
+    pub fn assert_is_valid_config(&self) -> Result<(), ProgramError> {
+        if self.amount_per_period == 0 || self.period == 0 || self.end_at <= self.start_at {
+            return Err(GovernanceError::InvalidTreasuryStreamConfig.into());
+        }
+
+        // Ensure the total streamed amount can't overflow
+        self.get_elapsed_periods(self.end_at)
+            .checked_mul(self.amount_per_period)
+            .ok_or(GovernanceError::InvalidTreasuryStreamConfig)?;
+
+        Ok(())
+    }
+}
+
+/// Deserializes TreasuryStream account, checks the owner program, and asserts
+/// the stream belongs to the given governance
+pub fn get_treasury_stream_data_for_governance(
+    program_id: &Pubkey,
+    treasury_stream_info: &AccountInfo,
+    governance: &Pubkey,
+) -> Result<TreasuryStream, ProgramError> {
+    let treasury_stream_data =
+        get_account_data::<TreasuryStream>(program_id, treasury_stream_info)?;
+
+    if treasury_stream_data.governance != *governance {
+        return Err(GovernanceError::InvalidGovernanceForTreasuryStream.into());
+    }
+
+    Ok(treasury_stream_data)
+}
+
+/// Returns TreasuryStream PDA seeds
+pub fn get_treasury_stream_address_seeds<'a>(
+    governance: &'a Pubkey,
+    stream_seed: &'a Pubkey,
+) -> [&'a [u8]; 3] {
+    [
+        b"treasury-stream".as_ref(),
+        governance.as_ref(),
+        stream_seed.as_ref(),
+    ]
+}
+
+/// Returns TreasuryStream PDA address
+pub fn get_treasury_stream_address<'a>(
+    program_id: &Pubkey,
+    governance: &'a Pubkey,
+    stream_seed: &'a Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &get_treasury_stream_address_seeds(governance, stream_seed),
+        program_id,
+    )
+    .0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn create_test_treasury_stream() -> TreasuryStream {
+        TreasuryStream {
+            account_type: GovernanceAccountType::TreasuryStream,
+            governance: Pubkey::new_unique(),
+            treasury: Pubkey::new_unique(),
+            mint: Some(Pubkey::new_unique()),
+            recipient: Pubkey::new_unique(),
+            amount_per_period: 100,
+            period: 10,
+            start_at: 1000,
+            end_at: 1055,
+            released_amount: 0,
+            reserved: [0; 32],
+        }
+    }
+
+    #[test]
+    fn test_max_size() {
+        let treasury_stream = create_test_treasury_stream();
+
+        let size = borsh::to_vec(&treasury_stream).unwrap().len();
+
+        assert_eq!(treasury_stream.get_max_size(), Some(size));
+    }
+
+    #[test]
+    fn test_get_vested_amount() {
+        let treasury_stream = create_test_treasury_stream();
+
+        assert_eq!(treasury_stream.get_vested_amount(900), 0);
+        assert_eq!(treasury_stream.get_vested_amount(1000), 0);
+        assert_eq!(treasury_stream.get_vested_amount(1009), 0);
+        assert_eq!(treasury_stream.get_vested_amount(1010), 100);
+        assert_eq!(treasury_stream.get_vested_amount(1035), 300);
+
+        // Partial period at the end of the stream is not vested
+        assert_eq!(treasury_stream.get_vested_amount(1055), 500);
+        assert_eq!(treasury_stream.get_vested_amount(2000), 500);
+    }
+
+    #[test]
+    fn test_get_releasable_amount() {
+        let mut treasury_stream = create_test_treasury_stream();
+        treasury_stream.released_amount = 200;
+
+        assert_eq!(treasury_stream.get_releasable_amount(1020), 0);
+        assert_eq!(treasury_stream.get_releasable_amount(1035), 100);
+    }
+
+    #[test]
+    fn test_assert_is_valid_config_with_zero_period_error() {
+        let mut treasury_stream = create_test_treasury_stream();
+        treasury_stream.period = 0;
+
+        let err = treasury_stream.assert_is_valid_config().err().unwrap();
+
+        assert_eq!(err, GovernanceError::InvalidTreasuryStreamConfig.into());
+    }
+
+    #[test]
+    fn test_assert_is_valid_config_with_end_before_start_error() {
+        let mut treasury_stream = create_test_treasury_stream();
+        treasury_stream.end_at = treasury_stream.start_at;
+
+        let err = treasury_stream.assert_is_valid_config().err().unwrap();
+
+        assert_eq!(err, GovernanceError::InvalidTreasuryStreamConfig.into());
+    }
+
+    #[test]
+    fn test_assert_is_valid_config_with_overflow_error() {
+        let mut treasury_stream = create_test_treasury_stream();
+        treasury_stream.amount_per_period = u64::MAX;
+
+        let err = treasury_stream.assert_is_valid_config().err().unwrap();
+
+        assert_eq!(err, GovernanceError::InvalidTreasuryStreamConfig.into());
+    }
+}
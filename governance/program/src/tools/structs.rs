@@ -23,23 +23,23 @@ impl Default for Reserved110 {
     }
 }
 
-/// Reserved 119 bytes
+/// Reserved 118 bytes
 #[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
-pub struct Reserved119 {
+pub struct Reserved118 {
     /// Reserved 64 bytes
     pub reserved64: [u8; 64],
     /// Reserved 32 bytes
     pub reserved32: [u8; 32],
-    /// Reserved 19 bytes
-    pub reserved23: [u8; 23],
+    /// Reserved 22 bytes
+    pub reserved22: [u8; 22],
 }
 
-impl Default for Reserved119 {
+impl Default for Reserved118 {
     fn default() -> Self {
         Self {
             reserved64: [0; 64],
             reserved32: [0; 32],
-            reserved23: [0; 23],
+            reserved22: [0; 22],
         }
     }
 }
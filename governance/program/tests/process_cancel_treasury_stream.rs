@@ -0,0 +1,172 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+
+use {
+    program_test::{
+        cookies::{GovernanceCookie, TokenOwnerRecordCookie, TreasuryStreamCookie},
+        *,
+    },
+    solana_program_test::tokio,
+    spl_governance::{
+        error::GovernanceError,
+        instruction::cancel_treasury_stream,
+        state::{enums::GovernanceAccountType, treasury_stream::TreasuryStream},
+    },
+};
+
+async fn setup_treasury_stream(
+    governance_test: &mut GovernanceProgramTest,
+) -> (
+    TokenOwnerRecordCookie,
+    GovernanceCookie,
+    TreasuryStreamCookie,
+) {
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let mut governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    let native_treasury_cookie = governance_test
+        .with_native_treasury(&governance_cookie)
+        .await;
+
+    let recipient_cookie = governance_test.bench.with_wallet().await;
+
+    let clock = governance_test.bench.get_clock().await;
+
+    let treasury_stream = TreasuryStream {
+        account_type: GovernanceAccountType::TreasuryStream,
+        governance: governance_cookie.address,
+        treasury: native_treasury_cookie.address,
+        mint: None,
+        recipient: recipient_cookie.address,
+        amount_per_period: 1_000_000,
+        period: 10,
+        start_at: clock.unix_timestamp,
+        end_at: clock.unix_timestamp + 10_000,
+        released_amount: 0,
+        reserved: [0; 32],
+    };
+
+    let treasury_stream_cookie = governance_test
+        .with_treasury_stream(
+            &token_owner_record_cookie,
+            &mut governance_cookie,
+            &treasury_stream,
+        )
+        .await
+        .unwrap();
+
+    (
+        token_owner_record_cookie,
+        governance_cookie,
+        treasury_stream_cookie,
+    )
+}
+
+#[tokio::test]
+async fn test_cancel_treasury_stream() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let (token_owner_record_cookie, mut governance_cookie, treasury_stream_cookie) =
+        setup_treasury_stream(&mut governance_test).await;
+
+    let mut proposal_cookie = governance_test
+        .with_proposal(&token_owner_record_cookie, &mut governance_cookie)
+        .await
+        .unwrap();
+
+    let beneficiary_cookie = governance_test.bench.with_wallet().await;
+
+    let proposal_transaction_cookie = governance_test
+        .with_cancel_treasury_stream_transaction(
+            &mut proposal_cookie,
+            &token_owner_record_cookie,
+            &treasury_stream_cookie,
+            &beneficiary_cookie.address,
+        )
+        .await
+        .unwrap();
+
+    governance_test
+        .sign_off_proposal_by_owner(&proposal_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    governance_test
+        .with_cast_yes_no_vote(&proposal_cookie, &token_owner_record_cookie, YesNoVote::Yes)
+        .await
+        .unwrap();
+
+    governance_test
+        .advance_clock_by_min_timespan(
+            governance_cookie.account.config.transactions_hold_up_time as u64,
+        )
+        .await;
+
+    // Act
+    governance_test
+        .execute_proposal_transaction(&proposal_cookie, &proposal_transaction_cookie)
+        .await
+        .unwrap();
+
+    // Assert
+    let treasury_stream_account = governance_test
+        .bench
+        .get_account(&treasury_stream_cookie.address)
+        .await;
+
+    assert_eq!(None, treasury_stream_account);
+
+    // The stream can't be released any longer
+    let err = governance_test
+        .release_treasury_stream(&treasury_stream_cookie)
+        .await
+        .err()
+        .unwrap();
+
+    assert_eq!(
+        err,
+        spl_governance_tools::error::GovernanceToolsError::AccountDoesNotExist.into()
+    );
+}
+
+#[tokio::test]
+async fn test_cancel_treasury_stream_with_governance_pda_must_sign_error() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let (_, governance_cookie, treasury_stream_cookie) =
+        setup_treasury_stream(&mut governance_test).await;
+
+    let beneficiary_cookie = governance_test.bench.with_wallet().await;
+
+    let mut cancel_treasury_stream_ix = cancel_treasury_stream(
+        &governance_test.program_id,
+        &governance_cookie.address,
+        &treasury_stream_cookie.address,
+        &beneficiary_cookie.address,
+    );
+
+    cancel_treasury_stream_ix.accounts[0].is_signer = false;
+
+    // Act
+    let err = governance_test
+        .bench
+        .process_transaction(&[cancel_treasury_stream_ix], Some(&[]))
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(err, GovernanceError::GovernancePdaMustSign.into());
+}
@@ -0,0 +1,320 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+
+use {
+    program_test::*,
+    solana_program::pubkey::Pubkey,
+    solana_program_test::tokio,
+    solana_sdk::signature::Signer,
+    spl_governance::{
+        error::GovernanceError,
+        instruction::create_treasury_stream,
+        state::{enums::GovernanceAccountType, treasury_stream::TreasuryStream},
+    },
+};
+
+fn get_test_treasury_stream(
+    governance: &Pubkey,
+    treasury: &Pubkey,
+    mint: Option<Pubkey>,
+    start_at: i64,
+) -> TreasuryStream {
+    TreasuryStream {
+        account_type: GovernanceAccountType::TreasuryStream,
+        governance: *governance,
+        treasury: *treasury,
+        mint,
+        recipient: Pubkey::new_unique(),
+        amount_per_period: 10,
+        period: 60,
+        start_at,
+        end_at: start_at + 600,
+        released_amount: 0,
+        reserved: [0; 32],
+    }
+}
+
+#[tokio::test]
+async fn test_create_native_treasury_stream() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let mut governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    let native_treasury_cookie = governance_test
+        .with_native_treasury(&governance_cookie)
+        .await;
+
+    let clock = governance_test.bench.get_clock().await;
+
+    let treasury_stream = get_test_treasury_stream(
+        &governance_cookie.address,
+        &native_treasury_cookie.address,
+        None,
+        clock.unix_timestamp,
+    );
+
+    // Act
+    let treasury_stream_cookie = governance_test
+        .with_treasury_stream(
+            &token_owner_record_cookie,
+            &mut governance_cookie,
+            &treasury_stream,
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    let treasury_stream_account = governance_test
+        .get_treasury_stream_account(&treasury_stream_cookie.address)
+        .await;
+
+    assert_eq!(treasury_stream_cookie.account, treasury_stream_account);
+}
+
+#[tokio::test]
+async fn test_create_token_treasury_stream() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let mut governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    let governed_token_account_cookie = governance_test
+        .with_governed_token_account(&governance_cookie)
+        .await;
+
+    let clock = governance_test.bench.get_clock().await;
+
+    let treasury_stream = get_test_treasury_stream(
+        &governance_cookie.address,
+        &governed_token_account_cookie.address,
+        Some(governed_token_account_cookie.token_mint),
+        clock.unix_timestamp,
+    );
+
+    // Act
+    let treasury_stream_cookie = governance_test
+        .with_treasury_stream(
+            &token_owner_record_cookie,
+            &mut governance_cookie,
+            &treasury_stream,
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    let treasury_stream_account = governance_test
+        .get_treasury_stream_account(&treasury_stream_cookie.address)
+        .await;
+
+    assert_eq!(treasury_stream_cookie.account, treasury_stream_account);
+}
+
+#[tokio::test]
+async fn test_create_native_treasury_stream_with_invalid_treasury_error() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let mut governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    let clock = governance_test.bench.get_clock().await;
+
+    let treasury_stream = get_test_treasury_stream(
+        &governance_cookie.address,
+        &Pubkey::new_unique(),
+        None,
+        clock.unix_timestamp,
+    );
+
+    // Act
+    let err = governance_test
+        .with_treasury_stream(
+            &token_owner_record_cookie,
+            &mut governance_cookie,
+            &treasury_stream,
+        )
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(
+        err,
+        GovernanceError::InvalidTreasuryForTreasuryStream.into()
+    );
+}
+
+#[tokio::test]
+async fn test_create_token_treasury_stream_with_invalid_mint_error() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let mut governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    let governed_token_account_cookie = governance_test
+        .with_governed_token_account(&governance_cookie)
+        .await;
+
+    let clock = governance_test.bench.get_clock().await;
+
+    let treasury_stream = get_test_treasury_stream(
+        &governance_cookie.address,
+        &governed_token_account_cookie.address,
+        Some(Pubkey::new_unique()),
+        clock.unix_timestamp,
+    );
+
+    // Act
+    let err = governance_test
+        .with_treasury_stream(
+            &token_owner_record_cookie,
+            &mut governance_cookie,
+            &treasury_stream,
+        )
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(
+        err,
+        GovernanceError::InvalidTreasuryForTreasuryStream.into()
+    );
+}
+
+#[tokio::test]
+async fn test_create_treasury_stream_with_invalid_config_error() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let mut governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    let native_treasury_cookie = governance_test
+        .with_native_treasury(&governance_cookie)
+        .await;
+
+    let clock = governance_test.bench.get_clock().await;
+
+    let mut treasury_stream = get_test_treasury_stream(
+        &governance_cookie.address,
+        &native_treasury_cookie.address,
+        None,
+        clock.unix_timestamp,
+    );
+
+    treasury_stream.period = 0;
+
+    // Act
+    let err = governance_test
+        .with_treasury_stream(
+            &token_owner_record_cookie,
+            &mut governance_cookie,
+            &treasury_stream,
+        )
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(err, GovernanceError::InvalidTreasuryStreamConfig.into());
+}
+
+#[tokio::test]
+async fn test_create_treasury_stream_with_governance_pda_must_sign_error() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    let native_treasury_cookie = governance_test
+        .with_native_treasury(&governance_cookie)
+        .await;
+
+    let mut create_treasury_stream_ix = create_treasury_stream(
+        &governance_test.program_id,
+        &governance_cookie.address,
+        &native_treasury_cookie.address,
+        &governance_test.bench.payer.pubkey(),
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        None,
+        10,
+        60,
+        0,
+        600,
+    );
+
+    create_treasury_stream_ix.accounts[0].is_signer = false;
+
+    // Act
+    let err = governance_test
+        .bench
+        .process_transaction(&[create_treasury_stream_ix], Some(&[]))
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(err, GovernanceError::GovernancePdaMustSign.into());
+}
@@ -2,7 +2,28 @@
 
 mod program_test;
 
-use {program_test::*, solana_program_test::tokio, spl_governance::error::GovernanceError};
+use {
+    program_test::*,
+    solana_program_test::tokio,
+    solana_sdk::signature::Signer,
+    spl_governance::{
+        error::GovernanceError,
+        instruction::insert_transaction,
+        state::instruction_policy::{AllowedInstruction, InstructionAmountLimit},
+    },
+};
+
+fn get_token_transfer_allowed_instruction(max_amount: u64) -> AllowedInstruction {
+    AllowedInstruction {
+        program_id: spl_token::id(),
+        // spl-token Transfer
+        discriminator: vec![3],
+        amount_limit: Some(InstructionAmountLimit {
+            offset: 1,
+            max_amount,
+        }),
+    }
+}
 
 #[tokio::test]
 async fn test_insert_transaction() {
@@ -300,3 +321,218 @@ async fn test_insert_transaction_with_invalid_governance_for_proposal_error() {
     // Assert
     assert_eq!(err, GovernanceError::InvalidGovernanceForProposal.into());
 }
+
+#[tokio::test]
+async fn test_insert_transaction_allowed_by_instruction_policy() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let mut governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    let governed_token_account_cookie = governance_test
+        .with_governed_token_account(&governance_cookie)
+        .await;
+
+    governance_test
+        .with_instruction_policy(
+            &realm_cookie,
+            &mut governance_cookie,
+            &[get_token_transfer_allowed_instruction(15)],
+        )
+        .await
+        .unwrap();
+
+    let mut proposal_cookie = governance_test
+        .with_proposal(&token_owner_record_cookie, &mut governance_cookie)
+        .await
+        .unwrap();
+
+    // Act
+    let proposal_transaction_cookie = governance_test
+        .with_transfer_tokens_transaction(
+            &governed_token_account_cookie,
+            &mut proposal_cookie,
+            &token_owner_record_cookie,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    let proposal_transaction_account = governance_test
+        .get_proposal_transaction_account(&proposal_transaction_cookie.address)
+        .await;
+
+    assert_eq!(
+        proposal_transaction_cookie.account,
+        proposal_transaction_account
+    );
+}
+
+#[tokio::test]
+async fn test_insert_transaction_with_instruction_not_allowed_by_policy_error() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let mut governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    governance_test
+        .with_instruction_policy(
+            &realm_cookie,
+            &mut governance_cookie,
+            &[get_token_transfer_allowed_instruction(100)],
+        )
+        .await
+        .unwrap();
+
+    let mut proposal_cookie = governance_test
+        .with_proposal(&token_owner_record_cookie, &mut governance_cookie)
+        .await
+        .unwrap();
+
+    // Act
+    let err = governance_test
+        .with_nop_transaction(&mut proposal_cookie, &token_owner_record_cookie, 0, None)
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(err, GovernanceError::InstructionNotAllowedByPolicy.into());
+}
+
+#[tokio::test]
+async fn test_insert_transaction_with_instruction_amount_exceeding_policy_limit_error() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let mut governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    let governed_token_account_cookie = governance_test
+        .with_governed_token_account(&governance_cookie)
+        .await;
+
+    governance_test
+        .with_instruction_policy(
+            &realm_cookie,
+            &mut governance_cookie,
+            &[get_token_transfer_allowed_instruction(10)],
+        )
+        .await
+        .unwrap();
+
+    let mut proposal_cookie = governance_test
+        .with_proposal(&token_owner_record_cookie, &mut governance_cookie)
+        .await
+        .unwrap();
+
+    // Act
+
+    // Transfer 15 tokens
+    let err = governance_test
+        .with_transfer_tokens_transaction(
+            &governed_token_account_cookie,
+            &mut proposal_cookie,
+            &token_owner_record_cookie,
+            None,
+        )
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(
+        err,
+        GovernanceError::InstructionAmountExceedsPolicyLimit.into()
+    );
+}
+
+#[tokio::test]
+async fn test_insert_transaction_with_instruction_policy_account_required_error() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let mut governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    governance_test
+        .with_instruction_policy(&realm_cookie, &mut governance_cookie, &[])
+        .await
+        .unwrap();
+
+    let proposal_cookie = governance_test
+        .with_proposal(&token_owner_record_cookie, &mut governance_cookie)
+        .await
+        .unwrap();
+
+    let mut insert_transaction_ix = insert_transaction(
+        &governance_test.program_id,
+        &governance_cookie.address,
+        &proposal_cookie.address,
+        &token_owner_record_cookie.address,
+        &token_owner_record_cookie.token_owner.pubkey(),
+        &governance_test.bench.payer.pubkey(),
+        0,
+        0,
+        vec![],
+    );
+
+    // Remove InstructionPolicy account
+    insert_transaction_ix.accounts.pop();
+
+    // Act
+    let err = governance_test
+        .bench
+        .process_transaction(
+            &[insert_transaction_ix],
+            Some(&[&token_owner_record_cookie.token_owner]),
+        )
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(
+        err,
+        GovernanceError::InstructionPolicyAccountRequired.into()
+    );
+}
@@ -0,0 +1,316 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+
+use {
+    program_test::*,
+    solana_program::pubkey::Pubkey,
+    solana_program_test::tokio,
+    solana_sdk::signature::{Keypair, Signer},
+    spl_governance::{
+        error::GovernanceError,
+        state::{enums::GovernanceAccountType, treasury_stream::TreasuryStream},
+    },
+};
+
+#[tokio::test]
+async fn test_release_native_treasury_stream() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let mut governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    let native_treasury_cookie = governance_test
+        .with_native_treasury(&governance_cookie)
+        .await;
+
+    let recipient_cookie = governance_test.bench.with_wallet().await;
+
+    let clock = governance_test.bench.get_clock().await;
+
+    let treasury_stream = TreasuryStream {
+        account_type: GovernanceAccountType::TreasuryStream,
+        governance: governance_cookie.address,
+        treasury: native_treasury_cookie.address,
+        mint: None,
+        recipient: recipient_cookie.address,
+        amount_per_period: 1_000_000,
+        period: 10,
+        start_at: clock.unix_timestamp,
+        end_at: clock.unix_timestamp + 10_000,
+        released_amount: 0,
+        reserved: [0; 32],
+    };
+
+    let treasury_stream_cookie = governance_test
+        .with_treasury_stream(
+            &token_owner_record_cookie,
+            &mut governance_cookie,
+            &treasury_stream,
+        )
+        .await
+        .unwrap();
+
+    governance_test
+        .advance_clock_by_min_timespan(treasury_stream.period as u64 * 2)
+        .await;
+
+    // Act
+    governance_test
+        .release_treasury_stream(&treasury_stream_cookie)
+        .await
+        .unwrap();
+
+    // Assert
+    let clock = governance_test.bench.get_clock().await;
+
+    let treasury_stream_account = governance_test
+        .get_treasury_stream_account(&treasury_stream_cookie.address)
+        .await;
+
+    let vested_amount = treasury_stream_account.get_vested_amount(clock.unix_timestamp);
+
+    assert!(vested_amount >= 2 * treasury_stream.amount_per_period);
+    assert_eq!(vested_amount, treasury_stream_account.released_amount);
+
+    let recipient_account = governance_test
+        .bench
+        .get_account(&recipient_cookie.address)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        recipient_cookie.account.lamports + vested_amount,
+        recipient_account.lamports
+    );
+}
+
+#[tokio::test]
+async fn test_release_token_treasury_stream_after_end() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let mut governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    let governed_token_account_cookie = governance_test
+        .with_governed_token_account(&governance_cookie)
+        .await;
+
+    let recipient = Keypair::new();
+
+    governance_test
+        .bench
+        .create_empty_token_account(
+            &recipient,
+            &governed_token_account_cookie.token_mint,
+            &Pubkey::new_unique(),
+        )
+        .await;
+
+    let clock = governance_test.bench.get_clock().await;
+
+    let treasury_stream = TreasuryStream {
+        account_type: GovernanceAccountType::TreasuryStream,
+        governance: governance_cookie.address,
+        treasury: governed_token_account_cookie.address,
+        mint: Some(governed_token_account_cookie.token_mint),
+        recipient: recipient.pubkey(),
+        amount_per_period: 5,
+        period: 10,
+        start_at: clock.unix_timestamp,
+        end_at: clock.unix_timestamp + 35,
+        released_amount: 0,
+        reserved: [0; 32],
+    };
+
+    let treasury_stream_cookie = governance_test
+        .with_treasury_stream(
+            &token_owner_record_cookie,
+            &mut governance_cookie,
+            &treasury_stream,
+        )
+        .await
+        .unwrap();
+
+    governance_test
+        .advance_clock_past_timestamp(treasury_stream.end_at)
+        .await;
+
+    // Act
+    governance_test
+        .release_treasury_stream(&treasury_stream_cookie)
+        .await
+        .unwrap();
+
+    // Assert
+
+    // Only 3 full periods are vested
+    let treasury_stream_account = governance_test
+        .get_treasury_stream_account(&treasury_stream_cookie.address)
+        .await;
+
+    assert_eq!(15, treasury_stream_account.released_amount);
+
+    let recipient_account = governance_test.get_token_account(&recipient.pubkey()).await;
+
+    assert_eq!(15, recipient_account.amount);
+
+    let treasury_account = governance_test
+        .get_token_account(&governed_token_account_cookie.address)
+        .await;
+
+    assert_eq!(85, treasury_account.amount);
+}
+
+#[tokio::test]
+async fn test_release_treasury_stream_with_nothing_to_release_error() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let mut governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    let native_treasury_cookie = governance_test
+        .with_native_treasury(&governance_cookie)
+        .await;
+
+    let recipient_cookie = governance_test.bench.with_wallet().await;
+
+    let clock = governance_test.bench.get_clock().await;
+
+    // Stream starting in the future
+    let treasury_stream = TreasuryStream {
+        account_type: GovernanceAccountType::TreasuryStream,
+        governance: governance_cookie.address,
+        treasury: native_treasury_cookie.address,
+        mint: None,
+        recipient: recipient_cookie.address,
+        amount_per_period: 1_000_000,
+        period: 10,
+        start_at: clock.unix_timestamp + 10_000,
+        end_at: clock.unix_timestamp + 20_000,
+        released_amount: 0,
+        reserved: [0; 32],
+    };
+
+    let treasury_stream_cookie = governance_test
+        .with_treasury_stream(
+            &token_owner_record_cookie,
+            &mut governance_cookie,
+            &treasury_stream,
+        )
+        .await
+        .unwrap();
+
+    // Act
+    let err = governance_test
+        .release_treasury_stream(&treasury_stream_cookie)
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(
+        err,
+        GovernanceError::TreasuryStreamHasNothingToRelease.into()
+    );
+}
+
+#[tokio::test]
+async fn test_release_treasury_stream_with_invalid_recipient_error() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let mut governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    let native_treasury_cookie = governance_test
+        .with_native_treasury(&governance_cookie)
+        .await;
+
+    let recipient_cookie = governance_test.bench.with_wallet().await;
+
+    let clock = governance_test.bench.get_clock().await;
+
+    let treasury_stream = TreasuryStream {
+        account_type: GovernanceAccountType::TreasuryStream,
+        governance: governance_cookie.address,
+        treasury: native_treasury_cookie.address,
+        mint: None,
+        recipient: recipient_cookie.address,
+        amount_per_period: 1_000_000,
+        period: 10,
+        start_at: clock.unix_timestamp,
+        end_at: clock.unix_timestamp + 10_000,
+        released_amount: 0,
+        reserved: [0; 32],
+    };
+
+    let mut treasury_stream_cookie = governance_test
+        .with_treasury_stream(
+            &token_owner_record_cookie,
+            &mut governance_cookie,
+            &treasury_stream,
+        )
+        .await
+        .unwrap();
+
+    governance_test
+        .advance_clock_by_min_timespan(treasury_stream.period as u64)
+        .await;
+
+    // Try to release the funds to a different wallet
+    let other_wallet_cookie = governance_test.bench.with_wallet().await;
+    treasury_stream_cookie.account.recipient = other_wallet_cookie.address;
+
+    // Act
+    let err = governance_test
+        .release_treasury_stream(&treasury_stream_cookie)
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(
+        err,
+        GovernanceError::InvalidRecipientForTreasuryStream.into()
+    );
+}
@@ -0,0 +1,156 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+
+use {
+    program_test::*,
+    solana_program_test::tokio,
+    solana_sdk::signature::{Keypair, Signer},
+    spl_governance::error::GovernanceError,
+    spl_governance_test_sdk::tools::NopOverride,
+};
+
+#[tokio::test]
+async fn test_remove_instruction_policy() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let mut governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    let instruction_policy_cookie = governance_test
+        .with_instruction_policy(&realm_cookie, &mut governance_cookie, &[])
+        .await
+        .unwrap();
+
+    let beneficiary_cookie = governance_test.bench.with_wallet().await;
+
+    // Act
+    governance_test
+        .remove_instruction_policy_using_instruction(
+            &realm_cookie,
+            &mut governance_cookie,
+            &beneficiary_cookie.address,
+            NopOverride,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    let instruction_policy_account = governance_test
+        .bench
+        .get_account(&instruction_policy_cookie.address)
+        .await;
+
+    assert_eq!(None, instruction_policy_account);
+
+    let governance_account = governance_test
+        .get_governance_account(&governance_cookie.address)
+        .await;
+
+    assert!(!governance_account.instruction_policy_enabled);
+
+    // Any instruction can be inserted again
+    let mut proposal_cookie = governance_test
+        .with_proposal(&token_owner_record_cookie, &mut governance_cookie)
+        .await
+        .unwrap();
+
+    governance_test
+        .with_nop_transaction(&mut proposal_cookie, &token_owner_record_cookie, 0, None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_remove_instruction_policy_with_realm_authority_must_sign_error() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let mut governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    governance_test
+        .with_instruction_policy(&realm_cookie, &mut governance_cookie, &[])
+        .await
+        .unwrap();
+
+    let beneficiary_cookie = governance_test.bench.with_wallet().await;
+
+    // Act
+    let err = governance_test
+        .remove_instruction_policy_using_instruction(
+            &realm_cookie,
+            &mut governance_cookie,
+            &beneficiary_cookie.address,
+            |i| i.accounts[3].is_signer = false,
+            Some(&[]),
+        )
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(err, GovernanceError::RealmAuthorityMustSign.into());
+}
+
+#[tokio::test]
+async fn test_remove_instruction_policy_with_invalid_realm_authority_error() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let mut governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    governance_test
+        .with_instruction_policy(&realm_cookie, &mut governance_cookie, &[])
+        .await
+        .unwrap();
+
+    let beneficiary_cookie = governance_test.bench.with_wallet().await;
+    let governance_authority = Keypair::new();
+
+    // Act
+    let err = governance_test
+        .remove_instruction_policy_using_instruction(
+            &realm_cookie,
+            &mut governance_cookie,
+            &beneficiary_cookie.address,
+            |i| i.accounts[3].pubkey = governance_authority.pubkey(),
+            Some(&[&governance_authority]),
+        )
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(err, GovernanceError::InvalidAuthorityForRealm.into());
+}
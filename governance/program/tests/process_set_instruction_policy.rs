@@ -0,0 +1,190 @@
+#![cfg(feature = "test-sbf")]
+
+mod program_test;
+
+use {
+    program_test::*,
+    solana_program_test::tokio,
+    solana_sdk::signature::{Keypair, Signer},
+    spl_governance::{
+        error::GovernanceError,
+        state::instruction_policy::{AllowedInstruction, InstructionAmountLimit},
+    },
+};
+
+fn get_token_transfer_allowed_instruction(max_amount: u64) -> AllowedInstruction {
+    AllowedInstruction {
+        program_id: spl_token::id(),
+        // spl-token Transfer
+        discriminator: vec![3],
+        amount_limit: Some(InstructionAmountLimit {
+            offset: 1,
+            max_amount,
+        }),
+    }
+}
+
+#[tokio::test]
+async fn test_set_instruction_policy() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let mut governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    // Act
+    let instruction_policy_cookie = governance_test
+        .with_instruction_policy(
+            &realm_cookie,
+            &mut governance_cookie,
+            &[get_token_transfer_allowed_instruction(100)],
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    let instruction_policy_account = governance_test
+        .get_instruction_policy_account(&instruction_policy_cookie.address)
+        .await;
+
+    assert_eq!(
+        instruction_policy_cookie.account,
+        instruction_policy_account
+    );
+
+    let governance_account = governance_test
+        .get_governance_account(&governance_cookie.address)
+        .await;
+
+    assert!(governance_account.instruction_policy_enabled);
+}
+
+#[tokio::test]
+async fn test_set_instruction_policy_for_existing_policy() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let mut governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    governance_test
+        .with_instruction_policy(
+            &realm_cookie,
+            &mut governance_cookie,
+            &[get_token_transfer_allowed_instruction(100)],
+        )
+        .await
+        .unwrap();
+
+    // Act
+    let instruction_policy_cookie = governance_test
+        .with_instruction_policy(
+            &realm_cookie,
+            &mut governance_cookie,
+            &[
+                get_token_transfer_allowed_instruction(100),
+                get_token_transfer_allowed_instruction(1000),
+            ],
+        )
+        .await
+        .unwrap();
+
+    // Assert
+    let instruction_policy_account = governance_test
+        .get_instruction_policy_account(&instruction_policy_cookie.address)
+        .await;
+
+    assert_eq!(
+        instruction_policy_cookie.account,
+        instruction_policy_account
+    );
+}
+
+#[tokio::test]
+async fn test_set_instruction_policy_with_realm_authority_must_sign_error() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let mut governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    // Act
+    let err = governance_test
+        .with_instruction_policy_using_instruction(
+            &realm_cookie,
+            &mut governance_cookie,
+            &[get_token_transfer_allowed_instruction(100)],
+            |i| i.accounts[3].is_signer = false,
+            Some(&[]),
+        )
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(err, GovernanceError::RealmAuthorityMustSign.into());
+}
+
+#[tokio::test]
+async fn test_set_instruction_policy_with_invalid_realm_authority_error() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_cookie = governance_test
+        .with_community_token_deposit(&realm_cookie)
+        .await
+        .unwrap();
+
+    let mut governance_cookie = governance_test
+        .with_governance(&realm_cookie, &token_owner_record_cookie)
+        .await
+        .unwrap();
+
+    // The Governance can't set its own policy and neither can anyone else
+    let governance_authority = Keypair::new();
+
+    // Act
+    let err = governance_test
+        .with_instruction_policy_using_instruction(
+            &realm_cookie,
+            &mut governance_cookie,
+            &[get_token_transfer_allowed_instruction(100)],
+            |i| i.accounts[3].pubkey = governance_authority.pubkey(),
+            Some(&[&governance_authority]),
+        )
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(err, GovernanceError::InvalidAuthorityForRealm.into());
+}
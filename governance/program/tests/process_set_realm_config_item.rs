@@ -10,9 +10,9 @@ use {
     spl_governance::{
         error::GovernanceError,
         state::{
-            enums::GovernanceAccountType,
+            enums::{GovernanceAccountType, MintMaxVoterWeightSource},
             realm::SetRealmConfigItemArgs,
-            realm_config::{GoverningTokenConfig, RealmConfigAccount},
+            realm_config::{GoverningTokenConfig, GoverningTokenType, RealmConfigAccount},
         },
         tools::structs::{Reserved110, SetConfigItemActionType},
     },
@@ -384,3 +384,302 @@ async fn test_remove_token_owner_record_lock_authority_with_authority_not_found_
         GovernanceError::TokenOwnerRecordLockAuthorityNotFound.into()
     );
 }
+
+#[tokio::test]
+async fn test_set_realm_config_item_remove_council_mint() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let args = SetRealmConfigItemArgs::CouncilMint { council_mint: None };
+
+    // Act
+    governance_test
+        .set_realm_config_item(&realm_cookie, args)
+        .await
+        .unwrap();
+
+    // Assert
+    let realm_account = governance_test
+        .get_realm_account(&realm_cookie.address)
+        .await;
+
+    assert_eq!(None, realm_account.config.council_mint);
+
+    // Ensure other config items are not changed
+    assert_eq!(
+        realm_cookie
+            .account
+            .config
+            .community_mint_max_voter_weight_source,
+        realm_account.config.community_mint_max_voter_weight_source
+    );
+    assert_eq!(
+        realm_cookie
+            .account
+            .config
+            .min_community_weight_to_create_governance,
+        realm_account
+            .config
+            .min_community_weight_to_create_governance
+    );
+}
+
+#[tokio::test]
+async fn test_set_realm_config_item_with_council_mint_change_not_supported_error() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let args = SetRealmConfigItemArgs::CouncilMint {
+        council_mint: Some(Pubkey::new_unique()),
+    };
+
+    // Act
+    let err = governance_test
+        .set_realm_config_item(&realm_cookie, args)
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(
+        err,
+        GovernanceError::RealmCouncilMintChangeIsNotSupported.into()
+    );
+}
+
+#[tokio::test]
+async fn test_set_realm_config_item_community_voter_weight_addin() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let token_owner_record_lock_authority_cookie = governance_test
+        .with_community_token_owner_record_lock_authority(&realm_cookie)
+        .await
+        .unwrap();
+
+    let voter_weight_addin = Pubkey::new_unique();
+
+    let args = SetRealmConfigItemArgs::VoterWeightAddin {
+        governing_token_mint: realm_cookie.account.community_mint,
+        voter_weight_addin: Some(voter_weight_addin),
+    };
+
+    // Act
+    governance_test
+        .set_realm_config_item(&realm_cookie, args)
+        .await
+        .unwrap();
+
+    // Assert
+    let realm_config_account = governance_test
+        .get_realm_config_account(&realm_cookie.realm_config.address)
+        .await;
+
+    assert_eq!(
+        Some(voter_weight_addin),
+        realm_config_account
+            .community_token_config
+            .voter_weight_addin
+    );
+
+    // Ensure other config items are not changed
+    assert_eq!(
+        None,
+        realm_config_account
+            .community_token_config
+            .max_voter_weight_addin
+    );
+    assert_eq!(
+        vec![token_owner_record_lock_authority_cookie.authority.pubkey()],
+        realm_config_account.community_token_config.lock_authorities
+    );
+}
+
+#[tokio::test]
+async fn test_set_realm_config_item_council_max_voter_weight_addin() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let max_voter_weight_addin = Pubkey::new_unique();
+
+    let args = SetRealmConfigItemArgs::MaxVoterWeightAddin {
+        governing_token_mint: realm_cookie.account.config.council_mint.unwrap(),
+        max_voter_weight_addin: Some(max_voter_weight_addin),
+    };
+
+    // Act
+    governance_test
+        .set_realm_config_item(&realm_cookie, args)
+        .await
+        .unwrap();
+
+    // Assert
+    let realm_config_account = governance_test
+        .get_realm_config_account(&realm_cookie.realm_config.address)
+        .await;
+
+    assert_eq!(
+        Some(max_voter_weight_addin),
+        realm_config_account
+            .council_token_config
+            .max_voter_weight_addin
+    );
+    assert_eq!(
+        None,
+        realm_config_account
+            .community_token_config
+            .max_voter_weight_addin
+    );
+}
+
+#[tokio::test]
+async fn test_set_realm_config_item_community_mint_max_voter_weight_source() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let community_mint_max_voter_weight_source = MintMaxVoterWeightSource::Absolute(1000);
+
+    let args = SetRealmConfigItemArgs::CommunityMintMaxVoterWeightSource {
+        community_mint_max_voter_weight_source: community_mint_max_voter_weight_source.clone(),
+    };
+
+    // Act
+    governance_test
+        .set_realm_config_item(&realm_cookie, args)
+        .await
+        .unwrap();
+
+    // Assert
+    let realm_account = governance_test
+        .get_realm_account(&realm_cookie.address)
+        .await;
+
+    assert_eq!(
+        community_mint_max_voter_weight_source,
+        realm_account.config.community_mint_max_voter_weight_source
+    );
+    assert_eq!(
+        realm_cookie.account.config.council_mint,
+        realm_account.config.council_mint
+    );
+}
+
+#[tokio::test]
+async fn test_set_realm_config_item_with_invalid_max_voter_weight_absolute_value_error() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let args = SetRealmConfigItemArgs::CommunityMintMaxVoterWeightSource {
+        community_mint_max_voter_weight_source: MintMaxVoterWeightSource::Absolute(0),
+    };
+
+    // Act
+    let err = governance_test
+        .set_realm_config_item(&realm_cookie, args)
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(
+        err,
+        GovernanceError::InvalidMaxVoterWeightAbsoluteValue.into()
+    );
+}
+
+#[tokio::test]
+async fn test_set_realm_config_item_council_governing_token_type() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let args = SetRealmConfigItemArgs::GoverningTokenType {
+        governing_token_mint: realm_cookie.account.config.council_mint.unwrap(),
+        token_type: GoverningTokenType::Membership,
+    };
+
+    // Act
+    governance_test
+        .set_realm_config_item(&realm_cookie, args)
+        .await
+        .unwrap();
+
+    // Assert
+    let realm_config_account = governance_test
+        .get_realm_config_account(&realm_cookie.realm_config.address)
+        .await;
+
+    assert_eq!(
+        GoverningTokenType::Membership,
+        realm_config_account.council_token_config.token_type
+    );
+}
+
+#[tokio::test]
+async fn test_set_realm_config_item_with_cannot_change_community_token_type_to_membership_error() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let args = SetRealmConfigItemArgs::GoverningTokenType {
+        governing_token_mint: realm_cookie.account.community_mint,
+        token_type: GoverningTokenType::Membership,
+    };
+
+    // Act
+    let err = governance_test
+        .set_realm_config_item(&realm_cookie, args)
+        .await
+        .err()
+        .unwrap();
+
+    // Assert
+    assert_eq!(
+        err,
+        GovernanceError::CannotChangeCommunityTokenTypeToMembership.into()
+    );
+}
+
+#[tokio::test]
+async fn test_set_realm_config_item_min_community_weight_to_create_governance() {
+    // Arrange
+    let mut governance_test = GovernanceProgramTest::start_new().await;
+
+    let realm_cookie = governance_test.with_realm().await;
+
+    let args = SetRealmConfigItemArgs::MinCommunityWeightToCreateGovernance {
+        min_community_weight_to_create_governance: 42,
+    };
+
+    // Act
+    governance_test
+        .set_realm_config_item(&realm_cookie, args)
+        .await
+        .unwrap();
+
+    // Assert
+    let realm_account = governance_test
+        .get_realm_account(&realm_cookie.address)
+        .await;
+
+    assert_eq!(
+        42,
+        realm_account
+            .config
+            .min_community_weight_to_create_governance
+    );
+}
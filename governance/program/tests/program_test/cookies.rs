@@ -3,11 +3,12 @@ use {
     solana_program::{clock::UnixTimestamp, instruction::Instruction, pubkey::Pubkey},
     solana_sdk::signature::Keypair,
     spl_governance::state::{
-        governance::GovernanceV2, native_treasury::NativeTreasury,
-        program_metadata::ProgramMetadata, proposal::ProposalV2, proposal_deposit::ProposalDeposit,
-        proposal_transaction::ProposalTransactionV2, realm::RealmV2,
-        realm_config::RealmConfigAccount, signatory_record::SignatoryRecordV2,
-        token_owner_record::TokenOwnerRecordV2, vote_record::VoteRecordV2,
+        governance::GovernanceV2, instruction_policy::InstructionPolicy,
+        native_treasury::NativeTreasury, program_metadata::ProgramMetadata, proposal::ProposalV2,
+        proposal_deposit::ProposalDeposit, proposal_transaction::ProposalTransactionV2,
+        realm::RealmV2, realm_config::RealmConfigAccount, signatory_record::SignatoryRecordV2,
+        token_owner_record::TokenOwnerRecordV2, treasury_stream::TreasuryStream,
+        vote_record::VoteRecordV2,
     },
     spl_governance_addin_api::{
         max_voter_weight::MaxVoterWeightRecord, voter_weight::VoterWeightRecord,
@@ -196,3 +197,15 @@ pub struct TokenOwnerRecordLockCookie {
 pub struct TokenOwnerRecordLockAuthorityCookie {
     pub authority: Keypair,
 }
+
+#[derive(Debug, Clone)]
+pub struct TreasuryStreamCookie {
+    pub address: Pubkey,
+    pub account: TreasuryStream,
+}
+
+#[derive(Debug, Clone)]
+pub struct InstructionPolicyCookie {
+    pub address: Pubkey,
+    pub account: InstructionPolicy,
+}
@@ -16,15 +16,16 @@ use {
     solana_sdk::signature::{Keypair, Signer},
     spl_governance::{
         instruction::{
-            add_required_signatory, add_signatory, cancel_proposal, cast_vote, complete_proposal,
-            create_governance, create_native_treasury, create_proposal, create_realm,
-            create_token_owner_record, deposit_governing_tokens, execute_transaction,
-            finalize_vote, insert_transaction, refund_proposal_deposit,
-            relinquish_token_owner_record_locks, relinquish_vote, remove_required_signatory,
+            add_required_signatory, add_signatory, cancel_proposal, cancel_treasury_stream,
+            cast_vote, complete_proposal, create_governance, create_native_treasury,
+            create_proposal, create_realm, create_token_owner_record, create_treasury_stream,
+            deposit_governing_tokens, execute_transaction, finalize_vote, insert_transaction,
+            refund_proposal_deposit, release_treasury_stream, relinquish_token_owner_record_locks,
+            relinquish_vote, remove_instruction_policy, remove_required_signatory,
             remove_transaction, revoke_governing_tokens, set_governance_config,
-            set_governance_delegate, set_realm_authority, set_realm_config, set_realm_config_item,
-            set_token_owner_record_lock, sign_off_proposal, upgrade_program_metadata,
-            withdraw_governing_tokens, AddSignatoryAuthority,
+            set_governance_delegate, set_instruction_policy, set_realm_authority, set_realm_config,
+            set_realm_config_item, set_token_owner_record_lock, sign_off_proposal,
+            upgrade_program_metadata, withdraw_governing_tokens, AddSignatoryAuthority,
         },
         processor::process_instruction,
         state::{
@@ -36,6 +37,9 @@ use {
                 get_governance_address, GovernanceConfig, GovernanceV2,
                 DEFAULT_DEPOSIT_EXEMPT_PROPOSAL_COUNT,
             },
+            instruction_policy::{
+                get_instruction_policy_address, AllowedInstruction, InstructionPolicy,
+            },
             native_treasury::{get_native_treasury_address, NativeTreasury},
             program_metadata::{get_program_metadata_address, ProgramMetadata},
             proposal::{
@@ -57,11 +61,12 @@ use {
                 get_token_owner_record_address, TokenOwnerRecordV2,
                 TOKEN_OWNER_RECORD_LAYOUT_VERSION,
             },
+            treasury_stream::{get_treasury_stream_address, TreasuryStream},
             vote_record::{get_vote_record_address, Vote, VoteChoice, VoteRecordV2},
         },
         tools::{
             bpf_loader_upgradeable::get_program_data_address,
-            structs::{Reserved110, Reserved119, SetConfigItemActionType},
+            structs::{Reserved110, Reserved118, SetConfigItemActionType},
         },
     },
     spl_governance_addin_api::{
@@ -83,10 +88,10 @@ use {
         args::{PluginSetupArgs, RealmSetupArgs},
         cookies::{
             GovernanceCookie, GovernedMintCookie, GovernedProgramCookie,
-            GovernedTokenAccountCookie, MaxVoterWeightRecordCookie, NativeTreasuryCookie,
-            ProgramMetadataCookie, ProposalCookie, ProposalDepositCookie,
+            GovernedTokenAccountCookie, InstructionPolicyCookie, MaxVoterWeightRecordCookie,
+            NativeTreasuryCookie, ProgramMetadataCookie, ProposalCookie, ProposalDepositCookie,
             ProposalTransactionCookie, RealmCookie, TokenOwnerRecordCookie,
-            TokenOwnerRecordLockCookie, VoteRecordCookie,
+            TokenOwnerRecordLockCookie, TreasuryStreamCookie, VoteRecordCookie,
         },
         program_test::cookies::{
             RealmConfigCookie, SignatoryRecordCookie, VoterWeightRecordCookie,
@@ -1487,7 +1492,8 @@ impl GovernanceProgramTest {
             governance_seed,
             config: governance_config.clone(),
             reserved1: 0,
-            reserved_v2: Reserved119::default(),
+            reserved_v2: Reserved118::default(),
+            instruction_policy_enabled: false,
             required_signatories_count: 0,
             active_proposal_count: 0,
         };
@@ -2704,6 +2710,240 @@ impl GovernanceProgramTest {
         Ok(())
     }
 
+    #[allow(dead_code)]
+    pub async fn with_create_treasury_stream_transaction(
+        &mut self,
+        proposal_cookie: &mut ProposalCookie,
+        token_owner_record_cookie: &TokenOwnerRecordCookie,
+        treasury_stream: &TreasuryStream,
+    ) -> Result<(ProposalTransactionCookie, TreasuryStreamCookie), ProgramError> {
+        let stream_seed = Pubkey::new_unique();
+
+        let mut create_treasury_stream_ix = create_treasury_stream(
+            &self.program_id,
+            &treasury_stream.governance,
+            &treasury_stream.treasury,
+            &self.bench.payer.pubkey(),
+            &stream_seed,
+            &treasury_stream.recipient,
+            treasury_stream.mint,
+            treasury_stream.amount_per_period,
+            treasury_stream.period,
+            treasury_stream.start_at,
+            treasury_stream.end_at,
+        );
+
+        let proposal_transaction_cookie = self
+            .with_proposal_transaction(
+                proposal_cookie,
+                token_owner_record_cookie,
+                0,
+                None,
+                &mut create_treasury_stream_ix,
+            )
+            .await?;
+
+        let treasury_stream_cookie = TreasuryStreamCookie {
+            address: get_treasury_stream_address(
+                &self.program_id,
+                &treasury_stream.governance,
+                &stream_seed,
+            ),
+            account: treasury_stream.clone(),
+        };
+
+        Ok((proposal_transaction_cookie, treasury_stream_cookie))
+    }
+
+    #[allow(dead_code)]
+    pub async fn with_cancel_treasury_stream_transaction(
+        &mut self,
+        proposal_cookie: &mut ProposalCookie,
+        token_owner_record_cookie: &TokenOwnerRecordCookie,
+        treasury_stream_cookie: &TreasuryStreamCookie,
+        beneficiary: &Pubkey,
+    ) -> Result<ProposalTransactionCookie, ProgramError> {
+        let mut cancel_treasury_stream_ix = cancel_treasury_stream(
+            &self.program_id,
+            &treasury_stream_cookie.account.governance,
+            &treasury_stream_cookie.address,
+            beneficiary,
+        );
+
+        self.with_proposal_transaction(
+            proposal_cookie,
+            token_owner_record_cookie,
+            0,
+            None,
+            &mut cancel_treasury_stream_ix,
+        )
+        .await
+    }
+
+    /// Sets InstructionPolicy for the Governance using the Realm authority
+    #[allow(dead_code)]
+    pub async fn with_instruction_policy(
+        &mut self,
+        realm_cookie: &RealmCookie,
+        governance_cookie: &mut GovernanceCookie,
+        allowed_instructions: &[AllowedInstruction],
+    ) -> Result<InstructionPolicyCookie, ProgramError> {
+        self.with_instruction_policy_using_instruction(
+            realm_cookie,
+            governance_cookie,
+            allowed_instructions,
+            NopOverride,
+            None,
+        )
+        .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn with_instruction_policy_using_instruction<F: Fn(&mut Instruction)>(
+        &mut self,
+        realm_cookie: &RealmCookie,
+        governance_cookie: &mut GovernanceCookie,
+        allowed_instructions: &[AllowedInstruction],
+        instruction_override: F,
+        signers_override: Option<&[&Keypair]>,
+    ) -> Result<InstructionPolicyCookie, ProgramError> {
+        let realm_authority = realm_cookie.realm_authority.as_ref().unwrap();
+
+        let mut set_instruction_policy_ix = set_instruction_policy(
+            &self.program_id,
+            &realm_cookie.address,
+            &governance_cookie.address,
+            &realm_authority.pubkey(),
+            &self.bench.payer.pubkey(),
+            allowed_instructions.to_vec(),
+        );
+
+        instruction_override(&mut set_instruction_policy_ix);
+
+        let default_signers = &[realm_authority];
+        let signers = signers_override.unwrap_or(default_signers);
+
+        self.bench
+            .process_transaction(&[set_instruction_policy_ix], Some(signers))
+            .await?;
+
+        governance_cookie.account.instruction_policy_enabled = true;
+
+        Ok(InstructionPolicyCookie {
+            address: get_instruction_policy_address(&self.program_id, &governance_cookie.address),
+            account: InstructionPolicy {
+                account_type: GovernanceAccountType::InstructionPolicy,
+                governance: governance_cookie.address,
+                allowed_instructions: allowed_instructions.to_vec(),
+                reserved: [0; 32],
+            },
+        })
+    }
+
+    /// Removes InstructionPolicy from the Governance using the Realm authority
+    #[allow(dead_code)]
+    pub async fn remove_instruction_policy_using_instruction<F: Fn(&mut Instruction)>(
+        &mut self,
+        realm_cookie: &RealmCookie,
+        governance_cookie: &mut GovernanceCookie,
+        beneficiary: &Pubkey,
+        instruction_override: F,
+        signers_override: Option<&[&Keypair]>,
+    ) -> Result<(), ProgramError> {
+        let realm_authority = realm_cookie.realm_authority.as_ref().unwrap();
+
+        let mut remove_instruction_policy_ix = remove_instruction_policy(
+            &self.program_id,
+            &realm_cookie.address,
+            &governance_cookie.address,
+            &realm_authority.pubkey(),
+            beneficiary,
+        );
+
+        instruction_override(&mut remove_instruction_policy_ix);
+
+        let default_signers = &[realm_authority];
+        let signers = signers_override.unwrap_or(default_signers);
+
+        self.bench
+            .process_transaction(&[remove_instruction_policy_ix], Some(signers))
+            .await?;
+
+        governance_cookie.account.instruction_policy_enabled = false;
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub async fn get_instruction_policy_account(&mut self, address: &Pubkey) -> InstructionPolicy {
+        self.bench
+            .get_borsh_account::<InstructionPolicy>(address)
+            .await
+    }
+
+    /// Creates TreasuryStream using a Proposal which is voted on and executed
+    #[allow(dead_code)]
+    pub async fn with_treasury_stream(
+        &mut self,
+        token_owner_record_cookie: &TokenOwnerRecordCookie,
+        governance_cookie: &mut GovernanceCookie,
+        treasury_stream: &TreasuryStream,
+    ) -> Result<TreasuryStreamCookie, ProgramError> {
+        let mut proposal_cookie = self
+            .with_proposal(token_owner_record_cookie, governance_cookie)
+            .await?;
+
+        let (proposal_transaction_cookie, treasury_stream_cookie) = self
+            .with_create_treasury_stream_transaction(
+                &mut proposal_cookie,
+                token_owner_record_cookie,
+                treasury_stream,
+            )
+            .await?;
+
+        self.sign_off_proposal_by_owner(&proposal_cookie, token_owner_record_cookie)
+            .await?;
+
+        self.with_cast_yes_no_vote(&proposal_cookie, token_owner_record_cookie, YesNoVote::Yes)
+            .await?;
+
+        self.advance_clock_by_min_timespan(
+            governance_cookie.account.config.transactions_hold_up_time as u64,
+        )
+        .await;
+
+        self.execute_proposal_transaction(&proposal_cookie, &proposal_transaction_cookie)
+            .await?;
+
+        Ok(treasury_stream_cookie)
+    }
+
+    #[allow(dead_code)]
+    pub async fn release_treasury_stream(
+        &mut self,
+        treasury_stream_cookie: &TreasuryStreamCookie,
+    ) -> Result<(), ProgramError> {
+        let release_treasury_stream_ix = release_treasury_stream(
+            &self.program_id,
+            &treasury_stream_cookie.account.governance,
+            &treasury_stream_cookie.address,
+            &treasury_stream_cookie.account.treasury,
+            &treasury_stream_cookie.account.recipient,
+            treasury_stream_cookie.account.mint.is_some(),
+        );
+
+        self.bench
+            .process_transaction(&[release_treasury_stream_ix], None)
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn get_treasury_stream_account(&mut self, address: &Pubkey) -> TreasuryStream {
+        self.bench
+            .get_borsh_account::<TreasuryStream>(address)
+            .await
+    }
+
     #[allow(dead_code)]
     pub async fn execute_proposal_transaction(
         &mut self,
@@ -0,0 +1,29 @@
+//! Error types
+
+use thiserror::Error;
+
+/// Errors that may be returned by the client
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum AccountCompressionClientError {
+    /// Account data isn't a ConcurrentMerkleTree account
+    #[error("Invalid ConcurrentMerkleTree account data")]
+    InvalidAccountData,
+
+    /// The program doesn't support trees of this depth and buffer size
+    #[error("Unsupported tree of max depth {max_depth} and max buffer size {max_buffer_size}")]
+    UnsupportedTreeSize {
+        /// Max depth of the tree
+        max_depth: u32,
+        /// Max buffer size of the tree
+        max_buffer_size: u32,
+    },
+
+    /// The canopy is deeper than the tree
+    #[error("Canopy depth {canopy_depth} is larger than max depth {max_depth}")]
+    InvalidCanopyDepth {
+        /// Depth of the canopy
+        canopy_depth: u32,
+        /// Max depth of the tree
+        max_depth: u32,
+    },
+}
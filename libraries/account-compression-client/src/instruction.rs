@@ -0,0 +1,576 @@
+//! Instruction builders
+//!
+//! The proofs passed to the instructions only need the nodes below the canopy
+//! of the tree: the program fills in the rest from the canopy.
+
+use {
+    crate::{noop, state::HashType},
+    borsh::BorshSerialize,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+    },
+    spl_concurrent_merkle_tree::{indexed_leaf::IndexedLeaf, node::Node},
+};
+
+/// Anchor discriminators of the instructions, the first 8 bytes of
+/// `sha256("global:<instruction name>")`
+pub mod discriminator {
+    /// `init_empty_merkle_tree`
+    pub const INIT_EMPTY_MERKLE_TREE: [u8; 8] = [191, 11, 119, 7, 180, 107, 220, 110];
+    /// `init_empty_merkle_tree_with_hash_type`
+    pub const INIT_EMPTY_MERKLE_TREE_WITH_HASH_TYPE: [u8; 8] = [97, 211, 88, 14, 157, 214, 233, 36];
+    /// `init_indexed_merkle_tree`
+    pub const INIT_INDEXED_MERKLE_TREE: [u8; 8] = [188, 122, 148, 174, 137, 0, 125, 111];
+    /// `prepare_batch_merkle_tree`
+    pub const PREPARE_BATCH_MERKLE_TREE: [u8; 8] = [230, 124, 120, 196, 249, 134, 199, 128];
+    /// `prepare_batch_merkle_tree_with_hash_type`
+    pub const PREPARE_BATCH_MERKLE_TREE_WITH_HASH_TYPE: [u8; 8] =
+        [208, 130, 147, 230, 72, 222, 98, 193];
+    /// `append_canopy_nodes`
+    pub const APPEND_CANOPY_NODES: [u8; 8] = [139, 155, 238, 167, 11, 243, 132, 205];
+    /// `init_prepared_tree_with_root`
+    pub const INIT_PREPARED_TREE_WITH_ROOT: [u8; 8] = [218, 248, 192, 55, 91, 205, 122, 10];
+    /// `replace_leaf`
+    pub const REPLACE_LEAF: [u8; 8] = [204, 165, 76, 100, 73, 147, 0, 128];
+    /// `replace_leaves`
+    pub const REPLACE_LEAVES: [u8; 8] = [117, 197, 157, 131, 219, 134, 73, 132];
+    /// `transfer_authority`
+    pub const TRANSFER_AUTHORITY: [u8; 8] = [48, 169, 76, 72, 229, 180, 55, 161];
+    /// `verify_leaf`
+    pub const VERIFY_LEAF: [u8; 8] = [124, 220, 22, 223, 104, 10, 250, 224];
+    /// `verify_non_membership`
+    pub const VERIFY_NON_MEMBERSHIP: [u8; 8] = [231, 161, 86, 239, 111, 236, 14, 74];
+    /// `append`
+    pub const APPEND: [u8; 8] = [149, 120, 18, 222, 236, 225, 88, 203];
+    /// `insert_or_append`
+    pub const INSERT_OR_APPEND: [u8; 8] = [6, 42, 50, 190, 51, 109, 178, 168];
+    /// `append_subtree`
+    pub const APPEND_SUBTREE: [u8; 8] = [144, 15, 220, 164, 14, 117, 50, 174];
+    /// `append_batch`
+    pub const APPEND_BATCH: [u8; 8] = [120, 189, 39, 45, 25, 212, 144, 174];
+    /// `insert_indexed_leaf`
+    pub const INSERT_INDEXED_LEAF: [u8; 8] = [154, 184, 228, 182, 223, 90, 187, 193];
+    /// `migrate_tree`
+    pub const MIGRATE_TREE: [u8; 8] = [4, 198, 44, 93, 214, 159, 85, 108];
+    /// `close_empty_tree`
+    pub const CLOSE_EMPTY_TREE: [u8; 8] = [50, 14, 219, 107, 78, 103, 16, 103];
+}
+
+/// Replacement of the leaf at `index`, argument of `replace_leaves`
+#[derive(BorshSerialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LeafUpdate {
+    /// Index of the leaf
+    pub index: u32,
+    /// Current value of the leaf
+    pub previous_leaf: Node,
+    /// New value of the leaf
+    pub new_leaf: Node,
+}
+
+/// Borsh encoding of an indexed leaf, argument of the indexed tree
+/// instructions
+fn indexed_leaf_args(leaf: &IndexedLeaf) -> (Node, u32, Node) {
+    (leaf.value, leaf.next_index, leaf.next_value)
+}
+
+fn instruction_data<T: BorshSerialize>(discriminator: [u8; 8], args: &T) -> Vec<u8> {
+    let mut data = discriminator.to_vec();
+    borsh::to_writer(&mut data, args).unwrap();
+    data
+}
+
+/// Proof nodes are passed as the remaining accounts of the instruction
+fn proof_accounts(proof: &[Node]) -> impl Iterator<Item = AccountMeta> + '_ {
+    proof
+        .iter()
+        .map(|node| AccountMeta::new_readonly(Pubkey::new_from_array(*node), false))
+}
+
+/// Accounts of the instructions initializing or modifying a tree
+fn tree_accounts(merkle_tree: &Pubkey, authority: &Pubkey) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*merkle_tree, false),
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new_readonly(noop::id(), false),
+    ]
+}
+
+/// Creates an `init_empty_merkle_tree` instruction, initializing an empty
+/// Keccak tree.
+///
+/// The tree account must be allocated, zeroed and owned by the program, with
+/// the size given by
+/// [get_concurrent_merkle_tree_account_size](crate::state::get_concurrent_merkle_tree_account_size),
+/// in a previous instruction of the same transaction.
+///
+/// Accounts expected by this instruction:
+///   0. `[writable]` Merkle tree
+///   1. `[signer]` Tree authority
+///   2. `[]` Noop program
+pub fn init_empty_merkle_tree(
+    merkle_tree: &Pubkey,
+    authority: &Pubkey,
+    max_depth: u32,
+    max_buffer_size: u32,
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: tree_accounts(merkle_tree, authority),
+        data: instruction_data(
+            discriminator::INIT_EMPTY_MERKLE_TREE,
+            &(max_depth, max_buffer_size),
+        ),
+    }
+}
+
+/// Creates an `init_empty_merkle_tree_with_hash_type` instruction,
+/// initializing an empty tree using the given hash function.
+///
+/// Accounts expected by this instruction:
+///   0. `[writable]` Merkle tree
+///   1. `[signer]` Tree authority
+///   2. `[]` Noop program
+pub fn init_empty_merkle_tree_with_hash_type(
+    merkle_tree: &Pubkey,
+    authority: &Pubkey,
+    max_depth: u32,
+    max_buffer_size: u32,
+    hash_type: HashType,
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: tree_accounts(merkle_tree, authority),
+        data: instruction_data(
+            discriminator::INIT_EMPTY_MERKLE_TREE_WITH_HASH_TYPE,
+            &(max_depth, max_buffer_size, hash_type),
+        ),
+    }
+}
+
+/// Creates an `init_indexed_merkle_tree` instruction, initializing an
+/// indexed tree using the given hash function, which starts with the default
+/// [IndexedLeaf] at index 0.
+///
+/// Accounts expected by this instruction:
+///   0. `[writable]` Merkle tree
+///   1. `[signer]` Tree authority
+///   2. `[]` Noop program
+pub fn init_indexed_merkle_tree(
+    merkle_tree: &Pubkey,
+    authority: &Pubkey,
+    max_depth: u32,
+    max_buffer_size: u32,
+    hash_type: HashType,
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: tree_accounts(merkle_tree, authority),
+        data: instruction_data(
+            discriminator::INIT_INDEXED_MERKLE_TREE,
+            &(max_depth, max_buffer_size, hash_type),
+        ),
+    }
+}
+
+/// Creates a `prepare_batch_merkle_tree` instruction, preparing a tree to be
+/// initialized with a root computed off-chain.
+///
+/// Accounts expected by this instruction:
+///   0. `[writable]` Merkle tree
+///   1. `[signer]` Tree authority
+///   2. `[]` Noop program
+pub fn prepare_batch_merkle_tree(
+    merkle_tree: &Pubkey,
+    authority: &Pubkey,
+    max_depth: u32,
+    max_buffer_size: u32,
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: tree_accounts(merkle_tree, authority),
+        data: instruction_data(
+            discriminator::PREPARE_BATCH_MERKLE_TREE,
+            &(max_depth, max_buffer_size),
+        ),
+    }
+}
+
+/// Creates a `prepare_batch_merkle_tree_with_hash_type` instruction,
+/// preparing a tree using the given hash function to be initialized with a
+/// root computed off-chain.
+///
+/// Accounts expected by this instruction:
+///   0. `[writable]` Merkle tree
+///   1. `[signer]` Tree authority
+///   2. `[]` Noop program
+pub fn prepare_batch_merkle_tree_with_hash_type(
+    merkle_tree: &Pubkey,
+    authority: &Pubkey,
+    max_depth: u32,
+    max_buffer_size: u32,
+    hash_type: HashType,
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: tree_accounts(merkle_tree, authority),
+        data: instruction_data(
+            discriminator::PREPARE_BATCH_MERKLE_TREE_WITH_HASH_TYPE,
+            &(max_depth, max_buffer_size, hash_type),
+        ),
+    }
+}
+
+/// Creates an `append_canopy_nodes` instruction, setting the nodes of the
+/// lowest level of the canopy of a prepared tree from `start_index`.
+///
+/// Accounts expected by this instruction:
+///   0. `[writable]` Merkle tree
+///   1. `[signer]` Tree authority
+///   2. `[]` Noop program
+pub fn append_canopy_nodes(
+    merkle_tree: &Pubkey,
+    authority: &Pubkey,
+    start_index: u32,
+    canopy_nodes: Vec<Node>,
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: tree_accounts(merkle_tree, authority),
+        data: instruction_data(
+            discriminator::APPEND_CANOPY_NODES,
+            &(start_index, canopy_nodes),
+        ),
+    }
+}
+
+/// Creates an `init_prepared_tree_with_root` instruction, initializing a
+/// prepared tree with a root and the proof of its rightmost leaf.
+///
+/// Accounts expected by this instruction:
+///   0. `[writable]` Merkle tree
+///   1. `[signer]` Tree authority
+///   2. `[]` Noop program
+///   3. ..3+N `[]` Proof of the rightmost leaf
+pub fn init_prepared_tree_with_root(
+    merkle_tree: &Pubkey,
+    authority: &Pubkey,
+    root: Node,
+    rightmost_leaf: Node,
+    rightmost_index: u32,
+    proof: &[Node],
+) -> Instruction {
+    let mut accounts = tree_accounts(merkle_tree, authority);
+    accounts.extend(proof_accounts(proof));
+    Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: instruction_data(
+            discriminator::INIT_PREPARED_TREE_WITH_ROOT,
+            &(root, rightmost_leaf, rightmost_index),
+        ),
+    }
+}
+
+/// Creates a `replace_leaf` instruction, replacing the leaf at `index` of a
+/// tree whose root was `root` when the proof was computed.
+///
+/// Accounts expected by this instruction:
+///   0. `[writable]` Merkle tree
+///   1. `[signer]` Tree authority
+///   2. `[]` Noop program
+///   3. ..3+N `[]` Proof of the leaf
+pub fn replace_leaf(
+    merkle_tree: &Pubkey,
+    authority: &Pubkey,
+    root: Node,
+    previous_leaf: Node,
+    new_leaf: Node,
+    index: u32,
+    proof: &[Node],
+) -> Instruction {
+    let mut accounts = tree_accounts(merkle_tree, authority);
+    accounts.extend(proof_accounts(proof));
+    Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: instruction_data(
+            discriminator::REPLACE_LEAF,
+            &(root, previous_leaf, new_leaf, index),
+        ),
+    }
+}
+
+/// Creates a `replace_leaves` instruction, replacing several leaves sorted by
+/// strictly increasing index.
+///
+/// Accounts expected by this instruction:
+///   0. `[writable]` Merkle tree
+///   1. `[signer]` Tree authority
+///   2. `[]` Noop program
+///   3. ..3+N `[]` Multiproof of the leaves, level by level from the leaves
+pub fn replace_leaves(
+    merkle_tree: &Pubkey,
+    authority: &Pubkey,
+    root: Node,
+    leaves: Vec<LeafUpdate>,
+    proof: &[Node],
+) -> Instruction {
+    let mut accounts = tree_accounts(merkle_tree, authority);
+    accounts.extend(proof_accounts(proof));
+    Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: instruction_data(discriminator::REPLACE_LEAVES, &(root, leaves)),
+    }
+}
+
+/// Creates a `transfer_authority` instruction.
+///
+/// Accounts expected by this instruction:
+///   0. `[writable]` Merkle tree
+///   1. `[signer]` Tree authority
+pub fn transfer_authority(
+    merkle_tree: &Pubkey,
+    authority: &Pubkey,
+    new_authority: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*merkle_tree, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+        data: instruction_data(discriminator::TRANSFER_AUTHORITY, new_authority),
+    }
+}
+
+/// Creates a `verify_leaf` instruction, failing if `leaf` isn't at `index` in
+/// the tree.
+///
+/// Accounts expected by this instruction:
+///   0. `[]` Merkle tree
+///   1. ..1+N `[]` Proof of the leaf
+pub fn verify_leaf(
+    merkle_tree: &Pubkey,
+    root: Node,
+    leaf: Node,
+    index: u32,
+    proof: &[Node],
+) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(*merkle_tree, false)];
+    accounts.extend(proof_accounts(proof));
+    Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: instruction_data(discriminator::VERIFY_LEAF, &(root, leaf, index)),
+    }
+}
+
+/// Creates a `verify_non_membership` instruction, failing if `low_leaf`, at
+/// `low_leaf_index` in the indexed tree, doesn't prove that `value` isn't in
+/// the tree.
+///
+/// Accounts expected by this instruction:
+///   0. `[]` Merkle tree
+///   1. ..1+N `[]` Proof of the low leaf
+pub fn verify_non_membership(
+    merkle_tree: &Pubkey,
+    root: Node,
+    value: Node,
+    low_leaf: &IndexedLeaf,
+    low_leaf_index: u32,
+    proof: &[Node],
+) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(*merkle_tree, false)];
+    accounts.extend(proof_accounts(proof));
+    Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: instruction_data(
+            discriminator::VERIFY_NON_MEMBERSHIP,
+            &(root, value, indexed_leaf_args(low_leaf), low_leaf_index),
+        ),
+    }
+}
+
+/// Creates an `append` instruction, appending a leaf to the right of the
+/// tree.
+///
+/// Accounts expected by this instruction:
+///   0. `[writable]` Merkle tree
+///   1. `[signer]` Tree authority
+///   2. `[]` Noop program
+pub fn append(merkle_tree: &Pubkey, authority: &Pubkey, leaf: Node) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: tree_accounts(merkle_tree, authority),
+        data: instruction_data(discriminator::APPEND, &leaf),
+    }
+}
+
+/// Creates an `insert_or_append` instruction, setting the leaf at `index` if
+/// it is empty, or appending it otherwise.
+///
+/// Accounts expected by this instruction:
+///   0. `[writable]` Merkle tree
+///   1. `[signer]` Tree authority
+///   2. `[]` Noop program
+///   3. ..3+N `[]` Proof of the leaf at `index`
+pub fn insert_or_append(
+    merkle_tree: &Pubkey,
+    authority: &Pubkey,
+    root: Node,
+    leaf: Node,
+    index: u32,
+    proof: &[Node],
+) -> Instruction {
+    let mut accounts = tree_accounts(merkle_tree, authority);
+    accounts.extend(proof_accounts(proof));
+    Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: instruction_data(discriminator::INSERT_OR_APPEND, &(root, leaf, index)),
+    }
+}
+
+/// Creates an `append_subtree` instruction, appending a subtree of `2^h`
+/// leaves, starting at a multiple of `2^h`, to the right of the tree.
+///
+/// The subtree must fit below the canopy of the tree.
+///
+/// Accounts expected by this instruction:
+///   0. `[writable]` Merkle tree
+///   1. `[signer]` Tree authority
+///   2. `[]` Noop program
+///   3. ..3+h `[]` Proof of the last leaf of the subtree within the subtree
+pub fn append_subtree(
+    merkle_tree: &Pubkey,
+    authority: &Pubkey,
+    subtree_root: Node,
+    rightmost_leaf: Node,
+    proof: &[Node],
+) -> Instruction {
+    let mut accounts = tree_accounts(merkle_tree, authority);
+    accounts.extend(proof_accounts(proof));
+    Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: instruction_data(
+            discriminator::APPEND_SUBTREE,
+            &(subtree_root, rightmost_leaf),
+        ),
+    }
+}
+
+/// Creates an `append_batch` instruction, appending several leaves to the
+/// right of the tree.
+///
+/// Accounts expected by this instruction:
+///   0. `[writable]` Merkle tree
+///   1. `[signer]` Tree authority
+///   2. `[]` Noop program
+pub fn append_batch(merkle_tree: &Pubkey, authority: &Pubkey, leaves: Vec<Node>) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: tree_accounts(merkle_tree, authority),
+        data: instruction_data(discriminator::APPEND_BATCH, &leaves),
+    }
+}
+
+/// Creates an `insert_indexed_leaf` instruction, inserting `value` in the
+/// indexed tree after `low_leaf`, the leaf at `low_leaf_index` holding the
+/// largest value of the tree smaller than `value`.
+///
+/// Accounts expected by this instruction:
+///   0. `[writable]` Merkle tree
+///   1. `[signer]` Tree authority
+///   2. `[]` Noop program
+///   3. ..3+N `[]` Proof of the low leaf
+pub fn insert_indexed_leaf(
+    merkle_tree: &Pubkey,
+    authority: &Pubkey,
+    root: Node,
+    value: Node,
+    low_leaf: &IndexedLeaf,
+    low_leaf_index: u32,
+    proof: &[Node],
+) -> Instruction {
+    let mut accounts = tree_accounts(merkle_tree, authority);
+    accounts.extend(proof_accounts(proof));
+    Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: instruction_data(
+            discriminator::INSERT_INDEXED_LEAF,
+            &(root, value, indexed_leaf_args(low_leaf), low_leaf_index),
+        ),
+    }
+}
+
+/// Creates a `migrate_tree` instruction, initializing `new_merkle_tree` as a
+/// tree of depth `max_depth` whose leftmost subtree is `merkle_tree`, and
+/// marking `merkle_tree` read-only.
+///
+/// The new tree account must be allocated, zeroed and owned by the program in
+/// a previous instruction of the same transaction. Its canopy can't be deeper
+/// than the one of `merkle_tree`, counting from the leaves. The proofs of the
+/// leaves in the new tree are given by
+/// [extend_proof](spl_concurrent_merkle_tree::hash::extend_proof).
+///
+/// Accounts expected by this instruction:
+///   0. `[writable]` Merkle tree
+///   1. `[writable]` New merkle tree
+///   2. `[signer]` Tree authority
+///   3. `[]` Noop program
+///   4. ..4+N `[]` Proof of the rightmost leaf of the merkle tree
+#[allow(clippy::too_many_arguments)]
+pub fn migrate_tree(
+    merkle_tree: &Pubkey,
+    new_merkle_tree: &Pubkey,
+    authority: &Pubkey,
+    root: Node,
+    rightmost_leaf: Node,
+    max_depth: u32,
+    max_buffer_size: u32,
+    proof: &[Node],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*merkle_tree, false),
+        AccountMeta::new(*new_merkle_tree, false),
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new_readonly(noop::id(), false),
+    ];
+    accounts.extend(proof_accounts(proof));
+    Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: instruction_data(
+            discriminator::MIGRATE_TREE,
+            &(root, rightmost_leaf, max_depth, max_buffer_size),
+        ),
+    }
+}
+
+/// Creates a `close_empty_tree` instruction, closing a tree without any
+/// non-empty leaf and sending its lamports to `recipient`.
+///
+/// Accounts expected by this instruction:
+///   0. `[writable]` Merkle tree
+///   1. `[signer]` Tree authority
+///   2. `[writable]` Recipient
+pub fn close_empty_tree(
+    merkle_tree: &Pubkey,
+    authority: &Pubkey,
+    recipient: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*merkle_tree, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*recipient, false),
+        ],
+        data: instruction_data(discriminator::CLOSE_EMPTY_TREE, &()),
+    }
+}
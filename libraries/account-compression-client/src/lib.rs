@@ -0,0 +1,21 @@
+//! Rust client of the SPL Account Compression program
+//!
+//! The program is written with Anchor, so its instructions are encoded as the
+//! 8-byte Anchor discriminator of the instruction followed by its borsh
+//! serialized arguments. This crate builds them without depending on Anchor,
+//! and deserializes ConcurrentMerkleTree accounts whose depth and buffer size
+//! are only known at runtime.
+
+#![deny(missing_docs)]
+
+pub mod error;
+pub mod instruction;
+pub mod state;
+
+solana_program::declare_id!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+
+/// Program id of the SPL Noop program, through which the program emits its
+/// events
+pub mod noop {
+    solana_program::declare_id!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+}
@@ -0,0 +1,387 @@
+//! State of ConcurrentMerkleTree accounts
+
+use {
+    crate::error::AccountCompressionClientError,
+    borsh::{BorshDeserialize, BorshSerialize},
+    solana_program::pubkey::Pubkey,
+    spl_concurrent_merkle_tree::{
+        changelog::ChangeLog,
+        concurrent_merkle_tree::ConcurrentMerkleTree,
+        concurrent_merkle_tree_view::{ConcurrentMerkleTreeView, DynamicChangeLog, DynamicPath},
+        node::Node,
+        path::Path,
+    },
+    std::mem::size_of,
+};
+
+/// Size of the header of a ConcurrentMerkleTree account
+pub const CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1: usize = 2 + 54;
+
+/// Commonly used (max_depth, max_buffer_size) pairs. The program supports any
+/// max depth up to 30 with any power of 2 max buffer size.
+pub const ALL_DEPTH_SIZE_PAIRS: &[(u32, u32)] = &[
+    (3, 8),
+    (5, 8),
+    (6, 16),
+    (7, 16),
+    (8, 16),
+    (9, 16),
+    (10, 32),
+    (11, 32),
+    (12, 32),
+    (13, 32),
+    (14, 64),
+    (14, 256),
+    (14, 1024),
+    (14, 2048),
+    (15, 64),
+    (16, 64),
+    (17, 64),
+    (18, 64),
+    (19, 64),
+    (20, 64),
+    (20, 256),
+    (20, 1024),
+    (20, 2048),
+    (24, 64),
+    (24, 256),
+    (24, 512),
+    (24, 1024),
+    (24, 2048),
+    (26, 512),
+    (26, 1024),
+    (26, 2048),
+    (30, 512),
+    (30, 1024),
+    (30, 2048),
+];
+
+/// Type of a ConcurrentMerkleTree account
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompressionAccountType {
+    /// Uninitialized
+    Uninitialized,
+    /// SPL ConcurrentMerkleTree data structure, may include a Canopy
+    ConcurrentMerkleTree,
+}
+
+/// Hash function used to compute the nodes of a tree
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HashType {
+    /// Keccak-256, used by all trees created before the hash type was recorded
+    #[default]
+    Keccak,
+    /// SHA-256, for trees whose roots are verified by EVM contracts
+    Sha256,
+    /// Poseidon over BN254, for trees whose roots are verified in ZK circuits
+    Poseidon,
+}
+
+/// Header of a ConcurrentMerkleTree account
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConcurrentMerkleTreeHeader {
+    /// Account type
+    pub account_type: CompressionAccountType,
+    /// Versioned header
+    pub header: ConcurrentMerkleTreeHeaderData,
+}
+
+/// Versioned data of the header of a ConcurrentMerkleTree account
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConcurrentMerkleTreeHeaderData {
+    /// Version 1
+    V1(ConcurrentMerkleTreeHeaderDataV1),
+}
+
+/// Data of the header of a ConcurrentMerkleTree account, version 1
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConcurrentMerkleTreeHeaderDataV1 {
+    /// Number of changelogs stored on-chain
+    pub max_buffer_size: u32,
+    /// Depth of the tree
+    pub max_depth: u32,
+    /// Authority allowed to modify the tree
+    pub authority: Pubkey,
+    /// Slot in which the tree was created
+    pub creation_slot: u64,
+    /// Whether the tree was prepared to be initialized with a root
+    pub is_batch_initialized: bool,
+    /// Hash function used to compute the nodes of the tree
+    pub hash_type: HashType,
+    /// Whether the tree was migrated into a deeper tree and is read-only
+    pub is_read_only: bool,
+    /// Whether the tree is an indexed tree, whose leaves can only be added
+    /// with `insert_indexed_leaf`
+    pub is_indexed: bool,
+    /// Padding keeping the tree 8-byte aligned
+    pub _padding: [u8; 2],
+}
+
+impl ConcurrentMerkleTreeHeader {
+    fn v1(&self) -> &ConcurrentMerkleTreeHeaderDataV1 {
+        match &self.header {
+            ConcurrentMerkleTreeHeaderData::V1(header) => header,
+        }
+    }
+
+    /// Returns the depth of the tree
+    pub fn get_max_depth(&self) -> u32 {
+        self.v1().max_depth
+    }
+
+    /// Returns the number of changelogs stored on-chain
+    pub fn get_max_buffer_size(&self) -> u32 {
+        self.v1().max_buffer_size
+    }
+
+    /// Returns the authority allowed to modify the tree
+    pub fn get_authority(&self) -> Pubkey {
+        self.v1().authority
+    }
+
+    /// Returns the slot in which the tree was created
+    pub fn get_creation_slot(&self) -> u64 {
+        self.v1().creation_slot
+    }
+
+    /// Returns whether the tree was prepared to be initialized with a root
+    pub fn get_is_batch_initialized(&self) -> bool {
+        self.v1().is_batch_initialized
+    }
+
+    /// Returns the hash function used to compute the nodes of the tree
+    pub fn get_hash_type(&self) -> HashType {
+        self.v1().hash_type
+    }
+
+    /// Returns whether the tree was migrated into a deeper tree and is
+    /// read-only
+    pub fn get_is_read_only(&self) -> bool {
+        self.v1().is_read_only
+    }
+
+    /// Returns whether the tree is an indexed tree
+    pub fn get_is_indexed(&self) -> bool {
+        self.v1().is_indexed
+    }
+}
+
+/// Change of a tree, with the nodes of a runtime depth
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChangeLogData {
+    /// Root of the tree after the change
+    pub root: Node,
+    /// Nodes of the changed path, from the leaf up
+    pub path: Vec<Node>,
+    /// Index of the changed leaf
+    pub index: u32,
+}
+
+impl<const MAX_DEPTH: usize> From<&ChangeLog<MAX_DEPTH>> for ChangeLogData {
+    fn from(change_log: &ChangeLog<MAX_DEPTH>) -> Self {
+        Self {
+            root: change_log.root,
+            path: change_log.path.to_vec(),
+            index: change_log.index,
+        }
+    }
+}
+
+impl From<DynamicChangeLog> for ChangeLogData {
+    fn from(change_log: DynamicChangeLog) -> Self {
+        Self {
+            root: change_log.root,
+            path: change_log.path,
+            index: change_log.index,
+        }
+    }
+}
+
+/// Proof of a leaf, with the nodes of a runtime depth
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PathData {
+    /// Siblings of the path of the leaf, from the leaf up
+    pub proof: Vec<Node>,
+    /// Value of the leaf
+    pub leaf: Node,
+    /// Index of the leaf
+    pub index: u32,
+}
+
+impl<const MAX_DEPTH: usize> From<&Path<MAX_DEPTH>> for PathData {
+    fn from(path: &Path<MAX_DEPTH>) -> Self {
+        Self {
+            proof: path.proof.to_vec(),
+            leaf: path.leaf,
+            index: path.index,
+        }
+    }
+}
+
+impl From<DynamicPath> for PathData {
+    fn from(path: DynamicPath) -> Self {
+        Self {
+            proof: path.proof,
+            leaf: path.leaf,
+            index: path.index,
+        }
+    }
+}
+
+/// ConcurrentMerkleTree with a runtime depth and buffer size
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConcurrentMerkleTreeData {
+    /// Number of changes applied to the tree
+    pub sequence_number: u64,
+    /// Index of the most recent changelog
+    pub active_index: u64,
+    /// Number of changelogs in use
+    pub buffer_size: u64,
+    /// Buffer of the most recent changelogs
+    pub change_logs: Vec<ChangeLogData>,
+    /// Proof of the rightmost leaf of the tree
+    pub rightmost_proof: PathData,
+}
+
+impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>
+    From<&ConcurrentMerkleTree<MAX_DEPTH, MAX_BUFFER_SIZE>> for ConcurrentMerkleTreeData
+{
+    fn from(tree: &ConcurrentMerkleTree<MAX_DEPTH, MAX_BUFFER_SIZE>) -> Self {
+        Self {
+            sequence_number: tree.sequence_number,
+            active_index: tree.active_index,
+            buffer_size: tree.buffer_size,
+            change_logs: tree.change_logs.iter().map(Into::into).collect(),
+            rightmost_proof: (&tree.rightmost_proof).into(),
+        }
+    }
+}
+
+impl ConcurrentMerkleTreeData {
+    /// Returns the current root of the tree
+    pub fn get_root(&self) -> Node {
+        self.change_logs[self.active_index as usize].root
+    }
+}
+
+impl<T: AsRef<[u8]>> From<&ConcurrentMerkleTreeView<T>> for ConcurrentMerkleTreeData {
+    fn from(tree: &ConcurrentMerkleTreeView<T>) -> Self {
+        Self {
+            sequence_number: tree.get_seq(),
+            active_index: tree.get_active_index(),
+            buffer_size: tree.get_buffer_size(),
+            change_logs: (0..tree.get_max_buffer_size() as usize)
+                .filter_map(|i| tree.get_change_log_at(i))
+                .map(Into::into)
+                .collect(),
+            rightmost_proof: tree.get_rightmost_proof().into(),
+        }
+    }
+}
+
+/// Returns the size of a ConcurrentMerkleTree, without header nor canopy
+pub fn get_merkle_tree_size(
+    max_depth: u32,
+    max_buffer_size: u32,
+) -> Result<usize, AccountCompressionClientError> {
+    ConcurrentMerkleTreeView::<&[u8]>::size(max_depth, max_buffer_size).map_err(|_| {
+        AccountCompressionClientError::UnsupportedTreeSize {
+            max_depth,
+            max_buffer_size,
+        }
+    })
+}
+
+/// Returns the size of a canopy caching the `canopy_depth` upper levels of a
+/// tree, below its root
+pub fn get_canopy_size(canopy_depth: u32) -> usize {
+    ((1 << (canopy_depth + 1)) - 2) * size_of::<Node>()
+}
+
+/// Returns the depth of the canopy stored in `canopy_size` bytes
+pub fn get_canopy_depth(canopy_size: usize) -> Result<u32, AccountCompressionClientError> {
+    let canopy_nodes = canopy_size / size_of::<Node>() + 2;
+    if canopy_size % size_of::<Node>() != 0 || !canopy_nodes.is_power_of_two() {
+        return Err(AccountCompressionClientError::InvalidAccountData);
+    }
+    Ok(canopy_nodes.trailing_zeros() - 1)
+}
+
+/// Returns the size of a ConcurrentMerkleTree account, which must be allocated
+/// before initializing the tree
+pub fn get_concurrent_merkle_tree_account_size(
+    max_depth: u32,
+    max_buffer_size: u32,
+    canopy_depth: u32,
+) -> Result<usize, AccountCompressionClientError> {
+    if canopy_depth > max_depth {
+        return Err(AccountCompressionClientError::InvalidCanopyDepth {
+            canopy_depth,
+            max_depth,
+        });
+    }
+    Ok(CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1
+        + get_merkle_tree_size(max_depth, max_buffer_size)?
+        + get_canopy_size(canopy_depth))
+}
+
+/// Deserialized ConcurrentMerkleTree account
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConcurrentMerkleTreeAccount {
+    /// Header of the account
+    pub header: ConcurrentMerkleTreeHeader,
+    /// Tree
+    pub tree: ConcurrentMerkleTreeData,
+    /// Nodes of the upper levels of the tree, below the root, level by level
+    pub canopy: Vec<Node>,
+}
+
+impl ConcurrentMerkleTreeAccount {
+    /// Deserializes the data of a ConcurrentMerkleTree account
+    pub fn from_bytes(data: &[u8]) -> Result<Self, AccountCompressionClientError> {
+        if data.len() < CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1 {
+            return Err(AccountCompressionClientError::InvalidAccountData);
+        }
+        let (header_bytes, rest) = data.split_at(CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1);
+        let header = ConcurrentMerkleTreeHeader::try_from_slice(header_bytes)
+            .map_err(|_| AccountCompressionClientError::InvalidAccountData)?;
+        if header.account_type != CompressionAccountType::ConcurrentMerkleTree {
+            return Err(AccountCompressionClientError::InvalidAccountData);
+        }
+
+        let max_depth = header.get_max_depth();
+        let max_buffer_size = header.get_max_buffer_size();
+        let tree_size = get_merkle_tree_size(max_depth, max_buffer_size)?;
+        if rest.len() < tree_size {
+            return Err(AccountCompressionClientError::InvalidAccountData);
+        }
+        let (tree_bytes, canopy_bytes) = rest.split_at(tree_size);
+        // The view reads the tree in place, so the account data doesn't need
+        // to be 8-byte aligned off-chain
+        let tree = ConcurrentMerkleTreeView::<_>::new(tree_bytes, max_depth, max_buffer_size)
+            .map(|tree| ConcurrentMerkleTreeData::from(&tree))
+            .map_err(|_| AccountCompressionClientError::InvalidAccountData)?;
+
+        get_canopy_depth(canopy_bytes.len())?;
+        let canopy = canopy_bytes
+            .chunks_exact(size_of::<Node>())
+            .map(|node| node.try_into().unwrap())
+            .collect();
+
+        Ok(Self {
+            header,
+            tree,
+            canopy,
+        })
+    }
+
+    /// Returns the current root of the tree
+    pub fn get_root(&self) -> Node {
+        self.tree.get_root()
+    }
+
+    /// Returns the number of upper levels of the tree cached in the canopy
+    pub fn get_canopy_depth(&self) -> u32 {
+        (self.canopy.len() + 2).trailing_zeros() - 1
+    }
+}
@@ -0,0 +1,421 @@
+#![allow(clippy::arithmetic_side_effects)]
+use {
+    solana_program::{hash::hash, instruction::AccountMeta, pubkey::Pubkey},
+    spl_account_compression_client::{
+        error::AccountCompressionClientError,
+        instruction::{self, discriminator, LeafUpdate},
+        noop,
+        state::{
+            get_canopy_depth, get_concurrent_merkle_tree_account_size, CompressionAccountType,
+            ConcurrentMerkleTreeAccount, ConcurrentMerkleTreeHeader,
+            ConcurrentMerkleTreeHeaderData, ConcurrentMerkleTreeHeaderDataV1, HashType,
+            ALL_DEPTH_SIZE_PAIRS, CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1,
+        },
+    },
+    spl_concurrent_merkle_tree::{
+        concurrent_merkle_tree::ConcurrentMerkleTree,
+        indexed_leaf::IndexedLeaf,
+        node::{Node, EMPTY},
+    },
+    spl_merkle_tree_reference::MerkleTree,
+};
+
+#[test]
+fn test_discriminators() {
+    for (name, discriminator) in [
+        (
+            "init_empty_merkle_tree",
+            discriminator::INIT_EMPTY_MERKLE_TREE,
+        ),
+        (
+            "init_empty_merkle_tree_with_hash_type",
+            discriminator::INIT_EMPTY_MERKLE_TREE_WITH_HASH_TYPE,
+        ),
+        (
+            "init_indexed_merkle_tree",
+            discriminator::INIT_INDEXED_MERKLE_TREE,
+        ),
+        (
+            "prepare_batch_merkle_tree",
+            discriminator::PREPARE_BATCH_MERKLE_TREE,
+        ),
+        (
+            "prepare_batch_merkle_tree_with_hash_type",
+            discriminator::PREPARE_BATCH_MERKLE_TREE_WITH_HASH_TYPE,
+        ),
+        ("append_canopy_nodes", discriminator::APPEND_CANOPY_NODES),
+        (
+            "init_prepared_tree_with_root",
+            discriminator::INIT_PREPARED_TREE_WITH_ROOT,
+        ),
+        ("replace_leaf", discriminator::REPLACE_LEAF),
+        ("replace_leaves", discriminator::REPLACE_LEAVES),
+        ("transfer_authority", discriminator::TRANSFER_AUTHORITY),
+        ("verify_leaf", discriminator::VERIFY_LEAF),
+        (
+            "verify_non_membership",
+            discriminator::VERIFY_NON_MEMBERSHIP,
+        ),
+        ("append", discriminator::APPEND),
+        ("insert_or_append", discriminator::INSERT_OR_APPEND),
+        ("append_subtree", discriminator::APPEND_SUBTREE),
+        ("append_batch", discriminator::APPEND_BATCH),
+        ("insert_indexed_leaf", discriminator::INSERT_INDEXED_LEAF),
+        ("migrate_tree", discriminator::MIGRATE_TREE),
+        ("close_empty_tree", discriminator::CLOSE_EMPTY_TREE),
+    ] {
+        assert_eq!(
+            hash(format!("global:{}", name).as_bytes()).to_bytes()[..8],
+            discriminator,
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn test_replace_leaf() {
+    let merkle_tree = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let proof = [[1; 32], [2; 32]];
+    let instruction = instruction::replace_leaf(
+        &merkle_tree,
+        &authority,
+        [3; 32],
+        [4; 32],
+        [5; 32],
+        6,
+        &proof,
+    );
+
+    assert_eq!(instruction.program_id, spl_account_compression_client::id());
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            AccountMeta::new(merkle_tree, false),
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new_readonly(noop::id(), false),
+            AccountMeta::new_readonly(Pubkey::new_from_array([1; 32]), false),
+            AccountMeta::new_readonly(Pubkey::new_from_array([2; 32]), false),
+        ]
+    );
+    let mut data = discriminator::REPLACE_LEAF.to_vec();
+    data.extend([3; 32]);
+    data.extend([4; 32]);
+    data.extend([5; 32]);
+    data.extend(6u32.to_le_bytes());
+    assert_eq!(instruction.data, data);
+}
+
+#[test]
+fn test_migrate_tree() {
+    let merkle_tree = Pubkey::new_unique();
+    let new_merkle_tree = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let instruction = instruction::migrate_tree(
+        &merkle_tree,
+        &new_merkle_tree,
+        &authority,
+        [1; 32],
+        [2; 32],
+        20,
+        64,
+        &[[3; 32]],
+    );
+
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            AccountMeta::new(merkle_tree, false),
+            AccountMeta::new(new_merkle_tree, false),
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new_readonly(noop::id(), false),
+            AccountMeta::new_readonly(Pubkey::new_from_array([3; 32]), false),
+        ]
+    );
+    let mut data = discriminator::MIGRATE_TREE.to_vec();
+    data.extend([1; 32]);
+    data.extend([2; 32]);
+    data.extend(20u32.to_le_bytes());
+    data.extend(64u32.to_le_bytes());
+    assert_eq!(instruction.data, data);
+}
+
+#[test]
+fn test_indexed_tree_instructions() {
+    let merkle_tree = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let low_leaf = IndexedLeaf {
+        value: [2; 32],
+        next_index: 3,
+        next_value: [4; 32],
+    };
+    let mut args = vec![];
+    args.extend([1; 32]);
+    args.extend([5; 32]);
+    args.extend([2; 32]);
+    args.extend(3u32.to_le_bytes());
+    args.extend([4; 32]);
+    args.extend(6u32.to_le_bytes());
+
+    let instruction = instruction::insert_indexed_leaf(
+        &merkle_tree,
+        &authority,
+        [1; 32],
+        [5; 32],
+        &low_leaf,
+        6,
+        &[[7; 32]],
+    );
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            AccountMeta::new(merkle_tree, false),
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new_readonly(noop::id(), false),
+            AccountMeta::new_readonly(Pubkey::new_from_array([7; 32]), false),
+        ]
+    );
+    let mut data = discriminator::INSERT_INDEXED_LEAF.to_vec();
+    data.extend(&args);
+    assert_eq!(instruction.data, data);
+
+    let instruction =
+        instruction::verify_non_membership(&merkle_tree, [1; 32], [5; 32], &low_leaf, 6, &[]);
+    assert_eq!(
+        instruction.accounts,
+        vec![AccountMeta::new_readonly(merkle_tree, false)]
+    );
+    let mut data = discriminator::VERIFY_NON_MEMBERSHIP.to_vec();
+    data.extend(&args);
+    assert_eq!(instruction.data, data);
+
+    let instruction =
+        instruction::init_indexed_merkle_tree(&merkle_tree, &authority, 14, 64, HashType::Sha256);
+    let mut data = discriminator::INIT_INDEXED_MERKLE_TREE.to_vec();
+    data.extend(14u32.to_le_bytes());
+    data.extend(64u32.to_le_bytes());
+    data.push(1);
+    assert_eq!(instruction.data, data);
+}
+
+#[test]
+fn test_instruction_data() {
+    let merkle_tree = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+
+    let instruction = instruction::init_empty_merkle_tree_with_hash_type(
+        &merkle_tree,
+        &authority,
+        14,
+        64,
+        HashType::Poseidon,
+    );
+    let mut data = discriminator::INIT_EMPTY_MERKLE_TREE_WITH_HASH_TYPE.to_vec();
+    data.extend(14u32.to_le_bytes());
+    data.extend(64u32.to_le_bytes());
+    data.push(2);
+    assert_eq!(instruction.data, data);
+
+    let instruction = instruction::prepare_batch_merkle_tree_with_hash_type(
+        &merkle_tree,
+        &authority,
+        14,
+        64,
+        HashType::Sha256,
+    );
+    let mut data = discriminator::PREPARE_BATCH_MERKLE_TREE_WITH_HASH_TYPE.to_vec();
+    data.extend(14u32.to_le_bytes());
+    data.extend(64u32.to_le_bytes());
+    data.push(1);
+    assert_eq!(instruction.data, data);
+
+    let instruction =
+        instruction::append_canopy_nodes(&merkle_tree, &authority, 4, vec![[7; 32], [8; 32]]);
+    let mut data = discriminator::APPEND_CANOPY_NODES.to_vec();
+    data.extend(4u32.to_le_bytes());
+    data.extend(2u32.to_le_bytes());
+    data.extend([7; 32]);
+    data.extend([8; 32]);
+    assert_eq!(instruction.data, data);
+
+    let leaves = vec![LeafUpdate {
+        index: 9,
+        previous_leaf: [10; 32],
+        new_leaf: [11; 32],
+    }];
+    let instruction =
+        instruction::replace_leaves(&merkle_tree, &authority, [12; 32], leaves, &[[13; 32]]);
+    let mut data = discriminator::REPLACE_LEAVES.to_vec();
+    data.extend([12; 32]);
+    data.extend(1u32.to_le_bytes());
+    data.extend(9u32.to_le_bytes());
+    data.extend([10; 32]);
+    data.extend([11; 32]);
+    assert_eq!(instruction.data, data);
+    assert_eq!(instruction.accounts.len(), 4);
+
+    let instruction =
+        instruction::append_subtree(&merkle_tree, &authority, [14; 32], [15; 32], &[[16; 32]]);
+    let mut data = discriminator::APPEND_SUBTREE.to_vec();
+    data.extend([14; 32]);
+    data.extend([15; 32]);
+    assert_eq!(instruction.data, data);
+    assert_eq!(instruction.accounts.len(), 4);
+
+    let instruction = instruction::append_batch(&merkle_tree, &authority, vec![[17; 32], [18; 32]]);
+    let mut data = discriminator::APPEND_BATCH.to_vec();
+    data.extend(2u32.to_le_bytes());
+    data.extend([17; 32]);
+    data.extend([18; 32]);
+    assert_eq!(instruction.data, data);
+    assert_eq!(instruction.accounts.len(), 3);
+
+    let recipient = Pubkey::new_unique();
+    let instruction = instruction::close_empty_tree(&merkle_tree, &authority, &recipient);
+    assert_eq!(instruction.data, discriminator::CLOSE_EMPTY_TREE);
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            AccountMeta::new(merkle_tree, false),
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(recipient, false),
+        ]
+    );
+
+    let instruction = instruction::verify_leaf(&merkle_tree, [1; 32], [2; 32], 3, &[[4; 32]]);
+    assert_eq!(
+        instruction.accounts,
+        vec![
+            AccountMeta::new_readonly(merkle_tree, false),
+            AccountMeta::new_readonly(Pubkey::new_from_array([4; 32]), false),
+        ]
+    );
+}
+
+#[test]
+fn test_account_size() {
+    for &(max_depth, max_buffer_size) in ALL_DEPTH_SIZE_PAIRS {
+        // Changelogs and the rightmost proof both hold `max_depth + 1` nodes
+        // and 8 bytes of index and padding
+        let path_size = 32 * (max_depth as usize + 1) + 8;
+        let tree_size = 24 + (max_buffer_size as usize + 1) * path_size;
+        assert_eq!(
+            get_concurrent_merkle_tree_account_size(max_depth, max_buffer_size, 0).unwrap(),
+            CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1 + tree_size
+        );
+        assert_eq!(
+            get_concurrent_merkle_tree_account_size(max_depth, max_buffer_size, 3).unwrap(),
+            CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1 + tree_size + 14 * 32
+        );
+    }
+    assert_eq!(
+        get_concurrent_merkle_tree_account_size(14, 64, 0).unwrap(),
+        31_800
+    );
+
+    // Any depth up to 30 with any power of 2 buffer size is supported
+    assert_eq!(
+        get_concurrent_merkle_tree_account_size(14, 128, 0).unwrap(),
+        CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1 + 24 + 129 * (32 * 15 + 8)
+    );
+    for (max_depth, max_buffer_size) in [(31, 64), (14, 100)] {
+        assert_eq!(
+            get_concurrent_merkle_tree_account_size(max_depth, max_buffer_size, 0),
+            Err(AccountCompressionClientError::UnsupportedTreeSize {
+                max_depth,
+                max_buffer_size,
+            })
+        );
+    }
+    assert_eq!(
+        get_concurrent_merkle_tree_account_size(3, 8, 4),
+        Err(AccountCompressionClientError::InvalidCanopyDepth {
+            canopy_depth: 4,
+            max_depth: 3,
+        })
+    );
+    assert_eq!(get_canopy_depth(0), Ok(0));
+    assert_eq!(get_canopy_depth(62 * 32), Ok(5));
+    assert_eq!(
+        get_canopy_depth(3 * 32),
+        Err(AccountCompressionClientError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn test_deserialize_account() {
+    const MAX_DEPTH: usize = 14;
+    const MAX_BUFFER_SIZE: usize = 64;
+    const CANOPY_DEPTH: u32 = 3;
+
+    let authority = Pubkey::new_unique();
+    let mut tree = ConcurrentMerkleTree::<MAX_DEPTH, MAX_BUFFER_SIZE>::new();
+    let mut reference_tree = MerkleTree::new(&[EMPTY; 1 << MAX_DEPTH]);
+    tree.initialize().unwrap();
+    for i in 0..70u8 {
+        let leaf: Node = [i + 1; 32];
+        tree.append(leaf).unwrap();
+        reference_tree.add_leaf(leaf, i as usize);
+    }
+
+    let header = ConcurrentMerkleTreeHeader {
+        account_type: CompressionAccountType::ConcurrentMerkleTree,
+        header: ConcurrentMerkleTreeHeaderData::V1(ConcurrentMerkleTreeHeaderDataV1 {
+            max_buffer_size: MAX_BUFFER_SIZE as u32,
+            max_depth: MAX_DEPTH as u32,
+            authority,
+            creation_slot: 42,
+            is_batch_initialized: false,
+            hash_type: HashType::Keccak,
+            is_read_only: true,
+            is_indexed: false,
+            _padding: [0; 2],
+        }),
+    };
+    let mut data = borsh::to_vec(&header).unwrap();
+    data.extend_from_slice(bytemuck::bytes_of(&tree));
+    let canopy: Vec<Node> = (0..14u8).map(|i| [i; 32]).collect();
+    data.extend(canopy.concat());
+    assert_eq!(
+        data.len(),
+        get_concurrent_merkle_tree_account_size(MAX_DEPTH as u32, MAX_BUFFER_SIZE as u32, 3)
+            .unwrap()
+    );
+
+    let account = ConcurrentMerkleTreeAccount::from_bytes(&data).unwrap();
+    assert_eq!(account.header, header);
+    assert_eq!(account.header.get_authority(), authority);
+    assert_eq!(account.header.get_creation_slot(), 42);
+    assert!(account.header.get_is_read_only());
+    assert_eq!(account.get_root(), reference_tree.get_root());
+    assert_eq!(account.get_canopy_depth(), CANOPY_DEPTH);
+    assert_eq!(account.canopy, canopy);
+    assert_eq!(account.tree.sequence_number, 70);
+    assert_eq!(account.tree.buffer_size, MAX_BUFFER_SIZE as u64);
+    assert_eq!(account.tree.change_logs.len(), MAX_BUFFER_SIZE);
+    assert_eq!(account.tree.rightmost_proof.index, 70);
+    assert_eq!(account.tree.rightmost_proof.leaf, [70; 32]);
+    assert_eq!(
+        account.tree.rightmost_proof.proof,
+        reference_tree.get_proof_of_leaf(69)
+    );
+
+    // The canopy must hold whole levels of the tree
+    let mut invalid_data = data.clone();
+    invalid_data.extend([0; 32]);
+    assert_eq!(
+        ConcurrentMerkleTreeAccount::from_bytes(&invalid_data),
+        Err(AccountCompressionClientError::InvalidAccountData)
+    );
+    assert_eq!(
+        ConcurrentMerkleTreeAccount::from_bytes(&data[..data.len() / 2]),
+        Err(AccountCompressionClientError::InvalidAccountData)
+    );
+    let mut uninitialized_data = data;
+    uninitialized_data[0] = 0;
+    assert_eq!(
+        ConcurrentMerkleTreeAccount::from_bytes(&uninitialized_data),
+        Err(AccountCompressionClientError::InvalidAccountData)
+    );
+}
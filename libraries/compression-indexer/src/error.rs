@@ -0,0 +1,41 @@
+//! Error types
+
+use {solana_program::pubkey::Pubkey, thiserror::Error};
+
+/// Errors that may be returned by the indexer
+#[derive(Error, Debug)]
+pub enum IndexerError {
+    /// No event of the tree was indexed
+    #[error("Tree {0} is not indexed")]
+    UnknownTree(Pubkey),
+
+    /// Leaf index larger than the capacity of the tree
+    #[error("Leaf index {0} is out of bounds for the tree")]
+    LeafIndexOutOfBounds(u32),
+
+    /// Change log path doesn't match the depth of the tree
+    #[error("Change log path of length {path_len} doesn't match max depth {max_depth}")]
+    InvalidPathLength {
+        /// Number of nodes of the path, including the root
+        path_len: usize,
+        /// Max depth of the indexed tree
+        max_depth: u32,
+    },
+
+    /// Account data isn't a ConcurrentMerkleTree account
+    #[error("Invalid ConcurrentMerkleTree account data")]
+    InvalidAccountData,
+
+    /// Transaction message or inner instructions can't be decoded
+    #[error("Transaction can't be decoded")]
+    InvalidTransaction,
+
+    /// Stored data can't be (de)serialized
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] std::io::Error),
+
+    /// SQLite store error
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+}
@@ -0,0 +1,115 @@
+//! Events emitted by the SPL Account Compression program
+//!
+//! These types mirror the ones of the on-chain program, which depend on
+//! Anchor, and share their Borsh serialization.
+
+use {
+    borsh::{BorshDeserialize, BorshSerialize},
+    solana_program::pubkey::Pubkey,
+    spl_concurrent_merkle_tree::node::Node,
+};
+
+/// Event emitted through the noop program
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, Eq)]
+pub enum AccountCompressionEvent {
+    /// Nodes changed in a tree
+    ChangeLog(ChangeLogEvent),
+    /// Data logged by the program owning the tree
+    ApplicationData(ApplicationDataEvent),
+}
+
+/// Versioned change log event
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, Eq)]
+pub enum ChangeLogEvent {
+    /// Change of a single leaf
+    V1(ChangeLogEventV1),
+    /// Change of several leaves
+    V2(ChangeLogEventV2),
+}
+
+/// Change of a single leaf of a tree
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct ChangeLogEventV1 {
+    /// Public key of the ConcurrentMerkleTree
+    pub id: Pubkey,
+    /// Nodes of the path from the leaf to the root
+    pub path: Vec<PathNode>,
+    /// Sequence number of the change
+    pub seq: u64,
+    /// Index of the leaf
+    pub index: u32,
+}
+
+/// Change of several leaves of a tree
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct ChangeLogEventV2 {
+    /// Public key of the ConcurrentMerkleTree
+    pub id: Pubkey,
+    /// Paths of the modified leaves, in the order they were applied
+    pub paths: Vec<ChangeLogPath>,
+}
+
+/// Path of a leaf changed by a [ChangeLogEventV2]
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct ChangeLogPath {
+    /// Nodes of the path from the leaf to the root
+    pub path: Vec<PathNode>,
+    /// Sequence number of the change
+    pub seq: u64,
+    /// Index of the leaf
+    pub index: u32,
+}
+
+/// Node of a change log path
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PathNode {
+    /// Value of the node
+    pub node: Node,
+    /// Index of the node in the tree, 1 being the root
+    pub index: u32,
+}
+
+/// Versioned application data event
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, Eq)]
+pub enum ApplicationDataEvent {
+    /// Raw application data
+    V1(ApplicationDataEventV1),
+}
+
+/// Raw application data
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct ApplicationDataEventV1 {
+    /// Data serialized by the program owning the tree
+    pub application_data: Vec<u8>,
+}
+
+impl ChangeLogEvent {
+    /// Returns the public key of the changed tree
+    pub fn tree_id(&self) -> Pubkey {
+        match self {
+            Self::V1(event) => event.id,
+            Self::V2(event) => event.id,
+        }
+    }
+
+    /// Returns the paths of the changed leaves, in the order they were applied
+    pub fn paths(&self) -> Vec<ChangeLogPath> {
+        match self {
+            Self::V1(event) => vec![ChangeLogPath {
+                path: event.path.clone(),
+                seq: event.seq,
+                index: event.index,
+            }],
+            Self::V2(event) => event.paths.clone(),
+        }
+    }
+}
+
+impl ApplicationDataEvent {
+    /// Returns the data serialized by the program owning the tree
+    pub fn data(&self) -> &[u8] {
+        match self {
+            Self::V1(event) => &event.application_data,
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! Indexer of the trees
+
+use {
+    crate::{
+        error::IndexerError,
+        events::{AccountCompressionEvent, ChangeLogEvent, ChangeLogPath},
+        ingest::{events_from_logs, events_from_transaction},
+        store::TreeStore,
+        tree::{Gap, LeafData, LeafProof, StoredNode, TreeConfig, TreeState},
+    },
+    solana_program::pubkey::Pubkey,
+    solana_transaction_status_client_types::EncodedTransactionWithStatusMeta,
+};
+
+/// Rebuilds the trees changed by the ingested events in a [TreeStore]
+///
+/// `ApplicationData` events are attached, in order, to the leaves of the
+/// `ChangeLog` event which follows them in the same batch of events, which is
+/// how programs owning trees log the contents of their leaves.
+#[derive(Debug)]
+pub struct Indexer<S: TreeStore> {
+    store: S,
+}
+
+impl<S: TreeStore> Indexer<S> {
+    /// Creates an indexer keeping the trees in `store`
+    pub fn new(store: S) -> Self {
+        Self { store }
+    }
+
+    /// Returns the store of the indexer
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Sets the parameters of the tree.
+    ///
+    /// Trees which aren't registered are indexed as Keccak trees without a
+    /// canopy, with the depth of their change log paths.
+    pub fn register_tree(
+        &mut self,
+        tree_id: &Pubkey,
+        config: TreeConfig,
+    ) -> Result<(), IndexerError> {
+        let tree = match self.store.get_tree(tree_id)? {
+            Some(tree) => TreeState { config, ..tree },
+            None => TreeState::new(config),
+        };
+        self.store.put_tree(tree_id, &tree)
+    }
+
+    /// Indexes the events emitted by the transaction and returns the gaps
+    /// they reveal
+    pub fn ingest_transaction(
+        &mut self,
+        transaction: &EncodedTransactionWithStatusMeta,
+    ) -> Result<Vec<Gap>, IndexerError> {
+        self.ingest_events(&events_from_transaction(transaction)?)
+    }
+
+    /// Indexes the events logged by a transaction and returns the gaps they
+    /// reveal
+    pub fn ingest_logs<L: AsRef<str>>(&mut self, logs: &[L]) -> Result<Vec<Gap>, IndexerError> {
+        self.ingest_events(&events_from_logs(logs))
+    }
+
+    /// Indexes the events, in order, and returns the gaps they reveal
+    pub fn ingest_events(
+        &mut self,
+        events: &[AccountCompressionEvent],
+    ) -> Result<Vec<Gap>, IndexerError> {
+        let mut gaps = vec![];
+        let mut application_data = vec![];
+        for event in events {
+            match event {
+                AccountCompressionEvent::ApplicationData(event) => {
+                    application_data.push(event.data().to_vec());
+                }
+                AccountCompressionEvent::ChangeLog(event) => {
+                    let mut application_data = application_data.drain(..);
+                    for path in event.paths() {
+                        gaps.extend(self.apply_path(event, &path, application_data.next())?);
+                    }
+                }
+            }
+        }
+        Ok(gaps)
+    }
+
+    fn apply_path(
+        &mut self,
+        event: &ChangeLogEvent,
+        path: &ChangeLogPath,
+        application_data: Option<Vec<u8>>,
+    ) -> Result<Option<Gap>, IndexerError> {
+        let tree_id = event.tree_id();
+        let mut tree = match self.store.get_tree(&tree_id)? {
+            Some(tree) => tree,
+            None => TreeState::new(TreeConfig {
+                max_depth: path.path.len().saturating_sub(1) as u32,
+                canopy_depth: 0,
+                hash_type: Default::default(),
+            }),
+        };
+        if path.path.len() != tree.config.max_depth as usize + 1 {
+            return Err(IndexerError::InvalidPathLength {
+                path_len: path.path.len(),
+                max_depth: tree.config.max_depth,
+            });
+        }
+
+        // Nodes are only overwritten by more recent changes, so that events
+        // can be applied in any order
+        for path_node in path.path.iter() {
+            let is_newer = self
+                .store
+                .get_node(&tree_id, path_node.index)?
+                .map_or(true, |stored| stored.seq < path.seq);
+            if is_newer {
+                self.store.put_node(
+                    &tree_id,
+                    path_node.index,
+                    &StoredNode {
+                        node: path_node.node,
+                        seq: path.seq,
+                    },
+                )?;
+            }
+        }
+        if let Some(data) = application_data {
+            let is_newer = self
+                .store
+                .get_leaf_data(&tree_id, path.index)?
+                .map_or(true, |stored| stored.seq < path.seq);
+            if is_newer {
+                self.store.put_leaf_data(
+                    &tree_id,
+                    path.index,
+                    &LeafData {
+                        data,
+                        seq: path.seq,
+                    },
+                )?;
+            }
+        }
+
+        let gap = tree.record_seq(&tree_id, path.seq);
+        self.store.put_tree(&tree_id, &tree)?;
+        Ok(gap)
+    }
+
+    /// Returns the sequence numbers of the tree which still need to be
+    /// backfilled
+    pub fn get_gaps(&self, tree_id: &Pubkey) -> Result<Vec<Gap>, IndexerError> {
+        Ok(self.get_tree(tree_id)?.gaps)
+    }
+
+    /// Returns the proof of the leaf, truncated to the canopy depth of the
+    /// tree.
+    ///
+    /// The proof is only valid for the current root of the tree if the tree
+    /// has no gaps.
+    pub fn get_proof(&self, tree_id: &Pubkey, leaf_index: u32) -> Result<LeafProof, IndexerError> {
+        let config = self.get_tree(tree_id)?.config;
+        if leaf_index >= 1 << config.max_depth {
+            return Err(IndexerError::LeafIndexOutOfBounds(leaf_index));
+        }
+        let empty_nodes = config.hash_type.empty_nodes(config.max_depth);
+        let get_node = |node_index: u32, level: u32| -> Result<_, IndexerError> {
+            Ok(self
+                .store
+                .get_node(tree_id, node_index)?
+                .map_or(empty_nodes[level as usize], |stored| stored.node))
+        };
+
+        let mut node_index = (1 << config.max_depth) + leaf_index;
+        let leaf = get_node(node_index, 0)?;
+        let mut proof = vec![];
+        for level in 0..config.max_depth.saturating_sub(config.canopy_depth) {
+            proof.push(get_node(node_index ^ 1, level)?);
+            node_index >>= 1;
+        }
+        Ok(LeafProof {
+            tree_id: *tree_id,
+            leaf_index,
+            leaf,
+            root: get_node(1, config.max_depth)?,
+            proof,
+        })
+    }
+
+    /// Returns the application data logged with the last change of the leaf
+    pub fn get_leaf_data(
+        &self,
+        tree_id: &Pubkey,
+        leaf_index: u32,
+    ) -> Result<Option<LeafData>, IndexerError> {
+        self.store.get_leaf_data(tree_id, leaf_index)
+    }
+
+    fn get_tree(&self, tree_id: &Pubkey) -> Result<TreeState, IndexerError> {
+        self.store
+            .get_tree(tree_id)?
+            .ok_or(IndexerError::UnknownTree(*tree_id))
+    }
+}
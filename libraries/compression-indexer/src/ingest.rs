@@ -0,0 +1,132 @@
+//! Extraction of the events from transactions and logs
+
+use {
+    crate::{error::IndexerError, events::AccountCompressionEvent, noop},
+    base64::{prelude::BASE64_STANDARD, Engine},
+    borsh::BorshDeserialize,
+    solana_program::pubkey::Pubkey,
+    solana_transaction_status_client_types::{
+        option_serializer::OptionSerializer, EncodedTransaction, EncodedTransactionWithStatusMeta,
+        UiInstruction, UiMessage, UiParsedInstruction,
+    },
+    std::str::FromStr,
+};
+
+/// Prefix of the logs holding data logged with `sol_log_data`
+const PROGRAM_DATA_LOG_PREFIX: &str = "Program data: ";
+
+/// Deserializes the data of a noop instruction, returning `None` if it isn't
+/// an account compression event
+pub fn event_from_instruction_data(data: &[u8]) -> Option<AccountCompressionEvent> {
+    AccountCompressionEvent::try_from_slice(data).ok()
+}
+
+/// Returns the events emitted through the noop program by the transaction, in
+/// the order they were emitted. Failed transactions don't emit any event.
+pub fn events_from_transaction(
+    transaction: &EncodedTransactionWithStatusMeta,
+) -> Result<Vec<AccountCompressionEvent>, IndexerError> {
+    let Some(meta) = &transaction.meta else {
+        return Err(IndexerError::InvalidTransaction);
+    };
+    if meta.err.is_some() {
+        return Ok(vec![]);
+    }
+    let OptionSerializer::Some(inner_instructions) = &meta.inner_instructions else {
+        return Ok(vec![]);
+    };
+
+    let account_keys = get_account_keys(transaction)?;
+    let mut inner_instructions = inner_instructions.iter().collect::<Vec<_>>();
+    inner_instructions.sort_by_key(|inner_instructions| inner_instructions.index);
+
+    let mut events = vec![];
+    for instruction in inner_instructions
+        .iter()
+        .flat_map(|inner_instructions| inner_instructions.instructions.iter())
+    {
+        let (program_id, data) = match instruction {
+            UiInstruction::Compiled(instruction) => (
+                account_keys
+                    .get(instruction.program_id_index as usize)
+                    .copied()
+                    .ok_or(IndexerError::InvalidTransaction)?,
+                &instruction.data,
+            ),
+            UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(instruction)) => {
+                (parse_pubkey(&instruction.program_id)?, &instruction.data)
+            }
+            // The noop program has no parser, so its instructions are never
+            // fully parsed
+            UiInstruction::Parsed(UiParsedInstruction::Parsed(_)) => continue,
+        };
+        if program_id != noop::id() {
+            continue;
+        }
+        let data = bs58::decode(data)
+            .into_vec()
+            .map_err(|_| IndexerError::InvalidTransaction)?;
+        events.extend(event_from_instruction_data(&data));
+    }
+    Ok(events)
+}
+
+/// Returns the events logged with `sol_log_data` in the transaction logs, in
+/// the order they were logged
+///
+/// Logs may be truncated by the runtime, so indexing transactions is
+/// preferred when available.
+pub fn events_from_logs<S: AsRef<str>>(logs: &[S]) -> Vec<AccountCompressionEvent> {
+    logs.iter()
+        .filter_map(|log| log.as_ref().strip_prefix(PROGRAM_DATA_LOG_PREFIX))
+        .flat_map(|data| data.split(' '))
+        .filter_map(|data| BASE64_STANDARD.decode(data).ok())
+        .filter_map(|data| event_from_instruction_data(&data))
+        .collect()
+}
+
+/// Returns the keys of the accounts of the transaction, including the ones
+/// loaded from address lookup tables
+fn get_account_keys(
+    transaction: &EncodedTransactionWithStatusMeta,
+) -> Result<Vec<Pubkey>, IndexerError> {
+    let mut account_keys = match &transaction.transaction {
+        EncodedTransaction::Json(transaction) => match &transaction.message {
+            // Parsed messages already list the loaded accounts
+            UiMessage::Parsed(message) => {
+                return message
+                    .account_keys
+                    .iter()
+                    .map(|account| parse_pubkey(&account.pubkey))
+                    .collect();
+            }
+            UiMessage::Raw(message) => message
+                .account_keys
+                .iter()
+                .map(|key| parse_pubkey(key))
+                .collect::<Result<Vec<_>, _>>()?,
+        },
+        encoded => encoded
+            .decode()
+            .ok_or(IndexerError::InvalidTransaction)?
+            .message
+            .static_account_keys()
+            .to_vec(),
+    };
+    if let Some(OptionSerializer::Some(loaded_addresses)) =
+        transaction.meta.as_ref().map(|meta| &meta.loaded_addresses)
+    {
+        for key in loaded_addresses
+            .writable
+            .iter()
+            .chain(loaded_addresses.readonly.iter())
+        {
+            account_keys.push(parse_pubkey(key)?);
+        }
+    }
+    Ok(account_keys)
+}
+
+fn parse_pubkey(key: &str) -> Result<Pubkey, IndexerError> {
+    Pubkey::from_str(key).map_err(|_| IndexerError::InvalidTransaction)
+}
@@ -0,0 +1,36 @@
+//! Indexer of SPL ConcurrentMerkleTrees
+//!
+//! The SPL Account Compression program only stores the changelog buffer and
+//! the canopy of a tree on-chain, so proofs for its leaves have to be served by
+//! an off-chain indexer. This crate rebuilds the full trees from the
+//! `AccountCompressionEvent`s emitted through the noop program, either from
+//! the inner instructions of transactions or from their logs.
+//!
+//! Every node is stored with the sequence number of the change which wrote it,
+//! so events can be ingested in any order. Missing sequence numbers are
+//! reported as [Gap](tree::Gap)s, which must be backfilled for the served
+//! proofs to be valid.
+//!
+//! The indexed trees are kept in a pluggable [TreeStore](store::TreeStore),
+//! either in memory or, with the `sqlite` feature, in a SQLite database.
+
+#![deny(missing_docs)]
+
+pub mod error;
+pub mod events;
+pub mod indexer;
+pub mod ingest;
+pub mod store;
+pub mod tree;
+
+pub use indexer::Indexer;
+
+/// Program id of the SPL Account Compression program
+pub mod account_compression {
+    solana_program::declare_id!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+}
+
+/// Program id of the SPL Noop program, through which events are emitted
+pub mod noop {
+    solana_program::declare_id!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+}
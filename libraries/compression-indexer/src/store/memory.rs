@@ -0,0 +1,73 @@
+//! In-memory store
+
+use {
+    crate::{
+        error::IndexerError,
+        store::TreeStore,
+        tree::{LeafData, StoredNode, TreeState},
+    },
+    solana_program::pubkey::Pubkey,
+    std::collections::HashMap,
+};
+
+/// Store keeping the indexed trees in memory
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    trees: HashMap<Pubkey, TreeState>,
+    nodes: HashMap<(Pubkey, u32), StoredNode>,
+    leaf_data: HashMap<(Pubkey, u32), LeafData>,
+}
+
+impl MemoryStore {
+    /// Creates an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TreeStore for MemoryStore {
+    fn get_tree(&self, tree_id: &Pubkey) -> Result<Option<TreeState>, IndexerError> {
+        Ok(self.trees.get(tree_id).cloned())
+    }
+
+    fn put_tree(&mut self, tree_id: &Pubkey, tree: &TreeState) -> Result<(), IndexerError> {
+        self.trees.insert(*tree_id, tree.clone());
+        Ok(())
+    }
+
+    fn get_node(
+        &self,
+        tree_id: &Pubkey,
+        node_index: u32,
+    ) -> Result<Option<StoredNode>, IndexerError> {
+        Ok(self.nodes.get(&(*tree_id, node_index)).copied())
+    }
+
+    fn put_node(
+        &mut self,
+        tree_id: &Pubkey,
+        node_index: u32,
+        node: &StoredNode,
+    ) -> Result<(), IndexerError> {
+        self.nodes.insert((*tree_id, node_index), *node);
+        Ok(())
+    }
+
+    fn get_leaf_data(
+        &self,
+        tree_id: &Pubkey,
+        leaf_index: u32,
+    ) -> Result<Option<LeafData>, IndexerError> {
+        Ok(self.leaf_data.get(&(*tree_id, leaf_index)).cloned())
+    }
+
+    fn put_leaf_data(
+        &mut self,
+        tree_id: &Pubkey,
+        leaf_index: u32,
+        data: &LeafData,
+    ) -> Result<(), IndexerError> {
+        self.leaf_data.insert((*tree_id, leaf_index), data.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+//! Storage of the indexed trees
+
+mod memory;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+pub use memory::MemoryStore;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStore;
+
+use {
+    crate::{
+        error::IndexerError,
+        tree::{LeafData, StoredNode, TreeState},
+    },
+    solana_program::pubkey::Pubkey,
+};
+
+/// Storage backend of the indexer
+///
+/// Nodes are addressed by their index in the tree: the root is node 1 and
+/// the children of node `i` are nodes `2 * i` and `2 * i + 1`.
+pub trait TreeStore {
+    /// Returns the state of the tree, if it is indexed
+    fn get_tree(&self, tree_id: &Pubkey) -> Result<Option<TreeState>, IndexerError>;
+
+    /// Stores the state of the tree
+    fn put_tree(&mut self, tree_id: &Pubkey, tree: &TreeState) -> Result<(), IndexerError>;
+
+    /// Returns the node of the tree at `node_index`, if it was ever set
+    fn get_node(
+        &self,
+        tree_id: &Pubkey,
+        node_index: u32,
+    ) -> Result<Option<StoredNode>, IndexerError>;
+
+    /// Stores the node of the tree at `node_index`
+    fn put_node(
+        &mut self,
+        tree_id: &Pubkey,
+        node_index: u32,
+        node: &StoredNode,
+    ) -> Result<(), IndexerError>;
+
+    /// Returns the application data of the leaf, if any was logged
+    fn get_leaf_data(
+        &self,
+        tree_id: &Pubkey,
+        leaf_index: u32,
+    ) -> Result<Option<LeafData>, IndexerError>;
+
+    /// Stores the application data of the leaf
+    fn put_leaf_data(
+        &mut self,
+        tree_id: &Pubkey,
+        leaf_index: u32,
+        data: &LeafData,
+    ) -> Result<(), IndexerError>;
+}
@@ -0,0 +1,151 @@
+//! SQLite store
+
+use {
+    crate::{
+        error::IndexerError,
+        store::TreeStore,
+        tree::{LeafData, StoredNode, TreeState},
+    },
+    borsh::BorshDeserialize,
+    rusqlite::{params, Connection, OptionalExtension},
+    solana_program::pubkey::Pubkey,
+    std::path::Path,
+};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS trees (
+        tree_id BLOB PRIMARY KEY,
+        state BLOB NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS nodes (
+        tree_id BLOB NOT NULL,
+        node_index INTEGER NOT NULL,
+        node BLOB NOT NULL,
+        seq INTEGER NOT NULL,
+        PRIMARY KEY (tree_id, node_index)
+    );
+    CREATE TABLE IF NOT EXISTS leaf_data (
+        tree_id BLOB NOT NULL,
+        leaf_index INTEGER NOT NULL,
+        data BLOB NOT NULL,
+        seq INTEGER NOT NULL,
+        PRIMARY KEY (tree_id, leaf_index)
+    );
+";
+
+/// Store keeping the indexed trees in a SQLite database
+#[derive(Debug)]
+pub struct SqliteStore {
+    connection: Connection,
+}
+
+impl SqliteStore {
+    /// Opens the database at `path`, creating it if needed
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, IndexerError> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// Opens a temporary in-memory database
+    pub fn open_in_memory() -> Result<Self, IndexerError> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(connection: Connection) -> Result<Self, IndexerError> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self { connection })
+    }
+}
+
+impl TreeStore for SqliteStore {
+    fn get_tree(&self, tree_id: &Pubkey) -> Result<Option<TreeState>, IndexerError> {
+        let state: Option<Vec<u8>> = self
+            .connection
+            .query_row(
+                "SELECT state FROM trees WHERE tree_id = ?1",
+                params![tree_id.as_ref()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        state
+            .map(|state| TreeState::try_from_slice(&state).map_err(IndexerError::from))
+            .transpose()
+    }
+
+    fn put_tree(&mut self, tree_id: &Pubkey, tree: &TreeState) -> Result<(), IndexerError> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO trees (tree_id, state) VALUES (?1, ?2)",
+            params![tree_id.as_ref(), borsh::to_vec(tree)?],
+        )?;
+        Ok(())
+    }
+
+    fn get_node(
+        &self,
+        tree_id: &Pubkey,
+        node_index: u32,
+    ) -> Result<Option<StoredNode>, IndexerError> {
+        let node: Option<(Vec<u8>, u64)> = self
+            .connection
+            .query_row(
+                "SELECT node, seq FROM nodes WHERE tree_id = ?1 AND node_index = ?2",
+                params![tree_id.as_ref(), node_index],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        node.map(|(node, seq)| {
+            Ok(StoredNode {
+                node: node
+                    .try_into()
+                    .map_err(|_| std::io::Error::from(std::io::ErrorKind::InvalidData))?,
+                seq,
+            })
+        })
+        .transpose()
+    }
+
+    fn put_node(
+        &mut self,
+        tree_id: &Pubkey,
+        node_index: u32,
+        node: &StoredNode,
+    ) -> Result<(), IndexerError> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO nodes (tree_id, node_index, node, seq) VALUES (?1, ?2, ?3, ?4)",
+            params![tree_id.as_ref(), node_index, node.node.as_ref(), node.seq],
+        )?;
+        Ok(())
+    }
+
+    fn get_leaf_data(
+        &self,
+        tree_id: &Pubkey,
+        leaf_index: u32,
+    ) -> Result<Option<LeafData>, IndexerError> {
+        Ok(self
+            .connection
+            .query_row(
+                "SELECT data, seq FROM leaf_data WHERE tree_id = ?1 AND leaf_index = ?2",
+                params![tree_id.as_ref(), leaf_index],
+                |row| {
+                    Ok(LeafData {
+                        data: row.get(0)?,
+                        seq: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    fn put_leaf_data(
+        &mut self,
+        tree_id: &Pubkey,
+        leaf_index: u32,
+        data: &LeafData,
+    ) -> Result<(), IndexerError> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO leaf_data (tree_id, leaf_index, data, seq) VALUES (?1, ?2, ?3, ?4)",
+            params![tree_id.as_ref(), leaf_index, data.data, data.seq],
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+//! Indexed tree state
+
+use {
+    crate::error::IndexerError,
+    borsh::{BorshDeserialize, BorshSerialize},
+    solana_program::pubkey::Pubkey,
+    spl_concurrent_merkle_tree::{
+        hash::{Hasher, Keccak, Poseidon, Sha256},
+        node::{Node, EMPTY},
+    },
+};
+
+/// Size of the header of a ConcurrentMerkleTree account
+const CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1: usize = 2 + 54;
+
+/// Account type of an initialized ConcurrentMerkleTree account
+const CONCURRENT_MERKLE_TREE_ACCOUNT_TYPE: u8 = 1;
+
+/// Hash function of a tree, mirroring the on-chain `HashType`
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HashType {
+    /// Keccak-256, the hash function of trees created with
+    /// `init_empty_merkle_tree`
+    #[default]
+    Keccak,
+    /// SHA-256
+    Sha256,
+    /// Poseidon over the BN254 curve
+    Poseidon,
+}
+
+impl HashType {
+    /// Computes the parent node of `left` and `right`
+    pub fn hash_pair(&self, left: &Node, right: &Node) -> Node {
+        match self {
+            Self::Keccak => Keccak::hash_pair(left, right),
+            Self::Sha256 => Sha256::hash_pair(left, right),
+            Self::Poseidon => Poseidon::hash_pair(left, right),
+        }
+    }
+
+    /// Returns the empty nodes of every level of a tree of depth `max_depth`,
+    /// from the leaves up to the root
+    pub fn empty_nodes(&self, max_depth: u32) -> Vec<Node> {
+        let mut nodes = vec![EMPTY];
+        for level in 0..max_depth as usize {
+            nodes.push(self.hash_pair(&nodes[level], &nodes[level]));
+        }
+        nodes
+    }
+}
+
+/// Parameters of an indexed tree
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TreeConfig {
+    /// Depth of the tree
+    pub max_depth: u32,
+    /// Number of upper levels of the tree cached on-chain, which are left
+    /// out of the served proofs
+    pub canopy_depth: u32,
+    /// Hash function of the tree
+    pub hash_type: HashType,
+}
+
+impl TreeConfig {
+    /// Reads the parameters of the tree from the data of its
+    /// ConcurrentMerkleTree account
+    pub fn from_account_data(data: &[u8]) -> Result<Self, IndexerError> {
+        if data.len() < CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1
+            || data[0] != CONCURRENT_MERKLE_TREE_ACCOUNT_TYPE
+            || data[1] != 0
+        {
+            return Err(IndexerError::InvalidAccountData);
+        }
+        let read_u32 =
+            |offset: usize| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+        let max_buffer_size = read_u32(2) as usize;
+        let max_depth = read_u32(6);
+        let hash_type = HashType::try_from_slice(&data[51..52])
+            .map_err(|_| IndexerError::InvalidAccountData)?;
+
+        // Sequence number, active index and buffer size, followed by the
+        // changelogs and the rightmost proof
+        let node_size = 32;
+        let path_size = node_size * (max_depth as usize + 1) + 8;
+        let tree_size = 24 + max_buffer_size * path_size + path_size;
+        let canopy_size = data
+            .len()
+            .checked_sub(CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1 + tree_size)
+            .ok_or(IndexerError::InvalidAccountData)?;
+        // The canopy holds all the nodes of its levels but the root
+        let canopy_nodes = canopy_size / node_size + 2;
+        if canopy_size % node_size != 0 || !canopy_nodes.is_power_of_two() {
+            return Err(IndexerError::InvalidAccountData);
+        }
+
+        Ok(Self {
+            max_depth,
+            canopy_depth: canopy_nodes.trailing_zeros() - 1,
+            hash_type,
+        })
+    }
+}
+
+/// Range of sequence numbers missing from an indexed tree
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Gap {
+    /// Public key of the tree
+    pub tree_id: Pubkey,
+    /// First missing sequence number
+    pub first_seq: u64,
+    /// Last missing sequence number, inclusive
+    pub last_seq: u64,
+}
+
+/// State of an indexed tree
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct TreeState {
+    /// Parameters of the tree
+    pub config: TreeConfig,
+    /// Highest sequence number indexed
+    pub seq: Option<u64>,
+    /// Sequence numbers lower than `seq` which aren't indexed yet
+    pub gaps: Vec<Gap>,
+}
+
+impl TreeState {
+    /// Creates the state of a tree without any indexed change
+    pub fn new(config: TreeConfig) -> Self {
+        Self {
+            config,
+            seq: None,
+            gaps: vec![],
+        }
+    }
+
+    /// Records that the change with sequence number `seq` is indexed and
+    /// returns the gap it reveals, if any
+    pub fn record_seq(&mut self, tree_id: &Pubkey, seq: u64) -> Option<Gap> {
+        let first_missing_seq = match self.seq {
+            Some(last_seq) if seq <= last_seq => {
+                self.fill_gap(seq);
+                return None;
+            }
+            Some(last_seq) => last_seq + 1,
+            None => 0,
+        };
+        self.seq = Some(seq);
+        if seq == first_missing_seq {
+            return None;
+        }
+        let gap = Gap {
+            tree_id: *tree_id,
+            first_seq: first_missing_seq,
+            last_seq: seq - 1,
+        };
+        self.gaps.push(gap);
+        Some(gap)
+    }
+
+    /// Removes `seq` from the gaps of the tree
+    fn fill_gap(&mut self, seq: u64) {
+        let Some(position) = self
+            .gaps
+            .iter()
+            .position(|gap| gap.first_seq <= seq && seq <= gap.last_seq)
+        else {
+            return;
+        };
+        let gap = self.gaps.remove(position);
+        if seq < gap.last_seq {
+            self.gaps.insert(
+                position,
+                Gap {
+                    first_seq: seq + 1,
+                    ..gap
+                },
+            );
+        }
+        if gap.first_seq < seq {
+            self.gaps.insert(
+                position,
+                Gap {
+                    last_seq: seq - 1,
+                    ..gap
+                },
+            );
+        }
+    }
+}
+
+/// Node of an indexed tree
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StoredNode {
+    /// Value of the node
+    pub node: Node,
+    /// Sequence number of the change which set the node
+    pub seq: u64,
+}
+
+/// Application data logged along with the change of a leaf
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct LeafData {
+    /// Data serialized by the program owning the tree
+    pub data: Vec<u8>,
+    /// Sequence number of the change of the leaf
+    pub seq: u64,
+}
+
+/// Proof of a leaf of an indexed tree
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LeafProof {
+    /// Public key of the tree
+    pub tree_id: Pubkey,
+    /// Index of the leaf
+    pub leaf_index: u32,
+    /// Value of the leaf
+    pub leaf: Node,
+    /// Root of the tree
+    pub root: Node,
+    /// Siblings of the path from the leaf up to the canopy
+    pub proof: Vec<Node>,
+}
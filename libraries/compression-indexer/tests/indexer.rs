@@ -0,0 +1,517 @@
+#![allow(clippy::arithmetic_side_effects)]
+use {
+    base64::{prelude::BASE64_STANDARD, Engine},
+    rand::{thread_rng, Rng},
+    solana_program::pubkey::Pubkey,
+    solana_transaction_status_client_types::EncodedTransactionWithStatusMeta,
+    spl_compression_indexer::{
+        account_compression,
+        error::IndexerError,
+        events::{
+            AccountCompressionEvent, ApplicationDataEvent, ApplicationDataEventV1, ChangeLogEvent,
+            ChangeLogEventV1, ChangeLogEventV2, ChangeLogPath, PathNode,
+        },
+        noop,
+        store::{MemoryStore, TreeStore},
+        tree::{Gap, HashType, TreeConfig},
+        Indexer,
+    },
+    spl_concurrent_merkle_tree::{
+        changelog::ChangeLog,
+        concurrent_merkle_tree::{ConcurrentMerkleTree, LeafUpdate, SetLeavesArgs},
+        hash::multi_proof_positions,
+        node::{Node, EMPTY},
+    },
+    spl_merkle_tree_reference::MerkleTree,
+};
+
+const DEPTH: usize = 8;
+const BUFFER_SIZE: usize = 16;
+
+fn path_from_change_log(change_log: &ChangeLog<DEPTH>, seq: u64) -> ChangeLogPath {
+    let mut path: Vec<PathNode> = change_log
+        .path
+        .iter()
+        .enumerate()
+        .map(|(level, node)| PathNode {
+            node: *node,
+            index: (1 << (DEPTH - level)) + (change_log.index >> level),
+        })
+        .collect();
+    path.push(PathNode {
+        node: change_log.root,
+        index: 1,
+    });
+    ChangeLogPath {
+        path,
+        seq,
+        index: change_log.index,
+    }
+}
+
+fn change_log_event(
+    tree_id: &Pubkey,
+    cmt: &ConcurrentMerkleTree<DEPTH, BUFFER_SIZE>,
+) -> AccountCompressionEvent {
+    let path = path_from_change_log(&cmt.get_change_log(), cmt.sequence_number);
+    AccountCompressionEvent::ChangeLog(ChangeLogEvent::V1(ChangeLogEventV1 {
+        id: *tree_id,
+        path: path.path,
+        seq: path.seq,
+        index: path.index,
+    }))
+}
+
+fn application_data_event(data: &[u8]) -> AccountCompressionEvent {
+    AccountCompressionEvent::ApplicationData(ApplicationDataEvent::V1(ApplicationDataEventV1 {
+        application_data: data.to_vec(),
+    }))
+}
+
+/// Initializes a tree and appends `count` random leaves to it, returning the
+/// events emitted for every change
+fn setup(
+    tree_id: &Pubkey,
+    count: usize,
+) -> (
+    ConcurrentMerkleTree<DEPTH, BUFFER_SIZE>,
+    MerkleTree,
+    Vec<AccountCompressionEvent>,
+) {
+    let mut rng = thread_rng();
+    let mut cmt = ConcurrentMerkleTree::<DEPTH, BUFFER_SIZE>::new();
+    let mut reference_tree = MerkleTree::new(&[EMPTY; 1 << DEPTH]);
+    cmt.initialize().unwrap();
+    let mut events = vec![change_log_event(tree_id, &cmt)];
+    for i in 0..count {
+        let leaf = rng.gen::<Node>();
+        cmt.append(leaf).unwrap();
+        reference_tree.add_leaf(leaf, i);
+        events.push(change_log_event(tree_id, &cmt));
+    }
+    (cmt, reference_tree, events)
+}
+
+fn assert_proof<S: TreeStore>(
+    indexer: &Indexer<S>,
+    tree_id: &Pubkey,
+    reference_tree: &MerkleTree,
+    leaf_index: u32,
+) {
+    let proof = indexer.get_proof(tree_id, leaf_index).unwrap();
+    assert_eq!(proof.root, reference_tree.get_root());
+    assert_eq!(proof.leaf, reference_tree.get_leaf(leaf_index as usize));
+    assert_eq!(
+        proof.proof,
+        reference_tree.get_proof_of_leaf(leaf_index as usize)
+    );
+}
+
+#[test]
+fn test_ingest_change_logs() {
+    let tree_id = Pubkey::new_unique();
+    let (cmt, reference_tree, events) = setup(&tree_id, 20);
+    let mut indexer = Indexer::new(MemoryStore::default());
+
+    let gaps = indexer.ingest_events(&events).unwrap();
+    assert!(gaps.is_empty());
+    assert_eq!(indexer.get_proof(&tree_id, 0).unwrap().root, cmt.get_root());
+    for leaf_index in [0, 7, 19, 20, 255] {
+        assert_proof(&indexer, &tree_id, &reference_tree, leaf_index);
+    }
+    assert!(matches!(
+        indexer.get_proof(&tree_id, 256),
+        Err(IndexerError::LeafIndexOutOfBounds(256))
+    ));
+    let unknown_tree_id = Pubkey::new_unique();
+    assert!(matches!(
+        indexer.get_proof(&unknown_tree_id, 0),
+        Err(IndexerError::UnknownTree(id)) if id == unknown_tree_id
+    ));
+}
+
+#[test]
+fn test_ingest_out_of_order() {
+    let tree_id = Pubkey::new_unique();
+    let (_, reference_tree, mut events) = setup(&tree_id, 30);
+    let mut indexer = Indexer::new(MemoryStore::default());
+
+    events.reverse();
+    indexer.ingest_events(&events).unwrap();
+    assert!(indexer.get_gaps(&tree_id).unwrap().is_empty());
+    for leaf_index in 0..32 {
+        assert_proof(&indexer, &tree_id, &reference_tree, leaf_index);
+    }
+}
+
+#[test]
+fn test_gaps_and_backfill() {
+    let tree_id = Pubkey::new_unique();
+    let (_, reference_tree, events) = setup(&tree_id, 12);
+    let mut indexer = Indexer::new(MemoryStore::default());
+
+    // Skip the changes with sequence numbers 3 to 5 and 8
+    let gaps = indexer.ingest_events(&events[..3]).unwrap();
+    assert!(gaps.is_empty());
+    let gaps = indexer.ingest_events(&events[6..8]).unwrap();
+    assert_eq!(
+        gaps,
+        vec![Gap {
+            tree_id,
+            first_seq: 3,
+            last_seq: 5,
+        }]
+    );
+    let gaps = indexer.ingest_events(&events[9..]).unwrap();
+    assert_eq!(
+        gaps,
+        vec![Gap {
+            tree_id,
+            first_seq: 8,
+            last_seq: 8,
+        }]
+    );
+    assert_eq!(indexer.get_gaps(&tree_id).unwrap().len(), 2);
+
+    // Backfilling the middle of a gap splits it
+    indexer.ingest_events(&events[4..5]).unwrap();
+    assert_eq!(
+        indexer.get_gaps(&tree_id).unwrap(),
+        vec![
+            Gap {
+                tree_id,
+                first_seq: 3,
+                last_seq: 3,
+            },
+            Gap {
+                tree_id,
+                first_seq: 5,
+                last_seq: 5,
+            },
+            Gap {
+                tree_id,
+                first_seq: 8,
+                last_seq: 8,
+            },
+        ]
+    );
+
+    indexer.ingest_events(&events[3..4]).unwrap();
+    indexer.ingest_events(&events[5..6]).unwrap();
+    indexer.ingest_events(&events[8..9]).unwrap();
+    assert!(indexer.get_gaps(&tree_id).unwrap().is_empty());
+    for leaf_index in 0..16 {
+        assert_proof(&indexer, &tree_id, &reference_tree, leaf_index);
+    }
+}
+
+#[test]
+fn test_first_event_reveals_gap() {
+    let tree_id = Pubkey::new_unique();
+    let (_, _, events) = setup(&tree_id, 4);
+    let mut indexer = Indexer::new(MemoryStore::default());
+
+    let gaps = indexer.ingest_events(&events[3..]).unwrap();
+    assert_eq!(
+        gaps,
+        vec![Gap {
+            tree_id,
+            first_seq: 0,
+            last_seq: 2,
+        }]
+    );
+}
+
+#[test]
+fn test_ingest_change_log_v2() {
+    let mut rng = thread_rng();
+    let tree_id = Pubkey::new_unique();
+    let (mut cmt, mut reference_tree, events) = setup(&tree_id, 16);
+    let mut indexer = Indexer::new(MemoryStore::default());
+    indexer.ingest_events(&events).unwrap();
+
+    let indices = [1, 2, 9, 14];
+    let mut proof_vec = vec![];
+    for level in 0..DEPTH as u32 {
+        for position in multi_proof_positions(&indices, level) {
+            let leaf_index = ((position ^ 1) << level) as usize;
+            proof_vec.push(reference_tree.get_proof_of_leaf(leaf_index)[level as usize]);
+        }
+    }
+    let leaves: Vec<LeafUpdate> = indices
+        .iter()
+        .map(|&index| LeafUpdate {
+            index,
+            previous_leaf: reference_tree.get_leaf(index as usize),
+            new_leaf: rng.gen::<Node>(),
+        })
+        .collect();
+    cmt.set_leaves(&SetLeavesArgs {
+        current_root: reference_tree.get_root(),
+        leaves: leaves.clone(),
+        proof_vec,
+    })
+    .unwrap();
+    for leaf in leaves.iter() {
+        reference_tree.add_leaf(leaf.new_leaf, leaf.index as usize);
+    }
+
+    let sequence_number = cmt.sequence_number;
+    let paths = cmt
+        .get_change_logs(indices.len())
+        .iter()
+        .enumerate()
+        .map(|(i, change_log)| {
+            path_from_change_log(
+                change_log,
+                sequence_number + 1 + i as u64 - indices.len() as u64,
+            )
+        })
+        .collect();
+    let mut events = indices
+        .iter()
+        .map(|index| application_data_event(&index.to_le_bytes()))
+        .collect::<Vec<_>>();
+    events.push(AccountCompressionEvent::ChangeLog(ChangeLogEvent::V2(
+        ChangeLogEventV2 { id: tree_id, paths },
+    )));
+
+    let gaps = indexer.ingest_events(&events).unwrap();
+    assert!(gaps.is_empty());
+    for leaf_index in 0..16 {
+        assert_proof(&indexer, &tree_id, &reference_tree, leaf_index);
+    }
+    for (i, index) in indices.iter().enumerate() {
+        let leaf_data = indexer.get_leaf_data(&tree_id, *index).unwrap().unwrap();
+        assert_eq!(leaf_data.data, index.to_le_bytes());
+        assert_eq!(
+            leaf_data.seq,
+            sequence_number + 1 + i as u64 - indices.len() as u64
+        );
+    }
+    assert_eq!(indexer.get_leaf_data(&tree_id, 0).unwrap(), None);
+}
+
+#[test]
+fn test_canopy_truncates_proofs() {
+    let tree_id = Pubkey::new_unique();
+    let (_, reference_tree, events) = setup(&tree_id, 8);
+    let mut indexer = Indexer::new(MemoryStore::default());
+    indexer
+        .register_tree(
+            &tree_id,
+            TreeConfig {
+                max_depth: DEPTH as u32,
+                canopy_depth: 3,
+                hash_type: HashType::Keccak,
+            },
+        )
+        .unwrap();
+    indexer.ingest_events(&events).unwrap();
+
+    let proof = indexer.get_proof(&tree_id, 5).unwrap();
+    assert_eq!(
+        proof.proof,
+        reference_tree.get_proof_of_leaf(5)[..DEPTH - 3]
+    );
+}
+
+#[test]
+fn test_invalid_path_length() {
+    let tree_id = Pubkey::new_unique();
+    let (_, _, events) = setup(&tree_id, 1);
+    let mut indexer = Indexer::new(MemoryStore::default());
+    indexer
+        .register_tree(
+            &tree_id,
+            TreeConfig {
+                max_depth: DEPTH as u32 + 1,
+                canopy_depth: 0,
+                hash_type: HashType::Keccak,
+            },
+        )
+        .unwrap();
+
+    assert!(matches!(
+        indexer.ingest_events(&events),
+        Err(IndexerError::InvalidPathLength {
+            path_len,
+            max_depth,
+        }) if path_len == DEPTH + 1 && max_depth == DEPTH as u32 + 1
+    ));
+}
+
+#[test]
+fn test_ingest_logs() {
+    let tree_id = Pubkey::new_unique();
+    let (_, reference_tree, events) = setup(&tree_id, 5);
+    let mut indexer = Indexer::new(MemoryStore::default());
+
+    let mut logs = vec![format!("Program {} invoke [1]", account_compression::id())];
+    for event in events.iter() {
+        logs.push(format!(
+            "Program data: {}",
+            BASE64_STANDARD.encode(borsh::to_vec(event).unwrap())
+        ));
+    }
+    logs.push("Program data: bm90IGFuIGV2ZW50".to_string());
+    logs.push(format!("Program {} success", account_compression::id()));
+
+    let gaps = indexer.ingest_logs(&logs).unwrap();
+    assert!(gaps.is_empty());
+    for leaf_index in 0..8 {
+        assert_proof(&indexer, &tree_id, &reference_tree, leaf_index);
+    }
+}
+
+fn encoded_transaction(
+    inner_instructions: &[(u8, Vec<AccountCompressionEvent>)],
+    err: Option<&str>,
+) -> EncodedTransactionWithStatusMeta {
+    let account_keys = [
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        account_compression::id(),
+        noop::id(),
+    ]
+    .map(|key| key.to_string());
+    let inner_instructions = inner_instructions
+        .iter()
+        .map(|(index, events)| {
+            let instructions = events
+                .iter()
+                .map(|event| {
+                    serde_json::json!({
+                        "programIdIndex": 3,
+                        "accounts": [],
+                        "data": bs58::encode(borsh::to_vec(event).unwrap()).into_string(),
+                        "stackHeight": 2,
+                    })
+                })
+                .collect::<Vec<_>>();
+            serde_json::json!({ "index": index, "instructions": instructions })
+        })
+        .collect::<Vec<_>>();
+    serde_json::from_value(serde_json::json!({
+        "transaction": {
+            "signatures": [bs58::encode([1; 64]).into_string()],
+            "message": {
+                "header": {
+                    "numRequiredSignatures": 1,
+                    "numReadonlySignedAccounts": 0,
+                    "numReadonlyUnsignedAccounts": 2,
+                },
+                "accountKeys": account_keys,
+                "recentBlockhash": Pubkey::default().to_string(),
+                "instructions": [
+                    { "programIdIndex": 2, "accounts": [0, 1], "data": "" },
+                    { "programIdIndex": 2, "accounts": [0, 1], "data": "" },
+                ],
+            },
+        },
+        "meta": {
+            "err": err,
+            "status": match err {
+                Some(err) => serde_json::json!({ "Err": err }),
+                None => serde_json::json!({ "Ok": null }),
+            },
+            "fee": 5000,
+            "preBalances": [0, 0, 0, 0],
+            "postBalances": [0, 0, 0, 0],
+            "innerInstructions": inner_instructions,
+            "logMessages": [],
+        },
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_ingest_transaction() {
+    let tree_id = Pubkey::new_unique();
+    let (_, reference_tree, events) = setup(&tree_id, 6);
+    let mut indexer = Indexer::new(MemoryStore::default());
+
+    // Inner instructions are listed out of order, and the events must still be
+    // applied in the order of the instructions which emitted them
+    let transaction = encoded_transaction(
+        &[(1, events[4..].to_vec()), (0, events[..4].to_vec())],
+        Some("AccountInUse"),
+    );
+    assert!(indexer.ingest_transaction(&transaction).unwrap().is_empty());
+    assert!(indexer.get_proof(&tree_id, 0).is_err());
+
+    let transaction = encoded_transaction(
+        &[(1, events[4..].to_vec()), (0, events[..4].to_vec())],
+        None,
+    );
+    let gaps = indexer.ingest_transaction(&transaction).unwrap();
+    assert!(gaps.is_empty());
+    for leaf_index in 0..8 {
+        assert_proof(&indexer, &tree_id, &reference_tree, leaf_index);
+    }
+}
+
+#[test]
+fn test_tree_config_from_account_data() {
+    let mut data =
+        vec![0; 2 + 54 + std::mem::size_of::<ConcurrentMerkleTree<DEPTH, BUFFER_SIZE>>()];
+    data[0] = 1;
+    data[2..6].copy_from_slice(&(BUFFER_SIZE as u32).to_le_bytes());
+    data[6..10].copy_from_slice(&(DEPTH as u32).to_le_bytes());
+    data[51] = 1;
+    assert_eq!(
+        TreeConfig::from_account_data(&data).unwrap(),
+        TreeConfig {
+            max_depth: DEPTH as u32,
+            canopy_depth: 0,
+            hash_type: HashType::Sha256,
+        }
+    );
+
+    // A canopy of depth 2 holds the 6 nodes of its two upper levels
+    data.extend([0; 6 * 32]);
+    assert_eq!(
+        TreeConfig::from_account_data(&data).unwrap().canopy_depth,
+        2
+    );
+
+    data.extend([0; 32]);
+    assert!(matches!(
+        TreeConfig::from_account_data(&data),
+        Err(IndexerError::InvalidAccountData)
+    ));
+    assert!(matches!(
+        TreeConfig::from_account_data(&[0; 56]),
+        Err(IndexerError::InvalidAccountData)
+    ));
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn test_sqlite_store() {
+    use spl_compression_indexer::store::SqliteStore;
+
+    let tree_id = Pubkey::new_unique();
+    let (_, reference_tree, events) = setup(&tree_id, 10);
+    let mut indexer = Indexer::new(SqliteStore::open_in_memory().unwrap());
+
+    let mut events_with_data = vec![application_data_event(b"leaf")];
+    events_with_data.extend(events[1..].iter().cloned());
+    indexer.ingest_events(&events_with_data).unwrap();
+    assert_eq!(
+        indexer.get_gaps(&tree_id).unwrap(),
+        vec![Gap {
+            tree_id,
+            first_seq: 0,
+            last_seq: 0,
+        }]
+    );
+    indexer.ingest_events(&events[..1]).unwrap();
+    assert!(indexer.get_gaps(&tree_id).unwrap().is_empty());
+    for leaf_index in 0..16 {
+        assert_proof(&indexer, &tree_id, &reference_tree, leaf_index);
+    }
+    let leaf_data = indexer.get_leaf_data(&tree_id, 0).unwrap().unwrap();
+    assert_eq!(leaf_data.data, b"leaf");
+    assert_eq!(leaf_data.seq, 1);
+}
@@ -1,5 +1,5 @@
 use crate::{
-    hash::hash_to_parent,
+    hash::{hash_to_parent_with_hasher, Hasher, Keccak},
     node::{Node, EMPTY},
 };
 
@@ -43,7 +43,13 @@ impl<const MAX_DEPTH: usize> ChangeLog<MAX_DEPTH> {
     }
 
     /// Sets all change log values from a leaf and valid proof
-    pub fn replace_and_recompute_path(
+    pub fn replace_and_recompute_path(&mut self, index: u32, node: Node, proof: &[Node]) -> Node {
+        self.replace_and_recompute_path_with_hasher::<Keccak>(index, node, proof)
+    }
+
+    /// Sets all change log values from a leaf and valid proof using the given
+    /// hasher
+    pub fn replace_and_recompute_path_with_hasher<H: Hasher>(
         &mut self,
         index: u32,
         mut node: Node,
@@ -52,7 +58,7 @@ impl<const MAX_DEPTH: usize> ChangeLog<MAX_DEPTH> {
         self.index = index;
         for (i, sibling) in proof.iter().enumerate() {
             self.path[i] = node;
-            hash_to_parent(&mut node, sibling, self.index >> i & 1 == 0);
+            hash_to_parent_with_hasher::<H>(&mut node, sibling, self.index >> i & 1 == 0);
         }
         self.root = node;
         node
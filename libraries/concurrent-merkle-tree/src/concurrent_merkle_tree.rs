@@ -2,12 +2,22 @@ use {
     crate::{
         changelog::ChangeLog,
         error::ConcurrentMerkleTreeError,
-        hash::{fill_in_proof, hash_to_parent, recompute},
-        node::{empty_node, empty_node_cached, Node, EMPTY},
+        hash::{
+            append_subtree_with_hasher, check_proof_nodes, extend_proof_with_hasher,
+            extend_root_with_hasher, fill_in_multi_proof_with_hasher, fill_in_proof_with_hasher,
+            hash_subtree_with_hasher, hash_to_parent_with_hasher, next_subtree_height,
+            recompute_with_hasher, Hasher, Keccak, MAX_SUPPORTED_DEPTH,
+        },
+        indexed_leaf::IndexedLeaf,
+        node::{
+            empty_node_cached_mut_with_hasher, empty_node_cached_with_hasher,
+            empty_node_with_hasher, Node, EMPTY,
+        },
         path::Path,
     },
     bytemuck::{Pod, Zeroable},
     log_compute, solana_logging,
+    std::marker::PhantomData,
 };
 
 /// Enforce constraints on max depth and buffer size
@@ -15,7 +25,7 @@ use {
 fn check_bounds(max_depth: usize, max_buffer_size: usize) {
     // We cannot allow a tree depth greater than 30 because of the bit math
     // required to update `ChangeLog`s
-    assert!(max_depth < 31);
+    assert!(max_depth <= MAX_SUPPORTED_DEPTH);
     // This will return true if MAX_BUFFER_SIZE is a power of 2 or if it is 0
     assert!(max_buffer_size & (max_buffer_size - 1) == 0);
 }
@@ -27,6 +37,47 @@ fn check_leaf_index(leaf_index: u32, max_depth: usize) -> Result<(), ConcurrentM
     Ok(())
 }
 
+fn check_leaf<H: Hasher>(leaf: &Node) -> Result<(), ConcurrentMerkleTreeError> {
+    if !H::is_valid_leaf(leaf) {
+        return Err(ConcurrentMerkleTreeError::InvalidLeaf);
+    }
+    Ok(())
+}
+
+fn check_append_leaf<H: Hasher>(leaf: &Node) -> Result<(), ConcurrentMerkleTreeError> {
+    if *leaf == EMPTY {
+        return Err(ConcurrentMerkleTreeError::CannotAppendEmptyNode);
+    }
+    check_leaf::<H>(leaf)
+}
+
+/// Checks that `num_leaves` leaves fit after the `rightmost_index` leaves of
+/// the tree
+fn check_capacity(
+    rightmost_index: u32,
+    num_leaves: usize,
+    max_depth: usize,
+) -> Result<(), ConcurrentMerkleTreeError> {
+    if rightmost_index as u64 + num_leaves as u64 > 1 << max_depth {
+        return Err(ConcurrentMerkleTreeError::TreeFull);
+    }
+    Ok(())
+}
+
+/// Checks that a tree of depth `max_depth` can hold the tree of depth `depth`
+/// as its leftmost subtree
+fn check_migration_depth(max_depth: u32, depth: usize) -> Result<(), ConcurrentMerkleTreeError> {
+    if max_depth as usize <= depth || max_depth as usize > MAX_SUPPORTED_DEPTH {
+        solana_logging!(
+            "Cannot migrate a tree of depth {} to depth {}",
+            depth,
+            max_depth
+        );
+        return Err(ConcurrentMerkleTreeError::InvalidTreeSize);
+    }
+    Ok(())
+}
+
 /// Conurrent Merkle Tree is a Merkle Tree that allows
 /// multiple tree operations targeted for the same tree root to succeed.
 ///
@@ -60,9 +111,17 @@ fn check_leaf_index(leaf_index: u32, max_depth: usize) -> Result<(), ConcurrentM
 /// given the max stack offset of just 4kb. In order to minimize the stack frame
 /// size, the arguments for the `ConcurrentMerkleTree` methods that contain the
 /// proofs are passed as references to structs.
+///
+/// The tree is generic over the [Hasher](crate::hash::Hasher) used to compute
+/// its nodes and defaults to [Keccak](crate::hash::Keccak). The hasher doesn't
+/// take any space, so trees using different hashers have the same layout.
 #[repr(C)]
 #[derive(Copy, Clone)]
-pub struct ConcurrentMerkleTree<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize> {
+pub struct ConcurrentMerkleTree<
+    const MAX_DEPTH: usize,
+    const MAX_BUFFER_SIZE: usize,
+    H: Hasher = Keccak,
+> {
     pub sequence_number: u64,
     /// Index of most recent root & changes
     pub active_index: u64,
@@ -71,19 +130,20 @@ pub struct ConcurrentMerkleTree<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: u
     /// Proof for respective root
     pub change_logs: [ChangeLog<MAX_DEPTH>; MAX_BUFFER_SIZE],
     pub rightmost_proof: Path<MAX_DEPTH>,
+    _hasher: PhantomData<H>,
 }
 
-unsafe impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize> Zeroable
-    for ConcurrentMerkleTree<MAX_DEPTH, MAX_BUFFER_SIZE>
+unsafe impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize, H: Hasher> Zeroable
+    for ConcurrentMerkleTree<MAX_DEPTH, MAX_BUFFER_SIZE, H>
 {
 }
-unsafe impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize> Pod
-    for ConcurrentMerkleTree<MAX_DEPTH, MAX_BUFFER_SIZE>
+unsafe impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize, H: Hasher> Pod
+    for ConcurrentMerkleTree<MAX_DEPTH, MAX_BUFFER_SIZE, H>
 {
 }
 
-impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize> Default
-    for ConcurrentMerkleTree<MAX_DEPTH, MAX_BUFFER_SIZE>
+impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize, H: Hasher> Default
+    for ConcurrentMerkleTree<MAX_DEPTH, MAX_BUFFER_SIZE, H>
 {
     fn default() -> Self {
         Self {
@@ -92,6 +152,7 @@ impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize> Default
             buffer_size: 0,
             change_logs: [ChangeLog::<MAX_DEPTH>::default(); MAX_BUFFER_SIZE],
             rightmost_proof: Path::<MAX_DEPTH>::default(),
+            _hasher: PhantomData,
         }
     }
 }
@@ -113,6 +174,65 @@ pub struct SetLeafArgs {
     pub index: u32,
 }
 
+/// A single leaf replacement applied by
+/// [set_leaves](ConcurrentMerkleTree::set_leaves).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LeafUpdate {
+    pub index: u32,
+    pub previous_leaf: Node,
+    pub new_leaf: Node,
+}
+
+/// Arguments structure for setting several leaves in the tree against the
+/// same root.
+///
+/// The `leaves` must be sorted by strictly increasing index. The `proof_vec`
+/// is shared by all of them: level by level starting from the leaves, it
+/// holds the nodes at the positions returned by
+/// [multi_proof_positions](crate::hash::multi_proof_positions). Missing nodes
+/// at the top are inferred as empty node hashes.
+pub struct SetLeavesArgs {
+    pub current_root: Node,
+    pub leaves: Vec<LeafUpdate>,
+    pub proof_vec: Vec<Node>,
+}
+
+/// Arguments structure for appending a subtree to the tree.
+///
+/// The subtree holds `1 << proof_vec.len()` leaves and must start at a
+/// multiple of its number of leaves. `proof_vec` is the proof of
+/// `rightmost_leaf`, the last leaf of the subtree, within the subtree.
+pub struct AppendSubtreeArgs {
+    pub subtree_root: Node,
+    pub rightmost_leaf: Node,
+    pub proof_vec: Vec<Node>,
+}
+
+/// Arguments structure for appending several leaves to the tree.
+///
+/// The leaves are appended as the fewest aligned subtrees of at most
+/// `max_subtree_height` levels, each recorded in its own changelog so that
+/// concurrent proofs can still be fast-forwarded. Limiting the height keeps
+/// every modified node at or above `max_subtree_height` on the path of a
+/// changelog, e.g. for the nodes cached by a canopy.
+pub struct AppendBatchArgs {
+    pub leaves: Vec<Node>,
+    pub max_subtree_height: u32,
+}
+
+/// Arguments structure for migrating the tree into a deeper tree.
+///
+/// The deeper tree, of depth `max_depth`, holds the tree as its leftmost
+/// subtree so that every leaf keeps its index. `proof_vec` is the proof of
+/// `rightmost_leaf`, the last leaf appended to the tree, against
+/// `current_root`.
+pub struct MigrateArgs {
+    pub current_root: Node,
+    pub rightmost_leaf: Node,
+    pub proof_vec: Vec<Node>,
+    pub max_depth: u32,
+}
+
 /// Arguments structure for filling an empty leaf or appending a new leaf to the
 /// tree.
 pub struct FillEmptyOrAppendArgs {
@@ -130,8 +250,30 @@ pub struct ProveLeafArgs {
     pub index: u32,
 }
 
-impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>
-    ConcurrentMerkleTree<MAX_DEPTH, MAX_BUFFER_SIZE>
+/// Arguments structure for inserting a value in an indexed tree.
+///
+/// `low_leaf` is the leaf at `low_leaf_index` holding the largest value of
+/// the tree smaller than `value`, and `proof_vec` is its proof.
+pub struct InsertIndexedLeafArgs {
+    pub current_root: Node,
+    pub value: Node,
+    pub low_leaf: IndexedLeaf,
+    pub low_leaf_index: u32,
+    pub proof_vec: Vec<Node>,
+}
+
+/// Arguments structure for proving that a value isn't in an indexed tree,
+/// with the same low leaf as [InsertIndexedLeafArgs].
+pub struct ProveNonMembershipArgs {
+    pub current_root: Node,
+    pub value: Node,
+    pub low_leaf: IndexedLeaf,
+    pub low_leaf_index: u32,
+    pub proof_vec: Vec<Node>,
+}
+
+impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize, H: Hasher>
+    ConcurrentMerkleTree<MAX_DEPTH, MAX_BUFFER_SIZE, H>
 {
     pub fn new() -> Self {
         Self::default()
@@ -149,15 +291,17 @@ impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>
             return Err(ConcurrentMerkleTreeError::TreeAlreadyInitialized);
         }
         let mut rightmost_proof = Path::default();
-        let empty_node_cache = [Node::default(); MAX_DEPTH];
+        let mut empty_node_cache = [Node::default(); MAX_DEPTH];
         for (i, node) in rightmost_proof.proof.iter_mut().enumerate() {
-            *node = empty_node_cached::<MAX_DEPTH>(i as u32, &empty_node_cache);
+            *node =
+                empty_node_cached_mut_with_hasher::<H, MAX_DEPTH>(i as u32, &mut empty_node_cache);
         }
         let mut path = [Node::default(); MAX_DEPTH];
         for (i, node) in path.iter_mut().enumerate() {
-            *node = empty_node_cached::<MAX_DEPTH>(i as u32, &empty_node_cache);
+            *node = empty_node_cached_with_hasher::<H, MAX_DEPTH>(i as u32, &empty_node_cache);
         }
-        self.change_logs[0].root = empty_node(MAX_DEPTH as u32);
+        self.change_logs[0].root =
+            empty_node_cached_with_hasher::<H, MAX_DEPTH>(MAX_DEPTH as u32, &empty_node_cache);
         self.change_logs[0].path = path;
         self.sequence_number = 0;
         self.active_index = 0;
@@ -179,10 +323,12 @@ impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>
     ) -> Result<Node, ConcurrentMerkleTreeError> {
         check_bounds(MAX_DEPTH, MAX_BUFFER_SIZE);
         check_leaf_index(args.index, MAX_DEPTH)?;
+        check_leaf::<H>(&args.rightmost_leaf)?;
 
         if self.is_initialized() {
             return Err(ConcurrentMerkleTreeError::TreeAlreadyInitialized);
         }
+        check_proof_nodes::<H>(&args.proof_vec)?;
         let mut proof: [Node; MAX_DEPTH] = [Node::default(); MAX_DEPTH];
         proof.copy_from_slice(&args.proof_vec);
         let rightmost_proof = Path {
@@ -196,7 +342,7 @@ impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>
         self.active_index = 0;
         self.buffer_size = 1;
         self.rightmost_proof = rightmost_proof;
-        if args.root != recompute(args.rightmost_leaf, &proof, args.index) {
+        if args.root != recompute_with_hasher::<H>(args.rightmost_leaf, &proof, args.index) {
             solana_logging!("Proof failed to verify");
             return Err(ConcurrentMerkleTreeError::InvalidProof);
         }
@@ -208,8 +354,7 @@ impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>
         if !self.is_initialized() {
             return Err(ConcurrentMerkleTreeError::TreeNotInitialized);
         }
-        let empty_node_cache = [EMPTY; MAX_DEPTH];
-        if self.get_root() != empty_node_cached::<MAX_DEPTH>(MAX_DEPTH as u32, &empty_node_cache) {
+        if self.get_root() != empty_node_with_hasher::<H>(MAX_DEPTH as u32) {
             return Err(ConcurrentMerkleTreeError::TreeNonEmpty);
         }
         Ok(())
@@ -229,6 +374,20 @@ impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>
         Box::new(self.change_logs[self.active_index as usize])
     }
 
+    /// Returns up to `count` most recent changelogs, oldest first
+    pub fn get_change_logs(&self, count: usize) -> Vec<ChangeLog<MAX_DEPTH>> {
+        if !self.is_initialized() {
+            solana_logging!("Tree is not initialized, returning no change logs");
+            return vec![];
+        }
+        let mask: usize = MAX_BUFFER_SIZE - 1;
+        let count = count.min(self.buffer_size as usize);
+        (0..count)
+            .rev()
+            .map(|i| self.change_logs[(self.active_index as usize).wrapping_sub(i) & mask])
+            .collect()
+    }
+
     /// This method will fail if the leaf cannot be proven
     /// to exist in the current tree root.
     ///
@@ -256,7 +415,7 @@ impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>
             Err(ConcurrentMerkleTreeError::LeafIndexOutOfBounds)
         } else {
             let mut proof: [Node; MAX_DEPTH] = [Node::default(); MAX_DEPTH];
-            fill_in_proof::<MAX_DEPTH>(&args.proof_vec, &mut proof);
+            fill_in_proof_with_hasher::<H, MAX_DEPTH>(&args.proof_vec, &mut proof);
             let valid_root =
                 self.check_valid_leaf(args.current_root, args.leaf, &mut proof, args.index, true)?;
             if !valid_root {
@@ -267,6 +426,87 @@ impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>
         }
     }
 
+    /// This method will fail if `args.value` cannot be proven to be absent
+    /// from the current tree root of an indexed tree.
+    ///
+    /// The low leaf must hold the largest value smaller than `args.value`, and
+    /// is proven like [prove_leaf](ConcurrentMerkleTree::prove_leaf).
+    pub fn prove_non_membership(
+        &self,
+        args: &ProveNonMembershipArgs,
+    ) -> Result<(), ConcurrentMerkleTreeError> {
+        check_bounds(MAX_DEPTH, MAX_BUFFER_SIZE);
+        check_leaf_index(args.low_leaf_index, MAX_DEPTH)?;
+        if !self.is_initialized() {
+            return Err(ConcurrentMerkleTreeError::TreeNotInitialized);
+        }
+        if !args.low_leaf.is_low_leaf_of(&args.value) {
+            return Err(ConcurrentMerkleTreeError::InvalidLowLeaf);
+        }
+        if !args.low_leaf.is_valid_with_hasher::<H>() {
+            return Err(ConcurrentMerkleTreeError::InvalidLeaf);
+        }
+        if args.low_leaf_index >= self.rightmost_proof.index {
+            return Err(ConcurrentMerkleTreeError::LeafIndexOutOfBounds);
+        }
+        let mut proof: [Node; MAX_DEPTH] = [Node::default(); MAX_DEPTH];
+        fill_in_proof_with_hasher::<H, MAX_DEPTH>(&args.proof_vec, &mut proof);
+        let valid_root = self.check_valid_leaf(
+            args.current_root,
+            args.low_leaf.hash_with_hasher::<H>(),
+            &mut proof,
+            args.low_leaf_index,
+            true,
+        )?;
+        if !valid_root {
+            solana_logging!("Proof failed to verify");
+            return Err(ConcurrentMerkleTreeError::InvalidProof);
+        }
+        Ok(())
+    }
+
+    /// Returns the arguments initializing a deeper tree whose leftmost subtree
+    /// is this tree with
+    /// [initialize_with_root](ConcurrentMerkleTree::initialize_with_root).
+    ///
+    /// The proof of the rightmost leaf is checked against the current root
+    /// like [prove_leaf](ConcurrentMerkleTree::prove_leaf), so the deeper
+    /// tree starts from the current root of this tree. Proofs of the leaves
+    /// of this tree are extended with
+    /// [extend_proof_with_hasher](crate::hash::extend_proof_with_hasher).
+    pub fn get_migration_args(
+        &self,
+        args: &MigrateArgs,
+    ) -> Result<InitializeWithRootArgs, ConcurrentMerkleTreeError> {
+        check_bounds(MAX_DEPTH, MAX_BUFFER_SIZE);
+        check_migration_depth(args.max_depth, MAX_DEPTH)?;
+        if !self.is_initialized() {
+            return Err(ConcurrentMerkleTreeError::TreeNotInitialized);
+        }
+        if self.rightmost_proof.index == 0 {
+            return Err(ConcurrentMerkleTreeError::CannotMigrateEmptyTree);
+        }
+        let index = self.rightmost_proof.index - 1;
+        let mut proof: [Node; MAX_DEPTH] = [Node::default(); MAX_DEPTH];
+        fill_in_proof_with_hasher::<H, MAX_DEPTH>(&args.proof_vec, &mut proof);
+        if !self.check_valid_leaf(
+            args.current_root,
+            args.rightmost_leaf,
+            &mut proof,
+            index,
+            true,
+        )? {
+            solana_logging!("Proof failed to verify");
+            return Err(ConcurrentMerkleTreeError::InvalidProof);
+        }
+        Ok(InitializeWithRootArgs {
+            root: extend_root_with_hasher::<H>(self.get_root(), MAX_DEPTH as u32, args.max_depth),
+            rightmost_leaf: args.rightmost_leaf,
+            proof_vec: extend_proof_with_hasher::<H>(&proof, args.max_depth),
+            index,
+        })
+    }
+
     /// Only used to initialize right most path for a completely empty tree.
     #[inline(always)]
     fn initialize_tree_from_append(
@@ -274,8 +514,8 @@ impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>
         leaf: Node,
         mut proof: [Node; MAX_DEPTH],
     ) -> Result<Node, ConcurrentMerkleTreeError> {
-        let old_root = recompute(EMPTY, &proof, 0);
-        if old_root == empty_node(MAX_DEPTH as u32) {
+        let old_root = recompute_with_hasher::<H>(EMPTY, &proof, 0);
+        if old_root == empty_node_with_hasher::<H>(MAX_DEPTH as u32) {
             self.try_apply_proof(old_root, EMPTY, leaf, &mut proof, 0, false)
         } else {
             Err(ConcurrentMerkleTreeError::TreeAlreadyInitialized)
@@ -291,6 +531,7 @@ impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>
         if node == EMPTY {
             return Err(ConcurrentMerkleTreeError::CannotAppendEmptyNode);
         }
+        check_leaf::<H>(&node)?;
         if self.rightmost_proof.index >= 1 << MAX_DEPTH {
             return Err(ConcurrentMerkleTreeError::TreeFull);
         }
@@ -301,30 +542,33 @@ impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>
         let intersection = self.rightmost_proof.index.trailing_zeros() as usize;
         let mut change_list = [EMPTY; MAX_DEPTH];
         let mut intersection_node = self.rightmost_proof.leaf;
-        let empty_node_cache = [Node::default(); MAX_DEPTH];
+        let mut empty_node_cache = [Node::default(); MAX_DEPTH];
 
         for (i, cl_item) in change_list.iter_mut().enumerate().take(MAX_DEPTH) {
             *cl_item = node;
             match i {
                 i if i < intersection => {
                     // Compute proof to the appended node from empty nodes
-                    let sibling = empty_node_cached::<MAX_DEPTH>(i as u32, &empty_node_cache);
-                    hash_to_parent(
+                    let sibling = empty_node_cached_mut_with_hasher::<H, MAX_DEPTH>(
+                        i as u32,
+                        &mut empty_node_cache,
+                    );
+                    hash_to_parent_with_hasher::<H>(
                         &mut intersection_node,
                         &self.rightmost_proof.proof[i],
                         ((self.rightmost_proof.index - 1) >> i) & 1 == 0,
                     );
-                    hash_to_parent(&mut node, &sibling, true);
+                    hash_to_parent_with_hasher::<H>(&mut node, &sibling, true);
                     self.rightmost_proof.proof[i] = sibling;
                 }
                 i if i == intersection => {
                     // Compute the where the new node intersects the main tree
-                    hash_to_parent(&mut node, &intersection_node, false);
+                    hash_to_parent_with_hasher::<H>(&mut node, &intersection_node, false);
                     self.rightmost_proof.proof[intersection] = intersection_node;
                 }
                 _ => {
                     // Update the change list path up to the root
-                    hash_to_parent(
+                    hash_to_parent_with_hasher::<H>(
                         &mut node,
                         &self.rightmost_proof.proof[i],
                         ((self.rightmost_proof.index - 1) >> i) & 1 == 0,
@@ -341,6 +585,96 @@ impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>
         Ok(node)
     }
 
+    /// Appends a whole subtree with a single changelog, which is cheaper than
+    /// appending its leaves one by one.
+    ///
+    /// The subtree must be aligned to the rightmost index of the tree, and
+    /// only its last leaf is checked to be a valid leaf.
+    pub fn append_subtree(
+        &mut self,
+        args: &AppendSubtreeArgs,
+    ) -> Result<Node, ConcurrentMerkleTreeError> {
+        check_bounds(MAX_DEPTH, MAX_BUFFER_SIZE);
+        if !self.is_initialized() {
+            return Err(ConcurrentMerkleTreeError::TreeNotInitialized);
+        }
+        check_append_leaf::<H>(&args.rightmost_leaf)?;
+        check_proof_nodes::<H>(&args.proof_vec)?;
+        let height = args.proof_vec.len();
+        if height > MAX_DEPTH {
+            return Err(ConcurrentMerkleTreeError::TreeFull);
+        }
+        check_capacity(self.rightmost_proof.index, 1 << height, MAX_DEPTH)?;
+        if self.rightmost_proof.index.trailing_zeros() < height as u32 {
+            return Err(ConcurrentMerkleTreeError::SubtreeNotAligned);
+        }
+        let subtree_rightmost_index = (1 << height) - 1;
+        if recompute_with_hasher::<H>(
+            args.rightmost_leaf,
+            &args.proof_vec,
+            subtree_rightmost_index,
+        ) != args.subtree_root
+        {
+            solana_logging!("Subtree proof failed to verify");
+            return Err(ConcurrentMerkleTreeError::InvalidProof);
+        }
+
+        log_compute!();
+        let mut path = [EMPTY; MAX_DEPTH];
+        let root = append_subtree_with_hasher::<H>(
+            &mut self.rightmost_proof.proof,
+            self.rightmost_proof.leaf,
+            self.rightmost_proof.index,
+            args.rightmost_leaf,
+            &args.proof_vec,
+            &mut path,
+        );
+        let index = self.rightmost_proof.index + subtree_rightmost_index;
+        self.update_internal_counters();
+        self.change_logs[self.active_index as usize] =
+            ChangeLog::<MAX_DEPTH>::new(root, path, index);
+        self.rightmost_proof.index = index + 1;
+        self.rightmost_proof.leaf = args.rightmost_leaf;
+        Ok(root)
+    }
+
+    /// Appends several non-empty leaves, as described in [AppendBatchArgs].
+    pub fn append_batch(
+        &mut self,
+        args: &AppendBatchArgs,
+    ) -> Result<Node, ConcurrentMerkleTreeError> {
+        check_bounds(MAX_DEPTH, MAX_BUFFER_SIZE);
+        if !self.is_initialized() {
+            return Err(ConcurrentMerkleTreeError::TreeNotInitialized);
+        }
+        if args.leaves.is_empty() {
+            return Err(ConcurrentMerkleTreeError::EmptyBatch);
+        }
+        for leaf in args.leaves.iter() {
+            check_append_leaf::<H>(leaf)?;
+        }
+        check_capacity(self.rightmost_proof.index, args.leaves.len(), MAX_DEPTH)?;
+
+        let mut root = self.get_root();
+        let mut leaves = &args.leaves[..];
+        while !leaves.is_empty() {
+            let height = next_subtree_height(
+                self.rightmost_proof.index,
+                leaves.len(),
+                args.max_subtree_height,
+            );
+            let (subtree, rest) = leaves.split_at(1 << height);
+            let (subtree_root, proof_vec) = hash_subtree_with_hasher::<H>(subtree);
+            root = self.append_subtree(&AppendSubtreeArgs {
+                subtree_root,
+                rightmost_leaf: subtree[subtree.len() - 1],
+                proof_vec,
+            })?;
+            leaves = rest;
+        }
+        Ok(root)
+    }
+
     /// Convenience function for `set_leaf`
     ///
     /// This method will `set_leaf` if the leaf at `index` is an empty node,
@@ -351,12 +685,13 @@ impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>
     ) -> Result<Node, ConcurrentMerkleTreeError> {
         check_bounds(MAX_DEPTH, MAX_BUFFER_SIZE);
         check_leaf_index(args.index, MAX_DEPTH)?;
+        check_leaf::<H>(&args.leaf)?;
         if !self.is_initialized() {
             return Err(ConcurrentMerkleTreeError::TreeNotInitialized);
         }
 
         let mut proof: [Node; MAX_DEPTH] = [Node::default(); MAX_DEPTH];
-        fill_in_proof::<MAX_DEPTH>(&args.proof_vec, &mut proof);
+        fill_in_proof_with_hasher::<H, MAX_DEPTH>(&args.proof_vec, &mut proof);
 
         log_compute!();
         match self.try_apply_proof(
@@ -381,6 +716,7 @@ impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>
     pub fn set_leaf(&mut self, args: &SetLeafArgs) -> Result<Node, ConcurrentMerkleTreeError> {
         check_bounds(MAX_DEPTH, MAX_BUFFER_SIZE);
         check_leaf_index(args.index, MAX_DEPTH)?;
+        check_leaf::<H>(&args.new_leaf)?;
         if !self.is_initialized() {
             return Err(ConcurrentMerkleTreeError::TreeNotInitialized);
         }
@@ -389,7 +725,7 @@ impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>
             Err(ConcurrentMerkleTreeError::LeafIndexOutOfBounds)
         } else {
             let mut proof: [Node; MAX_DEPTH] = [Node::default(); MAX_DEPTH];
-            fill_in_proof::<MAX_DEPTH>(&args.proof_vec, &mut proof);
+            fill_in_proof_with_hasher::<H, MAX_DEPTH>(&args.proof_vec, &mut proof);
 
             log_compute!();
             self.try_apply_proof(
@@ -403,6 +739,126 @@ impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>
         }
     }
 
+    /// This method will update the leaves of all the `args.leaves` updates,
+    /// in order, against the same `args.current_root`.
+    ///
+    /// The shared proof is expanded into the proof of every leaf, then each
+    /// update is applied like [set_leaf](ConcurrentMerkleTree::set_leaf). Every
+    /// update is recorded in its own changelog, so the later updates of the
+    /// batch and concurrent operations are fast-forwarded through the earlier
+    /// ones.
+    ///
+    /// A changelog holds a single path, and a concurrent proof against an
+    /// earlier root must be fast-forwarded through every path changed since,
+    /// so the batch can't be recorded in fewer changelogs without breaking
+    /// concurrent operations. Batching saves instructions and proof nodes
+    /// instead. At most `MAX_BUFFER_SIZE` leaves can be set at once, so the
+    /// root of the batch stays in the buffer until its last update, which
+    /// also bounds the fast-forwarding of the batch through itself.
+    ///
+    /// If one of the updates fails, the preceding ones are left applied and
+    /// the error must abort the whole operation.
+    pub fn set_leaves(&mut self, args: &SetLeavesArgs) -> Result<Node, ConcurrentMerkleTreeError> {
+        check_bounds(MAX_DEPTH, MAX_BUFFER_SIZE);
+        if args.leaves.is_empty() || args.leaves.windows(2).any(|w| w[0].index >= w[1].index) {
+            return Err(ConcurrentMerkleTreeError::InvalidLeafUpdates);
+        }
+        if args.leaves.len() > MAX_BUFFER_SIZE {
+            return Err(ConcurrentMerkleTreeError::TooManyLeafUpdates);
+        }
+        for leaf in args.leaves.iter() {
+            check_leaf_index(leaf.index, MAX_DEPTH)?;
+            check_leaf::<H>(&leaf.new_leaf)?;
+        }
+        if !self.is_initialized() {
+            return Err(ConcurrentMerkleTreeError::TreeNotInitialized);
+        }
+        // Only the last leaf may be the next one to be appended
+        if args.leaves[args.leaves.len() - 1].index > self.rightmost_proof.index {
+            return Err(ConcurrentMerkleTreeError::LeafIndexOutOfBounds);
+        }
+
+        let mut proofs = vec![[EMPTY; MAX_DEPTH]; args.leaves.len()];
+        fill_in_multi_proof_with_hasher::<H, _>(&args.leaves, &args.proof_vec, &mut proofs)?;
+
+        log_compute!();
+        // The changelogs overwritten by the updates are saved, along with the
+        // counters and the rightmost proof, so that the tree is left unchanged
+        // if a later update fails
+        let mask = MAX_BUFFER_SIZE as u64 - 1;
+        let (sequence_number, active_index, buffer_size) =
+            (self.sequence_number, self.active_index, self.buffer_size);
+        let rightmost_proof = Box::new(self.rightmost_proof);
+        let mut overwritten_change_logs = Vec::with_capacity(args.leaves.len());
+        let mut root = self.get_root();
+        for (leaf, proof) in args.leaves.iter().zip(proofs.iter_mut()) {
+            let next_index = (self.active_index + 1) & mask;
+            overwritten_change_logs.push(self.change_logs[next_index as usize]);
+            match self.try_apply_proof(
+                args.current_root,
+                leaf.previous_leaf,
+                leaf.new_leaf,
+                proof,
+                leaf.index,
+                true,
+            ) {
+                Ok(new_root) => root = new_root,
+                Err(err) => {
+                    for (i, change_log) in overwritten_change_logs.into_iter().enumerate() {
+                        let index = (active_index + 1 + i as u64) & mask;
+                        self.change_logs[index as usize] = change_log;
+                    }
+                    self.sequence_number = sequence_number;
+                    self.active_index = active_index;
+                    self.buffer_size = buffer_size;
+                    self.rightmost_proof = *rightmost_proof;
+                    return Err(err);
+                }
+            }
+        }
+        Ok(root)
+    }
+
+    /// This method will insert `args.value` in the sorted linked list of an
+    /// indexed tree, by updating its low leaf to point to the value, then
+    /// appending the leaf of the value.
+    ///
+    /// Both changes are recorded in their own changelog, so concurrent
+    /// insertions with different low leaves are fast-forwarded through each
+    /// other. The tree is left unchanged if it is full.
+    pub fn insert_indexed_leaf(
+        &mut self,
+        args: &InsertIndexedLeafArgs,
+    ) -> Result<Node, ConcurrentMerkleTreeError> {
+        check_bounds(MAX_DEPTH, MAX_BUFFER_SIZE);
+        check_leaf_index(args.low_leaf_index, MAX_DEPTH)?;
+        if !self.is_initialized() {
+            return Err(ConcurrentMerkleTreeError::TreeNotInitialized);
+        }
+        if self.rightmost_proof.index >= 1 << MAX_DEPTH {
+            return Err(ConcurrentMerkleTreeError::TreeFull);
+        }
+        if args.low_leaf_index >= self.rightmost_proof.index {
+            return Err(ConcurrentMerkleTreeError::LeafIndexOutOfBounds);
+        }
+        let (low_leaf, leaf) = args
+            .low_leaf
+            .insert_with_hasher::<H>(args.value, self.rightmost_proof.index)?;
+        let mut proof: [Node; MAX_DEPTH] = [Node::default(); MAX_DEPTH];
+        fill_in_proof_with_hasher::<H, MAX_DEPTH>(&args.proof_vec, &mut proof);
+
+        log_compute!();
+        self.try_apply_proof(
+            args.current_root,
+            args.low_leaf.hash_with_hasher::<H>(),
+            low_leaf.hash_with_hasher::<H>(),
+            &mut proof,
+            args.low_leaf_index,
+            true,
+        )?;
+        self.append(leaf.hash_with_hasher::<H>())
+    }
+
     /// Returns the Current Seq of the tree, the seq is the monotonic counter of
     /// the tree operations that is incremented every time a mutable
     /// operation is performed on the tree.
@@ -476,6 +932,8 @@ impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>
         leaf_index: u32,
         allow_inferred_proof: bool,
     ) -> Result<bool, ConcurrentMerkleTreeError> {
+        check_proof_nodes::<H>(&[leaf])?;
+        check_proof_nodes::<H>(proof)?;
         let mask: usize = MAX_BUFFER_SIZE - 1;
         let (changelog_index, use_full_buffer) = match self.find_root_in_changelog(current_root) {
             Some(matching_changelog_index) => (matching_changelog_index, false),
@@ -520,7 +978,10 @@ impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>
             solana_logging!("Leaf index out of bounds for max_depth");
             return false;
         }
-        recompute(leaf, proof, leaf_index) == self.get_root()
+        if check_proof_nodes::<H>(&[leaf]).is_err() || check_proof_nodes::<H>(proof).is_err() {
+            return false;
+        }
+        recompute_with_hasher::<H>(leaf, proof, leaf_index) == self.get_root()
     }
 
     /// Note: Enabling `allow_inferred_proof` will fast forward the given proof
@@ -565,7 +1026,7 @@ impl<const MAX_DEPTH: usize, const MAX_BUFFER_SIZE: usize>
     fn update_buffers_from_proof(&mut self, start: Node, proof: &[Node], index: u32) -> Node {
         let change_log = &mut self.change_logs[self.active_index as usize];
         // Also updates change_log's current root
-        let root = change_log.replace_and_recompute_path(index, start, proof);
+        let root = change_log.replace_and_recompute_path_with_hasher::<H>(index, start, proof);
         // Update rightmost path if possible
         if self.rightmost_proof.index < (1 << MAX_DEPTH) {
             if index < self.rightmost_proof.index {
@@ -0,0 +1,1084 @@
+use {
+    crate::{
+        changelog::ChangeLog,
+        concurrent_merkle_tree::{
+            AppendBatchArgs, AppendSubtreeArgs, FillEmptyOrAppendArgs, InitializeWithRootArgs,
+            InsertIndexedLeafArgs, MigrateArgs, ProveLeafArgs, ProveNonMembershipArgs, SetLeafArgs,
+            SetLeavesArgs,
+        },
+        error::ConcurrentMerkleTreeError,
+        hash::{
+            append_subtree_with_hasher, check_proof_nodes, extend_proof_with_hasher,
+            extend_root_with_hasher, fill_in_multi_proof_with_hasher, hash_subtree_with_hasher,
+            hash_to_parent_with_hasher, next_subtree_height, recompute_with_hasher, Hasher, Keccak,
+            MAX_SUPPORTED_DEPTH,
+        },
+        node::{
+            empty_node_cached_mut_with_hasher, empty_node_cached_with_hasher,
+            empty_node_with_hasher, Node, EMPTY,
+        },
+        path::Path,
+    },
+    log_compute, solana_logging,
+    std::{marker::PhantomData, mem::size_of},
+};
+
+const NODE_SIZE: usize = size_of::<Node>();
+
+/// Offsets of the counters at the start of the tree
+const SEQUENCE_NUMBER_OFFSET: usize = 0;
+const ACTIVE_INDEX_OFFSET: usize = 8;
+const BUFFER_SIZE_OFFSET: usize = 16;
+const CHANGE_LOGS_OFFSET: usize = 24;
+
+/// Changelog of a tree whose depth is only known at runtime
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DynamicChangeLog {
+    /// Root of the tree after the change
+    pub root: Node,
+    /// Nodes of the changed path, from the leaf up
+    pub path: Vec<Node>,
+    /// Index of the changed leaf
+    pub index: u32,
+}
+
+impl<const MAX_DEPTH: usize> From<ChangeLog<MAX_DEPTH>> for DynamicChangeLog {
+    fn from(change_log: ChangeLog<MAX_DEPTH>) -> Self {
+        Self {
+            root: change_log.root,
+            path: change_log.path.to_vec(),
+            index: change_log.index,
+        }
+    }
+}
+
+/// Proof of the rightmost leaf of a tree whose depth is only known at runtime
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DynamicPath {
+    /// Siblings of the path of the leaf, from the leaf up
+    pub proof: Vec<Node>,
+    /// Value of the leaf
+    pub leaf: Node,
+    /// Index of the leaf
+    pub index: u32,
+}
+
+impl<const MAX_DEPTH: usize> From<Path<MAX_DEPTH>> for DynamicPath {
+    fn from(path: Path<MAX_DEPTH>) -> Self {
+        Self {
+            proof: path.proof.to_vec(),
+            leaf: path.leaf,
+            index: path.index,
+        }
+    }
+}
+
+/// Zero-copy view of a [ConcurrentMerkleTree](crate::concurrent_merkle_tree::ConcurrentMerkleTree)
+/// whose max depth and max buffer size are only known at runtime.
+///
+/// The view reads and writes the tree in place in `data`, which has the exact
+/// layout of a `ConcurrentMerkleTree<MAX_DEPTH, MAX_BUFFER_SIZE>`, so both can
+/// be used interchangeably on the same bytes. Any max depth up to 30 and any
+/// power of 2 max buffer size are supported, without a match over a fixed list
+/// of tree sizes.
+///
+/// Fields are read as little-endian integers at their offsets, so `data`
+/// doesn't need to be aligned.
+pub struct ConcurrentMerkleTreeView<T, H: Hasher = Keccak> {
+    data: T,
+    max_depth: usize,
+    max_buffer_size: usize,
+    _hasher: PhantomData<H>,
+}
+
+impl<T: AsRef<[u8]>, H: Hasher> ConcurrentMerkleTreeView<T, H> {
+    /// Returns the size of a tree of the given dimensions, the same as
+    /// `size_of::<ConcurrentMerkleTree<MAX_DEPTH, MAX_BUFFER_SIZE>>()`
+    pub fn size(max_depth: u32, max_buffer_size: u32) -> Result<usize, ConcurrentMerkleTreeError> {
+        check_dimensions(max_depth, max_buffer_size)?;
+        Ok(CHANGE_LOGS_OFFSET + (max_buffer_size as usize + 1) * path_size(max_depth as usize))
+    }
+
+    /// Creates a view of the tree stored at the start of `data`
+    pub fn new(
+        data: T,
+        max_depth: u32,
+        max_buffer_size: u32,
+    ) -> Result<Self, ConcurrentMerkleTreeError> {
+        if data.as_ref().len() < Self::size(max_depth, max_buffer_size)? {
+            return Err(ConcurrentMerkleTreeError::InvalidTreeSize);
+        }
+        Ok(Self {
+            data,
+            max_depth: max_depth as usize,
+            max_buffer_size: max_buffer_size as usize,
+            _hasher: PhantomData,
+        })
+    }
+
+    /// Returns the bytes of the tree
+    pub fn data(&self) -> &[u8] {
+        self.data.as_ref()
+    }
+
+    /// Returns the underlying data
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+
+    /// Returns the max depth of the tree
+    pub fn get_max_depth(&self) -> u32 {
+        self.max_depth as u32
+    }
+
+    /// Returns the max buffer size of the tree
+    pub fn get_max_buffer_size(&self) -> u32 {
+        self.max_buffer_size as u32
+    }
+
+    /// Returns the Current Seq of the tree, the seq is the monotonic counter of
+    /// the tree operations that is incremented every time a mutable
+    /// operation is performed on the tree.
+    pub fn get_seq(&self) -> u64 {
+        self.read_u64(SEQUENCE_NUMBER_OFFSET)
+    }
+
+    /// Returns the index of the most recent changelog
+    pub fn get_active_index(&self) -> u64 {
+        self.read_u64(ACTIVE_INDEX_OFFSET)
+    }
+
+    /// Returns the number of changelogs in use
+    pub fn get_buffer_size(&self) -> u64 {
+        self.read_u64(BUFFER_SIZE_OFFSET)
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        !(self.get_buffer_size() == 0 && self.get_seq() == 0 && self.get_active_index() == 0)
+    }
+
+    /// Returns the current root of the merkle tree
+    pub fn get_root(&self) -> Node {
+        if !self.is_initialized() {
+            return EMPTY;
+        }
+        self.read_node(self.change_log_offset(self.get_active_index() as usize))
+    }
+
+    /// Returns the most recent changelog
+    pub fn get_change_log(&self) -> DynamicChangeLog {
+        if !self.is_initialized() {
+            solana_logging!("Tree is not initialized, returning default change log");
+            return DynamicChangeLog {
+                root: EMPTY,
+                path: vec![EMPTY; self.max_depth],
+                index: 0,
+            };
+        }
+        self.read_change_log(self.get_active_index() as usize)
+    }
+
+    /// Returns the changelog stored at `change_log_index` in the changelog
+    /// buffer, or `None` if the index is out of the buffer
+    pub fn get_change_log_at(&self, change_log_index: usize) -> Option<DynamicChangeLog> {
+        (change_log_index < self.max_buffer_size).then(|| self.read_change_log(change_log_index))
+    }
+
+    /// Returns up to `count` most recent changelogs, oldest first
+    pub fn get_change_logs(&self, count: usize) -> Vec<DynamicChangeLog> {
+        if !self.is_initialized() {
+            solana_logging!("Tree is not initialized, returning no change logs");
+            return vec![];
+        }
+        let mask = self.max_buffer_size - 1;
+        let active_index = self.get_active_index() as usize;
+        let count = count.min(self.get_buffer_size() as usize);
+        (0..count)
+            .rev()
+            .map(|i| self.read_change_log(active_index.wrapping_sub(i) & mask))
+            .collect()
+    }
+
+    /// Returns the proof of the rightmost leaf of the tree
+    pub fn get_rightmost_proof(&self) -> DynamicPath {
+        let offset = self.rightmost_proof_offset();
+        DynamicPath {
+            proof: self.read_nodes(offset, self.max_depth),
+            leaf: self.read_node(offset + self.max_depth * NODE_SIZE),
+            index: self.read_u32(offset + (self.max_depth + 1) * NODE_SIZE),
+        }
+    }
+
+    /// Errors if one of the leaves of the current merkle tree is non-EMPTY
+    pub fn prove_tree_is_empty(&self) -> Result<(), ConcurrentMerkleTreeError> {
+        if !self.is_initialized() {
+            return Err(ConcurrentMerkleTreeError::TreeNotInitialized);
+        }
+        if self.get_root() != empty_node_with_hasher::<H>(self.max_depth as u32) {
+            return Err(ConcurrentMerkleTreeError::TreeNonEmpty);
+        }
+        Ok(())
+    }
+
+    /// This method will fail if the leaf cannot be proven to exist in the
+    /// current tree root, like
+    /// [prove_leaf](crate::concurrent_merkle_tree::ConcurrentMerkleTree::prove_leaf).
+    pub fn prove_leaf(&self, args: &ProveLeafArgs) -> Result<(), ConcurrentMerkleTreeError> {
+        check_leaf_index(args.index, self.max_depth)?;
+        if !self.is_initialized() {
+            return Err(ConcurrentMerkleTreeError::TreeNotInitialized);
+        }
+        let rightmost_index = self.get_rightmost_index();
+        if args.index > rightmost_index {
+            solana_logging!(
+                "Received an index larger than the rightmost index {} > {}",
+                args.index,
+                rightmost_index
+            );
+            return Err(ConcurrentMerkleTreeError::LeafIndexOutOfBounds);
+        }
+        let mut proof = self.fill_in_proof(&args.proof_vec)?;
+        let valid_root =
+            self.check_valid_leaf(args.current_root, args.leaf, &mut proof, args.index, true)?;
+        if !valid_root {
+            solana_logging!("Proof failed to verify");
+            return Err(ConcurrentMerkleTreeError::InvalidProof);
+        }
+        Ok(())
+    }
+
+    /// This method will fail if `args.value` cannot be proven to be absent
+    /// from the current tree root of an indexed tree, like
+    /// [prove_non_membership](crate::concurrent_merkle_tree::ConcurrentMerkleTree::prove_non_membership).
+    pub fn prove_non_membership(
+        &self,
+        args: &ProveNonMembershipArgs,
+    ) -> Result<(), ConcurrentMerkleTreeError> {
+        check_leaf_index(args.low_leaf_index, self.max_depth)?;
+        if !self.is_initialized() {
+            return Err(ConcurrentMerkleTreeError::TreeNotInitialized);
+        }
+        if !args.low_leaf.is_low_leaf_of(&args.value) {
+            return Err(ConcurrentMerkleTreeError::InvalidLowLeaf);
+        }
+        if !args.low_leaf.is_valid_with_hasher::<H>() {
+            return Err(ConcurrentMerkleTreeError::InvalidLeaf);
+        }
+        if args.low_leaf_index >= self.get_rightmost_index() {
+            return Err(ConcurrentMerkleTreeError::LeafIndexOutOfBounds);
+        }
+        let mut proof = self.fill_in_proof(&args.proof_vec)?;
+        let valid_root = self.check_valid_leaf(
+            args.current_root,
+            args.low_leaf.hash_with_hasher::<H>(),
+            &mut proof,
+            args.low_leaf_index,
+            true,
+        )?;
+        if !valid_root {
+            solana_logging!("Proof failed to verify");
+            return Err(ConcurrentMerkleTreeError::InvalidProof);
+        }
+        Ok(())
+    }
+
+    /// Returns the arguments initializing a deeper tree whose leftmost subtree
+    /// is this tree, like
+    /// [get_migration_args](crate::concurrent_merkle_tree::ConcurrentMerkleTree::get_migration_args).
+    pub fn get_migration_args(
+        &self,
+        args: &MigrateArgs,
+    ) -> Result<InitializeWithRootArgs, ConcurrentMerkleTreeError> {
+        check_migration_depth(args.max_depth, self.max_depth)?;
+        if !self.is_initialized() {
+            return Err(ConcurrentMerkleTreeError::TreeNotInitialized);
+        }
+        let rightmost_index = self.get_rightmost_index();
+        if rightmost_index == 0 {
+            return Err(ConcurrentMerkleTreeError::CannotMigrateEmptyTree);
+        }
+        let index = rightmost_index - 1;
+        let mut proof = self.fill_in_proof(&args.proof_vec)?;
+        if !self.check_valid_leaf(
+            args.current_root,
+            args.rightmost_leaf,
+            &mut proof,
+            index,
+            true,
+        )? {
+            solana_logging!("Proof failed to verify");
+            return Err(ConcurrentMerkleTreeError::InvalidProof);
+        }
+        Ok(InitializeWithRootArgs {
+            root: extend_root_with_hasher::<H>(
+                self.get_root(),
+                self.max_depth as u32,
+                args.max_depth,
+            ),
+            rightmost_leaf: args.rightmost_leaf,
+            proof_vec: extend_proof_with_hasher::<H>(&proof, args.max_depth),
+            index,
+        })
+    }
+
+    /// Checks that the proof provided is valid for the current root.
+    pub fn check_valid_proof(&self, leaf: Node, proof: &[Node], leaf_index: u32) -> bool {
+        if !self.is_initialized() {
+            solana_logging!("Tree is not initialized, returning false");
+            return false;
+        }
+        if check_leaf_index(leaf_index, self.max_depth).is_err() {
+            solana_logging!("Leaf index out of bounds for max_depth");
+            return false;
+        }
+        if check_proof_nodes::<H>(&[leaf]).is_err() || check_proof_nodes::<H>(proof).is_err() {
+            return false;
+        }
+        proof.len() == self.max_depth
+            && recompute_with_hasher::<H>(leaf, proof, leaf_index) == self.get_root()
+    }
+
+    fn read_u64(&self, offset: usize) -> u64 {
+        u64::from_le_bytes(self.data()[offset..offset + 8].try_into().unwrap())
+    }
+
+    fn read_u32(&self, offset: usize) -> u32 {
+        u32::from_le_bytes(self.data()[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn read_node(&self, offset: usize) -> Node {
+        self.data()[offset..offset + NODE_SIZE].try_into().unwrap()
+    }
+
+    fn read_nodes(&self, offset: usize, count: usize) -> Vec<Node> {
+        (0..count)
+            .map(|i| self.read_node(offset + i * NODE_SIZE))
+            .collect()
+    }
+
+    /// A changelog is laid out as its root, its path and its index
+    fn change_log_offset(&self, change_log_index: usize) -> usize {
+        CHANGE_LOGS_OFFSET + change_log_index * path_size(self.max_depth)
+    }
+
+    /// The rightmost proof is laid out as its proof, its leaf and its index
+    fn rightmost_proof_offset(&self) -> usize {
+        self.change_log_offset(self.max_buffer_size)
+    }
+
+    fn get_rightmost_index(&self) -> u32 {
+        self.read_u32(self.rightmost_proof_offset() + (self.max_depth + 1) * NODE_SIZE)
+    }
+
+    fn read_change_log(&self, change_log_index: usize) -> DynamicChangeLog {
+        let offset = self.change_log_offset(change_log_index);
+        DynamicChangeLog {
+            root: self.read_node(offset),
+            path: self.read_nodes(offset + NODE_SIZE, self.max_depth),
+            index: self.read_u32(offset + (self.max_depth + 1) * NODE_SIZE),
+        }
+    }
+
+    /// Fills in proof to the height of the tree, inferring missing nodes as
+    /// empty node hashes
+    fn fill_in_proof(&self, proof_vec: &[Node]) -> Result<Vec<Node>, ConcurrentMerkleTreeError> {
+        if proof_vec.len() > self.max_depth {
+            return Err(ConcurrentMerkleTreeError::InvalidProof);
+        }
+        let mut proof = proof_vec.to_vec();
+        proof.extend(
+            (proof_vec.len()..self.max_depth).map(|i| empty_node_with_hasher::<H>(i as u32)),
+        );
+        Ok(proof)
+    }
+
+    /// Fast forwards the given proof and corresponding leaf by applying the
+    /// update of the changelog at `change_log_index`, like
+    /// [update_proof_or_leaf](ChangeLog::update_proof_or_leaf)
+    fn update_proof_or_leaf(
+        &self,
+        change_log_index: usize,
+        leaf_index: u32,
+        proof: &mut [Node],
+        leaf: &mut Node,
+    ) {
+        let offset = self.change_log_offset(change_log_index);
+        let change_log_leaf_index = self.read_u32(offset + (self.max_depth + 1) * NODE_SIZE);
+        if leaf_index != change_log_leaf_index {
+            let padding = 32 - self.max_depth;
+            // This bit math is used to identify which node in the proof
+            // we need to swap for a corresponding node in a saved change log
+            let common_path_len =
+                ((leaf_index ^ change_log_leaf_index) << padding).leading_zeros() as usize;
+            let critbit_index = (self.max_depth - 1) - common_path_len;
+            proof[critbit_index] = self.read_node(offset + (critbit_index + 1) * NODE_SIZE);
+        } else {
+            *leaf = self.read_node(offset + NODE_SIZE);
+        }
+    }
+
+    /// Modifies the `proof` for leaf at `leaf_index` in place by
+    /// fast-forwarding it through the changelogs, starting after index
+    /// `changelog_buffer_index`. Returns false if the leaf was updated in the
+    /// change log
+    fn fast_forward_proof(
+        &self,
+        leaf: &mut Node,
+        proof: &mut [Node],
+        leaf_index: u32,
+        mut changelog_buffer_index: u64,
+        use_full_buffer: bool,
+    ) -> bool {
+        solana_logging!(
+            "Fast-forwarding proof, starting index {}",
+            changelog_buffer_index
+        );
+        let mask = self.max_buffer_size as u64 - 1;
+        let active_index = self.get_active_index();
+
+        let mut updated_leaf = *leaf;
+        log_compute!();
+        loop {
+            if !use_full_buffer && changelog_buffer_index == active_index {
+                break;
+            }
+            changelog_buffer_index = (changelog_buffer_index + 1) & mask;
+            self.update_proof_or_leaf(
+                changelog_buffer_index as usize,
+                leaf_index,
+                proof,
+                &mut updated_leaf,
+            );
+            if use_full_buffer && changelog_buffer_index == active_index {
+                break;
+            }
+        }
+        log_compute!();
+        let proof_leaf_unchanged = updated_leaf == *leaf;
+        *leaf = updated_leaf;
+        proof_leaf_unchanged
+    }
+
+    fn find_root_in_changelog(&self, current_root: Node) -> Option<u64> {
+        let mask = self.max_buffer_size as u64 - 1;
+        let active_index = self.get_active_index();
+        for i in 0..self.get_buffer_size() {
+            let j = active_index.wrapping_sub(i) & mask;
+            if self.read_node(self.change_log_offset(j as usize)) == current_root {
+                return Some(j);
+            }
+        }
+        None
+    }
+
+    fn check_valid_leaf(
+        &self,
+        current_root: Node,
+        leaf: Node,
+        proof: &mut [Node],
+        leaf_index: u32,
+        allow_inferred_proof: bool,
+    ) -> Result<bool, ConcurrentMerkleTreeError> {
+        check_proof_nodes::<H>(&[leaf])?;
+        check_proof_nodes::<H>(proof)?;
+        let mask = self.max_buffer_size as u64 - 1;
+        let (changelog_index, use_full_buffer) = match self.find_root_in_changelog(current_root) {
+            Some(matching_changelog_index) => (matching_changelog_index, false),
+            None => {
+                if allow_inferred_proof {
+                    solana_logging!("Failed to find root in change log -> replaying full buffer");
+                    (
+                        self.get_active_index()
+                            .wrapping_sub(self.get_buffer_size() - 1)
+                            & mask,
+                        true,
+                    )
+                } else {
+                    return Err(ConcurrentMerkleTreeError::RootNotFound);
+                }
+            }
+        };
+        let mut updatable_leaf_node = leaf;
+        let proof_leaf_unchanged = self.fast_forward_proof(
+            &mut updatable_leaf_node,
+            proof,
+            leaf_index,
+            changelog_index,
+            use_full_buffer,
+        );
+        if !proof_leaf_unchanged {
+            return Err(ConcurrentMerkleTreeError::LeafContentsModified);
+        }
+        Ok(self.check_valid_proof(updatable_leaf_node, proof, leaf_index))
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>, H: Hasher> ConcurrentMerkleTreeView<T, H> {
+    /// This is the trustless initialization method that should be used in most
+    /// cases.
+    pub fn initialize(&mut self) -> Result<Node, ConcurrentMerkleTreeError> {
+        if self.is_initialized() {
+            return Err(ConcurrentMerkleTreeError::TreeAlreadyInitialized);
+        }
+        let mut empty_node_cache = [Node::default(); MAX_SUPPORTED_DEPTH];
+        let mut path = Vec::with_capacity(self.max_depth);
+        for i in 0..self.max_depth {
+            path.push(empty_node_cached_mut_with_hasher::<H, MAX_SUPPORTED_DEPTH>(
+                i as u32,
+                &mut empty_node_cache,
+            ));
+        }
+        let root = empty_node_cached_with_hasher::<H, MAX_SUPPORTED_DEPTH>(
+            self.max_depth as u32,
+            &empty_node_cache,
+        );
+        self.write_change_log(0, &root, &path, 0);
+        self.write_u64(SEQUENCE_NUMBER_OFFSET, 0);
+        self.write_u64(ACTIVE_INDEX_OFFSET, 0);
+        self.write_u64(BUFFER_SIZE_OFFSET, 1);
+        self.write_rightmost_proof(&path, &EMPTY, 0);
+        Ok(root)
+    }
+
+    /// This is a trustful initialization method that assumes the root contains
+    /// the expected leaves, like
+    /// [initialize_with_root](crate::concurrent_merkle_tree::ConcurrentMerkleTree::initialize_with_root).
+    pub fn initialize_with_root(
+        &mut self,
+        args: &InitializeWithRootArgs,
+    ) -> Result<Node, ConcurrentMerkleTreeError> {
+        check_leaf_index(args.index, self.max_depth)?;
+        check_leaf::<H>(&args.rightmost_leaf)?;
+        if self.is_initialized() {
+            return Err(ConcurrentMerkleTreeError::TreeAlreadyInitialized);
+        }
+        if args.proof_vec.len() != self.max_depth {
+            return Err(ConcurrentMerkleTreeError::InvalidProof);
+        }
+        check_proof_nodes::<H>(&args.proof_vec)?;
+        let offset = self.change_log_offset(0);
+        self.write_node(offset, &args.root);
+        self.write_u64(SEQUENCE_NUMBER_OFFSET, 1);
+        self.write_u64(ACTIVE_INDEX_OFFSET, 0);
+        self.write_u64(BUFFER_SIZE_OFFSET, 1);
+        self.write_rightmost_proof(&args.proof_vec, &args.rightmost_leaf, args.index + 1);
+        if args.root != recompute_with_hasher::<H>(args.rightmost_leaf, &args.proof_vec, args.index)
+        {
+            solana_logging!("Proof failed to verify");
+            return Err(ConcurrentMerkleTreeError::InvalidProof);
+        }
+        Ok(args.root)
+    }
+
+    /// Appending a non-empty Node will always succeed .
+    pub fn append(&mut self, mut node: Node) -> Result<Node, ConcurrentMerkleTreeError> {
+        if !self.is_initialized() {
+            return Err(ConcurrentMerkleTreeError::TreeNotInitialized);
+        }
+        if node == EMPTY {
+            return Err(ConcurrentMerkleTreeError::CannotAppendEmptyNode);
+        }
+        check_leaf::<H>(&node)?;
+        let DynamicPath {
+            proof: mut rightmost_proof,
+            leaf: rightmost_leaf,
+            index: rightmost_index,
+        } = self.get_rightmost_proof();
+        if rightmost_index >= 1 << self.max_depth {
+            return Err(ConcurrentMerkleTreeError::TreeFull);
+        }
+        if rightmost_index == 0 {
+            return self.initialize_tree_from_append(node, rightmost_proof);
+        }
+        let leaf = node;
+        let intersection = rightmost_index.trailing_zeros() as usize;
+        let mut change_list = vec![EMPTY; self.max_depth];
+        let mut intersection_node = rightmost_leaf;
+        let mut empty_node_cache = [Node::default(); MAX_SUPPORTED_DEPTH];
+
+        for (i, cl_item) in change_list.iter_mut().enumerate() {
+            *cl_item = node;
+            match i {
+                i if i < intersection => {
+                    // Compute proof to the appended node from empty nodes
+                    let sibling = empty_node_cached_mut_with_hasher::<H, MAX_SUPPORTED_DEPTH>(
+                        i as u32,
+                        &mut empty_node_cache,
+                    );
+                    hash_to_parent_with_hasher::<H>(
+                        &mut intersection_node,
+                        &rightmost_proof[i],
+                        ((rightmost_index - 1) >> i) & 1 == 0,
+                    );
+                    hash_to_parent_with_hasher::<H>(&mut node, &sibling, true);
+                    rightmost_proof[i] = sibling;
+                }
+                i if i == intersection => {
+                    // Compute the where the new node intersects the main tree
+                    hash_to_parent_with_hasher::<H>(&mut node, &intersection_node, false);
+                    rightmost_proof[intersection] = intersection_node;
+                }
+                _ => {
+                    // Update the change list path up to the root
+                    hash_to_parent_with_hasher::<H>(
+                        &mut node,
+                        &rightmost_proof[i],
+                        ((rightmost_index - 1) >> i) & 1 == 0,
+                    );
+                }
+            }
+        }
+
+        self.update_internal_counters();
+        let active_index = self.get_active_index() as usize;
+        self.write_change_log(active_index, &node, &change_list, rightmost_index);
+        self.write_rightmost_proof(&rightmost_proof, &leaf, rightmost_index + 1);
+        Ok(node)
+    }
+
+    /// Appends a whole subtree with a single changelog, like
+    /// [append_subtree](crate::concurrent_merkle_tree::ConcurrentMerkleTree::append_subtree).
+    pub fn append_subtree(
+        &mut self,
+        args: &AppendSubtreeArgs,
+    ) -> Result<Node, ConcurrentMerkleTreeError> {
+        if !self.is_initialized() {
+            return Err(ConcurrentMerkleTreeError::TreeNotInitialized);
+        }
+        check_append_leaf::<H>(&args.rightmost_leaf)?;
+        check_proof_nodes::<H>(&args.proof_vec)?;
+        let height = args.proof_vec.len();
+        if height > self.max_depth {
+            return Err(ConcurrentMerkleTreeError::TreeFull);
+        }
+        let rightmost_index = self.get_rightmost_index();
+        check_capacity(rightmost_index, 1 << height, self.max_depth)?;
+        if rightmost_index.trailing_zeros() < height as u32 {
+            return Err(ConcurrentMerkleTreeError::SubtreeNotAligned);
+        }
+        let subtree_rightmost_index = (1 << height) - 1;
+        if recompute_with_hasher::<H>(
+            args.rightmost_leaf,
+            &args.proof_vec,
+            subtree_rightmost_index,
+        ) != args.subtree_root
+        {
+            solana_logging!("Subtree proof failed to verify");
+            return Err(ConcurrentMerkleTreeError::InvalidProof);
+        }
+
+        log_compute!();
+        let DynamicPath {
+            proof: mut rightmost_proof,
+            leaf: rightmost_leaf,
+            ..
+        } = self.get_rightmost_proof();
+        let mut path = vec![EMPTY; self.max_depth];
+        let root = append_subtree_with_hasher::<H>(
+            &mut rightmost_proof,
+            rightmost_leaf,
+            rightmost_index,
+            args.rightmost_leaf,
+            &args.proof_vec,
+            &mut path,
+        );
+        let index = rightmost_index + subtree_rightmost_index;
+        self.update_internal_counters();
+        let active_index = self.get_active_index() as usize;
+        self.write_change_log(active_index, &root, &path, index);
+        self.write_rightmost_proof(&rightmost_proof, &args.rightmost_leaf, index + 1);
+        Ok(root)
+    }
+
+    /// Appends several non-empty leaves, as described in [AppendBatchArgs].
+    pub fn append_batch(
+        &mut self,
+        args: &AppendBatchArgs,
+    ) -> Result<Node, ConcurrentMerkleTreeError> {
+        if !self.is_initialized() {
+            return Err(ConcurrentMerkleTreeError::TreeNotInitialized);
+        }
+        if args.leaves.is_empty() {
+            return Err(ConcurrentMerkleTreeError::EmptyBatch);
+        }
+        for leaf in args.leaves.iter() {
+            check_append_leaf::<H>(leaf)?;
+        }
+        check_capacity(
+            self.get_rightmost_index(),
+            args.leaves.len(),
+            self.max_depth,
+        )?;
+
+        let mut root = self.get_root();
+        let mut leaves = &args.leaves[..];
+        while !leaves.is_empty() {
+            let height = next_subtree_height(
+                self.get_rightmost_index(),
+                leaves.len(),
+                args.max_subtree_height,
+            );
+            let (subtree, rest) = leaves.split_at(1 << height);
+            let (subtree_root, proof_vec) = hash_subtree_with_hasher::<H>(subtree);
+            root = self.append_subtree(&AppendSubtreeArgs {
+                subtree_root,
+                rightmost_leaf: subtree[subtree.len() - 1],
+                proof_vec,
+            })?;
+            leaves = rest;
+        }
+        Ok(root)
+    }
+
+    /// This method will `set_leaf` if the leaf at `index` is an empty node,
+    /// otherwise it will `append` the new leaf.
+    pub fn fill_empty_or_append(
+        &mut self,
+        args: &FillEmptyOrAppendArgs,
+    ) -> Result<Node, ConcurrentMerkleTreeError> {
+        check_leaf_index(args.index, self.max_depth)?;
+        check_leaf::<H>(&args.leaf)?;
+        if !self.is_initialized() {
+            return Err(ConcurrentMerkleTreeError::TreeNotInitialized);
+        }
+
+        let mut proof = self.fill_in_proof(&args.proof_vec)?;
+
+        log_compute!();
+        match self.try_apply_proof(
+            args.current_root,
+            EMPTY,
+            args.leaf,
+            &mut proof,
+            args.index,
+            false,
+        ) {
+            Ok(new_root) => Ok(new_root),
+            Err(error) => match error {
+                ConcurrentMerkleTreeError::LeafContentsModified => self.append(args.leaf),
+                _ => Err(error),
+            },
+        }
+    }
+
+    /// This method will update the leaf at `index`.
+    ///
+    /// However if the proof cannot be verified, this method will fail.
+    pub fn set_leaf(&mut self, args: &SetLeafArgs) -> Result<Node, ConcurrentMerkleTreeError> {
+        check_leaf_index(args.index, self.max_depth)?;
+        check_leaf::<H>(&args.new_leaf)?;
+        if !self.is_initialized() {
+            return Err(ConcurrentMerkleTreeError::TreeNotInitialized);
+        }
+
+        if args.index > self.get_rightmost_index() {
+            return Err(ConcurrentMerkleTreeError::LeafIndexOutOfBounds);
+        }
+        let mut proof = self.fill_in_proof(&args.proof_vec)?;
+
+        log_compute!();
+        self.try_apply_proof(
+            args.current_root,
+            args.previous_leaf,
+            args.new_leaf,
+            &mut proof,
+            args.index,
+            true,
+        )
+    }
+
+    /// This method will update the leaves of all the `args.leaves` updates,
+    /// in order, against the same `args.current_root`, like
+    /// [set_leaves](crate::concurrent_merkle_tree::ConcurrentMerkleTree::set_leaves).
+    pub fn set_leaves(&mut self, args: &SetLeavesArgs) -> Result<Node, ConcurrentMerkleTreeError> {
+        if args.leaves.is_empty() || args.leaves.windows(2).any(|w| w[0].index >= w[1].index) {
+            return Err(ConcurrentMerkleTreeError::InvalidLeafUpdates);
+        }
+        if args.leaves.len() > self.max_buffer_size {
+            return Err(ConcurrentMerkleTreeError::TooManyLeafUpdates);
+        }
+        for leaf in args.leaves.iter() {
+            check_leaf_index(leaf.index, self.max_depth)?;
+            check_leaf::<H>(&leaf.new_leaf)?;
+        }
+        if !self.is_initialized() {
+            return Err(ConcurrentMerkleTreeError::TreeNotInitialized);
+        }
+        // Only the last leaf may be the next one to be appended
+        if args.leaves[args.leaves.len() - 1].index > self.get_rightmost_index() {
+            return Err(ConcurrentMerkleTreeError::LeafIndexOutOfBounds);
+        }
+
+        let mut proofs = vec![vec![EMPTY; self.max_depth]; args.leaves.len()];
+        fill_in_multi_proof_with_hasher::<H, _>(&args.leaves, &args.proof_vec, &mut proofs)?;
+
+        log_compute!();
+        // The bytes overwritten by the updates are saved, so that the tree is
+        // left unchanged if a later update fails
+        let mask = self.max_buffer_size as u64 - 1;
+        let path_size = path_size(self.max_depth);
+        let header = self.data()[..CHANGE_LOGS_OFFSET].to_vec();
+        let rightmost_proof_offset = self.rightmost_proof_offset();
+        let rightmost_proof =
+            self.data()[rightmost_proof_offset..rightmost_proof_offset + path_size].to_vec();
+        let mut overwritten_change_logs = Vec::with_capacity(args.leaves.len());
+        let mut root = self.get_root();
+        for (leaf, proof) in args.leaves.iter().zip(proofs.iter_mut()) {
+            let offset = self.change_log_offset(((self.get_active_index() + 1) & mask) as usize);
+            overwritten_change_logs
+                .push((offset, self.data()[offset..offset + path_size].to_vec()));
+            match self.try_apply_proof(
+                args.current_root,
+                leaf.previous_leaf,
+                leaf.new_leaf,
+                proof,
+                leaf.index,
+                true,
+            ) {
+                Ok(new_root) => root = new_root,
+                Err(err) => {
+                    let data = self.bytes_mut();
+                    for (offset, change_log) in overwritten_change_logs {
+                        data[offset..offset + path_size].copy_from_slice(&change_log);
+                    }
+                    data[..CHANGE_LOGS_OFFSET].copy_from_slice(&header);
+                    data[rightmost_proof_offset..rightmost_proof_offset + path_size]
+                        .copy_from_slice(&rightmost_proof);
+                    return Err(err);
+                }
+            }
+        }
+        Ok(root)
+    }
+
+    /// This method will insert `args.value` in the sorted linked list of an
+    /// indexed tree, like
+    /// [insert_indexed_leaf](crate::concurrent_merkle_tree::ConcurrentMerkleTree::insert_indexed_leaf).
+    pub fn insert_indexed_leaf(
+        &mut self,
+        args: &InsertIndexedLeafArgs,
+    ) -> Result<Node, ConcurrentMerkleTreeError> {
+        check_leaf_index(args.low_leaf_index, self.max_depth)?;
+        if !self.is_initialized() {
+            return Err(ConcurrentMerkleTreeError::TreeNotInitialized);
+        }
+        let rightmost_index = self.get_rightmost_index();
+        if rightmost_index >= 1 << self.max_depth {
+            return Err(ConcurrentMerkleTreeError::TreeFull);
+        }
+        if args.low_leaf_index >= rightmost_index {
+            return Err(ConcurrentMerkleTreeError::LeafIndexOutOfBounds);
+        }
+        let (low_leaf, leaf) = args
+            .low_leaf
+            .insert_with_hasher::<H>(args.value, rightmost_index)?;
+        let mut proof = self.fill_in_proof(&args.proof_vec)?;
+
+        log_compute!();
+        self.try_apply_proof(
+            args.current_root,
+            args.low_leaf.hash_with_hasher::<H>(),
+            low_leaf.hash_with_hasher::<H>(),
+            &mut proof,
+            args.low_leaf_index,
+            true,
+        )?;
+        self.append(leaf.hash_with_hasher::<H>())
+    }
+
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        self.data.as_mut()
+    }
+
+    fn write_u64(&mut self, offset: usize, value: u64) {
+        self.bytes_mut()[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, offset: usize, value: u32) {
+        self.bytes_mut()[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_node(&mut self, offset: usize, node: &Node) {
+        self.bytes_mut()[offset..offset + NODE_SIZE].copy_from_slice(node);
+    }
+
+    fn write_nodes(&mut self, offset: usize, nodes: &[Node]) {
+        for (i, node) in nodes.iter().enumerate() {
+            self.write_node(offset + i * NODE_SIZE, node);
+        }
+    }
+
+    fn write_change_log(
+        &mut self,
+        change_log_index: usize,
+        root: &Node,
+        path: &[Node],
+        index: u32,
+    ) {
+        let offset = self.change_log_offset(change_log_index);
+        self.write_node(offset, root);
+        self.write_nodes(offset + NODE_SIZE, path);
+        self.write_u32(offset + (self.max_depth + 1) * NODE_SIZE, index);
+    }
+
+    fn write_rightmost_proof(&mut self, proof: &[Node], leaf: &Node, index: u32) {
+        let offset = self.rightmost_proof_offset();
+        self.write_nodes(offset, proof);
+        self.write_node(offset + self.max_depth * NODE_SIZE, leaf);
+        self.write_u32(offset + (self.max_depth + 1) * NODE_SIZE, index);
+    }
+
+    /// Only used to initialize right most path for a completely empty tree.
+    fn initialize_tree_from_append(
+        &mut self,
+        leaf: Node,
+        mut proof: Vec<Node>,
+    ) -> Result<Node, ConcurrentMerkleTreeError> {
+        let old_root = recompute_with_hasher::<H>(EMPTY, &proof, 0);
+        if old_root == empty_node_with_hasher::<H>(self.max_depth as u32) {
+            self.try_apply_proof(old_root, EMPTY, leaf, &mut proof, 0, false)
+        } else {
+            Err(ConcurrentMerkleTreeError::TreeAlreadyInitialized)
+        }
+    }
+
+    /// Note: Enabling `allow_inferred_proof` will fast forward the given proof
+    /// from the beginning of the buffer in the case that the supplied root is
+    /// not in the buffer.
+    fn try_apply_proof(
+        &mut self,
+        current_root: Node,
+        leaf: Node,
+        new_leaf: Node,
+        proof: &mut [Node],
+        leaf_index: u32,
+        allow_inferred_proof: bool,
+    ) -> Result<Node, ConcurrentMerkleTreeError> {
+        solana_logging!("Active Index: {}", self.get_active_index());
+        solana_logging!("Rightmost Index: {}", self.get_rightmost_index());
+        solana_logging!("Buffer Size: {}", self.get_buffer_size());
+        solana_logging!("Leaf Index: {}", leaf_index);
+        let valid_root =
+            self.check_valid_leaf(current_root, leaf, proof, leaf_index, allow_inferred_proof)?;
+        if !valid_root {
+            return Err(ConcurrentMerkleTreeError::InvalidProof);
+        }
+        self.update_internal_counters();
+        Ok(self.update_buffers_from_proof(new_leaf, proof, leaf_index))
+    }
+
+    /// Implements circular addition for changelog buffer index
+    fn update_internal_counters(&mut self) {
+        let mask = self.max_buffer_size as u64 - 1;
+        let active_index = (self.get_active_index() + 1) & mask;
+        self.write_u64(ACTIVE_INDEX_OFFSET, active_index);
+        let buffer_size = self.get_buffer_size();
+        if buffer_size < self.max_buffer_size as u64 {
+            self.write_u64(BUFFER_SIZE_OFFSET, buffer_size + 1);
+        }
+        let sequence_number = self.get_seq().saturating_add(1);
+        self.write_u64(SEQUENCE_NUMBER_OFFSET, sequence_number);
+    }
+
+    /// Creates a new root from a proof that is valid for the root at the
+    /// active index
+    fn update_buffers_from_proof(&mut self, start: Node, proof: &[Node], index: u32) -> Node {
+        let active_index = self.get_active_index() as usize;
+        let mut path = Vec::with_capacity(self.max_depth);
+        let mut root = start;
+        for (i, sibling) in proof.iter().enumerate() {
+            path.push(root);
+            hash_to_parent_with_hasher::<H>(&mut root, sibling, index >> i & 1 == 0);
+        }
+        self.write_change_log(active_index, &root, &path, index);
+
+        // Update rightmost path if possible
+        let DynamicPath {
+            proof: mut rightmost_proof,
+            leaf: mut rightmost_leaf,
+            index: rightmost_index,
+        } = self.get_rightmost_proof();
+        if rightmost_index < (1 << self.max_depth) {
+            if index < rightmost_index {
+                self.update_proof_or_leaf(
+                    active_index,
+                    rightmost_index - 1,
+                    &mut rightmost_proof,
+                    &mut rightmost_leaf,
+                );
+                self.write_rightmost_proof(&rightmost_proof, &rightmost_leaf, rightmost_index);
+            } else {
+                assert!(index == rightmost_index);
+                solana_logging!("Appending rightmost leaf");
+                self.write_rightmost_proof(proof, &path[0], index + 1);
+            }
+        }
+        root
+    }
+}
+
+/// Size of a changelog, and of the rightmost proof: `max_depth + 1` nodes,
+/// followed by an index and its padding
+fn path_size(max_depth: usize) -> usize {
+    (max_depth + 1) * NODE_SIZE + 8
+}
+
+fn check_dimensions(max_depth: u32, max_buffer_size: u32) -> Result<(), ConcurrentMerkleTreeError> {
+    // We cannot allow a tree depth greater than 30 because of the bit math
+    // required to update `ChangeLog`s
+    if max_depth == 0
+        || max_depth as usize > MAX_SUPPORTED_DEPTH
+        || !max_buffer_size.is_power_of_two()
+    {
+        return Err(ConcurrentMerkleTreeError::InvalidTreeSize);
+    }
+    Ok(())
+}
+
+fn check_leaf_index(leaf_index: u32, max_depth: usize) -> Result<(), ConcurrentMerkleTreeError> {
+    if leaf_index >= (1 << max_depth) {
+        return Err(ConcurrentMerkleTreeError::LeafIndexOutOfBounds);
+    }
+    Ok(())
+}
+
+fn check_leaf<H: Hasher>(leaf: &Node) -> Result<(), ConcurrentMerkleTreeError> {
+    if !H::is_valid_leaf(leaf) {
+        return Err(ConcurrentMerkleTreeError::InvalidLeaf);
+    }
+    Ok(())
+}
+
+fn check_append_leaf<H: Hasher>(leaf: &Node) -> Result<(), ConcurrentMerkleTreeError> {
+    if *leaf == EMPTY {
+        return Err(ConcurrentMerkleTreeError::CannotAppendEmptyNode);
+    }
+    check_leaf::<H>(leaf)
+}
+
+/// Checks that `num_leaves` leaves fit after the `rightmost_index` leaves of
+/// the tree
+fn check_capacity(
+    rightmost_index: u32,
+    num_leaves: usize,
+    max_depth: usize,
+) -> Result<(), ConcurrentMerkleTreeError> {
+    if rightmost_index as u64 + num_leaves as u64 > 1 << max_depth {
+        return Err(ConcurrentMerkleTreeError::TreeFull);
+    }
+    Ok(())
+}
+
+/// Checks that a tree of depth `max_depth` can hold the tree of depth `depth`
+/// as its leftmost subtree
+fn check_migration_depth(max_depth: u32, depth: usize) -> Result<(), ConcurrentMerkleTreeError> {
+    if max_depth as usize <= depth || max_depth as usize > MAX_SUPPORTED_DEPTH {
+        solana_logging!(
+            "Cannot migrate a tree of depth {} to depth {}",
+            depth,
+            max_depth
+        );
+        return Err(ConcurrentMerkleTreeError::InvalidTreeSize);
+    }
+    Ok(())
+}
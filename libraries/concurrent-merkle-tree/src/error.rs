@@ -39,4 +39,37 @@ pub enum ConcurrentMerkleTreeError {
     /// Tree has at least 1 non-EMPTY leaf
     #[error("Tree is not empty")]
     TreeNonEmpty,
+
+    /// Leaf can't be hashed by the hash function of the tree
+    #[error("Leaf is not a valid input for the hash function of the tree")]
+    InvalidLeaf,
+
+    /// Leaf updates are empty or not sorted by strictly increasing index
+    #[error("Leaf updates must be non-empty and sorted by strictly increasing index")]
+    InvalidLeafUpdates,
+
+    /// Max depth or max buffer size of the tree isn't supported, or the data
+    /// is too small to hold the tree
+    #[error("Invalid tree dimensions, or data too small for the tree")]
+    InvalidTreeSize,
+
+    /// Subtree doesn't start at a multiple of its number of leaves
+    #[error("Subtree must be aligned to the rightmost index of the tree")]
+    SubtreeNotAligned,
+
+    /// No leaves to append
+    #[error("Cannot append an empty batch of leaves")]
+    EmptyBatch,
+
+    /// A tree without leaves can't be migrated into a deeper tree
+    #[error("Cannot migrate a tree without leaves, close it instead")]
+    CannotMigrateEmptyTree,
+
+    /// Low leaf doesn't hold the largest value smaller than the value
+    #[error("Low leaf must hold the largest value of the tree smaller than the value")]
+    InvalidLowLeaf,
+
+    /// More leaf updates than changelogs held by the buffer of the tree
+    #[error("Cannot set more leaves at once than the changelog buffer holds")]
+    TooManyLeafUpdates,
 }
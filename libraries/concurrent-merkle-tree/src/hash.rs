@@ -1,13 +1,108 @@
-use {
-    crate::node::{empty_node, Node},
-    solana_program::keccak::hashv,
+use crate::{
+    concurrent_merkle_tree::LeafUpdate,
+    error::ConcurrentMerkleTreeError,
+    node::{empty_node_cached_mut_with_hasher, empty_node_with_hasher, Node},
 };
 
+/// Max depth of the trees supported by the bit math of the changelogs
+pub const MAX_SUPPORTED_DEPTH: usize = 30;
+
+/// Hash function used to compute the parent nodes of a merkle tree
+///
+/// The hash of empty nodes is derived from the hasher as well, so trees
+/// using different hashers have different empty roots.
+pub trait Hasher: Copy + Clone + Default + 'static {
+    /// Computes the parent node of `left` and `right`
+    ///
+    /// Both nodes must be valid leaves, see [is_valid_leaf](Hasher::is_valid_leaf).
+    /// The tree checks the nodes it is given before hashing them, and the
+    /// parent nodes it computes are always valid.
+    fn hash_pair(left: &Node, right: &Node) -> Node;
+
+    /// Returns true if the node can be hashed, and so stored as a leaf of the
+    /// tree or given as a proof node
+    fn is_valid_leaf(_leaf: &Node) -> bool {
+        true
+    }
+}
+
+/// Keccak-256 hasher, the default hasher of the concurrent merkle tree
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Keccak;
+
+impl Hasher for Keccak {
+    #[inline(always)]
+    fn hash_pair(left: &Node, right: &Node) -> Node {
+        solana_program::keccak::hashv(&[left, right]).to_bytes()
+    }
+}
+
+/// SHA-256 hasher, for trees verified by EVM contracts
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Sha256;
+
+impl Hasher for Sha256 {
+    #[inline(always)]
+    fn hash_pair(left: &Node, right: &Node) -> Node {
+        solana_program::hash::hashv(&[left, right]).to_bytes()
+    }
+}
+
+/// Poseidon hasher over the BN254 curve, for trees verified in ZK circuits
+///
+/// Nodes are big-endian BN254 scalar field elements, so leaves and proof nodes
+/// must be smaller than the field modulus.
+///
+/// Requires the `poseidon` feature, which pulls in `solana-poseidon` 2.x.
+#[cfg(feature = "poseidon")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Poseidon;
+
+/// Big-endian modulus of the BN254 scalar field
+#[cfg(feature = "poseidon")]
+const BN254_FIELD_MODULUS: Node = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
+];
+
+#[cfg(feature = "poseidon")]
+impl Hasher for Poseidon {
+    #[inline(always)]
+    fn hash_pair(left: &Node, right: &Node) -> Node {
+        use solana_poseidon::{Endianness, Parameters};
+        solana_poseidon::hashv(Parameters::Bn254X5, Endianness::BigEndian, &[left, right])
+            .expect("Poseidon inputs must be BN254 field elements")
+            .to_bytes()
+    }
+
+    fn is_valid_leaf(leaf: &Node) -> bool {
+        *leaf < BN254_FIELD_MODULUS
+    }
+}
+
+/// Checks that the nodes given to the tree, such as proof nodes or previous
+/// leaves, can be hashed, so that a malformed proof fails to verify instead of
+/// failing to hash
+pub(crate) fn check_proof_nodes<H: Hasher>(
+    nodes: &[Node],
+) -> Result<(), ConcurrentMerkleTreeError> {
+    if !nodes.iter().all(H::is_valid_leaf) {
+        solana_logging!("Proof nodes are not valid inputs for the hasher");
+        return Err(ConcurrentMerkleTreeError::InvalidProof);
+    }
+    Ok(())
+}
+
 /// Recomputes root of the Merkle tree from Node & proof
 pub fn recompute(leaf: Node, proof: &[Node], index: u32) -> Node {
+    recompute_with_hasher::<Keccak>(leaf, proof, index)
+}
+
+/// Recomputes root of the Merkle tree from Node & proof using the given hasher
+pub fn recompute_with_hasher<H: Hasher>(leaf: Node, proof: &[Node], index: u32) -> Node {
     let mut current_node = leaf;
     for (depth, sibling) in proof.iter().enumerate() {
-        hash_to_parent(&mut current_node, sibling, index >> depth & 1 == 0);
+        hash_to_parent_with_hasher::<H>(&mut current_node, sibling, index >> depth & 1 == 0);
     }
     current_node
 }
@@ -16,12 +111,18 @@ pub fn recompute(leaf: Node, proof: &[Node], index: u32) -> Node {
 /// `node`
 #[inline(always)]
 pub fn hash_to_parent(node: &mut Node, sibling: &Node, is_left: bool) {
-    let parent = if is_left {
-        hashv(&[node, sibling])
+    hash_to_parent_with_hasher::<Keccak>(node, sibling, is_left)
+}
+
+/// Computes the parent node of `node` and `sibling` using the given hasher and
+/// copies the result into `node`
+#[inline(always)]
+pub fn hash_to_parent_with_hasher<H: Hasher>(node: &mut Node, sibling: &Node, is_left: bool) {
+    *node = if is_left {
+        H::hash_pair(node, sibling)
     } else {
-        hashv(&[sibling, node])
+        H::hash_pair(sibling, node)
     };
-    node.copy_from_slice(parent.as_ref())
 }
 
 /// Fills in proof to the height of the concurrent merkle tree.
@@ -29,6 +130,15 @@ pub fn hash_to_parent(node: &mut Node, sibling: &Node, is_left: bool) {
 pub fn fill_in_proof<const MAX_DEPTH: usize>(
     proof_vec: &[Node],
     full_proof: &mut [Node; MAX_DEPTH],
+) {
+    fill_in_proof_with_hasher::<Keccak, MAX_DEPTH>(proof_vec, full_proof)
+}
+
+/// Fills in proof to the height of the concurrent merkle tree.
+/// Missing nodes are inferred as empty node hashes of the given hasher.
+pub fn fill_in_proof_with_hasher<H: Hasher, const MAX_DEPTH: usize>(
+    proof_vec: &[Node],
+    full_proof: &mut [Node; MAX_DEPTH],
 ) {
     solana_logging!("Attempting to fill in proof");
     if !proof_vec.is_empty() {
@@ -41,6 +151,218 @@ pub fn fill_in_proof<const MAX_DEPTH: usize>(
         .take(MAX_DEPTH)
         .skip(proof_vec.len())
     {
-        *item = empty_node(i as u32);
+        *item = empty_node_with_hasher::<H>(i as u32);
+    }
+}
+
+/// Computes the root of a tree of depth `max_depth` whose leftmost subtree is
+/// the tree of depth `depth` with the given `root`, all other leaves being
+/// empty
+pub fn extend_root(root: Node, depth: u32, max_depth: u32) -> Node {
+    extend_root_with_hasher::<Keccak>(root, depth, max_depth)
+}
+
+/// Computes the root of a tree of depth `max_depth` whose leftmost subtree is
+/// the tree of depth `depth` with the given `root`, all other leaves being
+/// empty node hashes of the given hasher
+pub fn extend_root_with_hasher<H: Hasher>(root: Node, depth: u32, max_depth: u32) -> Node {
+    let mut node = root;
+    for level in depth..max_depth {
+        hash_to_parent_with_hasher::<H>(&mut node, &empty_node_with_hasher::<H>(level), true);
+    }
+    node
+}
+
+/// Extends the proof of a leaf into the proof of the same leaf in a tree of
+/// depth `max_depth` whose leftmost subtree is the tree of the proof, as
+/// computed by [extend_root]
+pub fn extend_proof(proof: &[Node], max_depth: u32) -> Vec<Node> {
+    extend_proof_with_hasher::<Keccak>(proof, max_depth)
+}
+
+/// Extends the proof of a leaf into the proof of the same leaf in a tree of
+/// depth `max_depth` whose leftmost subtree is the tree of the proof, as
+/// computed by [extend_root_with_hasher]
+pub fn extend_proof_with_hasher<H: Hasher>(proof: &[Node], max_depth: u32) -> Vec<Node> {
+    let mut extended_proof = proof.to_vec();
+    extended_proof.extend((proof.len() as u32..max_depth).map(empty_node_with_hasher::<H>));
+    extended_proof
+}
+
+/// Computes the root of the subtree holding `leaves`, whose number must be a
+/// power of 2, and the proof of its last leaf within the subtree
+pub fn hash_subtree(leaves: &[Node]) -> (Node, Vec<Node>) {
+    hash_subtree_with_hasher::<Keccak>(leaves)
+}
+
+/// Computes the root of the subtree holding `leaves`, whose number must be a
+/// power of 2, and the proof of its last leaf within the subtree using the
+/// given hasher
+pub fn hash_subtree_with_hasher<H: Hasher>(leaves: &[Node]) -> (Node, Vec<Node>) {
+    assert!(leaves.len().is_power_of_two());
+    let mut level = leaves.to_vec();
+    let mut proof = vec![];
+    while level.len() > 1 {
+        proof.push(level[level.len() - 2]);
+        level = level
+            .chunks_exact(2)
+            .map(|pair| H::hash_pair(&pair[0], &pair[1]))
+            .collect();
+    }
+    (level[0], proof)
+}
+
+/// Returns the height of the largest subtree of at most `num_leaves` leaves
+/// and `max_height` levels which is aligned to `rightmost_index`
+pub(crate) fn next_subtree_height(rightmost_index: u32, num_leaves: usize, max_height: u32) -> u32 {
+    rightmost_index
+        .trailing_zeros()
+        .min(num_leaves.ilog2())
+        .min(max_height)
+}
+
+/// Appends the subtree whose last leaf is `subtree_rightmost_leaf`, with the
+/// proof `subtree_proof` within the subtree, right after the `rightmost_index`
+/// leaves of a tree. The tree is given by the `rightmost_proof` of its
+/// rightmost leaf `rightmost_leaf`, and the subtree must be aligned to
+/// `rightmost_index`.
+///
+/// Updates `rightmost_proof` to the proof of the last leaf of the subtree,
+/// fills in `path` with the nodes on its path and returns the new root.
+pub(crate) fn append_subtree_with_hasher<H: Hasher>(
+    rightmost_proof: &mut [Node],
+    rightmost_leaf: Node,
+    rightmost_index: u32,
+    subtree_rightmost_leaf: Node,
+    subtree_proof: &[Node],
+    path: &mut [Node],
+) -> Node {
+    let height = subtree_proof.len();
+    let intersection = rightmost_index.trailing_zeros() as usize;
+    // Compute the node where the subtree intersects the previous leaves
+    let mut intersection_node = rightmost_leaf;
+    if rightmost_index > 0 {
+        for (i, sibling) in rightmost_proof.iter().enumerate().take(intersection) {
+            hash_to_parent_with_hasher::<H>(
+                &mut intersection_node,
+                sibling,
+                ((rightmost_index - 1) >> i) & 1 == 0,
+            );
+        }
+    }
+
+    let mut node = subtree_rightmost_leaf;
+    let mut empty_node_cache = [Node::default(); MAX_SUPPORTED_DEPTH];
+    for (i, path_node) in path.iter_mut().enumerate() {
+        *path_node = node;
+        if i < height {
+            // The last leaf of the subtree is the rightmost child at every level
+            rightmost_proof[i] = subtree_proof[i];
+            hash_to_parent_with_hasher::<H>(&mut node, &subtree_proof[i], false);
+        } else if i < intersection {
+            // Nothing was appended to the right of the subtree
+            let sibling = empty_node_cached_mut_with_hasher::<H, MAX_SUPPORTED_DEPTH>(
+                i as u32,
+                &mut empty_node_cache,
+            );
+            hash_to_parent_with_hasher::<H>(&mut node, &sibling, true);
+            rightmost_proof[i] = sibling;
+        } else if i == intersection {
+            hash_to_parent_with_hasher::<H>(&mut node, &intersection_node, false);
+            rightmost_proof[i] = intersection_node;
+        } else {
+            // Update the path up to the root
+            hash_to_parent_with_hasher::<H>(
+                &mut node,
+                &rightmost_proof[i],
+                (rightmost_index >> i) & 1 == 0,
+            );
+        }
+    }
+    node
+}
+
+/// Returns the positions, at `level`, of the proof nodes shared by the leaves
+/// at the sorted and deduplicated `indices`: the siblings of their ancestors
+/// which aren't ancestors of one of the leaves themselves.
+pub fn multi_proof_positions(indices: &[u32], level: u32) -> Vec<u32> {
+    let mut ancestors: Vec<u32> = indices.iter().map(|index| index >> level).collect();
+    ancestors.dedup();
+    let mut positions = vec![];
+    for (i, ancestor) in ancestors.iter().enumerate() {
+        let has_sibling = if ancestor & 1 == 0 {
+            ancestors.get(i + 1) == Some(&(ancestor + 1))
+        } else {
+            i > 0 && ancestors[i - 1] == ancestor - 1
+        };
+        if !has_sibling {
+            positions.push(ancestor ^ 1);
+        }
+    }
+    positions
+}
+
+/// Expands the proof shared by the sorted `leaves` into the full proof of
+/// every leaf, for the tree holding the previous leaves. The depth of the tree
+/// is the length of the `proofs`, one per leaf.
+pub(crate) fn fill_in_multi_proof_with_hasher<H: Hasher, P: AsMut<[Node]>>(
+    leaves: &[LeafUpdate],
+    proof_vec: &[Node],
+    proofs: &mut [P],
+) -> Result<(), ConcurrentMerkleTreeError> {
+    check_proof_nodes::<H>(proof_vec)?;
+    for leaf in leaves {
+        check_proof_nodes::<H>(&[leaf.previous_leaf])?;
+    }
+    let max_depth = proofs.first_mut().map_or(0, |proof| proof.as_mut().len());
+    let mut empty_node_cache = [Node::default(); MAX_SUPPORTED_DEPTH];
+    let mut proof_nodes = proof_vec.iter();
+    // Nodes of the updated paths at the current level, sorted by position
+    let mut nodes: Vec<(u32, Node)> = leaves
+        .iter()
+        .map(|leaf| (leaf.index, leaf.previous_leaf))
+        .collect();
+    for level in 0..max_depth {
+        let mut parents = Vec::with_capacity(nodes.len());
+        let mut leaf_idx = 0;
+        let mut i = 0;
+        while i < nodes.len() {
+            let (position, node) = nodes[i];
+            let (left, right) =
+                if position & 1 == 0 && i + 1 < nodes.len() && nodes[i + 1].0 == position + 1 {
+                    i += 1;
+                    (node, nodes[i].1)
+                } else {
+                    let sibling = match proof_nodes.next() {
+                        Some(sibling) => *sibling,
+                        None => empty_node_cached_mut_with_hasher::<H, MAX_SUPPORTED_DEPTH>(
+                            level as u32,
+                            &mut empty_node_cache,
+                        ),
+                    };
+                    if position & 1 == 0 {
+                        (node, sibling)
+                    } else {
+                        (sibling, node)
+                    }
+                };
+            i += 1;
+            let parent = position >> 1;
+            while leaf_idx < leaves.len() && leaves[leaf_idx].index >> (level + 1) == parent {
+                proofs[leaf_idx].as_mut()[level] = if leaves[leaf_idx].index >> level & 1 == 0 {
+                    right
+                } else {
+                    left
+                };
+                leaf_idx += 1;
+            }
+            parents.push((parent, H::hash_pair(&left, &right)));
+        }
+        nodes = parents;
+    }
+    if proof_nodes.next().is_some() {
+        solana_logging!("Too many proof nodes for the leaves");
+        return Err(ConcurrentMerkleTreeError::InvalidProof);
     }
+    Ok(())
 }
@@ -0,0 +1,74 @@
+use crate::{
+    error::ConcurrentMerkleTreeError,
+    hash::{Hasher, Keccak},
+    node::Node,
+};
+
+/// Leaf of an indexed merkle tree, whose leaves form a linked list sorted by
+/// value so that the absence of a value can be proven.
+///
+/// Each leaf points to the leaf holding the next larger value of the tree.
+/// The leaf holding the largest value has a `next_index` of 0, as no leaf
+/// points back to the first leaf.
+///
+/// An indexed tree starts with the default leaf, of value 0, appended at index
+/// 0, so 0 can't be inserted. Values are compared as big-endian integers.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct IndexedLeaf {
+    pub value: Node,
+    pub next_index: u32,
+    pub next_value: Node,
+}
+
+impl IndexedLeaf {
+    /// Returns the node stored in the tree for this leaf
+    pub fn hash(&self) -> Node {
+        self.hash_with_hasher::<Keccak>()
+    }
+
+    /// Returns the node stored in the tree for this leaf, using the given
+    /// hasher
+    pub fn hash_with_hasher<H: Hasher>(&self) -> Node {
+        let mut next_index = Node::default();
+        next_index[28..].copy_from_slice(&self.next_index.to_be_bytes());
+        H::hash_pair(&H::hash_pair(&self.value, &self.next_value), &next_index)
+    }
+
+    /// Returns true if the values of this leaf can be hashed by the given
+    /// hasher
+    pub fn is_valid_with_hasher<H: Hasher>(&self) -> bool {
+        H::is_valid_leaf(&self.value) && H::is_valid_leaf(&self.next_value)
+    }
+
+    /// Returns true if this leaf holds the largest value smaller than `value`,
+    /// proving that `value` isn't in the tree
+    pub fn is_low_leaf_of(&self, value: &Node) -> bool {
+        self.value < *value && (self.next_index == 0 || *value < self.next_value)
+    }
+
+    /// Returns this low leaf updated to point to `value`, and the new leaf of
+    /// `value` stored at `index`, which points to the former next leaf.
+    pub fn insert_with_hasher<H: Hasher>(
+        &self,
+        value: Node,
+        index: u32,
+    ) -> Result<(IndexedLeaf, IndexedLeaf), ConcurrentMerkleTreeError> {
+        if !H::is_valid_leaf(&value) || !self.is_valid_with_hasher::<H>() {
+            return Err(ConcurrentMerkleTreeError::InvalidLeaf);
+        }
+        if !self.is_low_leaf_of(&value) {
+            return Err(ConcurrentMerkleTreeError::InvalidLowLeaf);
+        }
+        let low_leaf = IndexedLeaf {
+            value: self.value,
+            next_index: index,
+            next_value: value,
+        };
+        let leaf = IndexedLeaf {
+            value,
+            next_index: self.next_index,
+            next_value: self.next_value,
+        };
+        Ok((low_leaf, leaf))
+    }
+}
@@ -15,10 +15,14 @@ mod log;
 pub mod changelog;
 /// Core implementation of the concurrent merkle tree structure
 pub mod concurrent_merkle_tree;
+/// Zero-copy view of a concurrent merkle tree sized at runtime
+pub mod concurrent_merkle_tree_view;
 /// Descriptive errors
 pub mod error;
 /// Hashing utils to support merkle tree operations
 pub mod hash;
+/// Leaves of indexed merkle trees, supporting non-membership proofs
+pub mod indexed_leaf;
 /// Node implementation and utils
 pub mod node;
 /// Path implementation
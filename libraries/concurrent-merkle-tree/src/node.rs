@@ -1,4 +1,4 @@
-use solana_program::keccak::hashv;
+use crate::hash::{Hasher, Keccak};
 
 /// Abstract type for 32 byte leaf data
 pub type Node = [u8; 32];
@@ -8,38 +8,50 @@ pub const EMPTY: Node = [0_u8; 32];
 
 /// Calculates the hash of empty nodes up to level i
 pub fn empty_node(level: u32) -> Node {
-    empty_node_cached::<0>(level, &[])
+    empty_node_with_hasher::<Keccak>(level)
+}
+
+/// Calculates the hash of empty nodes up to level i using the given hasher
+pub fn empty_node_with_hasher<H: Hasher>(level: u32) -> Node {
+    empty_node_cached_with_hasher::<H, 0>(level, &[])
 }
 
 /// Calculates the hash of empty nodes up to level i using an existing cache
 pub fn empty_node_cached<const N: usize>(level: u32, cache: &[Node; N]) -> Node {
+    empty_node_cached_with_hasher::<Keccak, N>(level, cache)
+}
+
+/// Calculates the hash of empty nodes up to level i using the given hasher and
+/// an existing cache of empty nodes computed with the same hasher
+pub fn empty_node_cached_with_hasher<H: Hasher, const N: usize>(
+    level: u32,
+    cache: &[Node; N],
+) -> Node {
     let mut data = EMPTY;
     if level != 0 {
         let target = (level - 1) as usize;
         let lower_empty = if target < cache.len() && cache[target] != EMPTY {
             cache[target]
         } else {
-            empty_node(target as u32)
+            empty_node_with_hasher::<H>(target as u32)
         };
-        let hash = hashv(&[lower_empty.as_ref(), lower_empty.as_ref()]);
-        data.copy_from_slice(hash.as_ref());
+        data = H::hash_pair(&lower_empty, &lower_empty);
     }
     data
 }
 
 /// Calculates and caches the hash of empty nodes up to level i
 pub fn empty_node_cached_mut<const N: usize>(level: u32, cache: &mut [Node; N]) -> Node {
-    let mut data = EMPTY;
-    if level != 0 {
-        let target = (level - 1) as usize;
-        let lower_empty = if target < cache.len() && cache[target] != EMPTY {
-            cache[target]
-        } else {
-            empty_node(target as u32)
-        };
-        let hash = hashv(&[lower_empty.as_ref(), lower_empty.as_ref()]);
-        data.copy_from_slice(hash.as_ref());
-    }
+    empty_node_cached_mut_with_hasher::<Keccak, N>(level, cache)
+}
+
+/// Calculates and caches the hash of empty nodes up to level i using the given
+/// hasher
+pub fn empty_node_cached_mut_with_hasher<H: Hasher, const N: usize>(
+    level: u32,
+    cache: &mut [Node; N],
+) -> Node {
+    let data = empty_node_cached_with_hasher::<H, N>(level, cache);
     cache[level as usize] = data;
     data
 }
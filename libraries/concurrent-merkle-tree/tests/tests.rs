@@ -1,15 +1,25 @@
 #![allow(clippy::arithmetic_side_effects)]
+#[cfg(feature = "poseidon")]
+use spl_concurrent_merkle_tree::hash::Poseidon;
 use {
     rand::{self, thread_rng, Rng},
     spl_concurrent_merkle_tree::{
         concurrent_merkle_tree::{
-            ConcurrentMerkleTree, FillEmptyOrAppendArgs, InitializeWithRootArgs, ProveLeafArgs,
-            SetLeafArgs,
+            AppendBatchArgs, AppendSubtreeArgs, ConcurrentMerkleTree, FillEmptyOrAppendArgs,
+            InitializeWithRootArgs, InsertIndexedLeafArgs, LeafUpdate, MigrateArgs, ProveLeafArgs,
+            ProveNonMembershipArgs, SetLeafArgs, SetLeavesArgs,
         },
+        concurrent_merkle_tree_view::{ConcurrentMerkleTreeView, DynamicChangeLog},
         error::ConcurrentMerkleTreeError,
-        node::{Node, EMPTY},
+        hash::{extend_proof, hash_subtree, multi_proof_positions, Hasher, Keccak, Sha256},
+        indexed_leaf::IndexedLeaf,
+        node::{empty_node_with_hasher, Node, EMPTY},
     },
     spl_merkle_tree_reference::MerkleTree,
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        mem::size_of,
+    },
 };
 
 const DEPTH: usize = 10;
@@ -574,3 +584,1379 @@ async fn test_prove_tree_empty_batched() {
     // Check that the last leaf was successfully removed
     cmt.prove_tree_is_empty().unwrap();
 }
+
+/// Computes the root and the proofs of all leaves of a full tree using the
+/// given hasher
+fn compute_root_and_proofs<H: Hasher>(leaves: &[Node]) -> (Node, Vec<Vec<Node>>) {
+    let mut proofs = vec![vec![]; leaves.len()];
+    let mut level = leaves.to_vec();
+    let mut level_size = 1;
+    while level.len() > 1 {
+        for (i, proof) in proofs.iter_mut().enumerate() {
+            proof.push(level[(i / level_size) ^ 1]);
+        }
+        level = level
+            .chunks(2)
+            .map(|pair| H::hash_pair(&pair[0], &pair[1]))
+            .collect();
+        level_size *= 2;
+    }
+    (level[0], proofs)
+}
+
+fn random_leaf_with_hasher<H: Hasher>(rng: &mut impl Rng) -> Node {
+    loop {
+        let leaf = rng.gen::<Node>();
+        if H::is_valid_leaf(&leaf) {
+            return leaf;
+        }
+    }
+}
+
+fn test_append_and_replace_with_hasher<H: Hasher>() {
+    const HASHER_DEPTH: usize = 4;
+    let mut cmt = ConcurrentMerkleTree::<HASHER_DEPTH, 32, H>::new();
+    let mut rng = thread_rng();
+
+    let root = cmt.initialize().unwrap();
+    assert_eq!(root, empty_node_with_hasher::<H>(HASHER_DEPTH as u32));
+    cmt.prove_tree_is_empty().unwrap();
+
+    let mut leaves = vec![EMPTY; 1 << HASHER_DEPTH];
+    for leaf in leaves.iter_mut() {
+        *leaf = random_leaf_with_hasher::<H>(&mut rng);
+        cmt.append(*leaf).unwrap();
+    }
+
+    let (root, proofs) = compute_root_and_proofs::<H>(&leaves);
+    assert_eq!(cmt.get_root(), root);
+
+    for (i, leaf) in leaves.iter().enumerate() {
+        cmt.prove_leaf(&ProveLeafArgs {
+            current_root: root,
+            leaf: *leaf,
+            proof_vec: proofs[i].clone(),
+            index: i as u32,
+        })
+        .unwrap();
+    }
+
+    // Replace leaves with proofs for a stale root
+    for (i, leaf) in leaves.iter_mut().enumerate() {
+        let new_leaf = random_leaf_with_hasher::<H>(&mut rng);
+        cmt.set_leaf(&SetLeafArgs {
+            current_root: root,
+            previous_leaf: *leaf,
+            new_leaf,
+            proof_vec: proofs[i].clone(),
+            index: i as u32,
+        })
+        .unwrap();
+        *leaf = new_leaf;
+    }
+
+    let (root, _) = compute_root_and_proofs::<H>(&leaves);
+    assert_eq!(cmt.get_root(), root);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_append_and_replace_with_keccak() {
+    test_append_and_replace_with_hasher::<Keccak>();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_append_and_replace_with_sha256() {
+    test_append_and_replace_with_hasher::<Sha256>();
+}
+
+#[cfg(feature = "poseidon")]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_append_and_replace_with_poseidon() {
+    test_append_and_replace_with_hasher::<Poseidon>();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_empty_root_depends_on_hasher() {
+    let mut keccak_cmt = ConcurrentMerkleTree::<DEPTH, BUFFER_SIZE, Keccak>::new();
+    let mut sha256_cmt = ConcurrentMerkleTree::<DEPTH, BUFFER_SIZE, Sha256>::new();
+
+    let keccak_root = keccak_cmt.initialize().unwrap();
+    let sha256_root = sha256_cmt.initialize().unwrap();
+
+    assert_eq!(
+        keccak_root,
+        MerkleTree::new(&vec![EMPTY; 1 << DEPTH]).get_root()
+    );
+    assert_ne!(keccak_root, sha256_root);
+
+    #[cfg(feature = "poseidon")]
+    {
+        let mut poseidon_cmt = ConcurrentMerkleTree::<DEPTH, BUFFER_SIZE, Poseidon>::new();
+        let poseidon_root = poseidon_cmt.initialize().unwrap();
+        assert_ne!(keccak_root, poseidon_root);
+        assert_ne!(sha256_root, poseidon_root);
+    }
+}
+
+#[cfg(feature = "poseidon")]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_append_invalid_poseidon_leaf() {
+    let mut cmt = ConcurrentMerkleTree::<DEPTH, BUFFER_SIZE, Poseidon>::new();
+    cmt.initialize().unwrap();
+
+    // Larger than the BN254 field modulus
+    let leaf = [0xff; 32];
+
+    assert_eq!(
+        ConcurrentMerkleTreeError::InvalidLeaf,
+        cmt.append(leaf).unwrap_err(),
+    );
+}
+
+#[cfg(feature = "poseidon")]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_invalid_poseidon_proof_nodes() {
+    let mut cmt = ConcurrentMerkleTree::<DEPTH, BUFFER_SIZE, Poseidon>::new();
+    cmt.initialize().unwrap();
+    let mut rng = thread_rng();
+    let leaf = random_leaf_with_hasher::<Poseidon>(&mut rng);
+    cmt.append(leaf).unwrap();
+    let root = cmt.get_root();
+
+    // Larger than the BN254 field modulus
+    let invalid_node = [0xff; 32];
+    let mut proof_vec = vec![EMPTY; DEPTH];
+    proof_vec[0] = invalid_node;
+
+    // Malformed proofs fail to verify instead of failing to hash
+    assert_eq!(
+        cmt.prove_leaf(&ProveLeafArgs {
+            current_root: root,
+            leaf,
+            proof_vec: proof_vec.clone(),
+            index: 0,
+        }),
+        Err(ConcurrentMerkleTreeError::InvalidProof)
+    );
+    assert_eq!(
+        cmt.set_leaf(&SetLeafArgs {
+            current_root: root,
+            previous_leaf: invalid_node,
+            new_leaf: leaf,
+            proof_vec: vec![],
+            index: 0,
+        }),
+        Err(ConcurrentMerkleTreeError::InvalidProof)
+    );
+    assert_eq!(
+        cmt.set_leaves(&SetLeavesArgs {
+            current_root: root,
+            leaves: vec![LeafUpdate {
+                index: 0,
+                previous_leaf: leaf,
+                new_leaf: leaf,
+            }],
+            proof_vec: vec![invalid_node],
+        }),
+        Err(ConcurrentMerkleTreeError::InvalidProof)
+    );
+    assert_eq!(
+        cmt.append_subtree(&AppendSubtreeArgs {
+            subtree_root: root,
+            rightmost_leaf: leaf,
+            proof_vec: vec![invalid_node],
+        }),
+        Err(ConcurrentMerkleTreeError::InvalidProof)
+    );
+    let mut proof = [EMPTY; DEPTH];
+    proof[0] = invalid_node;
+    assert!(!cmt.check_valid_proof(leaf, &proof, 0));
+    assert_eq!(
+        cmt.prove_non_membership(&ProveNonMembershipArgs {
+            current_root: root,
+            value: [0x01; 32],
+            low_leaf: IndexedLeaf {
+                value: EMPTY,
+                next_index: 1,
+                next_value: invalid_node,
+            },
+            low_leaf_index: 0,
+            proof_vec: vec![],
+        }),
+        Err(ConcurrentMerkleTreeError::InvalidLeaf)
+    );
+    assert_eq!(cmt.get_root(), root);
+
+    let view = ConcurrentMerkleTreeView::<_, Poseidon>::new(
+        bytemuck::bytes_of(&cmt).to_vec(),
+        DEPTH as u32,
+        BUFFER_SIZE as u32,
+    )
+    .unwrap();
+    assert_eq!(
+        view.prove_leaf(&ProveLeafArgs {
+            current_root: root,
+            leaf,
+            proof_vec,
+            index: 0,
+        }),
+        Err(ConcurrentMerkleTreeError::InvalidProof)
+    );
+    assert!(!view.check_valid_proof(leaf, &proof, 0));
+}
+
+/// Builds the proof shared by the leaves at the sorted `indices`
+fn get_multi_proof(tree: &MerkleTree, indices: &[u32]) -> Vec<Node> {
+    let mut proof = vec![];
+    for level in 0..DEPTH as u32 {
+        for position in multi_proof_positions(indices, level) {
+            // The sibling of `position` is an ancestor of one of the leaves
+            let leaf_index = ((position ^ 1) << level) as usize;
+            proof.push(tree.get_proof_of_leaf(leaf_index)[level as usize]);
+        }
+    }
+    proof
+}
+
+fn random_leaf_updates(tree: &MerkleTree, indices: &[u32]) -> Vec<LeafUpdate> {
+    let mut rng = thread_rng();
+    indices
+        .iter()
+        .map(|&index| LeafUpdate {
+            index,
+            previous_leaf: tree.get_leaf(index as usize),
+            new_leaf: rng.gen::<Node>(),
+        })
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_set_leaves() {
+    let (mut cmt, mut tree) = setup();
+    let mut rng = thread_rng();
+    cmt.initialize().unwrap();
+
+    let num_leaves = 1 << (DEPTH - 1);
+    for i in 0..num_leaves {
+        let leaf = rng.gen::<Node>();
+        tree.add_leaf(leaf, i);
+        cmt.append(leaf).unwrap();
+    }
+
+    // Siblings, cousins, distant leaves and the next leaf to append
+    let indices = [0, 1, 2, 7, 100, 101, 300, num_leaves as u32];
+    let leaves = random_leaf_updates(&tree, &indices);
+    let seq = cmt.get_seq();
+    let root = cmt
+        .set_leaves(&SetLeavesArgs {
+            current_root: tree.get_root(),
+            leaves: leaves.clone(),
+            proof_vec: get_multi_proof(&tree, &indices),
+        })
+        .unwrap();
+
+    for leaf in leaves.iter() {
+        tree.add_leaf(leaf.new_leaf, leaf.index as usize);
+    }
+    assert_eq!(root, tree.get_root());
+    assert_eq!(cmt.get_root(), tree.get_root());
+    assert_eq!(cmt.get_seq(), seq + indices.len() as u64);
+    assert_eq!(cmt.rightmost_proof.index, num_leaves as u32 + 1);
+
+    let change_logs = cmt.get_change_logs(indices.len());
+    assert_eq!(
+        change_logs.iter().map(|cl| cl.index).collect::<Vec<_>>(),
+        indices
+    );
+    assert_eq!(change_logs.last().unwrap().root, tree.get_root());
+
+    // The tree keeps accepting single leaf updates
+    let leaf = rng.gen::<Node>();
+    cmt.set_leaf(&SetLeafArgs {
+        current_root: tree.get_root(),
+        previous_leaf: tree.get_leaf(3),
+        new_leaf: leaf,
+        proof_vec: tree.get_proof_of_leaf(3),
+        index: 3,
+    })
+    .unwrap();
+    tree.add_leaf(leaf, 3);
+    assert_eq!(cmt.get_root(), tree.get_root());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_set_leaves_with_stale_root() {
+    let (mut cmt, mut tree) = setup();
+    let mut rng = thread_rng();
+    cmt.initialize().unwrap();
+
+    for i in 0..(1 << DEPTH) {
+        let leaf = rng.gen::<Node>();
+        tree.add_leaf(leaf, i);
+        cmt.append(leaf).unwrap();
+    }
+
+    let stale_root = tree.get_root();
+    let indices = [4, 6, 512, 1000];
+    let proof_vec = get_multi_proof(&tree, &indices);
+    let leaves = random_leaf_updates(&tree, &indices);
+
+    // Concurrently modify a leaf sharing most of the proof with the batch
+    let leaf = rng.gen::<Node>();
+    cmt.set_leaf(&SetLeafArgs {
+        current_root: stale_root,
+        previous_leaf: tree.get_leaf(5),
+        new_leaf: leaf,
+        proof_vec: tree.get_proof_of_leaf(5),
+        index: 5,
+    })
+    .unwrap();
+    tree.add_leaf(leaf, 5);
+
+    cmt.set_leaves(&SetLeavesArgs {
+        current_root: stale_root,
+        leaves: leaves.clone(),
+        proof_vec,
+    })
+    .unwrap();
+
+    for leaf in leaves.iter() {
+        tree.add_leaf(leaf.new_leaf, leaf.index as usize);
+    }
+    assert_eq!(cmt.get_root(), tree.get_root());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_set_leaves_errors() {
+    let (mut cmt, mut tree) = setup();
+    let mut rng = thread_rng();
+    cmt.initialize().unwrap();
+
+    for i in 0..(1 << DEPTH) {
+        let leaf = rng.gen::<Node>();
+        tree.add_leaf(leaf, i);
+        cmt.append(leaf).unwrap();
+    }
+
+    for indices in [vec![], vec![8, 3], vec![3, 3]] {
+        assert_eq!(
+            cmt.set_leaves(&SetLeavesArgs {
+                current_root: tree.get_root(),
+                leaves: random_leaf_updates(&tree, &indices),
+                proof_vec: vec![],
+            }),
+            Err(ConcurrentMerkleTreeError::InvalidLeafUpdates)
+        );
+    }
+
+    let indices = [3, 8];
+    let mut proof_vec = get_multi_proof(&tree, &indices);
+    proof_vec.push(EMPTY);
+    assert_eq!(
+        cmt.set_leaves(&SetLeavesArgs {
+            current_root: tree.get_root(),
+            leaves: random_leaf_updates(&tree, &indices),
+            proof_vec,
+        }),
+        Err(ConcurrentMerkleTreeError::InvalidProof)
+    );
+
+    let mut leaves = random_leaf_updates(&tree, &indices);
+    leaves[1].previous_leaf = rng.gen::<Node>();
+    assert_eq!(
+        cmt.set_leaves(&SetLeavesArgs {
+            current_root: tree.get_root(),
+            leaves,
+            proof_vec: get_multi_proof(&tree, &indices),
+        }),
+        Err(ConcurrentMerkleTreeError::InvalidProof)
+    );
+
+    // A batch can't evict its own root from the changelog buffer
+    let indices: Vec<u32> = (0..=BUFFER_SIZE as u32).collect();
+    assert_eq!(
+        cmt.set_leaves(&SetLeavesArgs {
+            current_root: tree.get_root(),
+            leaves: random_leaf_updates(&tree, &indices),
+            proof_vec: get_multi_proof(&tree, &indices),
+        }),
+        Err(ConcurrentMerkleTreeError::TooManyLeafUpdates)
+    );
+
+    let leaves = random_leaf_updates(&tree, &indices[..BUFFER_SIZE]);
+    cmt.set_leaves(&SetLeavesArgs {
+        current_root: tree.get_root(),
+        leaves: leaves.clone(),
+        proof_vec: get_multi_proof(&tree, &indices[..BUFFER_SIZE]),
+    })
+    .unwrap();
+    for leaf in leaves {
+        tree.add_leaf(leaf.new_leaf, leaf.index as usize);
+    }
+    assert_eq!(cmt.get_root(), tree.get_root());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_set_leaves_is_atomic() {
+    let (mut cmt, mut tree) = setup();
+    let mut rng = thread_rng();
+    cmt.initialize().unwrap();
+
+    for i in 0..20 {
+        let leaf = rng.gen::<Node>();
+        tree.add_leaf(leaf, i);
+        cmt.append(leaf).unwrap();
+    }
+
+    // The updates are made against a root from before the second leaf was
+    // modified, so the first update is fast-forwarded while the second one
+    // fails, and the tree must be left as it was, including its changelogs
+    // and rightmost proof
+    let indices = [3, 8];
+    let args = SetLeavesArgs {
+        current_root: tree.get_root(),
+        leaves: random_leaf_updates(&tree, &indices),
+        proof_vec: get_multi_proof(&tree, &indices),
+    };
+    let new_leaf = rng.gen::<Node>();
+    cmt.set_leaf(&SetLeafArgs {
+        current_root: tree.get_root(),
+        previous_leaf: tree.get_leaf(8),
+        new_leaf,
+        proof_vec: tree.get_proof_of_leaf(8),
+        index: 8,
+    })
+    .unwrap();
+    tree.add_leaf(new_leaf, 8);
+    let data = bytemuck::bytes_of(&cmt).to_vec();
+    assert_eq!(
+        cmt.set_leaves(&args),
+        Err(ConcurrentMerkleTreeError::LeafContentsModified)
+    );
+    assert_eq!(cmt.get_root(), tree.get_root());
+    assert_eq!(bytemuck::bytes_of(&cmt), data.as_slice());
+
+    let mut view =
+        ConcurrentMerkleTreeView::<_>::new(data.clone(), DEPTH as u32, BUFFER_SIZE as u32).unwrap();
+    assert_eq!(
+        view.set_leaves(&args),
+        Err(ConcurrentMerkleTreeError::LeafContentsModified)
+    );
+    assert_eq!(view.get_root(), tree.get_root());
+    assert_eq!(view.data(), data.as_slice());
+
+    // The tree can still be updated afterwards
+    let leaves = random_leaf_updates(&tree, &indices);
+    let args = SetLeavesArgs {
+        current_root: tree.get_root(),
+        leaves: leaves.clone(),
+        proof_vec: get_multi_proof(&tree, &indices),
+    };
+    cmt.set_leaves(&args).unwrap();
+    view.set_leaves(&args).unwrap();
+    for leaf in leaves {
+        tree.add_leaf(leaf.new_leaf, leaf.index as usize);
+    }
+    assert_eq!(cmt.get_root(), tree.get_root());
+    assert_eq!(view.data(), bytemuck::bytes_of(&cmt));
+}
+
+fn random_leaves(num_leaves: usize) -> Vec<Node> {
+    let mut rng = thread_rng();
+    (0..num_leaves).map(|_| rng.gen::<Node>()).collect()
+}
+
+fn subtree_args(leaves: &[Node]) -> AppendSubtreeArgs {
+    let (subtree_root, proof_vec) = hash_subtree(leaves);
+    AppendSubtreeArgs {
+        subtree_root,
+        rightmost_leaf: leaves[leaves.len() - 1],
+        proof_vec,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_append_subtree() {
+    let (mut cmt, mut tree) = setup();
+    let mut rng = thread_rng();
+    cmt.initialize().unwrap();
+
+    let leaves = random_leaves(8);
+    let root = cmt.append_subtree(&subtree_args(&leaves)).unwrap();
+    for (i, leaf) in leaves.iter().enumerate() {
+        tree.add_leaf(*leaf, i);
+    }
+    assert_eq!(root, tree.get_root());
+    assert_eq!(cmt.get_seq(), 1);
+    assert_eq!(cmt.rightmost_proof.index, 8);
+    assert_eq!(cmt.rightmost_proof.leaf, leaves[7]);
+    assert_eq!(
+        cmt.rightmost_proof.proof.to_vec(),
+        tree.get_proof_of_leaf(7)
+    );
+    let change_log = cmt.get_change_log();
+    assert_eq!(change_log.index, 7);
+    assert_eq!(change_log.path[0], leaves[7]);
+
+    // Subtrees must start at a multiple of their number of leaves
+    let stale_root = tree.get_root();
+    let stale_proof = tree.get_proof_of_leaf(1);
+    assert_eq!(
+        cmt.append_subtree(&subtree_args(&random_leaves(16))),
+        Err(ConcurrentMerkleTreeError::SubtreeNotAligned)
+    );
+    let mut args = subtree_args(&random_leaves(4));
+    args.subtree_root = rng.gen::<Node>();
+    assert_eq!(
+        cmt.append_subtree(&args),
+        Err(ConcurrentMerkleTreeError::InvalidProof)
+    );
+    let mut leaves = random_leaves(2);
+    leaves[1] = EMPTY;
+    assert_eq!(
+        cmt.append_subtree(&subtree_args(&leaves)),
+        Err(ConcurrentMerkleTreeError::CannotAppendEmptyNode)
+    );
+
+    let leaves = random_leaves(4);
+    cmt.append_subtree(&subtree_args(&leaves)).unwrap();
+    for (i, leaf) in leaves.iter().enumerate() {
+        tree.add_leaf(*leaf, 8 + i);
+    }
+    assert_eq!(cmt.get_root(), tree.get_root());
+
+    // Proofs against the root before the subtree are fast-forwarded
+    let leaf = rng.gen::<Node>();
+    cmt.set_leaf(&SetLeafArgs {
+        current_root: stale_root,
+        previous_leaf: tree.get_leaf(1),
+        new_leaf: leaf,
+        proof_vec: stale_proof,
+        index: 1,
+    })
+    .unwrap();
+    tree.add_leaf(leaf, 1);
+    assert_eq!(cmt.get_root(), tree.get_root());
+
+    // Appending a single leaf still works after a subtree
+    let leaf = rng.gen::<Node>();
+    cmt.append(leaf).unwrap();
+    tree.add_leaf(leaf, 12);
+    assert_eq!(cmt.get_root(), tree.get_root());
+
+    assert_eq!(
+        cmt.append_subtree(&subtree_args(&random_leaves(1 << DEPTH))),
+        Err(ConcurrentMerkleTreeError::TreeFull)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_append_batch() {
+    let (mut cmt, mut tree) = setup();
+    let mut rng = thread_rng();
+    cmt.initialize().unwrap();
+
+    for i in 0..3 {
+        let leaf = rng.gen::<Node>();
+        cmt.append(leaf).unwrap();
+        tree.add_leaf(leaf, i);
+    }
+    let stale_root = tree.get_root();
+    let stale_proof = tree.get_proof_of_leaf(2);
+
+    let leaves = random_leaves(13);
+    let seq = cmt.get_seq();
+    let root = cmt
+        .append_batch(&AppendBatchArgs {
+            leaves: leaves.clone(),
+            max_subtree_height: DEPTH as u32,
+        })
+        .unwrap();
+    for (i, leaf) in leaves.iter().enumerate() {
+        tree.add_leaf(*leaf, 3 + i);
+    }
+    assert_eq!(root, tree.get_root());
+    assert_eq!(cmt.rightmost_proof.index, 16);
+    assert_eq!(
+        cmt.rightmost_proof.proof.to_vec(),
+        tree.get_proof_of_leaf(15)
+    );
+
+    // The leaves are appended as subtrees of 1, 4 and 8 leaves
+    assert_eq!(cmt.get_seq(), seq + 3);
+    assert_eq!(
+        cmt.get_change_logs(3)
+            .iter()
+            .map(|change_log| change_log.index)
+            .collect::<Vec<_>>(),
+        vec![3, 7, 15]
+    );
+
+    // Proofs of leaves next to the batch are fast-forwarded
+    let leaf = rng.gen::<Node>();
+    cmt.set_leaf(&SetLeafArgs {
+        current_root: stale_root,
+        previous_leaf: tree.get_leaf(2),
+        new_leaf: leaf,
+        proof_vec: stale_proof,
+        index: 2,
+    })
+    .unwrap();
+    tree.add_leaf(leaf, 2);
+    assert_eq!(cmt.get_root(), tree.get_root());
+
+    // The height of the subtrees is limited by `max_subtree_height`
+    let leaves = random_leaves(8);
+    let seq = cmt.get_seq();
+    cmt.append_batch(&AppendBatchArgs {
+        leaves: leaves.clone(),
+        max_subtree_height: 1,
+    })
+    .unwrap();
+    for (i, leaf) in leaves.iter().enumerate() {
+        tree.add_leaf(*leaf, 16 + i);
+    }
+    assert_eq!(cmt.get_seq(), seq + 4);
+    assert_eq!(cmt.get_root(), tree.get_root());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_append_batch_errors() {
+    let (mut cmt, _) = setup();
+    let args = AppendBatchArgs {
+        leaves: random_leaves(2),
+        max_subtree_height: 1,
+    };
+    assert_eq!(
+        cmt.append_batch(&args),
+        Err(ConcurrentMerkleTreeError::TreeNotInitialized)
+    );
+    cmt.initialize().unwrap();
+
+    assert_eq!(
+        cmt.append_batch(&AppendBatchArgs {
+            leaves: vec![],
+            max_subtree_height: 1,
+        }),
+        Err(ConcurrentMerkleTreeError::EmptyBatch)
+    );
+    let mut leaves = random_leaves(4);
+    leaves[2] = EMPTY;
+    assert_eq!(
+        cmt.append_batch(&AppendBatchArgs {
+            leaves,
+            max_subtree_height: 1,
+        }),
+        Err(ConcurrentMerkleTreeError::CannotAppendEmptyNode)
+    );
+    assert_eq!(
+        cmt.append_batch(&AppendBatchArgs {
+            leaves: random_leaves((1 << DEPTH) + 1),
+            max_subtree_height: DEPTH as u32,
+        }),
+        Err(ConcurrentMerkleTreeError::TreeFull)
+    );
+    // Failed batches don't modify the tree
+    assert_eq!(cmt.get_seq(), 0);
+    assert_eq!(cmt.rightmost_proof.index, 0);
+
+    cmt.append_batch(&AppendBatchArgs {
+        leaves: random_leaves(1 << DEPTH),
+        max_subtree_height: DEPTH as u32,
+    })
+    .unwrap();
+    assert_eq!(cmt.get_seq(), 1);
+    assert_eq!(
+        cmt.append_batch(&args),
+        Err(ConcurrentMerkleTreeError::TreeFull)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_migrate_full_tree() {
+    let mut cmt = ConcurrentMerkleTree::<4, 8>::new();
+    let mut leaves = vec![EMPTY; 1 << 6];
+    let mut rng = thread_rng();
+    cmt.initialize().unwrap();
+    for leaf in leaves.iter_mut().take(1 << 4) {
+        *leaf = rng.gen::<Node>();
+        cmt.append(*leaf).unwrap();
+    }
+    let mut tree = MerkleTree::new(&leaves[..1 << 4]);
+    let stale_root = tree.get_root();
+    let stale_proof = tree.get_proof_of_leaf(15);
+
+    // The rightmost proof of a full tree isn't kept up to date
+    for index in [15, 3] {
+        let leaf = rng.gen::<Node>();
+        cmt.set_leaf(&SetLeafArgs {
+            current_root: tree.get_root(),
+            previous_leaf: leaves[index],
+            new_leaf: leaf,
+            proof_vec: tree.get_proof_of_leaf(index),
+            index: index as u32,
+        })
+        .unwrap();
+        tree.add_leaf(leaf, index);
+        leaves[index] = leaf;
+    }
+
+    let args = MigrateArgs {
+        current_root: tree.get_root(),
+        rightmost_leaf: leaves[15],
+        proof_vec: tree.get_proof_of_leaf(15),
+        max_depth: 6,
+    };
+    for max_depth in [4, 31] {
+        assert!(matches!(
+            cmt.get_migration_args(&MigrateArgs {
+                max_depth,
+                proof_vec: args.proof_vec.clone(),
+                ..args
+            }),
+            Err(ConcurrentMerkleTreeError::InvalidTreeSize)
+        ));
+    }
+    assert!(matches!(
+        cmt.get_migration_args(&MigrateArgs {
+            rightmost_leaf: rng.gen::<Node>(),
+            proof_vec: args.proof_vec.clone(),
+            ..args
+        }),
+        Err(ConcurrentMerkleTreeError::InvalidProof)
+    ));
+
+    // Proofs against older roots are fast-forwarded
+    let migration_args = cmt
+        .get_migration_args(&MigrateArgs {
+            current_root: stale_root,
+            proof_vec: stale_proof,
+            ..args
+        })
+        .unwrap();
+    let mut deeper_cmt = ConcurrentMerkleTree::<6, 8>::new();
+    deeper_cmt.initialize_with_root(&migration_args).unwrap();
+    let mut deeper_tree = MerkleTree::new(&leaves);
+    assert_eq!(deeper_cmt.get_root(), deeper_tree.get_root());
+
+    // Leaves keep their index, with their proof extended
+    let leaf = rng.gen::<Node>();
+    deeper_cmt
+        .set_leaf(&SetLeafArgs {
+            current_root: deeper_tree.get_root(),
+            previous_leaf: leaves[3],
+            new_leaf: leaf,
+            proof_vec: extend_proof(&tree.get_proof_of_leaf(3), 6),
+            index: 3,
+        })
+        .unwrap();
+    deeper_tree.add_leaf(leaf, 3);
+    assert_eq!(deeper_cmt.get_root(), deeper_tree.get_root());
+
+    // New leaves are appended after the leaves of the migrated tree
+    let leaf = rng.gen::<Node>();
+    deeper_cmt.append(leaf).unwrap();
+    deeper_tree.add_leaf(leaf, 16);
+    assert_eq!(deeper_cmt.get_root(), deeper_tree.get_root());
+    assert_eq!(deeper_cmt.rightmost_proof.index, 17);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_migrate_view() {
+    let mut cmt = ConcurrentMerkleTree::<DEPTH, BUFFER_SIZE>::new();
+    let mut data = vec![0; size_of::<ConcurrentMerkleTree<DEPTH, BUFFER_SIZE>>()];
+    let mut view = ConcurrentMerkleTreeView::<&mut [u8]>::new(
+        data.as_mut_slice(),
+        DEPTH as u32,
+        BUFFER_SIZE as u32,
+    )
+    .unwrap();
+    let (_, mut tree) = setup();
+    let mut rng = thread_rng();
+
+    cmt.initialize().unwrap();
+    view.initialize().unwrap();
+    let args = MigrateArgs {
+        current_root: tree.get_root(),
+        rightmost_leaf: EMPTY,
+        proof_vec: tree.get_proof_of_leaf(0),
+        max_depth: DEPTH as u32 + 2,
+    };
+    assert!(matches!(
+        view.get_migration_args(&args),
+        Err(ConcurrentMerkleTreeError::CannotMigrateEmptyTree)
+    ));
+
+    for i in 0..5 {
+        let leaf = rng.gen::<Node>();
+        cmt.append(leaf).unwrap();
+        view.append(leaf).unwrap();
+        tree.add_leaf(leaf, i);
+    }
+    let args = MigrateArgs {
+        current_root: tree.get_root(),
+        rightmost_leaf: tree.get_leaf(4),
+        proof_vec: tree.get_proof_of_leaf(4),
+        ..args
+    };
+    let cmt_args = cmt.get_migration_args(&args).unwrap();
+    let view_args = view.get_migration_args(&args).unwrap();
+    assert_eq!(cmt_args.root, view_args.root);
+    assert_eq!(cmt_args.proof_vec, view_args.proof_vec);
+    assert_eq!(cmt_args.index, 4);
+
+    let mut deeper_data = vec![0; size_of::<ConcurrentMerkleTree<{ DEPTH + 2 }, 8>>()];
+    let mut deeper_view =
+        ConcurrentMerkleTreeView::<&mut [u8]>::new(deeper_data.as_mut_slice(), DEPTH as u32 + 2, 8)
+            .unwrap();
+    deeper_view.initialize_with_root(&view_args).unwrap();
+    let mut leaves = vec![EMPTY; 1 << (DEPTH + 2)];
+    for (i, leaf) in leaves.iter_mut().take(5).enumerate() {
+        *leaf = tree.get_leaf(i);
+    }
+    assert_eq!(deeper_view.get_root(), MerkleTree::new(&leaves).get_root());
+}
+
+enum Operation {
+    Append(Node),
+    SetLeaf(SetLeafArgs),
+    FillEmptyOrAppend(FillEmptyOrAppendArgs),
+    SetLeaves(SetLeavesArgs),
+    ProveLeaf(ProveLeafArgs),
+    AppendSubtree(AppendSubtreeArgs, Vec<Node>),
+    AppendBatch(AppendBatchArgs),
+}
+
+fn apply_to_tree<H: Hasher>(
+    cmt: &mut ConcurrentMerkleTree<DEPTH, BUFFER_SIZE, H>,
+    operation: &Operation,
+) -> Result<Node, ConcurrentMerkleTreeError> {
+    match operation {
+        Operation::Append(leaf) => cmt.append(*leaf),
+        Operation::SetLeaf(args) => cmt.set_leaf(args),
+        Operation::FillEmptyOrAppend(args) => cmt.fill_empty_or_append(args),
+        Operation::SetLeaves(args) => cmt.set_leaves(args),
+        Operation::ProveLeaf(args) => cmt.prove_leaf(args).map(|_| EMPTY),
+        Operation::AppendSubtree(args, _) => cmt.append_subtree(args),
+        Operation::AppendBatch(args) => cmt.append_batch(args),
+    }
+}
+
+fn apply_to_view<H: Hasher>(
+    view: &mut ConcurrentMerkleTreeView<&mut [u8], H>,
+    operation: &Operation,
+) -> Result<Node, ConcurrentMerkleTreeError> {
+    match operation {
+        Operation::Append(leaf) => view.append(*leaf),
+        Operation::SetLeaf(args) => view.set_leaf(args),
+        Operation::FillEmptyOrAppend(args) => view.fill_empty_or_append(args),
+        Operation::SetLeaves(args) => view.set_leaves(args),
+        Operation::ProveLeaf(args) => view.prove_leaf(args).map(|_| EMPTY),
+        Operation::AppendSubtree(args, _) => view.append_subtree(args),
+        Operation::AppendBatch(args) => view.append_batch(args),
+    }
+}
+
+fn random_operation(
+    rng: &mut impl Rng,
+    tree: &MerkleTree,
+    stale_tree: &MerkleTree,
+    num_leaves: usize,
+) -> Operation {
+    // Proofs are sometimes built against an older root, to be fast-forwarded
+    let tree = if rng.gen_bool(0.3) { stale_tree } else { tree };
+    let index = rng.gen_range(0..=num_leaves.min((1 << DEPTH) - 1));
+    match rng.gen_range(0..7) {
+        0 => Operation::Append(rng.gen::<Node>()),
+        5 => {
+            let leaves: Vec<Node> = (0..1 << rng.gen_range(0..4))
+                .map(|_| rng.gen::<Node>())
+                .collect();
+            let (subtree_root, proof_vec) = hash_subtree(&leaves);
+            Operation::AppendSubtree(
+                AppendSubtreeArgs {
+                    subtree_root,
+                    rightmost_leaf: leaves[leaves.len() - 1],
+                    proof_vec,
+                },
+                leaves,
+            )
+        }
+        6 => Operation::AppendBatch(AppendBatchArgs {
+            leaves: (0..rng.gen_range(1..10))
+                .map(|_| rng.gen::<Node>())
+                .collect(),
+            max_subtree_height: rng.gen_range(0..4),
+        }),
+        1 => Operation::SetLeaf(SetLeafArgs {
+            current_root: tree.get_root(),
+            previous_leaf: tree.get_leaf(index),
+            new_leaf: rng.gen::<Node>(),
+            proof_vec: tree.get_proof_of_leaf(index),
+            index: index as u32,
+        }),
+        2 => Operation::FillEmptyOrAppend(FillEmptyOrAppendArgs {
+            current_root: tree.get_root(),
+            leaf: rng.gen::<Node>(),
+            proof_vec: tree.get_proof_of_leaf(index),
+            index: index as u32,
+        }),
+        3 => {
+            let mut indices: Vec<u32> = (0..rng.gen_range(1..5))
+                .map(|_| rng.gen_range(0..=index) as u32)
+                .collect();
+            indices.sort_unstable();
+            indices.dedup();
+            Operation::SetLeaves(SetLeavesArgs {
+                current_root: tree.get_root(),
+                leaves: random_leaf_updates(tree, &indices),
+                proof_vec: get_multi_proof(tree, &indices),
+            })
+        }
+        _ => Operation::ProveLeaf(ProveLeafArgs {
+            current_root: tree.get_root(),
+            leaf: tree.get_leaf(index),
+            proof_vec: tree.get_proof_of_leaf(index),
+            index: index as u32,
+        }),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_view_matches_tree() {
+    let (mut cmt, mut tree) = setup();
+    let mut rng = thread_rng();
+    let mut data = vec![0; size_of::<ConcurrentMerkleTree<DEPTH, BUFFER_SIZE>>() + 1];
+    assert_eq!(
+        ConcurrentMerkleTreeView::<&[u8]>::size(DEPTH as u32, BUFFER_SIZE as u32),
+        Ok(data.len() - 1)
+    );
+    // The view doesn't require the data to be aligned
+    let mut view = ConcurrentMerkleTreeView::<&mut [u8]>::new(
+        &mut data[1..],
+        DEPTH as u32,
+        BUFFER_SIZE as u32,
+    )
+    .unwrap();
+
+    assert_eq!(cmt.initialize(), view.initialize());
+    let mut stale_tree = MerkleTree::new(&[EMPTY; 1 << DEPTH]);
+    for i in 0..2000 {
+        if i % 10 == 0 {
+            let leaves: Vec<Node> = (0..1 << DEPTH).map(|i| tree.get_leaf(i)).collect();
+            stale_tree = MerkleTree::new(&leaves);
+        }
+        let num_leaves = cmt.rightmost_proof.index as usize;
+        let operation = random_operation(&mut rng, &tree, &stale_tree, num_leaves);
+        let seq = cmt.get_seq();
+        let result = apply_to_tree(&mut cmt, &operation);
+        assert_eq!(result, apply_to_view(&mut view, &operation));
+        assert_eq!(bytemuck::bytes_of(&cmt), view.data());
+
+        // Replay the new leaves on the reference tree, from the changelogs
+        // unless several leaves were appended at once
+        let appended_leaves = match &operation {
+            Operation::AppendSubtree(_, leaves) => Some(leaves),
+            Operation::AppendBatch(args) => Some(&args.leaves),
+            _ => None,
+        };
+        match appended_leaves {
+            Some(leaves) if result.is_ok() => {
+                for (i, leaf) in leaves.iter().enumerate() {
+                    tree.add_leaf(*leaf, num_leaves + i);
+                }
+            }
+            _ => {
+                for change_log in cmt.get_change_logs((cmt.get_seq() - seq) as usize) {
+                    tree.add_leaf(change_log.path[0], change_log.index as usize);
+                }
+            }
+        }
+        assert_eq!(view.get_root(), tree.get_root());
+        assert_eq!(view.get_change_log(), (*cmt.get_change_log()).into());
+        assert_eq!(view.get_rightmost_proof(), cmt.rightmost_proof.into());
+    }
+    assert_eq!(
+        view.get_change_logs(BUFFER_SIZE),
+        cmt.get_change_logs(BUFFER_SIZE)
+            .into_iter()
+            .map(DynamicChangeLog::from)
+            .collect::<Vec<_>>()
+    );
+    for (i, change_log) in cmt.change_logs.iter().enumerate() {
+        assert_eq!(view.get_change_log_at(i), Some((*change_log).into()));
+    }
+    assert_eq!(view.get_change_log_at(BUFFER_SIZE), None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_view_initialize_with_root() {
+    let (mut cmt, mut tree) = setup();
+    let mut rng = thread_rng();
+    for i in 0..100 {
+        tree.add_leaf(rng.gen::<Node>(), i);
+    }
+    let args = InitializeWithRootArgs {
+        root: tree.get_root(),
+        rightmost_leaf: tree.get_leaf(99),
+        proof_vec: tree.get_proof_of_leaf(99),
+        index: 99,
+    };
+
+    let mut data = vec![0; size_of::<ConcurrentMerkleTree<DEPTH, BUFFER_SIZE>>()];
+    let mut view =
+        ConcurrentMerkleTreeView::<_>::new(data.as_mut_slice(), DEPTH as u32, BUFFER_SIZE as u32)
+            .unwrap();
+    assert_eq!(
+        view.prove_tree_is_empty(),
+        Err(ConcurrentMerkleTreeError::TreeNotInitialized)
+    );
+    assert_eq!(
+        cmt.initialize_with_root(&args),
+        view.initialize_with_root(&args)
+    );
+    assert_eq!(
+        view.initialize(),
+        Err(ConcurrentMerkleTreeError::TreeAlreadyInitialized)
+    );
+    assert_eq!(
+        view.prove_tree_is_empty(),
+        Err(ConcurrentMerkleTreeError::TreeNonEmpty)
+    );
+    assert_eq!(bytemuck::bytes_of(&cmt), data.as_slice());
+}
+
+#[cfg(feature = "poseidon")]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_view_with_hasher() {
+    let mut cmt = ConcurrentMerkleTree::<DEPTH, BUFFER_SIZE, Poseidon>::new();
+    let mut data = vec![0; size_of::<ConcurrentMerkleTree<DEPTH, BUFFER_SIZE, Poseidon>>()];
+    let mut view = ConcurrentMerkleTreeView::<_, Poseidon>::new(
+        data.as_mut_slice(),
+        DEPTH as u32,
+        BUFFER_SIZE as u32,
+    )
+    .unwrap();
+    let mut rng = thread_rng();
+
+    assert_eq!(cmt.initialize(), view.initialize());
+    assert_eq!(view.prove_tree_is_empty(), Ok(()));
+    assert_eq!(
+        view.get_root(),
+        empty_node_with_hasher::<Poseidon>(DEPTH as u32)
+    );
+    for _ in 0..10 {
+        let leaf = random_leaf_with_hasher::<Poseidon>(&mut rng);
+        assert_eq!(cmt.append(leaf), view.append(leaf));
+    }
+    assert_eq!(
+        view.append([0xff; 32]),
+        Err(ConcurrentMerkleTreeError::InvalidLeaf)
+    );
+    assert_eq!(bytemuck::bytes_of(&cmt), data.as_slice());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_view_dimensions() {
+    assert_eq!(
+        ConcurrentMerkleTreeView::<&[u8]>::size(14, 64),
+        Ok(size_of::<ConcurrentMerkleTree<14, 64>>())
+    );
+    assert_eq!(
+        ConcurrentMerkleTreeView::<&[u8]>::size(4, 4),
+        Ok(size_of::<ConcurrentMerkleTree<4, 4>>())
+    );
+    for (max_depth, max_buffer_size) in [(0, 8), (31, 8), (14, 0), (14, 63)] {
+        assert_eq!(
+            ConcurrentMerkleTreeView::<&[u8]>::size(max_depth, max_buffer_size),
+            Err(ConcurrentMerkleTreeError::InvalidTreeSize)
+        );
+    }
+    let data = vec![0; size_of::<ConcurrentMerkleTree<14, 64>>() - 1];
+    assert!(matches!(
+        ConcurrentMerkleTreeView::<_>::new(data.as_slice(), 14, 64),
+        Err(ConcurrentMerkleTreeError::InvalidTreeSize)
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_view_with_unlisted_dimensions() {
+    // Neither the depth nor the buffer size have to be one of the sizes
+    // supported by the account compression program so far
+    const MAX_DEPTH: u32 = 21;
+    const MAX_BUFFER_SIZE: u32 = 128;
+    let size = ConcurrentMerkleTreeView::<&[u8]>::size(MAX_DEPTH, MAX_BUFFER_SIZE).unwrap();
+    let mut data = vec![0; size];
+    let mut view =
+        ConcurrentMerkleTreeView::<_>::new(data.as_mut_slice(), MAX_DEPTH, MAX_BUFFER_SIZE)
+            .unwrap();
+    let mut rng = thread_rng();
+    view.initialize().unwrap();
+    assert_eq!(view.get_root(), empty_node_with_hasher::<Keccak>(MAX_DEPTH));
+
+    let mut leaves = vec![];
+    for _ in 0..256 {
+        let leaf = rng.gen::<Node>();
+        view.append(leaf).unwrap();
+        leaves.push(leaf);
+    }
+    assert_eq!(view.get_buffer_size(), MAX_BUFFER_SIZE as u64);
+    let rightmost_proof = view.get_rightmost_proof();
+    assert_eq!(rightmost_proof.index, 256);
+    assert!(view.check_valid_proof(leaves[255], &rightmost_proof.proof, 255));
+
+    // Proofs truncated to the first levels are filled in with empty nodes
+    let (_, proofs) = compute_root_and_proofs::<Keccak>(&leaves);
+    let leaf = rng.gen::<Node>();
+    view.set_leaf(&SetLeafArgs {
+        current_root: view.get_root(),
+        previous_leaf: leaves[5],
+        new_leaf: leaf,
+        proof_vec: proofs[5].clone(),
+        index: 5,
+    })
+    .unwrap();
+    leaves[5] = leaf;
+    let (mut root, _) = compute_root_and_proofs::<Keccak>(&leaves);
+    for level in 8..MAX_DEPTH {
+        root = Keccak::hash_pair(&root, &empty_node_with_hasher::<Keccak>(level));
+    }
+    assert_eq!(view.get_root(), root);
+}
+
+/// Reference indexed tree, tracking the sorted linked list of its leaves
+struct IndexedReference {
+    tree: MerkleTree,
+    leaves: Vec<IndexedLeaf>,
+    indices: BTreeMap<Node, u32>,
+}
+
+impl IndexedReference {
+    fn new() -> Self {
+        let (_, mut tree) = setup();
+        tree.add_leaf(IndexedLeaf::default().hash(), 0);
+        Self {
+            tree,
+            leaves: vec![IndexedLeaf::default()],
+            indices: BTreeMap::from([(EMPTY, 0)]),
+        }
+    }
+
+    fn insert_args(&self, value: Node) -> InsertIndexedLeafArgs {
+        let low_leaf_index = *self.indices.range(..value).next_back().unwrap().1;
+        InsertIndexedLeafArgs {
+            current_root: self.tree.get_root(),
+            value,
+            low_leaf: self.leaves[low_leaf_index as usize],
+            low_leaf_index,
+            proof_vec: self.tree.get_proof_of_leaf(low_leaf_index as usize),
+        }
+    }
+
+    fn non_membership_args(&self, value: Node) -> ProveNonMembershipArgs {
+        let args = self.insert_args(value);
+        ProveNonMembershipArgs {
+            current_root: args.current_root,
+            value,
+            low_leaf: args.low_leaf,
+            low_leaf_index: args.low_leaf_index,
+            proof_vec: args.proof_vec,
+        }
+    }
+
+    fn insert(&mut self, value: Node) {
+        let low_leaf_index = *self.indices.range(..value).next_back().unwrap().1 as usize;
+        let index = self.leaves.len();
+        let low_leaf = &mut self.leaves[low_leaf_index];
+        let leaf = IndexedLeaf {
+            value,
+            next_index: low_leaf.next_index,
+            next_value: low_leaf.next_value,
+        };
+        low_leaf.next_index = index as u32;
+        low_leaf.next_value = value;
+        self.tree.add_leaf(low_leaf.hash(), low_leaf_index);
+        self.tree.add_leaf(leaf.hash(), index);
+        self.leaves.push(leaf);
+        self.indices.insert(value, index as u32);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_indexed_tree_fuzz() {
+    let (mut cmt, _) = setup();
+    let mut data = vec![0; size_of::<ConcurrentMerkleTree<DEPTH, BUFFER_SIZE>>()];
+    let mut view = ConcurrentMerkleTreeView::<&mut [u8]>::new(
+        data.as_mut_slice(),
+        DEPTH as u32,
+        BUFFER_SIZE as u32,
+    )
+    .unwrap();
+    let mut reference = IndexedReference::new();
+    let mut rng = thread_rng();
+
+    cmt.initialize().unwrap();
+    cmt.append(IndexedLeaf::default().hash()).unwrap();
+    view.initialize().unwrap();
+    view.append(IndexedLeaf::default().hash()).unwrap();
+    assert_eq!(cmt.get_root(), reference.tree.get_root());
+
+    for _ in 0..200 {
+        // Insert values against the same root, with different low leaves so
+        // that each proof is fast-forwarded through the previous insertions
+        let mut low_leaf_indices = BTreeSet::new();
+        let batch: Vec<InsertIndexedLeafArgs> = (0..rng.gen_range(1..5))
+            .map(|_| reference.insert_args(rng.gen::<Node>()))
+            .filter(|args| low_leaf_indices.insert(args.low_leaf_index))
+            .collect();
+        for args in batch.iter() {
+            let result = cmt.insert_indexed_leaf(args);
+            assert_eq!(result, view.insert_indexed_leaf(args));
+            assert!(result.is_ok());
+            reference.insert(args.value);
+            assert_eq!(cmt.get_root(), reference.tree.get_root());
+        }
+        assert_eq!(bytemuck::bytes_of(&cmt), view.data());
+
+        let args = reference.non_membership_args(rng.gen::<Node>());
+        assert_eq!(cmt.prove_non_membership(&args), Ok(()));
+        assert_eq!(view.prove_non_membership(&args), Ok(()));
+
+        // Values of the tree have no low leaf
+        let member = *reference
+            .indices
+            .keys()
+            .nth(rng.gen_range(1..reference.indices.len()))
+            .unwrap();
+        let args = reference.non_membership_args(member);
+        assert_eq!(
+            cmt.prove_non_membership(&args),
+            Err(ConcurrentMerkleTreeError::InvalidLowLeaf)
+        );
+        let args = reference.insert_args(member);
+        assert_eq!(
+            view.insert_indexed_leaf(&args),
+            Err(ConcurrentMerkleTreeError::InvalidLowLeaf)
+        );
+    }
+
+    // The leaves form a linked list of all the values in increasing order
+    let mut values = vec![];
+    let mut leaf = reference.leaves[0];
+    while leaf.next_index != 0 {
+        values.push(leaf.next_value);
+        leaf = reference.leaves[leaf.next_index as usize];
+    }
+    assert_eq!(
+        values,
+        reference
+            .indices
+            .keys()
+            .skip(1)
+            .copied()
+            .collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_indexed_tree_errors() {
+    let (mut cmt, _) = setup();
+    let mut reference = IndexedReference::new();
+    let value = [1; 32];
+
+    cmt.initialize().unwrap();
+    // The default leaf must be appended first
+    assert_eq!(
+        cmt.insert_indexed_leaf(&reference.insert_args(value)),
+        Err(ConcurrentMerkleTreeError::LeafIndexOutOfBounds)
+    );
+    cmt.append(IndexedLeaf::default().hash()).unwrap();
+    // The value of the default leaf is already in the tree
+    let args = InsertIndexedLeafArgs {
+        value: EMPTY,
+        ..reference.insert_args(value)
+    };
+    assert_eq!(
+        cmt.insert_indexed_leaf(&args),
+        Err(ConcurrentMerkleTreeError::InvalidLowLeaf)
+    );
+
+    let mut args = reference.non_membership_args(value);
+    args.low_leaf.next_index = 1;
+    args.low_leaf.next_value = [2; 32];
+    assert_eq!(
+        cmt.prove_non_membership(&args),
+        Err(ConcurrentMerkleTreeError::InvalidProof)
+    );
+
+    let mut args = reference.insert_args(value);
+    args.value = [2; 32];
+    cmt.insert_indexed_leaf(&args).unwrap();
+    reference.insert([2; 32]);
+    assert_eq!(cmt.get_root(), reference.tree.get_root());
+
+    // The stale proof of the low leaf can't be used once it was modified
+    let args = InsertIndexedLeafArgs { value, ..args };
+    assert_eq!(
+        cmt.insert_indexed_leaf(&args),
+        Err(ConcurrentMerkleTreeError::LeafContentsModified)
+    );
+    cmt.insert_indexed_leaf(&reference.insert_args(value))
+        .unwrap();
+
+    // Values must be valid leaves for the hasher
+    #[cfg(feature = "poseidon")]
+    {
+        let mut poseidon_cmt = ConcurrentMerkleTree::<DEPTH, BUFFER_SIZE, Poseidon>::new();
+        poseidon_cmt.initialize().unwrap();
+        poseidon_cmt
+            .append(IndexedLeaf::default().hash_with_hasher::<Poseidon>())
+            .unwrap();
+        let args = InsertIndexedLeafArgs {
+            current_root: poseidon_cmt.get_root(),
+            value: [0xff; 32],
+            low_leaf: IndexedLeaf::default(),
+            low_leaf_index: 0,
+            proof_vec: vec![],
+        };
+        assert_eq!(
+            poseidon_cmt.insert_indexed_leaf(&args),
+            Err(ConcurrentMerkleTreeError::InvalidLeaf)
+        );
+    }
+
+    // The tree is left unchanged when it is full
+    let mut small_cmt = ConcurrentMerkleTree::<2, 8>::new();
+    small_cmt.initialize().unwrap();
+    small_cmt.append(IndexedLeaf::default().hash()).unwrap();
+    let mut leaves = vec![IndexedLeaf::default()];
+    let mut small_tree = MerkleTree::new(&[IndexedLeaf::default().hash(), EMPTY, EMPTY, EMPTY]);
+    for i in 1..=4u8 {
+        let low_leaf_index = leaves.len() - 1;
+        let args = InsertIndexedLeafArgs {
+            current_root: small_tree.get_root(),
+            value: [i; 32],
+            low_leaf: leaves[low_leaf_index],
+            low_leaf_index: low_leaf_index as u32,
+            proof_vec: small_tree.get_proof_of_leaf(low_leaf_index),
+        };
+        if i == 4 {
+            let root = small_cmt.get_root();
+            assert_eq!(
+                small_cmt.insert_indexed_leaf(&args),
+                Err(ConcurrentMerkleTreeError::TreeFull)
+            );
+            assert_eq!(small_cmt.get_root(), root);
+            break;
+        }
+        small_cmt.insert_indexed_leaf(&args).unwrap();
+        let (low_leaf, leaf) = leaves[low_leaf_index]
+            .insert_with_hasher::<Keccak>([i; 32], leaves.len() as u32)
+            .unwrap();
+        leaves[low_leaf_index] = low_leaf;
+        leaves.push(leaf);
+        small_tree.add_leaf(low_leaf.hash(), low_leaf_index);
+        small_tree.add_leaf(leaf.hash(), low_leaf_index + 1);
+        assert_eq!(small_cmt.get_root(), small_tree.get_root());
+    }
+}
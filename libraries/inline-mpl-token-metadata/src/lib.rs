@@ -0,0 +1,107 @@
+//! Inlined subset of the Metaplex Token Metadata program account layout, for
+//! programs that read metadata accounts without depending on the Metaplex
+//! crates. Only the fields up to the collection are deserialized.
+
+#![deny(missing_docs)]
+
+use {
+    borsh::BorshDeserialize,
+    solana_program::{
+        account_info::AccountInfo, borsh1::try_from_slice_unchecked, program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+solana_program::declare_id!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// Account discriminator of a `MetadataV1` account
+pub const METADATA_V1_KEY: u8 = 4;
+
+/// Metadata account
+#[derive(Clone, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct Metadata {
+    /// Account discriminator
+    pub key: u8,
+    /// Address of the update authority
+    pub update_authority: Pubkey,
+    /// Address of the mint
+    pub mint: Pubkey,
+    /// Asset data
+    pub data: Data,
+    /// Immutable, once flipped, all sales of this metadata are considered
+    /// secondary
+    pub primary_sale_happened: bool,
+    /// Whether or not the data struct is mutable, default is not
+    pub is_mutable: bool,
+    /// Nonce for easy calculation of editions, if present
+    pub edition_nonce: Option<u8>,
+    /// Token standard, stored as the raw enum discriminator
+    pub token_standard: Option<u8>,
+    /// Collection the asset belongs to
+    pub collection: Option<Collection>,
+}
+
+/// Metadata asset data
+#[derive(BorshDeserialize, Default, PartialEq, Eq, Debug, Clone)]
+pub struct Data {
+    /// The name of the asset
+    pub name: String,
+    /// The symbol for the asset
+    pub symbol: String,
+    /// URI pointing to JSON representing the asset
+    pub uri: String,
+    /// Royalty basis points that goes to creators in secondary sales
+    /// (0-10000)
+    pub seller_fee_basis_points: u16,
+    /// Array of creators, optional
+    pub creators: Option<Vec<Creator>>,
+}
+
+/// Metadata creator
+#[derive(BorshDeserialize, PartialEq, Debug, Clone, Eq, Hash)]
+pub struct Creator {
+    /// Creator address
+    pub address: Pubkey,
+    /// Whether the creator signed the metadata
+    pub verified: bool,
+    /// Share of the royalties in percents
+    pub share: u8,
+}
+
+/// Metadata collection
+#[derive(BorshDeserialize, PartialEq, Debug, Clone, Eq)]
+pub struct Collection {
+    /// Whether the collection authority verified the asset belongs to the
+    /// collection
+    pub verified: bool,
+    /// Collection NFT mint
+    pub key: Pubkey,
+}
+
+/// Returns Metadata PDA address
+pub fn get_metadata_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"metadata", id().as_ref(), mint.as_ref()], &id()).0
+}
+
+/// Deserializes Metadata account and checks it's the Metadata PDA for the given
+/// mint
+pub fn get_metadata_data_for_mint(
+    metadata_info: &AccountInfo,
+    mint: &Pubkey,
+) -> Result<Metadata, ProgramError> {
+    if *metadata_info.owner != id() {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    if *metadata_info.key != get_metadata_address(mint) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let metadata = try_from_slice_unchecked::<Metadata>(&metadata_info.try_borrow_data()?)?;
+
+    if metadata.mint != *mint {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(metadata)
+}
@@ -2,11 +2,27 @@ use {
     crate::assert_with_msg,
     solana_program::{
         account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
         program_error::ProgramError,
+        pubkey::Pubkey,
         system_program,
     },
 };
 
+fn is_token_program(key: &Pubkey) -> bool {
+    key == &spl_token::id() || key == &spl_token_2022::id()
+}
+
+/// Token-2022 managed accounts stay thawed and are gated by the transfer hook,
+/// so the thaw, operate and freeze instructions only apply to SPL Token mints
+fn assert_not_token_2022_mint(mint: &AccountInfo) -> ProgramResult {
+    assert_with_msg(
+        mint.owner != &spl_token_2022::id(),
+        ProgramError::IncorrectProgramId,
+        "Token-2022 managed accounts are used directly through Token-2022",
+    )
+}
+
 pub struct InitializeMint<'a, 'info> {
     pub mint: &'a AccountInfo<'info>,
     pub payer: &'a AccountInfo<'info>,
@@ -36,7 +52,7 @@ impl<'a, 'info> InitializeMint<'a, 'info> {
             "Mint account must be owned by the System Program when uninitialized",
         )?;
         assert_with_msg(
-            ctx.token_program.key == &spl_token::id(),
+            is_token_program(ctx.token_program.key),
             ProgramError::InvalidInstructionData,
             "Invalid key supplied for Token Program",
         )?;
@@ -106,7 +122,7 @@ impl<'a, 'info> InitializeAccount<'a, 'info> {
             "Mint account must be owned by the Token Program",
         )?;
         assert_with_msg(
-            ctx.token_program.key == &spl_token::id(),
+            is_token_program(ctx.token_program.key),
             ProgramError::InvalidInstructionData,
             "Invalid key supplied for Token Program",
         )?;
@@ -173,7 +189,7 @@ impl<'a, 'info> Mint<'a, 'info> {
             "Token account must be owned by the Token Program",
         )?;
         assert_with_msg(
-            ctx.token_program.key == &spl_token::id(),
+            is_token_program(ctx.token_program.key),
             ProgramError::InvalidInstructionData,
             "Invalid key supplied for Token Program",
         )?;
@@ -216,6 +232,7 @@ impl<'a, 'info> Burn<'a, 'info> {
             freeze_authority: next_account_info(account_iter)?,
             token_program: next_account_info(account_iter)?,
         };
+        assert_not_token_2022_mint(ctx.mint)?;
         assert_with_msg(
             ctx.mint.owner == ctx.token_program.key,
             ProgramError::IllegalOwner,
@@ -277,6 +294,7 @@ impl<'a, 'info> Transfer<'a, 'info> {
             freeze_authority: next_account_info(account_iter)?,
             token_program: next_account_info(account_iter)?,
         };
+        assert_not_token_2022_mint(ctx.mint)?;
         assert_with_msg(
             ctx.mint.owner == &spl_token::id(),
             ProgramError::IllegalOwner,
@@ -343,6 +361,7 @@ impl<'a, 'info> Close<'a, 'info> {
             freeze_authority: next_account_info(account_iter)?,
             token_program: next_account_info(account_iter)?,
         };
+        assert_not_token_2022_mint(ctx.mint)?;
         assert_with_msg(
             ctx.mint.owner == ctx.token_program.key,
             ProgramError::IllegalOwner,
@@ -409,6 +428,7 @@ impl<'a, 'info> Approve<'a, 'info> {
             freeze_authority: next_account_info(account_iter)?,
             token_program: next_account_info(account_iter)?,
         };
+        assert_not_token_2022_mint(ctx.mint)?;
         assert_with_msg(
             ctx.mint.owner == &spl_token::id(),
             ProgramError::IllegalOwner,
@@ -463,6 +483,7 @@ impl<'a, 'info> Revoke<'a, 'info> {
             freeze_authority: next_account_info(account_iter)?,
             token_program: next_account_info(account_iter)?,
         };
+        assert_not_token_2022_mint(ctx.mint)?;
         assert_with_msg(
             ctx.mint.owner == &spl_token::id(),
             ProgramError::IllegalOwner,
@@ -496,3 +517,198 @@ impl<'a, 'info> Revoke<'a, 'info> {
         Ok(ctx)
     }
 }
+
+pub struct SetTransferPolicy<'a, 'info> {
+    pub mint: &'a AccountInfo<'info>,
+    pub payer: &'a AccountInfo<'info>,
+    pub upstream_authority: &'a AccountInfo<'info>,
+    pub transfer_policy: &'a AccountInfo<'info>,
+    pub extra_account_metas: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> SetTransferPolicy<'a, 'info> {
+    pub fn load(accounts: &'a [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        let account_iter = &mut accounts.iter();
+        let ctx = Self {
+            mint: next_account_info(account_iter)?,
+            payer: next_account_info(account_iter)?,
+            upstream_authority: next_account_info(account_iter)?,
+            transfer_policy: next_account_info(account_iter)?,
+            extra_account_metas: next_account_info(account_iter)?,
+            system_program: next_account_info(account_iter)?,
+        };
+        assert_with_msg(
+            ctx.mint.owner == &spl_token_2022::id(),
+            ProgramError::IllegalOwner,
+            "Transfer policies are only supported for Token-2022 mints",
+        )?;
+        assert_with_msg(
+            ctx.system_program.key == &system_program::id(),
+            ProgramError::InvalidInstructionData,
+            "Invalid key supplied for System Program",
+        )?;
+        assert_with_msg(
+            ctx.transfer_policy.is_writable,
+            ProgramError::InvalidInstructionData,
+            "Transfer policy account must be writable",
+        )?;
+        assert_with_msg(
+            ctx.extra_account_metas.is_writable,
+            ProgramError::InvalidInstructionData,
+            "Extra account metas account must be writable",
+        )?;
+        assert_with_msg(
+            ctx.payer.is_writable,
+            ProgramError::InvalidInstructionData,
+            "Payer account must be writable (lamport balance will change)",
+        )?;
+        assert_with_msg(
+            ctx.payer.is_signer,
+            ProgramError::MissingRequiredSignature,
+            "Payer must sign for initialization",
+        )?;
+        assert_with_msg(
+            ctx.upstream_authority.is_signer,
+            ProgramError::MissingRequiredSignature,
+            "Upstream authority must sign to set the transfer policy",
+        )?;
+        Ok(ctx)
+    }
+}
+
+pub struct AddToAllowList<'a, 'info> {
+    pub mint: &'a AccountInfo<'info>,
+    pub payer: &'a AccountInfo<'info>,
+    pub upstream_authority: &'a AccountInfo<'info>,
+    pub allow_list_entry: &'a AccountInfo<'info>,
+    pub wallet: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> AddToAllowList<'a, 'info> {
+    pub fn load(accounts: &'a [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        let account_iter = &mut accounts.iter();
+        let ctx = Self {
+            mint: next_account_info(account_iter)?,
+            payer: next_account_info(account_iter)?,
+            upstream_authority: next_account_info(account_iter)?,
+            allow_list_entry: next_account_info(account_iter)?,
+            wallet: next_account_info(account_iter)?,
+            system_program: next_account_info(account_iter)?,
+        };
+        assert_with_msg(
+            ctx.mint.owner == &spl_token_2022::id(),
+            ProgramError::IllegalOwner,
+            "Allow lists are only supported for Token-2022 mints",
+        )?;
+        assert_with_msg(
+            ctx.allow_list_entry.data_is_empty(),
+            ProgramError::InvalidAccountData,
+            "Wallet is already on the allow list",
+        )?;
+        assert_with_msg(
+            ctx.system_program.key == &system_program::id(),
+            ProgramError::InvalidInstructionData,
+            "Invalid key supplied for System Program",
+        )?;
+        assert_with_msg(
+            ctx.allow_list_entry.is_writable,
+            ProgramError::InvalidInstructionData,
+            "Allow list entry must be writable",
+        )?;
+        assert_with_msg(
+            ctx.payer.is_writable,
+            ProgramError::InvalidInstructionData,
+            "Payer account must be writable (lamport balance will change)",
+        )?;
+        assert_with_msg(
+            ctx.payer.is_signer,
+            ProgramError::MissingRequiredSignature,
+            "Payer must sign for initialization",
+        )?;
+        assert_with_msg(
+            ctx.upstream_authority.is_signer,
+            ProgramError::MissingRequiredSignature,
+            "Upstream authority must sign for modification",
+        )?;
+        Ok(ctx)
+    }
+}
+
+pub struct RemoveFromAllowList<'a, 'info> {
+    pub mint: &'a AccountInfo<'info>,
+    pub upstream_authority: &'a AccountInfo<'info>,
+    pub allow_list_entry: &'a AccountInfo<'info>,
+    pub wallet: &'a AccountInfo<'info>,
+    pub destination: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> RemoveFromAllowList<'a, 'info> {
+    pub fn load(accounts: &'a [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        let account_iter = &mut accounts.iter();
+        let ctx = Self {
+            mint: next_account_info(account_iter)?,
+            upstream_authority: next_account_info(account_iter)?,
+            allow_list_entry: next_account_info(account_iter)?,
+            wallet: next_account_info(account_iter)?,
+            destination: next_account_info(account_iter)?,
+        };
+        assert_with_msg(
+            ctx.mint.owner == &spl_token_2022::id(),
+            ProgramError::IllegalOwner,
+            "Allow lists are only supported for Token-2022 mints",
+        )?;
+        assert_with_msg(
+            ctx.allow_list_entry.owner == &crate::id(),
+            ProgramError::IllegalOwner,
+            "Wallet is not on the allow list",
+        )?;
+        assert_with_msg(
+            ctx.allow_list_entry.is_writable,
+            ProgramError::InvalidInstructionData,
+            "Allow list entry must be writable",
+        )?;
+        assert_with_msg(
+            ctx.destination.is_writable,
+            ProgramError::InvalidInstructionData,
+            "Destination account must be writable",
+        )?;
+        assert_with_msg(
+            ctx.upstream_authority.is_signer,
+            ProgramError::MissingRequiredSignature,
+            "Upstream authority must sign for modification",
+        )?;
+        Ok(ctx)
+    }
+}
+
+pub struct Execute<'a, 'info> {
+    pub src_account: &'a AccountInfo<'info>,
+    pub mint: &'a AccountInfo<'info>,
+    pub dst_account: &'a AccountInfo<'info>,
+    pub owner: &'a AccountInfo<'info>,
+    /// Absent when the mint has no transfer policy yet
+    pub extra_account_metas: Option<&'a AccountInfo<'info>>,
+    pub remaining: &'a [AccountInfo<'info>],
+}
+
+impl<'a, 'info> Execute<'a, 'info> {
+    pub fn load(accounts: &'a [AccountInfo<'info>]) -> Result<Self, ProgramError> {
+        let account_iter = &mut accounts.iter();
+        let ctx = Self {
+            src_account: next_account_info(account_iter)?,
+            mint: next_account_info(account_iter)?,
+            dst_account: next_account_info(account_iter)?,
+            owner: next_account_info(account_iter)?,
+            extra_account_metas: account_iter.next(),
+            remaining: accounts.get(5..).unwrap_or_default(),
+        };
+        assert_with_msg(
+            ctx.mint.owner == &spl_token_2022::id(),
+            ProgramError::IllegalOwner,
+            "Mint account must be owned by the Token-2022 Program",
+        )?;
+        Ok(ctx)
+    }
+}
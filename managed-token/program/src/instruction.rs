@@ -1,5 +1,8 @@
 use {
-    crate::get_authority,
+    crate::{
+        get_authority,
+        state::{get_allow_list_entry_address, get_transfer_policy_address, TransferPolicyKind},
+    },
     borsh::{BorshDeserialize, BorshSerialize},
     shank::ShankInstruction,
     solana_program::{
@@ -8,7 +11,10 @@ use {
         pubkey::Pubkey,
         system_program,
     },
-    spl_associated_token_account_client::address::get_associated_token_address,
+    spl_associated_token_account_client::address::{
+        get_associated_token_address, get_associated_token_address_with_program_id,
+    },
+    spl_transfer_hook_interface::get_extra_account_metas_address,
 };
 
 #[derive(Debug, Clone, ShankInstruction, BorshSerialize, BorshDeserialize)]
@@ -88,6 +94,29 @@ pub enum ManagedTokenInstruction {
     #[account(4, name = "freeze_authority")]
     #[account(5, name = "token_program", desc = "Token program")]
     Revoke,
+
+    #[account(0, name = "mint")]
+    #[account(1, writable, signer, name = "payer")]
+    #[account(2, signer, name = "upstream_authority")]
+    #[account(3, writable, name = "transfer_policy")]
+    #[account(4, writable, name = "extra_account_metas", desc = "Transfer hook validation account")]
+    #[account(5, name = "system_program", desc = "System program")]
+    SetTransferPolicy { policy: TransferPolicyKind },
+
+    #[account(0, name = "mint")]
+    #[account(1, writable, signer, name = "payer")]
+    #[account(2, signer, name = "upstream_authority")]
+    #[account(3, writable, name = "allow_list_entry")]
+    #[account(4, name = "wallet")]
+    #[account(5, name = "system_program", desc = "System program")]
+    AddToAllowList,
+
+    #[account(0, name = "mint")]
+    #[account(1, signer, name = "upstream_authority")]
+    #[account(2, writable, name = "allow_list_entry")]
+    #[account(3, name = "wallet")]
+    #[account(4, writable, name = "destination")]
+    RemoveFromAllowList,
 }
 
 pub fn create_initialize_mint_instruction(
@@ -95,6 +124,25 @@ pub fn create_initialize_mint_instruction(
     payer: &Pubkey,
     upstream_authority: &Pubkey,
     decimals: u8,
+) -> Result<Instruction, ProgramError> {
+    create_initialize_mint_with_token_program_instruction(
+        mint,
+        payer,
+        upstream_authority,
+        &spl_token::id(),
+        decimals,
+    )
+}
+
+/// Token-2022 mints are created with frozen default account state and a
+/// transfer hook pointing at this program instead of relying on freeze/thaw
+/// around every instruction
+pub fn create_initialize_mint_with_token_program_instruction(
+    mint: &Pubkey,
+    payer: &Pubkey,
+    upstream_authority: &Pubkey,
+    token_program: &Pubkey,
+    decimals: u8,
 ) -> Result<Instruction, ProgramError> {
     Ok(Instruction {
         program_id: crate::id(),
@@ -103,7 +151,7 @@ pub fn create_initialize_mint_instruction(
             AccountMeta::new(*payer, true),
             AccountMeta::new_readonly(*upstream_authority, false),
             AccountMeta::new_readonly(system_program::id(), false),
-            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*token_program, false),
         ],
         data: borsh::to_vec(&ManagedTokenInstruction::InitializeMint { decimals })?,
     })
@@ -115,7 +163,23 @@ pub fn create_initialize_account_instruction(
     payer: &Pubkey,
     upstream_authority: &Pubkey,
 ) -> Result<Instruction, ProgramError> {
-    let account = get_associated_token_address(owner, mint);
+    create_initialize_account_with_token_program_instruction(
+        mint,
+        owner,
+        payer,
+        upstream_authority,
+        &spl_token::id(),
+    )
+}
+
+pub fn create_initialize_account_with_token_program_instruction(
+    mint: &Pubkey,
+    owner: &Pubkey,
+    payer: &Pubkey,
+    upstream_authority: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let account = get_associated_token_address_with_program_id(owner, mint, token_program);
     let (freeze_authority, _) = get_authority(upstream_authority);
     Ok(Instruction {
         program_id: crate::id(),
@@ -128,7 +192,7 @@ pub fn create_initialize_account_instruction(
             AccountMeta::new_readonly(*mint, false),
             AccountMeta::new_readonly(system_program::id(), false),
             AccountMeta::new_readonly(spl_associated_token_account_client::program::id(), false),
-            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*token_program, false),
         ],
         data: borsh::to_vec(&ManagedTokenInstruction::InitializeAccount)?,
     })
@@ -140,7 +204,23 @@ pub fn create_mint_to_instruction(
     upstream_authority: &Pubkey,
     amount: u64,
 ) -> Result<Instruction, ProgramError> {
-    let account = get_associated_token_address(owner, mint);
+    create_mint_to_with_token_program_instruction(
+        mint,
+        owner,
+        upstream_authority,
+        &spl_token::id(),
+        amount,
+    )
+}
+
+pub fn create_mint_to_with_token_program_instruction(
+    mint: &Pubkey,
+    owner: &Pubkey,
+    upstream_authority: &Pubkey,
+    token_program: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let account = get_associated_token_address_with_program_id(owner, mint, token_program);
     let (authority, _) = get_authority(upstream_authority);
     Ok(Instruction {
         program_id: crate::id(),
@@ -149,7 +229,7 @@ pub fn create_mint_to_instruction(
             AccountMeta::new(account, false),
             AccountMeta::new_readonly(*upstream_authority, true),
             AccountMeta::new_readonly(authority, false),
-            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(*token_program, false),
         ],
         data: borsh::to_vec(&ManagedTokenInstruction::MintTo { amount })?,
     })
@@ -294,3 +374,115 @@ pub fn create_revoke_instruction(
         data: borsh::to_vec(&ManagedTokenInstruction::Revoke)?,
     })
 }
+
+pub fn create_set_transfer_policy_instruction(
+    mint: &Pubkey,
+    payer: &Pubkey,
+    upstream_authority: &Pubkey,
+    policy: TransferPolicyKind,
+) -> Result<Instruction, ProgramError> {
+    let (transfer_policy, _) = get_transfer_policy_address(mint);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*upstream_authority, true),
+            AccountMeta::new(transfer_policy, false),
+            AccountMeta::new(get_extra_account_metas_address(mint, &crate::id()), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: borsh::to_vec(&ManagedTokenInstruction::SetTransferPolicy { policy })?,
+    })
+}
+
+pub fn create_add_to_allow_list_instruction(
+    mint: &Pubkey,
+    payer: &Pubkey,
+    upstream_authority: &Pubkey,
+    wallet: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let (allow_list_entry, _) = get_allow_list_entry_address(mint, wallet);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*upstream_authority, true),
+            AccountMeta::new(allow_list_entry, false),
+            AccountMeta::new_readonly(*wallet, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: borsh::to_vec(&ManagedTokenInstruction::AddToAllowList)?,
+    })
+}
+
+pub fn create_remove_from_allow_list_instruction(
+    mint: &Pubkey,
+    upstream_authority: &Pubkey,
+    wallet: &Pubkey,
+    destination: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let (allow_list_entry, _) = get_allow_list_entry_address(mint, wallet);
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(*upstream_authority, true),
+            AccountMeta::new(allow_list_entry, false),
+            AccountMeta::new_readonly(*wallet, false),
+            AccountMeta::new(*destination, false),
+        ],
+        data: borsh::to_vec(&ManagedTokenInstruction::RemoveFromAllowList)?,
+    })
+}
+
+/// Token-2022 `TransferChecked` between the associated token accounts of two
+/// wallets, with the accounts required by the mint's transfer policy appended
+pub fn create_transfer_checked_instruction(
+    src: &Pubkey,
+    dst: &Pubkey,
+    mint: &Pubkey,
+    policy: &TransferPolicyKind,
+    amount: u64,
+    decimals: u8,
+) -> Result<Instruction, ProgramError> {
+    let token_program = spl_token_2022::id();
+    let mut instruction = spl_token_2022::instruction::transfer_checked(
+        &token_program,
+        &get_associated_token_address_with_program_id(src, mint, &token_program),
+        mint,
+        &get_associated_token_address_with_program_id(dst, mint, &token_program),
+        src,
+        &[],
+        amount,
+        decimals,
+    )?;
+    instruction.accounts.push(AccountMeta::new_readonly(
+        get_transfer_policy_address(mint).0,
+        false,
+    ));
+    match policy {
+        TransferPolicyKind::AllowList => {
+            for wallet in [src, dst] {
+                instruction.accounts.push(AccountMeta::new_readonly(
+                    get_allow_list_entry_address(mint, wallet).0,
+                    false,
+                ));
+            }
+        }
+        TransferPolicyKind::Program(policy_program) => {
+            instruction
+                .accounts
+                .push(AccountMeta::new_readonly(*policy_program, false));
+        }
+    }
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(crate::id(), false));
+    instruction.accounts.push(AccountMeta::new_readonly(
+        get_extra_account_metas_address(mint, &crate::id()),
+        false,
+    ));
+    Ok(instruction)
+}
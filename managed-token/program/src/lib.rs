@@ -1,13 +1,33 @@
 solana_program::declare_id!("mTok58Lg4YfcmwqyrDHpf7ogp599WRhzb6PxjaBqAxS");
 
 use {
-    borsh::BorshDeserialize,
+    borsh::{BorshDeserialize, BorshSerialize},
     solana_program::{
-        account_info::AccountInfo, entrypoint::ProgramResult, msg, program::invoke,
-        program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, rent::Rent,
-        system_instruction, sysvar::Sysvar,
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        instruction::{AccountMeta, Instruction},
+        msg,
+        program::{invoke, invoke_signed},
+        program_error::ProgramError,
+        program_pack::Pack,
+        pubkey::Pubkey,
+        rent::Rent,
+        system_instruction,
+        sysvar::Sysvar,
     },
     spl_associated_token_account::instruction::create_associated_token_account,
+    spl_tlv_account_resolution::{
+        account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList,
+    },
+    spl_token_2022::extension::{ExtensionType, StateWithExtensions},
+    spl_transfer_hook_interface::{
+        collect_extra_account_metas_signer_seeds, get_extra_account_metas_address_and_bump_seed,
+        instruction::{ExecuteInstruction, TransferHookInstruction},
+    },
+    state::{
+        get_allow_list_entry_address, get_transfer_policy_address, AllowListEntry, TransferPolicy,
+        TransferPolicyKind, ALLOW_LIST_SEED, TRANSFER_POLICY_SEED,
+    },
 };
 
 #[track_caller]
@@ -24,11 +44,18 @@ pub fn assert_with_msg(v: bool, err: impl Into<ProgramError>, msg: &str) -> Prog
 
 pub mod accounts;
 pub mod instruction;
+pub mod state;
 pub mod token;
 use {
-    accounts::{Approve, Burn, Close, InitializeAccount, InitializeMint, Mint, Revoke, Transfer},
+    accounts::{
+        AddToAllowList, Approve, Burn, Close, Execute, InitializeAccount, InitializeMint, Mint,
+        RemoveFromAllowList, Revoke, SetTransferPolicy, Transfer,
+    },
     instruction::ManagedTokenInstruction,
-    token::{approve, burn, close, freeze, initialize_mint, mint_to, revoke, thaw, transfer},
+    token::{
+        approve, burn, close, freeze, initialize_managed_extensions, initialize_mint, mint_to,
+        revoke, thaw, transfer,
+    },
 };
 
 #[cfg(not(feature = "no-entrypoint"))]
@@ -64,6 +91,13 @@ pub fn process_instruction(
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
+    // Token-2022 invokes this program as the transfer hook of managed mints
+    if let Ok(TransferHookInstruction::Execute { amount }) =
+        TransferHookInstruction::unpack(instruction_data)
+    {
+        msg!("TransferHookInstruction::Execute");
+        return process_execute(accounts, amount);
+    }
     let instruction = ManagedTokenInstruction::try_from_slice(instruction_data)?;
     match instruction {
         ManagedTokenInstruction::InitializeMint { decimals } => {
@@ -98,6 +132,18 @@ pub fn process_instruction(
             msg!("ManagedTokenInstruction::Revoke");
             process_revoke(accounts)
         }
+        ManagedTokenInstruction::SetTransferPolicy { policy } => {
+            msg!("ManagedTokenInstruction::SetTransferPolicy");
+            process_set_transfer_policy(accounts, policy)
+        }
+        ManagedTokenInstruction::AddToAllowList => {
+            msg!("ManagedTokenInstruction::AddToAllowList");
+            process_add_to_allow_list(accounts)
+        }
+        ManagedTokenInstruction::RemoveFromAllowList => {
+            msg!("ManagedTokenInstruction::RemoveFromAllowList");
+            process_remove_from_allow_list(accounts)
+        }
     }
 }
 
@@ -109,7 +155,15 @@ pub fn process_initialize_mint(accounts: &[AccountInfo], decimals: u8) -> Progra
         system_program,
         token_program,
     } = InitializeMint::load(accounts)?;
-    let space = spl_token::state::Mint::LEN;
+    let is_token_2022 = token_program.key == &spl_token_2022::id();
+    let space = if is_token_2022 {
+        ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+            ExtensionType::DefaultAccountState,
+            ExtensionType::TransferHook,
+        ])?
+    } else {
+        spl_token::state::Mint::LEN
+    };
     invoke(
         &system_instruction::create_account(
             payer.key,
@@ -121,6 +175,9 @@ pub fn process_initialize_mint(accounts: &[AccountInfo], decimals: u8) -> Progra
         &[payer.clone(), mint.clone(), system_program.clone()],
     )?;
     let (authority, _) = get_authority(upstream_authority.key);
+    if is_token_2022 {
+        initialize_managed_extensions(&authority, mint, token_program)?;
+    }
     initialize_mint(&authority, &authority, mint, token_program, decimals)
 }
 
@@ -149,7 +206,14 @@ pub fn process_initialize_account(accounts: &[AccountInfo]) -> ProgramResult {
         ],
     )?;
     let seeds = get_authority_seeds_checked(upstream_authority.key, freeze_authority.key)?;
-    freeze(freeze_authority, mint, token_account, token_program, &seeds)
+    if token_program.key == &spl_token_2022::id() {
+        // Accounts start out frozen through the default account state, so
+        // thawing them once admits the owner; transfers are then gated by the
+        // transfer hook.
+        thaw(freeze_authority, mint, token_account, token_program, &seeds)
+    } else {
+        freeze(freeze_authority, mint, token_account, token_program, &seeds)
+    }
 }
 
 pub fn process_transfer(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
@@ -179,6 +243,16 @@ pub fn process_mint_to(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
         token_program,
     } = Mint::load(accounts)?;
     let authority_seeds = get_authority_seeds_checked(upstream_authority.key, authority.key)?;
+    if token_program.key == &spl_token_2022::id() {
+        return mint_to(
+            mint,
+            token_account,
+            authority,
+            token_program,
+            amount,
+            &authority_seeds,
+        );
+    }
     thaw(
         authority,
         mint,
@@ -263,3 +337,286 @@ pub fn process_revoke(accounts: &[AccountInfo]) -> ProgramResult {
     revoke(token_account, owner, token_program)?;
     freeze(freeze_authority, mint, token_account, token_program, &seeds)
 }
+
+/// Accounts the transfer hook requires for the given policy, after the
+/// transfer policy account itself
+pub fn get_extra_account_metas(
+    policy: &TransferPolicyKind,
+) -> Result<Vec<ExtraAccountMeta>, ProgramError> {
+    let mut metas = vec![ExtraAccountMeta::new_with_seeds(
+        &[
+            Seed::Literal {
+                bytes: TRANSFER_POLICY_SEED.to_vec(),
+            },
+            Seed::AccountKey { index: 1 },
+        ],
+        false,
+        false,
+    )?];
+    match policy {
+        TransferPolicyKind::AllowList => {
+            // Allow-list entries of the source and destination owners, read
+            // from the token account data
+            for account_index in [0, 2] {
+                metas.push(ExtraAccountMeta::new_with_seeds(
+                    &[
+                        Seed::Literal {
+                            bytes: ALLOW_LIST_SEED.to_vec(),
+                        },
+                        Seed::AccountKey { index: 1 },
+                        Seed::AccountData {
+                            account_index,
+                            data_index: 32,
+                            length: 32,
+                        },
+                    ],
+                    false,
+                    false,
+                )?);
+            }
+        }
+        TransferPolicyKind::Program(policy_program) => {
+            metas.push(ExtraAccountMeta::new_with_pubkey(
+                policy_program,
+                false,
+                false,
+            )?);
+        }
+    }
+    Ok(metas)
+}
+
+/// The largest number of extra accounts any policy requires
+const MAX_EXTRA_ACCOUNT_METAS: usize = 3;
+
+fn assert_upstream_authority_of_mint(
+    mint: &AccountInfo,
+    upstream_authority: &Pubkey,
+) -> ProgramResult {
+    let mint_data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let (authority, _) = get_authority(upstream_authority);
+    assert_with_msg(
+        mint.base.mint_authority == Some(authority).into(),
+        ProgramError::InvalidAccountData,
+        "Mint is not managed by the upstream authority",
+    )
+}
+
+fn create_pda<'a, 'info>(
+    payer: &'a AccountInfo<'info>,
+    account: &'a AccountInfo<'info>,
+    system_program: &'a AccountInfo<'info>,
+    space: usize,
+    seeds: &[&[u8]],
+) -> ProgramResult {
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            account.key,
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            &crate::id(),
+        ),
+        &[payer.clone(), account.clone(), system_program.clone()],
+        &[seeds],
+    )
+}
+
+pub fn process_set_transfer_policy(
+    accounts: &[AccountInfo],
+    policy: TransferPolicyKind,
+) -> ProgramResult {
+    let SetTransferPolicy {
+        mint,
+        payer,
+        upstream_authority,
+        transfer_policy,
+        extra_account_metas,
+        system_program,
+    } = SetTransferPolicy::load(accounts)?;
+    assert_upstream_authority_of_mint(mint, upstream_authority.key)?;
+
+    let (policy_key, policy_seeds) = get_transfer_policy_address(mint.key);
+    assert_with_msg(
+        transfer_policy.key == &policy_key,
+        ProgramError::InvalidSeeds,
+        "Invalid transfer policy address",
+    )?;
+    if transfer_policy.data_is_empty() {
+        create_pda(
+            payer,
+            transfer_policy,
+            system_program,
+            TransferPolicy::LEN,
+            &policy_seeds
+                .iter()
+                .map(|s| s.as_slice())
+                .collect::<Vec<_>>(),
+        )?;
+    }
+    let metas = get_extra_account_metas(&policy)?;
+    TransferPolicy {
+        upstream_authority: *upstream_authority.key,
+        kind: policy,
+    }
+    .serialize(&mut &mut transfer_policy.try_borrow_mut_data()?[..])?;
+
+    let (metas_key, bump) = get_extra_account_metas_address_and_bump_seed(mint.key, &crate::id());
+    assert_with_msg(
+        extra_account_metas.key == &metas_key,
+        ProgramError::InvalidSeeds,
+        "Invalid extra account metas address",
+    )?;
+    if extra_account_metas.data_is_empty() {
+        let bump = [bump];
+        create_pda(
+            payer,
+            extra_account_metas,
+            system_program,
+            ExtraAccountMetaList::size_of(MAX_EXTRA_ACCOUNT_METAS)?,
+            &collect_extra_account_metas_signer_seeds(mint.key, &bump),
+        )?;
+        ExtraAccountMetaList::init::<ExecuteInstruction>(
+            &mut extra_account_metas.try_borrow_mut_data()?,
+            &metas,
+        )
+    } else {
+        ExtraAccountMetaList::update::<ExecuteInstruction>(
+            &mut extra_account_metas.try_borrow_mut_data()?,
+            &metas,
+        )
+    }
+}
+
+pub fn process_add_to_allow_list(accounts: &[AccountInfo]) -> ProgramResult {
+    let AddToAllowList {
+        mint,
+        payer,
+        upstream_authority,
+        allow_list_entry,
+        wallet,
+        system_program,
+    } = AddToAllowList::load(accounts)?;
+    assert_upstream_authority_of_mint(mint, upstream_authority.key)?;
+    let (entry_key, seeds) = get_allow_list_entry_address(mint.key, wallet.key);
+    assert_with_msg(
+        allow_list_entry.key == &entry_key,
+        ProgramError::InvalidSeeds,
+        "Invalid allow list entry address",
+    )?;
+    create_pda(
+        payer,
+        allow_list_entry,
+        system_program,
+        AllowListEntry::LEN,
+        &seeds.iter().map(|s| s.as_slice()).collect::<Vec<_>>(),
+    )?;
+    AllowListEntry {
+        mint: *mint.key,
+        wallet: *wallet.key,
+    }
+    .serialize(&mut &mut allow_list_entry.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+pub fn process_remove_from_allow_list(accounts: &[AccountInfo]) -> ProgramResult {
+    let RemoveFromAllowList {
+        mint,
+        upstream_authority,
+        allow_list_entry,
+        wallet,
+        destination,
+    } = RemoveFromAllowList::load(accounts)?;
+    assert_upstream_authority_of_mint(mint, upstream_authority.key)?;
+    let (entry_key, _) = get_allow_list_entry_address(mint.key, wallet.key);
+    assert_with_msg(
+        allow_list_entry.key == &entry_key,
+        ProgramError::InvalidSeeds,
+        "Invalid allow list entry address",
+    )?;
+    let lamports = allow_list_entry.lamports();
+    **allow_list_entry.try_borrow_mut_lamports()? = 0;
+    **destination.try_borrow_mut_lamports()? = destination
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    allow_list_entry.realloc(0, false)?;
+    allow_list_entry.assign(&solana_program::system_program::id());
+    Ok(())
+}
+
+pub fn process_execute(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let Execute {
+        src_account,
+        mint,
+        dst_account,
+        owner,
+        extra_account_metas,
+        remaining,
+    } = Execute::load(accounts)?;
+    let extra_account_metas = match extra_account_metas {
+        Some(info) if !info.data_is_empty() => info,
+        _ => {
+            msg!("Transfer policy has not been set for this mint");
+            return Err(ProgramError::UninitializedAccount);
+        }
+    };
+    let (metas_key, _) = get_extra_account_metas_address_and_bump_seed(mint.key, &crate::id());
+    assert_with_msg(
+        extra_account_metas.key == &metas_key,
+        ProgramError::InvalidSeeds,
+        "Invalid extra account metas address",
+    )?;
+    let instruction_data = TransferHookInstruction::Execute { amount }.pack();
+    ExtraAccountMetaList::check_account_infos::<ExecuteInstruction>(
+        accounts,
+        &instruction_data,
+        &crate::id(),
+        &extra_account_metas.try_borrow_data()?,
+    )?;
+
+    // The extra accounts were derived from the validation account, so only
+    // their contents remain to be checked
+    let transfer_policy = &remaining[0];
+    assert_with_msg(
+        transfer_policy.owner == &crate::id(),
+        ProgramError::IllegalOwner,
+        "Transfer policy must be owned by the managed token program",
+    )?;
+    let policy = TransferPolicy::deserialize(&mut &transfer_policy.try_borrow_data()?[..])?;
+    match policy.kind {
+        TransferPolicyKind::AllowList => {
+            for entry in &remaining[1..3] {
+                assert_with_msg(
+                    entry.owner == &crate::id() && !entry.data_is_empty(),
+                    ProgramError::InvalidAccountData,
+                    "Token account owner is not on the allow list",
+                )?;
+            }
+            Ok(())
+        }
+        TransferPolicyKind::Program(policy_program) => {
+            let policy_program_info = &remaining[1];
+            invoke(
+                &Instruction {
+                    program_id: policy_program,
+                    accounts: vec![
+                        AccountMeta::new_readonly(*src_account.key, false),
+                        AccountMeta::new_readonly(*mint.key, false),
+                        AccountMeta::new_readonly(*dst_account.key, false),
+                        AccountMeta::new_readonly(*owner.key, false),
+                    ],
+                    data: instruction_data,
+                },
+                &[
+                    src_account.clone(),
+                    mint.clone(),
+                    dst_account.clone(),
+                    owner.clone(),
+                    policy_program_info.clone(),
+                ],
+            )
+        }
+    }
+}
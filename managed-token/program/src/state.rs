@@ -0,0 +1,62 @@
+use {
+    borsh::{BorshDeserialize, BorshSerialize},
+    solana_program::pubkey::Pubkey,
+};
+
+pub const TRANSFER_POLICY_SEED: &[u8] = b"transfer-policy";
+pub const ALLOW_LIST_SEED: &[u8] = b"allow-list";
+
+/// How transfers of a Token-2022 managed mint are approved by the transfer
+/// hook
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub enum TransferPolicyKind {
+    /// Both the source and destination owners must have an allow-list entry
+    AllowList,
+    /// The transfer is forwarded to the given program as a transfer-hook
+    /// `Execute` instruction, which may reject it
+    Program(Pubkey),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct TransferPolicy {
+    pub upstream_authority: Pubkey,
+    pub kind: TransferPolicyKind,
+}
+
+impl TransferPolicy {
+    /// Upstream authority + enum tag + largest variant
+    pub const LEN: usize = 32 + 1 + 32;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct AllowListEntry {
+    pub mint: Pubkey,
+    pub wallet: Pubkey,
+}
+
+impl AllowListEntry {
+    pub const LEN: usize = 32 + 32;
+}
+
+#[inline]
+pub fn get_transfer_policy_address(mint: &Pubkey) -> (Pubkey, Vec<Vec<u8>>) {
+    get_address_and_seeds(vec![TRANSFER_POLICY_SEED.to_vec(), mint.as_ref().to_vec()])
+}
+
+#[inline]
+pub fn get_allow_list_entry_address(mint: &Pubkey, wallet: &Pubkey) -> (Pubkey, Vec<Vec<u8>>) {
+    get_address_and_seeds(vec![
+        ALLOW_LIST_SEED.to_vec(),
+        mint.as_ref().to_vec(),
+        wallet.as_ref().to_vec(),
+    ])
+}
+
+fn get_address_and_seeds(mut seeds: Vec<Vec<u8>>) -> (Pubkey, Vec<Vec<u8>>) {
+    let (key, bump) = Pubkey::find_program_address(
+        &seeds.iter().map(|s| s.as_slice()).collect::<Vec<&[u8]>>(),
+        &crate::id(),
+    );
+    seeds.push(vec![bump]);
+    (key, seeds)
+}
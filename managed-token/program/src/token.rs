@@ -1,8 +1,14 @@
-use solana_program::{
-    account_info::AccountInfo,
-    entrypoint::ProgramResult,
-    program::{invoke, invoke_signed},
-    pubkey::Pubkey,
+use {
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program::{invoke, invoke_signed},
+        pubkey::Pubkey,
+    },
+    spl_token_2022::{
+        extension::{default_account_state, transfer_hook},
+        state::AccountState,
+    },
 };
 
 pub(crate) fn initialize_mint<'a, 'b>(
@@ -13,7 +19,7 @@ pub(crate) fn initialize_mint<'a, 'b>(
     decimals: u8,
 ) -> ProgramResult {
     invoke(
-        &spl_token::instruction::initialize_mint2(
+        &spl_token_2022::instruction::initialize_mint2(
             token_program.key,
             mint.key,
             mint_authority,
@@ -24,6 +30,30 @@ pub(crate) fn initialize_mint<'a, 'b>(
     )
 }
 
+pub(crate) fn initialize_managed_extensions<'a, 'b>(
+    authority: &Pubkey,
+    mint: &'a AccountInfo<'b>,
+    token_program: &'a AccountInfo<'b>,
+) -> ProgramResult {
+    invoke(
+        &default_account_state::instruction::initialize_default_account_state(
+            token_program.key,
+            mint.key,
+            &AccountState::Frozen,
+        )?,
+        &[token_program.clone(), mint.clone()],
+    )?;
+    invoke(
+        &transfer_hook::instruction::initialize(
+            token_program.key,
+            mint.key,
+            Some(*authority),
+            Some(crate::id()),
+        )?,
+        &[token_program.clone(), mint.clone()],
+    )
+}
+
 pub(crate) fn thaw<'a, 'b>(
     freeze_authority: &'a AccountInfo<'b>,
     mint: &'a AccountInfo<'b>,
@@ -32,7 +62,7 @@ pub(crate) fn thaw<'a, 'b>(
     seeds: &[Vec<u8>],
 ) -> ProgramResult {
     invoke_signed(
-        &spl_token::instruction::thaw_account(
+        &spl_token_2022::instruction::thaw_account(
             token_program.key,
             target.key,
             mint.key,
@@ -57,7 +87,7 @@ pub(crate) fn freeze<'a, 'b>(
     seeds: &[Vec<u8>],
 ) -> ProgramResult {
     invoke_signed(
-        &spl_token::instruction::freeze_account(
+        &spl_token_2022::instruction::freeze_account(
             token_program.key,
             target.key,
             mint.key,
@@ -108,7 +138,7 @@ pub(crate) fn mint_to<'a, 'b>(
     seeds: &[Vec<u8>],
 ) -> ProgramResult {
     invoke_signed(
-        &spl_token::instruction::mint_to(
+        &spl_token_2022::instruction::mint_to(
             token_program.key,
             mint.key,
             account.key,
@@ -1,19 +1,29 @@
 use {
-    solana_program::program_option::COption,
+    solana_program::{
+        account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+        program_option::COption,
+    },
     solana_program_test::*,
     solana_sdk::{
         commitment_config::CommitmentLevel,
-        instruction::Instruction,
+        instruction::{Instruction, InstructionError},
         native_token::LAMPORTS_PER_SOL,
         pubkey::Pubkey,
         signature::{Keypair, Signature, Signer},
         system_instruction,
-        transaction::Transaction,
+        transaction::{Transaction, TransactionError},
     },
     spl_associated_token_account::instruction::create_associated_token_account,
-    spl_associated_token_account_client::address::get_associated_token_address,
-    spl_managed_token::instruction::*,
+    spl_associated_token_account_client::address::{
+        get_associated_token_address, get_associated_token_address_with_program_id,
+    },
+    spl_managed_token::{instruction::*, state::TransferPolicyKind},
     spl_token::state::Account as TokenAccount,
+    spl_token_2022::{
+        extension::StateWithExtensionsOwned,
+        state::{Account as Token2022Account, AccountState},
+    },
+    spl_transfer_hook_interface::instruction::TransferHookInstruction,
 };
 
 pub fn sol(amount: f64) -> u64 {
@@ -310,3 +320,353 @@ async fn test_spl_managed_token_with_delegate_transfer() {
             == 1
     );
 }
+
+const POLICY_PROGRAM_ID: Pubkey =
+    solana_program::pubkey!("Po1icy1111111111111111111111111111111111111");
+
+/// Rejects any transfer of more than 10 tokens
+fn policy_program_process_instruction(
+    _program_id: &Pubkey,
+    _accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    match TransferHookInstruction::unpack(instruction_data)? {
+        TransferHookInstruction::Execute { amount } if amount <= 10 => Ok(()),
+        _ => Err(ProgramError::InvalidArgument),
+    }
+}
+
+/// Creates a Token-2022 managed mint, plus thawed accounts holding 1000
+/// tokens for each of the given wallets
+async fn setup_token_2022_mint(
+    context: &mut ProgramTestContext,
+    authority: &Keypair,
+    wallets: &[&Pubkey],
+) -> Pubkey {
+    let lwc = &mut context.banks_client;
+    transfer(lwc, &context.payer, &authority.pubkey(), sol(10.0))
+        .await
+        .unwrap();
+    let mint = Keypair::new();
+    let mint_key = mint.pubkey();
+    let create_ix = create_initialize_mint_with_token_program_instruction(
+        &mint_key,
+        &authority.pubkey(),
+        &authority.pubkey(),
+        &spl_token_2022::id(),
+        0,
+    )
+    .unwrap();
+    process_transaction(lwc, vec![create_ix], vec![authority, &mint])
+        .await
+        .unwrap();
+    for wallet in wallets {
+        let create_ata = create_initialize_account_with_token_program_instruction(
+            &mint_key,
+            wallet,
+            &authority.pubkey(),
+            &authority.pubkey(),
+            &spl_token_2022::id(),
+        )
+        .unwrap();
+        let mint_to_ix = create_mint_to_with_token_program_instruction(
+            &mint_key,
+            wallet,
+            &authority.pubkey(),
+            &spl_token_2022::id(),
+            1000,
+        )
+        .unwrap();
+        process_transaction(lwc, vec![create_ata, mint_to_ix], vec![authority])
+            .await
+            .unwrap();
+    }
+    mint_key
+}
+
+async fn get_token_2022_account(
+    client: &mut BanksClient,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+) -> Token2022Account {
+    let address = get_associated_token_address_with_program_id(wallet, mint, &spl_token_2022::id());
+    let account = client.get_account(address).await.unwrap().unwrap();
+    StateWithExtensionsOwned::<Token2022Account>::unpack(account.data)
+        .unwrap()
+        .base
+}
+
+#[tokio::test]
+async fn test_spl_managed_token_2022_allow_list() {
+    let mut context = spl_managed_token_test().start_with_context().await;
+    let authority = Keypair::new();
+    let alice = Keypair::new();
+    let alice_key = alice.pubkey();
+    let bob_key = Pubkey::new_unique();
+    let eve_key = Pubkey::new_unique();
+    let mint_key =
+        setup_token_2022_mint(&mut context, &authority, &[&alice_key, &bob_key, &eve_key]).await;
+    let lwc = &mut context.banks_client;
+    transfer(lwc, &context.payer, &alice_key, sol(1.0))
+        .await
+        .unwrap();
+
+    // Accounts created without the upstream authority stay frozen
+    let mallory_key = Pubkey::new_unique();
+    let create_mallory = create_associated_token_account(
+        &context.payer.pubkey(),
+        &mallory_key,
+        &mint_key,
+        &spl_token_2022::id(),
+    );
+    process_transaction(lwc, vec![create_mallory], vec![&context.payer])
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_2022_account(lwc, &mallory_key, &mint_key)
+            .await
+            .state,
+        AccountState::Frozen
+    );
+    assert_eq!(
+        get_token_2022_account(lwc, &alice_key, &mint_key)
+            .await
+            .state,
+        AccountState::Initialized
+    );
+
+    let policy = TransferPolicyKind::AllowList;
+    let alice_to_bob_ix =
+        create_transfer_checked_instruction(&alice_key, &bob_key, &mint_key, &policy, 100, 0)
+            .unwrap();
+
+    // No transfers until the upstream authority sets a policy
+    assert!(
+        process_transaction(lwc, vec![alice_to_bob_ix.clone()], vec![&alice])
+            .await
+            .is_err()
+    );
+
+    let set_policy_ix = create_set_transfer_policy_instruction(
+        &mint_key,
+        &authority.pubkey(),
+        &authority.pubkey(),
+        policy.clone(),
+    )
+    .unwrap();
+    let allow_ixs = [&alice_key, &bob_key]
+        .iter()
+        .map(|wallet| {
+            create_add_to_allow_list_instruction(
+                &mint_key,
+                &authority.pubkey(),
+                &authority.pubkey(),
+                wallet,
+            )
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+    process_transaction(
+        lwc,
+        [vec![set_policy_ix], allow_ixs].concat(),
+        vec![&authority],
+    )
+    .await
+    .unwrap();
+
+    // Only the owner signs, the upstream authority is not needed
+    process_transaction(lwc, vec![alice_to_bob_ix], vec![&alice])
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_2022_account(lwc, &bob_key, &mint_key)
+            .await
+            .amount,
+        1100
+    );
+
+    let alice_to_eve_ix =
+        create_transfer_checked_instruction(&alice_key, &eve_key, &mint_key, &policy, 100, 0)
+            .unwrap();
+    assert!(
+        process_transaction(lwc, vec![alice_to_eve_ix], vec![&alice])
+            .await
+            .is_err()
+    );
+
+    let remove_bob_ix = create_remove_from_allow_list_instruction(
+        &mint_key,
+        &authority.pubkey(),
+        &bob_key,
+        &authority.pubkey(),
+    )
+    .unwrap();
+    process_transaction(lwc, vec![remove_bob_ix], vec![&authority])
+        .await
+        .unwrap();
+    let alice_to_bob_ix =
+        create_transfer_checked_instruction(&alice_key, &bob_key, &mint_key, &policy, 50, 0)
+            .unwrap();
+    assert!(
+        process_transaction(lwc, vec![alice_to_bob_ix], vec![&alice])
+            .await
+            .is_err()
+    );
+
+    // Only the upstream authority can change the allow list
+    let eve = Keypair::new();
+    transfer(lwc, &context.payer, &eve.pubkey(), sol(1.0))
+        .await
+        .unwrap();
+    let self_allow_ix =
+        create_add_to_allow_list_instruction(&mint_key, &eve.pubkey(), &eve.pubkey(), &eve_key)
+            .unwrap();
+    assert!(process_transaction(lwc, vec![self_allow_ix], vec![&eve])
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_spl_managed_token_2022_policy_program() {
+    let mut program_test = spl_managed_token_test();
+    program_test.add_program(
+        "policy_program",
+        POLICY_PROGRAM_ID,
+        processor!(policy_program_process_instruction),
+    );
+    let mut context = program_test.start_with_context().await;
+    let authority = Keypair::new();
+    let alice = Keypair::new();
+    let alice_key = alice.pubkey();
+    let bob_key = Pubkey::new_unique();
+    let mint_key = setup_token_2022_mint(&mut context, &authority, &[&alice_key, &bob_key]).await;
+    let lwc = &mut context.banks_client;
+    transfer(lwc, &context.payer, &alice_key, sol(1.0))
+        .await
+        .unwrap();
+
+    let policy = TransferPolicyKind::Program(POLICY_PROGRAM_ID);
+    let set_policy_ix = create_set_transfer_policy_instruction(
+        &mint_key,
+        &authority.pubkey(),
+        &authority.pubkey(),
+        policy.clone(),
+    )
+    .unwrap();
+    process_transaction(lwc, vec![set_policy_ix], vec![&authority])
+        .await
+        .unwrap();
+
+    let small_transfer_ix =
+        create_transfer_checked_instruction(&alice_key, &bob_key, &mint_key, &policy, 10, 0)
+            .unwrap();
+    process_transaction(lwc, vec![small_transfer_ix], vec![&alice])
+        .await
+        .unwrap();
+
+    let large_transfer_ix =
+        create_transfer_checked_instruction(&alice_key, &bob_key, &mint_key, &policy, 11, 0)
+            .unwrap();
+    assert!(
+        process_transaction(lwc, vec![large_transfer_ix], vec![&alice])
+            .await
+            .is_err()
+    );
+
+    // Switching to an allow list without entries blocks all transfers
+    let set_policy_ix = create_set_transfer_policy_instruction(
+        &mint_key,
+        &authority.pubkey(),
+        &authority.pubkey(),
+        TransferPolicyKind::AllowList,
+    )
+    .unwrap();
+    process_transaction(lwc, vec![set_policy_ix], vec![&authority])
+        .await
+        .unwrap();
+    let allow_list_transfer_ix = create_transfer_checked_instruction(
+        &alice_key,
+        &bob_key,
+        &mint_key,
+        &TransferPolicyKind::AllowList,
+        1,
+        0,
+    )
+    .unwrap();
+    assert!(
+        process_transaction(lwc, vec![allow_list_transfer_ix], vec![&alice])
+            .await
+            .is_err()
+    );
+    assert_eq!(
+        get_token_2022_account(lwc, &bob_key, &mint_key)
+            .await
+            .amount,
+        1010
+    );
+}
+
+#[tokio::test]
+async fn test_spl_managed_token_2022_rejects_freeze_gated_instructions() {
+    let mut context = spl_managed_token_test().start_with_context().await;
+    let authority = Keypair::new();
+    let alice = Keypair::new();
+    let alice_key = alice.pubkey();
+    let bob_key = Pubkey::new_unique();
+    let mint_key = setup_token_2022_mint(&mut context, &authority, &[&alice_key, &bob_key]).await;
+    let lwc = &mut context.banks_client;
+    transfer(lwc, &context.payer, &alice_key, sol(1.0))
+        .await
+        .unwrap();
+
+    // Token-2022 accounts are already thawed, so thawing and re-freezing them
+    // around the operation is refused
+    let authority_key = authority.pubkey();
+    let ixs = [
+        create_transfer_instruction(&alice_key, &bob_key, &mint_key, &authority_key, 1),
+        create_burn_instruction(&mint_key, &alice_key, &authority_key, 1),
+        create_close_account_instruction(&mint_key, &alice_key, &authority_key),
+        create_approve_instruction(&mint_key, &alice_key, &bob_key, &authority_key, 1),
+        create_revoke_instruction(&mint_key, &alice_key, &authority_key),
+    ];
+    for ix in ixs {
+        let err = process_transaction(lwc, vec![ix.unwrap()], vec![&alice, &authority])
+            .await
+            .unwrap_err()
+            .unwrap();
+        assert_eq!(
+            err,
+            TransactionError::InstructionError(0, InstructionError::IncorrectProgramId)
+        );
+    }
+
+    // The owner burns and approves through Token-2022, without the upstream
+    // authority
+    let alice_account =
+        get_associated_token_address_with_program_id(&alice_key, &mint_key, &spl_token_2022::id());
+    let burn_ix = spl_token_2022::instruction::burn(
+        &spl_token_2022::id(),
+        &alice_account,
+        &mint_key,
+        &alice_key,
+        &[],
+        100,
+    )
+    .unwrap();
+    let approve_ix = spl_token_2022::instruction::approve(
+        &spl_token_2022::id(),
+        &alice_account,
+        &bob_key,
+        &alice_key,
+        &[],
+        100,
+    )
+    .unwrap();
+    process_transaction(lwc, vec![burn_ix, approve_ix], vec![&alice])
+        .await
+        .unwrap();
+    let alice_token_account = get_token_2022_account(lwc, &alice_key, &mint_key).await;
+    assert_eq!(alice_token_account.amount, 900);
+    assert_eq!(alice_token_account.delegate, COption::Some(bob_key));
+    assert_eq!(alice_token_account.state, AccountState::Initialized);
+}
@@ -129,11 +129,12 @@ export async function transferNameOwnership(
     const hashed_name = await getHashedName(name);
     const nameAccountKey = await getNameAccountKey(hashed_name, nameClass, nameParent);
 
+    const nameOwner = (await NameRegistryState.retrieve(connection, nameAccountKey)).owner;
     let curentNameOwner: PublicKey;
     if (nameClass) {
         curentNameOwner = nameClass;
     } else {
-        curentNameOwner = (await NameRegistryState.retrieve(connection, nameAccountKey)).owner;
+        curentNameOwner = nameOwner;
     }
 
     const transferInstr = transferInstruction(
@@ -143,6 +144,7 @@ export async function transferNameOwnership(
         curentNameOwner,
         nameClass,
         nameParent,
+        nameOwner,
     );
 
     return transferInstr;
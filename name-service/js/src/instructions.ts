@@ -1,6 +1,6 @@
 import { PublicKey, TransactionInstruction } from '@solana/web3.js';
 
-import { Numberu32, Numberu64 } from './utils';
+import { getNameExpiryKey, getReverseRecordKey, Numberu32, Numberu64 } from './utils';
 
 export function createInstruction(
     nameProgramId: PublicKey,
@@ -140,6 +140,7 @@ export function transferInstruction(
     currentNameOwnerKey: PublicKey,
     nameClassKey?: PublicKey,
     nameParent?: PublicKey,
+    nameOwnerKey: PublicKey = currentNameOwnerKey,
 ): TransactionInstruction {
     const buffers = [Buffer.from(Uint8Array.from([2])), newOwnerKey.toBuffer()];
 
@@ -164,6 +165,13 @@ export function transferInstruction(
             isSigner: true,
             isWritable: false,
         });
+    } else if (nameParent) {
+        // The parent name record comes after the class slot
+        keys.push({
+            pubkey: PublicKey.default,
+            isSigner: false,
+            isWritable: false,
+        });
     }
 
     if (nameParent) {
@@ -174,6 +182,20 @@ export function transferInstruction(
         });
     }
 
+    // Optional trailing accounts, clearing the reverse record of the name owner
+    keys.push(
+        {
+            pubkey: getReverseRecordKey(nameOwnerKey, nameProgramId),
+            isSigner: false,
+            isWritable: true,
+        },
+        {
+            pubkey: nameOwnerKey,
+            isSigner: false,
+            isWritable: true,
+        },
+    );
+
     return new TransactionInstruction({
         keys,
         programId: nameProgramId,
@@ -206,6 +228,16 @@ export function deleteInstruction(
             isSigner: false,
             isWritable: true,
         },
+        {
+            pubkey: getNameExpiryKey(nameAccountKey, nameProgramId),
+            isSigner: false,
+            isWritable: true,
+        },
+        {
+            pubkey: getReverseRecordKey(nameOwnerKey, nameProgramId),
+            isSigner: false,
+            isWritable: true,
+        },
     ];
 
     return new TransactionInstruction({
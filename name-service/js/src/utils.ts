@@ -119,6 +119,22 @@ export async function getNameAccountKey(
     return nameAccountKey;
 }
 
+export function getNameExpiryKey(nameAccountKey: PublicKey, nameProgramId = NAME_PROGRAM_ID): PublicKey {
+    const [nameExpiryKey] = PublicKey.findProgramAddressSync(
+        [Buffer.from('name_expiry'), nameAccountKey.toBuffer()],
+        nameProgramId,
+    );
+    return nameExpiryKey;
+}
+
+export function getReverseRecordKey(ownerKey: PublicKey, nameProgramId = NAME_PROGRAM_ID): PublicKey {
+    const [reverseRecordKey] = PublicKey.findProgramAddressSync(
+        [Buffer.from('reverse_record'), ownerKey.toBuffer()],
+        nameProgramId,
+    );
+    return reverseRecordKey;
+}
+
 export async function getNameOwner(connection: Connection, nameAccountKey: PublicKey): Promise<NameRegistryState> {
     const nameAccount = await connection.getAccountInfo(nameAccountKey);
     if (!nameAccount) {
@@ -7,7 +7,7 @@ import {
     transferInstruction,
     updateInstruction,
 } from '../../src';
-import { Numberu32, Numberu64 } from '../../src/utils';
+import { getNameExpiryKey, getReverseRecordKey, Numberu32, Numberu64 } from '../../src/utils';
 
 import { describe, expect, test } from '@jest/globals';
 
@@ -87,18 +87,43 @@ describe('SplNameService Instructions', () => {
         const newOwner = Keypair.generate().publicKey;
         const instruction = transferInstruction(nameServiceAddress, nameAccountKey, newOwner, nameOwnerKey);
 
-        expect(instruction.keys).toHaveLength(2);
+        expect(instruction.keys).toHaveLength(4);
         instruction.keys[0].pubkey.equals(nameAccountKey);
         instruction.keys[1].pubkey.equals(nameOwnerKey);
+        instruction.keys[2].pubkey.equals(getReverseRecordKey(nameOwnerKey, nameServiceAddress));
+        instruction.keys[3].pubkey.equals(nameOwnerKey);
+    });
+
+    test('transferInstruction signed by the parent name owner', () => {
+        const newOwner = Keypair.generate().publicKey;
+        const instruction = transferInstruction(
+            nameServiceAddress,
+            nameAccountKey,
+            newOwner,
+            nameParentOwner,
+            undefined,
+            nameParent,
+            nameOwnerKey,
+        );
+
+        expect(instruction.keys).toHaveLength(6);
+        instruction.keys[0].pubkey.equals(nameAccountKey);
+        instruction.keys[1].pubkey.equals(nameParentOwner);
+        instruction.keys[2].pubkey.equals(PublicKey.default);
+        instruction.keys[3].pubkey.equals(nameParent);
+        instruction.keys[4].pubkey.equals(getReverseRecordKey(nameOwnerKey, nameServiceAddress));
+        instruction.keys[5].pubkey.equals(nameOwnerKey);
     });
 
     test('deleteInstruction', () => {
         const instruction = deleteInstruction(nameServiceAddress, nameAccountKey, payerKey, nameOwnerKey);
 
-        expect(instruction.keys).toHaveLength(3);
+        expect(instruction.keys).toHaveLength(5);
         instruction.keys[0].pubkey.equals(nameAccountKey);
         instruction.keys[1].pubkey.equals(nameOwnerKey);
         instruction.keys[2].pubkey.equals(payerKey);
+        instruction.keys[3].pubkey.equals(getNameExpiryKey(nameAccountKey, nameServiceAddress));
+        instruction.keys[4].pubkey.equals(getReverseRecordKey(nameOwnerKey, nameServiceAddress));
     });
 
     test('reallocInstruction', () => {
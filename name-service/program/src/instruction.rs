@@ -1,4 +1,5 @@
 use {
+    crate::state::{get_name_expiry_key, get_registration_config_key, get_reverse_record_key},
     borsh::{BorshDeserialize, BorshSerialize},
     solana_program::{
         instruction::{AccountMeta, Instruction},
@@ -34,6 +35,12 @@ pub enum NameRegistryInstruction {
     ///      to no existing parent.
     ///   6. `[signer]` Owner of the parent name record. Optional but needed if
     ///      parent name different than default.
+    ///   7. `[]` Registration config of the parent name (optional). The name
+    ///      then expires after the configured period. The parent name owner
+    ///      does not need to sign, in which case account #6 must be writeable
+    ///      and is paid the registration price.
+    ///   8. `[writeable]` Name expiry account (program-derived address).
+    ///      Needed with #7
     Create {
         /// SHA256 of the (HASH_PREFIX + Name) of the record to create, hashing
         /// is done off-chain
@@ -79,20 +86,35 @@ pub enum NameRegistryInstruction {
     ///
     ///    * If the signer is the parent name account owner
     ///   0. `[writeable]` Name record to be transferred
-    ///   1. `[signer]` Account owner
+    ///   1. `[signer]` Parent name account owner
     ///   2. `[signer]` Account class
     ///   3. `[]` Parent name record
+    ///
+    /// Followed by two optional trailing accounts, in which case the reverse
+    /// record of the current owner is deleted if it points to the name record,
+    /// with its rent refunded to the current owner:
+    ///   * `[writeable]` Reverse record of the current owner
+    ///   * `[writeable]` Current owner
     Transfer { new_owner: Pubkey },
 
     /// Delete a name record.
     ///
     /// Any lamports remaining in the name record will be transferred to the
-    /// refund account (#2)
+    /// refund account (#2), along with those of the given expiry account and
+    /// of the owner's reverse record if it points to the name record
+    ///
+    /// Once a name is past its expiry and grace period, anyone can delete it
+    /// to register it again, passing its expiry account. The refund account
+    /// must then be the name owner.
     ///
     /// Accounts expected by this instruction:
     ///   0. `[writeable]` Name record to be deleted
-    ///   1. `[signer]` Account owner
+    ///   1. `[signer]` Account owner, or anyone if the name is reclaimable
     ///   2. `[writeable]` Refund account
+    ///   3. `[writeable]` Name expiry account (program-derived address),
+    ///      deleted along with the name record if it exists. Optional, but an
+    ///      omitted expiry account outlives the name record
+    ///   4. `[writeable]` Reverse record of the account owner (optional)
     Delete,
 
     /// Realloc the data of a name record.
@@ -112,6 +134,61 @@ pub enum NameRegistryInstruction {
         /// the new space is less than the current space.
         space: u32,
     },
+
+    /// Create or update the registration config of a parent name, letting
+    /// anyone register its child names for a limited period
+    ///
+    /// Accounts expected by this instruction:
+    ///   0. `[]` System program
+    ///   1. `[writeable, signer]` Funding account
+    ///   2. `[writeable]` Registration config (program-derived address)
+    ///   3. `[]` Parent name record
+    ///   4. `[signer]` Owner of the parent name record
+    SetRegistrationConfig {
+        /// Number of seconds a registration or renewal lasts
+        period: i64,
+        /// Number of seconds after expiry before the name can be reclaimed
+        grace_period: i64,
+        /// Price of a registration or renewal, paid to the parent name owner
+        renewal_lamports: u64,
+    },
+
+    /// Extend the expiry of a name by the period of its parent registration
+    /// config, paying the renewal price to the parent name owner. Anyone can
+    /// renew a name until its grace period is over.
+    ///
+    /// Accounts expected by this instruction:
+    ///   0. `[]` System program
+    ///   1. `[writeable, signer]` Funding account
+    ///   2. `[]` Name record to be renewed
+    ///   3. `[]` Parent name record
+    ///   4. `[writeable]` Owner of the parent name record
+    ///   5. `[]` Registration config of the parent name
+    ///   6. `[writeable]` Name expiry account
+    Renew,
+
+    /// Set the reverse record of a wallet to a name it owns, so that clients
+    /// can resolve the wallet back to the name
+    ///
+    /// Accounts expected by this instruction:
+    ///   0. `[]` System program
+    ///   1. `[writeable, signer]` Funding account, refunded if the record
+    ///      shrinks
+    ///   2. `[writeable]` Reverse record (program-derived address)
+    ///   3. `[signer]` Owner of the name record
+    ///   4. `[]` Name record
+    SetReverseRecord {
+        /// Name whose hash, class and parent derive the name record address
+        name: String,
+    },
+
+    /// Delete the reverse record of a wallet
+    ///
+    /// Accounts expected by this instruction:
+    ///   0. `[writeable]` Reverse record
+    ///   1. `[signer]` Owner of the reverse record
+    ///   2. `[writeable]` Refund account
+    DeleteReverseRecord,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -196,6 +273,7 @@ pub fn transfer(
     if let Some(key) = name_class_opt {
         accounts.push(AccountMeta::new_readonly(key, true));
     }
+    push_reverse_record_accounts(&mut accounts, &name_service_program_id, name_owner_key);
 
     Ok(Instruction {
         program_id: name_service_program_id,
@@ -204,6 +282,46 @@ pub fn transfer(
     })
 }
 
+/// Transfer a name record on behalf of its owner, signed by the owner of the
+/// parent name record
+pub fn transfer_by_parent_owner(
+    name_service_program_id: Pubkey,
+    new_owner: Pubkey,
+    name_account_key: Pubkey,
+    name_owner_key: Pubkey,
+    parent_name_owner_key: Pubkey,
+    name_class: Pubkey,
+    name_parent: Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let instruction_data = NameRegistryInstruction::Transfer { new_owner };
+    let data = borsh::to_vec(&instruction_data).unwrap();
+    let mut accounts = vec![
+        AccountMeta::new(name_account_key, false),
+        AccountMeta::new_readonly(parent_name_owner_key, true),
+        AccountMeta::new_readonly(name_class, name_class != Pubkey::default()),
+        AccountMeta::new_readonly(name_parent, false),
+    ];
+    push_reverse_record_accounts(&mut accounts, &name_service_program_id, name_owner_key);
+
+    Ok(Instruction {
+        program_id: name_service_program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Appends the optional trailing accounts of `Transfer`, clearing the
+/// reverse record of the name owner
+fn push_reverse_record_accounts(
+    accounts: &mut Vec<AccountMeta>,
+    name_service_program_id: &Pubkey,
+    name_owner_key: Pubkey,
+) {
+    let (reverse_record_key, _) = get_reverse_record_key(name_service_program_id, &name_owner_key);
+    accounts.push(AccountMeta::new(reverse_record_key, false));
+    accounts.push(AccountMeta::new(name_owner_key, false));
+}
+
 pub fn delete(
     name_service_program_id: Pubkey,
     name_account_key: Pubkey,
@@ -212,10 +330,14 @@ pub fn delete(
 ) -> Result<Instruction, ProgramError> {
     let instruction_data = NameRegistryInstruction::Delete;
     let data = borsh::to_vec(&instruction_data).unwrap();
+    let (name_expiry_key, _) = get_name_expiry_key(&name_service_program_id, &name_account_key);
+    let (reverse_record_key, _) = get_reverse_record_key(&name_service_program_id, &name_owner_key);
     let accounts = vec![
         AccountMeta::new(name_account_key, false),
         AccountMeta::new_readonly(name_owner_key, true),
         AccountMeta::new(refund_target, false),
+        AccountMeta::new(name_expiry_key, false),
+        AccountMeta::new(reverse_record_key, false),
     ];
 
     Ok(Instruction {
@@ -247,3 +369,162 @@ pub fn realloc(
         data,
     })
 }
+
+/// Create a child name under a parent with a registration config, paying the
+/// registration price to the parent name owner
+#[allow(clippy::too_many_arguments)]
+pub fn register(
+    name_service_program_id: Pubkey,
+    instruction_data: NameRegistryInstruction,
+    name_account_key: Pubkey,
+    payer_key: Pubkey,
+    name_owner: Pubkey,
+    name_class_opt: Option<Pubkey>,
+    name_parent: Pubkey,
+    name_parent_owner: Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let mut instruction = create(
+        name_service_program_id,
+        instruction_data,
+        name_account_key,
+        payer_key,
+        name_owner,
+        name_class_opt,
+        Some(name_parent),
+        None,
+    )?;
+    let (registration_config_key, _) =
+        get_registration_config_key(&name_service_program_id, &name_parent);
+    let (name_expiry_key, _) = get_name_expiry_key(&name_service_program_id, &name_account_key);
+    instruction.accounts.extend([
+        AccountMeta::new(name_parent_owner, false),
+        AccountMeta::new_readonly(registration_config_key, false),
+        AccountMeta::new(name_expiry_key, false),
+    ]);
+    Ok(instruction)
+}
+
+/// Delete a reclaimable name record on behalf of its owner, who is refunded
+/// its rent
+pub fn reclaim(
+    name_service_program_id: Pubkey,
+    name_account_key: Pubkey,
+    reclaimer_key: Pubkey,
+    name_owner_key: Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let mut instruction = delete(
+        name_service_program_id,
+        name_account_key,
+        name_owner_key,
+        name_owner_key,
+    )?;
+    instruction.accounts[1] = AccountMeta::new_readonly(reclaimer_key, true);
+    Ok(instruction)
+}
+
+pub fn set_registration_config(
+    name_service_program_id: Pubkey,
+    payer_key: Pubkey,
+    name_parent: Pubkey,
+    name_parent_owner: Pubkey,
+    period: i64,
+    grace_period: i64,
+    renewal_lamports: u64,
+) -> Result<Instruction, ProgramError> {
+    let instruction_data = NameRegistryInstruction::SetRegistrationConfig {
+        period,
+        grace_period,
+        renewal_lamports,
+    };
+    let data = borsh::to_vec(&instruction_data).unwrap();
+    let (registration_config_key, _) =
+        get_registration_config_key(&name_service_program_id, &name_parent);
+    let accounts = vec![
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(payer_key, true),
+        AccountMeta::new(registration_config_key, false),
+        AccountMeta::new_readonly(name_parent, false),
+        AccountMeta::new_readonly(name_parent_owner, true),
+    ];
+
+    Ok(Instruction {
+        program_id: name_service_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn renew(
+    name_service_program_id: Pubkey,
+    payer_key: Pubkey,
+    name_account_key: Pubkey,
+    name_parent: Pubkey,
+    name_parent_owner: Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let instruction_data = NameRegistryInstruction::Renew;
+    let data = borsh::to_vec(&instruction_data).unwrap();
+    let (registration_config_key, _) =
+        get_registration_config_key(&name_service_program_id, &name_parent);
+    let (name_expiry_key, _) = get_name_expiry_key(&name_service_program_id, &name_account_key);
+    let accounts = vec![
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(payer_key, true),
+        AccountMeta::new_readonly(name_account_key, false),
+        AccountMeta::new_readonly(name_parent, false),
+        AccountMeta::new(name_parent_owner, false),
+        AccountMeta::new_readonly(registration_config_key, false),
+        AccountMeta::new(name_expiry_key, false),
+    ];
+
+    Ok(Instruction {
+        program_id: name_service_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn set_reverse_record(
+    name_service_program_id: Pubkey,
+    payer_key: Pubkey,
+    name_owner_key: Pubkey,
+    name_account_key: Pubkey,
+    name: String,
+) -> Result<Instruction, ProgramError> {
+    let instruction_data = NameRegistryInstruction::SetReverseRecord { name };
+    let data = borsh::to_vec(&instruction_data).unwrap();
+    let (reverse_record_key, _) = get_reverse_record_key(&name_service_program_id, &name_owner_key);
+    let accounts = vec![
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(payer_key, true),
+        AccountMeta::new(reverse_record_key, false),
+        AccountMeta::new_readonly(name_owner_key, true),
+        AccountMeta::new_readonly(name_account_key, false),
+    ];
+
+    Ok(Instruction {
+        program_id: name_service_program_id,
+        accounts,
+        data,
+    })
+}
+
+pub fn delete_reverse_record(
+    name_service_program_id: Pubkey,
+    owner_key: Pubkey,
+    refund_target: Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let instruction_data = NameRegistryInstruction::DeleteReverseRecord;
+    let data = borsh::to_vec(&instruction_data).unwrap();
+    let (reverse_record_key, _) = get_reverse_record_key(&name_service_program_id, &owner_key);
+    let accounts = vec![
+        AccountMeta::new(reverse_record_key, false),
+        AccountMeta::new_readonly(owner_key, true),
+        AccountMeta::new(refund_target, false),
+    ];
+
+    Ok(Instruction {
+        program_id: name_service_program_id,
+        accounts,
+        data,
+    })
+}
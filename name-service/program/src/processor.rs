@@ -1,12 +1,19 @@
 use {
     crate::{
         instruction::NameRegistryInstruction,
-        state::{get_seeds_and_key, write_data, NameRecordHeader},
+        state::{
+            get_name_expiry_key, get_registration_config_key, get_reverse_record_key,
+            get_seeds_and_key, write_data, NameExpiry, NameRecordHeader, RegistrationConfig,
+            ReverseRecord, HASH_PREFIX, NAME_EXPIRY_SEED, REGISTRATION_CONFIG_SEED,
+            REVERSE_RECORD_SEED,
+        },
     },
     borsh::BorshDeserialize,
     solana_program::{
         account_info::{next_account_info, AccountInfo},
+        clock::Clock,
         entrypoint::ProgramResult,
+        hash::hashv,
         msg,
         program::{invoke, invoke_signed},
         program_error::ProgramError,
@@ -38,6 +45,8 @@ impl Processor {
         let name_class = next_account_info(accounts_iter)?;
         let parent_name_account = next_account_info(accounts_iter)?;
         let parent_name_owner = next_account_info(accounts_iter).ok();
+        let registration_config_account = next_account_info(accounts_iter).ok();
+        let name_expiry_account = next_account_info(accounts_iter).ok();
 
         let (name_account_key, seeds) = get_seeds_and_key(
             program_id,
@@ -63,14 +72,21 @@ impl Processor {
             msg!("The given name class is not a signer.");
             return Err(ProgramError::InvalidArgument);
         }
+        let registration_config = registration_config_account
+            .map(|account| {
+                Self::load_registration_config(program_id, parent_name_account.key, account)
+            })
+            .transpose()?;
         if *parent_name_account.key != Pubkey::default() {
-            if !parent_name_owner.unwrap().is_signer {
+            let parent_name_owner = parent_name_owner.ok_or(ProgramError::NotEnoughAccountKeys)?;
+            // Under a registration config, the registration is paid for instead
+            if !parent_name_owner.is_signer && registration_config.is_none() {
                 msg!("The given parent name account owner is not a signer.");
                 return Err(ProgramError::InvalidArgument);
             } else {
                 let parent_name_record_header =
                     NameRecordHeader::unpack_from_slice(&parent_name_account.data.borrow())?;
-                if &parent_name_record_header.owner != parent_name_owner.unwrap().key {
+                if &parent_name_record_header.owner != parent_name_owner.key {
                     msg!("The given parent name account owner is not correct.");
                     return Err(ProgramError::InvalidArgument);
                 }
@@ -119,6 +135,44 @@ impl Processor {
 
         name_state.pack_into_slice(&mut name_account.data.borrow_mut());
 
+        if let Some(registration_config) = registration_config {
+            let parent_name_owner = parent_name_owner.ok_or(ProgramError::NotEnoughAccountKeys)?;
+            let name_expiry_account =
+                name_expiry_account.ok_or(ProgramError::NotEnoughAccountKeys)?;
+            let (name_expiry_key, bump) = get_name_expiry_key(program_id, name_account.key);
+            if name_expiry_key != *name_expiry_account.key {
+                msg!("The given name expiry account is incorrect.");
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            if !parent_name_owner.is_signer {
+                Self::pay(
+                    system_program,
+                    payer_account,
+                    parent_name_owner,
+                    registration_config.renewal_lamports,
+                )?;
+            }
+
+            // Any existing expiry account is reset for the new registration
+            let name_expiry = NameExpiry {
+                name_account: *name_account.key,
+                expires_at: Clock::get()?
+                    .unix_timestamp
+                    .saturating_add(registration_config.period),
+                grace_period: registration_config.grace_period,
+            };
+            Self::create_or_resize_pda_account(
+                program_id,
+                system_program,
+                payer_account,
+                name_expiry_account,
+                &[NAME_EXPIRY_SEED, name_account.key.as_ref(), &[bump]],
+                NameExpiry::LEN,
+            )?;
+            borsh::to_writer(&mut name_expiry_account.data.borrow_mut()[..], &name_expiry)?;
+        }
+
         Ok(())
     }
 
@@ -170,17 +224,38 @@ impl Processor {
         Ok(())
     }
 
-    pub fn process_transfer(accounts: &[AccountInfo], new_owner: Pubkey) -> ProgramResult {
+    pub fn process_transfer(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        new_owner: Pubkey,
+    ) -> ProgramResult {
         let accounts_iter = &mut accounts.iter();
 
         let name_account = next_account_info(accounts_iter)?;
         let name_owner = next_account_info(accounts_iter)?;
-        let name_class_opt = next_account_info(accounts_iter).ok();
-        let parent_name = next_account_info(accounts_iter).ok();
 
         let mut name_record_header =
             NameRecordHeader::unpack_from_slice(&name_account.data.borrow())?;
 
+        // The reverse record of the current owner and the current owner are
+        // optional trailing accounts, told apart from the class and parent
+        // name by their keys
+        let (other_accounts, reverse_record_accounts) = match accounts_iter.as_slice() {
+            [other_accounts @ .., reverse_record_account, current_owner]
+                if *current_owner.key == name_record_header.owner
+                    && *reverse_record_account.key
+                        == get_reverse_record_key(program_id, current_owner.key).0 =>
+            {
+                (
+                    other_accounts,
+                    Some((reverse_record_account, current_owner)),
+                )
+            }
+            other_accounts => (other_accounts, None),
+        };
+        let name_class_opt = other_accounts.first();
+        let parent_name = other_accounts.get(1);
+
         // Verifications
         let is_parent_owner = if let Some(parent_name) = parent_name {
             if name_record_header.parent_name != *parent_name.key {
@@ -208,6 +283,18 @@ impl Processor {
             return Err(ProgramError::InvalidArgument);
         }
 
+        if let Some((reverse_record_account, current_owner)) = reverse_record_accounts {
+            if new_owner != name_record_header.owner {
+                Self::clear_reverse_record(
+                    program_id,
+                    name_account.key,
+                    &name_record_header.owner,
+                    reverse_record_account,
+                    current_owner,
+                )?;
+            }
+        }
+
         name_record_header.owner = new_owner;
         name_record_header
             .pack_into_slice(&mut name_account.data.borrow_mut()[..NameRecordHeader::LEN]);
@@ -215,29 +302,64 @@ impl Processor {
         Ok(())
     }
 
-    pub fn process_delete(accounts: &[AccountInfo]) -> ProgramResult {
+    pub fn process_delete(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
         let accounts_iter = &mut accounts.iter();
 
         let name_account = next_account_info(accounts_iter)?;
         let name_owner = next_account_info(accounts_iter)?;
         let refund_target = next_account_info(accounts_iter)?;
+        let name_expiry_account = next_account_info(accounts_iter).ok();
+        let reverse_record_account = next_account_info(accounts_iter).ok();
 
         let name_record_header = NameRecordHeader::unpack_from_slice(&name_account.data.borrow())?;
+        if let Some(name_expiry_account) = name_expiry_account {
+            let (name_expiry_key, _) = get_name_expiry_key(program_id, name_account.key);
+            if name_expiry_key != *name_expiry_account.key {
+                msg!("The given name expiry account is incorrect.");
+                return Err(ProgramError::InvalidArgument);
+            }
+        }
+        // The expiry account only exists for names created under a
+        // registration config
+        let name_expiry = match name_expiry_account {
+            Some(name_expiry_account) if name_expiry_account.owner == program_id => Some(
+                Self::unpack_name_expiry(name_account.key, name_expiry_account)?,
+            ),
+            _ => None,
+        };
 
         // Verifications
         if !name_owner.is_signer || name_record_header.owner != *name_owner.key {
-            msg!("The given name owner is incorrect or not a signer.");
-            return Err(ProgramError::InvalidArgument);
+            let now = Clock::get()?.unix_timestamp;
+            if !name_expiry
+                .as_ref()
+                .is_some_and(|name_expiry| name_expiry.is_reclaimable(now))
+            {
+                msg!("The given name owner is incorrect or not a signer.");
+                return Err(ProgramError::InvalidArgument);
+            }
+            if *refund_target.key != name_record_header.owner {
+                msg!("The refund account of a reclaimed name must be its owner.");
+                return Err(ProgramError::InvalidArgument);
+            }
         }
 
-        // Overwrite the data with zeroes
-        write_data(name_account, &vec![0; name_account.data_len()], 0);
-
-        // Close the account by transferring the rent sol
-        let source_amount: &mut u64 = &mut name_account.lamports.borrow_mut();
-        let dest_amount: &mut u64 = &mut refund_target.lamports.borrow_mut();
-        *dest_amount = dest_amount.saturating_add(*source_amount);
-        *source_amount = 0;
+        if let Some(reverse_record_account) = reverse_record_account {
+            Self::clear_reverse_record(
+                program_id,
+                name_account.key,
+                &name_record_header.owner,
+                reverse_record_account,
+                refund_target,
+            )?;
+        }
+        Self::close_account(name_account, refund_target);
+        // Always close the expiry along with the name when given, so that it
+        // cannot outlive it and make a name created again at the same address
+        // reclaimable
+        if let (Some(name_expiry_account), Some(_)) = (name_expiry_account, &name_expiry) {
+            Self::close_account(name_expiry_account, refund_target);
+        }
 
         Ok(())
     }
@@ -293,6 +415,357 @@ impl Processor {
         Ok(())
     }
 
+    fn process_set_registration_config(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        period: i64,
+        grace_period: i64,
+        renewal_lamports: u64,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let system_program = next_account_info(accounts_iter)?;
+        let payer_account = next_account_info(accounts_iter)?;
+        let registration_config_account = next_account_info(accounts_iter)?;
+        let parent_name_account = next_account_info(accounts_iter)?;
+        let parent_name_owner = next_account_info(accounts_iter)?;
+
+        let parent_name_record_header =
+            NameRecordHeader::unpack_from_slice(&parent_name_account.data.borrow())?;
+
+        // Verifications
+        if !parent_name_owner.is_signer || parent_name_record_header.owner != *parent_name_owner.key
+        {
+            msg!("The given parent name owner is incorrect or not a signer.");
+            return Err(ProgramError::InvalidArgument);
+        }
+        let (registration_config_key, bump) =
+            get_registration_config_key(program_id, parent_name_account.key);
+        if registration_config_key != *registration_config_account.key {
+            msg!("The given registration config account is incorrect.");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if period <= 0 || grace_period < 0 {
+            msg!("The registration period must be positive and the grace period non-negative.");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let registration_config = RegistrationConfig {
+            parent_name: *parent_name_account.key,
+            period,
+            grace_period,
+            renewal_lamports,
+        };
+        Self::create_or_resize_pda_account(
+            program_id,
+            system_program,
+            payer_account,
+            registration_config_account,
+            &[
+                REGISTRATION_CONFIG_SEED,
+                parent_name_account.key.as_ref(),
+                &[bump],
+            ],
+            RegistrationConfig::LEN,
+        )?;
+        borsh::to_writer(
+            &mut registration_config_account.data.borrow_mut()[..],
+            &registration_config,
+        )?;
+
+        Ok(())
+    }
+
+    fn process_renew(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let system_program = next_account_info(accounts_iter)?;
+        let payer_account = next_account_info(accounts_iter)?;
+        let name_account = next_account_info(accounts_iter)?;
+        let parent_name_account = next_account_info(accounts_iter)?;
+        let parent_name_owner = next_account_info(accounts_iter)?;
+        let registration_config_account = next_account_info(accounts_iter)?;
+        let name_expiry_account = next_account_info(accounts_iter)?;
+
+        let name_record_header = NameRecordHeader::unpack_from_slice(&name_account.data.borrow())?;
+        let parent_name_record_header =
+            NameRecordHeader::unpack_from_slice(&parent_name_account.data.borrow())?;
+        let registration_config = Self::load_registration_config(
+            program_id,
+            parent_name_account.key,
+            registration_config_account,
+        )?;
+        let mut name_expiry =
+            Self::load_name_expiry(program_id, name_account.key, name_expiry_account)?;
+
+        // Verifications
+        if name_record_header.parent_name != *parent_name_account.key {
+            msg!("Invalid parent name account");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if parent_name_record_header.owner != *parent_name_owner.key {
+            msg!("The given parent name account owner is not correct.");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if name_expiry.is_reclaimable(Clock::get()?.unix_timestamp) {
+            msg!("The grace period is over, the name can only be reclaimed.");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        Self::pay(
+            system_program,
+            payer_account,
+            parent_name_owner,
+            registration_config.renewal_lamports,
+        )?;
+
+        name_expiry.expires_at = name_expiry
+            .expires_at
+            .saturating_add(registration_config.period);
+        name_expiry.grace_period = registration_config.grace_period;
+        borsh::to_writer(&mut name_expiry_account.data.borrow_mut()[..], &name_expiry)?;
+
+        Ok(())
+    }
+
+    fn process_set_reverse_record(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        name: String,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let system_program = next_account_info(accounts_iter)?;
+        let payer_account = next_account_info(accounts_iter)?;
+        let reverse_record_account = next_account_info(accounts_iter)?;
+        let name_owner = next_account_info(accounts_iter)?;
+        let name_account = next_account_info(accounts_iter)?;
+
+        let name_record_header = NameRecordHeader::unpack_from_slice(&name_account.data.borrow())?;
+
+        // Verifications
+        if name_account.owner != program_id {
+            msg!("The given name account is not owned by the program.");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if !name_owner.is_signer || name_record_header.owner != *name_owner.key {
+            msg!("The given name owner is incorrect or not a signer.");
+            return Err(ProgramError::InvalidArgument);
+        }
+        let hashed_name = hashv(&[(HASH_PREFIX.to_owned() + &name).as_bytes()])
+            .as_ref()
+            .to_vec();
+        let (name_account_key, _) = get_seeds_and_key(
+            program_id,
+            hashed_name,
+            Some(&name_record_header.class),
+            Some(&name_record_header.parent_name),
+        );
+        if name_account_key != *name_account.key {
+            msg!("The given name does not match the name account.");
+            return Err(ProgramError::InvalidArgument);
+        }
+        let (reverse_record_key, bump) = get_reverse_record_key(program_id, name_owner.key);
+        if reverse_record_key != *reverse_record_account.key {
+            msg!("The given reverse record account is incorrect.");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let reverse_record_len = ReverseRecord::get_packed_len(&name);
+        let reverse_record = ReverseRecord {
+            owner: *name_owner.key,
+            name_account: *name_account.key,
+            name,
+        };
+        Self::create_or_resize_pda_account(
+            program_id,
+            system_program,
+            payer_account,
+            reverse_record_account,
+            &[REVERSE_RECORD_SEED, name_owner.key.as_ref(), &[bump]],
+            reverse_record_len,
+        )?;
+        borsh::to_writer(
+            &mut reverse_record_account.data.borrow_mut()[..],
+            &reverse_record,
+        )?;
+
+        Ok(())
+    }
+
+    fn process_delete_reverse_record(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let reverse_record_account = next_account_info(accounts_iter)?;
+        let owner = next_account_info(accounts_iter)?;
+        let refund_target = next_account_info(accounts_iter)?;
+
+        // Verifications
+        if !owner.is_signer {
+            msg!("The given reverse record owner is not a signer.");
+            return Err(ProgramError::InvalidArgument);
+        }
+        let (reverse_record_key, _) = get_reverse_record_key(program_id, owner.key);
+        if reverse_record_key != *reverse_record_account.key
+            || reverse_record_account.owner != program_id
+        {
+            msg!("The given reverse record account is incorrect.");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        Self::close_account(reverse_record_account, refund_target);
+
+        Ok(())
+    }
+
+    fn load_registration_config(
+        program_id: &Pubkey,
+        parent_name_key: &Pubkey,
+        registration_config_account: &AccountInfo,
+    ) -> Result<RegistrationConfig, ProgramError> {
+        let (registration_config_key, _) = get_registration_config_key(program_id, parent_name_key);
+        if registration_config_key != *registration_config_account.key
+            || registration_config_account.owner != program_id
+        {
+            msg!("The given registration config account is incorrect.");
+            return Err(ProgramError::InvalidArgument);
+        }
+        RegistrationConfig::try_from_slice(&registration_config_account.data.borrow())
+            .map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    fn load_name_expiry(
+        program_id: &Pubkey,
+        name_account_key: &Pubkey,
+        name_expiry_account: &AccountInfo,
+    ) -> Result<NameExpiry, ProgramError> {
+        let (name_expiry_key, _) = get_name_expiry_key(program_id, name_account_key);
+        if name_expiry_key != *name_expiry_account.key || name_expiry_account.owner != program_id {
+            msg!("The given name expiry account is incorrect.");
+            return Err(ProgramError::InvalidArgument);
+        }
+        Self::unpack_name_expiry(name_account_key, name_expiry_account)
+    }
+
+    fn unpack_name_expiry(
+        name_account_key: &Pubkey,
+        name_expiry_account: &AccountInfo,
+    ) -> Result<NameExpiry, ProgramError> {
+        let name_expiry = NameExpiry::try_from_slice(&name_expiry_account.data.borrow())
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        if name_expiry.name_account != *name_account_key {
+            msg!("The given name expiry account does not belong to the name.");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(name_expiry)
+    }
+
+    /// Closes the reverse record of `owner` if it points to the name account,
+    /// as the owner is about to lose the name
+    fn clear_reverse_record(
+        program_id: &Pubkey,
+        name_account_key: &Pubkey,
+        owner: &Pubkey,
+        reverse_record_account: &AccountInfo,
+        refund_target: &AccountInfo,
+    ) -> ProgramResult {
+        let (reverse_record_key, _) = get_reverse_record_key(program_id, owner);
+        if reverse_record_key != *reverse_record_account.key {
+            msg!("The given reverse record account is incorrect.");
+            return Err(ProgramError::InvalidArgument);
+        }
+        if reverse_record_account.owner != program_id {
+            return Ok(());
+        }
+        let points_to_name = ReverseRecord::try_from_slice(&reverse_record_account.data.borrow())
+            .is_ok_and(|reverse_record| reverse_record.name_account == *name_account_key);
+        if points_to_name {
+            Self::close_account(reverse_record_account, refund_target);
+        }
+        Ok(())
+    }
+
+    fn pay<'a>(
+        system_program: &AccountInfo<'a>,
+        payer_account: &AccountInfo<'a>,
+        recipient: &AccountInfo<'a>,
+        lamports: u64,
+    ) -> ProgramResult {
+        if lamports == 0 {
+            return Ok(());
+        }
+        invoke(
+            &system_instruction::transfer(payer_account.key, recipient.key, lamports),
+            &[
+                payer_account.clone(),
+                recipient.clone(),
+                system_program.clone(),
+            ],
+        )
+    }
+
+    /// Creates a rent-exempt program-derived account owned by the program, or
+    /// resizes it and adjusts its rent if it already exists
+    fn create_or_resize_pda_account<'a>(
+        program_id: &Pubkey,
+        system_program: &AccountInfo<'a>,
+        payer_account: &AccountInfo<'a>,
+        account: &AccountInfo<'a>,
+        signer_seeds: &[&[u8]],
+        space: usize,
+    ) -> ProgramResult {
+        let required_lamports = Rent::get()?.minimum_balance(space);
+        if account.owner != program_id {
+            Self::pay(
+                system_program,
+                payer_account,
+                account,
+                required_lamports.saturating_sub(account.lamports()),
+            )?;
+            invoke_signed(
+                &system_instruction::allocate(account.key, space as u64),
+                &[account.clone(), system_program.clone()],
+                &[signer_seeds],
+            )?;
+            invoke_signed(
+                &system_instruction::assign(account.key, program_id),
+                &[account.clone(), system_program.clone()],
+                &[signer_seeds],
+            )?;
+            return Ok(());
+        }
+
+        match account.lamports().cmp(&required_lamports) {
+            Ordering::Less => {
+                // Overflow cannot happen here because we already checked the sizes.
+                #[allow(clippy::arithmetic_side_effects)]
+                let lamports_to_add = required_lamports - account.lamports();
+                Self::pay(system_program, payer_account, account, lamports_to_add)?;
+            }
+            Ordering::Greater => {
+                // Overflow cannot happen here because we already checked the sizes.
+                #[allow(clippy::arithmetic_side_effects)]
+                let lamports_to_remove = account.lamports() - required_lamports;
+                let source_amount: &mut u64 = &mut account.lamports.borrow_mut();
+                let dest_amount: &mut u64 = &mut payer_account.lamports.borrow_mut();
+                *source_amount = source_amount.saturating_sub(lamports_to_remove);
+                *dest_amount = dest_amount.saturating_add(lamports_to_remove);
+            }
+            Ordering::Equal => {}
+        }
+        account.realloc(space, true)
+    }
+
+    fn close_account(account: &AccountInfo, refund_target: &AccountInfo) {
+        // Overwrite the data with zeroes
+        write_data(account, &vec![0; account.data_len()], 0);
+
+        // Close the account by transferring the rent sol
+        let source_amount: &mut u64 = &mut account.lamports.borrow_mut();
+        let dest_amount: &mut u64 = &mut refund_target.lamports.borrow_mut();
+        *dest_amount = dest_amount.saturating_add(*source_amount);
+        *source_amount = 0;
+    }
+
     pub fn process_instruction(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
@@ -318,16 +791,42 @@ impl Processor {
             }
             NameRegistryInstruction::Transfer { new_owner } => {
                 msg!("Instruction: Transfer Ownership");
-                Processor::process_transfer(accounts, new_owner)?;
+                Processor::process_transfer(program_id, accounts, new_owner)?;
             }
             NameRegistryInstruction::Delete => {
                 msg!("Instruction: Delete Name");
-                Processor::process_delete(accounts)?;
+                Processor::process_delete(program_id, accounts)?;
             }
             NameRegistryInstruction::Realloc { space } => {
                 msg!("Instruction: Realloc Name Record");
                 Processor::process_realloc(accounts, space)?;
             }
+            NameRegistryInstruction::SetRegistrationConfig {
+                period,
+                grace_period,
+                renewal_lamports,
+            } => {
+                msg!("Instruction: Set Registration Config");
+                Processor::process_set_registration_config(
+                    program_id,
+                    accounts,
+                    period,
+                    grace_period,
+                    renewal_lamports,
+                )?;
+            }
+            NameRegistryInstruction::Renew => {
+                msg!("Instruction: Renew Name");
+                Processor::process_renew(program_id, accounts)?;
+            }
+            NameRegistryInstruction::SetReverseRecord { name } => {
+                msg!("Instruction: Set Reverse Record");
+                Processor::process_set_reverse_record(program_id, accounts, name)?;
+            }
+            NameRegistryInstruction::DeleteReverseRecord => {
+                msg!("Instruction: Delete Reverse Record");
+                Processor::process_delete_reverse_record(program_id, accounts)?;
+            }
         }
         Ok(())
     }
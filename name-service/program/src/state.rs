@@ -95,3 +95,94 @@ pub fn get_seeds_and_key(
 
     (name_account_key, seeds_vec)
 }
+
+////////////////////////////////////////////////////////////
+
+pub const REGISTRATION_CONFIG_SEED: &[u8] = b"registration_config";
+pub const NAME_EXPIRY_SEED: &[u8] = b"name_expiry";
+pub const REVERSE_RECORD_SEED: &[u8] = b"reverse_record";
+
+/// Registration terms of the child names of a parent name, stored at
+/// `get_registration_config_key(parent_name)`.
+///
+/// With a registration config, anyone can create a child name by paying
+/// `renewal_lamports` to the parent name owner, and the child name expires
+/// after `period` seconds unless it is renewed.
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct RegistrationConfig {
+    pub parent_name: Pubkey,
+
+    // Number of seconds a registration or renewal lasts
+    pub period: i64,
+
+    // Number of seconds after expiry during which the name can still be
+    // renewed, and cannot be reclaimed by anyone else
+    pub grace_period: i64,
+
+    // Price of a registration or renewal, paid to the parent name owner
+    pub renewal_lamports: u64,
+}
+
+impl RegistrationConfig {
+    pub const LEN: usize = 56;
+}
+
+/// Expiry of a child name registered under a `RegistrationConfig`, stored at
+/// `get_name_expiry_key(name_account)`
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct NameExpiry {
+    pub name_account: Pubkey,
+
+    // Unix timestamp at which the registration expires
+    pub expires_at: i64,
+
+    // Grace period of the parent registration config at the last registration
+    // or renewal
+    pub grace_period: i64,
+}
+
+impl NameExpiry {
+    pub const LEN: usize = 48;
+
+    /// Once the grace period is over, anyone can delete the name and register
+    /// it again
+    pub fn is_reclaimable(&self, unix_timestamp: i64) -> bool {
+        unix_timestamp >= self.expires_at.saturating_add(self.grace_period)
+    }
+}
+
+/// Primary name of a wallet, stored at `get_reverse_record_key(owner)` and set
+/// by the owner of the name.
+///
+/// The record is deleted when `name_account` is transferred or deleted, so it
+/// always points to a name held by `owner`.
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct ReverseRecord {
+    pub owner: Pubkey,
+
+    pub name_account: Pubkey,
+
+    // Name whose hash derives `name_account`, without `HASH_PREFIX`
+    pub name: String,
+}
+
+impl ReverseRecord {
+    pub fn get_packed_len(name: &str) -> usize {
+        32 + 32 + 4 + name.len()
+    }
+}
+
+pub fn get_registration_config_key(program_id: &Pubkey, parent_name: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[REGISTRATION_CONFIG_SEED, parent_name.as_ref()],
+        program_id,
+    )
+}
+
+pub fn get_name_expiry_key(program_id: &Pubkey, name_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[NAME_EXPIRY_SEED, name_account.as_ref()], program_id)
+}
+
+pub fn get_reverse_record_key(program_id: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REVERSE_RECORD_SEED, owner.as_ref()], program_id)
+}
@@ -1,6 +1,13 @@
 #![cfg(feature = "test-sbf")]
 use {
-    solana_program::{hash::hashv, instruction::Instruction, program_pack::Pack, pubkey::Pubkey},
+    borsh::BorshDeserialize,
+    solana_program::{
+        clock::Clock,
+        hash::hashv,
+        instruction::{AccountMeta, Instruction},
+        program_pack::Pack,
+        pubkey::Pubkey,
+    },
     solana_program_test::{
         processor, tokio, ProgramTest, ProgramTestBanksClientExt, ProgramTestContext,
     },
@@ -10,9 +17,16 @@ use {
         transport::TransportError,
     },
     spl_name_service::{
-        instruction::{create, delete, realloc, transfer, update, NameRegistryInstruction},
+        instruction::{
+            create, delete, delete_reverse_record, realloc, reclaim, register, renew,
+            set_registration_config, set_reverse_record, transfer, transfer_by_parent_owner,
+            update, NameRegistryInstruction,
+        },
         processor::Processor,
-        state::{get_seeds_and_key, NameRecordHeader, HASH_PREFIX},
+        state::{
+            get_name_expiry_key, get_reverse_record_key, get_seeds_and_key, NameExpiry,
+            NameRecordHeader, ReverseRecord, HASH_PREFIX,
+        },
     },
     std::str::FromStr,
 };
@@ -241,6 +255,566 @@ async fn test_name_service() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn test_name_registration() {
+    let program_id = Pubkey::from_str("XCWuBvfNamesXCWuBvfkegQfZyiNwAJb9Ss623VQ5DA").unwrap();
+
+    let program_test = ProgramTest::new(
+        "spl_name_service",
+        program_id,
+        processor!(Processor::process_instruction),
+    );
+
+    let mut ctx = program_test.start_with_context().await;
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = 100usize;
+    let name_lamports = rent.minimum_balance(space.saturating_add(NameRecordHeader::LEN));
+
+    // Create the parent name, owned by the registrar
+    let registrar = Keypair::new();
+    let hashed_root_name: Vec<u8> = hashv(&[(HASH_PREFIX.to_owned() + ".sol").as_bytes()])
+        .as_ref()
+        .to_vec();
+    let (root_name_account_key, _) =
+        get_seeds_and_key(&program_id, hashed_root_name.clone(), None, None);
+    let create_root_instruction = create(
+        program_id,
+        NameRegistryInstruction::Create {
+            hashed_name: hashed_root_name,
+            lamports: name_lamports,
+            space: space as u32,
+        },
+        root_name_account_key,
+        ctx.payer.pubkey(),
+        registrar.pubkey(),
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+    sign_send_instruction(&mut ctx, create_root_instruction, vec![])
+        .await
+        .unwrap();
+
+    // Open registrations under it
+    let period = 1_000i64;
+    let grace_period = 100i64;
+    let renewal_lamports = 1_000_000u64;
+    let config_instruction = set_registration_config(
+        program_id,
+        ctx.payer.pubkey(),
+        root_name_account_key,
+        registrar.pubkey(),
+        period,
+        grace_period,
+        renewal_lamports,
+    )
+    .unwrap();
+    sign_send_instruction(&mut ctx, config_instruction, vec![&registrar])
+        .await
+        .unwrap();
+
+    // Anyone can register a subdomain by paying the registrar
+    let name = "bonfida";
+    let owner = Keypair::new();
+    let hashed_name: Vec<u8> = hashv(&[(HASH_PREFIX.to_owned() + name).as_bytes()])
+        .as_ref()
+        .to_vec();
+    let (name_account_key, _) = get_seeds_and_key(
+        &program_id,
+        hashed_name.clone(),
+        None,
+        Some(&root_name_account_key),
+    );
+    let payer = ctx.payer.pubkey();
+    let register_instruction = |owner: Pubkey| {
+        register(
+            program_id,
+            NameRegistryInstruction::Create {
+                hashed_name: hashed_name.clone(),
+                lamports: name_lamports,
+                space: space as u32,
+            },
+            name_account_key,
+            payer,
+            owner,
+            None,
+            root_name_account_key,
+            registrar.pubkey(),
+        )
+        .unwrap()
+    };
+    let instruction = register_instruction(owner.pubkey());
+    sign_send_instruction(&mut ctx, instruction, vec![])
+        .await
+        .unwrap();
+
+    let registrar_balance = ctx
+        .banks_client
+        .get_balance(registrar.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(registrar_balance, renewal_lamports);
+
+    let (name_expiry_key, _) = get_name_expiry_key(&program_id, &name_account_key);
+    let get_name_expiry = |data: Vec<u8>| NameExpiry::try_from_slice(&data).unwrap();
+    let now = ctx
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp;
+    let name_expiry = get_name_expiry(
+        ctx.banks_client
+            .get_account(name_expiry_key)
+            .await
+            .unwrap()
+            .unwrap()
+            .data,
+    );
+    assert_eq!(name_expiry.name_account, name_account_key);
+    assert_eq!(name_expiry.expires_at, now + period);
+    assert_eq!(name_expiry.grace_period, grace_period);
+
+    // Point the owner's reverse record at the name
+    let reverse_instruction = set_reverse_record(
+        program_id,
+        ctx.payer.pubkey(),
+        owner.pubkey(),
+        name_account_key,
+        name.to_string(),
+    )
+    .unwrap();
+    sign_send_instruction(&mut ctx, reverse_instruction, vec![&owner])
+        .await
+        .unwrap();
+    let (reverse_record_key, _) = get_reverse_record_key(&program_id, &owner.pubkey());
+    let reverse_record = ReverseRecord::try_from_slice(
+        &ctx.banks_client
+            .get_account(reverse_record_key)
+            .await
+            .unwrap()
+            .unwrap()
+            .data,
+    )
+    .unwrap();
+    assert_eq!(reverse_record.name_account, name_account_key);
+    assert_eq!(reverse_record.name, name);
+
+    // A reverse record must match the name it points to
+    let bad_reverse_instruction = set_reverse_record(
+        program_id,
+        ctx.payer.pubkey(),
+        owner.pubkey(),
+        name_account_key,
+        "solana".to_string(),
+    )
+    .unwrap();
+    sign_send_instruction(&mut ctx, bad_reverse_instruction, vec![&owner])
+        .await
+        .unwrap_err();
+
+    // Renew before expiry
+    let renew_instruction = renew(
+        program_id,
+        ctx.payer.pubkey(),
+        name_account_key,
+        root_name_account_key,
+        registrar.pubkey(),
+    )
+    .unwrap();
+    sign_send_instruction(&mut ctx, renew_instruction.clone(), vec![])
+        .await
+        .unwrap();
+    let renewed_expiry = get_name_expiry(
+        ctx.banks_client
+            .get_account(name_expiry_key)
+            .await
+            .unwrap()
+            .unwrap()
+            .data,
+    );
+    assert_eq!(renewed_expiry.expires_at, name_expiry.expires_at + period);
+
+    // Nobody else can delete the name before the grace period is over
+    let reclaimer = Keypair::new();
+    let reclaim_instruction = reclaim(
+        program_id,
+        name_account_key,
+        reclaimer.pubkey(),
+        owner.pubkey(),
+    )
+    .unwrap();
+    sign_send_instruction(&mut ctx, reclaim_instruction.clone(), vec![&reclaimer])
+        .await
+        .unwrap_err();
+
+    // Past expiry and grace, renewal fails and anyone can reclaim the name
+    let mut clock = ctx.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = renewed_expiry.expires_at + grace_period;
+    ctx.set_sysvar(&clock);
+    ctx.last_blockhash = ctx
+        .banks_client
+        .get_new_latest_blockhash(&ctx.last_blockhash)
+        .await
+        .unwrap();
+
+    sign_send_instruction(&mut ctx, renew_instruction, vec![])
+        .await
+        .unwrap_err();
+
+    // The rent of a reclaimed name goes back to its owner
+    let mut wrong_refund_instruction = reclaim_instruction.clone();
+    wrong_refund_instruction.accounts[2] = AccountMeta::new(reclaimer.pubkey(), false);
+    sign_send_instruction(&mut ctx, wrong_refund_instruction, vec![&reclaimer])
+        .await
+        .unwrap_err();
+
+    sign_send_instruction(&mut ctx, reclaim_instruction, vec![&reclaimer])
+        .await
+        .unwrap();
+    assert!(ctx
+        .banks_client
+        .get_account(name_expiry_key)
+        .await
+        .unwrap()
+        .is_none());
+
+    // The name can then be registered again
+    let instruction = register_instruction(reclaimer.pubkey());
+    sign_send_instruction(&mut ctx, instruction, vec![])
+        .await
+        .unwrap();
+    let name_record_header = NameRecordHeader::unpack_from_slice(
+        &ctx.banks_client
+            .get_account(name_account_key)
+            .await
+            .unwrap()
+            .unwrap()
+            .data,
+    )
+    .unwrap();
+    assert_eq!(name_record_header.owner, reclaimer.pubkey());
+
+    // The previous owner's reverse record went away with the name
+    assert!(ctx
+        .banks_client
+        .get_account(reverse_record_key)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_name_deletion() {
+    let program_id = Pubkey::from_str("XCWuBvfNamesXCWuBvfkegQfZyiNwAJb9Ss623VQ5DA").unwrap();
+
+    let program_test = ProgramTest::new(
+        "spl_name_service",
+        program_id,
+        processor!(Processor::process_instruction),
+    );
+
+    let mut ctx = program_test.start_with_context().await;
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let space = 100usize;
+    let name_lamports = rent.minimum_balance(space.saturating_add(NameRecordHeader::LEN));
+
+    // Open registrations under a parent name
+    let registrar = Keypair::new();
+    let hashed_root_name: Vec<u8> = hashv(&[(HASH_PREFIX.to_owned() + ".sol").as_bytes()])
+        .as_ref()
+        .to_vec();
+    let (root_name_account_key, _) =
+        get_seeds_and_key(&program_id, hashed_root_name.clone(), None, None);
+    let create_root_instruction = create(
+        program_id,
+        NameRegistryInstruction::Create {
+            hashed_name: hashed_root_name,
+            lamports: name_lamports,
+            space: space as u32,
+        },
+        root_name_account_key,
+        ctx.payer.pubkey(),
+        registrar.pubkey(),
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+    sign_send_instruction(&mut ctx, create_root_instruction, vec![])
+        .await
+        .unwrap();
+    let period = 1_000i64;
+    let grace_period = 100i64;
+    let config_instruction = set_registration_config(
+        program_id,
+        ctx.payer.pubkey(),
+        root_name_account_key,
+        registrar.pubkey(),
+        period,
+        grace_period,
+        0,
+    )
+    .unwrap();
+    sign_send_instruction(&mut ctx, config_instruction, vec![&registrar])
+        .await
+        .unwrap();
+
+    let name = "bonfida";
+    let owner = Keypair::new();
+    let hashed_name: Vec<u8> = hashv(&[(HASH_PREFIX.to_owned() + name).as_bytes()])
+        .as_ref()
+        .to_vec();
+    let (name_account_key, _) = get_seeds_and_key(
+        &program_id,
+        hashed_name.clone(),
+        None,
+        Some(&root_name_account_key),
+    );
+    let register_instruction = register(
+        program_id,
+        NameRegistryInstruction::Create {
+            hashed_name: hashed_name.clone(),
+            lamports: name_lamports,
+            space: space as u32,
+        },
+        name_account_key,
+        ctx.payer.pubkey(),
+        owner.pubkey(),
+        None,
+        root_name_account_key,
+        registrar.pubkey(),
+    )
+    .unwrap();
+    sign_send_instruction(&mut ctx, register_instruction, vec![])
+        .await
+        .unwrap();
+    let (name_expiry_key, _) = get_name_expiry_key(&program_id, &name_account_key);
+    let expires_at = NameExpiry::try_from_slice(
+        &ctx.banks_client
+            .get_account(name_expiry_key)
+            .await
+            .unwrap()
+            .unwrap()
+            .data,
+    )
+    .unwrap()
+    .expires_at;
+
+    // Transferring the name deletes the reverse record pointing to it
+    let payer = ctx.payer.pubkey();
+    let set_reverse_instruction = |owner: &Keypair| {
+        set_reverse_record(
+            program_id,
+            payer,
+            owner.pubkey(),
+            name_account_key,
+            name.to_string(),
+        )
+        .unwrap()
+    };
+    let instruction = set_reverse_instruction(&owner);
+    sign_send_instruction(&mut ctx, instruction, vec![&owner])
+        .await
+        .unwrap();
+    let new_owner = Keypair::new();
+    let transfer_instruction = transfer(
+        program_id,
+        new_owner.pubkey(),
+        name_account_key,
+        owner.pubkey(),
+        None,
+    )
+    .unwrap();
+    sign_send_instruction(&mut ctx, transfer_instruction, vec![&owner])
+        .await
+        .unwrap();
+    let (reverse_record_key, _) = get_reverse_record_key(&program_id, &owner.pubkey());
+    assert!(ctx
+        .banks_client
+        .get_account(reverse_record_key)
+        .await
+        .unwrap()
+        .is_none());
+
+    // The new owner can set and delete their own reverse record
+    let instruction = set_reverse_instruction(&new_owner);
+    sign_send_instruction(&mut ctx, instruction, vec![&new_owner])
+        .await
+        .unwrap();
+    let (new_reverse_record_key, _) = get_reverse_record_key(&program_id, &new_owner.pubkey());
+    let delete_reverse_instruction =
+        delete_reverse_record(program_id, new_owner.pubkey(), payer).unwrap();
+    sign_send_instruction(&mut ctx, delete_reverse_instruction, vec![&new_owner])
+        .await
+        .unwrap();
+    assert!(ctx
+        .banks_client
+        .get_account(new_reverse_record_key)
+        .await
+        .unwrap()
+        .is_none());
+
+    // The parent owner can transfer the name as well, which deletes the
+    // reverse record of its current owner
+    ctx.last_blockhash = ctx
+        .banks_client
+        .get_new_latest_blockhash(&ctx.last_blockhash)
+        .await
+        .unwrap();
+    let instruction = set_reverse_instruction(&new_owner);
+    sign_send_instruction(&mut ctx, instruction, vec![&new_owner])
+        .await
+        .unwrap();
+    let transfer_instruction = transfer_by_parent_owner(
+        program_id,
+        owner.pubkey(),
+        name_account_key,
+        new_owner.pubkey(),
+        registrar.pubkey(),
+        Pubkey::default(),
+        root_name_account_key,
+    )
+    .unwrap();
+    sign_send_instruction(&mut ctx, transfer_instruction, vec![&registrar])
+        .await
+        .unwrap();
+    assert!(ctx
+        .banks_client
+        .get_account(new_reverse_record_key)
+        .await
+        .unwrap()
+        .is_none());
+
+    // Clients that don't pass the reverse record accounts can still transfer
+    let mut legacy_transfer_instruction = transfer(
+        program_id,
+        new_owner.pubkey(),
+        name_account_key,
+        owner.pubkey(),
+        None,
+    )
+    .unwrap();
+    legacy_transfer_instruction.accounts.truncate(2);
+    sign_send_instruction(&mut ctx, legacy_transfer_instruction, vec![&owner])
+        .await
+        .unwrap();
+    let name_record_header = NameRecordHeader::unpack_from_slice(
+        &ctx.banks_client
+            .get_account(name_account_key)
+            .await
+            .unwrap()
+            .unwrap()
+            .data,
+    )
+    .unwrap();
+    assert_eq!(name_record_header.owner, new_owner.pubkey());
+
+    // The reverse record of another owner cannot be passed instead
+    ctx.last_blockhash = ctx
+        .banks_client
+        .get_new_latest_blockhash(&ctx.last_blockhash)
+        .await
+        .unwrap();
+    let instruction = set_reverse_instruction(&new_owner);
+    sign_send_instruction(&mut ctx, instruction, vec![&new_owner])
+        .await
+        .unwrap();
+    let mut wrong_reverse_instruction = delete(
+        program_id,
+        name_account_key,
+        new_owner.pubkey(),
+        owner.pubkey(),
+    )
+    .unwrap();
+    wrong_reverse_instruction.accounts[4] = AccountMeta::new(reverse_record_key, false);
+    sign_send_instruction(&mut ctx, wrong_reverse_instruction, vec![&new_owner])
+        .await
+        .unwrap_err();
+
+    // Deleting the name deletes its expiry and reverse record
+    let delete_instruction = delete(
+        program_id,
+        name_account_key,
+        new_owner.pubkey(),
+        new_owner.pubkey(),
+    )
+    .unwrap();
+    sign_send_instruction(&mut ctx, delete_instruction, vec![&new_owner])
+        .await
+        .unwrap();
+    for key in [name_account_key, name_expiry_key, new_reverse_record_key] {
+        assert!(ctx.banks_client.get_account(key).await.unwrap().is_none());
+    }
+
+    // The parent owner creates the name again, without an expiry
+    let create_instruction = create(
+        program_id,
+        NameRegistryInstruction::Create {
+            hashed_name,
+            lamports: name_lamports,
+            space: space as u32,
+        },
+        name_account_key,
+        ctx.payer.pubkey(),
+        owner.pubkey(),
+        None,
+        Some(root_name_account_key),
+        Some(registrar.pubkey()),
+    )
+    .unwrap();
+    sign_send_instruction(&mut ctx, create_instruction, vec![&registrar])
+        .await
+        .unwrap();
+
+    // It cannot be reclaimed once the previous registration has expired
+    let mut clock = ctx.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = expires_at + grace_period;
+    ctx.set_sysvar(&clock);
+    ctx.last_blockhash = ctx
+        .banks_client
+        .get_new_latest_blockhash(&ctx.last_blockhash)
+        .await
+        .unwrap();
+    let reclaimer = Keypair::new();
+    let reclaim_instruction = reclaim(
+        program_id,
+        name_account_key,
+        reclaimer.pubkey(),
+        owner.pubkey(),
+    )
+    .unwrap();
+    sign_send_instruction(&mut ctx, reclaim_instruction, vec![&reclaimer])
+        .await
+        .unwrap_err();
+    let name_record_header = NameRecordHeader::unpack_from_slice(
+        &ctx.banks_client
+            .get_account(name_account_key)
+            .await
+            .unwrap()
+            .unwrap()
+            .data,
+    )
+    .unwrap();
+    assert_eq!(name_record_header.owner, owner.pubkey());
+
+    // Clients that don't pass the expiry and reverse record accounts can still
+    // delete a name
+    let mut legacy_delete_instruction =
+        delete(program_id, name_account_key, owner.pubkey(), owner.pubkey()).unwrap();
+    legacy_delete_instruction.accounts.truncate(3);
+    sign_send_instruction(&mut ctx, legacy_delete_instruction, vec![&owner])
+        .await
+        .unwrap();
+    assert!(ctx
+        .banks_client
+        .get_account(name_account_key)
+        .await
+        .unwrap()
+        .is_none());
+}
+
 // Utils
 pub async fn sign_send_instruction(
     ctx: &mut ProgramTestContext,
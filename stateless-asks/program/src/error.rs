@@ -18,6 +18,12 @@ pub enum UtilError {
     NotRentExempt,
     #[error("NumericalOverflow")]
     NumericalOverflow,
+    #[error("OfferExpired")]
+    OfferExpired,
+    #[error("InvalidFillSize")]
+    InvalidFillSize,
+    #[error("IncorrectTokenProgram")]
+    IncorrectTokenProgram,
 }
 
 impl From<UtilError> for ProgramError {
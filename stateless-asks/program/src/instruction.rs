@@ -4,6 +4,7 @@ use {
     borsh::{BorshDeserialize, BorshSchema, BorshSerialize},
     solana_program::{
         instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
         pubkey::Pubkey,
         system_program,
     },
@@ -26,6 +27,34 @@ pub enum StatelessOfferInstruction {
     ///
     ///  Bob initializes tkr_src_account (Token B) and tkr_dst_account (Token A)
     /// if they don't exist  Bob (or anyone) executes AcceptOffer
+    ///
+    ///  Bob may take only `fill_size` of Alice's tokens, paying the same share
+    /// of taker_size rounded up. Each fill consumes the delegated allowance, so
+    /// the offer is exhausted once the allowance is. An `expiry` unix
+    /// timestamp other than 0 is also part of the PDA seeds, after which the
+    /// offer can no longer be accepted.
+    ///
+    ///  Either mint may belong to Token-2022. Transfer fees are withheld from
+    /// the amounts received, and the accounts required by transfer hooks are
+    /// appended after the creator accounts.
+    ///
+    ///   0. `[]` Maker wallet
+    ///   1. `[signer]` Taker wallet
+    ///   2. `[writable]` Maker source token account
+    ///   3. `[writable]` Maker destination token account
+    ///   4. `[writable]` Taker source token account
+    ///   5. `[writable]` Taker destination token account
+    ///   6. `[]` Maker mint
+    ///   7. `[]` Taker mint
+    ///   8. `[]` Transfer authority PDA
+    ///   9. `[]` Token program of the maker mint
+    ///   10. `[]` Optional token program of the taker mint, if it differs
+    ///       from the maker one
+    ///   11. `[]` System program, if the taker pays in SOL
+    ///   12. `[]` Metadata, if `has_metadata`
+    ///   13. `[writable]` Creator wallets, each followed by its token account
+    ///       unless paid in SOL
+    ///   14. `[]` Accounts required by transfer hooks
     AcceptOffer {
         #[allow(dead_code)]
         has_metadata: bool,
@@ -35,9 +64,48 @@ pub enum StatelessOfferInstruction {
         taker_size: u64,
         #[allow(dead_code)]
         bump_seed: u8,
+        #[allow(dead_code)]
+        fill_size: u64,
+        #[allow(dead_code)]
+        expiry: i64,
+    },
+}
+
+/// `AcceptOffer` data sent before partial fills and expiries existed
+#[derive(BorshDeserialize)]
+enum LegacyStatelessOfferInstruction {
+    AcceptOffer {
+        has_metadata: bool,
+        maker_size: u64,
+        taker_size: u64,
+        bump_seed: u8,
     },
 }
 
+impl StatelessOfferInstruction {
+    /// Unpacks the instruction data. Legacy `AcceptOffer` data, without
+    /// `fill_size` and `expiry`, fills the whole offer, which never expires.
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        if let Ok(instruction) = Self::try_from_slice(input) {
+            return Ok(instruction);
+        }
+        let LegacyStatelessOfferInstruction::AcceptOffer {
+            has_metadata,
+            maker_size,
+            taker_size,
+            bump_seed,
+        } = LegacyStatelessOfferInstruction::try_from_slice(input)?;
+        Ok(Self::AcceptOffer {
+            has_metadata,
+            maker_size,
+            taker_size,
+            bump_seed,
+            fill_size: maker_size,
+            expiry: 0,
+        })
+    }
+}
+
 /// Creates an 'initialize' instruction.
 #[allow(clippy::too_many_arguments)]
 pub fn accept_offer(
@@ -52,16 +120,21 @@ pub fn accept_offer(
     taker_mint: &Pubkey,
     authority: &Pubkey,
     token_program_id: &Pubkey,
+    taker_token_program_id: &Pubkey,
     is_native: bool,
     maker_size: u64,
     taker_size: u64,
     bump_seed: u8,
+    fill_size: u64,
+    expiry: i64,
 ) -> Instruction {
     let init_data = StatelessOfferInstruction::AcceptOffer {
         has_metadata: false,
         maker_size,
         taker_size,
         bump_seed,
+        fill_size,
+        expiry,
     };
     let data = borsh::to_vec(&init_data).unwrap();
     let mut accounts = vec![
@@ -76,6 +149,9 @@ pub fn accept_offer(
         AccountMeta::new_readonly(*authority, false),
         AccountMeta::new_readonly(*token_program_id, false),
     ];
+    if taker_token_program_id != token_program_id {
+        accounts.push(AccountMeta::new_readonly(*taker_token_program_id, false));
+    }
     if is_native {
         accounts.push(AccountMeta::new_readonly(system_program::id(), false));
     }
@@ -100,18 +176,23 @@ pub fn accept_offer_with_metadata(
     taker_mint: &Pubkey,
     authority: &Pubkey,
     token_program_id: &Pubkey,
+    taker_token_program_id: &Pubkey,
     metadata: &Pubkey,
     creators: &[&Pubkey],
     is_native: bool,
     maker_size: u64,
     taker_size: u64,
     bump_seed: u8,
+    fill_size: u64,
+    expiry: i64,
 ) -> Instruction {
     let init_data = StatelessOfferInstruction::AcceptOffer {
         has_metadata: true,
         maker_size,
         taker_size,
         bump_seed,
+        fill_size,
+        expiry,
     };
     let data = borsh::to_vec(&init_data).unwrap();
     let mut accounts = vec![
@@ -126,6 +207,9 @@ pub fn accept_offer_with_metadata(
         AccountMeta::new_readonly(*authority, false),
         AccountMeta::new_readonly(*token_program_id, false),
     ];
+    if taker_token_program_id != token_program_id {
+        accounts.push(AccountMeta::new_readonly(*taker_token_program_id, false));
+    }
     if is_native {
         accounts.push(AccountMeta::new_readonly(system_program::id(), false));
     }
@@ -9,3 +9,35 @@ mod entrypoint;
 // Export current sdk types for downstream users building with a different sdk
 // version
 pub use solana_program;
+use solana_program::pubkey::Pubkey;
+
+/// Derives the transfer authority the maker delegates their tokens to.
+///
+/// Every term of the offer is part of the seeds, so the taker cannot accept
+/// it on any other terms. An `expiry` of 0 means the offer never expires and
+/// is left out of the seeds.
+pub fn find_offer_authority(
+    program_id: &Pubkey,
+    maker_wallet: &Pubkey,
+    maker_mint: &Pubkey,
+    taker_mint: &Pubkey,
+    maker_size: u64,
+    taker_size: u64,
+    expiry: i64,
+) -> (Pubkey, u8) {
+    let maker_size_bytes = maker_size.to_le_bytes();
+    let taker_size_bytes = taker_size.to_le_bytes();
+    let expiry_bytes = expiry.to_le_bytes();
+    let mut seeds: Vec<&[u8]> = vec![
+        b"stateless_offer",
+        maker_wallet.as_ref(),
+        maker_mint.as_ref(),
+        taker_mint.as_ref(),
+        &maker_size_bytes,
+        &taker_size_bytes,
+    ];
+    if expiry != 0 {
+        seeds.push(&expiry_bytes);
+    }
+    Pubkey::find_program_address(&seeds, program_id)
+}
@@ -4,75 +4,42 @@ use {
     crate::{
         error::UtilError,
         instruction::StatelessOfferInstruction,
-        validation_utils::{assert_is_ata, assert_keys_equal},
+        validation_utils::{
+            assert_is_ata, assert_keys_equal, assert_token_program, unpack_mint,
+            unpack_token_account,
+        },
     },
-    borsh::BorshDeserialize,
     solana_program::{
         account_info::{next_account_info, AccountInfo},
         borsh1::try_from_slice_unchecked,
+        clock::Clock,
         entrypoint::ProgramResult,
         msg,
-        program::{invoke, invoke_signed},
+        program::invoke,
         program_error::ProgramError,
         program_option::COption,
-        program_pack::Pack,
         pubkey::Pubkey,
         system_instruction, system_program,
+        sysvar::Sysvar,
     },
+    spl_token_2022::onchain::invoke_transfer_checked,
     std::slice::Iter,
 };
 
-pub(crate) mod inline_mpl_token_metadata {
-    use {borsh::BorshDeserialize, solana_program::pubkey::Pubkey};
-    solana_program::declare_id!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
-
-    #[derive(Clone, BorshDeserialize, Debug, PartialEq, Eq)]
-    pub(crate) struct Metadata {
-        /// Account discriminator.
-        pub key: u8,
-        /// Address of the update authority.
-        pub update_authority: Pubkey,
-        /// Address of the mint.
-        pub mint: Pubkey,
-        /// Asset data.
-        pub data: Data,
-    }
-
-    #[derive(BorshDeserialize, Default, PartialEq, Eq, Debug, Clone)]
-    pub(crate) struct Data {
-        /// The name of the asset
-        pub name: String,
-        /// The symbol for the asset
-        pub symbol: String,
-        /// URI pointing to JSON representing the asset
-        pub uri: String,
-        /// Royalty basis points that goes to creators in secondary sales
-        /// (0-10000)
-        pub seller_fee_basis_points: u16,
-        /// Array of creators, optional
-        pub creators: Option<Vec<Creator>>,
-    }
-
-    #[derive(BorshDeserialize, PartialEq, Debug, Clone, Eq, Hash)]
-    pub(crate) struct Creator {
-        pub address: Pubkey,
-        pub verified: bool,
-        pub share: u8,
-    }
-}
-
 /// Program state handler.
 pub struct Processor {}
 impl Processor {
     /// Processes [Instruction](enum.Instruction.html).
     pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
-        let instruction = StatelessOfferInstruction::try_from_slice(input)?;
+        let instruction = StatelessOfferInstruction::unpack(input)?;
         match instruction {
             StatelessOfferInstruction::AcceptOffer {
                 has_metadata,
                 maker_size,
                 taker_size,
                 bump_seed,
+                fill_size,
+                expiry,
             } => {
                 msg!("Instruction: accept offer");
                 process_accept_offer(
@@ -82,12 +49,31 @@ impl Processor {
                     maker_size,
                     taker_size,
                     bump_seed,
+                    fill_size,
+                    expiry,
                 )
             }
         }
     }
 }
 
+/// Amount of taker tokens owed for `fill_size` of the maker's tokens, rounded
+/// up in favour of the maker
+fn taker_fill_size(maker_size: u64, taker_size: u64, fill_size: u64) -> Result<u64, ProgramError> {
+    if fill_size == 0 || fill_size > maker_size {
+        return Err(UtilError::InvalidFillSize.into());
+    }
+    let numerator = (taker_size as u128)
+        .checked_mul(fill_size as u128)
+        .ok_or(UtilError::NumericalOverflow)?;
+    let size = numerator
+        .checked_add(maker_size as u128 - 1)
+        .ok_or(UtilError::NumericalOverflow)?
+        / maker_size as u128;
+    u64::try_from(size).map_err(|_| UtilError::NumericalOverflow.into())
+}
+
+#[allow(clippy::too_many_arguments)]
 fn process_accept_offer(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -95,6 +81,8 @@ fn process_accept_offer(
     maker_size: u64,
     taker_size: u64,
     bump_seed: u8,
+    fill_size: u64,
+    expiry: i64,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     let maker_wallet = next_account_info(account_info_iter)?;
@@ -107,40 +95,82 @@ fn process_accept_offer(
     let taker_src_mint = next_account_info(account_info_iter)?;
     let transfer_authority = next_account_info(account_info_iter)?;
     let token_program_info = next_account_info(account_info_iter)?;
+    // The taker token program was added after the maker's one, so offers built
+    // before it existed, which pay with the same token program, still resolve
+    let taker_token_program_info = match account_info_iter.as_slice().first() {
+        Some(info) if *info.key == spl_token::id() || *info.key == spl_token_2022::id() => {
+            next_account_info(account_info_iter)?
+        }
+        _ => token_program_info,
+    };
     let mut system_program_info: Option<&AccountInfo> = None;
     let is_native = *taker_src_mint.key == spl_token::native_mint::id();
     if is_native {
         assert_keys_equal(*taker_wallet.key, *taker_src_account.key)?;
         assert_keys_equal(*maker_wallet.key, *maker_dst_account.key)?;
         system_program_info = Some(next_account_info(account_info_iter)?);
+    } else {
+        assert_token_program(taker_src_mint, taker_token_program_info)?;
     }
-    let seeds = &[
+    assert_token_program(maker_src_mint, token_program_info)?;
+    if expiry != 0 && Clock::get()?.unix_timestamp > expiry {
+        msg!("Offer expired at {}", expiry);
+        return Err(UtilError::OfferExpired.into());
+    }
+    let maker_size_bytes = maker_size.to_le_bytes();
+    let taker_size_bytes = taker_size.to_le_bytes();
+    let expiry_bytes = expiry.to_le_bytes();
+    let bump_seed = [bump_seed];
+    let mut seeds: Vec<&[u8]> = vec![
         b"stateless_offer",
         maker_wallet.key.as_ref(),
         maker_src_mint.key.as_ref(),
         taker_src_mint.key.as_ref(),
-        &maker_size.to_le_bytes(),
-        &taker_size.to_le_bytes(),
-        &[bump_seed],
+        &maker_size_bytes,
+        &taker_size_bytes,
     ];
+    // Offers without an expiry keep the authority they had before it existed
+    if expiry != 0 {
+        seeds.push(&expiry_bytes);
+    }
+    seeds.push(&bump_seed);
+    let seeds = seeds.as_slice();
+    let authority_key = Pubkey::create_program_address(seeds, program_id)?;
+    assert_keys_equal(authority_key, *transfer_authority.key)?;
+    msg!("Authority key matches");
+
+    let maker_src_token_account = unpack_token_account(maker_src_account)?;
+    // Ensure that authority is the delegate of this token account
+    msg!(
+        "Delegate {}",
+        maker_src_token_account
+            .delegate
+            .unwrap_or(*maker_wallet.key)
+    );
+    if maker_src_token_account.delegate != COption::Some(authority_key) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    msg!("Delegate matches");
+    // The remaining allowance is what is left of the offer, so it can neither
+    // exceed the offer nor be overdrawn by this fill
+    msg!(
+        "Delegated Amount {}",
+        maker_src_token_account.delegated_amount
+    );
+    if maker_src_token_account.delegated_amount > maker_size
+        || maker_src_token_account.delegated_amount < fill_size
+    {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let taker_fill_size = taker_fill_size(maker_size, taker_size, fill_size)?;
+    msg!("Filling {} for {}", fill_size, taker_fill_size);
+
     let (maker_pay_size, taker_pay_size) = if has_metadata {
         let metadata_info = next_account_info(account_info_iter)?;
-        let (maker_metadata_key, _) = Pubkey::find_program_address(
-            &[
-                b"metadata",
-                inline_mpl_token_metadata::id().as_ref(),
-                maker_src_mint.key.as_ref(),
-            ],
-            &inline_mpl_token_metadata::id(),
-        );
-        let (taker_metadata_key, _) = Pubkey::find_program_address(
-            &[
-                b"metadata",
-                inline_mpl_token_metadata::id().as_ref(),
-                taker_src_mint.key.as_ref(),
-            ],
-            &inline_mpl_token_metadata::id(),
-        );
+        let maker_metadata_key =
+            spl_inline_mpl_token_metadata::get_metadata_address(maker_src_mint.key);
+        let taker_metadata_key =
+            spl_inline_mpl_token_metadata::get_metadata_address(taker_src_mint.key);
         if *metadata_info.key == maker_metadata_key {
             msg!("Taker pays for fees");
             let taker_remaining_size = pay_creator_fees(
@@ -148,14 +178,14 @@ fn process_accept_offer(
                 metadata_info,
                 taker_src_account,
                 taker_wallet,
-                token_program_info,
+                taker_token_program_info,
                 system_program_info,
                 taker_src_mint,
-                taker_size,
+                taker_fill_size,
                 is_native,
                 &[],
             )?;
-            (maker_size, taker_remaining_size)
+            (fill_size, taker_remaining_size)
         } else if *metadata_info.key == taker_metadata_key {
             msg!("Maker pays for fees");
             let maker_remaining_size = pay_creator_fees(
@@ -166,65 +196,46 @@ fn process_accept_offer(
                 token_program_info,
                 system_program_info,
                 maker_src_mint,
-                maker_size,
+                fill_size,
                 is_native,
                 seeds,
             )?;
-            (maker_remaining_size, taker_size)
+            (maker_remaining_size, taker_fill_size)
         } else {
             msg!("Neither maker nor taker metadata keys match");
             return Err(ProgramError::InvalidAccountData);
         }
     } else {
-        (maker_size, taker_size)
+        (fill_size, taker_fill_size)
     };
+    // Whatever follows the creators is passed along to transfer hooks
+    let additional_accounts = account_info_iter.as_slice();
 
-    let maker_src_token_account: spl_token::state::Account =
-        spl_token::state::Account::unpack(&maker_src_account.data.borrow())?;
-    // Ensure that the delegated amount is exactly equal to the maker_size
-    msg!(
-        "Delegate {}",
-        maker_src_token_account
-            .delegate
-            .unwrap_or(*maker_wallet.key)
-    );
-    msg!(
-        "Delegated Amount {}",
-        maker_src_token_account.delegated_amount
-    );
-    if maker_src_token_account.delegated_amount != maker_pay_size {
-        return Err(ProgramError::InvalidAccountData);
-    }
-    let authority_key = Pubkey::create_program_address(seeds, program_id)?;
-    assert_keys_equal(authority_key, *transfer_authority.key)?;
-    // Ensure that authority is the delegate of this token account
-    msg!("Authority key matches");
-    if maker_src_token_account.delegate != COption::Some(authority_key) {
-        return Err(ProgramError::InvalidAccountData);
-    }
-    msg!("Delegate matches");
-    assert_keys_equal(spl_token::id(), *token_program_info.key)?;
     // Both of these transfers will fail if the `transfer_authority` is the delegate
     // of these ATA's One consideration is that the taker can get tricked in the
     // case that the maker size is greater than the token amount in the maker's
     // ATA, but these stateless offers should just be invalidated in the client.
-    assert_is_ata(maker_src_account, maker_wallet.key, maker_src_mint.key)?;
-    assert_is_ata(taker_dst_account, taker_wallet.key, maker_src_mint.key)?;
-    invoke_signed(
-        &spl_token::instruction::transfer(
-            token_program_info.key,
-            maker_src_account.key,
-            taker_dst_account.key,
-            transfer_authority.key,
-            &[],
-            maker_pay_size,
-        )?,
-        &[
-            maker_src_account.clone(),
-            taker_dst_account.clone(),
-            transfer_authority.clone(),
-            token_program_info.clone(),
-        ],
+    assert_is_ata(
+        maker_src_account,
+        maker_wallet.key,
+        maker_src_mint.key,
+        token_program_info.key,
+    )?;
+    assert_is_ata(
+        taker_dst_account,
+        taker_wallet.key,
+        maker_src_mint.key,
+        token_program_info.key,
+    )?;
+    invoke_transfer_checked(
+        token_program_info.key,
+        maker_src_account.clone(),
+        maker_src_mint.clone(),
+        taker_dst_account.clone(),
+        transfer_authority.clone(),
+        additional_accounts,
+        maker_pay_size,
+        unpack_mint(maker_src_mint)?.decimals,
         &[seeds],
     )?;
     msg!("done tx from maker to taker {}", maker_pay_size);
@@ -248,23 +259,28 @@ fn process_accept_offer(
             _ => return Err(ProgramError::InvalidAccountData),
         }
     } else {
-        assert_is_ata(maker_dst_account, maker_wallet.key, taker_src_mint.key)?;
-        assert_is_ata(taker_src_account, taker_wallet.key, taker_src_mint.key)?;
-        invoke(
-            &spl_token::instruction::transfer(
-                token_program_info.key,
-                taker_src_account.key,
-                maker_dst_account.key,
-                taker_wallet.key,
-                &[],
-                taker_pay_size,
-            )?,
-            &[
-                taker_src_account.clone(),
-                maker_dst_account.clone(),
-                taker_wallet.clone(),
-                token_program_info.clone(),
-            ],
+        assert_is_ata(
+            maker_dst_account,
+            maker_wallet.key,
+            taker_src_mint.key,
+            taker_token_program_info.key,
+        )?;
+        assert_is_ata(
+            taker_src_account,
+            taker_wallet.key,
+            taker_src_mint.key,
+            taker_token_program_info.key,
+        )?;
+        invoke_transfer_checked(
+            taker_token_program_info.key,
+            taker_src_account.clone(),
+            taker_src_mint.clone(),
+            maker_dst_account.clone(),
+            taker_wallet.clone(),
+            additional_accounts,
+            taker_pay_size,
+            unpack_mint(taker_src_mint)?.decimals,
+            &[],
         )?;
     }
     msg!("done tx from taker to maker {}", taker_pay_size);
@@ -274,7 +290,7 @@ fn process_accept_offer(
 
 #[allow(clippy::too_many_arguments)]
 fn pay_creator_fees<'a>(
-    account_info_iter: &mut Iter<AccountInfo<'a>>,
+    account_info_iter: &mut Iter<'_, AccountInfo<'a>>,
     metadata_info: &AccountInfo<'a>,
     src_account_info: &AccountInfo<'a>,
     src_authority_info: &AccountInfo<'a>,
@@ -285,10 +301,10 @@ fn pay_creator_fees<'a>(
     is_native: bool,
     seeds: &[&[u8]],
 ) -> Result<u64, ProgramError> {
-    if *metadata_info.owner != inline_mpl_token_metadata::id() {
+    if *metadata_info.owner != spl_inline_mpl_token_metadata::id() {
         return Err(ProgramError::InvalidAccountData);
     }
-    let metadata = try_from_slice_unchecked::<inline_mpl_token_metadata::Metadata>(
+    let metadata = try_from_slice_unchecked::<spl_inline_mpl_token_metadata::Metadata>(
         &metadata_info.try_borrow_data()?,
     )?;
     let fees = metadata.data.seller_fee_basis_points;
@@ -303,6 +319,20 @@ fn pay_creator_fees<'a>(
         .ok_or(UtilError::NumericalOverflow)?;
     match metadata.data.creators {
         Some(creators) => {
+            // Each creator comes with its token account unless paid in SOL, and
+            // whatever follows them is left to the transfer hooks
+            let creator_accounts_len = creators
+                .len()
+                .checked_mul(if is_native { 1 } else { 2 })
+                .ok_or(UtilError::NumericalOverflow)?;
+            let remaining_accounts = account_info_iter.as_slice();
+            if remaining_accounts.len() < creator_accounts_len {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            }
+            let (creator_accounts, additional_accounts) =
+                remaining_accounts.split_at(creator_accounts_len);
+            *account_info_iter = additional_accounts.iter();
+            let creator_account_iter = &mut creator_accounts.iter();
             for creator in creators {
                 let pct = creator.share as u64;
                 let creator_fee = pct
@@ -313,52 +343,31 @@ fn pay_creator_fees<'a>(
                 remaining_fee = remaining_fee
                     .checked_sub(creator_fee)
                     .ok_or(UtilError::NumericalOverflow)?;
-                let current_creator_info = next_account_info(account_info_iter)?;
+                let current_creator_info = next_account_info(creator_account_iter)?;
                 assert_keys_equal(creator.address, *current_creator_info.key)?;
                 if !is_native {
-                    let current_creator_token_account_info = next_account_info(account_info_iter)?;
+                    let current_creator_token_account_info =
+                        next_account_info(creator_account_iter)?;
                     assert_is_ata(
                         current_creator_token_account_info,
                         current_creator_info.key,
                         fee_mint.key,
+                        token_program_info.key,
                     )?;
                     if creator_fee > 0 {
-                        if seeds.is_empty() {
-                            invoke(
-                                &spl_token::instruction::transfer(
-                                    token_program_info.key,
-                                    src_account_info.key,
-                                    current_creator_token_account_info.key,
-                                    src_authority_info.key,
-                                    &[],
-                                    creator_fee,
-                                )?,
-                                &[
-                                    src_account_info.clone(),
-                                    current_creator_token_account_info.clone(),
-                                    src_authority_info.clone(),
-                                    token_program_info.clone(),
-                                ],
-                            )?;
-                        } else {
-                            invoke_signed(
-                                &spl_token::instruction::transfer(
-                                    token_program_info.key,
-                                    src_account_info.key,
-                                    current_creator_token_account_info.key,
-                                    src_authority_info.key,
-                                    &[],
-                                    creator_fee,
-                                )?,
-                                &[
-                                    src_account_info.clone(),
-                                    current_creator_token_account_info.clone(),
-                                    src_authority_info.clone(),
-                                    token_program_info.clone(),
-                                ],
-                                &[seeds],
-                            )?;
-                        }
+                        let signer_seeds: &[&[&[u8]]] =
+                            if seeds.is_empty() { &[] } else { &[seeds] };
+                        invoke_transfer_checked(
+                            token_program_info.key,
+                            src_account_info.clone(),
+                            fee_mint.clone(),
+                            current_creator_token_account_info.clone(),
+                            src_authority_info.clone(),
+                            additional_accounts,
+                            creator_fee,
+                            unpack_mint(fee_mint)?.decimals,
+                            signer_seeds,
+                        )?;
                     }
                 } else if creator_fee > 0 {
                     if !seeds.is_empty() {
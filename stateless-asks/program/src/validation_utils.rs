@@ -7,18 +7,52 @@ use {
         program_pack::{IsInitialized, Pack},
         pubkey::Pubkey,
     },
-    spl_associated_token_account_client::address::get_associated_token_address,
-    spl_token::{self, state::Account},
+    spl_associated_token_account_client::address::get_associated_token_address_with_program_id,
+    spl_token_2022::{
+        extension::StateWithExtensions,
+        state::{Account, Mint},
+    },
 };
 
-pub fn assert_is_ata(ata: &AccountInfo, wallet: &Pubkey, mint: &Pubkey) -> ProgramResult {
-    assert_owned_by(ata, &spl_token::id())?;
-    let ata_account: Account = assert_initialized(ata)?;
+pub fn assert_is_ata(
+    ata: &AccountInfo,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> ProgramResult {
+    assert_owned_by(ata, token_program)?;
+    let ata_account = unpack_token_account(ata)?;
     assert_keys_equal(ata_account.owner, *wallet)?;
-    assert_keys_equal(get_associated_token_address(wallet, mint), *ata.key)?;
+    assert_keys_equal(
+        get_associated_token_address_with_program_id(wallet, mint, token_program),
+        *ata.key,
+    )?;
     Ok(())
 }
 
+/// Checks that `token_program` is either SPL Token or Token-2022 and that it
+/// owns `mint`
+pub fn assert_token_program(mint: &AccountInfo, token_program: &AccountInfo) -> ProgramResult {
+    if *token_program.key != spl_token::id() && *token_program.key != spl_token_2022::id() {
+        return Err(UtilError::IncorrectTokenProgram.into());
+    }
+    assert_owned_by(mint, token_program.key)
+}
+
+/// Unpacks the base state of an SPL Token or Token-2022 account
+pub fn unpack_token_account(account_info: &AccountInfo) -> Result<Account, ProgramError> {
+    let data = account_info.try_borrow_data()?;
+    let account = StateWithExtensions::<Account>::unpack(&data)?;
+    Ok(account.base)
+}
+
+/// Unpacks the base state of an SPL Token or Token-2022 mint
+pub fn unpack_mint(account_info: &AccountInfo) -> Result<Mint, ProgramError> {
+    let data = account_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&data)?;
+    Ok(mint.base)
+}
+
 pub fn assert_keys_equal(key1: Pubkey, key2: Pubkey) -> ProgramResult {
     if key1 != key2 {
         Err(UtilError::PublicKeyMismatch.into())
@@ -0,0 +1,678 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    solana_program::{
+        account_info::AccountInfo,
+        clock::Clock,
+        entrypoint::ProgramResult,
+        instruction::{AccountMeta, Instruction, InstructionError},
+        program_error::ProgramError,
+        program_option::COption,
+        program_pack::Pack,
+        pubkey::Pubkey,
+        rent::Rent,
+    },
+    solana_program_test::{processor, tokio, ProgramTest, ProgramTestContext},
+    solana_sdk::{
+        account::Account,
+        signature::{Keypair, Signer},
+        transaction::{Transaction, TransactionError},
+    },
+    spl_associated_token_account_client::address::get_associated_token_address_with_program_id,
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
+    spl_tlv_account_resolution::state::ExtraAccountMetaList,
+    spl_token_2022::{
+        extension::{
+            transfer_fee::{TransferFee, TransferFeeAmount, TransferFeeConfig},
+            transfer_hook::{TransferHook, TransferHookAccount},
+            BaseStateWithExtensionsMut, ExtensionType, StateWithExtensions, StateWithExtensionsMut,
+        },
+        state::{Account as TokenAccount, AccountState, Mint},
+    },
+    spl_transfer_hook_interface::{
+        get_extra_account_metas_address,
+        instruction::{ExecuteInstruction, TransferHookInstruction},
+    },
+    stateless_asks::{
+        error::UtilError,
+        find_offer_authority,
+        instruction::{accept_offer, accept_offer_with_metadata},
+        processor::Processor,
+    },
+};
+
+const MAKER_SIZE: u64 = 10;
+const TAKER_SIZE: u64 = 25;
+const TAKER_BALANCE: u64 = 1_000;
+
+struct Offer {
+    program_id: Pubkey,
+    maker_wallet: Pubkey,
+    taker_wallet: Keypair,
+    maker_mint: Pubkey,
+    taker_mint: Pubkey,
+    maker_token_program_id: Pubkey,
+    taker_token_program_id: Pubkey,
+    authority: Pubkey,
+    bump_seed: u8,
+    expiry: i64,
+}
+
+impl Offer {
+    fn maker_src(&self) -> Pubkey {
+        get_associated_token_address_with_program_id(
+            &self.maker_wallet,
+            &self.maker_mint,
+            &self.maker_token_program_id,
+        )
+    }
+
+    fn maker_dst(&self) -> Pubkey {
+        get_associated_token_address_with_program_id(
+            &self.maker_wallet,
+            &self.taker_mint,
+            &self.taker_token_program_id,
+        )
+    }
+
+    fn taker_src(&self) -> Pubkey {
+        get_associated_token_address_with_program_id(
+            &self.taker_wallet.pubkey(),
+            &self.taker_mint,
+            &self.taker_token_program_id,
+        )
+    }
+
+    fn taker_dst(&self) -> Pubkey {
+        get_associated_token_address_with_program_id(
+            &self.taker_wallet.pubkey(),
+            &self.maker_mint,
+            &self.maker_token_program_id,
+        )
+    }
+}
+
+const HOOK_PROGRAM_ID: Pubkey = Pubkey::new_from_array([7; 32]);
+/// Largest transfer the transfer hook lets through
+const HOOK_MAX_AMOUNT: u64 = 5;
+/// Transfer fee of the maker mint, in basis points
+const TRANSFER_FEE_BASIS_POINTS: u16 = 1_000;
+
+fn hook_process_instruction(
+    _program_id: &Pubkey,
+    _accounts: &[AccountInfo],
+    input: &[u8],
+) -> ProgramResult {
+    match TransferHookInstruction::unpack(input)? {
+        TransferHookInstruction::Execute { amount } if amount <= HOOK_MAX_AMOUNT => Ok(()),
+        _ => Err(ProgramError::InvalidArgument),
+    }
+}
+
+/// Token-2022 extension of the maker mint
+#[derive(Clone, Copy, PartialEq)]
+enum MakerMintExtension {
+    None,
+    TransferFee,
+    TransferHook,
+}
+
+impl MakerMintExtension {
+    fn mint_extensions(self) -> Vec<ExtensionType> {
+        match self {
+            Self::None => vec![],
+            Self::TransferFee => vec![ExtensionType::TransferFeeConfig],
+            Self::TransferHook => vec![ExtensionType::TransferHook],
+        }
+    }
+}
+
+fn add_mint(
+    program_test: &mut ProgramTest,
+    mint: &Pubkey,
+    token_program_id: &Pubkey,
+    extension: MakerMintExtension,
+) {
+    let base = Mint {
+        supply: u64::MAX,
+        decimals: 0,
+        is_initialized: true,
+        ..Mint::default()
+    };
+    let data = if extension == MakerMintExtension::None {
+        let mut data = vec![0; Mint::LEN];
+        base.pack_into_slice(&mut data);
+        data
+    } else {
+        let space =
+            ExtensionType::try_calculate_account_len::<Mint>(&extension.mint_extensions()).unwrap();
+        let mut data = vec![0; space];
+        let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        match extension {
+            MakerMintExtension::TransferFee => {
+                let transfer_fee = TransferFee {
+                    epoch: 0.into(),
+                    maximum_fee: u64::MAX.into(),
+                    transfer_fee_basis_points: TRANSFER_FEE_BASIS_POINTS.into(),
+                };
+                let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+                config.older_transfer_fee = transfer_fee;
+                config.newer_transfer_fee = transfer_fee;
+            }
+            MakerMintExtension::TransferHook => {
+                state
+                    .init_extension::<TransferHook>(true)
+                    .unwrap()
+                    .program_id = OptionalNonZeroPubkey::try_from(Some(HOOK_PROGRAM_ID)).unwrap();
+            }
+            MakerMintExtension::None => unreachable!(),
+        }
+        state.base = base;
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    };
+    program_test.add_account(
+        *mint,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: *token_program_id,
+            ..Account::default()
+        },
+    );
+}
+
+fn add_token_account(
+    program_test: &mut ProgramTest,
+    address: Pubkey,
+    token_account: TokenAccount,
+    token_program_id: &Pubkey,
+) {
+    add_token_account_with_extension(
+        program_test,
+        address,
+        token_account,
+        token_program_id,
+        MakerMintExtension::None,
+    );
+}
+
+/// Adds a token account with the extensions required by the extension of its
+/// mint
+fn add_token_account_with_extension(
+    program_test: &mut ProgramTest,
+    address: Pubkey,
+    token_account: TokenAccount,
+    token_program_id: &Pubkey,
+    extension: MakerMintExtension,
+) {
+    let data = if extension == MakerMintExtension::None {
+        let mut data = vec![0; TokenAccount::LEN];
+        token_account.pack_into_slice(&mut data);
+        data
+    } else {
+        let account_extensions =
+            ExtensionType::get_required_init_account_extensions(&extension.mint_extensions());
+        let space =
+            ExtensionType::try_calculate_account_len::<TokenAccount>(&account_extensions).unwrap();
+        let mut data = vec![0; space];
+        let mut state =
+            StateWithExtensionsMut::<TokenAccount>::unpack_uninitialized(&mut data).unwrap();
+        match extension {
+            MakerMintExtension::TransferFee => {
+                state.init_extension::<TransferFeeAmount>(true).unwrap();
+            }
+            MakerMintExtension::TransferHook => {
+                state.init_extension::<TransferHookAccount>(true).unwrap();
+            }
+            MakerMintExtension::None => unreachable!(),
+        }
+        state.base = token_account;
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    };
+    program_test.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: *token_program_id,
+            ..Account::default()
+        },
+    );
+}
+
+/// Sets up an offer of `MAKER_SIZE` maker tokens for `TAKER_SIZE` taker
+/// tokens, fully delegated to the offer authority
+async fn setup_offer(
+    maker_token_program_id: Pubkey,
+    taker_token_program_id: Pubkey,
+    expiry: i64,
+) -> (ProgramTestContext, Offer) {
+    let (program_test, offer) = build_offer(
+        maker_token_program_id,
+        taker_token_program_id,
+        expiry,
+        MakerMintExtension::None,
+    );
+    (program_test.start_with_context().await, offer)
+}
+
+/// Builds the accounts of an offer, like `setup_offer`, so that more can be
+/// added before the test starts
+fn build_offer(
+    maker_token_program_id: Pubkey,
+    taker_token_program_id: Pubkey,
+    expiry: i64,
+    maker_mint_extension: MakerMintExtension,
+) -> (ProgramTest, Offer) {
+    let program_id = Pubkey::new_unique();
+    let mut program_test =
+        ProgramTest::new("stateless_asks", program_id, processor!(Processor::process));
+
+    let maker_wallet = Pubkey::new_unique();
+    let maker_mint = Pubkey::new_unique();
+    let taker_mint = Pubkey::new_unique();
+    let (authority, bump_seed) = find_offer_authority(
+        &program_id,
+        &maker_wallet,
+        &maker_mint,
+        &taker_mint,
+        MAKER_SIZE,
+        TAKER_SIZE,
+        expiry,
+    );
+    let offer = Offer {
+        program_id,
+        maker_wallet,
+        taker_wallet: Keypair::new(),
+        maker_mint,
+        taker_mint,
+        maker_token_program_id,
+        taker_token_program_id,
+        authority,
+        bump_seed,
+        expiry,
+    };
+
+    add_mint(
+        &mut program_test,
+        &maker_mint,
+        &maker_token_program_id,
+        maker_mint_extension,
+    );
+    add_mint(
+        &mut program_test,
+        &taker_mint,
+        &taker_token_program_id,
+        MakerMintExtension::None,
+    );
+    let token_account = |mint: Pubkey, owner: Pubkey, amount: u64| TokenAccount {
+        mint,
+        owner,
+        amount,
+        state: AccountState::Initialized,
+        ..TokenAccount::default()
+    };
+    add_token_account_with_extension(
+        &mut program_test,
+        offer.maker_src(),
+        TokenAccount {
+            delegate: COption::Some(authority),
+            delegated_amount: MAKER_SIZE,
+            ..token_account(maker_mint, maker_wallet, MAKER_SIZE)
+        },
+        &maker_token_program_id,
+        maker_mint_extension,
+    );
+    add_token_account(
+        &mut program_test,
+        offer.maker_dst(),
+        token_account(taker_mint, maker_wallet, 0),
+        &taker_token_program_id,
+    );
+    add_token_account(
+        &mut program_test,
+        offer.taker_src(),
+        token_account(taker_mint, offer.taker_wallet.pubkey(), TAKER_BALANCE),
+        &taker_token_program_id,
+    );
+    add_token_account_with_extension(
+        &mut program_test,
+        offer.taker_dst(),
+        token_account(maker_mint, offer.taker_wallet.pubkey(), 0),
+        &maker_token_program_id,
+        maker_mint_extension,
+    );
+
+    (program_test, offer)
+}
+
+fn accept_offer_instruction(offer: &Offer, fill_size: u64) -> Instruction {
+    accept_offer(
+        &offer.program_id,
+        &offer.maker_wallet,
+        &offer.taker_wallet.pubkey(),
+        &offer.maker_src(),
+        &offer.maker_dst(),
+        &offer.taker_src(),
+        &offer.taker_dst(),
+        &offer.maker_mint,
+        &offer.taker_mint,
+        &offer.authority,
+        &offer.maker_token_program_id,
+        &offer.taker_token_program_id,
+        false,
+        MAKER_SIZE,
+        TAKER_SIZE,
+        offer.bump_seed,
+        fill_size,
+        offer.expiry,
+    )
+}
+
+async fn fill_offer(
+    context: &mut ProgramTestContext,
+    offer: &Offer,
+    fill_size: u64,
+) -> Result<(), TransactionError> {
+    let instruction = accept_offer_instruction(offer, fill_size);
+    process_instruction(context, offer, instruction).await
+}
+
+async fn process_instruction(
+    context: &mut ProgramTestContext,
+    offer: &Offer,
+    instruction: Instruction,
+) -> Result<(), TransactionError> {
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &offer.taker_wallet],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|e| e.unwrap())
+}
+
+async fn get_token_amount(context: &mut ProgramTestContext, address: Pubkey) -> u64 {
+    let account = context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .unwrap();
+    StateWithExtensions::<TokenAccount>::unpack(&account.data)
+        .unwrap()
+        .base
+        .amount
+}
+
+#[tokio::test]
+async fn test_partial_fills() {
+    let (mut context, offer) = setup_offer(spl_token::id(), spl_token::id(), 0).await;
+
+    // The taker pays its share of the asking price, rounded up
+    fill_offer(&mut context, &offer, 4).await.unwrap();
+    assert_eq!(get_token_amount(&mut context, offer.taker_dst()).await, 4);
+    assert_eq!(get_token_amount(&mut context, offer.maker_dst()).await, 10);
+
+    fill_offer(&mut context, &offer, 6).await.unwrap();
+    assert_eq!(get_token_amount(&mut context, offer.taker_dst()).await, 10);
+    assert_eq!(get_token_amount(&mut context, offer.maker_dst()).await, 25);
+    assert_eq!(
+        get_token_amount(&mut context, offer.taker_src()).await,
+        TAKER_BALANCE - TAKER_SIZE
+    );
+
+    // The allowance is used up
+    assert_eq!(
+        fill_offer(&mut context, &offer, 1).await.unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+}
+
+#[tokio::test]
+async fn test_fill_larger_than_offer() {
+    let (mut context, offer) = setup_offer(spl_token::id(), spl_token::id(), 0).await;
+
+    assert_eq!(
+        fill_offer(&mut context, &offer, MAKER_SIZE + 1)
+            .await
+            .unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::InvalidAccountData)
+    );
+}
+
+async fn set_clock(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    context.set_sysvar(&clock);
+}
+
+#[tokio::test]
+async fn test_expiry() {
+    let expiry = 1_000_000;
+    let (mut context, offer) = setup_offer(spl_token::id(), spl_token::id(), expiry).await;
+
+    set_clock(&mut context, expiry).await;
+    fill_offer(&mut context, &offer, 1).await.unwrap();
+
+    set_clock(&mut context, expiry + 1).await;
+    assert_eq!(
+        fill_offer(&mut context, &offer, 2).await.unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(UtilError::OfferExpired as u32)
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_token_2022() {
+    let (mut context, offer) = setup_offer(spl_token_2022::id(), spl_token::id(), 0).await;
+
+    fill_offer(&mut context, &offer, MAKER_SIZE).await.unwrap();
+    assert_eq!(
+        get_token_amount(&mut context, offer.taker_dst()).await,
+        MAKER_SIZE
+    );
+    assert_eq!(
+        get_token_amount(&mut context, offer.maker_dst()).await,
+        TAKER_SIZE
+    );
+}
+
+#[tokio::test]
+async fn test_legacy_accept_offer_data() {
+    let (mut context, offer) = setup_offer(spl_token::id(), spl_token::id(), 0).await;
+
+    // Clients built before partial fills and expiries don't send them, and
+    // fill the whole offer
+    let mut instruction = accept_offer_instruction(&offer, MAKER_SIZE);
+    instruction.data.truncate(instruction.data.len() - 16);
+    process_instruction(&mut context, &offer, instruction)
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_amount(&mut context, offer.taker_dst()).await,
+        MAKER_SIZE
+    );
+    assert_eq!(
+        get_token_amount(&mut context, offer.maker_dst()).await,
+        TAKER_SIZE
+    );
+}
+
+#[tokio::test]
+async fn test_creator_fees_on_partial_fills() {
+    let (mut program_test, offer) = build_offer(
+        spl_token::id(),
+        spl_token::id(),
+        0,
+        MakerMintExtension::None,
+    );
+
+    // 10% royalties on the maker mint, paid by the taker to a single creator
+    let creator = Pubkey::new_unique();
+    let creator_account = get_associated_token_address_with_program_id(
+        &creator,
+        &offer.taker_mint,
+        &offer.taker_token_program_id,
+    );
+    add_token_account(
+        &mut program_test,
+        creator_account,
+        TokenAccount {
+            mint: offer.taker_mint,
+            owner: creator,
+            state: AccountState::Initialized,
+            ..TokenAccount::default()
+        },
+        &offer.taker_token_program_id,
+    );
+    let metadata = spl_inline_mpl_token_metadata::get_metadata_address(&offer.maker_mint);
+    let metadata_data = borsh::to_vec(&(
+        (4u8, Pubkey::new_unique(), offer.maker_mint),
+        (
+            String::from("Offer"),
+            String::from("OFR"),
+            String::new(),
+            1_000u16,
+            Some(vec![(creator, true, 100u8)]),
+        ),
+        (true, true, None::<u8>, None::<u8>, None::<(bool, Pubkey)>),
+    ))
+    .unwrap();
+    program_test.add_account(
+        metadata,
+        Account {
+            lamports: Rent::default().minimum_balance(metadata_data.len()),
+            data: metadata_data,
+            owner: spl_inline_mpl_token_metadata::id(),
+            ..Account::default()
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+
+    let fill_instruction = |fill_size| {
+        accept_offer_with_metadata(
+            &offer.program_id,
+            &offer.maker_wallet,
+            &offer.taker_wallet.pubkey(),
+            &offer.maker_src(),
+            &offer.maker_dst(),
+            &offer.taker_src(),
+            &offer.taker_dst(),
+            &offer.maker_mint,
+            &offer.taker_mint,
+            &offer.authority,
+            &offer.maker_token_program_id,
+            &offer.taker_token_program_id,
+            &metadata,
+            &[&creator, &creator_account],
+            false,
+            MAKER_SIZE,
+            TAKER_SIZE,
+            offer.bump_seed,
+            fill_size,
+            offer.expiry,
+        )
+    };
+
+    // The taker pays 10 for 4 maker tokens, 1 of which goes to the creator
+    process_instruction(&mut context, &offer, fill_instruction(4))
+        .await
+        .unwrap();
+    assert_eq!(get_token_amount(&mut context, offer.taker_dst()).await, 4);
+    assert_eq!(get_token_amount(&mut context, offer.maker_dst()).await, 9);
+    assert_eq!(get_token_amount(&mut context, creator_account).await, 1);
+
+    // The taker pays 15 for the remaining 6, the creator fee is rounded down
+    process_instruction(&mut context, &offer, fill_instruction(6))
+        .await
+        .unwrap();
+    assert_eq!(get_token_amount(&mut context, offer.taker_dst()).await, 10);
+    assert_eq!(get_token_amount(&mut context, offer.maker_dst()).await, 23);
+    assert_eq!(get_token_amount(&mut context, creator_account).await, 2);
+    assert_eq!(
+        get_token_amount(&mut context, offer.taker_src()).await,
+        TAKER_BALANCE - TAKER_SIZE
+    );
+}
+
+#[tokio::test]
+async fn test_token_2022_transfer_fee() {
+    let (program_test, offer) = build_offer(
+        spl_token_2022::id(),
+        spl_token::id(),
+        0,
+        MakerMintExtension::TransferFee,
+    );
+    let mut context = program_test.start_with_context().await;
+
+    // The transfer fee is withheld from the maker tokens the taker receives
+    fill_offer(&mut context, &offer, 4).await.unwrap();
+    assert_eq!(get_token_amount(&mut context, offer.taker_dst()).await, 3);
+    assert_eq!(get_token_amount(&mut context, offer.maker_dst()).await, 10);
+}
+
+#[tokio::test]
+async fn test_token_2022_transfer_hook() {
+    let (mut program_test, offer) = build_offer(
+        spl_token_2022::id(),
+        spl_token::id(),
+        0,
+        MakerMintExtension::TransferHook,
+    );
+    program_test.add_program(
+        "transfer_hook",
+        HOOK_PROGRAM_ID,
+        processor!(hook_process_instruction),
+    );
+    let extra_account_metas = get_extra_account_metas_address(&offer.maker_mint, &HOOK_PROGRAM_ID);
+    let mut extra_account_metas_data = vec![0; ExtraAccountMetaList::size_of(0).unwrap()];
+    ExtraAccountMetaList::init::<ExecuteInstruction>(&mut extra_account_metas_data, &[]).unwrap();
+    program_test.add_account(
+        extra_account_metas,
+        Account {
+            lamports: Rent::default().minimum_balance(extra_account_metas_data.len()),
+            data: extra_account_metas_data,
+            owner: HOOK_PROGRAM_ID,
+            ..Account::default()
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+
+    // The accounts of the transfer hook follow the other accounts
+    let fill_instruction = |fill_size| {
+        let mut instruction = accept_offer_instruction(&offer, fill_size);
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(extra_account_metas, false));
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(HOOK_PROGRAM_ID, false));
+        instruction
+    };
+
+    // The hook is run on the maker tokens, and may reject the fill
+    assert_eq!(
+        process_instruction(&mut context, &offer, fill_instruction(HOOK_MAX_AMOUNT + 1))
+            .await
+            .unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+    process_instruction(&mut context, &offer, fill_instruction(HOOK_MAX_AMOUNT))
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_amount(&mut context, offer.taker_dst()).await,
+        HOOK_MAX_AMOUNT
+    );
+}
@@ -1,11 +1,14 @@
 //! Program entrypoint
 
 use {
-    crate::processor,
+    crate::{error::TokenCollectionError, processor},
     solana_program::{
-        account_info::AccountInfo, entrypoint::ProgramResult, program_error::PrintProgramError,
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::{PrintProgramError, ProgramError},
         pubkey::Pubkey,
     },
+    spl_program_error::num_traits::FromPrimitive,
     spl_token_group_interface::error::TokenGroupError,
 };
 
@@ -16,7 +19,12 @@ fn process_instruction(
     instruction_data: &[u8],
 ) -> ProgramResult {
     if let Err(error) = processor::process(program_id, accounts, instruction_data) {
-        error.print::<TokenGroupError>();
+        match error {
+            ProgramError::Custom(code) if TokenCollectionError::from_u32(code).is_some() => {
+                error.print::<TokenCollectionError>()
+            }
+            _ => error.print::<TokenGroupError>(),
+        }
         return Err(error);
     }
     Ok(())
@@ -0,0 +1,30 @@
+//! Error types
+
+use spl_program_error::*;
+
+/// Errors that may be returned by the Token Collection program, in addition
+/// to the ones defined by the SPL Token Group interface.
+#[spl_program_error(hash_error_code_start = 1_117_003_397)]
+pub enum TokenCollectionError {
+    /// Mint is not a member of the collection
+    #[error("Mint is not a member of the collection")]
+    NotACollectionMember,
+    /// Collection is already a member of a parent collection
+    #[error("Collection is already a member of a parent collection")]
+    CollectionAlreadyNested,
+    /// Nesting the collection would create a cycle
+    #[error("Nesting the collection would create a cycle")]
+    CollectionCycle,
+    /// Account does not match the parent collection
+    #[error("Account does not match the parent collection")]
+    IncorrectParentCollection,
+    /// Member mint does not match the mint of the nested collection
+    #[error("Member mint does not match the mint of the nested collection")]
+    IncorrectCollectionMint,
+    /// Royalty rate or creator shares are invalid
+    #[error("Royalty rate or creator shares are invalid")]
+    InvalidRoyaltyConfig,
+    /// An intermediate collection has its own royalty config
+    #[error("An intermediate collection has its own royalty config")]
+    IncorrectRoyaltyCollection,
+}
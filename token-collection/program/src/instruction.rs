@@ -0,0 +1,275 @@
+//! Instruction types
+//!
+//! These instructions extend the SPL Token Group interface, and are
+//! distinguished from it by their discriminators.
+
+use {
+    crate::state::RoyaltyConfig,
+    bytemuck::{Pod, Zeroable},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        system_program,
+    },
+    spl_discriminator::{ArrayDiscriminator, SplDiscriminate},
+    spl_pod::{
+        bytemuck::{pod_bytes_of, pod_from_bytes},
+        primitives::PodU64,
+    },
+    spl_token_group_interface::instruction::initialize_member,
+};
+
+/// Instruction data for removing a member from a collection
+#[derive(Clone, Copy, Debug, PartialEq, SplDiscriminate)]
+#[discriminator_hash_input("spl_token_collection:remove_member")]
+pub struct RemoveMember;
+
+/// Instruction data for verifying that a mint is a member of a collection
+#[derive(Clone, Copy, Debug, PartialEq, SplDiscriminate)]
+#[discriminator_hash_input("spl_token_collection:verify_member")]
+pub struct VerifyMember;
+
+/// Instruction data for setting the royalty config of a collection
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable, SplDiscriminate)]
+#[discriminator_hash_input("spl_token_collection:set_royalty_config")]
+pub struct SetRoyaltyConfig {
+    /// The new royalty config
+    pub config: RoyaltyConfig,
+}
+
+/// Instruction data for computing the royalties owed on the sale of a member
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable, SplDiscriminate)]
+#[discriminator_hash_input("spl_token_collection:get_royalty_payouts")]
+pub struct GetRoyaltyPayouts {
+    /// The sale price
+    pub price: PodU64,
+}
+
+/// Instructions supported by the Token Collection program on top of the SPL
+/// Token Group interface
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenCollectionInstruction {
+    /// Remove a member from a collection
+    ///
+    /// Decrements the size of the collection and zeroes the member's entry
+    /// for the collection, which can be reused by a later `InitializeMember`.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[w]`  Member
+    ///   1. `[w]`  Collection
+    ///   2. `[s]`  Collection update authority
+    RemoveMember(RemoveMember),
+
+    /// Verify that a mint is a member of a collection, either directly or
+    /// through nested collections. Fails if it is not, so that programs can
+    /// assert membership through CPI.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]`   Member
+    ///   1. `[]`   Member mint
+    ///   2. `[]`   Collection
+    ///   3. ..`3+N` `[]` Intermediate collections, starting from the
+    ///      collection of the member up to a direct child of the collection
+    VerifyMember(VerifyMember),
+
+    /// Set the royalty config of a collection, which applies to its members
+    /// and the members of its nested collections, unless a nearer collection
+    /// has its own config
+    ///
+    /// If the collection does not have a config yet, its account is
+    /// reallocated to make room for one, with the payer covering the
+    /// additional rent.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[w]`  Collection
+    ///   1. `[s]`  Collection update authority
+    ///   2. `[ws]` Payer
+    ///   3. `[]`   System program
+    SetRoyaltyConfig(SetRoyaltyConfig),
+
+    /// Compute the royalties owed to each creator for the sale of a member
+    ///
+    /// Verifies membership like `VerifyMember`, and fails if an intermediate
+    /// collection has a royalty config of its own. The payouts are set as
+    /// return data, as a `RoyaltyPayout` array.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[]`   Member
+    ///   1. `[]`   Member mint
+    ///   2. `[]`   Collection with the royalty config
+    ///   3. ..`3+N` `[]` Intermediate collections, starting from the
+    ///      collection of the member up to a direct child of the collection
+    GetRoyaltyPayouts(GetRoyaltyPayouts),
+}
+impl TokenCollectionInstruction {
+    /// Unpacks a byte buffer into a `TokenCollectionInstruction`
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        if input.len() < ArrayDiscriminator::LENGTH {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (discriminator, rest) = input.split_at(ArrayDiscriminator::LENGTH);
+        Ok(match discriminator {
+            RemoveMember::SPL_DISCRIMINATOR_SLICE if rest.is_empty() => {
+                Self::RemoveMember(RemoveMember)
+            }
+            VerifyMember::SPL_DISCRIMINATOR_SLICE if rest.is_empty() => {
+                Self::VerifyMember(VerifyMember)
+            }
+            SetRoyaltyConfig::SPL_DISCRIMINATOR_SLICE => {
+                let data = pod_from_bytes::<SetRoyaltyConfig>(rest)?;
+                Self::SetRoyaltyConfig(*data)
+            }
+            GetRoyaltyPayouts::SPL_DISCRIMINATOR_SLICE => {
+                let data = pod_from_bytes::<GetRoyaltyPayouts>(rest)?;
+                Self::GetRoyaltyPayouts(*data)
+            }
+            _ => return Err(ProgramError::InvalidInstructionData),
+        })
+    }
+
+    /// Packs a `TokenCollectionInstruction` into a byte buffer.
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = vec![];
+        match self {
+            Self::RemoveMember(_) => {
+                buf.extend_from_slice(RemoveMember::SPL_DISCRIMINATOR_SLICE);
+            }
+            Self::VerifyMember(_) => {
+                buf.extend_from_slice(VerifyMember::SPL_DISCRIMINATOR_SLICE);
+            }
+            Self::SetRoyaltyConfig(data) => {
+                buf.extend_from_slice(SetRoyaltyConfig::SPL_DISCRIMINATOR_SLICE);
+                buf.extend_from_slice(pod_bytes_of(data));
+            }
+            Self::GetRoyaltyPayouts(data) => {
+                buf.extend_from_slice(GetRoyaltyPayouts::SPL_DISCRIMINATOR_SLICE);
+                buf.extend_from_slice(pod_bytes_of(data));
+            }
+        };
+        buf
+    }
+}
+
+/// Creates an `InitializeMember` instruction that makes a collection a member
+/// of a parent collection. `parent_ancestors` lists the collections above the
+/// parent, from its own parent up to the root, and is used to reject cycles.
+pub fn initialize_nested_collection(
+    program_id: &Pubkey,
+    collection: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_mint_authority: &Pubkey,
+    parent: &Pubkey,
+    parent_update_authority: &Pubkey,
+    parent_ancestors: &[Pubkey],
+) -> Instruction {
+    let mut instruction = initialize_member(
+        program_id,
+        collection,
+        collection_mint,
+        collection_mint_authority,
+        parent,
+        parent_update_authority,
+    );
+    instruction.accounts.extend(
+        parent_ancestors
+            .iter()
+            .map(|ancestor| AccountMeta::new_readonly(*ancestor, false)),
+    );
+    instruction
+}
+
+/// Creates a `RemoveMember` instruction
+pub fn remove_member(
+    program_id: &Pubkey,
+    member: &Pubkey,
+    collection: &Pubkey,
+    collection_update_authority: &Pubkey,
+) -> Instruction {
+    let data = TokenCollectionInstruction::RemoveMember(RemoveMember).pack();
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*member, false),
+            AccountMeta::new(*collection, false),
+            AccountMeta::new_readonly(*collection_update_authority, true),
+        ],
+        data,
+    }
+}
+
+/// Creates a `VerifyMember` instruction
+pub fn verify_member(
+    program_id: &Pubkey,
+    member: &Pubkey,
+    member_mint: &Pubkey,
+    collection: &Pubkey,
+    intermediate_collections: &[Pubkey],
+) -> Instruction {
+    let data = TokenCollectionInstruction::VerifyMember(VerifyMember).pack();
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*member, false),
+        AccountMeta::new_readonly(*member_mint, false),
+        AccountMeta::new_readonly(*collection, false),
+    ];
+    accounts.extend(
+        intermediate_collections
+            .iter()
+            .map(|collection| AccountMeta::new_readonly(*collection, false)),
+    );
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Creates a `SetRoyaltyConfig` instruction
+pub fn set_royalty_config(
+    program_id: &Pubkey,
+    collection: &Pubkey,
+    collection_update_authority: &Pubkey,
+    payer: &Pubkey,
+    config: RoyaltyConfig,
+) -> Instruction {
+    let data = TokenCollectionInstruction::SetRoyaltyConfig(SetRoyaltyConfig { config }).pack();
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*collection, false),
+            AccountMeta::new_readonly(*collection_update_authority, true),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data,
+    }
+}
+
+/// Creates a `GetRoyaltyPayouts` instruction
+pub fn get_royalty_payouts(
+    program_id: &Pubkey,
+    member: &Pubkey,
+    member_mint: &Pubkey,
+    collection: &Pubkey,
+    intermediate_collections: &[Pubkey],
+    price: u64,
+) -> Instruction {
+    let mut instruction = verify_member(
+        program_id,
+        member,
+        member_mint,
+        collection,
+        intermediate_collections,
+    );
+    instruction.data = TokenCollectionInstruction::GetRoyaltyPayouts(GetRoyaltyPayouts {
+        price: price.into(),
+    })
+    .pack();
+    instruction
+}
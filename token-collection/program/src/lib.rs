@@ -4,7 +4,10 @@
 #![deny(missing_docs)]
 #![forbid(unsafe_code)]
 
+pub mod error;
+pub mod instruction;
 pub mod processor;
+pub mod state;
 
 #[cfg(not(feature = "no-entrypoint"))]
 mod entrypoint;
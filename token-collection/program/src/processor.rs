@@ -1,15 +1,28 @@
 //! Program state processor
 
 use {
+    crate::{
+        error::TokenCollectionError,
+        instruction::{
+            GetRoyaltyPayouts, SetRoyaltyConfig, TokenCollectionInstruction, VerifyMember,
+        },
+        state::RoyaltyConfig,
+    },
+    bytemuck::cast_slice,
     solana_program::{
         account_info::{next_account_info, AccountInfo},
         entrypoint::ProgramResult,
         msg,
+        program::{invoke, set_return_data},
         program_error::ProgramError,
         program_option::COption,
         pubkey::Pubkey,
+        rent::Rent,
+        system_instruction,
+        sysvar::Sysvar,
     },
-    spl_pod::optional_keys::OptionalNonZeroPubkey,
+    spl_discriminator::ArrayDiscriminator,
+    spl_pod::{bytemuck::pod_from_bytes, optional_keys::OptionalNonZeroPubkey},
     spl_token_2022::{
         extension::{
             metadata_pointer::MetadataPointer, BaseStateWithExtensions, StateWithExtensions,
@@ -18,11 +31,17 @@ use {
     },
     spl_token_group_interface::{
         error::TokenGroupError,
-        instruction::{InitializeGroup, TokenGroupInstruction},
+        instruction::{
+            InitializeGroup, TokenGroupInstruction, UpdateGroupAuthority, UpdateGroupMaxSize,
+        },
         state::{TokenGroup, TokenGroupMember},
     },
     spl_token_metadata_interface::state::TokenMetadata,
-    spl_type_length_value::state::TlvStateMut,
+    spl_type_length_value::{
+        length::Length,
+        state::{TlvState, TlvStateBorrowed, TlvStateMut},
+    },
+    std::mem::size_of,
 };
 
 fn check_update_authority(
@@ -67,6 +86,83 @@ fn check_mint_and_metadata(
     Ok(())
 }
 
+/// Returns the repetition number of the membership entry of `mint` in
+/// `group`, if any. Removed memberships are zeroed, so they never match.
+fn find_membership<S: TlvState>(state: &S, mint: &Pubkey, group: &Pubkey) -> Option<usize> {
+    (0..)
+        .map_while(|i| state.get_value_with_repetition::<TokenGroupMember>(i).ok())
+        .position(|member| member.mint == *mint && member.group == *group)
+}
+
+/// Returns the number of bytes used by the TLV entries in `data`, ignoring
+/// any uninitialized space at the end.
+fn get_tlv_len(data: &[u8]) -> Result<usize, ProgramError> {
+    let base_len = TlvStateBorrowed::get_base_len();
+    let mut len = 0usize;
+    while let Some(header) = data.get(len..len.saturating_add(base_len)) {
+        let (discriminator, length) = header.split_at(ArrayDiscriminator::LENGTH);
+        if discriminator == ArrayDiscriminator::UNINITIALIZED.as_slice() {
+            break;
+        }
+        let length = usize::try_from(*pod_from_bytes::<Length>(length)?)?;
+        len = len.saturating_add(base_len).saturating_add(length);
+    }
+    Ok(len)
+}
+
+/// Returns the parent of a nested collection, if any.
+fn get_parent_collection(
+    program_id: &Pubkey,
+    collection_info: &AccountInfo,
+) -> Result<Option<Pubkey>, ProgramError> {
+    if collection_info.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    let buffer = collection_info.try_borrow_data()?;
+    let state = TlvStateBorrowed::unpack(&buffer)?;
+    let collection = state.get_first_value::<TokenGroup>()?;
+    Ok(find_membership_of_mint(&state, &collection.mint))
+}
+
+/// Returns the collection of the first live membership entry of `mint`.
+fn find_membership_of_mint<S: TlvState>(state: &S, mint: &Pubkey) -> Option<Pubkey> {
+    (0..)
+        .map_while(|i| state.get_value_with_repetition::<TokenGroupMember>(i).ok())
+        .find(|member| member.mint == *mint)
+        .map(|member| member.group)
+}
+
+/// Checks that making `collection_info` a member of `parent_info` does not
+/// create a cycle, by walking the ancestors of the parent given in
+/// `ancestor_infos` up to the root collection.
+fn check_nesting<'a, 'b: 'a>(
+    program_id: &Pubkey,
+    collection_info: &AccountInfo,
+    parent_info: &'a AccountInfo<'b>,
+    ancestor_infos: &mut impl Iterator<Item = &'a AccountInfo<'b>>,
+) -> ProgramResult {
+    {
+        let buffer = collection_info.try_borrow_data()?;
+        let state = TlvStateBorrowed::unpack(&buffer)?;
+        let collection = state.get_first_value::<TokenGroup>()?;
+        if find_membership_of_mint(&state, &collection.mint).is_some() {
+            return Err(TokenCollectionError::CollectionAlreadyNested.into());
+        }
+    }
+
+    let mut ancestor_info = parent_info;
+    while let Some(parent) = get_parent_collection(program_id, ancestor_info)? {
+        if parent == *collection_info.key {
+            return Err(TokenCollectionError::CollectionCycle.into());
+        }
+        ancestor_info = next_account_info(ancestor_infos)?;
+        if *ancestor_info.key != parent {
+            return Err(TokenCollectionError::IncorrectParentCollection.into());
+        }
+    }
+    Ok(())
+}
+
 /// Processes an [InitializeGroup](enum.GroupInterfaceInstruction.html)
 /// instruction to initialize a collection.
 pub fn process_initialize_collection(
@@ -91,10 +187,58 @@ pub fn process_initialize_collection(
     Ok(())
 }
 
+/// Processes an
+/// [UpdateGroupMaxSize](enum.GroupInterfaceInstruction.html)
+/// instruction
+pub fn process_update_collection_max_size(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: UpdateGroupMaxSize,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let collection_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+
+    let mut buffer = collection_info.try_borrow_mut_data()?;
+    let mut state = TlvStateMut::unpack(&mut buffer)?;
+    let collection = state.get_first_value_mut::<TokenGroup>()?;
+
+    check_update_authority(update_authority_info, &collection.update_authority)?;
+
+    collection.update_max_size(data.max_size.into())?;
+
+    Ok(())
+}
+
+/// Processes an
+/// [UpdateGroupAuthority](enum.GroupInterfaceInstruction.html)
+/// instruction
+pub fn process_update_collection_authority(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: UpdateGroupAuthority,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let collection_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+
+    let mut buffer = collection_info.try_borrow_mut_data()?;
+    let mut state = TlvStateMut::unpack(&mut buffer)?;
+    let collection = state.get_first_value_mut::<TokenGroup>()?;
+
+    check_update_authority(update_authority_info, &collection.update_authority)?;
+
+    collection.update_authority = data.new_authority;
+
+    Ok(())
+}
+
 /// Processes an [InitializeMember](enum.GroupInterfaceInstruction.html)
 /// instruction
 pub fn process_initialize_collection_member(
-    _program_id: &Pubkey,
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
@@ -111,6 +255,23 @@ pub fn process_initialize_collection_member(
         return Err(TokenGroupError::MemberAccountIsGroupAccount.into());
     }
 
+    // If the member is itself a collection, it is being nested under the
+    // collection, and the trailing accounts are the collection's ancestors.
+    let member_collection_mint = {
+        let buffer = member_info.try_borrow_data()?;
+        let state = TlvStateBorrowed::unpack(&buffer)?;
+        state
+            .get_first_value::<TokenGroup>()
+            .ok()
+            .map(|collection| collection.mint)
+    };
+    if let Some(member_collection_mint) = member_collection_mint {
+        if member_collection_mint != *mint_info.key {
+            return Err(TokenCollectionError::IncorrectCollectionMint.into());
+        }
+        check_nesting(program_id, member_info, collection_info, account_info_iter)?;
+    }
+
     let mut buffer = collection_info.try_borrow_mut_data()?;
     let mut state = TlvStateMut::unpack(&mut buffer)?;
     let collection = state.get_first_value_mut::<TokenGroup>()?;
@@ -124,16 +285,254 @@ pub fn process_initialize_collection_member(
     let mut buffer = member_info.try_borrow_mut_data()?;
     let mut state = TlvStateMut::unpack(&mut buffer)?;
 
-    // This program uses `allow_repetition: true` because the same mint can be
+    // Reuse the entry of a removed membership if there is one. Otherwise,
+    // this program uses `allow_repetition: true` because the same mint can be
     // a member of multiple collections.
-    let (member, _) = state.init_value::<TokenGroupMember>(/* allow_repetition */ true)?;
+    let member = match find_membership(&state, &Pubkey::default(), &Pubkey::default()) {
+        Some(repetition_number) => {
+            state.get_value_with_repetition_mut::<TokenGroupMember>(repetition_number)?
+        }
+        None => {
+            state
+                .init_value::<TokenGroupMember>(/* allow_repetition */ true)?
+                .0
+        }
+    };
     *member = TokenGroupMember::new(mint_info.key, collection_info.key, member_number);
 
     Ok(())
 }
 
-/// Processes an `SplTokenGroupInstruction`
+/// Processes a [RemoveMember](enum.TokenCollectionInstruction.html)
+/// instruction
+pub fn process_remove_collection_member(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let member_info = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let collection_update_authority_info = next_account_info(account_info_iter)?;
+
+    if member_info.owner != program_id || collection_info.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    if member_info.key == collection_info.key {
+        return Err(TokenGroupError::MemberAccountIsGroupAccount.into());
+    }
+
+    let mut buffer = collection_info.try_borrow_mut_data()?;
+    let mut state = TlvStateMut::unpack(&mut buffer)?;
+    let collection = state.get_first_value_mut::<TokenGroup>()?;
+
+    check_update_authority(
+        collection_update_authority_info,
+        &collection.update_authority,
+    )?;
+    collection.size = u64::from(collection.size)
+        .checked_sub(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?
+        .into();
+
+    let mut buffer = member_info.try_borrow_mut_data()?;
+    let mut state = TlvStateMut::unpack(&mut buffer)?;
+    let repetition_number = (0..)
+        .map_while(|i| state.get_value_with_repetition::<TokenGroupMember>(i).ok())
+        .position(|member| member.group == *collection_info.key)
+        .ok_or(TokenCollectionError::NotACollectionMember)?;
+    let member = state.get_value_with_repetition_mut::<TokenGroupMember>(repetition_number)?;
+    *member = TokenGroupMember::default();
+
+    Ok(())
+}
+
+/// Checks that the mint is a member of the collection, directly or through
+/// the given intermediate collections: the member must belong to the first
+/// intermediate collection, each intermediate collection to the next one, and
+/// the last one to the collection.
+fn check_collection_membership(
+    program_id: &Pubkey,
+    member_info: &AccountInfo,
+    mint_info: &AccountInfo,
+    collection_info: &AccountInfo,
+    intermediate_infos: &[AccountInfo],
+) -> ProgramResult {
+    if member_info.owner != program_id || collection_info.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let mut groups = intermediate_infos
+        .iter()
+        .map(|info| info.key)
+        .chain(std::iter::once(collection_info.key));
+    {
+        let group = groups.next().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let buffer = member_info.try_borrow_data()?;
+        let state = TlvStateBorrowed::unpack(&buffer)?;
+        if find_membership(&state, mint_info.key, group).is_none() {
+            return Err(TokenCollectionError::NotACollectionMember.into());
+        }
+    }
+    for (intermediate_info, group) in intermediate_infos.iter().zip(groups) {
+        let parent = get_parent_collection(program_id, intermediate_info)?;
+        if parent.as_ref() != Some(group) {
+            return Err(TokenCollectionError::NotACollectionMember.into());
+        }
+    }
+    Ok(())
+}
+
+/// Processes a [VerifyMember](enum.TokenCollectionInstruction.html)
+/// instruction
+pub fn process_verify_collection_member(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _data: VerifyMember,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let member_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let intermediate_infos = account_info_iter.as_slice();
+
+    check_collection_membership(
+        program_id,
+        member_info,
+        mint_info,
+        collection_info,
+        intermediate_infos,
+    )
+}
+
+/// Processes a [SetRoyaltyConfig](enum.TokenCollectionInstruction.html)
+/// instruction
+pub fn process_set_royalty_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: SetRoyaltyConfig,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let collection_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    if collection_info.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    data.config.check()?;
+
+    // Make room for the config if the collection does not have one yet
+    let required_len = {
+        let buffer = collection_info.try_borrow_data()?;
+        let state = TlvStateBorrowed::unpack(&buffer)?;
+        let collection = state.get_first_value::<TokenGroup>()?;
+
+        check_update_authority(update_authority_info, &collection.update_authority)?;
+
+        if state.get_first_value::<RoyaltyConfig>().is_ok() {
+            buffer.len()
+        } else {
+            get_tlv_len(&buffer)?
+                .saturating_add(TlvStateBorrowed::get_base_len())
+                .saturating_add(size_of::<RoyaltyConfig>())
+                .max(buffer.len())
+        }
+    };
+    if required_len > collection_info.data_len() {
+        let lamports = Rent::get()?
+            .minimum_balance(required_len)
+            .saturating_sub(collection_info.lamports());
+        if lamports > 0 {
+            invoke(
+                &system_instruction::transfer(payer_info.key, collection_info.key, lamports),
+                &[
+                    payer_info.clone(),
+                    collection_info.clone(),
+                    system_program_info.clone(),
+                ],
+            )?;
+        }
+        collection_info.realloc(required_len, true)?;
+    }
+
+    let mut buffer = collection_info.try_borrow_mut_data()?;
+    let mut state = TlvStateMut::unpack(&mut buffer)?;
+    let config = match state.get_first_value_mut::<RoyaltyConfig>() {
+        Ok(config) => config,
+        Err(_) => state.init_value::<RoyaltyConfig>(false)?.0,
+    };
+    *config = data.config;
+
+    Ok(())
+}
+
+/// Processes a [GetRoyaltyPayouts](enum.TokenCollectionInstruction.html)
+/// instruction
+pub fn process_get_royalty_payouts(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: GetRoyaltyPayouts,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let member_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let intermediate_infos = account_info_iter.as_slice();
+
+    check_collection_membership(
+        program_id,
+        member_info,
+        mint_info,
+        collection_info,
+        intermediate_infos,
+    )?;
+
+    // Members inherit the config of the nearest collection that has one
+    for intermediate_info in intermediate_infos {
+        let buffer = intermediate_info.try_borrow_data()?;
+        let state = TlvStateBorrowed::unpack(&buffer)?;
+        if state.get_first_value::<RoyaltyConfig>().is_ok() {
+            return Err(TokenCollectionError::IncorrectRoyaltyCollection.into());
+        }
+    }
+
+    let buffer = collection_info.try_borrow_data()?;
+    let state = TlvStateBorrowed::unpack(&buffer)?;
+    let config = state.get_first_value::<RoyaltyConfig>()?;
+    let payouts = config.payouts(data.price.into())?;
+    set_return_data(cast_slice(&payouts));
+
+    Ok(())
+}
+
+/// Processes an `SplTokenGroupInstruction`, or a `TokenCollectionInstruction`
 pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+    if let Ok(instruction) = TokenCollectionInstruction::unpack(input) {
+        return match instruction {
+            TokenCollectionInstruction::RemoveMember(_) => {
+                msg!("Instruction: RemoveCollectionMember");
+                process_remove_collection_member(program_id, accounts)
+            }
+            TokenCollectionInstruction::VerifyMember(data) => {
+                msg!("Instruction: VerifyCollectionMember");
+                process_verify_collection_member(program_id, accounts, data)
+            }
+            TokenCollectionInstruction::SetRoyaltyConfig(data) => {
+                msg!("Instruction: SetRoyaltyConfig");
+                process_set_royalty_config(program_id, accounts, data)
+            }
+            TokenCollectionInstruction::GetRoyaltyPayouts(data) => {
+                msg!("Instruction: GetRoyaltyPayouts");
+                process_get_royalty_payouts(program_id, accounts, data)
+            }
+        };
+    }
+
     let instruction = TokenGroupInstruction::unpack(input)?;
     match instruction {
         TokenGroupInstruction::InitializeGroup(data) => {
@@ -142,17 +541,11 @@ pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> P
         }
         TokenGroupInstruction::UpdateGroupMaxSize(data) => {
             msg!("Instruction: UpdateCollectionMaxSize");
-            // Same functionality as the example program
-            spl_token_group_example::processor::process_update_group_max_size(
-                program_id, accounts, data,
-            )
+            process_update_collection_max_size(program_id, accounts, data)
         }
         TokenGroupInstruction::UpdateGroupAuthority(data) => {
             msg!("Instruction: UpdateCollectionAuthority");
-            // Same functionality as the example program
-            spl_token_group_example::processor::process_update_group_authority(
-                program_id, accounts, data,
-            )
+            process_update_collection_authority(program_id, accounts, data)
         }
         TokenGroupInstruction::InitializeMember(_) => {
             msg!("Instruction: InitializeCollectionMember");
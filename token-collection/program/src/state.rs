@@ -0,0 +1,144 @@
+//! State types
+
+use {
+    crate::error::TokenCollectionError,
+    bytemuck::{Pod, Zeroable},
+    solana_program::{program_error::ProgramError, pubkey::Pubkey},
+    spl_discriminator::SplDiscriminate,
+    spl_pod::primitives::{PodU16, PodU64},
+};
+
+/// Maximum number of creators sharing the royalties of a collection
+pub const MAX_CREATORS: usize = 5;
+
+/// Basis points making up the whole of a sale price
+pub const MAX_BASIS_POINTS: u16 = 10_000;
+
+/// A creator receiving part of the royalties of a collection
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct Creator {
+    /// The address royalties are paid to
+    pub address: Pubkey,
+    /// The percentage of the royalties paid to this creator
+    pub share: u8,
+}
+
+/// Royalty configuration stored as a TLV entry on a collection account, and
+/// inherited by all its members. Members of a nested collection inherit the
+/// configuration of the nearest collection above them that has one.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable, SplDiscriminate)]
+#[discriminator_hash_input("spl_token_collection:royalty_config")]
+pub struct RoyaltyConfig {
+    /// The royalty rate, in basis points of the sale price
+    pub basis_points: PodU16,
+    /// The number of creators in use in `creators`
+    pub creator_count: u8,
+    /// The creators sharing the royalties, the unused ones being zeroed
+    pub creators: [Creator; MAX_CREATORS],
+}
+
+/// The royalty owed to a creator for a sale
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct RoyaltyPayout {
+    /// The address the royalty is paid to
+    pub creator: Pubkey,
+    /// The amount owed
+    pub amount: PodU64,
+}
+
+impl RoyaltyConfig {
+    /// Creates a new `RoyaltyConfig`, checking the rate and the creator shares
+    pub fn new(basis_points: u16, creators: &[Creator]) -> Result<Self, ProgramError> {
+        let mut config = Self {
+            basis_points: basis_points.into(),
+            creator_count: u8::try_from(creators.len())
+                .map_err(|_| TokenCollectionError::InvalidRoyaltyConfig)?,
+            ..Self::default()
+        };
+        config
+            .creators
+            .get_mut(..creators.len())
+            .ok_or(TokenCollectionError::InvalidRoyaltyConfig)?
+            .copy_from_slice(creators);
+        config.check()?;
+        Ok(config)
+    }
+
+    /// Checks that the rate is at most 100%, and that the shares of distinct
+    /// creators add up to 100% if royalties are due
+    pub fn check(&self) -> Result<(), ProgramError> {
+        let creators = self.creators()?;
+        let basis_points = u16::from(self.basis_points);
+        if basis_points > MAX_BASIS_POINTS {
+            return Err(TokenCollectionError::InvalidRoyaltyConfig.into());
+        }
+        let total_share: u32 = creators.iter().map(|c| u32::from(c.share)).sum();
+        if (basis_points > 0 || !creators.is_empty()) && total_share != 100 {
+            return Err(TokenCollectionError::InvalidRoyaltyConfig.into());
+        }
+        for (i, creator) in creators.iter().enumerate() {
+            if creators[..i].iter().any(|c| c.address == creator.address) {
+                return Err(TokenCollectionError::InvalidRoyaltyConfig.into());
+            }
+        }
+        if self.creators[creators.len()..]
+            .iter()
+            .any(|c| *c != Creator::default())
+        {
+            return Err(TokenCollectionError::InvalidRoyaltyConfig.into());
+        }
+        Ok(())
+    }
+
+    /// The creators in use
+    pub fn creators(&self) -> Result<&[Creator], ProgramError> {
+        self.creators
+            .get(..usize::from(self.creator_count))
+            .ok_or_else(|| TokenCollectionError::InvalidRoyaltyConfig.into())
+    }
+
+    /// Computes the royalties owed to each creator for a sale at `price`.
+    ///
+    /// Each creator gets their share of the royalty rounded down, and the
+    /// rounding remainder goes to the first creator, so that the payouts add
+    /// up to the royalty on the whole price.
+    pub fn payouts(&self, price: u64) -> Result<Vec<RoyaltyPayout>, ProgramError> {
+        let creators = self.creators()?;
+        let royalty = u128::from(price)
+            .checked_mul(u128::from(u16::from(self.basis_points)))
+            .and_then(|r| r.checked_div(u128::from(MAX_BASIS_POINTS)))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        let mut payouts = creators
+            .iter()
+            .map(|creator| {
+                let amount = royalty
+                    .checked_mul(u128::from(creator.share))
+                    .and_then(|a| a.checked_div(100))
+                    .and_then(|a| u64::try_from(a).ok())
+                    .ok_or(ProgramError::ArithmeticOverflow)?;
+                Ok(RoyaltyPayout {
+                    creator: creator.address,
+                    amount: amount.into(),
+                })
+            })
+            .collect::<Result<Vec<_>, ProgramError>>()?;
+        let paid: u128 = payouts
+            .iter()
+            .map(|p| u128::from(u64::from(p.amount)))
+            .sum();
+        if let Some(first) = payouts.first_mut() {
+            let remainder = royalty
+                .checked_sub(paid)
+                .and_then(|r| u64::try_from(r).ok())
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            first.amount = u64::from(first.amount)
+                .checked_add(remainder)
+                .ok_or(ProgramError::ArithmeticOverflow)?
+                .into();
+        }
+        Ok(payouts)
+    }
+}
@@ -4,16 +4,36 @@ mod setup;
 
 use {
     setup::{setup_group, setup_mint_and_metadata, setup_program_test},
-    solana_program::{pubkey::Pubkey, system_instruction},
-    solana_program_test::tokio,
-    solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction},
-    spl_token_client::token::Token,
+    solana_program::{
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        system_instruction,
+    },
+    solana_program_test::{tokio, ProgramTestBanksClientExt, ProgramTestContext},
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+        transaction::{Transaction, TransactionError},
+    },
+    spl_token_client::{
+        client::{ProgramBanksClientProcessTransaction, ProgramClient},
+        token::Token,
+    },
+    spl_token_collection::{
+        error::TokenCollectionError,
+        instruction::{
+            get_royalty_payouts, initialize_nested_collection, remove_member, set_royalty_config,
+            verify_member,
+        },
+        state::{Creator, RoyaltyConfig, RoyaltyPayout},
+    },
     spl_token_group_interface::{
         instruction::initialize_member,
         state::{TokenGroup, TokenGroupMember},
     },
     spl_token_metadata_interface::state::TokenMetadata,
     spl_type_length_value::state::{TlvState, TlvStateBorrowed},
+    std::sync::Arc,
 };
 
 /// All snakes are reptiles, but not all reptiles are snakes.
@@ -387,3 +407,616 @@ async fn test_token_collection() {
     let membership = state.get_first_value::<TokenGroupMember>().unwrap();
     assert_eq!(membership.group, reptile.pubkey(),);
 }
+
+/// Set up a mint with metadata, returning the mint and its authority
+async fn setup_mint(
+    client: &Arc<dyn ProgramClient<ProgramBanksClientProcessTransaction>>,
+    payer: &Arc<Keypair>,
+    name: &str,
+) -> (Keypair, Keypair) {
+    let mint = Keypair::new();
+    let mint_authority = Keypair::new();
+    let metadata = TokenMetadata {
+        name: name.to_string(),
+        ..TokenMetadata::default()
+    };
+    setup_mint_and_metadata(
+        &Token::new(
+            client.clone(),
+            &spl_token_2022::id(),
+            &mint.pubkey(),
+            Some(0),
+            payer.clone(),
+        ),
+        &mint,
+        &mint_authority,
+        &metadata,
+        payer.clone(),
+    )
+    .await;
+    (mint, mint_authority)
+}
+
+async fn create_account(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    account: &Keypair,
+    space: usize,
+) {
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::create_account(
+            &context.payer.pubkey(),
+            &account.pubkey(),
+            rent.minimum_balance(space),
+            space.try_into().unwrap(),
+            program_id,
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, account],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+async fn process_instruction(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), TransactionError> {
+    context.last_blockhash = context
+        .banks_client
+        .get_new_latest_blockhash(&context.last_blockhash)
+        .await
+        .unwrap();
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|e| e.unwrap())
+}
+
+async fn get_collection_size(context: &mut ProgramTestContext, collection: &Pubkey) -> u64 {
+    let buffer = context
+        .banks_client
+        .get_account(*collection)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let state = TlvStateBorrowed::unpack(&buffer).unwrap();
+    u64::from(state.get_first_value::<TokenGroup>().unwrap().size)
+}
+
+fn collection_error(error: TokenCollectionError) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+}
+
+/// Animals contain reptiles, which contain snakes, which contain pythons.
+#[tokio::test]
+async fn test_nested_collections() {
+    let program_id = Pubkey::new_unique();
+    let (context, client, payer) = setup_program_test(&program_id).await;
+
+    let (animal_mint, animal_mint_authority) = setup_mint(&client, &payer, "Animals").await;
+    let (reptile_mint, reptile_mint_authority) = setup_mint(&client, &payer, "Reptiles").await;
+    let (snake_mint, snake_mint_authority) = setup_mint(&client, &payer, "Snakes").await;
+    let (python_mint, python_mint_authority) = setup_mint(&client, &payer, "Python").await;
+
+    let mut context = context.lock().await;
+
+    // Collections get room for a membership in a parent collection
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let collection_space = TlvStateBorrowed::get_base_len() + std::mem::size_of::<TokenGroup>();
+    let member_space = TlvStateBorrowed::get_base_len() + std::mem::size_of::<TokenGroupMember>();
+    let nested_collection_space = collection_space + member_space;
+    let nested_collection_rent_lamports = rent.minimum_balance(nested_collection_space);
+
+    let update_authority = Keypair::new();
+    let animal = Keypair::new();
+    let reptile = Keypair::new();
+    let snake = Keypair::new();
+    for (collection, mint, mint_authority) in [
+        (&animal, &animal_mint, &animal_mint_authority),
+        (&reptile, &reptile_mint, &reptile_mint_authority),
+        (&snake, &snake_mint, &snake_mint_authority),
+    ] {
+        setup_group(
+            &mut context,
+            &program_id,
+            collection,
+            mint,
+            mint_authority,
+            Some(update_authority.pubkey()),
+            10,
+            nested_collection_rent_lamports,
+            nested_collection_space,
+        )
+        .await;
+    }
+
+    // Reptiles are animals, and snakes are reptiles
+    process_instruction(
+        &mut context,
+        initialize_nested_collection(
+            &program_id,
+            &reptile.pubkey(),
+            &reptile_mint.pubkey(),
+            &reptile_mint_authority.pubkey(),
+            &animal.pubkey(),
+            &update_authority.pubkey(),
+            &[],
+        ),
+        &[&reptile_mint_authority, &update_authority],
+    )
+    .await
+    .unwrap();
+    process_instruction(
+        &mut context,
+        initialize_nested_collection(
+            &program_id,
+            &snake.pubkey(),
+            &snake_mint.pubkey(),
+            &snake_mint_authority.pubkey(),
+            &reptile.pubkey(),
+            &update_authority.pubkey(),
+            &[animal.pubkey()],
+        ),
+        &[&snake_mint_authority, &update_authority],
+    )
+    .await
+    .unwrap();
+
+    // A collection can only have one parent
+    let error = process_instruction(
+        &mut context,
+        initialize_nested_collection(
+            &program_id,
+            &snake.pubkey(),
+            &snake_mint.pubkey(),
+            &snake_mint_authority.pubkey(),
+            &animal.pubkey(),
+            &update_authority.pubkey(),
+            &[],
+        ),
+        &[&snake_mint_authority, &update_authority],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        collection_error(TokenCollectionError::CollectionAlreadyNested)
+    );
+
+    // Animals cannot be snakes
+    let error = process_instruction(
+        &mut context,
+        initialize_nested_collection(
+            &program_id,
+            &animal.pubkey(),
+            &animal_mint.pubkey(),
+            &animal_mint_authority.pubkey(),
+            &snake.pubkey(),
+            &update_authority.pubkey(),
+            &[reptile.pubkey(), animal.pubkey()],
+        ),
+        &[&animal_mint_authority, &update_authority],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        collection_error(TokenCollectionError::CollectionCycle)
+    );
+
+    // A python is a snake
+    let python = Keypair::new();
+    create_account(&mut context, &program_id, &python, member_space).await;
+    process_instruction(
+        &mut context,
+        initialize_member(
+            &program_id,
+            &python.pubkey(),
+            &python_mint.pubkey(),
+            &python_mint_authority.pubkey(),
+            &snake.pubkey(),
+            &update_authority.pubkey(),
+        ),
+        &[&python_mint_authority, &update_authority],
+    )
+    .await
+    .unwrap();
+
+    // ... and therefore a reptile and an animal
+    for (collection, intermediate_collections) in [
+        (&snake, vec![]),
+        (&reptile, vec![snake.pubkey()]),
+        (&animal, vec![snake.pubkey(), reptile.pubkey()]),
+    ] {
+        process_instruction(
+            &mut context,
+            verify_member(
+                &program_id,
+                &python.pubkey(),
+                &python_mint.pubkey(),
+                &collection.pubkey(),
+                &intermediate_collections,
+            ),
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    // Skipping a collection along the way fails
+    let error = process_instruction(
+        &mut context,
+        verify_member(
+            &program_id,
+            &python.pubkey(),
+            &python_mint.pubkey(),
+            &animal.pubkey(),
+            &[snake.pubkey()],
+        ),
+        &[],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        collection_error(TokenCollectionError::NotACollectionMember)
+    );
+
+    // Only members held by the program can be removed
+    let mut forged_member = context
+        .banks_client
+        .get_account(python.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    forged_member.owner = Pubkey::new_unique();
+    let forged_member_address = Pubkey::new_unique();
+    context.set_account(&forged_member_address, &forged_member.into());
+    let error = process_instruction(
+        &mut context,
+        remove_member(
+            &program_id,
+            &forged_member_address,
+            &snake.pubkey(),
+            &update_authority.pubkey(),
+        ),
+        &[&update_authority],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(0, InstructionError::IllegalOwner)
+    );
+
+    // The python leaves the snakes
+    assert_eq!(get_collection_size(&mut context, &snake.pubkey()).await, 1);
+    process_instruction(
+        &mut context,
+        remove_member(
+            &program_id,
+            &python.pubkey(),
+            &snake.pubkey(),
+            &update_authority.pubkey(),
+        ),
+        &[&update_authority],
+    )
+    .await
+    .unwrap();
+    assert_eq!(get_collection_size(&mut context, &snake.pubkey()).await, 0);
+    let error = process_instruction(
+        &mut context,
+        verify_member(
+            &program_id,
+            &python.pubkey(),
+            &python_mint.pubkey(),
+            &snake.pubkey(),
+            &[],
+        ),
+        &[],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        collection_error(TokenCollectionError::NotACollectionMember)
+    );
+
+    // ... and can join the reptiles directly, reusing its membership entry
+    process_instruction(
+        &mut context,
+        initialize_member(
+            &program_id,
+            &python.pubkey(),
+            &python_mint.pubkey(),
+            &python_mint_authority.pubkey(),
+            &reptile.pubkey(),
+            &update_authority.pubkey(),
+        ),
+        &[&python_mint_authority, &update_authority],
+    )
+    .await
+    .unwrap();
+    process_instruction(
+        &mut context,
+        verify_member(
+            &program_id,
+            &python.pubkey(),
+            &python_mint.pubkey(),
+            &animal.pubkey(),
+            &[reptile.pubkey()],
+        ),
+        &[],
+    )
+    .await
+    .unwrap();
+}
+
+/// Royalties set on animals apply to pythons, until reptiles set their own.
+#[tokio::test]
+async fn test_royalty_payouts() {
+    let program_id = Pubkey::new_unique();
+    let (context, client, payer) = setup_program_test(&program_id).await;
+
+    let (animal_mint, animal_mint_authority) = setup_mint(&client, &payer, "Animals").await;
+    let (reptile_mint, reptile_mint_authority) = setup_mint(&client, &payer, "Reptiles").await;
+    let (python_mint, python_mint_authority) = setup_mint(&client, &payer, "Python").await;
+
+    let mut context = context.lock().await;
+
+    // Collections get room for a parent collection, and grow when they get a
+    // royalty config
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let member_space = TlvStateBorrowed::get_base_len() + std::mem::size_of::<TokenGroupMember>();
+    let collection_space =
+        TlvStateBorrowed::get_base_len() + std::mem::size_of::<TokenGroup>() + member_space;
+    let collection_rent_lamports = rent.minimum_balance(collection_space);
+    let payer_key = context.payer.pubkey();
+
+    let update_authority = Keypair::new();
+    let animal = Keypair::new();
+    let reptile = Keypair::new();
+    for (collection, mint, mint_authority) in [
+        (&animal, &animal_mint, &animal_mint_authority),
+        (&reptile, &reptile_mint, &reptile_mint_authority),
+    ] {
+        setup_group(
+            &mut context,
+            &program_id,
+            collection,
+            mint,
+            mint_authority,
+            Some(update_authority.pubkey()),
+            10,
+            collection_rent_lamports,
+            collection_space,
+        )
+        .await;
+    }
+    process_instruction(
+        &mut context,
+        initialize_nested_collection(
+            &program_id,
+            &reptile.pubkey(),
+            &reptile_mint.pubkey(),
+            &reptile_mint_authority.pubkey(),
+            &animal.pubkey(),
+            &update_authority.pubkey(),
+            &[],
+        ),
+        &[&reptile_mint_authority, &update_authority],
+    )
+    .await
+    .unwrap();
+    let python = Keypair::new();
+    create_account(&mut context, &program_id, &python, member_space).await;
+    process_instruction(
+        &mut context,
+        initialize_member(
+            &program_id,
+            &python.pubkey(),
+            &python_mint.pubkey(),
+            &python_mint_authority.pubkey(),
+            &reptile.pubkey(),
+            &update_authority.pubkey(),
+        ),
+        &[&python_mint_authority, &update_authority],
+    )
+    .await
+    .unwrap();
+
+    // Creator shares must add up to 100%
+    let first_creator = Pubkey::new_unique();
+    let second_creator = Pubkey::new_unique();
+    let mut config = RoyaltyConfig::new(
+        333,
+        &[
+            Creator {
+                address: first_creator,
+                share: 70,
+            },
+            Creator {
+                address: second_creator,
+                share: 30,
+            },
+        ],
+    )
+    .unwrap();
+    config.creators[1].share = 20;
+    let error = process_instruction(
+        &mut context,
+        set_royalty_config(
+            &program_id,
+            &animal.pubkey(),
+            &update_authority.pubkey(),
+            &payer_key,
+            config,
+        ),
+        &[&update_authority],
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        collection_error(TokenCollectionError::InvalidRoyaltyConfig)
+    );
+
+    config.creators[1].share = 30;
+    process_instruction(
+        &mut context,
+        set_royalty_config(
+            &program_id,
+            &animal.pubkey(),
+            &update_authority.pubkey(),
+            &payer_key,
+            config,
+        ),
+        &[&update_authority],
+    )
+    .await
+    .unwrap();
+
+    // The royalty on 1000 is 33, split 23.1 / 9.9, with the remainder going to
+    // the first creator
+    let payouts = simulate_royalty_payouts(
+        &mut context,
+        get_royalty_payouts(
+            &program_id,
+            &python.pubkey(),
+            &python_mint.pubkey(),
+            &animal.pubkey(),
+            &[reptile.pubkey()],
+            1_000,
+        ),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        payouts,
+        vec![
+            RoyaltyPayout {
+                creator: first_creator,
+                amount: 24.into(),
+            },
+            RoyaltyPayout {
+                creator: second_creator,
+                amount: 9.into(),
+            },
+        ]
+    );
+
+    // Once reptiles have their own config, it takes precedence
+    let reptile_config = RoyaltyConfig::new(
+        1_000,
+        &[Creator {
+            address: second_creator,
+            share: 100,
+        }],
+    )
+    .unwrap();
+    process_instruction(
+        &mut context,
+        set_royalty_config(
+            &program_id,
+            &reptile.pubkey(),
+            &update_authority.pubkey(),
+            &payer_key,
+            reptile_config,
+        ),
+        &[&update_authority],
+    )
+    .await
+    .unwrap();
+
+    // Reptiles already used all their space, so the payer covered the growth
+    let reptile_account = context
+        .banks_client
+        .get_account(reptile.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        reptile_account.data.len(),
+        collection_space + TlvStateBorrowed::get_base_len() + std::mem::size_of::<RoyaltyConfig>()
+    );
+    assert!(rent.is_exempt(reptile_account.lamports, reptile_account.data.len()));
+    let error = simulate_royalty_payouts(
+        &mut context,
+        get_royalty_payouts(
+            &program_id,
+            &python.pubkey(),
+            &python_mint.pubkey(),
+            &animal.pubkey(),
+            &[reptile.pubkey()],
+            1_000,
+        ),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error,
+        collection_error(TokenCollectionError::IncorrectRoyaltyCollection)
+    );
+    let payouts = simulate_royalty_payouts(
+        &mut context,
+        get_royalty_payouts(
+            &program_id,
+            &python.pubkey(),
+            &python_mint.pubkey(),
+            &reptile.pubkey(),
+            &[],
+            1_000,
+        ),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        payouts,
+        vec![RoyaltyPayout {
+            creator: second_creator,
+            amount: 100.into(),
+        }]
+    );
+}
+
+async fn simulate_royalty_payouts(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+) -> Result<Vec<RoyaltyPayout>, TransactionError> {
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    let simulation = context
+        .banks_client
+        .simulate_transaction(transaction)
+        .await
+        .unwrap();
+    simulation.result.unwrap()?;
+    let return_data = simulation
+        .simulation_details
+        .unwrap()
+        .return_data
+        .unwrap()
+        .data;
+    Ok(bytemuck::try_cast_slice(&return_data).unwrap().to_vec())
+}
@@ -1,5 +1,8 @@
 use {
-    clap::{crate_description, crate_name, crate_version, Arg, ArgAction, Command},
+    clap::{
+        crate_description, crate_name, crate_version, value_parser, Arg, ArgAction, ArgMatches,
+        Command,
+    },
     solana_clap_v3_utils::{
         input_parsers::{
             parse_url_or_moniker,
@@ -12,6 +15,7 @@ use {
     solana_remote_wallet::remote_wallet::RemoteWalletManager,
     solana_sdk::{
         commitment_config::CommitmentConfig,
+        instruction::Instruction,
         message::Message,
         program_option::COption,
         pubkey::Pubkey,
@@ -22,12 +26,16 @@ use {
     spl_token_2022::{
         extension::StateWithExtensions,
         state::{Account, Mint},
+        ui_amount_to_amount,
     },
     spl_token_client::{
         client::{ProgramRpcClient, ProgramRpcClientSendTransaction, RpcClientResponse},
         token::Token,
     },
-    spl_token_upgrade::{get_token_upgrade_authority_address, instruction::exchange},
+    spl_token_upgrade::{
+        get_token_upgrade_authority_address,
+        instruction::{exchange, initialize_exchange_config, reclaim, reverse_exchange},
+    },
     std::{error::Error, process::exit, rc::Rc, sync::Arc},
 };
 
@@ -48,6 +56,43 @@ async fn get_mint_owner_checked(
     Ok(mint_account.owner)
 }
 
+/// Returns the token program owning the mint, along with its decimals
+async fn get_mint_checked(
+    rpc_client: &RpcClient,
+    mint: &Pubkey,
+) -> Result<(Pubkey, u8), Box<dyn Error>> {
+    let mint_account = rpc_client.get_account(mint).await?;
+    let mint_state = StateWithExtensions::<Mint>::unpack(&mint_account.data)
+        .map_err(|_| format!("Account {} is not a valid mint", mint))?;
+    Ok((mint_account.owner, mint_state.base.decimals))
+}
+
+async fn send_transaction(
+    rpc_client: &RpcClient,
+    payer: &Arc<dyn Signer>,
+    instructions: &[Instruction],
+    bulk_signers: &[Arc<dyn Signer>],
+) -> Result<Signature, Box<dyn Error>> {
+    let mut transaction =
+        Transaction::new_unsigned(Message::new(instructions, Some(&payer.pubkey())));
+
+    let blockhash = rpc_client
+        .get_latest_blockhash()
+        .await
+        .map_err(|err| format!("error: unable to get latest blockhash: {}", err))?;
+
+    transaction
+        .try_sign(bulk_signers, blockhash)
+        .map_err(|err| format!("error: failed to sign transaction: {}", err))?;
+
+    let signature = rpc_client
+        .send_and_confirm_transaction_with_spinner(&transaction)
+        .await
+        .map_err(|err| format!("error: send transaction: {}", err))?;
+
+    Ok(signature)
+}
+
 async fn escrow_exists_checked(
     rpc_client: &RpcClient,
     escrow: &Pubkey,
@@ -81,20 +126,27 @@ async fn process_create_escrow_account(
     original_mint: &Pubkey,
     new_mint: &Pubkey,
     account_keypair: Option<&dyn Signer>,
+    for_original_mint: bool,
 ) -> Result<RpcClientResponse, Box<dyn Error>> {
-    let _ = get_mint_owner_checked(rpc_client, original_mint).await?;
+    let original_program_id = get_mint_owner_checked(rpc_client, original_mint).await?;
     let new_program_id = get_mint_owner_checked(rpc_client, new_mint).await?;
     let escrow_authority =
         get_token_upgrade_authority_address(original_mint, new_mint, &spl_token_upgrade::id());
 
+    // reverse exchanges pay out of an escrow of original tokens
+    let (escrow_program_id, escrow_mint) = if for_original_mint {
+        (original_program_id, original_mint)
+    } else {
+        (new_program_id, new_mint)
+    };
     let program_client = Arc::new(ProgramRpcClient::new(
         rpc_client.clone(),
         ProgramRpcClientSendTransaction,
     ));
     let token = Token::new(
         program_client.clone(),
-        &new_program_id,
-        new_mint,
+        &escrow_program_id,
+        escrow_mint,
         None,
         payer.clone(),
     );
@@ -103,7 +155,7 @@ async fn process_create_escrow_account(
         .map(|k| k.pubkey())
         .unwrap_or_else(|| token.get_associated_token_address(&escrow_authority));
 
-    if escrow_exists_checked(rpc_client, &escrow, &escrow_authority, new_mint).await? {
+    if escrow_exists_checked(rpc_client, &escrow, &escrow_authority, escrow_mint).await? {
         return Err(format!(
             "Escrow account {} already exists, not doing anything",
             escrow
@@ -166,7 +218,9 @@ async fn process_exchange(
         "Burning tokens from account {}, receiving tokens into account {}",
         burn_from, destination
     );
-    let mut transaction = Transaction::new_unsigned(Message::new(
+    send_transaction(
+        rpc_client,
+        payer,
         &[exchange(
             &spl_token_upgrade::id(),
             &burn_from,
@@ -179,24 +233,305 @@ async fn process_exchange(
             &owner.pubkey(),
             &multisig_pubkeys.iter().collect::<Vec<_>>(),
         )],
-        Some(&payer.pubkey()),
-    ));
+        &bulk_signers,
+    )
+    .await
+}
 
-    let blockhash = rpc_client
-        .get_latest_blockhash()
-        .await
-        .map_err(|err| format!("error: unable to get latest blockhash: {}", err))?;
+#[allow(clippy::too_many_arguments)]
+async fn process_create_exchange_config(
+    rpc_client: &Arc<RpcClient>,
+    payer: &Arc<dyn Signer>,
+    original_mint: &Pubkey,
+    new_mint: &Pubkey,
+    admin: &Arc<dyn Signer>,
+    (original_ui_amount, new_ui_amount): (f64, f64),
+    exchange_deadline: Option<i64>,
+    reverse_deadline: Option<i64>,
+) -> Result<Signature, Box<dyn Error>> {
+    let (_, original_decimals) = get_mint_checked(rpc_client, original_mint).await?;
+    let (_, new_decimals) = get_mint_checked(rpc_client, new_mint).await?;
+    let original_amount = ui_amount_to_amount(original_ui_amount, original_decimals);
+    let new_amount = ui_amount_to_amount(new_ui_amount, new_decimals);
+    if original_amount == 0 || new_amount == 0 {
+        return Err("Exchange rate amounts must be greater than zero".into());
+    }
 
-    transaction
-        .try_sign(&bulk_signers, blockhash)
-        .map_err(|err| format!("error: failed to sign transaction: {}", err))?;
+    println!(
+        "Exchanging {} original tokens for {} new tokens",
+        original_ui_amount, new_ui_amount
+    );
+    send_transaction(
+        rpc_client,
+        payer,
+        &[initialize_exchange_config(
+            &spl_token_upgrade::id(),
+            &payer.pubkey(),
+            original_mint,
+            new_mint,
+            &admin.pubkey(),
+            original_amount,
+            new_amount,
+            exchange_deadline.unwrap_or_default(),
+            reverse_deadline.unwrap_or_default(),
+        )],
+        &[payer.clone(), admin.clone()],
+    )
+    .await
+}
 
-    let signature = rpc_client
-        .send_and_confirm_transaction_with_spinner(&transaction)
-        .await
-        .map_err(|err| format!("error: send transaction: {}", err))?;
+#[allow(clippy::too_many_arguments)]
+async fn process_reverse_exchange(
+    rpc_client: &Arc<RpcClient>,
+    payer: &Arc<dyn Signer>,
+    original_mint: &Pubkey,
+    new_mint: &Pubkey,
+    ui_amount: f64,
+    owner: &Arc<dyn Signer>,
+    source: Option<Pubkey>,
+    new_escrow: Option<Pubkey>,
+    original_escrow: Option<Pubkey>,
+    destination: Option<Pubkey>,
+    multisig_pubkeys: &[Pubkey],
+    bulk_signers: Vec<Arc<dyn Signer>>,
+) -> Result<Signature, Box<dyn Error>> {
+    let (original_program_id, _) = get_mint_checked(rpc_client, original_mint).await?;
+    let (new_program_id, new_decimals) = get_mint_checked(rpc_client, new_mint).await?;
+    let escrow_authority =
+        get_token_upgrade_authority_address(original_mint, new_mint, &spl_token_upgrade::id());
+    let amount = ui_amount_to_amount(ui_amount, new_decimals);
 
-    Ok(signature)
+    let source = source.unwrap_or_else(|| {
+        get_associated_token_address_with_program_id(&owner.pubkey(), new_mint, &new_program_id)
+    });
+    let new_escrow = new_escrow.unwrap_or_else(|| {
+        get_associated_token_address_with_program_id(&escrow_authority, new_mint, &new_program_id)
+    });
+    let original_escrow = original_escrow.unwrap_or_else(|| {
+        get_associated_token_address_with_program_id(
+            &escrow_authority,
+            original_mint,
+            &original_program_id,
+        )
+    });
+    let destination = destination.unwrap_or_else(|| {
+        get_associated_token_address_with_program_id(
+            &owner.pubkey(),
+            original_mint,
+            &original_program_id,
+        )
+    });
+
+    println!(
+        "Returning tokens from account {}, receiving tokens into account {}",
+        source, destination
+    );
+    send_transaction(
+        rpc_client,
+        payer,
+        &[reverse_exchange(
+            &spl_token_upgrade::id(),
+            &source,
+            new_mint,
+            &new_escrow,
+            &original_escrow,
+            &destination,
+            original_mint,
+            &original_program_id,
+            &new_program_id,
+            &owner.pubkey(),
+            &multisig_pubkeys.iter().collect::<Vec<_>>(),
+            amount,
+        )],
+        &bulk_signers,
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn process_reclaim(
+    rpc_client: &Arc<RpcClient>,
+    payer: &Arc<dyn Signer>,
+    original_mint: &Pubkey,
+    new_mint: &Pubkey,
+    admin: &Arc<dyn Signer>,
+    from_original_escrow: bool,
+    escrow: Option<Pubkey>,
+    destination: Option<Pubkey>,
+) -> Result<Signature, Box<dyn Error>> {
+    let escrow_mint = if from_original_escrow {
+        original_mint
+    } else {
+        new_mint
+    };
+    let escrow_program_id = get_mint_owner_checked(rpc_client, escrow_mint).await?;
+    let escrow_authority =
+        get_token_upgrade_authority_address(original_mint, new_mint, &spl_token_upgrade::id());
+
+    let escrow = escrow.unwrap_or_else(|| {
+        get_associated_token_address_with_program_id(
+            &escrow_authority,
+            escrow_mint,
+            &escrow_program_id,
+        )
+    });
+    let destination = destination.unwrap_or_else(|| {
+        get_associated_token_address_with_program_id(
+            &admin.pubkey(),
+            escrow_mint,
+            &escrow_program_id,
+        )
+    });
+
+    println!(
+        "Reclaiming tokens from escrow {} into account {}",
+        escrow, destination
+    );
+    send_transaction(
+        rpc_client,
+        payer,
+        &[reclaim(
+            &spl_token_upgrade::id(),
+            &escrow,
+            escrow_mint,
+            &destination,
+            &escrow_program_id,
+            original_mint,
+            new_mint,
+            &admin.pubkey(),
+        )],
+        &[payer.clone(), admin.clone()],
+    )
+    .await
+}
+
+struct TransferSigners {
+    owner: Arc<dyn Signer>,
+    multisig_pubkeys: Vec<Pubkey>,
+    bulk_signers: Vec<Arc<dyn Signer>>,
+}
+
+/// Collects the owner and multisig signers of the account tokens are taken
+/// from
+fn get_transfer_signers(
+    matches: &ArgMatches,
+    config: &Config,
+    wallet_manager: &mut Option<Rc<RemoteWalletManager>>,
+) -> Result<TransferSigners, Box<dyn Error>> {
+    let mut bulk_signers = vec![config.payer.clone()];
+    let mut multisig_pubkeys = vec![];
+
+    if let Some(sources) = matches.try_get_many::<SignerSource>("multisig_signer")? {
+        for (i, source) in sources.enumerate() {
+            let name = format!("{}-{}", "multisig_signer", i.saturating_add(1));
+            let signer =
+                signer_from_source(matches, source, &name, wallet_manager).unwrap_or_else(|e| {
+                    eprint!("error parsing multisig signer: {}", e);
+                    exit(1);
+                });
+            let signer_pubkey = signer.pubkey();
+            let signer = Arc::from(signer);
+            if !bulk_signers.contains(&signer) {
+                bulk_signers.push(signer);
+            }
+            if !multisig_pubkeys.contains(&signer_pubkey) {
+                multisig_pubkeys.push(signer_pubkey);
+            }
+        }
+    }
+
+    let signer_config = SignerFromPathConfig {
+        allow_null_signer: !multisig_pubkeys.is_empty(),
+    };
+    let owner = if let Ok(Some((signer, _))) =
+        SignerSource::try_get_signer(matches, "owner", wallet_manager)
+    {
+        Arc::from(signer)
+    } else {
+        config.payer.clone()
+    };
+    if !signer_config.allow_null_signer && !bulk_signers.contains(&owner) {
+        bulk_signers.push(owner.clone());
+    }
+
+    Ok(TransferSigners {
+        owner,
+        multisig_pubkeys,
+        bulk_signers,
+    })
+}
+
+/// Returns the admin keypair, defaulting to the fee payer
+fn get_admin(
+    matches: &ArgMatches,
+    config: &Config,
+    wallet_manager: &mut Option<Rc<RemoteWalletManager>>,
+) -> Arc<dyn Signer> {
+    if let Ok(Some((signer, _))) = SignerSource::try_get_signer(matches, "admin", wallet_manager) {
+        Arc::from(signer)
+    } else {
+        config.payer.clone()
+    }
+}
+
+fn parse_exchange_rate(rate: &str) -> Result<(f64, f64), String> {
+    let (original, new) = rate.split_once(':').ok_or_else(|| {
+        format!(
+            "Exchange rate must look like ORIGINAL:NEW, received {}",
+            rate
+        )
+    })?;
+    let parse = |amount: &str| {
+        amount
+            .parse::<f64>()
+            .ok()
+            .filter(|amount| *amount > 0.0)
+            .ok_or_else(|| format!("Invalid exchange rate amount: {}", amount))
+    };
+    Ok((parse(original)?, parse(new)?))
+}
+
+fn multisig_signer_arg() -> Arg<'static> {
+    Arg::new("multisig_signer")
+        .long("multisig-signer")
+        .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+        .value_name("MULTISIG_SIGNER")
+        .takes_value(true)
+        .action(ArgAction::Append)
+        .min_values(0)
+        .max_values(spl_token_2022::instruction::MAX_SIGNERS)
+        .help("Member signer of a multisig account")
+}
+
+fn mint_args(command: Command<'static>) -> Command<'static> {
+    command
+        .arg(
+            Arg::new("original_mint")
+                .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+                .value_name("ADDRESS")
+                .required(true)
+                .takes_value(true)
+                .index(1)
+                .help("Original mint address, whose tokens will be burned"),
+        )
+        .arg(
+            Arg::new("new_mint")
+                .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+                .value_name("ADDRESS")
+                .required(true)
+                .takes_value(true)
+                .index(2)
+                .help("New mint address, whose tokens will be transferred to users"),
+        )
+}
+
+fn admin_arg() -> Arg<'static> {
+    Arg::new("admin")
+        .long("admin")
+        .value_name("ADMIN_KEYPAIR")
+        .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+        .takes_value(true)
+        .help("Specify the exchange admin. This may be a keypair file or the ASK keyword. [default: fee payer]")
 }
 
 #[tokio::main]
@@ -275,6 +610,12 @@ async fn main() -> Result<(), Box<dyn Error>> {
                     .index(3)
                     .help("Specify the account keypair. This may be a keypair file or the ASK keyword. [default: associated token account for escrow authority]"),
             )
+            .arg(
+                Arg::new("original")
+                    .long("original")
+                    .takes_value(false)
+                    .help("Create the escrow of original tokens paid out by reverse exchanges, instead of the escrow of new tokens"),
+            )
         )
         .subcommand(
             Command::new("exchange").about("Exchange original tokens for new tokens")
@@ -328,16 +669,114 @@ async fn main() -> Result<(), Box<dyn Error>> {
                     .takes_value(true)
                     .help("Specify the destination account to receive new tokens. [default: associated token account for owner on new mint]"),
             )
+            .arg(multisig_signer_arg())
+        )
+        .subcommand(
+            mint_args(Command::new("create-exchange-config").about("Set the exchange rate and deadlines for a pair of mints, before funding the escrow"))
             .arg(
-                Arg::new("multisig_signer")
-                    .long("multisig-signer")
+                Arg::new("rate")
+                    .long("rate")
+                    .value_name("ORIGINAL:NEW")
+                    .value_parser(parse_exchange_rate)
+                    .takes_value(true)
+                    .required(true)
+                    .help("Amount of original tokens, and amount of new tokens received for them, e.g. 1000:1 to redenominate"),
+            )
+            .arg(
+                Arg::new("deadline")
+                    .long("deadline")
+                    .value_name("UNIX_TIMESTAMP")
+                    .value_parser(value_parser!(i64))
+                    .takes_value(true)
+                    .help("Time from which exchanges are refused, and the admin may reclaim escrowed tokens [default: no deadline]"),
+            )
+            .arg(
+                Arg::new("reverse_deadline")
+                    .long("reverse-deadline")
+                    .value_name("UNIX_TIMESTAMP")
+                    .value_parser(value_parser!(i64))
+                    .takes_value(true)
+                    .help("Time until which new tokens may be exchanged back for original tokens [default: no reverse exchange]"),
+            )
+            .arg(admin_arg().help("Specify the exchange admin, which must be the mint authority of the new mint. This may be a keypair file or the ASK keyword. [default: fee payer]"))
+        )
+        .subcommand(
+            mint_args(Command::new("reverse-exchange").about("Exchange new tokens back for original tokens"))
+            .arg(
+                Arg::new("amount")
+                    .value_parser(value_parser!(f64))
+                    .value_name("AMOUNT")
+                    .required(true)
+                    .takes_value(true)
+                    .index(3)
+                    .help("Amount of new tokens to exchange back"),
+            )
+            .arg(
+                Arg::new("owner")
+                    .long("owner")
+                    .value_name("OWNER_KEYPAIR")
                     .value_parser(SignerSourceParserBuilder::default().allow_all().build())
-                    .value_name("MULTISIG_SIGNER")
                     .takes_value(true)
-                    .action(ArgAction::Append)
-                    .min_values(0)
-                    .max_values(spl_token_2022::instruction::MAX_SIGNERS)
-                    .help("Member signer of a multisig account")
+                    .help("Specify the owner or delegate for the new token account. This may be a keypair file or the ASK keyword. [default: fee payer]"),
+            )
+            .arg(
+                Arg::new("source")
+                    .long("source")
+                    .value_name("NEW_TOKEN_ACCOUNT_ADDRESS")
+                    .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+                    .takes_value(true)
+                    .help("Specify the account to take new tokens from. [default: associated token account for owner on new mint]"),
+            )
+            .arg(
+                Arg::new("escrow")
+                    .long("escrow")
+                    .value_name("ESCROW_TOKEN_ACCOUNT_ADDRESS")
+                    .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+                    .takes_value(true)
+                    .help("Specify the escrow account receiving new tokens. [default: associated token account for the escrow authority on new mint]"),
+            )
+            .arg(
+                Arg::new("original_escrow")
+                    .long("original-escrow")
+                    .value_name("ESCROW_TOKEN_ACCOUNT_ADDRESS")
+                    .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+                    .takes_value(true)
+                    .help("Specify the escrow account to transfer original tokens from. [default: associated token account for the escrow authority on original mint]"),
+            )
+            .arg(
+                Arg::new("destination")
+                    .long("destination")
+                    .value_name("DESTINATION_ACCOUNT_ADDRESS")
+                    .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+                    .takes_value(true)
+                    .help("Specify the destination account to receive original tokens. [default: associated token account for owner on original mint]"),
+            )
+            .arg(multisig_signer_arg())
+        )
+        .subcommand(
+            mint_args(Command::new("reclaim").about("Reclaim escrowed tokens once the exchange deadline has passed"))
+            .arg(admin_arg())
+            .arg(
+                Arg::new("original")
+                    .long("original")
+                    .takes_value(false)
+                    .help("Reclaim from the escrow of original tokens, instead of the escrow of new tokens"),
+            )
+            .arg(
+                Arg::new("escrow")
+                    .long("escrow")
+                    .value_name("ESCROW_TOKEN_ACCOUNT_ADDRESS")
+                    .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+                    .takes_value(true)
+                    .help("Specify the escrow account to reclaim. [default: associated token account for the escrow authority]"),
+            )
+            .arg(
+                Arg::new("destination")
+                    .long("destination")
+                    .value_name("DESTINATION_ACCOUNT_ADDRESS")
+                    .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+                    .takes_value(true)
+                    .help("Specify the account to receive reclaimed tokens. [default: associated token account for admin]"),
             )
         )
         .get_matches();
@@ -407,6 +846,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 &original_mint,
                 &new_mint,
                 account_keypair.as_ref().map(|k| k.as_ref()),
+                arg_matches.try_contains_id("original")?,
             )
             .await
             .unwrap_or_else(|err| {
@@ -416,29 +856,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
             println!("{}", response);
         }
         ("exchange", arg_matches) => {
-            let mut bulk_signers = vec![config.payer.clone()];
-            let mut multisig_pubkeys = vec![];
-
-            if let Some(sources) = arg_matches.try_get_many::<SignerSource>("multisig_signer")? {
-                for (i, source) in sources.enumerate() {
-                    let name = format!("{}-{}", "multisig_signer", i.saturating_add(1));
-                    let signer =
-                        signer_from_source(arg_matches, source, &name, &mut wallet_manager)
-                            .unwrap_or_else(|e| {
-                                eprint!("error parsing multisig signer: {}", e);
-                                exit(1);
-                            });
-                    let signer_pubkey = signer.pubkey();
-                    let signer = Arc::from(signer);
-                    if !bulk_signers.contains(&signer) {
-                        bulk_signers.push(signer);
-                    }
-                    if !multisig_pubkeys.contains(&signer_pubkey) {
-                        multisig_pubkeys.push(signer_pubkey);
-                    }
-                }
-            }
-
+            let signers = get_transfer_signers(arg_matches, &config, &mut wallet_manager)?;
             let original_mint =
                 SignerSource::try_get_pubkey(arg_matches, "original_mint", &mut wallet_manager)
                     .unwrap()
@@ -447,19 +865,6 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 SignerSource::try_get_pubkey(arg_matches, "new_mint", &mut wallet_manager)
                     .unwrap()
                     .unwrap();
-            let signer_config = SignerFromPathConfig {
-                allow_null_signer: !multisig_pubkeys.is_empty(),
-            };
-            let owner = if let Ok(Some((signer, _))) =
-                SignerSource::try_get_signer(matches, "owner", &mut wallet_manager)
-            {
-                Arc::from(signer)
-            } else {
-                config.payer.clone()
-            };
-            if !signer_config.allow_null_signer && !bulk_signers.contains(&owner) {
-                bulk_signers.push(owner.clone());
-            }
             let burn_from =
                 SignerSource::try_get_pubkey(arg_matches, "burn_from", &mut wallet_manager)
                     .unwrap();
@@ -474,12 +879,12 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 &config.payer,
                 &original_mint,
                 &new_mint,
-                &owner,
+                &signers.owner,
                 burn_from,
                 escrow,
                 destination,
-                &multisig_pubkeys,
-                bulk_signers,
+                &signers.multisig_pubkeys,
+                signers.bulk_signers,
             )
             .await
             .unwrap_or_else(|err| {
@@ -488,6 +893,113 @@ async fn main() -> Result<(), Box<dyn Error>> {
             });
             println!("Signature: {}", signature);
         }
+        ("create-exchange-config", arg_matches) => {
+            let original_mint =
+                SignerSource::try_get_pubkey(arg_matches, "original_mint", &mut wallet_manager)
+                    .unwrap()
+                    .unwrap();
+            let new_mint =
+                SignerSource::try_get_pubkey(arg_matches, "new_mint", &mut wallet_manager)
+                    .unwrap()
+                    .unwrap();
+            let rate = *arg_matches.get_one::<(f64, f64)>("rate").unwrap();
+            let exchange_deadline = arg_matches.get_one::<i64>("deadline").copied();
+            let reverse_deadline = arg_matches.get_one::<i64>("reverse_deadline").copied();
+            let admin = get_admin(arg_matches, &config, &mut wallet_manager);
+
+            let signature = process_create_exchange_config(
+                &rpc_client,
+                &config.payer,
+                &original_mint,
+                &new_mint,
+                &admin,
+                rate,
+                exchange_deadline,
+                reverse_deadline,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                eprintln!("error: create exchange config: {}", err);
+                exit(1);
+            });
+            println!("Signature: {}", signature);
+        }
+        ("reverse-exchange", arg_matches) => {
+            let signers = get_transfer_signers(arg_matches, &config, &mut wallet_manager)?;
+            let original_mint =
+                SignerSource::try_get_pubkey(arg_matches, "original_mint", &mut wallet_manager)
+                    .unwrap()
+                    .unwrap();
+            let new_mint =
+                SignerSource::try_get_pubkey(arg_matches, "new_mint", &mut wallet_manager)
+                    .unwrap()
+                    .unwrap();
+            let amount = *arg_matches.get_one::<f64>("amount").unwrap();
+            let source =
+                SignerSource::try_get_pubkey(arg_matches, "source", &mut wallet_manager).unwrap();
+            let escrow =
+                SignerSource::try_get_pubkey(arg_matches, "escrow", &mut wallet_manager).unwrap();
+            let original_escrow =
+                SignerSource::try_get_pubkey(arg_matches, "original_escrow", &mut wallet_manager)
+                    .unwrap();
+            let destination =
+                SignerSource::try_get_pubkey(arg_matches, "destination", &mut wallet_manager)
+                    .unwrap();
+
+            let signature = process_reverse_exchange(
+                &rpc_client,
+                &config.payer,
+                &original_mint,
+                &new_mint,
+                amount,
+                &signers.owner,
+                source,
+                escrow,
+                original_escrow,
+                destination,
+                &signers.multisig_pubkeys,
+                signers.bulk_signers,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                eprintln!("error: send transaction: {}", err);
+                exit(1);
+            });
+            println!("Signature: {}", signature);
+        }
+        ("reclaim", arg_matches) => {
+            let original_mint =
+                SignerSource::try_get_pubkey(arg_matches, "original_mint", &mut wallet_manager)
+                    .unwrap()
+                    .unwrap();
+            let new_mint =
+                SignerSource::try_get_pubkey(arg_matches, "new_mint", &mut wallet_manager)
+                    .unwrap()
+                    .unwrap();
+            let admin = get_admin(arg_matches, &config, &mut wallet_manager);
+            let escrow =
+                SignerSource::try_get_pubkey(arg_matches, "escrow", &mut wallet_manager).unwrap();
+            let destination =
+                SignerSource::try_get_pubkey(arg_matches, "destination", &mut wallet_manager)
+                    .unwrap();
+
+            let signature = process_reclaim(
+                &rpc_client,
+                &config.payer,
+                &original_mint,
+                &new_mint,
+                &admin,
+                arg_matches.try_contains_id("original")?,
+                escrow,
+                destination,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                eprintln!("error: reclaim: {}", err);
+                exit(1);
+            });
+            println!("Signature: {}", signature);
+        }
         _ => unreachable!(),
     };
 
@@ -574,7 +1086,8 @@ mod test {
             &payer,
             original_token.get_address(),
             new_token.get_address(),
-            Some(&account_keypair)
+            Some(&account_keypair),
+            false,
         )
         .await
         .is_ok());
@@ -595,7 +1108,8 @@ mod test {
             &payer,
             original_token.get_address(),
             new_token.get_address(),
-            None
+            None,
+            false,
         )
         .await
         .is_ok());
@@ -643,6 +1157,7 @@ mod test {
             original_token.get_address(),
             new_token.get_address(),
             None,
+            false,
         )
         .await
         .unwrap();
@@ -751,6 +1266,7 @@ mod test {
             original_token.get_address(),
             new_token.get_address(),
             Some(&escrow),
+            false,
         )
         .await
         .unwrap();
@@ -818,4 +1334,140 @@ mod test {
         let destination_account = new_token.get_account_info(&destination).await.unwrap();
         assert_eq!(destination_account.base.amount, amount);
     }
+
+    #[tokio::test]
+    async fn success_exchange_rate_and_reverse() {
+        let (test_validator, payer) = new_validator_for_test().await;
+        let payer: Arc<dyn Signer> = Arc::new(payer);
+        let rpc_client = Arc::new(test_validator.get_async_rpc_client());
+        let client = Arc::new(ProgramRpcClient::new(
+            rpc_client.clone(),
+            ProgramRpcClientSendTransaction,
+        ));
+
+        let mint_authority = Keypair::new();
+        let original_token = setup_mint(
+            &spl_token::id(),
+            &mint_authority.pubkey(),
+            6,
+            payer.clone(),
+            client.clone(),
+        )
+        .await;
+        let new_token = setup_mint(
+            &spl_token_2022::id(),
+            &mint_authority.pubkey(),
+            2,
+            payer.clone(),
+            client.clone(),
+        )
+        .await;
+
+        let admin: Arc<dyn Signer> = Arc::new(mint_authority.insecure_clone());
+        process_create_exchange_config(
+            &rpc_client,
+            &payer,
+            original_token.get_address(),
+            new_token.get_address(),
+            &admin,
+            (1000.0, 1.0),
+            None,
+            Some(i64::MAX),
+        )
+        .await
+        .unwrap();
+        for for_original_mint in [false, true] {
+            process_create_escrow_account(
+                &rpc_client,
+                &payer,
+                original_token.get_address(),
+                new_token.get_address(),
+                None,
+                for_original_mint,
+            )
+            .await
+            .unwrap();
+        }
+        let escrow_authority = get_token_upgrade_authority_address(
+            original_token.get_address(),
+            new_token.get_address(),
+            &spl_token_upgrade::id(),
+        );
+        for (token, amount) in [(&original_token, 1_000_000_000), (&new_token, 1_000)] {
+            token
+                .mint_to(
+                    &token.get_associated_token_address(&escrow_authority),
+                    &mint_authority.pubkey(),
+                    amount,
+                    &[&mint_authority],
+                )
+                .await
+                .unwrap();
+        }
+
+        // 5,000 original tokens become 5 new tokens
+        let user = Keypair::new();
+        original_token
+            .create_associated_token_account(&user.pubkey())
+            .await
+            .unwrap();
+        let original_account = original_token.get_associated_token_address(&user.pubkey());
+        original_token
+            .mint_to(
+                &original_account,
+                &mint_authority.pubkey(),
+                5_000_000_000,
+                &[&mint_authority],
+            )
+            .await
+            .unwrap();
+        new_token
+            .create_associated_token_account(&user.pubkey())
+            .await
+            .unwrap();
+        let new_account = new_token.get_associated_token_address(&user.pubkey());
+
+        let user: Arc<dyn Signer> = Arc::new(user);
+        process_exchange(
+            &rpc_client,
+            &payer,
+            original_token.get_address(),
+            new_token.get_address(),
+            &user,
+            None,
+            None,
+            None,
+            &[],
+            vec![payer.clone(), user.clone()],
+        )
+        .await
+        .unwrap();
+        let new_account_info = new_token.get_account_info(&new_account).await.unwrap();
+        assert_eq!(new_account_info.base.amount, 500);
+
+        // 2 new tokens go back for 2,000 original tokens
+        process_reverse_exchange(
+            &rpc_client,
+            &payer,
+            original_token.get_address(),
+            new_token.get_address(),
+            2.0,
+            &user,
+            None,
+            None,
+            None,
+            None,
+            &[],
+            vec![payer.clone(), user.clone()],
+        )
+        .await
+        .unwrap();
+        let original_account_info = original_token
+            .get_account_info(&original_account)
+            .await
+            .unwrap();
+        assert_eq!(original_account_info.base.amount, 2_000_000_000);
+        let new_account_info = new_token.get_account_info(&new_account).await.unwrap();
+        assert_eq!(new_account_info.base.amount, 300);
+    }
 }
@@ -16,6 +16,29 @@ pub enum TokenUpgradeError {
     /// Decimals of original and new token mint do not match
     #[error("Decimals of original and new token mint do not match")]
     DecimalsMismatch,
+    /// Exchange rate amounts must be greater than zero
+    #[error("Exchange rate amounts must be greater than zero")]
+    InvalidExchangeRate,
+    /// Exchange admin must be the mint authority of the new mint
+    #[error("Exchange admin must be the mint authority of the new mint")]
+    InvalidAdmin,
+    /// Exchange deadline has passed
+    #[error("Exchange deadline has passed")]
+    ExchangeDeadlinePassed,
+
+    // 5
+    /// Exchange deadline has not passed yet
+    #[error("Exchange deadline has not passed yet")]
+    ExchangeDeadlineNotReached,
+    /// Reverse exchange is not available for this pair of mints
+    #[error("Reverse exchange is not available for this pair of mints")]
+    ReverseExchangeClosed,
+    /// Exchanged amount rounds down to zero
+    #[error("Exchanged amount rounds down to zero")]
+    AmountTooSmall,
+    /// Escrow is not owned by the upgrade token account authority
+    #[error("Escrow is not owned by the upgrade token account authority")]
+    InvalidEscrow,
 }
 impl From<TokenUpgradeError> for ProgramError {
     fn from(e: TokenUpgradeError) -> Self {
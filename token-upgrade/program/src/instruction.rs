@@ -1,11 +1,12 @@
 //! Program instructions
 
 use {
-    crate::get_token_upgrade_authority_address,
+    crate::{get_exchange_config_address, get_token_upgrade_authority_address},
     num_enum::{IntoPrimitive, TryFromPrimitive},
     solana_program::{
         instruction::{AccountMeta, Instruction},
         pubkey::Pubkey,
+        system_program,
     },
 };
 
@@ -14,8 +15,12 @@ use {
 #[repr(u8)]
 pub enum TokenUpgradeInstruction {
     /// Burns all of the original tokens in the user's account, and transfers
-    /// the same amount of tokens from an account owned by a PDA into
-    /// another account.
+    /// new tokens from an account owned by a PDA into another account.
+    ///
+    /// If the exchange config is provided and initialized, the amount of new
+    /// tokens follows its exchange rate, and the exchange fails after its
+    /// deadline. Otherwise, both mints must have the same decimals, and the
+    /// same amount of new tokens is transferred.
     ///
     /// Accounts expected by this instruction:
     ///
@@ -35,9 +40,91 @@ pub enum TokenUpgradeInstruction {
     ///   8. `[]` Original token account transfer authority (owner or delegate)
     ///   9. ..9+M `[signer]` M multisig signer accounts
     ///
+    /// Followed by an optional trailing account:
+    ///
+    ///   * `[]` Exchange config, possibly uninitialized, at address:
+    ///     `get_exchange_config_address(original_mint, new_mint, program_id)`
+    ///
     /// Data expected by this instruction:
     ///   None
     Exchange,
+
+    /// Creates the exchange config for a pair of mints.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writeable,signer]` Funding account (must be a system account)
+    ///   1. `[writeable]` Uninitialized exchange config, at address:
+    ///      `get_exchange_config_address(original_mint, new_mint, program_id)`
+    ///   2. `[]` Original token mint
+    ///   3. `[]` New token mint
+    ///   4. `[signer]` Exchange admin, must be the mint authority of the new
+    ///      mint
+    ///   5. `[]` System program
+    ///
+    /// Data expected by this instruction:
+    ///   * little-endian u64: amount of original tokens, in base units
+    ///   * little-endian u64: amount of new tokens, in base units, received
+    ///     for the amount of original tokens
+    ///   * little-endian i64: exchange deadline as a unix timestamp, or 0 for
+    ///     none
+    ///   * little-endian i64: end of the reverse exchange window as a unix
+    ///     timestamp, or 0 to disable reverse exchanges
+    InitializeExchangeConfig,
+
+    /// Transfers new tokens back into the escrow of new tokens, and transfers
+    /// original tokens from an escrow of original tokens owned by a PDA, at
+    /// the configured exchange rate. Only available until the end of the
+    /// reverse exchange window.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writeable]` New token account to transfer from
+    ///   1. `[]` New token mint
+    ///   2. `[writeable]` Escrow of new tokens owned by PDA at address:
+    ///      `get_token_upgrade_authority_address(original_mint, new_mint,
+    ///      program_id)`
+    ///   3. `[writeable]` Escrow of original tokens held by or delegated to PDA
+    ///   4. `[writeable]` Original token account to transfer into
+    ///   5. `[]` Original token mint
+    ///   6. `[]` Transfer authority (owner or delegate) of original token
+    ///      escrow held by PDA, must be:
+    ///      `get_token_upgrade_authority_address(original_mint, new_mint,
+    ///      program_id)`
+    ///   7. `[]` SPL Token program for original mint
+    ///   8. `[]` SPL Token program for new mint
+    ///   9. `[]` Exchange config, at address:
+    ///      `get_exchange_config_address(original_mint, new_mint, program_id)`
+    ///   10. `[]` New token account transfer authority (owner or delegate)
+    ///   11. ..11+M `[signer]` M multisig signer accounts
+    ///
+    /// Data expected by this instruction:
+    ///   * little-endian u64: amount of new tokens to exchange back
+    ReverseExchange,
+
+    /// Transfers all tokens out of an escrow owned by the PDA, once the
+    /// exchange deadline has passed.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[writeable]` Escrow of original or new tokens owned by PDA at
+    ///      address: `get_token_upgrade_authority_address(original_mint,
+    ///      new_mint, program_id)`
+    ///   1. `[]` Mint of the escrow, either the original or new mint
+    ///   2. `[writeable]` Token account to transfer into
+    ///   3. `[]` Owner of the escrow, must be:
+    ///      `get_token_upgrade_authority_address(original_mint, new_mint,
+    ///      program_id)`
+    ///   4. `[]` SPL Token program for the escrow mint
+    ///   5. `[]` Exchange config, at address:
+    ///      `get_exchange_config_address(original_mint, new_mint, program_id)`
+    ///   6. `[]` Original token mint
+    ///   7. `[]` New token mint
+    ///   8. `[signer]` Exchange admin
+    ///
+    /// Data expected by this instruction:
+    ///   None
+    Reclaim,
 }
 
 /// Create an `Exchange` instruction
@@ -55,7 +142,8 @@ pub fn exchange(
     original_multisig_signers: &[&Pubkey],
 ) -> Instruction {
     let escrow_authority = get_token_upgrade_authority_address(original_mint, new_mint, program_id);
-    let mut accounts = Vec::with_capacity(9usize.saturating_add(original_multisig_signers.len()));
+    let exchange_config = get_exchange_config_address(original_mint, new_mint, program_id);
+    let mut accounts = Vec::with_capacity(10usize.saturating_add(original_multisig_signers.len()));
     accounts.push(AccountMeta::new(*original_account, false));
     accounts.push(AccountMeta::new(*original_mint, false));
     accounts.push(AccountMeta::new(*new_escrow, false));
@@ -71,6 +159,7 @@ pub fn exchange(
     for signer_pubkey in original_multisig_signers.iter() {
         accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
     }
+    accounts.push(AccountMeta::new_readonly(exchange_config, false));
 
     Instruction {
         program_id: *program_id,
@@ -78,3 +167,116 @@ pub fn exchange(
         data: vec![TokenUpgradeInstruction::Exchange.into()],
     }
 }
+
+/// Create an `InitializeExchangeConfig` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_exchange_config(
+    program_id: &Pubkey,
+    funding_address: &Pubkey,
+    original_mint: &Pubkey,
+    new_mint: &Pubkey,
+    admin: &Pubkey,
+    original_amount: u64,
+    new_amount: u64,
+    exchange_deadline: i64,
+    reverse_deadline: i64,
+) -> Instruction {
+    let exchange_config = get_exchange_config_address(original_mint, new_mint, program_id);
+    let accounts = vec![
+        AccountMeta::new(*funding_address, true),
+        AccountMeta::new(exchange_config, false),
+        AccountMeta::new_readonly(*original_mint, false),
+        AccountMeta::new_readonly(*new_mint, false),
+        AccountMeta::new_readonly(*admin, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    let mut data = vec![TokenUpgradeInstruction::InitializeExchangeConfig.into()];
+    data.extend_from_slice(&original_amount.to_le_bytes());
+    data.extend_from_slice(&new_amount.to_le_bytes());
+    data.extend_from_slice(&exchange_deadline.to_le_bytes());
+    data.extend_from_slice(&reverse_deadline.to_le_bytes());
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Create a `ReverseExchange` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn reverse_exchange(
+    program_id: &Pubkey,
+    new_account: &Pubkey,
+    new_mint: &Pubkey,
+    new_escrow: &Pubkey,
+    original_escrow: &Pubkey,
+    original_account: &Pubkey,
+    original_mint: &Pubkey,
+    original_token_program_id: &Pubkey,
+    new_token_program_id: &Pubkey,
+    new_transfer_authority: &Pubkey,
+    new_multisig_signers: &[&Pubkey],
+    amount: u64,
+) -> Instruction {
+    let escrow_authority = get_token_upgrade_authority_address(original_mint, new_mint, program_id);
+    let exchange_config = get_exchange_config_address(original_mint, new_mint, program_id);
+    let mut accounts = Vec::with_capacity(11usize.saturating_add(new_multisig_signers.len()));
+    accounts.push(AccountMeta::new(*new_account, false));
+    accounts.push(AccountMeta::new_readonly(*new_mint, false));
+    accounts.push(AccountMeta::new(*new_escrow, false));
+    accounts.push(AccountMeta::new(*original_escrow, false));
+    accounts.push(AccountMeta::new(*original_account, false));
+    accounts.push(AccountMeta::new_readonly(*original_mint, false));
+    accounts.push(AccountMeta::new_readonly(escrow_authority, false));
+    accounts.push(AccountMeta::new_readonly(*original_token_program_id, false));
+    accounts.push(AccountMeta::new_readonly(*new_token_program_id, false));
+    accounts.push(AccountMeta::new_readonly(exchange_config, false));
+    accounts.push(AccountMeta::new_readonly(
+        *new_transfer_authority,
+        new_multisig_signers.is_empty(),
+    ));
+    for signer_pubkey in new_multisig_signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    let mut data = vec![TokenUpgradeInstruction::ReverseExchange.into()];
+    data.extend_from_slice(&amount.to_le_bytes());
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Create a `Reclaim` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn reclaim(
+    program_id: &Pubkey,
+    escrow: &Pubkey,
+    escrow_mint: &Pubkey,
+    destination: &Pubkey,
+    escrow_token_program_id: &Pubkey,
+    original_mint: &Pubkey,
+    new_mint: &Pubkey,
+    admin: &Pubkey,
+) -> Instruction {
+    let escrow_authority = get_token_upgrade_authority_address(original_mint, new_mint, program_id);
+    let exchange_config = get_exchange_config_address(original_mint, new_mint, program_id);
+    let accounts = vec![
+        AccountMeta::new(*escrow, false),
+        AccountMeta::new_readonly(*escrow_mint, false),
+        AccountMeta::new(*destination, false),
+        AccountMeta::new_readonly(escrow_authority, false),
+        AccountMeta::new_readonly(*escrow_token_program_id, false),
+        AccountMeta::new_readonly(exchange_config, false),
+        AccountMeta::new_readonly(*original_mint, false),
+        AccountMeta::new_readonly(*new_mint, false),
+        AccountMeta::new_readonly(*admin, true),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: vec![TokenUpgradeInstruction::Reclaim.into()],
+    }
+}
@@ -6,6 +6,7 @@ mod entrypoint;
 pub mod error;
 pub mod instruction;
 pub mod processor;
+pub mod state;
 
 // Export current SDK types for downstream users building with a different SDK
 // version
@@ -59,3 +60,33 @@ pub(crate) fn collect_token_upgrade_authority_signer_seeds<'a>(
         bump_seed,
     ]
 }
+
+const EXCHANGE_CONFIG_SEED: &[u8] = b"exchange-config";
+
+/// Get the exchange config address, keyed by the upgrade token account
+/// authority
+pub fn get_exchange_config_address(
+    original_mint: &Pubkey,
+    new_mint: &Pubkey,
+    program_id: &Pubkey,
+) -> Pubkey {
+    let escrow_authority = get_token_upgrade_authority_address(original_mint, new_mint, program_id);
+    get_exchange_config_address_and_bump_seed(&escrow_authority, program_id).0
+}
+
+pub(crate) fn get_exchange_config_address_and_bump_seed(
+    escrow_authority: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[EXCHANGE_CONFIG_SEED, escrow_authority.as_ref()],
+        program_id,
+    )
+}
+
+pub(crate) fn collect_exchange_config_signer_seeds<'a>(
+    escrow_authority: &'a Pubkey,
+    bump_seed: &'a [u8],
+) -> [&'a [u8]; 3] {
+    [EXCHANGE_CONFIG_SEED, escrow_authority.as_ref(), bump_seed]
+}
@@ -2,22 +2,32 @@
 
 use {
     crate::{
-        collect_token_upgrade_authority_signer_seeds, error::TokenUpgradeError,
+        collect_exchange_config_signer_seeds, collect_token_upgrade_authority_signer_seeds,
+        error::TokenUpgradeError, get_exchange_config_address_and_bump_seed,
         get_token_upgrade_authority_address_and_bump_seed, instruction::TokenUpgradeInstruction,
+        state::ExchangeConfig,
     },
     solana_program::{
         account_info::{next_account_info, AccountInfo},
+        clock::Clock,
         entrypoint::ProgramResult,
         msg,
         program::{invoke, invoke_signed},
         program_error::ProgramError,
+        program_option::COption,
         pubkey::Pubkey,
+        rent::Rent,
+        system_instruction, system_program,
+        sysvar::Sysvar,
     },
+    spl_pod::bytemuck::{pod_from_bytes, pod_from_bytes_mut},
     spl_token_2022::{
+        check_spl_token_program_account,
         extension::StateWithExtensions,
         instruction::decode_instruction_type,
         state::{Account, Mint},
     },
+    std::mem::size_of,
 };
 
 fn check_owner(account_info: &AccountInfo, expected_owner: &Pubkey) -> ProgramResult {
@@ -53,7 +63,34 @@ fn burn_original_tokens<'a>(
 }
 
 #[allow(clippy::too_many_arguments)]
-fn transfer_new_tokens<'a>(
+fn transfer_user_tokens<'a>(
+    token_program: AccountInfo<'a>,
+    source: AccountInfo<'a>,
+    mint: AccountInfo<'a>,
+    destination: AccountInfo<'a>,
+    authority: AccountInfo<'a>,
+    multisig_signers: &[AccountInfo<'a>],
+    amount: u64,
+    decimals: u8,
+) -> ProgramResult {
+    let multisig_pubkeys = multisig_signers.iter().map(|s| s.key).collect::<Vec<_>>();
+    let ix = spl_token_2022::instruction::transfer_checked(
+        token_program.key,
+        source.key,
+        mint.key,
+        destination.key,
+        authority.key,
+        &multisig_pubkeys,
+        amount,
+        decimals,
+    )?;
+    let mut account_infos = vec![source, mint, destination, authority];
+    account_infos.extend_from_slice(multisig_signers);
+    invoke(&ix, &account_infos)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn transfer_escrowed_tokens<'a>(
     new_token_program: AccountInfo<'a>,
     source: AccountInfo<'a>,
     mint: AccountInfo<'a>,
@@ -80,6 +117,78 @@ fn transfer_new_tokens<'a>(
     )
 }
 
+/// Checks the escrow authority against its derivation from the mints,
+/// returning its bump seed
+fn check_escrow_authority(
+    escrow_authority_info: &AccountInfo,
+    original_mint: &Pubkey,
+    new_mint: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<u8, ProgramError> {
+    let (expected_escrow_authority, bump_seed) =
+        get_token_upgrade_authority_address_and_bump_seed(original_mint, new_mint, program_id);
+    if expected_escrow_authority != *escrow_authority_info.key {
+        msg!(
+            "Expected escrow authority {}, received {}",
+            &expected_escrow_authority,
+            escrow_authority_info.key
+        );
+        return Err(TokenUpgradeError::InvalidOwner.into());
+    }
+    Ok(bump_seed)
+}
+
+/// Reads the exchange config of the escrow authority, returning `None` if it
+/// was never initialized
+fn unpack_exchange_config(
+    exchange_config_info: &AccountInfo,
+    escrow_authority: &Pubkey,
+    program_id: &Pubkey,
+) -> Result<Option<ExchangeConfig>, ProgramError> {
+    let (expected_exchange_config, _) =
+        get_exchange_config_address_and_bump_seed(escrow_authority, program_id);
+    if expected_exchange_config != *exchange_config_info.key {
+        msg!(
+            "Expected exchange config {}, received {}",
+            &expected_exchange_config,
+            exchange_config_info.key
+        );
+        return Err(TokenUpgradeError::InvalidOwner.into());
+    }
+    if exchange_config_info.owner != program_id {
+        return if exchange_config_info.data_is_empty() {
+            Ok(None)
+        } else {
+            Err(ProgramError::IllegalOwner)
+        };
+    }
+    let exchange_config_data = exchange_config_info.try_borrow_data()?;
+    Ok(Some(*pod_from_bytes::<ExchangeConfig>(
+        &exchange_config_data,
+    )?))
+}
+
+/// Checks that the escrow is owned, not just delegated, by the escrow
+/// authority, returning its balance
+fn unpack_owned_escrow_amount(
+    escrow_info: &AccountInfo,
+    mint: &Pubkey,
+    escrow_authority: &Pubkey,
+) -> Result<u64, ProgramError> {
+    let escrow_data = escrow_info.try_borrow_data()?;
+    let escrow = StateWithExtensions::<Account>::unpack(&escrow_data)?;
+    if escrow.base.owner != *escrow_authority || escrow.base.mint != *mint {
+        msg!(
+            "Escrow {} must be owned by {} and hold mint {}",
+            escrow_info.key,
+            escrow_authority,
+            mint
+        );
+        return Err(TokenUpgradeError::InvalidEscrow.into());
+    }
+    Ok(escrow.base.amount)
+}
+
 fn process_exchange(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
 
@@ -100,29 +209,40 @@ fn process_exchange(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramRes
     check_owner(new_account_info, new_token_program.key)?;
     check_owner(new_mint_info, new_token_program.key)?;
 
-    // PDA derivation check
-    let (expected_escrow_authority, bump_seed) = get_token_upgrade_authority_address_and_bump_seed(
+    // PDA derivation checks
+    let bump_seed = check_escrow_authority(
+        new_transfer_authority_info,
         original_mint_info.key,
         new_mint_info.key,
         program_id,
-    );
+    )?;
     let bump_seed = [bump_seed];
     let authority_seeds = collect_token_upgrade_authority_signer_seeds(
         original_mint_info.key,
         new_mint_info.key,
         &bump_seed,
     );
-    if expected_escrow_authority != *new_transfer_authority_info.key {
-        msg!(
-            "Expected escrow authority {}, received {}",
-            &expected_escrow_authority,
-            new_transfer_authority_info.key
-        );
-        return Err(TokenUpgradeError::InvalidOwner.into());
-    }
+
+    // the exchange config is an optional trailing account, after the multisig
+    // signers, recognized by its address
+    let (exchange_config_address, _) =
+        get_exchange_config_address_and_bump_seed(new_transfer_authority_info.key, program_id);
+    let (multisig_signers, exchange_config) = match account_info_iter.as_slice().split_last() {
+        Some((exchange_config_info, multisig_signers))
+            if *exchange_config_info.key == exchange_config_address =>
+        {
+            let exchange_config = unpack_exchange_config(
+                exchange_config_info,
+                new_transfer_authority_info.key,
+                program_id,
+            )?;
+            (multisig_signers, exchange_config)
+        }
+        _ => (account_info_iter.as_slice(), None),
+    };
 
     // pull out these values in a block to drop all data before performing CPIs
-    let (token_amount, decimals) = {
+    let (token_amount, new_token_amount, original_decimals, new_decimals) = {
         // check mints are actually mints
         let original_mint_data = original_mint_info.try_borrow_data()?;
         let original_mint = StateWithExtensions::<Mint>::unpack(&original_mint_data)?;
@@ -138,24 +258,45 @@ fn process_exchange(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramRes
         let _ = StateWithExtensions::<Account>::unpack(&new_account_data)?;
 
         let token_amount = original_account.base.amount;
-        if new_escrow.base.amount < token_amount {
+        let new_token_amount = if let Some(exchange_config) = exchange_config {
+            if !exchange_config.is_exchange_open(Clock::get()?.unix_timestamp) {
+                return Err(TokenUpgradeError::ExchangeDeadlinePassed.into());
+            }
+            let new_token_amount = exchange_config
+                .new_tokens_for(token_amount)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            if new_token_amount == 0 && token_amount > 0 {
+                return Err(TokenUpgradeError::AmountTooSmall.into());
+            }
+            new_token_amount
+        } else {
+            // the exchange rate accounts for differing decimals, without it
+            // the mints must match
+            if original_mint.base.decimals != new_mint.base.decimals {
+                msg!(
+                    "Original and new token mint decimals mismatch: original has {} decimals, and new has {}",
+                    original_mint.base.decimals,
+                    new_mint.base.decimals,
+                );
+                return Err(TokenUpgradeError::DecimalsMismatch.into());
+            }
+            token_amount
+        };
+        if new_escrow.base.amount < new_token_amount {
             msg!(
                 "Escrow only has {} tokens, needs at least {}",
                 new_escrow.base.amount,
-                token_amount
+                new_token_amount
             );
             return Err(ProgramError::InsufficientFunds);
         }
-        if original_mint.base.decimals != new_mint.base.decimals {
-            msg!(
-                "Original and new token mint decimals mismatch: original has {} decimals, and new has {}",
-                original_mint.base.decimals,
-                new_mint.base.decimals,
-            );
-            return Err(TokenUpgradeError::DecimalsMismatch.into());
-        }
 
-        (original_account.base.amount, original_mint.base.decimals)
+        (
+            token_amount,
+            new_token_amount,
+            original_mint.base.decimals,
+            new_mint.base.decimals,
+        )
     };
 
     burn_original_tokens(
@@ -163,28 +304,339 @@ fn process_exchange(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramRes
         original_account_info.clone(),
         original_mint_info.clone(),
         original_transfer_authority_info.clone(),
-        account_info_iter.as_slice(),
+        multisig_signers,
         token_amount,
-        decimals,
+        original_decimals,
     )?;
 
-    transfer_new_tokens(
+    transfer_escrowed_tokens(
         new_token_program.clone(),
         new_escrow_info.clone(),
         new_mint_info.clone(),
         new_account_info.clone(),
         new_transfer_authority_info.clone(),
         &authority_seeds,
-        token_amount,
-        decimals,
+        new_token_amount,
+        new_decimals,
+    )?;
+
+    Ok(())
+}
+
+fn unpack_mint_authority(mint_info: &AccountInfo) -> Result<COption<Pubkey>, ProgramError> {
+    check_spl_token_program_account(mint_info.owner)?;
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+    Ok(mint.base.mint_authority)
+}
+
+fn process_initialize_exchange_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    exchange_config: ExchangeConfig,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let funding_info = next_account_info(account_info_iter)?;
+    let exchange_config_info = next_account_info(account_info_iter)?;
+    let original_mint_info = next_account_info(account_info_iter)?;
+    let new_mint_info = next_account_info(account_info_iter)?;
+    let admin_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    if !system_program::check_id(system_program_info.key) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if !admin_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // only the issuer of the new token, who funds its escrow, may configure
+    // the exchange and reclaim the escrows
+    check_spl_token_program_account(original_mint_info.owner)?;
+    if unpack_mint_authority(new_mint_info)? != COption::Some(*admin_info.key) {
+        return Err(TokenUpgradeError::InvalidAdmin.into());
+    }
+    if u64::from(exchange_config.original_amount) == 0 || u64::from(exchange_config.new_amount) == 0
+    {
+        return Err(TokenUpgradeError::InvalidExchangeRate.into());
+    }
+
+    // PDA derivation check
+    let (escrow_authority, _) = get_token_upgrade_authority_address_and_bump_seed(
+        original_mint_info.key,
+        new_mint_info.key,
+        program_id,
+    );
+    let (expected_exchange_config, bump_seed) =
+        get_exchange_config_address_and_bump_seed(&escrow_authority, program_id);
+    if expected_exchange_config != *exchange_config_info.key {
+        msg!(
+            "Expected exchange config {}, received {}",
+            &expected_exchange_config,
+            exchange_config_info.key
+        );
+        return Err(TokenUpgradeError::InvalidOwner.into());
+    }
+    if !exchange_config_info.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let bump_seed = [bump_seed];
+    let signer_seeds = collect_exchange_config_signer_seeds(&escrow_authority, &bump_seed);
+    let space = size_of::<ExchangeConfig>();
+    let required_lamports = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(exchange_config_info.lamports());
+    if required_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(
+                funding_info.key,
+                exchange_config_info.key,
+                required_lamports,
+            ),
+            &[
+                funding_info.clone(),
+                exchange_config_info.clone(),
+                system_program_info.clone(),
+            ],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(exchange_config_info.key, space as u64),
+        &[exchange_config_info.clone(), system_program_info.clone()],
+        &[&signer_seeds],
     )?;
+    invoke_signed(
+        &system_instruction::assign(exchange_config_info.key, program_id),
+        &[exchange_config_info.clone(), system_program_info.clone()],
+        &[&signer_seeds],
+    )?;
+
+    let mut exchange_config_data = exchange_config_info.try_borrow_mut_data()?;
+    *pod_from_bytes_mut::<ExchangeConfig>(&mut exchange_config_data)? = ExchangeConfig {
+        admin: *admin_info.key,
+        ..exchange_config
+    };
 
     Ok(())
 }
 
+fn process_reverse_exchange(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let new_account_info = next_account_info(account_info_iter)?;
+    let new_mint_info = next_account_info(account_info_iter)?;
+    let new_escrow_info = next_account_info(account_info_iter)?;
+    let original_escrow_info = next_account_info(account_info_iter)?;
+    let original_account_info = next_account_info(account_info_iter)?;
+    let original_mint_info = next_account_info(account_info_iter)?;
+    let escrow_authority_info = next_account_info(account_info_iter)?;
+    let original_token_program = next_account_info(account_info_iter)?;
+    let new_token_program = next_account_info(account_info_iter)?;
+    let exchange_config_info = next_account_info(account_info_iter)?;
+    let new_transfer_authority_info = next_account_info(account_info_iter)?;
+
+    // owner checks
+    check_owner(new_account_info, new_token_program.key)?;
+    check_owner(new_mint_info, new_token_program.key)?;
+    check_owner(new_escrow_info, new_token_program.key)?;
+    check_owner(original_escrow_info, original_token_program.key)?;
+    check_owner(original_account_info, original_token_program.key)?;
+    check_owner(original_mint_info, original_token_program.key)?;
+
+    // PDA derivation checks
+    let bump_seed = check_escrow_authority(
+        escrow_authority_info,
+        original_mint_info.key,
+        new_mint_info.key,
+        program_id,
+    )?;
+    let bump_seed = [bump_seed];
+    let authority_seeds = collect_token_upgrade_authority_signer_seeds(
+        original_mint_info.key,
+        new_mint_info.key,
+        &bump_seed,
+    );
+    let exchange_config =
+        unpack_exchange_config(exchange_config_info, escrow_authority_info.key, program_id)?
+            .ok_or(TokenUpgradeError::ReverseExchangeClosed)?;
+    if !exchange_config.is_reverse_exchange_open(Clock::get()?.unix_timestamp) {
+        return Err(TokenUpgradeError::ReverseExchangeClosed.into());
+    }
+
+    let original_token_amount = exchange_config
+        .original_tokens_for(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    if original_token_amount == 0 {
+        return Err(TokenUpgradeError::AmountTooSmall.into());
+    }
+
+    // pull out these values in a block to drop all data before performing CPIs
+    let (original_decimals, new_decimals) = {
+        let original_mint_data = original_mint_info.try_borrow_data()?;
+        let original_mint = StateWithExtensions::<Mint>::unpack(&original_mint_data)?;
+        let new_mint_data = new_mint_info.try_borrow_data()?;
+        let new_mint = StateWithExtensions::<Mint>::unpack(&new_mint_data)?;
+
+        // new tokens must go back into the escrow, not to an account of the
+        // caller
+        unpack_owned_escrow_amount(
+            new_escrow_info,
+            new_mint_info.key,
+            escrow_authority_info.key,
+        )?;
+        let original_escrow_data = original_escrow_info.try_borrow_data()?;
+        let original_escrow = StateWithExtensions::<Account>::unpack(&original_escrow_data)?;
+        if original_escrow.base.amount < original_token_amount {
+            msg!(
+                "Escrow only has {} tokens, needs at least {}",
+                original_escrow.base.amount,
+                original_token_amount
+            );
+            return Err(ProgramError::InsufficientFunds);
+        }
+
+        (original_mint.base.decimals, new_mint.base.decimals)
+    };
+
+    transfer_user_tokens(
+        new_token_program.clone(),
+        new_account_info.clone(),
+        new_mint_info.clone(),
+        new_escrow_info.clone(),
+        new_transfer_authority_info.clone(),
+        account_info_iter.as_slice(),
+        amount,
+        new_decimals,
+    )?;
+
+    transfer_escrowed_tokens(
+        original_token_program.clone(),
+        original_escrow_info.clone(),
+        original_mint_info.clone(),
+        original_account_info.clone(),
+        escrow_authority_info.clone(),
+        &authority_seeds,
+        original_token_amount,
+        original_decimals,
+    )
+}
+
+fn process_reclaim(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let escrow_info = next_account_info(account_info_iter)?;
+    let escrow_mint_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let escrow_authority_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let exchange_config_info = next_account_info(account_info_iter)?;
+    let original_mint_info = next_account_info(account_info_iter)?;
+    let new_mint_info = next_account_info(account_info_iter)?;
+    let admin_info = next_account_info(account_info_iter)?;
+
+    // owner checks
+    check_owner(escrow_info, token_program.key)?;
+    check_owner(escrow_mint_info, token_program.key)?;
+    if escrow_mint_info.key != original_mint_info.key && escrow_mint_info.key != new_mint_info.key {
+        msg!(
+            "Escrow mint {} is neither the original nor the new mint",
+            escrow_mint_info.key
+        );
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // PDA derivation checks
+    let bump_seed = check_escrow_authority(
+        escrow_authority_info,
+        original_mint_info.key,
+        new_mint_info.key,
+        program_id,
+    )?;
+    let bump_seed = [bump_seed];
+    let authority_seeds = collect_token_upgrade_authority_signer_seeds(
+        original_mint_info.key,
+        new_mint_info.key,
+        &bump_seed,
+    );
+    let exchange_config =
+        unpack_exchange_config(exchange_config_info, escrow_authority_info.key, program_id)?
+            .ok_or(ProgramError::UninitializedAccount)?;
+
+    // admin checks
+    if !admin_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if exchange_config.admin != *admin_info.key {
+        return Err(TokenUpgradeError::InvalidAdmin.into());
+    }
+    if i64::from(exchange_config.exchange_deadline) == 0
+        || exchange_config.is_exchange_open(Clock::get()?.unix_timestamp)
+    {
+        return Err(TokenUpgradeError::ExchangeDeadlineNotReached.into());
+    }
+
+    let (amount, decimals) = {
+        let amount = unpack_owned_escrow_amount(
+            escrow_info,
+            escrow_mint_info.key,
+            escrow_authority_info.key,
+        )?;
+        let escrow_mint_data = escrow_mint_info.try_borrow_data()?;
+        let escrow_mint = StateWithExtensions::<Mint>::unpack(&escrow_mint_data)?;
+        (amount, escrow_mint.base.decimals)
+    };
+
+    transfer_escrowed_tokens(
+        token_program.clone(),
+        escrow_info.clone(),
+        escrow_mint_info.clone(),
+        destination_info.clone(),
+        escrow_authority_info.clone(),
+        &authority_seeds,
+        amount,
+        decimals,
+    )
+}
+
+fn unpack_u64(input: &[u8], offset: usize) -> Result<u64, ProgramError> {
+    input
+        .get(offset..offset.saturating_add(8))
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)
+}
+
+fn unpack_i64(input: &[u8], offset: usize) -> Result<i64, ProgramError> {
+    input
+        .get(offset..offset.saturating_add(8))
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(i64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)
+}
+
 /// Instruction processor
 pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
     match decode_instruction_type(input)? {
         TokenUpgradeInstruction::Exchange => process_exchange(program_id, accounts),
+        TokenUpgradeInstruction::InitializeExchangeConfig => {
+            let exchange_config = ExchangeConfig {
+                admin: Pubkey::default(),
+                original_amount: unpack_u64(input, 1)?.into(),
+                new_amount: unpack_u64(input, 9)?.into(),
+                exchange_deadline: unpack_i64(input, 17)?.into(),
+                reverse_deadline: unpack_i64(input, 25)?.into(),
+            };
+            process_initialize_exchange_config(program_id, accounts, exchange_config)
+        }
+        TokenUpgradeInstruction::ReverseExchange => {
+            process_reverse_exchange(program_id, accounts, unpack_u64(input, 1)?)
+        }
+        TokenUpgradeInstruction::Reclaim => process_reclaim(program_id, accounts),
     }
 }
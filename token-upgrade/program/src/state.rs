@@ -0,0 +1,70 @@
+//! State types
+
+use {
+    bytemuck::{Pod, Zeroable},
+    solana_program::pubkey::Pubkey,
+    spl_pod::primitives::{PodI64, PodU64},
+};
+
+/// Exchange configuration for a pair of mints, stored at
+/// `get_exchange_config_address(original_mint, new_mint, program_id)`.
+///
+/// Without a config, tokens are exchanged 1:1 in one direction, with no
+/// deadline.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct ExchangeConfig {
+    /// Admin allowed to reclaim escrowed tokens after the exchange deadline
+    pub admin: Pubkey,
+    /// Amount of original tokens, in base units, exchanged for `new_amount`
+    pub original_amount: PodU64,
+    /// Amount of new tokens, in base units, received for `original_amount`
+    pub new_amount: PodU64,
+    /// Unix timestamp from which exchanges are refused and the admin may
+    /// reclaim escrowed tokens, or 0 for no deadline
+    pub exchange_deadline: PodI64,
+    /// Unix timestamp until which new tokens may be exchanged back for
+    /// original tokens, or 0 if reverse exchanges are disabled
+    pub reverse_deadline: PodI64,
+}
+
+impl ExchangeConfig {
+    /// Amount of new tokens received for `original_amount` original tokens,
+    /// rounded down
+    pub fn new_tokens_for(&self, original_amount: u64) -> Option<u64> {
+        convert(
+            original_amount,
+            self.new_amount.into(),
+            self.original_amount.into(),
+        )
+    }
+
+    /// Amount of original tokens received for `new_amount` new tokens,
+    /// rounded down
+    pub fn original_tokens_for(&self, new_amount: u64) -> Option<u64> {
+        convert(
+            new_amount,
+            self.original_amount.into(),
+            self.new_amount.into(),
+        )
+    }
+
+    /// Check if exchanges are still accepted at `unix_timestamp`
+    pub fn is_exchange_open(&self, unix_timestamp: i64) -> bool {
+        let deadline = i64::from(self.exchange_deadline);
+        deadline == 0 || unix_timestamp < deadline
+    }
+
+    /// Check if reverse exchanges are accepted at `unix_timestamp`
+    pub fn is_reverse_exchange_open(&self, unix_timestamp: i64) -> bool {
+        self.is_exchange_open(unix_timestamp) && unix_timestamp < i64::from(self.reverse_deadline)
+    }
+}
+
+fn convert(amount: u64, numerator: u64, denominator: u64) -> Option<u64> {
+    u128::from(amount)
+        .checked_mul(u128::from(numerator))?
+        .checked_div(u128::from(denominator))?
+        .try_into()
+        .ok()
+}
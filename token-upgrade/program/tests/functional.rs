@@ -9,7 +9,8 @@ use {
         ProgramTest, ProgramTestContext,
     },
     solana_sdk::{
-        instruction::{AccountMeta, InstructionError},
+        clock::Clock,
+        instruction::{AccountMeta, Instruction, InstructionError},
         pubkey::Pubkey,
         signature::Signer,
         signer::keypair::Keypair,
@@ -23,7 +24,10 @@ use {
         token::Token,
     },
     spl_token_upgrade::{
-        error::TokenUpgradeError, get_token_upgrade_authority_address, instruction::exchange,
+        error::TokenUpgradeError,
+        get_exchange_config_address, get_token_upgrade_authority_address,
+        instruction::{exchange, initialize_exchange_config, reclaim, reverse_exchange},
+        state::ExchangeConfig,
     },
     std::sync::Arc,
     test_case::test_case,
@@ -411,3 +415,523 @@ async fn fail_decimals_mismatch(original_program_id: Pubkey, new_program_id: Pub
         )
     );
 }
+
+async fn process(
+    context: &Mutex<ProgramTestContext>,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), TransactionError> {
+    let context = context.lock().await;
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .map_err(|e| e.unwrap())
+}
+
+fn custom_error(error: TokenUpgradeError) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(error as u32))
+}
+
+async fn get_unix_timestamp(context: &Mutex<ProgramTestContext>) -> i64 {
+    let context = context.lock().await;
+    context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp
+}
+
+async fn set_unix_timestamp(context: &Mutex<ProgramTestContext>, unix_timestamp: i64) {
+    let context = context.lock().await;
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    context.set_sysvar(&clock);
+}
+
+struct Exchange {
+    original_token: Token<ProgramBanksClientProcessTransaction>,
+    new_token: Token<ProgramBanksClientProcessTransaction>,
+    original_mint_authority: Keypair,
+    mint_authority: Keypair,
+    wallet: Keypair,
+    original_account: Pubkey,
+    new_account: Pubkey,
+    original_escrow: Pubkey,
+    new_escrow: Pubkey,
+}
+
+impl Exchange {
+    fn exchange(&self) -> Instruction {
+        exchange(
+            &spl_token_upgrade::id(),
+            &self.original_account,
+            self.original_token.get_address(),
+            &self.new_escrow,
+            &self.new_account,
+            self.new_token.get_address(),
+            &spl_token::id(),
+            &spl_token_2022::id(),
+            &self.wallet.pubkey(),
+            &[],
+        )
+    }
+
+    fn reverse_exchange(&self, new_escrow: &Pubkey, amount: u64) -> Instruction {
+        reverse_exchange(
+            &spl_token_upgrade::id(),
+            &self.new_account,
+            self.new_token.get_address(),
+            new_escrow,
+            &self.original_escrow,
+            &self.original_account,
+            self.original_token.get_address(),
+            &spl_token::id(),
+            &spl_token_2022::id(),
+            &self.wallet.pubkey(),
+            &[],
+            amount,
+        )
+    }
+
+    fn initialize_exchange_config(
+        &self,
+        payer: &Pubkey,
+        admin: &Pubkey,
+        original_amount: u64,
+        new_amount: u64,
+        exchange_deadline: i64,
+        reverse_deadline: i64,
+    ) -> Instruction {
+        initialize_exchange_config(
+            &spl_token_upgrade::id(),
+            payer,
+            self.original_token.get_address(),
+            self.new_token.get_address(),
+            admin,
+            original_amount,
+            new_amount,
+            exchange_deadline,
+            reverse_deadline,
+        )
+    }
+}
+
+/// Creates an original SPL Token mint and a new Token-2022 mint, with funded
+/// escrows of both tokens, and a wallet holding `original_amount` original
+/// tokens. `mint_authority` is the mint authority of the new mint.
+async fn setup_exchange(
+    client: Arc<dyn ProgramClient<ProgramBanksClientProcessTransaction>>,
+    payer: Arc<Keypair>,
+    original_decimals: u8,
+    new_decimals: u8,
+    original_amount: u64,
+    escrow_amount: u64,
+) -> Exchange {
+    let wallet = Keypair::new();
+    let original_mint_authority = Keypair::new();
+    let mint_authority = Keypair::new();
+    let mint_authority_pubkey = mint_authority.pubkey();
+    let original_token = setup_mint(
+        &spl_token::id(),
+        &original_mint_authority.pubkey(),
+        original_decimals,
+        payer.clone(),
+        client.clone(),
+    )
+    .await;
+    let new_token = setup_mint(
+        &spl_token_2022::id(),
+        &mint_authority_pubkey,
+        new_decimals,
+        payer.clone(),
+        client.clone(),
+    )
+    .await;
+    let program_escrow = get_token_upgrade_authority_address(
+        original_token.get_address(),
+        new_token.get_address(),
+        &spl_token_upgrade::id(),
+    );
+
+    let mut accounts = vec![];
+    for (token, authority, owner, amount) in [
+        (
+            &original_token,
+            &original_mint_authority,
+            wallet.pubkey(),
+            original_amount,
+        ),
+        (&new_token, &mint_authority, wallet.pubkey(), 0),
+        (
+            &original_token,
+            &original_mint_authority,
+            program_escrow,
+            escrow_amount,
+        ),
+        (&new_token, &mint_authority, program_escrow, escrow_amount),
+    ] {
+        token.create_associated_token_account(&owner).await.unwrap();
+        let account = token.get_associated_token_address(&owner);
+        if amount > 0 {
+            token
+                .mint_to(&account, &authority.pubkey(), amount, &[authority])
+                .await
+                .unwrap();
+        }
+        accounts.push(account);
+    }
+
+    Exchange {
+        original_token,
+        new_token,
+        original_mint_authority,
+        mint_authority,
+        wallet,
+        original_account: accounts[0],
+        new_account: accounts[1],
+        original_escrow: accounts[2],
+        new_escrow: accounts[3],
+    }
+}
+
+#[tokio::test]
+async fn success_exchange_rate() {
+    let (context, client, payer) = setup().await;
+    // 2 original tokens with 9 decimals for 3 new tokens with 6 decimals
+    let exchange = setup_exchange(client, payer.clone(), 9, 6, 10_000_000_000, 1_000_000_000).await;
+    let instruction = exchange.initialize_exchange_config(
+        &payer.pubkey(),
+        &exchange.mint_authority.pubkey(),
+        2_000_000_000,
+        3_000_000,
+        0,
+        0,
+    );
+    process(&context, &[instruction], &[&exchange.mint_authority])
+        .await
+        .unwrap();
+
+    let exchange_config_address = get_exchange_config_address(
+        exchange.original_token.get_address(),
+        exchange.new_token.get_address(),
+        &spl_token_upgrade::id(),
+    );
+    let exchange_config_account = {
+        let context = context.lock().await;
+        context
+            .banks_client
+            .get_account(exchange_config_address)
+            .await
+            .unwrap()
+            .unwrap()
+    };
+    let exchange_config = bytemuck::from_bytes::<ExchangeConfig>(&exchange_config_account.data);
+    assert_eq!(exchange_config.admin, exchange.mint_authority.pubkey());
+    assert_eq!(u64::from(exchange_config.new_amount), 3_000_000);
+
+    process(&context, &[exchange.exchange()], &[&exchange.wallet])
+        .await
+        .unwrap();
+    let original_account = exchange
+        .original_token
+        .get_account_info(&exchange.original_account)
+        .await
+        .unwrap();
+    assert_eq!(original_account.base.amount, 0);
+    let new_account = exchange
+        .new_token
+        .get_account_info(&exchange.new_account)
+        .await
+        .unwrap();
+    assert_eq!(new_account.base.amount, 15_000_000);
+
+    // a second config cannot replace the first
+    let instruction = exchange.initialize_exchange_config(
+        &payer.pubkey(),
+        &exchange.mint_authority.pubkey(),
+        1,
+        1,
+        0,
+        0,
+    );
+    assert_eq!(
+        process(&context, &[instruction], &[&exchange.mint_authority])
+            .await
+            .unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::AccountAlreadyInitialized)
+    );
+}
+
+#[tokio::test]
+async fn success_exchange_without_exchange_config_account() {
+    let (context, client, payer) = setup().await;
+    let exchange = setup_exchange(client, payer, 2, 2, 1_000, 1_000).await;
+
+    // clients built before exchange configs don't pass the trailing account
+    let mut instruction = exchange.exchange();
+    let exchange_config = instruction.accounts.pop().unwrap();
+    assert_eq!(
+        exchange_config.pubkey,
+        get_exchange_config_address(
+            exchange.original_token.get_address(),
+            exchange.new_token.get_address(),
+            &spl_token_upgrade::id(),
+        )
+    );
+    process(&context, &[instruction], &[&exchange.wallet])
+        .await
+        .unwrap();
+
+    let new_account = exchange
+        .new_token
+        .get_account_info(&exchange.new_account)
+        .await
+        .unwrap();
+    assert_eq!(new_account.base.amount, 1_000);
+}
+
+#[tokio::test]
+async fn fail_initialize_exchange_config() {
+    let (context, client, payer) = setup().await;
+    let exchange = setup_exchange(client, payer.clone(), 2, 2, 1_000, 1_000).await;
+
+    let not_an_issuer = Keypair::new();
+    let instruction =
+        exchange.initialize_exchange_config(&payer.pubkey(), &not_an_issuer.pubkey(), 1, 1, 0, 0);
+    assert_eq!(
+        process(&context, &[instruction], &[&not_an_issuer])
+            .await
+            .unwrap_err(),
+        custom_error(TokenUpgradeError::InvalidAdmin)
+    );
+
+    // the issuer of the original token doesn't fund the new escrow, so it
+    // can't take control of it
+    let instruction = exchange.initialize_exchange_config(
+        &payer.pubkey(),
+        &exchange.original_mint_authority.pubkey(),
+        1,
+        1,
+        0,
+        0,
+    );
+    assert_eq!(
+        process(
+            &context,
+            &[instruction],
+            &[&exchange.original_mint_authority]
+        )
+        .await
+        .unwrap_err(),
+        custom_error(TokenUpgradeError::InvalidAdmin)
+    );
+
+    let instruction = exchange.initialize_exchange_config(
+        &payer.pubkey(),
+        &exchange.mint_authority.pubkey(),
+        1,
+        0,
+        0,
+        0,
+    );
+    assert_eq!(
+        process(&context, &[instruction], &[&exchange.mint_authority])
+            .await
+            .unwrap_err(),
+        custom_error(TokenUpgradeError::InvalidExchangeRate)
+    );
+}
+
+#[tokio::test]
+async fn success_reverse_exchange() {
+    let (context, client, payer) = setup().await;
+    let exchange = setup_exchange(client, payer.clone(), 2, 2, 1_000, 10_000).await;
+    let now = get_unix_timestamp(&context).await;
+    let instructions = [
+        exchange.initialize_exchange_config(
+            &payer.pubkey(),
+            &exchange.mint_authority.pubkey(),
+            1,
+            2,
+            0,
+            now + 1_000,
+        ),
+        exchange.exchange(),
+    ];
+    process(
+        &context,
+        &instructions,
+        &[&exchange.mint_authority, &exchange.wallet],
+    )
+    .await
+    .unwrap();
+
+    // new tokens must go back into the escrow
+    let instruction = exchange.reverse_exchange(&exchange.new_account, 500);
+    assert_eq!(
+        process(&context, &[instruction], &[&exchange.wallet])
+            .await
+            .unwrap_err(),
+        custom_error(TokenUpgradeError::InvalidEscrow)
+    );
+
+    let instruction = exchange.reverse_exchange(&exchange.new_escrow, 501);
+    process(&context, &[instruction], &[&exchange.wallet])
+        .await
+        .unwrap();
+    let original_account = exchange
+        .original_token
+        .get_account_info(&exchange.original_account)
+        .await
+        .unwrap();
+    assert_eq!(original_account.base.amount, 250);
+    let new_account = exchange
+        .new_token
+        .get_account_info(&exchange.new_account)
+        .await
+        .unwrap();
+    assert_eq!(new_account.base.amount, 1_499);
+    let new_escrow = exchange
+        .new_token
+        .get_account_info(&exchange.new_escrow)
+        .await
+        .unwrap();
+    assert_eq!(new_escrow.base.amount, 10_000 - 2_000 + 501);
+
+    // too small to receive anything
+    let instruction = exchange.reverse_exchange(&exchange.new_escrow, 1);
+    assert_eq!(
+        process(&context, &[instruction], &[&exchange.wallet])
+            .await
+            .unwrap_err(),
+        custom_error(TokenUpgradeError::AmountTooSmall)
+    );
+
+    // the window closes
+    set_unix_timestamp(&context, now + 1_000).await;
+    let instruction = exchange.reverse_exchange(&exchange.new_escrow, 2);
+    assert_eq!(
+        process(&context, &[instruction], &[&exchange.wallet])
+            .await
+            .unwrap_err(),
+        custom_error(TokenUpgradeError::ReverseExchangeClosed)
+    );
+}
+
+#[tokio::test]
+async fn fail_reverse_exchange_without_config() {
+    let (context, client, payer) = setup().await;
+    let exchange = setup_exchange(client, payer, 2, 2, 1_000, 10_000).await;
+    process(&context, &[exchange.exchange()], &[&exchange.wallet])
+        .await
+        .unwrap();
+
+    let instruction = exchange.reverse_exchange(&exchange.new_escrow, 100);
+    assert_eq!(
+        process(&context, &[instruction], &[&exchange.wallet])
+            .await
+            .unwrap_err(),
+        custom_error(TokenUpgradeError::ReverseExchangeClosed)
+    );
+}
+
+#[tokio::test]
+async fn success_reclaim_after_deadline() {
+    let (context, client, payer) = setup().await;
+    let exchange = setup_exchange(client, payer.clone(), 2, 2, 1_000, 10_000).await;
+    let now = get_unix_timestamp(&context).await;
+    let instruction = exchange.initialize_exchange_config(
+        &payer.pubkey(),
+        &exchange.mint_authority.pubkey(),
+        1,
+        1,
+        now + 1_000,
+        0,
+    );
+    process(&context, &[instruction], &[&exchange.mint_authority])
+        .await
+        .unwrap();
+
+    let admin_account = exchange
+        .new_token
+        .get_associated_token_address(&exchange.mint_authority.pubkey());
+    exchange
+        .new_token
+        .create_associated_token_account(&exchange.mint_authority.pubkey())
+        .await
+        .unwrap();
+    let reclaim_instruction = |admin: &Pubkey| {
+        reclaim(
+            &spl_token_upgrade::id(),
+            &exchange.new_escrow,
+            exchange.new_token.get_address(),
+            &admin_account,
+            &spl_token_2022::id(),
+            exchange.original_token.get_address(),
+            exchange.new_token.get_address(),
+            admin,
+        )
+    };
+
+    assert_eq!(
+        process(
+            &context,
+            &[reclaim_instruction(&exchange.mint_authority.pubkey())],
+            &[&exchange.mint_authority]
+        )
+        .await
+        .unwrap_err(),
+        custom_error(TokenUpgradeError::ExchangeDeadlineNotReached)
+    );
+
+    set_unix_timestamp(&context, now + 1_000).await;
+    assert_eq!(
+        process(&context, &[exchange.exchange()], &[&exchange.wallet])
+            .await
+            .unwrap_err(),
+        custom_error(TokenUpgradeError::ExchangeDeadlinePassed)
+    );
+
+    let not_admin = Keypair::new();
+    assert_eq!(
+        process(
+            &context,
+            &[reclaim_instruction(&not_admin.pubkey())],
+            &[&not_admin]
+        )
+        .await
+        .unwrap_err(),
+        custom_error(TokenUpgradeError::InvalidAdmin)
+    );
+
+    process(
+        &context,
+        &[reclaim_instruction(&exchange.mint_authority.pubkey())],
+        &[&exchange.mint_authority],
+    )
+    .await
+    .unwrap();
+    let admin_account = exchange
+        .new_token
+        .get_account_info(&admin_account)
+        .await
+        .unwrap();
+    assert_eq!(admin_account.base.amount, 10_000);
+    let new_escrow = exchange
+        .new_token
+        .get_account_info(&exchange.new_escrow)
+        .await
+        .unwrap();
+    assert_eq!(new_escrow.base.amount, 0);
+}
@@ -0,0 +1,1287 @@
+mod output;
+
+use {
+    crate::output::{CliCreateMint, CliSyncMetadata, CliTransfer, CliWrapInfo, CliWrappedMint},
+    clap::{crate_description, crate_name, crate_version, Arg, ArgAction, ArgMatches, Command},
+    solana_clap_v3_utils::{
+        input_parsers::{
+            parse_url_or_moniker,
+            signer::{SignerSource, SignerSourceParserBuilder},
+        },
+        input_validators::normalize_to_url_if_moniker,
+        keypair::{signer_from_path, signer_from_source, SignerFromPathConfig},
+    },
+    solana_cli_output::OutputFormat,
+    solana_client::nonblocking::rpc_client::RpcClient,
+    solana_remote_wallet::remote_wallet::RemoteWalletManager,
+    solana_sdk::{
+        account::Account as SolanaAccount,
+        commitment_config::CommitmentConfig,
+        instruction::Instruction,
+        message::Message,
+        pubkey::Pubkey,
+        signature::{Signature, Signer},
+        transaction::Transaction,
+    },
+    spl_associated_token_account_client::{
+        address::get_associated_token_address_with_program_id,
+        instruction::create_associated_token_account_idempotent,
+    },
+    spl_token_2022::{
+        amount_to_ui_amount_string_trimmed, check_spl_token_program_account,
+        extension::{
+            metadata_pointer::MetadataPointer, BaseStateWithExtensions, StateWithExtensions,
+        },
+        state::{Account, Mint},
+        ui_amount_to_amount,
+    },
+    spl_token_wrap::{
+        get_wrapped_mint_address, get_wrapped_mint_authority, get_wrapped_mint_backpointer_address,
+        instruction::{create_mint, sync_metadata, unwrap, wrap},
+        state::Backpointer,
+    },
+    std::{error::Error, process::exit, rc::Rc, sync::Arc},
+};
+
+struct Config {
+    commitment_config: CommitmentConfig,
+    payer: Arc<dyn Signer>,
+    json_rpc_url: String,
+    output_format: OutputFormat,
+    verbose: bool,
+}
+
+async fn get_account(
+    rpc_client: &RpcClient,
+    address: &Pubkey,
+) -> Result<Option<SolanaAccount>, Box<dyn Error>> {
+    Ok(rpc_client
+        .get_account_with_commitment(address, rpc_client.commitment())
+        .await?
+        .value)
+}
+
+/// Returns the token program owning the mint, along with the mint
+async fn get_mint_checked(
+    rpc_client: &RpcClient,
+    mint: &Pubkey,
+) -> Result<(Pubkey, Mint), Box<dyn Error>> {
+    let mint_account = get_account(rpc_client, mint)
+        .await?
+        .ok_or_else(|| format!("Mint {} does not exist", mint))?;
+    check_spl_token_program_account(&mint_account.owner)
+        .map_err(|_| format!("Mint {} is not owned by a token program", mint))?;
+    let mint_state = StateWithExtensions::<Mint>::unpack(&mint_account.data)
+        .map_err(|_| format!("Account {} is not a valid mint", mint))?;
+    Ok((mint_account.owner, mint_state.base))
+}
+
+/// Reads the backpointer of a wrapped mint, returning `None` if the mint is
+/// not wrapped
+async fn get_unwrapped_mint(
+    rpc_client: &RpcClient,
+    wrapped_mint: &Pubkey,
+) -> Result<Option<Pubkey>, Box<dyn Error>> {
+    let backpointer_address = get_wrapped_mint_backpointer_address(wrapped_mint);
+    let Some(backpointer_account) = get_account(rpc_client, &backpointer_address).await? else {
+        return Ok(None);
+    };
+    if backpointer_account.owner != spl_token_wrap::id() {
+        return Err(format!(
+            "Backpointer {} is not owned by the token wrap program",
+            backpointer_address
+        )
+        .into());
+    }
+    let backpointer = bytemuck::try_from_bytes::<Backpointer>(&backpointer_account.data)
+        .map_err(|_| format!("Account {} is not a valid backpointer", backpointer_address))?;
+    Ok(Some(backpointer.unwrapped_mint))
+}
+
+async fn send_transaction(
+    rpc_client: &RpcClient,
+    payer: &Arc<dyn Signer>,
+    instructions: &[Instruction],
+    bulk_signers: &[Arc<dyn Signer>],
+) -> Result<Signature, Box<dyn Error>> {
+    let mut transaction =
+        Transaction::new_unsigned(Message::new(instructions, Some(&payer.pubkey())));
+
+    let blockhash = rpc_client
+        .get_latest_blockhash()
+        .await
+        .map_err(|err| format!("error: unable to get latest blockhash: {}", err))?;
+
+    transaction
+        .try_sign(bulk_signers, blockhash)
+        .map_err(|err| format!("error: failed to sign transaction: {}", err))?;
+
+    let signature = rpc_client
+        .send_and_confirm_transaction_with_spinner(&transaction)
+        .await
+        .map_err(|err| format!("error: send transaction: {}", err))?;
+
+    Ok(signature)
+}
+
+async fn process_create_mint(
+    rpc_client: &Arc<RpcClient>,
+    payer: &Arc<dyn Signer>,
+    unwrapped_mint: &Pubkey,
+    wrapped_token_program_id: &Pubkey,
+    idempotent: bool,
+) -> Result<CliCreateMint, Box<dyn Error>> {
+    let _ = get_mint_checked(rpc_client, unwrapped_mint).await?;
+    check_spl_token_program_account(wrapped_token_program_id)
+        .map_err(|_| format!("{} is not a token program", wrapped_token_program_id))?;
+
+    let wrapped_mint = get_wrapped_mint_address(unwrapped_mint, wrapped_token_program_id);
+    let wrapped_backpointer = get_wrapped_mint_backpointer_address(&wrapped_mint);
+    let mut output = CliCreateMint {
+        wrapped_mint: wrapped_mint.to_string(),
+        wrapped_backpointer: wrapped_backpointer.to_string(),
+        wrapped_mint_authority: get_wrapped_mint_authority(&wrapped_mint).to_string(),
+        signature: None,
+    };
+
+    if get_account(rpc_client, &wrapped_mint).await?.is_some() {
+        if idempotent {
+            return Ok(output);
+        }
+        return Err(format!("Wrapped mint {} already exists", wrapped_mint).into());
+    }
+
+    let signature = send_transaction(
+        rpc_client,
+        payer,
+        &[create_mint(
+            &spl_token_wrap::id(),
+            &payer.pubkey(),
+            &wrapped_mint,
+            &wrapped_backpointer,
+            unwrapped_mint,
+            wrapped_token_program_id,
+            idempotent,
+        )],
+        &[payer.clone()],
+    )
+    .await?;
+    output.signature = Some(signature.to_string());
+    Ok(output)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn process_wrap(
+    rpc_client: &Arc<RpcClient>,
+    payer: &Arc<dyn Signer>,
+    unwrapped_mint: &Pubkey,
+    wrapped_token_program_id: &Pubkey,
+    ui_amount: f64,
+    owner: &Arc<dyn Signer>,
+    source: Option<Pubkey>,
+    escrow: Option<Pubkey>,
+    recipient: Option<Pubkey>,
+    multisig_pubkeys: &[Pubkey],
+    bulk_signers: Vec<Arc<dyn Signer>>,
+) -> Result<CliTransfer, Box<dyn Error>> {
+    let (unwrapped_token_program_id, unwrapped_mint_state) =
+        get_mint_checked(rpc_client, unwrapped_mint).await?;
+    let wrapped_mint = get_wrapped_mint_address(unwrapped_mint, wrapped_token_program_id);
+    get_mint_checked(rpc_client, &wrapped_mint)
+        .await
+        .map_err(|_| {
+            format!(
+                "Wrapped mint {} does not exist, create it with `create-mint` first",
+                wrapped_mint
+            )
+        })?;
+    let wrapped_mint_authority = get_wrapped_mint_authority(&wrapped_mint);
+    let amount = ui_amount_to_amount(ui_amount, unwrapped_mint_state.decimals);
+
+    let mut instructions = vec![];
+    let source = source.unwrap_or_else(|| {
+        get_associated_token_address_with_program_id(
+            &owner.pubkey(),
+            unwrapped_mint,
+            &unwrapped_token_program_id,
+        )
+    });
+    let escrow = escrow.unwrap_or_else(|| {
+        instructions.push(create_associated_token_account_idempotent(
+            &payer.pubkey(),
+            &wrapped_mint_authority,
+            unwrapped_mint,
+            &unwrapped_token_program_id,
+        ));
+        get_associated_token_address_with_program_id(
+            &wrapped_mint_authority,
+            unwrapped_mint,
+            &unwrapped_token_program_id,
+        )
+    });
+    let recipient = recipient.unwrap_or_else(|| {
+        instructions.push(create_associated_token_account_idempotent(
+            &payer.pubkey(),
+            &owner.pubkey(),
+            &wrapped_mint,
+            wrapped_token_program_id,
+        ));
+        get_associated_token_address_with_program_id(
+            &owner.pubkey(),
+            &wrapped_mint,
+            wrapped_token_program_id,
+        )
+    });
+
+    instructions.push(wrap(
+        &spl_token_wrap::id(),
+        &source,
+        &escrow,
+        unwrapped_mint,
+        &wrapped_mint,
+        &recipient,
+        &unwrapped_token_program_id,
+        wrapped_token_program_id,
+        &owner.pubkey(),
+        &multisig_pubkeys.iter().collect::<Vec<_>>(),
+        amount,
+    ));
+    let signature = send_transaction(rpc_client, payer, &instructions, &bulk_signers).await?;
+
+    Ok(CliTransfer {
+        source: source.to_string(),
+        destination: recipient.to_string(),
+        escrow: escrow.to_string(),
+        amount: amount_to_ui_amount_string_trimmed(amount, unwrapped_mint_state.decimals),
+        signature: signature.to_string(),
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn process_unwrap(
+    rpc_client: &Arc<RpcClient>,
+    payer: &Arc<dyn Signer>,
+    wrapped_mint: &Pubkey,
+    ui_amount: f64,
+    owner: &Arc<dyn Signer>,
+    source: Option<Pubkey>,
+    escrow: Option<Pubkey>,
+    recipient: Option<Pubkey>,
+    multisig_pubkeys: &[Pubkey],
+    bulk_signers: Vec<Arc<dyn Signer>>,
+) -> Result<CliTransfer, Box<dyn Error>> {
+    let (wrapped_token_program_id, wrapped_mint_state) =
+        get_mint_checked(rpc_client, wrapped_mint).await?;
+    let unwrapped_mint = get_unwrapped_mint(rpc_client, wrapped_mint)
+        .await?
+        .ok_or_else(|| format!("Mint {} is not a wrapped mint", wrapped_mint))?;
+    let (unwrapped_token_program_id, _) = get_mint_checked(rpc_client, &unwrapped_mint).await?;
+    let wrapped_mint_authority = get_wrapped_mint_authority(wrapped_mint);
+    let amount = ui_amount_to_amount(ui_amount, wrapped_mint_state.decimals);
+
+    let mut instructions = vec![];
+    let source = source.unwrap_or_else(|| {
+        get_associated_token_address_with_program_id(
+            &owner.pubkey(),
+            wrapped_mint,
+            &wrapped_token_program_id,
+        )
+    });
+    let escrow = escrow.unwrap_or_else(|| {
+        get_associated_token_address_with_program_id(
+            &wrapped_mint_authority,
+            &unwrapped_mint,
+            &unwrapped_token_program_id,
+        )
+    });
+    let recipient = recipient.unwrap_or_else(|| {
+        instructions.push(create_associated_token_account_idempotent(
+            &payer.pubkey(),
+            &owner.pubkey(),
+            &unwrapped_mint,
+            &unwrapped_token_program_id,
+        ));
+        get_associated_token_address_with_program_id(
+            &owner.pubkey(),
+            &unwrapped_mint,
+            &unwrapped_token_program_id,
+        )
+    });
+
+    instructions.push(unwrap(
+        &spl_token_wrap::id(),
+        &source,
+        wrapped_mint,
+        &escrow,
+        &recipient,
+        &unwrapped_mint,
+        &wrapped_token_program_id,
+        &unwrapped_token_program_id,
+        &owner.pubkey(),
+        &multisig_pubkeys.iter().collect::<Vec<_>>(),
+        amount,
+    ));
+    let signature = send_transaction(rpc_client, payer, &instructions, &bulk_signers).await?;
+
+    Ok(CliTransfer {
+        source: source.to_string(),
+        destination: recipient.to_string(),
+        escrow: escrow.to_string(),
+        amount: amount_to_ui_amount_string_trimmed(amount, wrapped_mint_state.decimals),
+        signature: signature.to_string(),
+    })
+}
+
+/// Finds where the metadata of the unwrapped mint lives, returning the
+/// Metaplex metadata address if the mint does not hold its own metadata
+async fn get_metadata_source(
+    rpc_client: &RpcClient,
+    unwrapped_mint: &Pubkey,
+) -> Result<Option<Pubkey>, Box<dyn Error>> {
+    let mint_account = get_account(rpc_client, unwrapped_mint)
+        .await?
+        .ok_or_else(|| format!("Mint {} does not exist", unwrapped_mint))?;
+    if mint_account.owner == spl_token_2022::id() {
+        let mint_state = StateWithExtensions::<Mint>::unpack(&mint_account.data)
+            .map_err(|_| format!("Account {} is not a valid mint", unwrapped_mint))?;
+        if let Ok(pointer) = mint_state.get_extension::<MetadataPointer>() {
+            if Option::<Pubkey>::from(pointer.metadata_address) == Some(*unwrapped_mint) {
+                return Ok(None);
+            }
+        }
+    }
+
+    let metaplex_metadata = spl_inline_mpl_token_metadata::get_metadata_address(unwrapped_mint);
+    if get_account(rpc_client, &metaplex_metadata).await?.is_none() {
+        return Err(format!("Mint {} has no metadata to copy", unwrapped_mint).into());
+    }
+    Ok(Some(metaplex_metadata))
+}
+
+async fn process_sync_metadata(
+    rpc_client: &Arc<RpcClient>,
+    payer: &Arc<dyn Signer>,
+    wrapped_mint: &Pubkey,
+) -> Result<CliSyncMetadata, Box<dyn Error>> {
+    let (wrapped_token_program_id, _) = get_mint_checked(rpc_client, wrapped_mint).await?;
+    if wrapped_token_program_id != spl_token_2022::id() {
+        return Err(format!("Mint {} is not a Token-2022 mint", wrapped_mint).into());
+    }
+    let unwrapped_mint = get_unwrapped_mint(rpc_client, wrapped_mint)
+        .await?
+        .ok_or_else(|| format!("Mint {} is not a wrapped mint", wrapped_mint))?;
+    let metaplex_metadata = get_metadata_source(rpc_client, &unwrapped_mint).await?;
+
+    let signature = send_transaction(
+        rpc_client,
+        payer,
+        &[sync_metadata(
+            &spl_token_wrap::id(),
+            &payer.pubkey(),
+            wrapped_mint,
+            &unwrapped_mint,
+            metaplex_metadata.as_ref(),
+        )],
+        &[payer.clone()],
+    )
+    .await?;
+
+    Ok(CliSyncMetadata {
+        wrapped_mint: wrapped_mint.to_string(),
+        metadata_source: metaplex_metadata.unwrap_or(unwrapped_mint).to_string(),
+        signature: signature.to_string(),
+    })
+}
+
+async fn get_wrapped_mint_info(
+    rpc_client: &RpcClient,
+    unwrapped_mint: &Pubkey,
+    wrapped_mint: &Pubkey,
+) -> Result<CliWrappedMint, Box<dyn Error>> {
+    let (unwrapped_token_program_id, unwrapped_mint_state) =
+        get_mint_checked(rpc_client, unwrapped_mint).await?;
+    let (wrapped_token_program_id, wrapped_mint_state) =
+        get_mint_checked(rpc_client, wrapped_mint).await?;
+    let wrapped_mint_authority = get_wrapped_mint_authority(wrapped_mint);
+    let escrow = get_associated_token_address_with_program_id(
+        &wrapped_mint_authority,
+        unwrapped_mint,
+        &unwrapped_token_program_id,
+    );
+    let escrow_balance = match get_account(rpc_client, &escrow).await? {
+        Some(escrow_account) => {
+            let escrow_state = StateWithExtensions::<Account>::unpack(&escrow_account.data)
+                .map_err(|_| format!("Account {} is not a valid account", escrow))?;
+            Some(amount_to_ui_amount_string_trimmed(
+                escrow_state.base.amount,
+                unwrapped_mint_state.decimals,
+            ))
+        }
+        None => None,
+    };
+
+    Ok(CliWrappedMint {
+        unwrapped_mint: unwrapped_mint.to_string(),
+        unwrapped_token_program: unwrapped_token_program_id.to_string(),
+        wrapped_mint: wrapped_mint.to_string(),
+        wrapped_token_program: wrapped_token_program_id.to_string(),
+        wrapped_mint_authority: wrapped_mint_authority.to_string(),
+        wrapped_backpointer: get_wrapped_mint_backpointer_address(wrapped_mint).to_string(),
+        wrapped_supply: amount_to_ui_amount_string_trimmed(
+            wrapped_mint_state.supply,
+            wrapped_mint_state.decimals,
+        ),
+        escrow: escrow.to_string(),
+        escrow_balance,
+    })
+}
+
+async fn process_info(
+    rpc_client: &Arc<RpcClient>,
+    mint: &Pubkey,
+) -> Result<CliWrapInfo, Box<dyn Error>> {
+    let _ = get_mint_checked(rpc_client, mint).await?;
+
+    let mut wrapped_mints = vec![];
+    if let Some(unwrapped_mint) = get_unwrapped_mint(rpc_client, mint).await? {
+        wrapped_mints.push(get_wrapped_mint_info(rpc_client, &unwrapped_mint, mint).await?);
+    } else {
+        for wrapped_token_program_id in [spl_token::id(), spl_token_2022::id()] {
+            let wrapped_mint = get_wrapped_mint_address(mint, &wrapped_token_program_id);
+            if get_account(rpc_client, &wrapped_mint).await?.is_some() {
+                wrapped_mints.push(get_wrapped_mint_info(rpc_client, mint, &wrapped_mint).await?);
+            }
+        }
+    }
+
+    Ok(CliWrapInfo {
+        mint: mint.to_string(),
+        wrapped_mints,
+    })
+}
+
+/// Owner and signers of a wrap or unwrap
+struct TransferSigners {
+    owner: Arc<dyn Signer>,
+    multisig_pubkeys: Vec<Pubkey>,
+    bulk_signers: Vec<Arc<dyn Signer>>,
+}
+
+fn get_transfer_signers(
+    matches: &ArgMatches,
+    config: &Config,
+    wallet_manager: &mut Option<Rc<RemoteWalletManager>>,
+) -> Result<TransferSigners, Box<dyn Error>> {
+    let mut bulk_signers = vec![config.payer.clone()];
+    let mut multisig_pubkeys = vec![];
+
+    if let Some(sources) = matches.try_get_many::<SignerSource>("multisig_signer")? {
+        for (i, source) in sources.enumerate() {
+            let name = format!("{}-{}", "multisig_signer", i.saturating_add(1));
+            let signer =
+                signer_from_source(matches, source, &name, wallet_manager).unwrap_or_else(|e| {
+                    eprint!("error parsing multisig signer: {}", e);
+                    exit(1);
+                });
+            let signer_pubkey = signer.pubkey();
+            let signer = Arc::from(signer);
+            if !bulk_signers.contains(&signer) {
+                bulk_signers.push(signer);
+            }
+            if !multisig_pubkeys.contains(&signer_pubkey) {
+                multisig_pubkeys.push(signer_pubkey);
+            }
+        }
+    }
+
+    let signer_config = SignerFromPathConfig {
+        allow_null_signer: !multisig_pubkeys.is_empty(),
+    };
+    let owner = if let Ok(Some((signer, _))) =
+        SignerSource::try_get_signer(matches, "owner", wallet_manager)
+    {
+        Arc::from(signer)
+    } else {
+        config.payer.clone()
+    };
+    if !signer_config.allow_null_signer && !bulk_signers.contains(&owner) {
+        bulk_signers.push(owner.clone());
+    }
+
+    Ok(TransferSigners {
+        owner,
+        multisig_pubkeys,
+        bulk_signers,
+    })
+}
+
+fn transfer_args(command: Command<'static>) -> Command<'static> {
+    command
+        .arg(
+            Arg::new("owner")
+                .long("owner")
+                .value_name("OWNER_KEYPAIR")
+                .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+                .takes_value(true)
+                .help("Specify the owner or delegate for the source account. This may be a keypair file or the ASK keyword. [default: fee payer]"),
+        )
+        .arg(
+            Arg::new("source")
+                .long("source")
+                .value_name("SOURCE_TOKEN_ACCOUNT_ADDRESS")
+                .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+                .takes_value(true)
+                .help("Specify the account to take tokens from. [default: associated token account for owner]"),
+        )
+        .arg(
+            Arg::new("escrow")
+                .long("escrow")
+                .value_name("ESCROW_TOKEN_ACCOUNT_ADDRESS")
+                .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+                .takes_value(true)
+                .help("Specify the escrow account holding unwrapped tokens. [default: associated token account for the wrapped mint authority]"),
+        )
+        .arg(
+            Arg::new("recipient")
+                .long("recipient")
+                .value_name("RECIPIENT_TOKEN_ACCOUNT_ADDRESS")
+                .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+                .takes_value(true)
+                .help("Specify the account to receive tokens, created if needed. [default: associated token account for owner]"),
+        )
+        .arg(
+            Arg::new("multisig_signer")
+                .long("multisig-signer")
+                .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+                .value_name("MULTISIG_SIGNER")
+                .takes_value(true)
+                .action(ArgAction::Append)
+                .min_values(0)
+                .max_values(spl_token_2022::instruction::MAX_SIGNERS)
+                .help("Member signer of a multisig account"),
+        )
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let app_matches = Command::new(crate_name!())
+        .about(crate_description!())
+        .version(crate_version!())
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .arg({
+            let arg = Arg::new("config_file")
+                .short('C')
+                .long("config")
+                .value_name("PATH")
+                .takes_value(true)
+                .global(true)
+                .help("Configuration file to use");
+            if let Some(ref config_file) = *solana_cli_config::CONFIG_FILE {
+                arg.default_value(config_file)
+            } else {
+                arg
+            }
+        })
+        .arg(
+            Arg::new("payer")
+                .long("payer")
+                .value_name("KEYPAIR")
+                .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+                .takes_value(true)
+                .global(true)
+                .help("Filepath or URL to a keypair [default: client keypair]"),
+        )
+        .arg(
+            Arg::new("verbose")
+                .long("verbose")
+                .short('v')
+                .takes_value(false)
+                .global(true)
+                .help("Show additional information"),
+        )
+        .arg(
+            Arg::new("output_format")
+                .long("output")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .global(true)
+                .possible_values(["json", "json-compact"])
+                .help("Return information in specified output format"),
+        )
+        .arg(
+            Arg::new("json_rpc_url")
+                .short('u')
+                .long("url")
+                .value_name("URL")
+                .takes_value(true)
+                .global(true)
+                .value_parser(parse_url_or_moniker)
+                .help("JSON RPC URL for the cluster [default: value from configuration file]"),
+        )
+        .subcommand(
+            Command::new("create-mint")
+                .about("Create the wrapped mint for an unwrapped mint")
+                .arg(
+                    Arg::new("unwrapped_mint")
+                        .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+                        .value_name("ADDRESS")
+                        .required(true)
+                        .takes_value(true)
+                        .index(1)
+                        .help("Unwrapped mint address, whose tokens will be held in escrow"),
+                )
+                .arg(
+                    Arg::new("wrapped_token_program")
+                        .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+                        .value_name("PROGRAM_ID")
+                        .required(true)
+                        .takes_value(true)
+                        .index(2)
+                        .help(
+                            "Token program of the wrapped mint, either SPL Token or SPL Token-2022",
+                        ),
+                )
+                .arg(
+                    Arg::new("idempotent")
+                        .long("idempotent")
+                        .takes_value(false)
+                        .help("Succeed without doing anything if the wrapped mint already exists"),
+                ),
+        )
+        .subcommand(transfer_args(
+            Command::new("wrap")
+                .about("Wrap tokens, moving them into escrow and minting wrapped tokens")
+                .arg(
+                    Arg::new("unwrapped_mint")
+                        .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+                        .value_name("ADDRESS")
+                        .required(true)
+                        .takes_value(true)
+                        .index(1)
+                        .help("Unwrapped mint address, whose tokens will be held in escrow"),
+                )
+                .arg(
+                    Arg::new("wrapped_token_program")
+                        .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+                        .value_name("PROGRAM_ID")
+                        .required(true)
+                        .takes_value(true)
+                        .index(2)
+                        .help(
+                            "Token program of the wrapped mint, either SPL Token or SPL Token-2022",
+                        ),
+                )
+                .arg(
+                    Arg::new("amount")
+                        .value_parser(clap::value_parser!(f64))
+                        .value_name("AMOUNT")
+                        .required(true)
+                        .takes_value(true)
+                        .index(3)
+                        .help("Amount of unwrapped tokens to wrap"),
+                ),
+        ))
+        .subcommand(transfer_args(
+            Command::new("unwrap")
+                .about("Unwrap tokens, burning wrapped tokens and releasing them from escrow")
+                .arg(
+                    Arg::new("wrapped_mint")
+                        .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+                        .value_name("ADDRESS")
+                        .required(true)
+                        .takes_value(true)
+                        .index(1)
+                        .help("Wrapped mint address, whose tokens will be burned"),
+                )
+                .arg(
+                    Arg::new("amount")
+                        .value_parser(clap::value_parser!(f64))
+                        .value_name("AMOUNT")
+                        .required(true)
+                        .takes_value(true)
+                        .index(2)
+                        .help("Amount of wrapped tokens to unwrap"),
+                ),
+        ))
+        .subcommand(
+            Command::new("sync-metadata")
+                .about("Copy the unwrapped mint's Token-2022 or Metaplex metadata onto a Token-2022 wrapped mint")
+                .arg(
+                    Arg::new("wrapped_mint")
+                        .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+                        .value_name("ADDRESS")
+                        .required(true)
+                        .takes_value(true)
+                        .index(1)
+                        .help("Wrapped mint address, must belong to the Token-2022 program"),
+                ),
+        )
+        .subcommand(
+            Command::new("info")
+                .about("Show the wrapped mints and escrow balances of a mint")
+                .arg(
+                    Arg::new("mint")
+                        .value_parser(SignerSourceParserBuilder::default().allow_all().build())
+                        .value_name("ADDRESS")
+                        .required(true)
+                        .takes_value(true)
+                        .index(1)
+                        .help("Wrapped or unwrapped mint address"),
+                ),
+        )
+        .get_matches();
+
+    let (command, matches) = app_matches.subcommand().unwrap();
+    let mut wallet_manager: Option<Rc<RemoteWalletManager>> = None;
+
+    let config = {
+        let cli_config = if let Some(config_file) = matches.try_get_one::<String>("config_file")? {
+            solana_cli_config::Config::load(config_file).unwrap_or_default()
+        } else {
+            solana_cli_config::Config::default()
+        };
+
+        let payer = if let Ok(Some((signer, _))) =
+            SignerSource::try_get_signer(matches, "payer", &mut wallet_manager)
+        {
+            Box::new(signer)
+        } else {
+            signer_from_path(
+                matches,
+                &cli_config.keypair_path,
+                "payer",
+                &mut wallet_manager,
+            )?
+        };
+
+        let json_rpc_url = normalize_to_url_if_moniker(
+            matches
+                .get_one::<String>("json_rpc_url")
+                .unwrap_or(&cli_config.json_rpc_url),
+        );
+
+        let verbose = matches.try_contains_id("verbose")?;
+        let output_format = match matches
+            .try_get_one::<String>("output_format")?
+            .map(|s| s.as_str())
+        {
+            Some("json") => OutputFormat::Json,
+            Some("json-compact") => OutputFormat::JsonCompact,
+            _ if verbose => OutputFormat::DisplayVerbose,
+            _ => OutputFormat::Display,
+        };
+
+        Config {
+            commitment_config: CommitmentConfig::confirmed(),
+            payer: Arc::from(payer),
+            json_rpc_url,
+            output_format,
+            verbose,
+        }
+    };
+    solana_logger::setup_with_default("solana=info");
+
+    if config.verbose {
+        println!("JSON RPC URL: {}", config.json_rpc_url);
+    }
+    let rpc_client = Arc::new(RpcClient::new_with_commitment(
+        config.json_rpc_url.clone(),
+        config.commitment_config,
+    ));
+
+    let output = match (command, matches) {
+        ("create-mint", arg_matches) => {
+            let unwrapped_mint =
+                SignerSource::try_get_pubkey(arg_matches, "unwrapped_mint", &mut wallet_manager)
+                    .unwrap()
+                    .unwrap();
+            let wrapped_token_program_id = SignerSource::try_get_pubkey(
+                arg_matches,
+                "wrapped_token_program",
+                &mut wallet_manager,
+            )
+            .unwrap()
+            .unwrap();
+            let idempotent = arg_matches.try_contains_id("idempotent")?;
+            let output = process_create_mint(
+                &rpc_client,
+                &config.payer,
+                &unwrapped_mint,
+                &wrapped_token_program_id,
+                idempotent,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                eprintln!("error: create mint: {}", err);
+                exit(1);
+            });
+            config.output_format.formatted_string(&output)
+        }
+        ("wrap", arg_matches) => {
+            let signers = get_transfer_signers(arg_matches, &config, &mut wallet_manager)?;
+            let unwrapped_mint =
+                SignerSource::try_get_pubkey(arg_matches, "unwrapped_mint", &mut wallet_manager)
+                    .unwrap()
+                    .unwrap();
+            let wrapped_token_program_id = SignerSource::try_get_pubkey(
+                arg_matches,
+                "wrapped_token_program",
+                &mut wallet_manager,
+            )
+            .unwrap()
+            .unwrap();
+            let amount = *arg_matches.get_one::<f64>("amount").unwrap();
+            let source =
+                SignerSource::try_get_pubkey(arg_matches, "source", &mut wallet_manager).unwrap();
+            let escrow =
+                SignerSource::try_get_pubkey(arg_matches, "escrow", &mut wallet_manager).unwrap();
+            let recipient =
+                SignerSource::try_get_pubkey(arg_matches, "recipient", &mut wallet_manager)
+                    .unwrap();
+
+            let output = process_wrap(
+                &rpc_client,
+                &config.payer,
+                &unwrapped_mint,
+                &wrapped_token_program_id,
+                amount,
+                &signers.owner,
+                source,
+                escrow,
+                recipient,
+                &signers.multisig_pubkeys,
+                signers.bulk_signers,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                eprintln!("error: wrap: {}", err);
+                exit(1);
+            });
+            config.output_format.formatted_string(&output)
+        }
+        ("unwrap", arg_matches) => {
+            let signers = get_transfer_signers(arg_matches, &config, &mut wallet_manager)?;
+            let wrapped_mint =
+                SignerSource::try_get_pubkey(arg_matches, "wrapped_mint", &mut wallet_manager)
+                    .unwrap()
+                    .unwrap();
+            let amount = *arg_matches.get_one::<f64>("amount").unwrap();
+            let source =
+                SignerSource::try_get_pubkey(arg_matches, "source", &mut wallet_manager).unwrap();
+            let escrow =
+                SignerSource::try_get_pubkey(arg_matches, "escrow", &mut wallet_manager).unwrap();
+            let recipient =
+                SignerSource::try_get_pubkey(arg_matches, "recipient", &mut wallet_manager)
+                    .unwrap();
+
+            let output = process_unwrap(
+                &rpc_client,
+                &config.payer,
+                &wrapped_mint,
+                amount,
+                &signers.owner,
+                source,
+                escrow,
+                recipient,
+                &signers.multisig_pubkeys,
+                signers.bulk_signers,
+            )
+            .await
+            .unwrap_or_else(|err| {
+                eprintln!("error: unwrap: {}", err);
+                exit(1);
+            });
+            config.output_format.formatted_string(&output)
+        }
+        ("sync-metadata", arg_matches) => {
+            let wrapped_mint =
+                SignerSource::try_get_pubkey(arg_matches, "wrapped_mint", &mut wallet_manager)
+                    .unwrap()
+                    .unwrap();
+            let output = process_sync_metadata(&rpc_client, &config.payer, &wrapped_mint)
+                .await
+                .unwrap_or_else(|err| {
+                    eprintln!("error: sync metadata: {}", err);
+                    exit(1);
+                });
+            config.output_format.formatted_string(&output)
+        }
+        ("info", arg_matches) => {
+            let mint = SignerSource::try_get_pubkey(arg_matches, "mint", &mut wallet_manager)
+                .unwrap()
+                .unwrap();
+            let output = process_info(&rpc_client, &mint)
+                .await
+                .unwrap_or_else(|err| {
+                    eprintln!("error: info: {}", err);
+                    exit(1);
+                });
+            config.output_format.formatted_string(&output)
+        }
+        _ => unreachable!(),
+    };
+    println!("{}", output);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        solana_sdk::{bpf_loader_upgradeable, signer::keypair::Keypair},
+        solana_test_validator::{TestValidator, TestValidatorGenesis, UpgradeableProgramInfo},
+        spl_token_client::{
+            client::{
+                ProgramClient, ProgramRpcClient, ProgramRpcClientSendTransaction, SendTransaction,
+                SimulateTransaction,
+            },
+            token::{ExtensionInitializationParams, Token},
+        },
+        std::path::PathBuf,
+    };
+
+    async fn new_validator_for_test() -> (TestValidator, Keypair) {
+        solana_logger::setup();
+        let mut test_validator_genesis = TestValidatorGenesis::default();
+        test_validator_genesis.add_upgradeable_programs_with_path(&[UpgradeableProgramInfo {
+            program_id: spl_token_wrap::id(),
+            loader: bpf_loader_upgradeable::id(),
+            program_path: PathBuf::from("../../target/deploy/spl_token_wrap.so"),
+            upgrade_authority: Pubkey::new_unique(),
+        }]);
+        test_validator_genesis.start_async().await
+    }
+
+    async fn setup_mint<T: SendTransaction + SimulateTransaction>(
+        program_id: &Pubkey,
+        mint_authority: &Pubkey,
+        decimals: u8,
+        payer: Arc<dyn Signer>,
+        client: Arc<dyn ProgramClient<T>>,
+    ) -> Token<T> {
+        let mint_account = Keypair::new();
+        let token = Token::new(
+            client,
+            program_id,
+            &mint_account.pubkey(),
+            Some(decimals),
+            payer,
+        );
+        token
+            .create_mint(mint_authority, None, vec![], &[&mint_account])
+            .await
+            .unwrap();
+        token
+    }
+
+    #[tokio::test]
+    async fn success_create_mint() {
+        let (test_validator, payer) = new_validator_for_test().await;
+        let payer: Arc<dyn Signer> = Arc::new(payer);
+        let rpc_client = Arc::new(test_validator.get_async_rpc_client());
+        let client = Arc::new(ProgramRpcClient::new(
+            rpc_client.clone(),
+            ProgramRpcClientSendTransaction,
+        ));
+
+        let mint_authority = Keypair::new();
+        let unwrapped_token = setup_mint(
+            &spl_token::id(),
+            &mint_authority.pubkey(),
+            2,
+            payer.clone(),
+            client.clone(),
+        )
+        .await;
+
+        let output = process_create_mint(
+            &rpc_client,
+            &payer,
+            unwrapped_token.get_address(),
+            &spl_token_2022::id(),
+            false,
+        )
+        .await
+        .unwrap();
+        assert!(output.signature.is_some());
+        let wrapped_mint =
+            get_wrapped_mint_address(unwrapped_token.get_address(), &spl_token_2022::id());
+        assert_eq!(output.wrapped_mint, wrapped_mint.to_string());
+        assert_eq!(
+            get_unwrapped_mint(&rpc_client, &wrapped_mint)
+                .await
+                .unwrap(),
+            Some(*unwrapped_token.get_address())
+        );
+
+        // only succeeds again if idempotent
+        assert!(process_create_mint(
+            &rpc_client,
+            &payer,
+            unwrapped_token.get_address(),
+            &spl_token_2022::id(),
+            false,
+        )
+        .await
+        .is_err());
+        let output = process_create_mint(
+            &rpc_client,
+            &payer,
+            unwrapped_token.get_address(),
+            &spl_token_2022::id(),
+            true,
+        )
+        .await
+        .unwrap();
+        assert!(output.signature.is_none());
+    }
+
+    #[tokio::test]
+    async fn success_wrap_unwrap() {
+        let (test_validator, payer) = new_validator_for_test().await;
+        let payer: Arc<dyn Signer> = Arc::new(payer);
+        let rpc_client = Arc::new(test_validator.get_async_rpc_client());
+        let client = Arc::new(ProgramRpcClient::new(
+            rpc_client.clone(),
+            ProgramRpcClientSendTransaction,
+        ));
+
+        let mint_authority = Keypair::new();
+        let decimals = 2;
+        let unwrapped_token = setup_mint(
+            &spl_token::id(),
+            &mint_authority.pubkey(),
+            decimals,
+            payer.clone(),
+            client.clone(),
+        )
+        .await;
+        let unwrapped_mint = *unwrapped_token.get_address();
+        process_create_mint(
+            &rpc_client,
+            &payer,
+            &unwrapped_mint,
+            &spl_token_2022::id(),
+            false,
+        )
+        .await
+        .unwrap();
+        let wrapped_mint = get_wrapped_mint_address(&unwrapped_mint, &spl_token_2022::id());
+        let wrapped_token = Token::new(
+            client.clone(),
+            &spl_token_2022::id(),
+            &wrapped_mint,
+            Some(decimals),
+            payer.clone(),
+        );
+
+        let user = Keypair::new();
+        unwrapped_token
+            .create_associated_token_account(&user.pubkey())
+            .await
+            .unwrap();
+        let unwrapped_account = unwrapped_token.get_associated_token_address(&user.pubkey());
+        unwrapped_token
+            .mint_to(
+                &unwrapped_account,
+                &mint_authority.pubkey(),
+                1_000,
+                &[&mint_authority],
+            )
+            .await
+            .unwrap();
+
+        // wrap creates the escrow and the wrapped account
+        let user: Arc<dyn Signer> = Arc::new(user);
+        let output = process_wrap(
+            &rpc_client,
+            &payer,
+            &unwrapped_mint,
+            &spl_token_2022::id(),
+            6.0,
+            &user,
+            None,
+            None,
+            None,
+            &[],
+            vec![payer.clone(), user.clone()],
+        )
+        .await
+        .unwrap();
+        assert_eq!(output.amount, "6");
+        let wrapped_account = wrapped_token.get_associated_token_address(&user.pubkey());
+        assert_eq!(output.destination, wrapped_account.to_string());
+        let account = wrapped_token
+            .get_account_info(&wrapped_account)
+            .await
+            .unwrap();
+        assert_eq!(account.base.amount, 600);
+
+        // info is the same from both sides
+        let info = process_info(&rpc_client, &unwrapped_mint).await.unwrap();
+        assert_eq!(info.wrapped_mints.len(), 1);
+        assert_eq!(info.wrapped_mints[0].wrapped_mint, wrapped_mint.to_string());
+        assert_eq!(info.wrapped_mints[0].wrapped_supply, "6");
+        assert_eq!(info.wrapped_mints[0].escrow_balance.as_deref(), Some("6"));
+        let info = process_info(&rpc_client, &wrapped_mint).await.unwrap();
+        assert_eq!(info.wrapped_mints.len(), 1);
+        assert_eq!(
+            info.wrapped_mints[0].unwrapped_mint,
+            unwrapped_mint.to_string()
+        );
+
+        process_unwrap(
+            &rpc_client,
+            &payer,
+            &wrapped_mint,
+            2.5,
+            &user,
+            None,
+            None,
+            None,
+            &[],
+            vec![payer.clone(), user.clone()],
+        )
+        .await
+        .unwrap();
+        let account = unwrapped_token
+            .get_account_info(&unwrapped_account)
+            .await
+            .unwrap();
+        assert_eq!(account.base.amount, 650);
+        let info = process_info(&rpc_client, &wrapped_mint).await.unwrap();
+        assert_eq!(info.wrapped_mints[0].wrapped_supply, "3.5");
+        assert_eq!(info.wrapped_mints[0].escrow_balance.as_deref(), Some("3.5"));
+    }
+
+    #[tokio::test]
+    async fn info_unwrapped_mint() {
+        let (test_validator, payer) = new_validator_for_test().await;
+        let payer: Arc<dyn Signer> = Arc::new(payer);
+        let rpc_client = Arc::new(test_validator.get_async_rpc_client());
+        let client = Arc::new(ProgramRpcClient::new(
+            rpc_client.clone(),
+            ProgramRpcClientSendTransaction,
+        ));
+
+        let mint_authority = Keypair::new();
+        let token = setup_mint(
+            &spl_token_2022::id(),
+            &mint_authority.pubkey(),
+            0,
+            payer.clone(),
+            client.clone(),
+        )
+        .await;
+        let info = process_info(&rpc_client, token.get_address())
+            .await
+            .unwrap();
+        assert!(info.wrapped_mints.is_empty());
+
+        for wrapped_token_program_id in [spl_token::id(), spl_token_2022::id()] {
+            process_create_mint(
+                &rpc_client,
+                &payer,
+                token.get_address(),
+                &wrapped_token_program_id,
+                false,
+            )
+            .await
+            .unwrap();
+        }
+        let info = process_info(&rpc_client, token.get_address())
+            .await
+            .unwrap();
+        assert_eq!(info.wrapped_mints.len(), 2);
+        assert!(info
+            .wrapped_mints
+            .iter()
+            .all(|wrapped_mint| wrapped_mint.escrow_balance.is_none()));
+    }
+
+    #[tokio::test]
+    async fn success_sync_metadata() {
+        let (test_validator, payer) = new_validator_for_test().await;
+        let payer: Arc<dyn Signer> = Arc::new(payer);
+        let rpc_client = Arc::new(test_validator.get_async_rpc_client());
+        let client = Arc::new(ProgramRpcClient::new(
+            rpc_client.clone(),
+            ProgramRpcClientSendTransaction,
+        ));
+
+        let mint_authority = Keypair::new();
+        let mint_account = Keypair::new();
+        let token = Token::new(
+            client,
+            &spl_token_2022::id(),
+            &mint_account.pubkey(),
+            Some(2),
+            payer.clone(),
+        );
+        token
+            .create_mint(
+                &mint_authority.pubkey(),
+                None,
+                vec![ExtensionInitializationParams::MetadataPointer {
+                    authority: Some(mint_authority.pubkey()),
+                    metadata_address: Some(mint_account.pubkey()),
+                }],
+                &[&mint_account],
+            )
+            .await
+            .unwrap();
+        token
+            .token_metadata_initialize_with_rent_transfer(
+                &payer.pubkey(),
+                &mint_authority.pubkey(),
+                &mint_authority.pubkey(),
+                "Name".to_string(),
+                "SYM".to_string(),
+                "https://example.com".to_string(),
+                &[&mint_authority],
+            )
+            .await
+            .unwrap();
+
+        // SPL Token wrapped mints cannot hold metadata
+        process_create_mint(
+            &rpc_client,
+            &payer,
+            token.get_address(),
+            &spl_token::id(),
+            false,
+        )
+        .await
+        .unwrap();
+        let wrapped_mint = get_wrapped_mint_address(token.get_address(), &spl_token::id());
+        assert!(process_sync_metadata(&rpc_client, &payer, &wrapped_mint)
+            .await
+            .is_err());
+
+        process_create_mint(
+            &rpc_client,
+            &payer,
+            token.get_address(),
+            &spl_token_2022::id(),
+            false,
+        )
+        .await
+        .unwrap();
+        let wrapped_mint = get_wrapped_mint_address(token.get_address(), &spl_token_2022::id());
+        let output = process_sync_metadata(&rpc_client, &payer, &wrapped_mint)
+            .await
+            .unwrap();
+        assert_eq!(output.metadata_source, token.get_address().to_string());
+
+        let account = rpc_client.get_account(&wrapped_mint).await.unwrap();
+        let mint_state = StateWithExtensions::<Mint>::unpack(&account.data).unwrap();
+        let metadata = mint_state
+            .get_variable_len_extension::<spl_token_metadata_interface::state::TokenMetadata>()
+            .unwrap();
+        assert_eq!(metadata.name, "Name");
+        assert_eq!(metadata.symbol, "SYM");
+        assert_eq!(metadata.uri, "https://example.com");
+    }
+}
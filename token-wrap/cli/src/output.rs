@@ -0,0 +1,137 @@
+use {
+    serde::Serialize,
+    solana_cli_output::{display::writeln_name_value, QuietDisplay, VerboseDisplay},
+    std::fmt::{Display, Formatter, Result},
+};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliCreateMint {
+    pub wrapped_mint: String,
+    pub wrapped_backpointer: String,
+    pub wrapped_mint_authority: String,
+    pub signature: Option<String>,
+}
+
+impl QuietDisplay for CliCreateMint {}
+impl VerboseDisplay for CliCreateMint {}
+
+impl Display for CliCreateMint {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        writeln_name_value(f, "Wrapped mint:", &self.wrapped_mint)?;
+        writeln_name_value(f, "Wrapped backpointer:", &self.wrapped_backpointer)?;
+        writeln_name_value(f, "Wrapped mint authority:", &self.wrapped_mint_authority)?;
+        if let Some(signature) = &self.signature {
+            writeln_name_value(f, "Signature:", signature)?;
+        } else {
+            writeln!(f, "Wrapped mint already exists, nothing to do")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliTransfer {
+    pub source: String,
+    pub destination: String,
+    pub escrow: String,
+    pub amount: String,
+    pub signature: String,
+}
+
+impl QuietDisplay for CliTransfer {}
+impl VerboseDisplay for CliTransfer {}
+
+impl Display for CliTransfer {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        writeln_name_value(f, "Source:", &self.source)?;
+        writeln_name_value(f, "Destination:", &self.destination)?;
+        writeln_name_value(f, "Escrow:", &self.escrow)?;
+        writeln_name_value(f, "Amount:", &self.amount)?;
+        writeln_name_value(f, "Signature:", &self.signature)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliSyncMetadata {
+    pub wrapped_mint: String,
+    pub metadata_source: String,
+    pub signature: String,
+}
+
+impl QuietDisplay for CliSyncMetadata {}
+impl VerboseDisplay for CliSyncMetadata {}
+
+impl Display for CliSyncMetadata {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        writeln_name_value(f, "Wrapped mint:", &self.wrapped_mint)?;
+        writeln_name_value(f, "Metadata source:", &self.metadata_source)?;
+        writeln_name_value(f, "Signature:", &self.signature)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliWrappedMint {
+    pub unwrapped_mint: String,
+    pub unwrapped_token_program: String,
+    pub wrapped_mint: String,
+    pub wrapped_token_program: String,
+    pub wrapped_mint_authority: String,
+    pub wrapped_backpointer: String,
+    pub wrapped_supply: String,
+    pub escrow: String,
+    pub escrow_balance: Option<String>,
+}
+
+impl QuietDisplay for CliWrappedMint {}
+impl VerboseDisplay for CliWrappedMint {}
+
+impl Display for CliWrappedMint {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        writeln_name_value(f, "Unwrapped mint:", &self.unwrapped_mint)?;
+        writeln_name_value(f, "Unwrapped token program:", &self.unwrapped_token_program)?;
+        writeln_name_value(f, "Wrapped mint:", &self.wrapped_mint)?;
+        writeln_name_value(f, "Wrapped token program:", &self.wrapped_token_program)?;
+        writeln_name_value(f, "Wrapped mint authority:", &self.wrapped_mint_authority)?;
+        writeln_name_value(f, "Wrapped backpointer:", &self.wrapped_backpointer)?;
+        writeln_name_value(f, "Wrapped supply:", &self.wrapped_supply)?;
+        writeln_name_value(f, "Escrow:", &self.escrow)?;
+        writeln_name_value(
+            f,
+            "Escrow balance:",
+            self.escrow_balance
+                .as_deref()
+                .unwrap_or("(escrow not created)"),
+        )?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliWrapInfo {
+    pub mint: String,
+    pub wrapped_mints: Vec<CliWrappedMint>,
+}
+
+impl QuietDisplay for CliWrapInfo {}
+impl VerboseDisplay for CliWrapInfo {}
+
+impl Display for CliWrapInfo {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        writeln_name_value(f, "Mint:", &self.mint)?;
+        if self.wrapped_mints.is_empty() {
+            writeln!(f, "No wrapped mint found")?;
+        }
+        for wrapped_mint in &self.wrapped_mints {
+            writeln!(f)?;
+            write!(f, "{}", wrapped_mint)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+//! Error types
+
+use {
+    num_derive::FromPrimitive,
+    solana_program::{decode_error::DecodeError, program_error::ProgramError},
+    thiserror::Error,
+};
+
+/// Errors that may be returned by the program.
+#[derive(Clone, Debug, Eq, Error, FromPrimitive, PartialEq)]
+pub enum TokenWrapError {
+    // 0
+    /// Wrapped mint account address does not match expected PDA
+    #[error("Wrapped mint account address does not match expected PDA")]
+    WrappedMintMismatch,
+    /// Wrapped backpointer account address does not match expected PDA
+    #[error("Wrapped backpointer account address does not match expected PDA")]
+    BackpointerMismatch,
+    /// Wrapped mint authority does not match expected PDA
+    #[error("Wrapped mint authority does not match expected PDA")]
+    MintAuthorityMismatch,
+    /// Escrow account is not owned by the wrapped mint authority
+    #[error("Escrow account is not owned by the wrapped mint authority")]
+    EscrowOwnerMismatch,
+    /// Escrow account does not hold the unwrapped mint
+    #[error("Escrow account does not hold the unwrapped mint")]
+    EscrowMintMismatch,
+
+    // 5
+    /// Amount to wrap or unwrap must be greater than zero
+    #[error("Amount to wrap or unwrap must be greater than zero")]
+    ZeroAmount,
+    /// Escrow did not receive any tokens from the wrap transfer
+    #[error("Escrow did not receive any tokens from the wrap transfer")]
+    NothingReceived,
+    /// Unwrapped mint has neither Token-2022 nor Metaplex metadata
+    #[error("Unwrapped mint has neither Token-2022 nor Metaplex metadata")]
+    MetadataSourceNotFound,
+    /// Metaplex metadata account is invalid for the unwrapped mint
+    #[error("Metaplex metadata account is invalid for the unwrapped mint")]
+    InvalidMetaplexMetadata,
+}
+impl From<TokenWrapError> for ProgramError {
+    fn from(e: TokenWrapError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+impl<T> DecodeError<T> for TokenWrapError {
+    fn type_of() -> &'static str {
+        "TokenWrapError"
+    }
+}
@@ -1,6 +1,14 @@
 //! Program instructions
 
-use num_enum::{IntoPrimitive, TryFromPrimitive};
+use {
+    crate::get_wrapped_mint_authority,
+    num_enum::{IntoPrimitive, TryFromPrimitive},
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+};
 
 /// Instructions supported by the Token Wrap program
 #[derive(Clone, Debug, PartialEq, TryFromPrimitive, IntoPrimitive)]
@@ -21,6 +29,9 @@ pub enum TokenWrapInstruction {
     /// 4. `[]` System program
     /// 5. `[]` SPL Token program for wrapped mint
     ///
+    /// Wrapped mints under SPL Token-2022 are created with the metadata
+    /// pointer extension pointing to themselves, ready for `SyncMetadata`.
+    ///
     /// Data expected by this instruction:
     ///   * bool: true = idempotent creation, false = non-idempotent creation
     CreateMint,
@@ -78,4 +89,179 @@ pub enum TokenWrapInstruction {
     /// Data expected by this instruction:
     ///   * little-endian u64 representing the amount to unwrap
     Unwrap,
+
+    /// Copy the unwrapped mint's metadata onto an SPL Token-2022 wrapped mint
+    ///
+    /// Initializes the token metadata of the wrapped mint on first use, and
+    /// brings its name, symbol, uri and additional fields in line with the
+    /// unwrapped mint afterwards. Anyone may call this instruction.
+    ///
+    /// The metadata is read from the unwrapped mint itself if it is an SPL
+    /// Token-2022 mint whose metadata pointer points to itself, otherwise
+    /// from the provided Metaplex metadata account.
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    /// 0. `[writeable,signer]` Funding account for the rent of the resized
+    ///    wrapped mint
+    /// 1. `[writeable]` Wrapped mint, must be an SPL Token-2022 mint, address
+    ///    must be: `get_wrapped_mint_address(unwrapped_mint_address,
+    ///    spl_token_2022::id())`
+    /// 2. `[]` Wrapped mint authority, address must be:
+    ///    `get_wrapped_mint_authority(wrapped_mint)`
+    /// 3. `[]` Unwrapped token mint
+    /// 4. `[]` System program
+    /// 5. `[]` SPL Token-2022 program
+    /// 6. `[]` (Optional) Metaplex metadata of the unwrapped mint, address
+    ///    must be:
+    ///    `spl_inline_mpl_token_metadata::get_metadata_address(unwrapped_mint_address)`
+    SyncMetadata,
+}
+
+/// Create a `CreateMint` instruction
+pub fn create_mint(
+    program_id: &Pubkey,
+    funding_address: &Pubkey,
+    wrapped_mint_address: &Pubkey,
+    wrapped_backpointer_address: &Pubkey,
+    unwrapped_mint_address: &Pubkey,
+    wrapped_token_program_id: &Pubkey,
+    idempotent: bool,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*funding_address, true),
+        AccountMeta::new(*wrapped_mint_address, false),
+        AccountMeta::new(*wrapped_backpointer_address, false),
+        AccountMeta::new_readonly(*unwrapped_mint_address, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(*wrapped_token_program_id, false),
+    ];
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: vec![TokenWrapInstruction::CreateMint.into(), idempotent.into()],
+    }
+}
+
+/// Create a `Wrap` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn wrap(
+    program_id: &Pubkey,
+    unwrapped_token_account_address: &Pubkey,
+    escrow_address: &Pubkey,
+    unwrapped_mint_address: &Pubkey,
+    wrapped_mint_address: &Pubkey,
+    recipient_wrapped_token_account_address: &Pubkey,
+    unwrapped_token_program_id: &Pubkey,
+    wrapped_token_program_id: &Pubkey,
+    transfer_authority_address: &Pubkey,
+    multisig_signers: &[&Pubkey],
+    amount: u64,
+) -> Instruction {
+    let wrapped_mint_authority = get_wrapped_mint_authority(wrapped_mint_address);
+    let mut accounts = Vec::with_capacity(9usize.saturating_add(multisig_signers.len()));
+    accounts.push(AccountMeta::new(*unwrapped_token_account_address, false));
+    accounts.push(AccountMeta::new(*escrow_address, false));
+    accounts.push(AccountMeta::new_readonly(*unwrapped_mint_address, false));
+    accounts.push(AccountMeta::new(*wrapped_mint_address, false));
+    accounts.push(AccountMeta::new(
+        *recipient_wrapped_token_account_address,
+        false,
+    ));
+    accounts.push(AccountMeta::new_readonly(wrapped_mint_authority, false));
+    accounts.push(AccountMeta::new_readonly(
+        *unwrapped_token_program_id,
+        false,
+    ));
+    accounts.push(AccountMeta::new_readonly(*wrapped_token_program_id, false));
+    accounts.push(AccountMeta::new_readonly(
+        *transfer_authority_address,
+        multisig_signers.is_empty(),
+    ));
+    for signer_pubkey in multisig_signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    let mut data = vec![TokenWrapInstruction::Wrap.into()];
+    data.extend_from_slice(&amount.to_le_bytes());
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Create an `Unwrap` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn unwrap(
+    program_id: &Pubkey,
+    wrapped_token_account_address: &Pubkey,
+    wrapped_mint_address: &Pubkey,
+    escrow_address: &Pubkey,
+    recipient_unwrapped_token_account_address: &Pubkey,
+    unwrapped_mint_address: &Pubkey,
+    wrapped_token_program_id: &Pubkey,
+    unwrapped_token_program_id: &Pubkey,
+    transfer_authority_address: &Pubkey,
+    multisig_signers: &[&Pubkey],
+    amount: u64,
+) -> Instruction {
+    let wrapped_mint_authority = get_wrapped_mint_authority(wrapped_mint_address);
+    let mut accounts = Vec::with_capacity(9usize.saturating_add(multisig_signers.len()));
+    accounts.push(AccountMeta::new(*wrapped_token_account_address, false));
+    accounts.push(AccountMeta::new(*wrapped_mint_address, false));
+    accounts.push(AccountMeta::new(*escrow_address, false));
+    accounts.push(AccountMeta::new(
+        *recipient_unwrapped_token_account_address,
+        false,
+    ));
+    accounts.push(AccountMeta::new_readonly(*unwrapped_mint_address, false));
+    accounts.push(AccountMeta::new_readonly(wrapped_mint_authority, false));
+    accounts.push(AccountMeta::new_readonly(*wrapped_token_program_id, false));
+    accounts.push(AccountMeta::new_readonly(
+        *unwrapped_token_program_id,
+        false,
+    ));
+    accounts.push(AccountMeta::new_readonly(
+        *transfer_authority_address,
+        multisig_signers.is_empty(),
+    ));
+    for signer_pubkey in multisig_signers.iter() {
+        accounts.push(AccountMeta::new_readonly(**signer_pubkey, true));
+    }
+
+    let mut data = vec![TokenWrapInstruction::Unwrap.into()];
+    data.extend_from_slice(&amount.to_le_bytes());
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    }
+}
+
+/// Create a `SyncMetadata` instruction
+pub fn sync_metadata(
+    program_id: &Pubkey,
+    funding_address: &Pubkey,
+    wrapped_mint_address: &Pubkey,
+    unwrapped_mint_address: &Pubkey,
+    metaplex_metadata_address: Option<&Pubkey>,
+) -> Instruction {
+    let wrapped_mint_authority = get_wrapped_mint_authority(wrapped_mint_address);
+    let mut accounts = vec![
+        AccountMeta::new(*funding_address, true),
+        AccountMeta::new(*wrapped_mint_address, false),
+        AccountMeta::new_readonly(wrapped_mint_authority, false),
+        AccountMeta::new_readonly(*unwrapped_mint_address, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(spl_token_2022::id(), false),
+    ];
+    if let Some(metaplex_metadata_address) = metaplex_metadata_address {
+        accounts.push(AccountMeta::new_readonly(*metaplex_metadata_address, false));
+    }
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: vec![TokenWrapInstruction::SyncMetadata.into()],
+    }
 }
@@ -1,26 +1,706 @@
 //! Program state processor
 
 use {
-    crate::instruction::TokenWrapInstruction,
-    solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey},
-    spl_token_2022::instruction::decode_instruction_type,
+    crate::{
+        error::TokenWrapError, get_wrapped_mint_address_with_seed,
+        get_wrapped_mint_authority_signer_seeds, get_wrapped_mint_authority_with_seed,
+        get_wrapped_mint_backpointer_address_signer_seeds,
+        get_wrapped_mint_backpointer_address_with_seed, get_wrapped_mint_signer_seeds,
+        instruction::TokenWrapInstruction, state::Backpointer,
+    },
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        instruction::Instruction,
+        msg,
+        program::{invoke, invoke_signed},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        rent::Rent,
+        system_instruction, system_program,
+        sysvar::Sysvar,
+    },
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
+    spl_token_2022::{
+        check_spl_token_program_account,
+        extension::{
+            metadata_pointer::{self, MetadataPointer},
+            BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+        },
+        instruction::decode_instruction_type,
+        state::{Account, Mint},
+    },
+    spl_token_metadata_interface::state::{Field, TokenMetadata},
+    std::{convert::TryInto, mem::size_of},
 };
 
+fn check_owner(account_info: &AccountInfo, expected_owner: &Pubkey) -> ProgramResult {
+    if account_info.owner != expected_owner {
+        Err(ProgramError::IllegalOwner)
+    } else {
+        Ok(())
+    }
+}
+
+fn unpack_amount(input: &[u8]) -> Result<u64, ProgramError> {
+    input
+        .get(..8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidInstructionData)
+}
+
+fn unpack_decimals(mint_info: &AccountInfo) -> Result<u8, ProgramError> {
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+    Ok(mint.base.decimals)
+}
+
+/// Returns the balance of the escrow, after checking that it holds the
+/// unwrapped mint and belongs to the wrapped mint authority
+fn unpack_escrow_amount(
+    escrow_info: &AccountInfo,
+    unwrapped_mint: &Pubkey,
+    wrapped_mint_authority: &Pubkey,
+) -> Result<u64, ProgramError> {
+    let escrow_data = escrow_info.try_borrow_data()?;
+    let escrow = StateWithExtensions::<Account>::unpack(&escrow_data)?;
+    if escrow.base.owner != *wrapped_mint_authority {
+        msg!(
+            "Expected escrow owned by {}, received {}",
+            wrapped_mint_authority,
+            &escrow.base.owner
+        );
+        return Err(TokenWrapError::EscrowOwnerMismatch.into());
+    }
+    if escrow.base.mint != *unwrapped_mint {
+        msg!(
+            "Expected escrow of mint {}, received {}",
+            unwrapped_mint,
+            &escrow.base.mint
+        );
+        return Err(TokenWrapError::EscrowMintMismatch.into());
+    }
+    Ok(escrow.base.amount)
+}
+
+/// Checks the wrapped mint and its authority against the derived addresses,
+/// returning the bump seed of the authority
+fn check_wrapped_mint_and_authority(
+    wrapped_mint_info: &AccountInfo,
+    wrapped_mint_authority_info: &AccountInfo,
+    unwrapped_mint: &Pubkey,
+    wrapped_token_program_id: &Pubkey,
+) -> Result<u8, ProgramError> {
+    let (expected_wrapped_mint, _) =
+        get_wrapped_mint_address_with_seed(unwrapped_mint, wrapped_token_program_id);
+    if expected_wrapped_mint != *wrapped_mint_info.key {
+        msg!(
+            "Expected wrapped mint {}, received {}",
+            &expected_wrapped_mint,
+            wrapped_mint_info.key
+        );
+        return Err(TokenWrapError::WrappedMintMismatch.into());
+    }
+    let (expected_authority, bump_seed) =
+        get_wrapped_mint_authority_with_seed(wrapped_mint_info.key);
+    if expected_authority != *wrapped_mint_authority_info.key {
+        msg!(
+            "Expected wrapped mint authority {}, received {}",
+            &expected_authority,
+            wrapped_mint_authority_info.key
+        );
+        return Err(TokenWrapError::MintAuthorityMismatch.into());
+    }
+    Ok(bump_seed)
+}
+
+/// Creates an account at a PDA, funded by `funding_info`
+fn create_pda_account<'a>(
+    funding_info: &AccountInfo<'a>,
+    new_account_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    space: usize,
+    owner: &Pubkey,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    let required_lamports = Rent::get()?.minimum_balance(space);
+
+    // Anybody can send lamports to a PDA, which would block `create_account`,
+    // so top up, allocate and assign the account instead
+    if new_account_info.lamports() > 0 {
+        let top_up_lamports = required_lamports.saturating_sub(new_account_info.lamports());
+        if top_up_lamports > 0 {
+            invoke(
+                &system_instruction::transfer(
+                    funding_info.key,
+                    new_account_info.key,
+                    top_up_lamports,
+                ),
+                &[
+                    funding_info.clone(),
+                    new_account_info.clone(),
+                    system_program_info.clone(),
+                ],
+            )?;
+        }
+        invoke_signed(
+            &system_instruction::allocate(new_account_info.key, space as u64),
+            &[new_account_info.clone(), system_program_info.clone()],
+            &[signer_seeds],
+        )?;
+        invoke_signed(
+            &system_instruction::assign(new_account_info.key, owner),
+            &[new_account_info.clone(), system_program_info.clone()],
+            &[signer_seeds],
+        )
+    } else {
+        invoke_signed(
+            &system_instruction::create_account(
+                funding_info.key,
+                new_account_info.key,
+                required_lamports,
+                space as u64,
+                owner,
+            ),
+            &[
+                funding_info.clone(),
+                new_account_info.clone(),
+                system_program_info.clone(),
+            ],
+            &[signer_seeds],
+        )
+    }
+}
+
+fn process_create_mint(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    idempotent: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let funding_info = next_account_info(account_info_iter)?;
+    let wrapped_mint_info = next_account_info(account_info_iter)?;
+    let wrapped_backpointer_info = next_account_info(account_info_iter)?;
+    let unwrapped_mint_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let wrapped_token_program_info = next_account_info(account_info_iter)?;
+
+    // program checks
+    if !system_program::check_id(system_program_info.key) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    check_spl_token_program_account(wrapped_token_program_info.key)?;
+    check_spl_token_program_account(unwrapped_mint_info.owner)?;
+
+    // PDA derivation checks
+    let (expected_wrapped_mint, wrapped_mint_bump_seed) =
+        get_wrapped_mint_address_with_seed(unwrapped_mint_info.key, wrapped_token_program_info.key);
+    if expected_wrapped_mint != *wrapped_mint_info.key {
+        msg!(
+            "Expected wrapped mint {}, received {}",
+            &expected_wrapped_mint,
+            wrapped_mint_info.key
+        );
+        return Err(TokenWrapError::WrappedMintMismatch.into());
+    }
+    let (expected_backpointer, backpointer_bump_seed) =
+        get_wrapped_mint_backpointer_address_with_seed(wrapped_mint_info.key);
+    if expected_backpointer != *wrapped_backpointer_info.key {
+        msg!(
+            "Expected wrapped backpointer {}, received {}",
+            &expected_backpointer,
+            wrapped_backpointer_info.key
+        );
+        return Err(TokenWrapError::BackpointerMismatch.into());
+    }
+
+    // both accounts are created in this instruction, so either both exist or
+    // neither does
+    if wrapped_mint_info.data_len() > 0 || wrapped_backpointer_info.data_len() > 0 {
+        if idempotent
+            && wrapped_mint_info.owner == wrapped_token_program_info.key
+            && wrapped_backpointer_info.owner == program_id
+        {
+            return Ok(());
+        }
+        msg!("Wrapped mint {} already exists", wrapped_mint_info.key);
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let decimals = unpack_decimals(unwrapped_mint_info)?;
+
+    // create and initialize the wrapped mint, Token-2022 mints point to
+    // themselves for metadata filled in by `SyncMetadata`
+    let extension_types = if spl_token_2022::check_id(wrapped_token_program_info.key) {
+        vec![ExtensionType::MetadataPointer]
+    } else {
+        vec![]
+    };
+    let mint_space = ExtensionType::try_calculate_account_len::<Mint>(&extension_types)?;
+    let bump_seed = [wrapped_mint_bump_seed];
+    let wrapped_mint_signer_seeds = get_wrapped_mint_signer_seeds(
+        unwrapped_mint_info.key,
+        wrapped_token_program_info.key,
+        &bump_seed,
+    );
+    create_pda_account(
+        funding_info,
+        wrapped_mint_info,
+        system_program_info,
+        mint_space,
+        wrapped_token_program_info.key,
+        &wrapped_mint_signer_seeds,
+    )?;
+    let (wrapped_mint_authority, _) = get_wrapped_mint_authority_with_seed(wrapped_mint_info.key);
+    if extension_types.contains(&ExtensionType::MetadataPointer) {
+        invoke(
+            &metadata_pointer::instruction::initialize(
+                wrapped_token_program_info.key,
+                wrapped_mint_info.key,
+                Some(wrapped_mint_authority),
+                Some(*wrapped_mint_info.key),
+            )?,
+            &[wrapped_mint_info.clone()],
+        )?;
+    }
+    invoke(
+        &spl_token_2022::instruction::initialize_mint2(
+            wrapped_token_program_info.key,
+            wrapped_mint_info.key,
+            &wrapped_mint_authority,
+            None,
+            decimals,
+        )?,
+        &[wrapped_mint_info.clone()],
+    )?;
+
+    // create and write the backpointer
+    let bump_seed = [backpointer_bump_seed];
+    let backpointer_signer_seeds =
+        get_wrapped_mint_backpointer_address_signer_seeds(wrapped_mint_info.key, &bump_seed);
+    create_pda_account(
+        funding_info,
+        wrapped_backpointer_info,
+        system_program_info,
+        size_of::<Backpointer>(),
+        program_id,
+        &backpointer_signer_seeds,
+    )?;
+    let mut backpointer_data = wrapped_backpointer_info.try_borrow_mut_data()?;
+    let backpointer = bytemuck::try_from_bytes_mut::<Backpointer>(&mut backpointer_data)
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    backpointer.unwrapped_mint = *unwrapped_mint_info.key;
+
+    Ok(())
+}
+
+fn process_wrap(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    if amount == 0 {
+        return Err(TokenWrapError::ZeroAmount.into());
+    }
+
+    let account_info_iter = &mut accounts.iter();
+
+    let unwrapped_token_account_info = next_account_info(account_info_iter)?;
+    let escrow_info = next_account_info(account_info_iter)?;
+    let unwrapped_mint_info = next_account_info(account_info_iter)?;
+    let wrapped_mint_info = next_account_info(account_info_iter)?;
+    let recipient_wrapped_token_account_info = next_account_info(account_info_iter)?;
+    let wrapped_mint_authority_info = next_account_info(account_info_iter)?;
+    let unwrapped_token_program_info = next_account_info(account_info_iter)?;
+    let wrapped_token_program_info = next_account_info(account_info_iter)?;
+    let transfer_authority_info = next_account_info(account_info_iter)?;
+    let multisig_signer_infos = account_info_iter.as_slice();
+
+    // program and owner checks
+    check_spl_token_program_account(unwrapped_token_program_info.key)?;
+    check_spl_token_program_account(wrapped_token_program_info.key)?;
+    check_owner(unwrapped_mint_info, unwrapped_token_program_info.key)?;
+    check_owner(escrow_info, unwrapped_token_program_info.key)?;
+    check_owner(wrapped_mint_info, wrapped_token_program_info.key)?;
+
+    // PDA derivation checks
+    let bump_seed = check_wrapped_mint_and_authority(
+        wrapped_mint_info,
+        wrapped_mint_authority_info,
+        unwrapped_mint_info.key,
+        wrapped_token_program_info.key,
+    )?;
+    let bump_seed = [bump_seed];
+    let authority_signer_seeds =
+        get_wrapped_mint_authority_signer_seeds(wrapped_mint_info.key, &bump_seed);
+
+    let unwrapped_decimals = unpack_decimals(unwrapped_mint_info)?;
+    let wrapped_decimals = unpack_decimals(wrapped_mint_info)?;
+    let escrow_amount_before = unpack_escrow_amount(
+        escrow_info,
+        unwrapped_mint_info.key,
+        wrapped_mint_authority_info.key,
+    )?;
+
+    // move the unwrapped tokens into escrow
+    let multisig_pubkeys = multisig_signer_infos
+        .iter()
+        .map(|s| s.key)
+        .collect::<Vec<_>>();
+    let ix = spl_token_2022::instruction::transfer_checked(
+        unwrapped_token_program_info.key,
+        unwrapped_token_account_info.key,
+        unwrapped_mint_info.key,
+        escrow_info.key,
+        transfer_authority_info.key,
+        &multisig_pubkeys,
+        amount,
+        unwrapped_decimals,
+    )?;
+    let mut account_infos = vec![
+        unwrapped_token_account_info.clone(),
+        unwrapped_mint_info.clone(),
+        escrow_info.clone(),
+        transfer_authority_info.clone(),
+    ];
+    account_infos.extend_from_slice(multisig_signer_infos);
+    invoke(&ix, &account_infos)?;
+
+    // only mint what the escrow actually received, since the unwrapped mint
+    // may withhold a transfer fee
+    let escrow_amount_after = unpack_escrow_amount(
+        escrow_info,
+        unwrapped_mint_info.key,
+        wrapped_mint_authority_info.key,
+    )?;
+    let received_amount = escrow_amount_after
+        .checked_sub(escrow_amount_before)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    if received_amount == 0 {
+        return Err(TokenWrapError::NothingReceived.into());
+    }
+
+    invoke_signed(
+        &spl_token_2022::instruction::mint_to_checked(
+            wrapped_token_program_info.key,
+            wrapped_mint_info.key,
+            recipient_wrapped_token_account_info.key,
+            wrapped_mint_authority_info.key,
+            &[],
+            received_amount,
+            wrapped_decimals,
+        )?,
+        &[
+            wrapped_mint_info.clone(),
+            recipient_wrapped_token_account_info.clone(),
+            wrapped_mint_authority_info.clone(),
+        ],
+        &[&authority_signer_seeds],
+    )
+}
+
+fn process_unwrap(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    if amount == 0 {
+        return Err(TokenWrapError::ZeroAmount.into());
+    }
+
+    let account_info_iter = &mut accounts.iter();
+
+    let wrapped_token_account_info = next_account_info(account_info_iter)?;
+    let wrapped_mint_info = next_account_info(account_info_iter)?;
+    let escrow_info = next_account_info(account_info_iter)?;
+    let recipient_unwrapped_token_account_info = next_account_info(account_info_iter)?;
+    let unwrapped_mint_info = next_account_info(account_info_iter)?;
+    let wrapped_mint_authority_info = next_account_info(account_info_iter)?;
+    let wrapped_token_program_info = next_account_info(account_info_iter)?;
+    let unwrapped_token_program_info = next_account_info(account_info_iter)?;
+    let transfer_authority_info = next_account_info(account_info_iter)?;
+    let multisig_signer_infos = account_info_iter.as_slice();
+
+    // program and owner checks
+    check_spl_token_program_account(unwrapped_token_program_info.key)?;
+    check_spl_token_program_account(wrapped_token_program_info.key)?;
+    check_owner(unwrapped_mint_info, unwrapped_token_program_info.key)?;
+    check_owner(escrow_info, unwrapped_token_program_info.key)?;
+    check_owner(wrapped_mint_info, wrapped_token_program_info.key)?;
+
+    // PDA derivation checks
+    let bump_seed = check_wrapped_mint_and_authority(
+        wrapped_mint_info,
+        wrapped_mint_authority_info,
+        unwrapped_mint_info.key,
+        wrapped_token_program_info.key,
+    )?;
+    let bump_seed = [bump_seed];
+    let authority_signer_seeds =
+        get_wrapped_mint_authority_signer_seeds(wrapped_mint_info.key, &bump_seed);
+
+    let unwrapped_decimals = unpack_decimals(unwrapped_mint_info)?;
+    let wrapped_decimals = unpack_decimals(wrapped_mint_info)?;
+    unpack_escrow_amount(
+        escrow_info,
+        unwrapped_mint_info.key,
+        wrapped_mint_authority_info.key,
+    )?;
+
+    // burn the wrapped tokens
+    let multisig_pubkeys = multisig_signer_infos
+        .iter()
+        .map(|s| s.key)
+        .collect::<Vec<_>>();
+    let ix = spl_token_2022::instruction::burn_checked(
+        wrapped_token_program_info.key,
+        wrapped_token_account_info.key,
+        wrapped_mint_info.key,
+        transfer_authority_info.key,
+        &multisig_pubkeys,
+        amount,
+        wrapped_decimals,
+    )?;
+    let mut account_infos = vec![
+        wrapped_token_account_info.clone(),
+        wrapped_mint_info.clone(),
+        transfer_authority_info.clone(),
+    ];
+    account_infos.extend_from_slice(multisig_signer_infos);
+    invoke(&ix, &account_infos)?;
+
+    // release the same amount from escrow, any transfer fee is withheld from
+    // the recipient by the unwrapped mint
+    invoke_signed(
+        &spl_token_2022::instruction::transfer_checked(
+            unwrapped_token_program_info.key,
+            escrow_info.key,
+            unwrapped_mint_info.key,
+            recipient_unwrapped_token_account_info.key,
+            wrapped_mint_authority_info.key,
+            &[],
+            amount,
+            unwrapped_decimals,
+        )?,
+        &[
+            escrow_info.clone(),
+            unwrapped_mint_info.clone(),
+            recipient_unwrapped_token_account_info.clone(),
+            wrapped_mint_authority_info.clone(),
+        ],
+        &[&authority_signer_seeds],
+    )
+}
+
+/// Reads the metadata of the unwrapped mint, either from its own Token-2022
+/// metadata or from its Metaplex metadata account
+fn get_unwrapped_metadata(
+    unwrapped_mint_info: &AccountInfo,
+    metaplex_metadata_info: Option<&AccountInfo>,
+) -> Result<TokenMetadata, ProgramError> {
+    if spl_token_2022::check_id(unwrapped_mint_info.owner) {
+        let mint_data = unwrapped_mint_info.try_borrow_data()?;
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+        let points_to_self = mint
+            .get_extension::<MetadataPointer>()
+            .map(|pointer| {
+                Option::<Pubkey>::from(pointer.metadata_address) == Some(*unwrapped_mint_info.key)
+            })
+            .unwrap_or(false);
+        if points_to_self {
+            return mint.get_variable_len_extension::<TokenMetadata>();
+        }
+    }
+
+    let metaplex_metadata_info =
+        metaplex_metadata_info.ok_or(TokenWrapError::MetadataSourceNotFound)?;
+    let metadata = spl_inline_mpl_token_metadata::get_metadata_data_for_mint(
+        metaplex_metadata_info,
+        unwrapped_mint_info.key,
+    )
+    .map_err(|_| {
+        msg!(
+            "Expected Metaplex metadata {}, received {}",
+            &spl_inline_mpl_token_metadata::get_metadata_address(unwrapped_mint_info.key),
+            metaplex_metadata_info.key
+        );
+        TokenWrapError::InvalidMetaplexMetadata
+    })?;
+    if metadata.key != spl_inline_mpl_token_metadata::METADATA_V1_KEY {
+        return Err(TokenWrapError::InvalidMetaplexMetadata.into());
+    }
+    Ok(TokenMetadata {
+        mint: *unwrapped_mint_info.key,
+        // Metaplex pads strings with null bytes to a fixed size
+        name: metadata.data.name.trim_end_matches('\0').to_string(),
+        symbol: metadata.data.symbol.trim_end_matches('\0').to_string(),
+        uri: metadata.data.uri.trim_end_matches('\0').to_string(),
+        ..TokenMetadata::default()
+    })
+}
+
+/// Builds the token metadata instructions turning `current` into `target`,
+/// initializing the metadata if there is none yet
+fn sync_metadata_instructions(
+    wrapped_mint: &Pubkey,
+    wrapped_mint_authority: &Pubkey,
+    current: Option<TokenMetadata>,
+    target: TokenMetadata,
+) -> Vec<Instruction> {
+    let program_id = spl_token_2022::id();
+    let update_field = |field, value| {
+        spl_token_metadata_interface::instruction::update_field(
+            &program_id,
+            wrapped_mint,
+            wrapped_mint_authority,
+            field,
+            value,
+        )
+    };
+
+    let mut instructions = vec![];
+    let current = match current {
+        Some(current) => current,
+        None => {
+            instructions.push(spl_token_metadata_interface::instruction::initialize(
+                &program_id,
+                wrapped_mint,
+                wrapped_mint_authority,
+                wrapped_mint,
+                wrapped_mint_authority,
+                target.name,
+                target.symbol,
+                target.uri,
+            ));
+            for (key, value) in target.additional_metadata {
+                instructions.push(update_field(Field::Key(key), value));
+            }
+            return instructions;
+        }
+    };
+
+    for (key, _) in &current.additional_metadata {
+        if !target.additional_metadata.iter().any(|(k, _)| k == key) {
+            instructions.push(spl_token_metadata_interface::instruction::remove_key(
+                &program_id,
+                wrapped_mint,
+                wrapped_mint_authority,
+                key.clone(),
+                true,
+            ));
+        }
+    }
+    for (field, current_value, target_value) in [
+        (Field::Name, current.name, target.name),
+        (Field::Symbol, current.symbol, target.symbol),
+        (Field::Uri, current.uri, target.uri),
+    ] {
+        if current_value != target_value {
+            instructions.push(update_field(field, target_value));
+        }
+    }
+    for (key, value) in target.additional_metadata {
+        let current_value = current
+            .additional_metadata
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v);
+        if current_value != Some(&value) {
+            instructions.push(update_field(Field::Key(key), value));
+        }
+    }
+    instructions
+}
+
+fn process_sync_metadata(accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let funding_info = next_account_info(account_info_iter)?;
+    let wrapped_mint_info = next_account_info(account_info_iter)?;
+    let wrapped_mint_authority_info = next_account_info(account_info_iter)?;
+    let unwrapped_mint_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let token_2022_program_info = next_account_info(account_info_iter)?;
+    let metaplex_metadata_info = account_info_iter.next();
+
+    // program and owner checks
+    if !system_program::check_id(system_program_info.key) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    if !spl_token_2022::check_id(token_2022_program_info.key) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    check_owner(wrapped_mint_info, token_2022_program_info.key)?;
+    check_spl_token_program_account(unwrapped_mint_info.owner)?;
+
+    // PDA derivation checks
+    let bump_seed = check_wrapped_mint_and_authority(
+        wrapped_mint_info,
+        wrapped_mint_authority_info,
+        unwrapped_mint_info.key,
+        token_2022_program_info.key,
+    )?;
+    let bump_seed = [bump_seed];
+    let authority_signer_seeds =
+        get_wrapped_mint_authority_signer_seeds(wrapped_mint_info.key, &bump_seed);
+
+    let unwrapped_metadata = get_unwrapped_metadata(unwrapped_mint_info, metaplex_metadata_info)?;
+    let target_metadata = TokenMetadata {
+        update_authority: OptionalNonZeroPubkey(*wrapped_mint_authority_info.key),
+        mint: *wrapped_mint_info.key,
+        ..unwrapped_metadata
+    };
+    let current_metadata = {
+        let mint_data = wrapped_mint_info.try_borrow_data()?;
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+        mint.get_variable_len_extension::<TokenMetadata>().ok()
+    };
+
+    for instruction in sync_metadata_instructions(
+        wrapped_mint_info.key,
+        wrapped_mint_authority_info.key,
+        current_metadata,
+        target_metadata,
+    ) {
+        invoke_signed(
+            &instruction,
+            &[
+                wrapped_mint_info.clone(),
+                wrapped_mint_authority_info.clone(),
+            ],
+            &[&authority_signer_seeds],
+        )?;
+    }
+
+    // Token-2022 resizes the mint without funding it, so cover the rent of
+    // the final size
+    let required_lamports = Rent::get()?.minimum_balance(wrapped_mint_info.data_len());
+    let top_up_lamports = required_lamports.saturating_sub(wrapped_mint_info.lamports());
+    if top_up_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(funding_info.key, wrapped_mint_info.key, top_up_lamports),
+            &[
+                funding_info.clone(),
+                wrapped_mint_info.clone(),
+                system_program_info.clone(),
+            ],
+        )?;
+    }
+
+    Ok(())
+}
+
 /// Instruction processor
 pub fn process_instruction(
-    _program_id: &Pubkey,
-    _accounts: &[AccountInfo],
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
     input: &[u8],
 ) -> ProgramResult {
     match decode_instruction_type(input)? {
         TokenWrapInstruction::CreateMint => {
-            unimplemented!();
-        }
-        TokenWrapInstruction::Wrap => {
-            unimplemented!();
-        }
-        TokenWrapInstruction::Unwrap => {
-            unimplemented!();
+            let idempotent = match input.get(1) {
+                Some(0) => false,
+                Some(1) => true,
+                _ => return Err(ProgramError::InvalidInstructionData),
+            };
+            process_create_mint(program_id, accounts, idempotent)
         }
+        TokenWrapInstruction::Wrap => process_wrap(accounts, unpack_amount(&input[1..])?),
+        TokenWrapInstruction::Unwrap => process_unwrap(accounts, unpack_amount(&input[1..])?),
+        TokenWrapInstruction::SyncMetadata => process_sync_metadata(accounts),
     }
 }
@@ -0,0 +1,1107 @@
+// Mark this test as SBF-only due to current `ProgramTest` limitations when
+// CPIing into the system program
+#![cfg(feature = "test-sbf")]
+
+use {
+    solana_program_test::{processor, tokio, BanksClientError, ProgramTest, ProgramTestContext},
+    solana_sdk::{
+        account::Account as SolanaAccount,
+        instruction::{Instruction, InstructionError},
+        program_error::ProgramError,
+        program_pack::Pack,
+        pubkey::Pubkey,
+        signature::Signer,
+        signer::keypair::Keypair,
+        system_instruction,
+        transaction::{Transaction, TransactionError},
+    },
+    spl_associated_token_account::{
+        get_associated_token_address_with_program_id,
+        instruction::create_associated_token_account_idempotent,
+    },
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
+    spl_token_2022::{
+        extension::{
+            metadata_pointer::{self, MetadataPointer},
+            transfer_fee, BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+        },
+        state::{Account, Mint, Multisig},
+    },
+    spl_token_metadata_interface::state::{Field, TokenMetadata},
+    spl_token_wrap::{
+        error::TokenWrapError,
+        get_wrapped_mint_address, get_wrapped_mint_authority, get_wrapped_mint_backpointer_address,
+        instruction::{create_mint, sync_metadata, unwrap, wrap},
+        state::Backpointer,
+    },
+    test_case::test_case,
+};
+
+const DECIMALS: u8 = 6;
+
+async fn setup() -> ProgramTestContext {
+    let mut program_test = ProgramTest::new(
+        "spl_token_wrap",
+        spl_token_wrap::id(),
+        processor!(spl_token_wrap::processor::process_instruction),
+    );
+
+    program_test.prefer_bpf(false); // simplicity in the build
+
+    program_test.add_program(
+        "spl_token_2022",
+        spl_token_2022::id(),
+        processor!(spl_token_2022::processor::Processor::process),
+    );
+    program_test.add_program(
+        "spl_token",
+        spl_token::id(),
+        processor!(spl_token::processor::Processor::process),
+    );
+    program_test.add_program(
+        "spl_associated_token_account",
+        spl_associated_token_account::id(),
+        processor!(spl_associated_token_account::processor::process_instruction),
+    );
+
+    program_test.start_with_context().await
+}
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+fn assert_custom_error(error: BanksClientError, expected: TokenWrapError) {
+    assert_eq!(
+        error.unwrap(),
+        TransactionError::InstructionError(0, InstructionError::Custom(expected as u32))
+    );
+}
+
+/// Creates a mint with the payer as mint authority, optionally charging a
+/// transfer fee in basis points
+async fn setup_mint(
+    context: &mut ProgramTestContext,
+    token_program_id: &Pubkey,
+    transfer_fee_basis_points: Option<u16>,
+) -> Pubkey {
+    let mint = Keypair::new();
+    let payer = context.payer.pubkey();
+    let extensions = if transfer_fee_basis_points.is_some() {
+        vec![ExtensionType::TransferFeeConfig]
+    } else {
+        vec![]
+    };
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&extensions).unwrap();
+    let rent = context.banks_client.get_rent().await.unwrap();
+
+    let mut instructions = vec![system_instruction::create_account(
+        &payer,
+        &mint.pubkey(),
+        rent.minimum_balance(space),
+        space as u64,
+        token_program_id,
+    )];
+    if let Some(basis_points) = transfer_fee_basis_points {
+        instructions.push(
+            transfer_fee::instruction::initialize_transfer_fee_config(
+                token_program_id,
+                &mint.pubkey(),
+                None,
+                None,
+                basis_points,
+                u64::MAX,
+            )
+            .unwrap(),
+        );
+    }
+    instructions.push(
+        spl_token_2022::instruction::initialize_mint2(
+            token_program_id,
+            &mint.pubkey(),
+            &payer,
+            None,
+            DECIMALS,
+        )
+        .unwrap(),
+    );
+    process(context, &instructions, &[&mint]).await.unwrap();
+    mint.pubkey()
+}
+
+async fn setup_token_account(
+    context: &mut ProgramTestContext,
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    owner: &Pubkey,
+) -> Pubkey {
+    let instruction = create_associated_token_account_idempotent(
+        &context.payer.pubkey(),
+        owner,
+        mint,
+        token_program_id,
+    );
+    process(context, &[instruction], &[]).await.unwrap();
+    get_associated_token_address_with_program_id(owner, mint, token_program_id)
+}
+
+async fn mint_to(
+    context: &mut ProgramTestContext,
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    account: &Pubkey,
+    amount: u64,
+) {
+    let instruction = spl_token_2022::instruction::mint_to(
+        token_program_id,
+        mint,
+        account,
+        &context.payer.pubkey(),
+        &[],
+        amount,
+    )
+    .unwrap();
+    process(context, &[instruction], &[]).await.unwrap();
+}
+
+/// Creates a 2 of 3 multisig
+async fn setup_multisig(
+    context: &mut ProgramTestContext,
+    token_program_id: &Pubkey,
+) -> (Pubkey, Vec<Keypair>) {
+    let multisig = Keypair::new();
+    let signers = vec![Keypair::new(), Keypair::new(), Keypair::new()];
+    let signer_pubkeys = signers.iter().map(|s| s.pubkey()).collect::<Vec<_>>();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let instructions = [
+        system_instruction::create_account(
+            &context.payer.pubkey(),
+            &multisig.pubkey(),
+            rent.minimum_balance(Multisig::LEN),
+            Multisig::LEN as u64,
+            token_program_id,
+        ),
+        spl_token_2022::instruction::initialize_multisig2(
+            token_program_id,
+            &multisig.pubkey(),
+            &signer_pubkeys.iter().collect::<Vec<_>>(),
+            2,
+        )
+        .unwrap(),
+    ];
+    process(context, &instructions, &[&multisig]).await.unwrap();
+    (multisig.pubkey(), signers)
+}
+
+async fn get_amount(context: &mut ProgramTestContext, address: &Pubkey) -> u64 {
+    let account = context
+        .banks_client
+        .get_account(*address)
+        .await
+        .unwrap()
+        .unwrap();
+    StateWithExtensions::<Account>::unpack(&account.data)
+        .unwrap()
+        .base
+        .amount
+}
+
+async fn get_supply(context: &mut ProgramTestContext, mint: &Pubkey) -> u64 {
+    let account = context
+        .banks_client
+        .get_account(*mint)
+        .await
+        .unwrap()
+        .unwrap();
+    StateWithExtensions::<Mint>::unpack(&account.data)
+        .unwrap()
+        .base
+        .supply
+}
+
+/// Creates the wrapped mint for a new unwrapped mint, returning both
+async fn setup_wrapped_mint(
+    context: &mut ProgramTestContext,
+    unwrapped_token_program_id: &Pubkey,
+    wrapped_token_program_id: &Pubkey,
+    transfer_fee_basis_points: Option<u16>,
+) -> (Pubkey, Pubkey) {
+    let unwrapped_mint = setup_mint(
+        context,
+        unwrapped_token_program_id,
+        transfer_fee_basis_points,
+    )
+    .await;
+    let wrapped_mint = get_wrapped_mint_address(&unwrapped_mint, wrapped_token_program_id);
+    let instruction = create_mint(
+        &spl_token_wrap::id(),
+        &context.payer.pubkey(),
+        &wrapped_mint,
+        &get_wrapped_mint_backpointer_address(&wrapped_mint),
+        &unwrapped_mint,
+        wrapped_token_program_id,
+        false,
+    );
+    process(context, &[instruction], &[]).await.unwrap();
+    (unwrapped_mint, wrapped_mint)
+}
+
+#[test_case(spl_token::id(), spl_token_2022::id() ; "token to token-2022")]
+#[test_case(spl_token_2022::id(), spl_token::id() ; "token-2022 to token")]
+#[test_case(spl_token::id(), spl_token::id() ; "token to token")]
+#[test_case(spl_token_2022::id(), spl_token_2022::id() ; "token-2022 to token-2022")]
+#[tokio::test]
+async fn success(unwrapped_token_program_id: Pubkey, wrapped_token_program_id: Pubkey) {
+    let mut context = setup().await;
+    let (unwrapped_mint, wrapped_mint) = setup_wrapped_mint(
+        &mut context,
+        &unwrapped_token_program_id,
+        &wrapped_token_program_id,
+        None,
+    )
+    .await;
+
+    // wrapped mint mirrors the unwrapped mint, controlled by the PDA
+    let wrapped_mint_authority = get_wrapped_mint_authority(&wrapped_mint);
+    let account = context
+        .banks_client
+        .get_account(wrapped_mint)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.owner, wrapped_token_program_id);
+    let mint = StateWithExtensions::<Mint>::unpack(&account.data).unwrap();
+    assert_eq!(mint.base.decimals, DECIMALS);
+    assert_eq!(
+        mint.base.mint_authority,
+        Some(wrapped_mint_authority).into()
+    );
+    assert_eq!(mint.base.freeze_authority, None.into());
+    assert_eq!(mint.base.supply, 0);
+
+    let account = context
+        .banks_client
+        .get_account(get_wrapped_mint_backpointer_address(&wrapped_mint))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.owner, spl_token_wrap::id());
+    let backpointer = bytemuck::from_bytes::<Backpointer>(&account.data);
+    assert_eq!(backpointer.unwrapped_mint, unwrapped_mint);
+
+    // wrap
+    let wallet = Keypair::new();
+    let unwrapped_account = setup_token_account(
+        &mut context,
+        &unwrapped_token_program_id,
+        &unwrapped_mint,
+        &wallet.pubkey(),
+    )
+    .await;
+    let wrapped_account = setup_token_account(
+        &mut context,
+        &wrapped_token_program_id,
+        &wrapped_mint,
+        &wallet.pubkey(),
+    )
+    .await;
+    let escrow = setup_token_account(
+        &mut context,
+        &unwrapped_token_program_id,
+        &unwrapped_mint,
+        &wrapped_mint_authority,
+    )
+    .await;
+    mint_to(
+        &mut context,
+        &unwrapped_token_program_id,
+        &unwrapped_mint,
+        &unwrapped_account,
+        1_000,
+    )
+    .await;
+
+    let instruction = wrap(
+        &spl_token_wrap::id(),
+        &unwrapped_account,
+        &escrow,
+        &unwrapped_mint,
+        &wrapped_mint,
+        &wrapped_account,
+        &unwrapped_token_program_id,
+        &wrapped_token_program_id,
+        &wallet.pubkey(),
+        &[],
+        600,
+    );
+    process(&mut context, &[instruction], &[&wallet])
+        .await
+        .unwrap();
+
+    assert_eq!(get_amount(&mut context, &unwrapped_account).await, 400);
+    assert_eq!(get_amount(&mut context, &escrow).await, 600);
+    assert_eq!(get_amount(&mut context, &wrapped_account).await, 600);
+    assert_eq!(get_supply(&mut context, &wrapped_mint).await, 600);
+
+    // unwrap
+    let instruction = unwrap(
+        &spl_token_wrap::id(),
+        &wrapped_account,
+        &wrapped_mint,
+        &escrow,
+        &unwrapped_account,
+        &unwrapped_mint,
+        &wrapped_token_program_id,
+        &unwrapped_token_program_id,
+        &wallet.pubkey(),
+        &[],
+        250,
+    );
+    process(&mut context, &[instruction], &[&wallet])
+        .await
+        .unwrap();
+
+    assert_eq!(get_amount(&mut context, &unwrapped_account).await, 650);
+    assert_eq!(get_amount(&mut context, &escrow).await, 350);
+    assert_eq!(get_amount(&mut context, &wrapped_account).await, 350);
+    assert_eq!(get_supply(&mut context, &wrapped_mint).await, 350);
+}
+
+#[test_case(spl_token::id(), spl_token_2022::id() ; "token to token-2022")]
+#[test_case(spl_token_2022::id(), spl_token::id() ; "token-2022 to token")]
+#[tokio::test]
+async fn success_multisig(unwrapped_token_program_id: Pubkey, wrapped_token_program_id: Pubkey) {
+    let mut context = setup().await;
+    let (unwrapped_mint, wrapped_mint) = setup_wrapped_mint(
+        &mut context,
+        &unwrapped_token_program_id,
+        &wrapped_token_program_id,
+        None,
+    )
+    .await;
+
+    let (unwrapped_multisig, unwrapped_signers) =
+        setup_multisig(&mut context, &unwrapped_token_program_id).await;
+    let (wrapped_multisig, wrapped_signers) =
+        setup_multisig(&mut context, &wrapped_token_program_id).await;
+    let unwrapped_account = setup_token_account(
+        &mut context,
+        &unwrapped_token_program_id,
+        &unwrapped_mint,
+        &unwrapped_multisig,
+    )
+    .await;
+    let wrapped_account = setup_token_account(
+        &mut context,
+        &wrapped_token_program_id,
+        &wrapped_mint,
+        &wrapped_multisig,
+    )
+    .await;
+    let escrow = setup_token_account(
+        &mut context,
+        &unwrapped_token_program_id,
+        &unwrapped_mint,
+        &get_wrapped_mint_authority(&wrapped_mint),
+    )
+    .await;
+    mint_to(
+        &mut context,
+        &unwrapped_token_program_id,
+        &unwrapped_mint,
+        &unwrapped_account,
+        1_000,
+    )
+    .await;
+
+    let instruction = wrap(
+        &spl_token_wrap::id(),
+        &unwrapped_account,
+        &escrow,
+        &unwrapped_mint,
+        &wrapped_mint,
+        &wrapped_account,
+        &unwrapped_token_program_id,
+        &wrapped_token_program_id,
+        &unwrapped_multisig,
+        &[
+            &unwrapped_signers[0].pubkey(),
+            &unwrapped_signers[2].pubkey(),
+        ],
+        1_000,
+    );
+    process(
+        &mut context,
+        &[instruction],
+        &[&unwrapped_signers[0], &unwrapped_signers[2]],
+    )
+    .await
+    .unwrap();
+    assert_eq!(get_amount(&mut context, &escrow).await, 1_000);
+    assert_eq!(get_amount(&mut context, &wrapped_account).await, 1_000);
+
+    let instruction = unwrap(
+        &spl_token_wrap::id(),
+        &wrapped_account,
+        &wrapped_mint,
+        &escrow,
+        &unwrapped_account,
+        &unwrapped_mint,
+        &wrapped_token_program_id,
+        &unwrapped_token_program_id,
+        &wrapped_multisig,
+        &[&wrapped_signers[0].pubkey(), &wrapped_signers[1].pubkey()],
+        1_000,
+    );
+    process(
+        &mut context,
+        &[instruction],
+        &[&wrapped_signers[0], &wrapped_signers[1]],
+    )
+    .await
+    .unwrap();
+    assert_eq!(get_amount(&mut context, &unwrapped_account).await, 1_000);
+    assert_eq!(get_amount(&mut context, &escrow).await, 0);
+    assert_eq!(get_supply(&mut context, &wrapped_mint).await, 0);
+}
+
+#[test_case(spl_token::id() ; "to token")]
+#[test_case(spl_token_2022::id() ; "to token-2022")]
+#[tokio::test]
+async fn success_transfer_fee(wrapped_token_program_id: Pubkey) {
+    let mut context = setup().await;
+    let unwrapped_token_program_id = spl_token_2022::id();
+    // 1% fee
+    let (unwrapped_mint, wrapped_mint) = setup_wrapped_mint(
+        &mut context,
+        &unwrapped_token_program_id,
+        &wrapped_token_program_id,
+        Some(100),
+    )
+    .await;
+
+    let wallet = Keypair::new();
+    let unwrapped_account = setup_token_account(
+        &mut context,
+        &unwrapped_token_program_id,
+        &unwrapped_mint,
+        &wallet.pubkey(),
+    )
+    .await;
+    let wrapped_account = setup_token_account(
+        &mut context,
+        &wrapped_token_program_id,
+        &wrapped_mint,
+        &wallet.pubkey(),
+    )
+    .await;
+    let escrow = setup_token_account(
+        &mut context,
+        &unwrapped_token_program_id,
+        &unwrapped_mint,
+        &get_wrapped_mint_authority(&wrapped_mint),
+    )
+    .await;
+    mint_to(
+        &mut context,
+        &unwrapped_token_program_id,
+        &unwrapped_mint,
+        &unwrapped_account,
+        10_000,
+    )
+    .await;
+
+    // only the amount received by the escrow gets wrapped
+    let instruction = wrap(
+        &spl_token_wrap::id(),
+        &unwrapped_account,
+        &escrow,
+        &unwrapped_mint,
+        &wrapped_mint,
+        &wrapped_account,
+        &unwrapped_token_program_id,
+        &wrapped_token_program_id,
+        &wallet.pubkey(),
+        &[],
+        10_000,
+    );
+    process(&mut context, &[instruction], &[&wallet])
+        .await
+        .unwrap();
+    assert_eq!(get_amount(&mut context, &unwrapped_account).await, 0);
+    assert_eq!(get_amount(&mut context, &escrow).await, 9_900);
+    assert_eq!(get_amount(&mut context, &wrapped_account).await, 9_900);
+    assert_eq!(get_supply(&mut context, &wrapped_mint).await, 9_900);
+
+    // the recipient pays the fee on the way out
+    let instruction = unwrap(
+        &spl_token_wrap::id(),
+        &wrapped_account,
+        &wrapped_mint,
+        &escrow,
+        &unwrapped_account,
+        &unwrapped_mint,
+        &wrapped_token_program_id,
+        &unwrapped_token_program_id,
+        &wallet.pubkey(),
+        &[],
+        9_900,
+    );
+    process(&mut context, &[instruction], &[&wallet])
+        .await
+        .unwrap();
+    assert_eq!(get_amount(&mut context, &unwrapped_account).await, 9_801);
+    assert_eq!(get_amount(&mut context, &escrow).await, 0);
+    assert_eq!(get_supply(&mut context, &wrapped_mint).await, 0);
+
+    let account = context
+        .banks_client
+        .get_account(unwrapped_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let state = StateWithExtensions::<Account>::unpack(&account.data).unwrap();
+    let withheld = state
+        .get_extension::<transfer_fee::TransferFeeAmount>()
+        .unwrap()
+        .withheld_amount;
+    assert_eq!(u64::from(withheld), 99);
+}
+
+#[test_case(spl_token::id() ; "token")]
+#[test_case(spl_token_2022::id() ; "token-2022")]
+#[tokio::test]
+async fn create_mint_idempotent(wrapped_token_program_id: Pubkey) {
+    let mut context = setup().await;
+    let (unwrapped_mint, wrapped_mint) = setup_wrapped_mint(
+        &mut context,
+        &spl_token::id(),
+        &wrapped_token_program_id,
+        None,
+    )
+    .await;
+    let backpointer = get_wrapped_mint_backpointer_address(&wrapped_mint);
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+
+    let instruction = create_mint(
+        &spl_token_wrap::id(),
+        &context.payer.pubkey(),
+        &wrapped_mint,
+        &backpointer,
+        &unwrapped_mint,
+        &wrapped_token_program_id,
+        false,
+    );
+    let error = process(&mut context, &[instruction], &[])
+        .await
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(0, InstructionError::AccountAlreadyInitialized)
+    );
+
+    let instruction = create_mint(
+        &spl_token_wrap::id(),
+        &context.payer.pubkey(),
+        &wrapped_mint,
+        &backpointer,
+        &unwrapped_mint,
+        &wrapped_token_program_id,
+        true,
+    );
+    process(&mut context, &[instruction], &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn create_mint_prefunded() {
+    let mut context = setup().await;
+    let unwrapped_mint = setup_mint(&mut context, &spl_token::id(), None).await;
+    let wrapped_mint = get_wrapped_mint_address(&unwrapped_mint, &spl_token_2022::id());
+    let backpointer = get_wrapped_mint_backpointer_address(&wrapped_mint);
+
+    // sending lamports to the PDAs must not block creation
+    let instructions = [
+        system_instruction::transfer(&context.payer.pubkey(), &wrapped_mint, 1),
+        system_instruction::transfer(&context.payer.pubkey(), &backpointer, 1_000_000_000),
+        create_mint(
+            &spl_token_wrap::id(),
+            &context.payer.pubkey(),
+            &wrapped_mint,
+            &backpointer,
+            &unwrapped_mint,
+            &spl_token_2022::id(),
+            false,
+        ),
+    ];
+    process(&mut context, &instructions, &[]).await.unwrap();
+
+    let account = context
+        .banks_client
+        .get_account(wrapped_mint)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.owner, spl_token_2022::id());
+    StateWithExtensions::<Mint>::unpack(&account.data).unwrap();
+    let account = context
+        .banks_client
+        .get_account(backpointer)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        bytemuck::from_bytes::<Backpointer>(&account.data).unwrapped_mint,
+        unwrapped_mint
+    );
+}
+
+#[tokio::test]
+async fn fail_create_mint_wrong_address() {
+    let mut context = setup().await;
+    let unwrapped_mint = setup_mint(&mut context, &spl_token::id(), None).await;
+    // derived for the other token program
+    let wrapped_mint = get_wrapped_mint_address(&unwrapped_mint, &spl_token::id());
+    let instruction = create_mint(
+        &spl_token_wrap::id(),
+        &context.payer.pubkey(),
+        &wrapped_mint,
+        &get_wrapped_mint_backpointer_address(&wrapped_mint),
+        &unwrapped_mint,
+        &spl_token_2022::id(),
+        false,
+    );
+    let error = process(&mut context, &[instruction], &[])
+        .await
+        .unwrap_err();
+    assert_custom_error(error, TokenWrapError::WrappedMintMismatch);
+}
+
+#[tokio::test]
+async fn fail_wrap_invalid_escrow() {
+    let mut context = setup().await;
+    let (unwrapped_mint, wrapped_mint) =
+        setup_wrapped_mint(&mut context, &spl_token::id(), &spl_token_2022::id(), None).await;
+
+    let wallet = Keypair::new();
+    let unwrapped_account = setup_token_account(
+        &mut context,
+        &spl_token::id(),
+        &unwrapped_mint,
+        &wallet.pubkey(),
+    )
+    .await;
+    let wrapped_account = setup_token_account(
+        &mut context,
+        &spl_token_2022::id(),
+        &wrapped_mint,
+        &wallet.pubkey(),
+    )
+    .await;
+    mint_to(
+        &mut context,
+        &spl_token::id(),
+        &unwrapped_mint,
+        &unwrapped_account,
+        1_000,
+    )
+    .await;
+
+    // escrow not owned by the wrapped mint authority
+    let escrow_owner = Keypair::new();
+    let escrow = setup_token_account(
+        &mut context,
+        &spl_token::id(),
+        &unwrapped_mint,
+        &escrow_owner.pubkey(),
+    )
+    .await;
+    let instruction = wrap(
+        &spl_token_wrap::id(),
+        &unwrapped_account,
+        &escrow,
+        &unwrapped_mint,
+        &wrapped_mint,
+        &wrapped_account,
+        &spl_token::id(),
+        &spl_token_2022::id(),
+        &wallet.pubkey(),
+        &[],
+        1_000,
+    );
+    let error = process(&mut context, &[instruction], &[&wallet])
+        .await
+        .unwrap_err();
+    assert_custom_error(error, TokenWrapError::EscrowOwnerMismatch);
+
+    let escrow = setup_token_account(
+        &mut context,
+        &spl_token::id(),
+        &unwrapped_mint,
+        &get_wrapped_mint_authority(&wrapped_mint),
+    )
+    .await;
+    let instruction = wrap(
+        &spl_token_wrap::id(),
+        &unwrapped_account,
+        &escrow,
+        &unwrapped_mint,
+        &wrapped_mint,
+        &wrapped_account,
+        &spl_token::id(),
+        &spl_token_2022::id(),
+        &wallet.pubkey(),
+        &[],
+        0,
+    );
+    let error = process(&mut context, &[instruction], &[&wallet])
+        .await
+        .unwrap_err();
+    assert_custom_error(error, TokenWrapError::ZeroAmount);
+}
+
+/// Creates a Token-2022 mint holding its own metadata, with the payer as mint
+/// and update authority
+async fn setup_mint_with_metadata(context: &mut ProgramTestContext) -> Pubkey {
+    let mint = Keypair::new();
+    let payer = context.payer.pubkey();
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::MetadataPointer])
+        .unwrap();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let instructions = [
+        // the metadata is allocated later, so prefund it generously
+        system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            rent.minimum_balance(space + 1_000),
+            space as u64,
+            &spl_token_2022::id(),
+        ),
+        metadata_pointer::instruction::initialize(
+            &spl_token_2022::id(),
+            &mint.pubkey(),
+            Some(payer),
+            Some(mint.pubkey()),
+        )
+        .unwrap(),
+        spl_token_2022::instruction::initialize_mint2(
+            &spl_token_2022::id(),
+            &mint.pubkey(),
+            &payer,
+            None,
+            DECIMALS,
+        )
+        .unwrap(),
+        spl_token_metadata_interface::instruction::initialize(
+            &spl_token_2022::id(),
+            &mint.pubkey(),
+            &payer,
+            &mint.pubkey(),
+            &payer,
+            "Unwrapped".to_string(),
+            "UNW".to_string(),
+            "https://example.com/unwrapped.json".to_string(),
+        ),
+        spl_token_metadata_interface::instruction::update_field(
+            &spl_token_2022::id(),
+            &mint.pubkey(),
+            &payer,
+            Field::Key("color".to_string()),
+            "blue".to_string(),
+        ),
+    ];
+    process(context, &instructions, &[&mint]).await.unwrap();
+    mint.pubkey()
+}
+
+/// Writes a Metaplex metadata account for `mint`, padding the strings the way
+/// Metaplex does
+fn set_metaplex_metadata(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    name: &str,
+    symbol: &str,
+    uri: &str,
+) -> Pubkey {
+    fn pack_padded(data: &mut Vec<u8>, value: &str, size: usize) {
+        data.extend_from_slice(&(size as u32).to_le_bytes());
+        data.extend_from_slice(value.as_bytes());
+        data.resize(data.len() + size - value.len(), 0);
+    }
+
+    let mut data = vec![4];
+    data.extend_from_slice(Pubkey::new_unique().as_ref());
+    data.extend_from_slice(mint.as_ref());
+    pack_padded(&mut data, name, 32);
+    pack_padded(&mut data, symbol, 10);
+    pack_padded(&mut data, uri, 200);
+    // seller fee basis points, no creators, primary sale, mutable
+    data.extend_from_slice(&[0xf4, 0x01, 0, 0, 1]);
+    // Metaplex allocates the maximum metadata size and zeroes the rest
+    data.resize(679, 0);
+
+    let address = spl_inline_mpl_token_metadata::get_metadata_address(mint);
+    context.set_account(
+        &address,
+        &SolanaAccount {
+            lamports: 1_000_000_000,
+            data,
+            owner: spl_inline_mpl_token_metadata::id(),
+            ..SolanaAccount::default()
+        }
+        .into(),
+    );
+    address
+}
+
+async fn get_token_metadata(context: &mut ProgramTestContext, mint: &Pubkey) -> TokenMetadata {
+    let account = context
+        .banks_client
+        .get_account(*mint)
+        .await
+        .unwrap()
+        .unwrap();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    assert!(rent.is_exempt(account.lamports, account.data.len()));
+    StateWithExtensions::<Mint>::unpack(&account.data)
+        .unwrap()
+        .get_variable_len_extension::<TokenMetadata>()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn create_mint_metadata_pointer() {
+    let mut context = setup().await;
+    let (_, wrapped_mint) =
+        setup_wrapped_mint(&mut context, &spl_token::id(), &spl_token_2022::id(), None).await;
+    let account = context
+        .banks_client
+        .get_account(wrapped_mint)
+        .await
+        .unwrap()
+        .unwrap();
+    let mint = StateWithExtensions::<Mint>::unpack(&account.data).unwrap();
+    let pointer = mint.get_extension::<MetadataPointer>().unwrap();
+    assert_eq!(
+        pointer.authority,
+        OptionalNonZeroPubkey(get_wrapped_mint_authority(&wrapped_mint))
+    );
+    assert_eq!(
+        pointer.metadata_address,
+        OptionalNonZeroPubkey(wrapped_mint)
+    );
+}
+
+#[tokio::test]
+async fn success_sync_metadata_token_2022() {
+    let mut context = setup().await;
+    let unwrapped_mint = setup_mint_with_metadata(&mut context).await;
+    let wrapped_mint = get_wrapped_mint_address(&unwrapped_mint, &spl_token_2022::id());
+    let instructions = [
+        create_mint(
+            &spl_token_wrap::id(),
+            &context.payer.pubkey(),
+            &wrapped_mint,
+            &get_wrapped_mint_backpointer_address(&wrapped_mint),
+            &unwrapped_mint,
+            &spl_token_2022::id(),
+            false,
+        ),
+        sync_metadata(
+            &spl_token_wrap::id(),
+            &context.payer.pubkey(),
+            &wrapped_mint,
+            &unwrapped_mint,
+            None,
+        ),
+    ];
+    process(&mut context, &instructions, &[]).await.unwrap();
+
+    let metadata = get_token_metadata(&mut context, &wrapped_mint).await;
+    assert_eq!(
+        metadata,
+        TokenMetadata {
+            update_authority: OptionalNonZeroPubkey(get_wrapped_mint_authority(&wrapped_mint)),
+            mint: wrapped_mint,
+            name: "Unwrapped".to_string(),
+            symbol: "UNW".to_string(),
+            uri: "https://example.com/unwrapped.json".to_string(),
+            additional_metadata: vec![("color".to_string(), "blue".to_string())],
+        }
+    );
+
+    // change the source and sync again
+    let payer = context.payer.pubkey();
+    let update_field = |field, value: &str| {
+        spl_token_metadata_interface::instruction::update_field(
+            &spl_token_2022::id(),
+            &unwrapped_mint,
+            &payer,
+            field,
+            value.to_string(),
+        )
+    };
+    let instructions = [
+        system_instruction::transfer(&payer, &unwrapped_mint, 1_000_000_000),
+        update_field(Field::Name, "A much longer unwrapped name"),
+        update_field(Field::Uri, "https://example.com/u.json"),
+        update_field(Field::Key("size".to_string()), "large"),
+        spl_token_metadata_interface::instruction::remove_key(
+            &spl_token_2022::id(),
+            &unwrapped_mint,
+            &payer,
+            "color".to_string(),
+            false,
+        ),
+    ];
+    process(&mut context, &instructions, &[]).await.unwrap();
+    let instruction = sync_metadata(
+        &spl_token_wrap::id(),
+        &context.payer.pubkey(),
+        &wrapped_mint,
+        &unwrapped_mint,
+        None,
+    );
+    process(&mut context, &[instruction], &[]).await.unwrap();
+
+    let metadata = get_token_metadata(&mut context, &wrapped_mint).await;
+    assert_eq!(metadata.name, "A much longer unwrapped name");
+    assert_eq!(metadata.symbol, "UNW");
+    assert_eq!(metadata.uri, "https://example.com/u.json");
+    assert_eq!(
+        metadata.additional_metadata,
+        vec![("size".to_string(), "large".to_string())]
+    );
+}
+
+#[tokio::test]
+async fn success_sync_metadata_metaplex() {
+    let mut context = setup().await;
+    let (unwrapped_mint, wrapped_mint) =
+        setup_wrapped_mint(&mut context, &spl_token::id(), &spl_token_2022::id(), None).await;
+    let metaplex_metadata = set_metaplex_metadata(
+        &mut context,
+        &unwrapped_mint,
+        "Classic",
+        "CLS",
+        "https://example.com/classic.json",
+    );
+
+    let instruction = sync_metadata(
+        &spl_token_wrap::id(),
+        &context.payer.pubkey(),
+        &wrapped_mint,
+        &unwrapped_mint,
+        Some(&metaplex_metadata),
+    );
+    process(&mut context, &[instruction], &[]).await.unwrap();
+    let metadata = get_token_metadata(&mut context, &wrapped_mint).await;
+    assert_eq!(metadata.name, "Classic");
+    assert_eq!(metadata.symbol, "CLS");
+    assert_eq!(metadata.uri, "https://example.com/classic.json");
+    assert!(metadata.additional_metadata.is_empty());
+
+    // syncing without changes is a no-op, and changes are picked up
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let instruction = sync_metadata(
+        &spl_token_wrap::id(),
+        &context.payer.pubkey(),
+        &wrapped_mint,
+        &unwrapped_mint,
+        Some(&metaplex_metadata),
+    );
+    process(&mut context, &[instruction.clone()], &[])
+        .await
+        .unwrap();
+    set_metaplex_metadata(
+        &mut context,
+        &unwrapped_mint,
+        "Classic",
+        "CLASSIC",
+        "https://example.com/classic.json",
+    );
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    process(&mut context, &[instruction], &[]).await.unwrap();
+    let metadata = get_token_metadata(&mut context, &wrapped_mint).await;
+    assert_eq!(metadata.symbol, "CLASSIC");
+}
+
+#[tokio::test]
+async fn fail_sync_metadata() {
+    let mut context = setup().await;
+    let (unwrapped_mint, wrapped_mint) =
+        setup_wrapped_mint(&mut context, &spl_token::id(), &spl_token_2022::id(), None).await;
+
+    // no metadata source
+    let instruction = sync_metadata(
+        &spl_token_wrap::id(),
+        &context.payer.pubkey(),
+        &wrapped_mint,
+        &unwrapped_mint,
+        None,
+    );
+    let error = process(&mut context, &[instruction], &[])
+        .await
+        .unwrap_err();
+    assert_custom_error(error, TokenWrapError::MetadataSourceNotFound);
+
+    // Metaplex metadata of another mint
+    let other_mint = setup_mint(&mut context, &spl_token::id(), None).await;
+    let other_metadata = set_metaplex_metadata(&mut context, &other_mint, "Other", "OTH", "");
+    let instruction = sync_metadata(
+        &spl_token_wrap::id(),
+        &context.payer.pubkey(),
+        &wrapped_mint,
+        &unwrapped_mint,
+        Some(&other_metadata),
+    );
+    let error = process(&mut context, &[instruction], &[])
+        .await
+        .unwrap_err();
+    assert_custom_error(error, TokenWrapError::InvalidMetaplexMetadata);
+
+    // wrapped mint under SPL Token
+    let metaplex_metadata = set_metaplex_metadata(&mut context, &unwrapped_mint, "A", "A", "");
+    let wrapped_mint = get_wrapped_mint_address(&unwrapped_mint, &spl_token::id());
+    let instructions = [
+        create_mint(
+            &spl_token_wrap::id(),
+            &context.payer.pubkey(),
+            &wrapped_mint,
+            &get_wrapped_mint_backpointer_address(&wrapped_mint),
+            &unwrapped_mint,
+            &spl_token::id(),
+            false,
+        ),
+        sync_metadata(
+            &spl_token_wrap::id(),
+            &context.payer.pubkey(),
+            &wrapped_mint,
+            &unwrapped_mint,
+            Some(&metaplex_metadata),
+        ),
+    ];
+    let error = process(&mut context, &instructions, &[])
+        .await
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(
+        error,
+        TransactionError::InstructionError(
+            1,
+            InstructionError::from(u64::from(ProgramError::IllegalOwner))
+        )
+    );
+}